@@ -2,11 +2,12 @@ use criterion::{
     black_box, criterion_group, criterion_main, AxisScale, BenchmarkId, Criterion,
     PlotConfiguration, Throughput,
 };
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use zarrs::array_subset::ArraySubset;
 
 fn array_subset_indices_iterator(c: &mut Criterion) {
     let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
-    let mut group = c.benchmark_group(format!("array_subset_indices_iterator"));
+    let mut group = c.benchmark_group("array_subset_indices_iterator");
     group.plot_config(plot_config);
 
     for array_subset_size in [4, 16, 64, 256] {
@@ -22,5 +23,40 @@ fn array_subset_indices_iterator(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, array_subset_indices_iterator);
+fn array_subset_contiguous_linearised_indices_iterator(c: &mut Criterion) {
+    let plot_config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    let mut group = c.benchmark_group("array_subset_contiguous_linearised_indices_iterator");
+    group.plot_config(plot_config);
+
+    for array_subset_size in [4, 16, 64, 256] {
+        // The subset is inset by one element in each dimension of the array, so each row is a separate run
+        let array_shape = vec![array_subset_size + 2; 3];
+        let array_subset =
+            ArraySubset::new_with_start_shape(vec![1; 3], vec![array_subset_size; 3]).unwrap();
+        let contiguous_indices = array_subset
+            .contiguous_linearised_indices(&array_shape)
+            .unwrap();
+        group.throughput(Throughput::Elements(contiguous_indices.len() as u64));
+        group.bench_function(BenchmarkId::new("serial", array_subset_size), |b| {
+            b.iter(|| {
+                contiguous_indices.iter().for_each(|indices| {
+                    black_box(indices);
+                })
+            });
+        });
+        group.bench_function(BenchmarkId::new("parallel", array_subset_size), |b| {
+            b.iter(|| {
+                contiguous_indices.into_par_iter().for_each(|indices| {
+                    black_box(indices);
+                })
+            });
+        });
+    }
+}
+
+criterion_group!(
+    benches,
+    array_subset_indices_iterator,
+    array_subset_contiguous_linearised_indices_iterator
+);
 criterion_main!(benches);
@@ -115,11 +115,51 @@ fn array_read_all_sharded(c: &mut Criterion) {
     group.finish();
 }
 
+fn array_read_subset_small_chunks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("array_read_subset_small_chunks");
+    for size in [128u64, 256u64, 512u64].iter() {
+        // A subset offset from the chunk grid, so most chunks are only partially intersected
+        let subset = zarrs::array_subset::ArraySubset::new_with_ranges(&[
+            1..size - 1,
+            1..size - 1,
+            1..size - 1,
+        ]);
+        group.throughput(Throughput::Bytes(subset.num_elements() * 2));
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            // Write the data
+            let store = zarrs::storage::store::MemoryStore::new();
+            let array = zarrs::array::ArrayBuilder::new(
+                vec![size; 3],
+                zarrs::array::DataType::UInt16,
+                vec![8; 3].try_into().unwrap(),
+                zarrs::array::FillValue::from(0u16),
+            )
+            .build(store.into(), "/")
+            .unwrap();
+            let num_elements: u64 = size * size * size;
+            let data = vec![1u16; num_elements.try_into().unwrap()];
+            array
+                .store_array_subset_elements(
+                    &zarrs::array_subset::ArraySubset::new_with_shape(vec![size; 3]),
+                    data,
+                )
+                .unwrap();
+
+            // Benchmark reading the data
+            b.iter(|| {
+                let _bytes = array.retrieve_array_subset(&subset).unwrap();
+            });
+        });
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     array_write_all,
     array_read_all,
     array_write_all_sharded,
-    array_read_all_sharded
+    array_read_all_sharded,
+    array_read_subset_small_chunks
 );
 criterion_main!(benches);
@@ -93,7 +93,7 @@ async fn async_array_write_read() -> Result<(), Box<dyn std::error::Error>> {
         .iter()
         .map(|(i, chunk_indices, chunk_subset)| {
             array.async_store_chunk_elements(
-                &chunk_indices,
+                chunk_indices,
                 vec![*i as f32 * 0.1; chunk_subset.num_elements() as usize],
             )
         })
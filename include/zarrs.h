@@ -0,0 +1,73 @@
+/* The zarrs C API. Build zarrs with the `ffi` feature (see the `zarrs::ffi` module documentation). */
+
+#ifndef ZARRS_H
+#define ZARRS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum ZarrsResult {
+  ZARRS_SUCCESS = 0,
+  ZARRS_ERROR_NULL_PTR = -1,
+  ZARRS_ERROR_STORAGE = -2,
+  ZARRS_ERROR_ARRAY = -3,
+  ZARRS_ERROR_GROUP = -4,
+  ZARRS_ERROR_NODE = -5,
+  ZARRS_ERROR_BUFFER_LENGTH = -6,
+  ZARRS_ERROR_INVALID_DIMENSIONALITY = -7,
+  ZARRS_ERROR_INVALID_ARGUMENT = -8,
+  ZARRS_ERROR_PANIC = -9,
+} ZarrsResult;
+
+typedef enum ZarrsNodeType {
+  ZARRS_NODE_TYPE_ARRAY = 0,
+  ZARRS_NODE_TYPE_GROUP = 1,
+} ZarrsNodeType;
+
+typedef struct ZarrsStorage_T ZarrsStorage_T;
+typedef ZarrsStorage_T *ZarrsStorage;
+
+typedef struct ZarrsArray_T ZarrsArray_T;
+typedef ZarrsArray_T *ZarrsArray;
+
+/* Errors and strings */
+const char *zarrs_last_error(void);
+void zarrs_free_string(char *string);
+
+/* Storage */
+ZarrsResult zarrs_create_storage_filesystem(const char *path, ZarrsStorage *storage);
+ZarrsResult zarrs_destroy_storage(ZarrsStorage storage);
+
+/* Arrays */
+ZarrsResult zarrs_open_array(ZarrsStorage storage, const char *path, ZarrsArray *array);
+ZarrsResult zarrs_create_array(ZarrsStorage storage, const char *path, const char *metadata,
+                               ZarrsArray *array);
+ZarrsResult zarrs_destroy_array(ZarrsArray array);
+ZarrsResult zarrs_array_get_dimensionality(ZarrsArray array, size_t *dimensionality);
+ZarrsResult zarrs_array_get_shape(ZarrsArray array, size_t dimensionality, uint64_t *shape);
+ZarrsResult zarrs_array_get_element_size(ZarrsArray array, size_t *element_size);
+ZarrsResult zarrs_array_get_metadata(ZarrsArray array, char **metadata);
+ZarrsResult zarrs_array_get_subset_size(ZarrsArray array, size_t dimensionality,
+                                        const uint64_t *subset_shape, size_t *subset_size);
+ZarrsResult zarrs_array_retrieve_subset(ZarrsArray array, size_t dimensionality,
+                                        const uint64_t *subset_start, const uint64_t *subset_shape,
+                                        size_t subset_bytes_length, uint8_t *subset_bytes);
+ZarrsResult zarrs_array_store_subset(ZarrsArray array, size_t dimensionality,
+                                     const uint64_t *subset_start, const uint64_t *subset_shape,
+                                     size_t subset_bytes_length, const uint8_t *subset_bytes);
+
+/* Groups and hierarchies */
+ZarrsResult zarrs_create_group(ZarrsStorage storage, const char *path, const char *metadata);
+ZarrsResult zarrs_storage_get_node_type(ZarrsStorage storage, const char *path,
+                                        ZarrsNodeType *node_type);
+ZarrsResult zarrs_storage_list_nodes(ZarrsStorage storage, const char *path, char **nodes);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZARRS_H */
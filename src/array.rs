@@ -6,17 +6,24 @@
 //! Use [`ArrayBuilder`] to setup a new array, or use [`Array::new`] for an existing array.
 //! The documentation for [`Array`] details how to interact with arrays.
 
+mod allocation_map;
 mod array_builder;
+mod array_coordinates;
 mod array_errors;
 mod array_metadata;
 mod array_metadata_options;
 mod array_representation;
+mod array_subset_view;
+mod array_verification;
 mod array_view;
+mod auto_codec;
 mod bytes_representation;
 pub mod chunk_grid;
 pub mod chunk_key_encoding;
 mod chunk_shape;
+mod chunk_statistics;
 pub mod codec;
+mod concatenated_array;
 pub mod concurrency;
 pub mod data_type;
 mod dimension_name;
@@ -24,27 +31,43 @@ mod fill_value;
 mod fill_value_metadata;
 mod nan_representations;
 mod unsafe_cell_slice;
+mod virtual_array;
 
+#[cfg(feature = "arrow")]
+mod array_arrow;
 #[cfg(feature = "sharding")]
 mod array_sharded_ext;
 #[cfg(feature = "sharding")]
 mod array_sync_sharded_readable_ext;
 
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
 pub use self::{
+    allocation_map::{AllocationMap, ALLOCATION_MAP_KEY_NAME},
     array_builder::ArrayBuilder,
-    array_errors::{ArrayCreateError, ArrayError},
-    array_metadata::{ArrayMetadata, ArrayMetadataV3},
+    array_coordinates::{
+        CoordinateError, CoordinateValues, Coordinates, Label, LabelSelection,
+        ARRAY_DIMENSIONS_ATTRIBUTE,
+    },
+    array_errors::{ArrayCreateError, ArrayError, ChunkDecodeError},
+    array_metadata::{ArrayMetadata, ArrayMetadataDiagnostic, ArrayMetadataV3},
     array_metadata_options::ArrayMetadataOptions,
     array_representation::{ArrayRepresentation, ChunkRepresentation},
+    array_subset_view::ArraySubsetView,
+    array_verification::{ChunkVerification, ChunkVerificationStatus, VerificationReport},
     array_view::{ArrayView, ArrayViewCreateError},
+    auto_codec::{AutoCodec, AutoCodecError, AutoCodecObjective, AUTO_CODEC_ATTRIBUTE},
     bytes_representation::BytesRepresentation,
     chunk_grid::ChunkGrid,
     chunk_key_encoding::ChunkKeyEncoding,
     chunk_shape::{chunk_shape_to_array_shape, ChunkShape},
+    chunk_statistics::{ChunkStatistics, StatisticValue, CHUNK_STATISTICS_KEY_SUFFIX},
     codec::ArrayCodecTraits,
     codec::CodecChain,
+    concatenated_array::{ConcatenatedArray, ConcatenatedArrayError},
     concurrency::RecommendedConcurrency,
     data_type::DataType,
     dimension_name::DimensionName,
@@ -52,24 +75,40 @@ pub use self::{
     fill_value_metadata::FillValueMetadata,
     nan_representations::{ZARR_NAN_BF16, ZARR_NAN_F16, ZARR_NAN_F32, ZARR_NAN_F64},
     unsafe_cell_slice::UnsafeCellSlice,
+    virtual_array::{VirtualArray, VirtualArrayFn},
 };
 
+#[cfg(feature = "arrow")]
+pub use array_arrow::{arrow_array_to_bytes, arrow_data_type, bytes_to_arrow_array};
 #[cfg(feature = "sharding")]
 pub use array_sharded_ext::ArrayShardedExt;
 #[cfg(feature = "sharding")]
 pub use array_sync_sharded_readable_ext::{ArrayShardedReadableExt, ArrayShardedReadableExtCache};
 // TODO: Add AsyncArrayShardedReadableExt and AsyncArrayShardedReadableExtCache
 
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 use crate::{
-    array_subset::{ArraySubset, IncompatibleDimensionalityError},
+    array_subset::{
+        iterators::Indices, ArraySubset, IncompatibleDimensionalityError, StridedArraySubset,
+    },
     metadata::AdditionalFields,
     node::NodePath,
-    storage::storage_transformer::StorageTransformerChain,
+    storage::{
+        data_key, storage_transformer::StorageTransformerChain, store_lock::StoreLocks,
+        StorageError, StoreKey,
+    },
+};
+
+use self::codec::{
+    ArrayToBytesCodecTraits, ChunkProgress, CodecError, CodecOptions, PartialDecodePlan,
+    ProgressCallback,
 };
 
+#[cfg(feature = "async")]
+use crate::storage::store_lock::AsyncStoreLocks;
+
 /// An ND index to an element in an array.
 pub type ArrayIndices = Vec<u64>;
 
@@ -89,7 +128,14 @@ pub struct NonZeroError;
 /// When a value is read from a store, it returns `MaybeBytes` which is [`None`] if the key is not available.
 /// A bytes to bytes codec only decodes `MaybeBytes` holding actual bytes, otherwise the bytes are propagated to the next decoder.
 /// An array to bytes partial decoder must take care of converting missing chunks to the fill value.
-pub type MaybeBytes = Option<Vec<u8>>;
+///
+/// The bytes are reference counted, so stores can hand out values they already hold (e.g. in memory or from an HTTP response) without copying.
+pub type MaybeBytes = Option<bytes::Bytes>;
+
+/// Bytes which are either borrowed or owned.
+///
+/// Codecs take and return `RawBytes` so that stages which do not change their input (e.g. the `bytes` codec with native endianness or the `crc32c` codec when decoding) can pass it through without copying.
+pub type RawBytes<'a> = std::borrow::Cow<'a, [u8]>;
 
 /// A Zarr array.
 ///
@@ -178,7 +224,8 @@ pub type MaybeBytes = Option<Vec<u8>>;
 ///
 /// Partial writes to a chunk may be lost if these rules are not respected.
 ///
-/// zarrs does not currently offer an API for locking chunks or regions.
+/// Alternatively, chunk locks can be set with [`Array::set_chunk_locks`] (or `Array::set_async_chunk_locks`) to serialise these operations on a chunk.
+/// [`DefaultStoreLocks`](crate::storage::store_lock::DefaultStoreLocks) synchronise writes within a process, and custom locks (e.g. file-based or distributed locks) can be implemented with [`StoreLocksTraits`](crate::storage::store_lock::StoreLocksTraits) for multi-process writes.
 ///
 /// ### Best Practices
 ///
@@ -210,7 +257,7 @@ pub struct Array<TStorage: ?Sized> {
     /// Provides an element value to use for uninitialised portions of the Zarr array. It encodes the underlying data type.
     fill_value: FillValue,
     /// Specifies a list of codecs to be used for encoding and decoding chunks.
-    codecs: CodecChain,
+    codecs: Arc<CodecChain>,
     /// Optional user defined attributes.
     attributes: serde_json::Map<String, serde_json::Value>,
     /// An optional list of storage transformers.
@@ -221,6 +268,15 @@ pub struct Array<TStorage: ?Sized> {
     additional_fields: AdditionalFields,
     /// Zarrs metadata.
     include_zarrs_metadata: bool,
+    /// Chunk statistics.
+    include_chunk_statistics: bool,
+    /// The allocation map maintained by the array.
+    allocation_map: Option<Arc<parking_lot::Mutex<AllocationMap>>>,
+    /// Locks acquired while updating a chunk.
+    chunk_locks: Option<StoreLocks>,
+    /// Async locks acquired while updating a chunk.
+    #[cfg(feature = "async")]
+    async_chunk_locks: Option<AsyncStoreLocks>,
 }
 
 impl<TStorage: ?Sized> Array<TStorage> {
@@ -286,12 +342,17 @@ impl<TStorage: ?Sized> Array<TStorage> {
             chunk_grid,
             chunk_key_encoding,
             fill_value,
-            codecs,
+            codecs: Arc::new(codecs),
             attributes: metadata.attributes,
             additional_fields: metadata.additional_fields,
             storage_transformers,
             dimension_names: metadata.dimension_names,
             include_zarrs_metadata: true,
+            include_chunk_statistics: false,
+            allocation_map: None,
+            chunk_locks: None,
+            #[cfg(feature = "async")]
+            async_chunk_locks: None,
         })
     }
 
@@ -306,6 +367,53 @@ impl<TStorage: ?Sized> Array<TStorage> {
         &mut self.attributes
     }
 
+    /// Get the attribute `name` deserialised as `T`.
+    ///
+    /// Returns [`None`] if the attribute does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the attribute cannot be deserialised as `T`.
+    pub fn attribute<T: DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, serde_json::Error> {
+        self.attributes
+            .get(name)
+            .map(|value| T::deserialize(value))
+            .transpose()
+    }
+
+    /// Set the attribute `name` to `value` serialised as JSON.
+    ///
+    /// Returns the previous value of the attribute, if any.
+    /// This does **not** write to the store, use [`store_attributes`](Array<ReadableWritableStorageTraits>::store_attributes) to write the updated attributes.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if `value` cannot be serialised.
+    pub fn set_attribute<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<Option<serde_json::Value>, serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        Ok(self.attributes.insert(name.to_string(), value))
+    }
+
+    /// Remove the attribute `name`.
+    ///
+    /// Returns the removed value of the attribute, if any.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<serde_json::Value> {
+        self.attributes.remove(name)
+    }
+
+    /// Merge `patch` into the attributes following [JSON Merge Patch (RFC 7396)](https://www.rfc-editor.org/rfc/rfc7396) semantics.
+    ///
+    /// Objects are merged recursively, `null` values remove the corresponding attribute, and any other value replaces it.
+    /// This does **not** write to the store, use [`store_attributes`](Array<ReadableWritableStorageTraits>::store_attributes) to write the updated attributes.
+    pub fn update_attributes(&mut self, patch: &serde_json::Map<String, serde_json::Value>) {
+        merge_patch(&mut self.attributes, patch);
+    }
+
     /// Get the node path.
     #[must_use]
     pub const fn path(&self) -> &NodePath {
@@ -324,6 +432,12 @@ impl<TStorage: ?Sized> Array<TStorage> {
         &self.fill_value
     }
 
+    /// Get the storage of the array.
+    #[cfg_attr(not(feature = "sharding"), allow(dead_code))]
+    pub(crate) fn storage(&self) -> &Arc<TStorage> {
+        &self.storage
+    }
+
     /// Get the array shape.
     #[must_use]
     pub fn shape(&self) -> &[u64] {
@@ -338,7 +452,7 @@ impl<TStorage: ?Sized> Array<TStorage> {
 
     /// Get the codecs.
     #[must_use]
-    pub const fn codecs(&self) -> &CodecChain {
+    pub fn codecs(&self) -> &CodecChain {
         &self.codecs
     }
 
@@ -385,6 +499,56 @@ impl<TStorage: ?Sized> Array<TStorage> {
         self.include_zarrs_metadata = include_zarrs_metadata;
     }
 
+    /// Return true if [`ChunkStatistics`] are stored when chunks are stored.
+    #[must_use]
+    pub const fn include_chunk_statistics(&self) -> bool {
+        self.include_chunk_statistics
+    }
+
+    /// Enable or disable the storage of [`ChunkStatistics`] when chunks are stored. Disabled by default.
+    ///
+    /// If enabled, the statistics of every chunk stored by the array are written to [`chunk_statistics_key`](Array::chunk_statistics_key), and erased with the chunk.
+    /// Statistics can then be queried with [`chunks_where`](Array::chunks_where) to skip chunks during reads.
+    /// Chunks stored with [`store_encoded_chunk`](Array::store_encoded_chunk) have their statistics erased.
+    ///
+    /// Statistics become stale if chunks are stored while this is disabled, so it should be enabled for every writer of an array with statistics.
+    pub fn set_include_chunk_statistics(&mut self, include_chunk_statistics: bool) {
+        self.include_chunk_statistics = include_chunk_statistics;
+    }
+
+    /// Get the locks acquired while updating a chunk.
+    #[must_use]
+    pub fn chunk_locks(&self) -> Option<&StoreLocks> {
+        self.chunk_locks.as_ref()
+    }
+
+    /// Set the locks acquired while updating a chunk. Disabled by default.
+    ///
+    /// If set, [`store_chunk_subset`](Array::store_chunk_subset) and [`store_array_subset`](Array::store_array_subset) (and their variants) lock the key of a chunk while it is retrieved, updated, and stored.
+    /// See [`store_lock`](crate::storage::store_lock) for lock implementations.
+    ///
+    /// A locked chunk is retrieved, updated, and stored serially on the current thread (with a [concurrent target](CodecOptions::concurrent_target) of one and without a [thread pool](CodecOptions::thread_pool)).
+    /// Otherwise, a [`rayon`] thread holding the lock could steal an update of the same chunk while waiting on parallel work, and deadlock.
+    /// Custom codecs and stores used with chunk locks must not use [`rayon`] regardless of the concurrent target for the same reason.
+    pub fn set_chunk_locks(&mut self, chunk_locks: Option<StoreLocks>) {
+        self.chunk_locks = chunk_locks;
+    }
+
+    /// Get the async locks acquired while updating a chunk.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn async_chunk_locks(&self) -> Option<&AsyncStoreLocks> {
+        self.async_chunk_locks.as_ref()
+    }
+
+    /// Set the async locks acquired while updating a chunk. Disabled by default.
+    ///
+    /// Async variant of [`set_chunk_locks`](Array::set_chunk_locks).
+    #[cfg(feature = "async")]
+    pub fn set_async_chunk_locks(&mut self, async_chunk_locks: Option<AsyncStoreLocks>) {
+        self.async_chunk_locks = async_chunk_locks;
+    }
+
     /// Create [`ArrayMetadata`].
     #[must_use]
     pub fn metadata_opt(&self, options: &ArrayMetadataOptions) -> ArrayMetadata {
@@ -442,6 +606,12 @@ impl<TStorage: ?Sized> Array<TStorage> {
         unsafe { self.chunk_grid().grid_shape_unchecked(self.shape()) }
     }
 
+    /// Return the store key of the chunk at `chunk_indices`.
+    #[must_use]
+    pub fn chunk_key(&self, chunk_indices: &[u64]) -> StoreKey {
+        data_key(self.path(), chunk_indices, self.chunk_key_encoding())
+    }
+
     /// Return the origin of the chunk at `chunk_indices`.
     ///
     /// # Errors
@@ -586,6 +756,60 @@ impl<TStorage: ?Sized> Array<TStorage> {
         }
     }
 
+    /// Plan the store keys and byte ranges required to retrieve `array_subset`, without performing any I/O.
+    ///
+    /// Returns the chunk indices, store key, and [`PartialDecodePlan`] of each chunk intersecting `array_subset`.
+    /// Byte ranges can be planned for chunks encoded with only the `bytes` or `sharding_indexed` codecs, otherwise the whole chunk is required.
+    /// A [`PartialDecodePlan::Index`] plan (e.g. for a shard) requires the encoded index at that byte range of the chunk, which is passed to [`chunk_byte_range_plan`](Array::chunk_byte_range_plan) to resolve the byte ranges of the chunk.
+    ///
+    /// This is intended for servers that translate requests for array subsets into ranged requests for store values (e.g. presigned URLs).
+    /// Chunks and byte ranges that are not stored should be treated as the fill value.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is invalid or a codec cannot plan the byte ranges.
+    pub fn byte_range_plan(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<(ArrayIndices, StoreKey, PartialDecodePlan)>, ArrayError> {
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+        chunks
+            .indices()
+            .into_iter()
+            .map(|chunk_indices| {
+                let plan = self.chunk_byte_range_plan(&chunk_indices, array_subset, None)?;
+                let key = self.chunk_key(&chunk_indices);
+                Ok((chunk_indices, key, plan))
+            })
+            .collect()
+    }
+
+    /// Plan the byte ranges of the chunk at `chunk_indices` required to retrieve the intersection of `array_subset` and the chunk, without performing any I/O.
+    ///
+    /// `index` is the encoded index read at the byte range of a previous [`PartialDecodePlan::Index`] plan of the chunk, if any.
+    /// See [`byte_range_plan`](Array::byte_range_plan).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` or `array_subset` are invalid, `index` is invalid, or a codec cannot plan the byte ranges.
+    pub fn chunk_byte_range_plan(
+        &self,
+        chunk_indices: &[u64],
+        array_subset: &ArraySubset,
+        index: Option<&[u8]>,
+    ) -> Result<PartialDecodePlan, ArrayError> {
+        let chunk_subset = self.chunk_subset(chunk_indices)?;
+        let overlap = chunk_subset.overlap(array_subset)?;
+        let array_subset_in_chunk = unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        self.codecs()
+            .partial_decode_plan(&chunk_representation, &[array_subset_in_chunk], index)
+            .map_err(|err| self.chunk_error(chunk_indices, err))
+    }
+
     /// Calculate the recommended codec concurrency.
     fn recommended_codec_concurrency(
         &self,
@@ -595,6 +819,489 @@ impl<TStorage: ?Sized> Array<TStorage> {
             .codecs()
             .recommended_concurrency(chunk_representation)?)
     }
+
+    /// Partition chunks into those entirely within `array_subset` and those partially intersecting it.
+    fn partition_chunks_in_array_subset(
+        &self,
+        chunks: &Indices,
+        array_subset: &ArraySubset,
+    ) -> Result<(Vec<ArrayIndices>, Vec<ArrayIndices>), ArrayError> {
+        let mut chunks_whole = Vec::new();
+        let mut chunks_partial = Vec::new();
+        for chunk_indices in chunks {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            if unsafe { chunk_subset.overlap_unchecked(array_subset) } == chunk_subset {
+                chunks_whole.push(chunk_indices);
+            } else {
+                chunks_partial.push(chunk_indices);
+            }
+        }
+        Ok((chunks_whole, chunks_partial))
+    }
+
+    /// Group `array_indices` by the chunk containing them.
+    ///
+    /// Returns a map from chunk indices to the position of each array indices in `array_indices` and its indices within the chunk.
+    fn group_array_indices_by_chunk<TIndices: AsRef<[u64]>>(
+        &self,
+        array_indices: &[TIndices],
+    ) -> Result<BTreeMap<ArrayIndices, Vec<(usize, ArrayIndices)>>, ArrayError> {
+        let mut chunk_elements: BTreeMap<ArrayIndices, Vec<(usize, ArrayIndices)>> =
+            BTreeMap::new();
+        for (index, indices) in array_indices.iter().enumerate() {
+            let indices = indices.as_ref();
+            let invalid_indices =
+                || ArrayError::InvalidArrayIndices(indices.to_vec(), self.shape().to_vec());
+            if indices.len() != self.dimensionality()
+                || std::iter::zip(indices, self.shape()).any(|(index, size)| index >= size)
+            {
+                return Err(invalid_indices());
+            }
+            let chunk_indices = self
+                .chunk_grid()
+                .chunk_indices(indices, self.shape())?
+                .ok_or_else(invalid_indices)?;
+            let chunk_element_indices = self
+                .chunk_grid()
+                .chunk_element_indices(indices, self.shape())?
+                .ok_or_else(invalid_indices)?;
+            chunk_elements
+                .entry(chunk_indices)
+                .or_default()
+                .push((index, chunk_element_indices));
+        }
+        Ok(chunk_elements)
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Convert `datetimes` to the elements of a `numpy.datetime64` array.
+    fn datetimes_to_elements(
+        &self,
+        datetimes: &[Option<chrono::DateTime<chrono::Utc>>],
+    ) -> Result<Vec<i64>, ArrayError> {
+        let DataType::DateTime64(unit) = *self.data_type() else {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "chrono::DateTime",
+            ));
+        };
+        datetimes
+            .iter()
+            .map(|datetime| {
+                datetime.as_ref().map_or(Ok(data_type::NAT), |datetime| {
+                    unit.from_datetime(datetime).ok_or_else(|| {
+                        ArrayError::ElementOutOfRange(
+                            datetime.to_string(),
+                            self.data_type().clone(),
+                        )
+                    })
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Convert `timedeltas` to the elements of a `numpy.timedelta64` array.
+    fn timedeltas_to_elements(
+        &self,
+        timedeltas: &[Option<chrono::TimeDelta>],
+    ) -> Result<Vec<i64>, ArrayError> {
+        let DataType::TimeDelta64(unit) = *self.data_type() else {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "chrono::TimeDelta",
+            ));
+        };
+        timedeltas
+            .iter()
+            .map(|timedelta| {
+                timedelta.as_ref().map_or(Ok(data_type::NAT), |timedelta| {
+                    unit.from_timedelta(timedelta).ok_or_else(|| {
+                        ArrayError::ElementOutOfRange(
+                            timedelta.to_string(),
+                            self.data_type().clone(),
+                        )
+                    })
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Return the codec chain without its first codec if that codec is a `transpose` to F (column-major) order.
+    ///
+    /// Decoding a chunk with the returned codec chain produces the chunk in F order without transposing it.
+    fn codecs_without_fortran_transpose(&self) -> Option<CodecChain> {
+        use codec::array_to_array::transpose::{TransposeCodecConfiguration, IDENTIFIER};
+        let codecs = self.codecs();
+        let metadatas = codecs.create_metadatas();
+        if codecs.array_to_array_codecs().is_empty()
+            || metadatas.len()
+                != codecs.array_to_array_codecs().len() + 1 + codecs.bytes_to_bytes_codecs().len()
+        {
+            return None;
+        }
+        let transpose = metadatas.first()?;
+        if transpose.name() != IDENTIFIER {
+            return None;
+        }
+        let TransposeCodecConfiguration::V1(configuration) = transpose.to_configuration().ok()?;
+        if !configuration
+            .order
+            .0
+            .iter()
+            .copied()
+            .eq((0..self.dimensionality()).rev())
+        {
+            return None;
+        }
+        CodecChain::from_metadata(&metadatas[1..]).ok()
+    }
+
+    /// Return the chunks containing an element of the orthogonal selection `dimension_indices` and the shape of the selection.
+    ///
+    /// Each chunk is paired with its selected elements along each dimension as (selection index, chunk element index) pairs.
+    #[allow(clippy::type_complexity)]
+    fn orthogonal_selection_chunks<TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+    ) -> Result<(Vec<(ArrayIndices, Vec<Vec<(u64, u64)>>)>, ArrayShape), ArrayError> {
+        if dimension_indices.len() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                dimension_indices.len(),
+                self.dimensionality(),
+            )
+            .into());
+        }
+        for (axis, indices) in dimension_indices.iter().enumerate() {
+            if let Some(&index) = indices
+                .as_ref()
+                .iter()
+                .find(|&&index| index >= self.shape()[axis])
+            {
+                return Err(ArrayError::InvalidOrthogonalIndex(
+                    axis,
+                    index,
+                    self.shape().to_vec(),
+                ));
+            }
+        }
+        let selection_shape: ArrayShape = dimension_indices
+            .iter()
+            .map(|indices| indices.as_ref().len() as u64)
+            .collect();
+        if selection_shape.contains(&0) {
+            return Ok((vec![], selection_shape));
+        }
+
+        // Group the indices along each dimension by chunk
+        let mut dimension_chunks: Vec<Vec<(u64, Vec<(u64, u64)>)>> =
+            Vec::with_capacity(self.dimensionality());
+        for (axis, indices) in dimension_indices.iter().enumerate() {
+            let mut chunks: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+            let mut array_indices = vec![0; self.dimensionality()];
+            for (selection_index, &index) in indices.as_ref().iter().enumerate() {
+                let invalid_index =
+                    || ArrayError::InvalidOrthogonalIndex(axis, index, self.shape().to_vec());
+                array_indices[axis] = index;
+                let chunk_indices = self
+                    .chunk_grid()
+                    .chunk_indices(&array_indices, self.shape())?
+                    .ok_or_else(invalid_index)?;
+                let chunk_element_indices = self
+                    .chunk_grid()
+                    .chunk_element_indices(&array_indices, self.shape())?
+                    .ok_or_else(invalid_index)?;
+                chunks
+                    .entry(chunk_indices[axis])
+                    .or_default()
+                    .push((selection_index as u64, chunk_element_indices[axis]));
+            }
+            dimension_chunks.push(chunks.into_iter().collect());
+        }
+
+        // Every combination of chunks along each dimension contains a selected element
+        let dimension_chunks_shape = dimension_chunks
+            .iter()
+            .map(|chunks| chunks.len() as u64)
+            .collect();
+        let chunks = ArraySubset::new_with_shape(dimension_chunks_shape)
+            .indices()
+            .iter()
+            .map(|combination| {
+                std::iter::zip(&combination, &dimension_chunks)
+                    .map(|(&i, chunks)| chunks[usize::try_from(i).unwrap()].clone())
+                    .unzip()
+            })
+            .collect();
+        Ok((chunks, selection_shape))
+    }
+
+    /// Return the chunks containing an element of `array_subset` selected by `mask`, and the overlap of each chunk with `array_subset`.
+    ///
+    /// Also returns the position of each element of `array_subset` among the selected elements.
+    #[allow(clippy::type_complexity)]
+    fn masked_array_subset_chunks(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+    ) -> Result<(Vec<(ArrayIndices, ArraySubset)>, Vec<usize>), ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        if mask.len() as u64 != array_subset.num_elements() {
+            return Err(ArrayError::InvalidMaskSize(
+                mask.len(),
+                array_subset.num_elements(),
+            ));
+        }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        let positions = mask
+            .iter()
+            .scan(0, |position, &selected| {
+                let current = *position;
+                *position += usize::from(selected);
+                Some(current)
+            })
+            .collect();
+
+        let mut masked_chunks = Vec::new();
+        for chunk_indices in &chunks.indices() {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let overlap = unsafe { chunk_subset.overlap_unchecked(array_subset) };
+            let overlap_in_array_subset =
+                unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+            let selected = unsafe {
+                overlap_in_array_subset.linearised_indices_unchecked(array_subset.shape())
+            }
+            .iter()
+            .any(|index| mask[usize::try_from(index).unwrap_or(usize::MAX)]);
+            if selected {
+                masked_chunks.push((chunk_indices, overlap));
+            }
+        }
+        Ok((masked_chunks, positions))
+    }
+
+    /// Return the chunks containing an element of `strided_array_subset` and the elements of `strided_array_subset` within each chunk.
+    ///
+    /// Chunks within the bounds of `strided_array_subset` that are skipped over by its step are excluded.
+    fn strided_array_subset_chunks(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+    ) -> Result<Vec<(ArrayIndices, StridedArraySubset)>, ArrayError> {
+        let bounding_subset = strided_array_subset.bounding_subset();
+        if strided_array_subset.dimensionality() != self.dimensionality()
+            || !bounding_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                bounding_subset,
+                self.shape().to_vec(),
+            ));
+        }
+        let Some(chunks) = self.chunks_in_array_subset(&bounding_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                bounding_subset,
+                self.shape().to_vec(),
+            ));
+        };
+        let mut strided_chunks = Vec::new();
+        for chunk_indices in &chunks.indices() {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let overlap = strided_array_subset.overlap(&chunk_subset)?;
+            if !overlap.is_empty() {
+                strided_chunks.push((chunk_indices, overlap));
+            }
+        }
+        Ok(strided_chunks)
+    }
+
+    /// Add the array path, chunk indices, and store key of the chunk at `chunk_indices` to a storage or codec error.
+    ///
+    /// Other errors (e.g. [`ArrayError::Cancelled`]) and errors that already have a chunk context are returned unchanged.
+    fn chunk_error(&self, chunk_indices: &[u64], err: impl Into<ArrayError>) -> ArrayError {
+        match err.into() {
+            err @ (ArrayError::StorageError(_) | ArrayError::CodecError(_)) => {
+                ArrayError::ChunkError {
+                    array_path: self.path().clone(),
+                    chunk_indices: chunk_indices.to_vec(),
+                    key: self.chunk_key(chunk_indices),
+                    source: Box::new(err),
+                }
+            }
+            err => err,
+        }
+    }
+
+    /// Encode the decoded chunk `chunk_bytes` at `chunk_indices` for storage.
+    ///
+    /// Returns [`None`] if the chunk is entirely the fill value and should be erased rather than stored (see [`CodecOptions::store_empty_chunks`]).
+    fn encode_chunk_for_store(
+        &self,
+        chunk_indices: &[u64],
+        chunk_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<u8>>, ArrayError> {
+        check_cancelled(options)?;
+        if !options.store_empty_chunks() && self.fill_value().equals_all(&chunk_bytes) {
+            Ok(None)
+        } else {
+            let chunk_array_representation = self.chunk_array_representation(chunk_indices)?;
+            let chunk_encoded = self
+                .codecs()
+                .encode(chunk_bytes.into(), &chunk_array_representation, options)
+                .map_err(|err| self.chunk_error(chunk_indices, err))?;
+            Ok(Some(chunk_encoded.into_owned()))
+        }
+    }
+
+    /// Decode an encoded chunk into `array_view`, or fill `array_view` with the fill value if the chunk does not exist.
+    fn decode_chunk_into_array_view(
+        &self,
+        chunk_indices: &[u64],
+        chunk_encoded: Option<&[u8]>,
+        chunk_representation: &ChunkRepresentation,
+        array_view: &ArrayView,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        check_cancelled(options)?;
+        if let Some(chunk_encoded) = chunk_encoded {
+            match self.codecs().decode_into_array_view(
+                chunk_encoded,
+                chunk_representation,
+                array_view,
+                options,
+            ) {
+                Err(err) if fill_value_on_decode_error(&err, options) => {
+                    self.fill_array_view(array_view);
+                    Ok(())
+                }
+                result => result.map_err(|err| self.chunk_error(chunk_indices, err)),
+            }
+        } else {
+            self.fill_array_view(array_view);
+            Ok(())
+        }
+    }
+
+    /// Fill `array_view` with the fill value.
+    fn fill_array_view(&self, array_view: &ArrayView) {
+        array_view.fill(self.fill_value().as_ne_bytes());
+    }
+}
+
+/// Returns true if a chunk that failed to decode with `err` should be retrieved as the fill value.
+///
+/// See [`CodecOptions::fill_value_on_decode_error`].
+fn fill_value_on_decode_error(err: &CodecError, options: &CodecOptions) -> bool {
+    options.fill_value_on_decode_error() && is_decode_error(err)
+}
+
+/// Returns [`ArrayError::Cancelled`] if the cancellation token in `options` has been cancelled.
+///
+/// This is checked before each chunk is decoded or encoded.
+fn check_cancelled(options: &CodecOptions) -> Result<(), ArrayError> {
+    if options.is_cancelled() {
+        Err(ArrayError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// Return the options to retrieve, update, and store a chunk with while its chunk lock is held, if `locked`.
+///
+/// A [`rayon`] thread waiting on parallel work can steal another update of the same chunk, which then blocks on the lock held further up its own stack.
+/// So a locked chunk is updated serially on the current thread, with a concurrent target of one and without a thread pool.
+fn chunk_update_options(
+    locked: bool,
+    options: &CodecOptions,
+) -> std::borrow::Cow<'_, CodecOptions> {
+    if locked {
+        let mut options = options.clone();
+        options.set_concurrent_target(1);
+        options.set_thread_pool(None);
+        std::borrow::Cow::Owned(options)
+    } else {
+        std::borrow::Cow::Borrowed(options)
+    }
+}
+
+/// Reports the progress of a multi-chunk request to the [`ProgressCallback`] of its [`CodecOptions`].
+struct ChunkProgressTracker<'a> {
+    callback: Option<&'a ProgressCallback>,
+    progress: Mutex<ChunkProgress>,
+}
+
+impl<'a> ChunkProgressTracker<'a> {
+    fn new(options: &'a CodecOptions, chunks_total: usize) -> Self {
+        Self {
+            callback: options.progress_callback(),
+            progress: Mutex::new(ChunkProgress {
+                chunks_completed: 0,
+                chunks_total,
+                bytes_transferred: 0,
+            }),
+        }
+    }
+
+    /// Record a completed chunk with `bytes` decoded bytes retrieved or stored.
+    fn chunk_completed(&self, bytes: u64) {
+        if let Some(callback) = self.callback {
+            let mut progress = self.progress.lock().unwrap();
+            progress.chunks_completed += 1;
+            progress.bytes_transferred += bytes;
+            callback.call(*progress);
+        }
+    }
+}
+
+/// Merge `patch` into `target` following JSON Merge Patch (RFC 7396) semantics.
+fn merge_patch(
+    target: &mut serde_json::Map<String, serde_json::Value>,
+    patch: &serde_json::Map<String, serde_json::Value>,
+) {
+    for (name, value) in patch {
+        match value {
+            serde_json::Value::Null => {
+                target.remove(name);
+            }
+            serde_json::Value::Object(patch) => {
+                let target = target
+                    .entry(name.clone())
+                    .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+                if !target.is_object() {
+                    *target = serde_json::Value::Object(serde_json::Map::new());
+                }
+                if let serde_json::Value::Object(target) = target {
+                    merge_patch(target, patch);
+                }
+            }
+            value => {
+                target.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Returns true if `err` is caused by a chunk that cannot be decoded (e.g. it is corrupt), rather than a storage failure.
+///
+/// Storage errors are excluded, except for invalid byte ranges which indicate a truncated chunk.
+fn is_decode_error(err: &CodecError) -> bool {
+    !matches!(
+        err.root(),
+        CodecError::StorageError(err) if !matches!(err.root(), StorageError::InvalidByteRangeError(_))
+    )
 }
 
 macro_rules! array_store_elements {
@@ -683,12 +1390,22 @@ mod array_async_writable;
 mod array_async_readable_writable;
 
 /// Transmute from `Vec<u8>` to `Vec<T>`.
+///
+/// The bytes are copied if their allocation is not aligned for `T`, which is always the case if `T` has an alignment greater than 1.
+/// Decode directly into a `Vec<T>` (e.g. with [`Array::retrieve_chunk_elements_aligned`]) to avoid the copy.
 #[must_use]
 pub fn transmute_from_bytes_vec<T: bytemuck::Pod>(from: Vec<u8>) -> Vec<T> {
     bytemuck::allocation::try_cast_vec(from)
         .unwrap_or_else(|(_err, from)| bytemuck::allocation::pod_collect_to_vec(&from))
 }
 
+/// Allocate a zeroed `Vec<T>` with the number of elements in `chunk_representation` to decode a chunk into.
+///
+/// The allocation is aligned for `T`, so decoding into its bytes avoids the copy in [`transmute_from_bytes_vec`].
+fn aligned_elements_vec<T: bytemuck::Pod>(chunk_representation: &ChunkRepresentation) -> Vec<T> {
+    bytemuck::allocation::zeroed_vec(chunk_representation.num_elements_usize())
+}
+
 /// Transmute from `Vec<T>` to `Vec<u8>`.
 #[must_use]
 pub fn transmute_to_bytes_vec<T: bytemuck::NoUninit>(from: Vec<T>) -> Vec<u8> {
@@ -696,6 +1413,63 @@ pub fn transmute_to_bytes_vec<T: bytemuck::NoUninit>(from: Vec<T>) -> Vec<u8> {
         .unwrap_or_else(|(_err, from)| bytemuck::allocation::pod_collect_to_vec(&from))
 }
 
+/// Return the linearised offsets of the elements of an orthogonal selection within a chunk.
+///
+/// `selection` holds the (selection index, chunk element index) pairs along each dimension, and `region` is the region of the chunk holding the elements.
+/// Returns (selection offset, region offset) pairs in C order of the selection.
+fn orthogonal_selection_offsets(
+    selection: &[Vec<(u64, u64)>],
+    selection_shape: &[u64],
+    region: &ArraySubset,
+) -> Vec<(usize, usize)> {
+    let strides = |shape: &[u64]| -> Vec<u64> {
+        let mut strides = vec![1; shape.len()];
+        for i in (1..shape.len()).rev() {
+            strides[i - 1] = strides[i] * shape[i];
+        }
+        strides
+    };
+    let selection_strides = strides(selection_shape);
+    let region_strides = strides(region.shape());
+    let dimension_offsets: Vec<Vec<(u64, u64)>> = selection
+        .iter()
+        .enumerate()
+        .map(|(axis, elements)| {
+            elements
+                .iter()
+                .map(|&(selection_index, chunk_element_index)| {
+                    (
+                        selection_index * selection_strides[axis],
+                        (chunk_element_index - region.start()[axis]) * region_strides[axis],
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    let selection_counts = selection
+        .iter()
+        .map(|elements| elements.len() as u64)
+        .collect();
+    ArraySubset::new_with_shape(selection_counts)
+        .indices()
+        .iter()
+        .map(|combination| {
+            let (selection_offset, region_offset) =
+                std::iter::zip(&combination, &dimension_offsets).fold(
+                    (0, 0),
+                    |(selection_offset, region_offset), (&i, offsets)| {
+                        let (selection_i, region_i) = offsets[usize::try_from(i).unwrap()];
+                        (selection_offset + selection_i, region_offset + region_i)
+                    },
+                );
+            (
+                usize::try_from(selection_offset).unwrap(),
+                usize::try_from(region_offset).unwrap(),
+            )
+        })
+        .collect()
+}
+
 /// Unravel a linearised index to ND indices.
 #[must_use]
 pub fn unravel_index(mut index: u64, shape: &[u64]) -> ArrayIndices {
@@ -759,6 +1533,27 @@ pub fn elements_to_ndarray<T>(
     })
 }
 
+#[cfg(feature = "ndarray")]
+/// Convert a vector of elements in F (column-major) order to an [`ndarray::ArrayD`] with F memory layout.
+///
+/// # Errors
+/// Returns an error if the length of `elements` is not equal to the product of the components in `shape`.
+pub fn elements_to_ndarray_fortran<T>(
+    shape: &[u64],
+    elements: Vec<T>,
+) -> Result<ndarray::ArrayD<T>, ArrayError> {
+    use ndarray::ShapeBuilder;
+    let length = elements.len();
+    ndarray::ArrayD::<T>::from_shape_vec(iter_u64_to_usize(shape.iter()).f(), elements).map_err(
+        |_| {
+            ArrayError::CodecError(codec::CodecError::UnexpectedChunkDecodedSize(
+                length * std::mem::size_of::<T>(),
+                shape.iter().product::<u64>() * std::mem::size_of::<T>() as u64,
+            ))
+        },
+    )
+}
+
 #[cfg(feature = "ndarray")]
 /// Convert a vector of bytes to an [`ndarray::ArrayD`].
 ///
@@ -778,7 +1573,13 @@ pub fn bytes_to_ndarray<T: bytemuck::Pod>(
 
 #[cfg(test)]
 mod tests {
-    use crate::storage::store::MemoryStore;
+    use itertools::Itertools;
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+    use crate::storage::{
+        store::MemoryStore, ListableStorageTraits, MetadataWriteError, MetadataWriteOptions,
+        ReadableStorageTraits, WritableStorageTraits,
+    };
 
     use super::*;
 
@@ -892,44 +1693,2188 @@ mod tests {
             .is_none());
     }
 
-    // fn array_subset_locking(locks: StoreLocks, expect_equal: bool) {
-    //     let store = Arc::new(MemoryStore::new_with_locks(locks));
-
-    //     let array_path = "/array";
-    //     let array = ArrayBuilder::new(
-    //         vec![100, 4],
-    //         DataType::UInt8,
-    //         vec![10, 2].try_into().unwrap(),
-    //         FillValue::from(0u8),
-    //     )
-    //     .build(store, array_path)
-    //     .unwrap();
-
-    //     let mut any_not_equal = false;
-    //     for j in 1..10 {
-    //         (0..100).into_par_iter().for_each(|i| {
-    //             let subset = ArraySubset::new_with_ranges(&[i..i + 1, 0..4]);
-    //             array.store_array_subset(&subset, vec![j; 4]).unwrap();
-    //         });
-    //         let subset_all = ArraySubset::new_with_shape(array.shape().to_vec());
-    //         let data_all = array.retrieve_array_subset(&subset_all).unwrap();
-    //         let all_equal = data_all.iter().all_equal_value() == Ok(&j);
-    //         if expect_equal {
-    //             assert!(all_equal);
-    //         } else {
-    //             any_not_equal |= !all_equal;
-    //         }
-    //     }
-    //     if !expect_equal {
-    //         assert!(any_not_equal);
-    //     }
-    // }
+    #[test]
+    fn array_store_fill_value_chunk_erases() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array.store_chunk(&[0, 0], vec![1; 4]).unwrap();
+        array.store_chunk(&[0, 1], vec![1; 4]).unwrap();
+        array.store_chunk(&[1, 0], vec![1; 4]).unwrap();
+        assert!(array.chunk_exists(&[0, 0]).unwrap());
+
+        // Overwriting a chunk with the fill value erases it
+        array.store_chunk(&[0, 0], vec![0; 4]).unwrap();
+        assert!(!array.chunk_exists(&[0, 0]).unwrap());
+
+        // Unless empty chunks are stored
+        let mut options = codec::CodecOptions::default();
+        options.set_store_empty_chunks(true);
+        array
+            .store_chunk_opt(&[0, 0], vec![0; 4], &options)
+            .unwrap();
+        assert!(array.chunk_exists(&[0, 0]).unwrap());
 
-    // #[test]
-    // #[cfg_attr(miri, ignore)]
-    // fn array_subset_locking_default() {
-    //     array_subset_locking(Arc::new(DefaultStoreLocks::default()), true);
-    // }
+        array
+            .store_array_subset(&ArraySubset::new_with_ranges(&[0..2, 2..4]), vec![0; 4])
+            .unwrap();
+        assert!(!array.chunk_exists(&[0, 1]).unwrap());
+
+        array
+            .erase_chunks(&ArraySubset::new_with_shape(vec![2, 2]))
+            .unwrap();
+        assert!(array.chunks_stored().unwrap().is_empty());
+    }
+
+    #[test]
+    fn array_prefetch_array_subset() {
+        use crate::storage::storage_transformer::{
+            CacheStorageTransformer, StorageTransformerExtension,
+        };
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_chunk_elements::<u16>(&[0, 0], vec![1; 4])
+            .unwrap();
+        array
+            .store_chunk_elements::<u16>(&[1, 1], vec![2; 4])
+            .unwrap();
+
+        let cache = Arc::new(CacheStorageTransformer::new(1024));
+        let array = Array::new(cache.clone().create_readable_transformer(store), "/array").unwrap();
+        let array_subset = ArraySubset::new_with_ranges(&[0..4, 0..4]);
+        array.prefetch_array_subset(&array_subset).unwrap();
+        assert_eq!(cache.len(), 5); // metadata and 4 chunks, including missing chunks
+        assert_eq!(cache.misses(), 5);
+
+        // Retrieval is served from the cache
+        let elements = array
+            .retrieve_array_subset_elements::<u16>(&array_subset)
+            .unwrap();
+        assert_eq!(
+            elements,
+            vec![1, 1, 0, 0, 1, 1, 0, 0, 0, 0, 2, 2, 0, 0, 2, 2]
+        );
+        assert_eq!((cache.hits(), cache.misses()), (4, 5));
+
+        assert!(array
+            .prefetch_array_subset(&ArraySubset::new_with_shape(vec![4]))
+            .is_err());
+    }
+
+    #[test]
+    fn array_chunks_stored() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        assert!(array.chunks_stored().unwrap().is_empty());
+
+        array
+            .store_chunk_elements::<u16>(&[0, 1], vec![1; 4])
+            .unwrap();
+        array
+            .store_chunk_elements::<u16>(&[1, 0], vec![1; 4])
+            .unwrap();
+        assert!(array.chunk_exists(&[0, 1]).unwrap());
+        assert!(!array.chunk_exists(&[0, 0]).unwrap());
+        assert_eq!(array.chunk_stored_size(&[1, 0]).unwrap(), Some(8));
+        assert_eq!(array.chunk_stored_size(&[1, 1]).unwrap(), None);
+        assert!(array.chunk_exists(&[0]).is_err());
+        assert_eq!(array.chunks_stored().unwrap(), vec![vec![0, 1], vec![1, 0]]);
+    }
+
+    #[cfg(feature = "sharding")]
+    #[test]
+    fn array_retrieve_array_subsets() {
+        use crate::storage::storage_transformer::PerformanceMetricsStorageTransformer;
+
+        let store = Arc::new(MemoryStore::default());
+        let performance_metrics = Arc::new(PerformanceMetricsStorageTransformer::new());
+        let storage = StorageTransformerChain::new(vec![performance_metrics.clone()])
+            .create_readable_writable_transformer(store);
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .array_to_bytes_codec(Box::new(
+            codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+                vec![2, 2].try_into().unwrap(),
+            )
+            .build(),
+        ))
+        .build(storage, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![8, 4]),
+                (0..32).collect(),
+            )
+            .unwrap();
+
+        let array_subsets = [
+            ArraySubset::new_with_ranges(&[0..1, 0..1]),
+            ArraySubset::new_with_ranges(&[2..4, 1..3]),
+            ArraySubset::new_with_ranges(&[3..6, 2..6]),
+            ArraySubset::new_with_ranges(&[0..0, 0..0]),
+            ArraySubset::new_with_ranges(&[6..10, 0..2]),
+        ];
+        let reads = performance_metrics.reads();
+        let subsets_bytes = array.retrieve_array_subsets(&array_subsets).unwrap();
+        let subsets_reads = performance_metrics.reads() - reads;
+        let reads = performance_metrics.reads();
+        for (array_subset, subset_bytes) in std::iter::zip(&array_subsets, subsets_bytes) {
+            assert_eq!(
+                subset_bytes,
+                array.retrieve_array_subset(array_subset).unwrap()
+            );
+        }
+        assert!(subsets_reads < performance_metrics.reads() - reads);
+
+        assert!(array
+            .retrieve_array_subsets(&[ArraySubset::new_with_shape(vec![1])])
+            .is_err());
+    }
+
+    #[test]
+    fn array_retrieve_array_subset_into() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(9u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_ranges(&[0..2, 0..4]),
+                (0..8).collect(),
+            )
+            .unwrap();
+
+        let mut elements = vec![0u16; 6];
+        for subset in [
+            ArraySubset::new_with_ranges(&[1..3, 1..4]),
+            ArraySubset::new_with_ranges(&[0..2, 1..4]),
+            ArraySubset::new_with_ranges(&[2..4, 0..3]),
+        ] {
+            array
+                .retrieve_array_subset_elements_into::<u16>(&subset, &mut elements)
+                .unwrap();
+            assert_eq!(
+                elements,
+                array
+                    .retrieve_array_subset_elements::<u16>(&subset)
+                    .unwrap()
+            );
+        }
+        assert_eq!(elements, vec![9; 6]);
+
+        let mut bytes = vec![0u8; 4];
+        let subset = ArraySubset::new_with_ranges(&[0..1, 2..4]);
+        array
+            .retrieve_array_subset_into(&subset, &mut bytes)
+            .unwrap();
+        assert_eq!(bytes, array.retrieve_array_subset(&subset).unwrap());
+        assert!(matches!(
+            array.retrieve_array_subset_into(&subset, &mut bytes[..3]),
+            Err(ArrayError::InvalidBytesInputSize(3, 4))
+        ));
+        assert!(array
+            .retrieve_array_subset_elements_into::<u8>(&subset, &mut bytes)
+            .is_err());
+    }
+
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn array_retrieve_array_subset_parallel_assembly() {
+        // Chunks with enough contiguous runs in a subset to be assembled across tasks
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![600, 300],
+            DataType::UInt16,
+            vec![300, 300].try_into().unwrap(),
+            FillValue::from(u16::MAX),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_chunk_subset_elements::<u16>(
+                &[0, 0],
+                &ArraySubset::new_with_ranges(&[1..299, 1..299]),
+                (0..298 * 298u32)
+                    .map(|i| u16::try_from(i % 1000).unwrap())
+                    .collect(),
+            )
+            .unwrap();
+
+        let subset = ArraySubset::new_with_ranges(&[1..599, 1..299]);
+        let elements = array
+            .retrieve_array_subset_elements::<u16>(&subset)
+            .unwrap();
+        let expected = subset
+            .indices()
+            .into_iter()
+            .map(|indices| {
+                if (1..299).contains(&indices[0]) && (1..299).contains(&indices[1]) {
+                    u16::try_from(((indices[0] - 1) * 298 + indices[1] - 1) % 1000).unwrap()
+                } else {
+                    u16::MAX
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(elements, expected);
+    }
+
+    #[test]
+    fn array_fill_value_on_decode_error() {
+        use crate::storage::WritableStorageTraits;
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .bytes_to_bytes_codecs(vec![Box::new(codec::Crc32cCodec::new())])
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(vec![4, 4]),
+                (1..=16).collect(),
+            )
+            .unwrap();
+
+        // Truncate chunk [0, 1]
+        let key = array.chunk_key(&[0, 1]);
+        store.erase(&key).unwrap();
+        store.set(&key, &[0]).unwrap();
+
+        let subset = ArraySubset::new_with_ranges(&[0..2, 1..4]);
+        assert!(array.retrieve_chunk(&[0, 1]).is_err());
+        assert!(array.retrieve_array_subset(&subset).is_err());
+
+        let options = CodecOptions::builder()
+            .fill_value_on_decode_error(true)
+            .build();
+        assert_eq!(
+            array.retrieve_chunk_opt(&[0, 1], &options).unwrap(),
+            vec![0; 4]
+        );
+        assert_eq!(
+            array
+                .retrieve_chunk_subset_opt(
+                    &[0, 1],
+                    &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+                    &options
+                )
+                .unwrap(),
+            vec![0; 2]
+        );
+        assert_eq!(
+            array.retrieve_array_subset_opt(&subset, &options).unwrap(),
+            vec![2, 0, 0, 6, 0, 0]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subsets_opt(std::slice::from_ref(&subset), &options)
+                .unwrap(),
+            vec![vec![2, 0, 0, 6, 0, 0]]
+        );
+    }
+
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn array_chunk_error_context() {
+        use crate::storage::{ReadableStorageTraits, WritableStorageTraits};
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .bytes_to_bytes_codecs(vec![Box::new(codec::Crc32cCodec::new())])
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(vec![4, 4]),
+                (1..=16).collect(),
+            )
+            .unwrap();
+
+        // Corrupt the checksum of chunk [0, 1]
+        let key = array.chunk_key(&[0, 1]);
+        let mut chunk = Vec::from(store.get(&key).unwrap().unwrap());
+        *chunk.last_mut().unwrap() ^= 1;
+        store.set(&key, &chunk).unwrap();
+
+        let err = array
+            .retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..2, 1..4]))
+            .unwrap_err();
+        assert_eq!(err.chunk_indices(), Some([0, 1].as_slice()));
+        assert_eq!(err.key(), Some(&key));
+        assert!(err.to_string().contains("array/c/0/1"));
+        let ArrayError::CodecError(codec_err) = err.root() else {
+            panic!("expected a codec error, got {err:?}");
+        };
+        assert_eq!(codec_err.codecs(), vec!["crc32c"]);
+        assert!(matches!(codec_err.root(), CodecError::InvalidChecksum));
+
+        // Errors unrelated to a chunk have no chunk context
+        let err = array.retrieve_chunk(&[0]).unwrap_err();
+        assert!(err.chunk_indices().is_none());
+        assert!(std::ptr::eq(err.root(), &err));
+    }
+
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn array_retrieve_array_subset_lenient() {
+        use crate::storage::WritableStorageTraits;
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .bytes_to_bytes_codecs(vec![Box::new(codec::Crc32cCodec::new())])
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(vec![4, 4]),
+                (1..=16).collect(),
+            )
+            .unwrap();
+
+        let subset = ArraySubset::new_with_ranges(&[1..4, 0..4]);
+        let (elements, errors) = array
+            .retrieve_array_subset_elements_lenient::<u8>(&subset)
+            .unwrap();
+        assert_eq!(elements, (5..=16).collect::<Vec<u8>>());
+        assert!(errors.is_empty());
+
+        // Truncate chunks [1, 1] and [0, 0]
+        for chunk_indices in [[1, 1], [0, 0]] {
+            let key = array.chunk_key(&chunk_indices);
+            store.erase(&key).unwrap();
+            store.set(&key, &[0]).unwrap();
+        }
+
+        // Fill value on decode error is ignored
+        let options = CodecOptions::builder()
+            .fill_value_on_decode_error(true)
+            .build();
+        let (bytes, errors) = array
+            .retrieve_array_subset_lenient_opt(&subset, &options)
+            .unwrap();
+        assert_eq!(bytes, vec![0, 0, 7, 8, 9, 10, 0, 0, 13, 14, 0, 0]);
+        assert_eq!(
+            errors
+                .iter()
+                .map(ChunkDecodeError::chunk_indices)
+                .collect::<Vec<_>>(),
+            vec![&[0, 0], &[1, 1]]
+        );
+        assert!(array.retrieve_array_subset(&subset).is_err());
+    }
+
+    #[test]
+    fn array_big_endian_round_trip() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![2, 2],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .array_to_bytes_codec(Box::new(codec::BytesCodec::big()))
+        .build(store.clone(), "/array")
+        .unwrap();
+        let subset = ArraySubset::new_with_shape(vec![2, 2]);
+        array
+            .store_array_subset_elements::<u16>(&subset, vec![0x0102, 0x0304, 0x0506, 0x0708])
+            .unwrap();
+
+        // Stored big endian, retrieved native
+        assert_eq!(
+            store.get(&array.chunk_key(&[0, 0])).unwrap().unwrap(),
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&subset)
+                .unwrap(),
+            vec![0x0102, 0x0304, 0x0506, 0x0708]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_endian(
+                    &subset,
+                    codec::array_to_bytes::bytes::Endianness::Big
+                )
+                .unwrap(),
+            vec![1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_endian(
+                    &subset,
+                    codec::array_to_bytes::bytes::Endianness::Little
+                )
+                .unwrap(),
+            vec![2, 1, 4, 3, 6, 5, 8, 7]
+        );
+
+        array
+            .store_array_subset_endian(
+                &ArraySubset::new_with_ranges(&[0..1, 0..2]),
+                vec![0, 9, 0, 10],
+                codec::array_to_bytes::bytes::Endianness::Big,
+            )
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&subset)
+                .unwrap(),
+            vec![9, 10, 0x0506, 0x0708]
+        );
+    }
+
+    #[cfg(feature = "transpose")]
+    #[test]
+    fn array_store_array_subset_fortran() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+        // [[1, 2, 3], [4, 5, 6]] in F order
+        let subset = ArraySubset::new_with_ranges(&[1..3, 0..3]);
+        array
+            .store_array_subset_elements_fortran::<u16>(&subset, vec![1, 4, 2, 5, 3, 6])
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&subset)
+                .unwrap(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert!(matches!(
+            array.store_array_subset_fortran(&subset, vec![0; 3]),
+            Err(ArrayError::InvalidBytesInputSize(3, 12))
+        ));
+    }
+
+    #[cfg(feature = "transpose")]
+    #[test]
+    fn array_retrieve_array_subset_fortran() {
+        let transpose = |order: &str| -> Box<dyn codec::ArrayToArrayCodecTraits> {
+            Box::new(
+                codec::TransposeCodec::new_with_configuration(
+                    &serde_json::from_str(&format!(r#"{{"order":{order}}}"#)).unwrap(),
+                )
+                .unwrap(),
+            )
+        };
+        let builder = || {
+            ArrayBuilder::new(
+                vec![4, 5, 6],
+                DataType::UInt16,
+                vec![2, 3, 4].try_into().unwrap(),
+                FillValue::from(0u16),
+            )
+        };
+        let mut builders = vec![builder(), builder(), builder()];
+        builders[1].array_to_array_codecs(vec![transpose("[2,1,0]")]);
+        builders[2]
+            .array_to_array_codecs(vec![transpose("[2,1,0]")])
+            .array_to_bytes_codec(Box::new(
+                codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+                    vec![1, 3, 2].try_into().unwrap(),
+                )
+                .build(),
+            ));
+        let mut fallback = builder();
+        fallback.array_to_array_codecs(vec![transpose("[1,0,2]")]);
+        builders.push(fallback);
+
+        for (builder, transpose_fortran) in std::iter::zip(builders, [false, true, true, false]) {
+            let array = builder
+                .build(Arc::new(MemoryStore::default()), "/array")
+                .unwrap();
+            assert_eq!(
+                array.codecs_without_fortran_transpose().is_some(),
+                transpose_fortran
+            );
+            // Leave the last chunks empty
+            let elements: Vec<u16> = (1..=90).collect();
+            array
+                .store_array_subset_elements::<u16>(
+                    &ArraySubset::new_with_shape(vec![3, 5, 6]),
+                    elements,
+                )
+                .unwrap();
+
+            for subset in [
+                ArraySubset::new_with_shape(vec![4, 5, 6]),
+                ArraySubset::new_with_ranges(&[1..4, 2..5, 3..5]),
+                ArraySubset::new_with_ranges(&[0..1, 0..3, 0..4]),
+            ] {
+                let elements_c = array
+                    .retrieve_array_subset_elements::<u16>(&subset)
+                    .unwrap();
+                let elements_f = array
+                    .retrieve_array_subset_elements_fortran::<u16>(&subset)
+                    .unwrap();
+                let bytes_f = codec::array_to_array::transpose::c_order_to_f_order(
+                    transmute_to_bytes_vec(elements_c),
+                    subset.shape(),
+                    2,
+                )
+                .unwrap();
+                assert_eq!(elements_f, transmute_from_bytes_vec::<u16>(bytes_f));
+
+                #[cfg(feature = "ndarray")]
+                {
+                    let ndarray_c = array.retrieve_array_subset_ndarray::<u16>(&subset).unwrap();
+                    let ndarray_f = array
+                        .retrieve_array_subset_ndarray_fortran::<u16>(&subset)
+                        .unwrap();
+                    assert_eq!(ndarray_c, ndarray_f);
+                    assert!(ndarray_f.t().is_standard_layout());
+                }
+            }
+            assert!(array
+                .retrieve_array_subset_fortran(&ArraySubset::new_with_ranges(&[0..1, 0..1]))
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn array_bfloat16_round_trip() {
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::BFloat16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(ZARR_NAN_BF16),
+        )
+        .array_to_bytes_codec(Box::new(codec::BytesCodec::big()))
+        .build(store.clone(), array_path)
+        .unwrap();
+        array.store_metadata().unwrap();
+
+        let elements: Vec<half::bf16> = (0..6u8)
+            .map(|i| half::bf16::from_f32(f32::from(i) * -0.5))
+            .collect();
+        let subset = ArraySubset::new_with_ranges(&[1..3, 0..3]);
+        array
+            .store_array_subset_elements::<half::bf16>(&subset, elements.clone())
+            .unwrap();
+
+        let array = Array::new(store, array_path).unwrap();
+        assert_eq!(array.data_type(), &DataType::BFloat16);
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<half::bf16>(&subset)
+                .unwrap(),
+            elements
+        );
+        assert!(array
+            .retrieve_array_subset_elements::<half::bf16>(&ArraySubset::new_with_ranges(&[
+                0..1,
+                0..1
+            ]))
+            .unwrap()[0]
+            .is_nan());
+        assert!(array
+            .retrieve_array_subset_elements::<u32>(&subset)
+            .is_err());
+
+        #[cfg(feature = "ndarray")]
+        {
+            let ndarray = array
+                .retrieve_array_subset_ndarray::<half::bf16>(&subset)
+                .unwrap();
+            assert_eq!(ndarray.shape(), &[2, 3]);
+            assert_eq!(ndarray.iter().copied().collect::<Vec<_>>(), elements);
+            array
+                .store_array_subset_ndarray(&[3, 3], ndarray::array![[half::bf16::ONE]])
+                .unwrap();
+            assert_eq!(
+                array
+                    .retrieve_array_subset_ndarray::<half::bf16>(&ArraySubset::new_with_ranges(&[
+                        3..4,
+                        3..4
+                    ]))
+                    .unwrap(),
+                ndarray::array![[half::bf16::ONE]].into_dyn()
+            );
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn array_datetime_round_trip() {
+        use chrono::{TimeDelta, TimeZone, Utc};
+
+        use crate::array::data_type::{DateTimeUnit, NAT};
+
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4],
+            DataType::DateTime64(DateTimeUnit::Second),
+            vec![2].try_into().unwrap(),
+            FillValue::from(NAT),
+        )
+        .build(store.clone(), "/datetime")
+        .unwrap();
+        let datetimes = vec![
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+            None,
+            Some(Utc.with_ymd_and_hms(1900, 1, 1, 0, 0, 0).unwrap()),
+        ];
+        let subset = ArraySubset::new_with_start_shape(vec![0], vec![3]).unwrap();
+        array
+            .store_array_subset_datetimes(&subset, &datetimes)
+            .unwrap();
+        assert_eq!(
+            array.retrieve_array_subset_datetimes(&subset).unwrap(),
+            datetimes
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_datetimes(
+                    &ArraySubset::new_with_start_shape(vec![3], vec![1]).unwrap()
+                )
+                .unwrap(),
+            vec![None]
+        );
+        assert!(array.retrieve_array_subset_timedeltas(&subset).is_err());
+
+        let array = ArrayBuilder::new(
+            vec![2],
+            DataType::TimeDelta64(DateTimeUnit::Millisecond),
+            vec![2].try_into().unwrap(),
+            FillValue::from(0i64),
+        )
+        .build(store, "/timedelta")
+        .unwrap();
+        let timedeltas = vec![Some(TimeDelta::try_milliseconds(-1500).unwrap()), None];
+        let subset = ArraySubset::new_with_start_shape(vec![0], vec![2]).unwrap();
+        array
+            .store_array_subset_timedeltas(&subset, &timedeltas)
+            .unwrap();
+        assert_eq!(
+            array.retrieve_array_subset_timedeltas(&subset).unwrap(),
+            timedeltas
+        );
+    }
+
+    #[test]
+    fn array_raw_bits_round_trip() {
+        let store = Arc::new(MemoryStore::default());
+        let array_path = "/array";
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::RawBits(3),
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from([0u8, 0, 255]),
+        )
+        .bytes_to_bytes_codecs(vec![
+            #[cfg(feature = "gzip")]
+            Box::new(codec::GzipCodec::new(5).unwrap()),
+        ])
+        .build(store.clone(), array_path)
+        .unwrap();
+        array.store_metadata().unwrap();
+
+        let records: Vec<[u8; 3]> = (0..4u8).map(|i| [i, i + 1, i + 2]).collect();
+        array
+            .store_array_subset_elements::<[u8; 3]>(
+                &ArraySubset::new_with_ranges(&[1..3, 1..3]),
+                records.clone(),
+            )
+            .unwrap();
+
+        let array = Array::new(store, array_path).unwrap();
+        assert_eq!(array.data_type(), &DataType::RawBits(3));
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<[u8; 3]>(&ArraySubset::new_with_ranges(&[
+                    1..3,
+                    1..3
+                ]))
+                .unwrap(),
+            records
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<[u8; 3]>(&ArraySubset::new_with_ranges(&[
+                    0..1,
+                    0..1
+                ]))
+                .unwrap(),
+            vec![[0, 0, 255]]
+        );
+    }
+
+    fn array_empty_builders() -> Vec<ArrayBuilder> {
+        let builder = || {
+            ArrayBuilder::new(
+                vec![4, 4],
+                DataType::UInt16,
+                vec![2, 2].try_into().unwrap(),
+                FillValue::from(7u16),
+            )
+        };
+        #[allow(unused_mut)]
+        let mut builders = vec![builder()];
+        #[cfg(feature = "transpose")]
+        {
+            let mut builder = builder();
+            builder.array_to_array_codecs(vec![Box::new(
+                codec::TransposeCodec::new_with_configuration(
+                    &serde_json::from_str(r#"{"order": [1, 0]}"#).unwrap(),
+                )
+                .unwrap(),
+            )]);
+            builders.push(builder);
+        }
+        #[cfg(feature = "gzip")]
+        {
+            let mut builder = builder();
+            builder.bytes_to_bytes_codecs(vec![Box::new(codec::GzipCodec::new(5).unwrap())]);
+            builders.push(builder);
+        }
+        #[cfg(feature = "zstd")]
+        {
+            let mut builder = builder();
+            builder.bytes_to_bytes_codecs(vec![Box::new(codec::ZstdCodec::new(5, false))]);
+            builders.push(builder);
+        }
+        #[cfg(feature = "blosc")]
+        {
+            use codec::bytes_to_bytes::blosc::{BloscCompressor, BloscShuffleMode};
+            let mut builder = builder();
+            builder.bytes_to_bytes_codecs(vec![Box::new(
+                codec::BloscCodec::new(
+                    BloscCompressor::LZ4,
+                    5.try_into().unwrap(),
+                    None,
+                    BloscShuffleMode::Shuffle,
+                    Some(2),
+                )
+                .unwrap(),
+            )]);
+            builders.push(builder);
+        }
+        #[cfg(feature = "sharding")]
+        {
+            let mut builder = builder();
+            builder.array_to_bytes_codec(Box::new(
+                codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+                    vec![1, 1].try_into().unwrap(),
+                )
+                .build(),
+            ));
+            builders.push(builder);
+        }
+        builders
+    }
+
+    #[test]
+    fn array_empty_dimension() {
+        for mut builder in array_empty_builders() {
+            let store = Arc::new(MemoryStore::default());
+            let array = builder
+                .shape(vec![0, 4])
+                .build(store.clone(), "/array")
+                .unwrap();
+            array.store_metadata().unwrap();
+            assert_eq!(array.chunk_grid_shape(), Some(vec![0, 2]));
+
+            let subset_all = ArraySubset::new_with_shape(array.shape().to_vec());
+            array
+                .store_array_subset_elements::<u16>(&subset_all, vec![])
+                .unwrap();
+            assert_eq!(store.list().unwrap().len(), 1); // metadata only
+            assert!(array.retrieve_array_subset(&subset_all).unwrap().is_empty());
+            assert!(array
+                .retrieve_chunks(&ArraySubset::new_with_shape(vec![0, 2]))
+                .unwrap()
+                .is_empty());
+            assert!(array.chunks_stored().unwrap().is_empty());
+            #[cfg(feature = "ndarray")]
+            assert_eq!(
+                array
+                    .retrieve_array_subset_ndarray::<u16>(&subset_all)
+                    .unwrap()
+                    .shape(),
+                &[0, 4]
+            );
+        }
+    }
+
+    #[test]
+    fn array_empty_subset() {
+        for builder in array_empty_builders() {
+            let store = Arc::new(MemoryStore::default());
+            let array = builder.build(store.clone(), "/array").unwrap();
+            array
+                .store_array_subset_elements::<u16>(
+                    &ArraySubset::new_with_ranges(&[0..2, 0..4]),
+                    (0..8).collect(),
+                )
+                .unwrap();
+            let data = array
+                .retrieve_array_subset(&ArraySubset::new_with_shape(vec![4, 4]))
+                .unwrap();
+
+            for subset in [
+                ArraySubset::new_with_ranges(&[1..1, 0..4]),
+                ArraySubset::new_with_ranges(&[0..4, 3..3]),
+                ArraySubset::new_with_ranges(&[4..4, 4..4]),
+            ] {
+                assert!(array.retrieve_array_subset(&subset).unwrap().is_empty());
+                assert_eq!(
+                    array
+                        .retrieve_array_subsets(std::slice::from_ref(&subset))
+                        .unwrap(),
+                    vec![Vec::<u8>::new()]
+                );
+                array.store_array_subset(&subset, vec![]).unwrap();
+                assert!(array.store_array_subset(&subset, vec![0; 2]).is_err());
+            }
+
+            let chunk_subset = ArraySubset::new_with_ranges(&[0..0, 0..2]);
+            for chunk_indices in [[0, 0], [1, 1]] {
+                assert!(array
+                    .retrieve_chunk_subset(&chunk_indices, &chunk_subset)
+                    .unwrap()
+                    .is_empty());
+                assert_eq!(
+                    array
+                        .partial_decoder(&chunk_indices)
+                        .unwrap()
+                        .partial_decode(std::slice::from_ref(&chunk_subset))
+                        .unwrap(),
+                    vec![Vec::<u8>::new()]
+                );
+                array
+                    .store_chunk_subset(&chunk_indices, &chunk_subset, vec![])
+                    .unwrap();
+            }
+            assert!(!array.chunk_exists(&[1, 1]).unwrap());
+            assert_eq!(
+                array
+                    .retrieve_array_subset(&ArraySubset::new_with_shape(vec![4, 4]))
+                    .unwrap(),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn array_strided_round_trip() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![8, 8]),
+                (0..64).collect(),
+            )
+            .unwrap();
+        let step = [
+            std::num::NonZeroU64::new(4).unwrap(),
+            std::num::NonZeroU64::new(3).unwrap(),
+        ];
+
+        // Chunks skipped over by the step are not decoded
+        let strided = ArraySubset::new_with_ranges(&[1..8, 0..8])
+            .with_step(&step)
+            .unwrap();
+        for chunk_indices in [[0, 2], [1, 0], [1, 1], [2, 2], [3, 3]] {
+            let key = array.chunk_key(&chunk_indices);
+            store.erase(&key).unwrap();
+            store.set(&key, &[0xff]).unwrap();
+        }
+        assert!(array
+            .retrieve_array_subset(&ArraySubset::new_with_shape(vec![8, 8]))
+            .is_err());
+        assert_eq!(
+            array
+                .retrieve_array_subset_strided_elements::<u16>(&strided)
+                .unwrap(),
+            vec![8, 11, 14, 40, 43, 46]
+        );
+        #[cfg(feature = "ndarray")]
+        assert_eq!(
+            array
+                .retrieve_array_subset_strided_ndarray::<u16>(&strided)
+                .unwrap()
+                .shape(),
+            &[2, 3]
+        );
+        assert!(array
+            .retrieve_array_subset_strided(
+                &ArraySubset::new_with_ranges(&[1..10, 0..8])
+                    .with_step(&step)
+                    .unwrap()
+            )
+            .is_err());
+
+        // Store
+        let array = ArrayBuilder::new(
+            vec![7, 7],
+            DataType::UInt16,
+            vec![3, 3].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array_store")
+        .unwrap();
+        let strided = ArraySubset::new_with_ranges(&[0..7, 1..7])
+            .with_step(&[
+                std::num::NonZeroU64::new(3).unwrap(),
+                std::num::NonZeroU64::new(2).unwrap(),
+            ])
+            .unwrap();
+        assert_eq!(strided.shape(), &[3, 3]);
+        assert!(array
+            .store_array_subset_strided_elements::<u16>(&strided, vec![1; 8])
+            .is_err());
+        array
+            .store_array_subset_strided_elements::<u16>(&strided, (1..10).collect())
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![7, 7]))
+                .unwrap(),
+            vec![
+                0, 1, 0, 2, 0, 3, 0, //
+                0, 0, 0, 0, 0, 0, 0, //
+                0, 0, 0, 0, 0, 0, 0, //
+                0, 4, 0, 5, 0, 6, 0, //
+                0, 0, 0, 0, 0, 0, 0, //
+                0, 0, 0, 0, 0, 0, 0, //
+                0, 7, 0, 8, 0, 9, 0, //
+            ]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_strided_elements::<u16>(&strided)
+                .unwrap(),
+            (1..10).collect::<Vec<u16>>()
+        );
+    }
+
+    #[test]
+    fn array_elements_at() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .bytes_to_bytes_codecs(vec![
+            #[cfg(feature = "gzip")]
+            Box::new(codec::GzipCodec::new(5).unwrap()),
+        ])
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_ranges(&[0..4, 0..8]),
+                (0..32).collect(),
+            )
+            .unwrap();
+
+        // Values are returned in input order, including for chunks that do not exist
+        let points = [[3, 7], [0, 0], [7, 7], [1, 2], [3, 7]];
+        assert_eq!(
+            array.retrieve_elements_at::<u16, _>(&points).unwrap(),
+            vec![31, 0, 0, 10, 31]
+        );
+        assert_eq!(
+            array.retrieve_elements_at::<u16, _>(&[vec![2, 5]]).unwrap(),
+            vec![21]
+        );
+        assert!(array
+            .retrieve_elements_at::<u16, [u64; 2]>(&[])
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            array.retrieve_elements_at::<u16, _>(&[[8, 0]]),
+            Err(ArrayError::InvalidArrayIndices(_, _))
+        ));
+        assert!(array.retrieve_elements_at::<u16, _>(&[[0]]).is_err());
+        assert!(array.retrieve_elements_at::<u8, _>(&[[0, 0]]).is_err());
+
+        // The last of duplicate array indices is stored
+        array
+            .store_elements_at::<u16, _>(
+                &[[0, 0], [6, 1], [0, 0], [7, 7]],
+                vec![100, 101, 102, 103],
+            )
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_elements_at::<u16, _>(&[[0, 0], [6, 1], [7, 7], [0, 1]])
+                .unwrap(),
+            vec![102, 101, 103, 1]
+        );
+        assert!(array.chunk_exists(&[1, 1]).unwrap());
+        assert!(matches!(
+            array.store_elements_at::<u16, _>(&[[0, 0]], vec![1, 2]),
+            Err(ArrayError::InvalidBytesInputSize(4, 2))
+        ));
+        assert!(array
+            .store_elements_at::<u16, _>(&[[0, 8]], vec![1])
+            .is_err());
+    }
+
+    #[test]
+    fn array_masked() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_ranges(&[0..4, 0..8]),
+                (0..32).collect(),
+            )
+            .unwrap();
+
+        // Selected elements are returned packed in C order
+        let array_subset = ArraySubset::new_with_ranges(&[2..6, 2..6]);
+        let diagonal: Vec<bool> = (0..16).map(|i| i % 5 == 0).collect();
+        assert_eq!(
+            array
+                .retrieve_array_subset_masked_elements::<u16>(&array_subset, &diagonal)
+                .unwrap(),
+            vec![18, 27, 0, 0]
+        );
+        assert!(array
+            .retrieve_array_subset_masked_elements::<u16>(&array_subset, &[false; 16])
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            array.retrieve_array_subset_masked_elements::<u16>(&array_subset, &[true; 15]),
+            Err(ArrayError::InvalidMaskSize(15, 16))
+        ));
+        assert!(array
+            .retrieve_array_subset_masked_elements::<u16>(
+                &ArraySubset::new_with_ranges(&[6..9, 0..1]),
+                &[true; 3]
+            )
+            .is_err());
+
+        // Only selected elements are updated, and chunks without selected elements are not written
+        let mut mask = vec![false; 16];
+        mask[0] = true;
+        mask[5] = true;
+        array
+            .store_array_subset_masked_elements::<u16>(&array_subset, &mask, vec![200, 201])
+            .unwrap();
+        assert!(!array.chunk_exists(&[1, 1]).unwrap());
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[2..4, 2..4]))
+                .unwrap(),
+            vec![200, 19, 26, 201]
+        );
+        assert!(matches!(
+            array.store_array_subset_masked_elements::<u16>(&array_subset, &mask, vec![1]),
+            Err(ArrayError::InvalidBytesInputSize(2, 4))
+        ));
+
+        // A fully selected chunk is stored without being decoded
+        let chunk_key = array.chunk_key(&[1, 1]);
+        store.set(&chunk_key, &[0; 3]).unwrap();
+        let array_subset = ArraySubset::new_with_ranges(&[4..8, 4..8]);
+        array
+            .store_array_subset_masked_elements::<u16>(
+                &array_subset,
+                &[true; 16],
+                (100..116).collect(),
+            )
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&array_subset)
+                .unwrap(),
+            (100..116).collect::<Vec<u16>>()
+        );
+    }
+
+    #[test]
+    fn array_orthogonal() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![8, 8]),
+                (0..64).collect(),
+            )
+            .unwrap();
+
+        // Indices are unsorted and may repeat
+        let selection = [vec![6, 1, 1], vec![0, 5, 3]];
+        assert_eq!(
+            array
+                .retrieve_orthogonal_elements::<u16, _>(&selection)
+                .unwrap(),
+            vec![48, 53, 51, 8, 13, 11, 8, 13, 11]
+        );
+        #[cfg(feature = "ndarray")]
+        assert_eq!(
+            array
+                .retrieve_orthogonal_ndarray::<u16, _>(&selection)
+                .unwrap()
+                .shape(),
+            &[3, 3]
+        );
+        assert!(array
+            .retrieve_orthogonal_elements::<u16, _>(&[vec![], vec![1]])
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            array.retrieve_orthogonal_elements::<u16, _>(&[vec![0], vec![8]]),
+            Err(ArrayError::InvalidOrthogonalIndex(1, 8, _))
+        ));
+        assert!(array
+            .retrieve_orthogonal_elements::<u16, _>(&[vec![0]])
+            .is_err());
+
+        // Only selected elements are updated
+        array
+            .store_orthogonal_elements::<u16, _>(&[[0, 7], [1, 6]], vec![100, 101, 102, 103])
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_orthogonal_elements::<u16, _>(&[vec![0, 7], vec![0, 1, 6]])
+                .unwrap(),
+            vec![0, 100, 101, 56, 102, 103]
+        );
+        assert!(matches!(
+            array.store_orthogonal_elements::<u16, _>(&[[0, 7], [1, 6]], vec![1]),
+            Err(ArrayError::InvalidBytesInputSize(2, 8))
+        ));
+
+        // A fully selected chunk is stored without being decoded
+        store.set(&array.chunk_key(&[1, 0]), &[0; 3]).unwrap();
+        array
+            .store_orthogonal_elements::<u16, _>(
+                &[vec![7, 6, 5, 4], vec![0, 1, 2, 3]],
+                (0..16).collect(),
+            )
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[4..8, 0..4]))
+                .unwrap(),
+            vec![12, 13, 14, 15, 8, 9, 10, 11, 4, 5, 6, 7, 0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn array_cancellation() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+
+        let token = codec::CancellationToken::new();
+        let options = CodecOptions::builder()
+            .cancellation_token(token.clone())
+            .build();
+        let array_subset = ArraySubset::new_with_ranges(&[2..6, 2..6]);
+        array
+            .store_array_subset_opt(&array_subset, vec![1; 32], &options)
+            .unwrap();
+
+        token.cancel();
+        assert!(options.is_cancelled());
+        assert!(matches!(
+            array.store_array_subset_opt(&array_subset, vec![2; 32], &options),
+            Err(ArrayError::Cancelled)
+        ));
+        assert!(matches!(
+            array.retrieve_array_subset_opt(&array_subset, &options),
+            Err(ArrayError::Cancelled)
+        ));
+        assert!(matches!(
+            array.retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..2, 0..2]), &options),
+            Err(ArrayError::Cancelled)
+        ));
+
+        // The cancelled store did not modify the array
+        assert_eq!(
+            array.retrieve_array_subset(&array_subset).unwrap(),
+            vec![1; 32]
+        );
+    }
+
+    #[test]
+    fn array_typed_attributes() {
+        let store = Arc::new(MemoryStore::default());
+        let mut array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+
+        assert_eq!(array.set_attribute("units", "m").unwrap(), None);
+        array
+            .set_attribute("scale", &serde_json::json!({"x": 1.0, "y": 2.0}))
+            .unwrap();
+        assert_eq!(
+            array.attribute::<String>("units").unwrap().as_deref(),
+            Some("m")
+        );
+        assert_eq!(array.attribute::<u32>("missing").unwrap(), None);
+        assert!(array.attribute::<u32>("units").is_err());
+
+        array.update_attributes(
+            serde_json::json!({"units": null, "scale": {"y": 3.0, "z": 4.0}, "version": 2})
+                .as_object()
+                .unwrap(),
+        );
+        assert_eq!(array.attribute::<String>("units").unwrap(), None);
+        assert_eq!(
+            array.attribute::<serde_json::Value>("scale").unwrap(),
+            Some(serde_json::json!({"x": 1.0, "y": 3.0, "z": 4.0}))
+        );
+        assert_eq!(
+            array.remove_attribute("version"),
+            Some(serde_json::json!(2))
+        );
+
+        // Only the attributes are replaced in the stored metadata
+        let mut array_resized = Array::new(store.clone(), "/array").unwrap();
+        array_resized.set_shape(vec![16, 16]);
+        array_resized.store_metadata().unwrap();
+        array.store_attributes().unwrap();
+        let array = Array::new(store, "/array").unwrap();
+        assert_eq!(array.shape(), &[16, 16]);
+        assert_eq!(
+            array.attribute::<serde_json::Value>("scale").unwrap(),
+            Some(serde_json::json!({"x": 1.0, "y": 3.0, "z": 4.0}))
+        );
+    }
+
+    #[test]
+    fn array_byte_range_plan() {
+        use crate::byte_range::ByteRange;
+
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let plan = array
+            .byte_range_plan(&ArraySubset::new_with_ranges(&[1..3, 3..5]))
+            .unwrap();
+        assert_eq!(
+            plan,
+            vec![
+                (
+                    vec![0, 0],
+                    array.chunk_key(&[0, 0]),
+                    PartialDecodePlan::ByteRanges(vec![
+                        ByteRange::FromStart(7, Some(1)),
+                        ByteRange::FromStart(11, Some(1))
+                    ])
+                ),
+                (
+                    vec![0, 1],
+                    array.chunk_key(&[0, 1]),
+                    PartialDecodePlan::ByteRanges(vec![
+                        ByteRange::FromStart(4, Some(1)),
+                        ByteRange::FromStart(8, Some(1))
+                    ])
+                ),
+            ]
+        );
+        assert!(array
+            .byte_range_plan(&ArraySubset::new_with_shape(vec![2]))
+            .is_err());
+
+        #[cfg(feature = "gzip")]
+        {
+            let array = ArrayBuilder::new(
+                vec![8, 8],
+                DataType::UInt8,
+                vec![4, 4].try_into().unwrap(),
+                FillValue::from(0u8),
+            )
+            .bytes_to_bytes_codecs(vec![Box::new(codec::GzipCodec::new(5).unwrap())])
+            .build(store.clone(), "/gzip")
+            .unwrap();
+            let plan = array
+                .byte_range_plan(&ArraySubset::new_with_ranges(&[1..3, 1..3]))
+                .unwrap();
+            assert_eq!(plan[0].2, PartialDecodePlan::Whole);
+        }
+    }
+
+    #[cfg(feature = "sharding")]
+    #[test]
+    fn array_byte_range_plan_sharded() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .array_to_bytes_codec(Box::new(
+            codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+                vec![2, 2].try_into().unwrap(),
+            )
+            .build(),
+        ))
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(vec![4, 4]),
+                (1..=16).collect(),
+            )
+            .unwrap();
+
+        // The shard index is read to resolve the byte ranges of the inner chunks
+        let array_subset = ArraySubset::new_with_ranges(&[2..4, 0..2]);
+        let plan = array.byte_range_plan(&array_subset).unwrap();
+        assert_eq!(plan.len(), 1);
+        let (chunk_indices, key, PartialDecodePlan::Index(index_byte_range)) = &plan[0] else {
+            panic!("expected an index plan");
+        };
+        let index = store
+            .get_partial_values_key(key, &[*index_byte_range])
+            .unwrap()
+            .unwrap()
+            .remove(0);
+        let PartialDecodePlan::ByteRanges(byte_ranges) = array
+            .chunk_byte_range_plan(chunk_indices, &array_subset, Some(&index))
+            .unwrap()
+        else {
+            panic!("expected a byte ranges plan");
+        };
+        let bytes = store
+            .get_partial_values_key(key, &byte_ranges)
+            .unwrap()
+            .unwrap()
+            .concat();
+        assert_eq!(bytes, array.retrieve_array_subset(&array_subset).unwrap());
+    }
+
+    #[test]
+    fn array_encoded_chunk_copy() {
+        let builder = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        );
+        let array_src = builder
+            .build(Arc::new(MemoryStore::new()), "/array")
+            .unwrap();
+        let array_dst = builder
+            .build(Arc::new(MemoryStore::new()), "/array")
+            .unwrap();
+        array_src.store_chunk(&[0, 1], vec![1; 4 * 4]).unwrap();
+
+        assert!(array_src.retrieve_encoded_chunk(&[0, 0]).unwrap().is_none());
+        let chunk_encoded = array_src.retrieve_encoded_chunk(&[0, 1]).unwrap().unwrap();
+        array_dst
+            .store_encoded_chunk(&[0, 1], &chunk_encoded)
+            .unwrap();
+        assert_eq!(array_dst.retrieve_chunk(&[0, 1]).unwrap(), vec![1; 4 * 4]);
+        assert!(array_dst.store_encoded_chunk(&[0], &chunk_encoded).is_err());
+    }
+
+    #[test]
+    fn array_chunk_elements_aligned() {
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::Float32,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0.5f32),
+        )
+        .build(Arc::new(MemoryStore::new()), "/array")
+        .unwrap();
+        let elements: Vec<f32> = (0..16u8).map(f32::from).collect();
+        array
+            .store_chunk_elements(&[0, 1], elements.clone())
+            .unwrap();
+
+        let aligned = array
+            .retrieve_chunk_elements_aligned::<f32>(&[0, 1])
+            .unwrap();
+        assert_eq!(aligned, elements);
+        assert_eq!(
+            aligned,
+            array.retrieve_chunk_elements::<f32>(&[0, 1]).unwrap()
+        );
+        assert_eq!(
+            array
+                .retrieve_chunk_elements_aligned::<f32>(&[1, 1])
+                .unwrap(),
+            vec![0.5; 16]
+        );
+        assert!(array
+            .retrieve_chunk_elements_aligned::<u16>(&[0, 1])
+            .is_err());
+    }
+
+    #[test]
+    fn array_store_chunks_batched() {
+        use crate::storage::storage_transformer::{
+            StorageTransformerExtension, UsageLogOperation, UsageLogStorageTransformer,
+        };
+
+        let records = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let usage_log = UsageLogStorageTransformer::new_with_callback({
+            let records = records.clone();
+            move |record| records.lock().unwrap().push(record.operation)
+        });
+        let store = Arc::new(MemoryStore::new());
+        let storage =
+            Arc::new(usage_log).create_readable_writable_listable_transformer(store.clone());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(storage, "/array")
+        .unwrap();
+
+        // The chunk [1, 1] is all fill value and is erased rather than stored
+        let mut bytes = vec![1u8; 8 * 8];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            if i / 8 >= 4 && i % 8 >= 4 {
+                *byte = 0;
+            }
+        }
+        array
+            .store_chunks(&ArraySubset::new_with_shape(vec![2, 2]), bytes.clone())
+            .unwrap();
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![UsageLogOperation::SetValues, UsageLogOperation::EraseValues]
+        );
+        assert_eq!(store.list().unwrap().len(), 3);
+        assert_eq!(
+            array
+                .retrieve_chunks(&ArraySubset::new_with_shape(vec![2, 2]))
+                .unwrap(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn array_store_metadata_if_not_exists() {
+        let store = Arc::new(MemoryStore::default());
+        let mut builder = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        );
+        let array = builder.build(store.clone(), "/array").unwrap();
+        assert!(array.store_metadata_if_not_exists().unwrap());
+
+        let array_other = builder
+            .shape(vec![16, 16])
+            .build(store.clone(), "/array")
+            .unwrap();
+        assert!(!array_other.store_metadata_if_not_exists().unwrap());
+        assert_eq!(Array::new(store, "/array").unwrap().shape(), &[8, 8]);
+    }
+
+    #[test]
+    fn array_mutate_metadata() {
+        let store = Arc::new(MemoryStore::default());
+        let mut array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt8,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+
+        // Changes stored by another writer are preserved
+        let mut array_other = Array::new(store.clone(), "/array").unwrap();
+        array_other.set_shape(vec![16, 16]);
+        array_other.store_metadata().unwrap();
+        array
+            .mutate_metadata(|metadata| {
+                let ArrayMetadata::V3(metadata) = metadata;
+                metadata
+                    .attributes
+                    .insert("units".to_string(), serde_json::json!("m"));
+            })
+            .unwrap();
+        assert_eq!(array.shape(), &[16, 16]);
+        let array_stored = Array::new(store.clone(), "/array").unwrap();
+        assert_eq!(array_stored.shape(), &[16, 16]);
+        assert_eq!(
+            array_stored
+                .attribute::<String>("units")
+                .unwrap()
+                .as_deref(),
+            Some("m")
+        );
+
+        // Invalid metadata is not stored
+        assert!(array
+            .mutate_metadata(|metadata| {
+                let ArrayMetadata::V3(metadata) = metadata;
+                metadata.shape = vec![16];
+            })
+            .is_err());
+        assert_eq!(
+            Array::new(store.clone(), "/array").unwrap().shape(),
+            &[16, 16]
+        );
+
+        // Concurrent modification on every attempt
+        let mut options = MetadataWriteOptions::default();
+        options.set_max_retries(2);
+        options.set_retry_delay(std::time::Duration::ZERO);
+        let mut count = 0;
+        let result = array.mutate_metadata_opt(
+            |_| {
+                count += 1;
+                array_other.set_attribute("count", &count).unwrap();
+                array_other.store_metadata().unwrap();
+            },
+            &options,
+        );
+        assert!(matches!(
+            result,
+            Err(ArrayCreateError::MetadataWriteError(
+                MetadataWriteError::Conflict { attempts: 3, .. }
+            ))
+        ));
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn array_thread_pool() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name(|i| format!("zarrs-test-pool-{i}"))
+            .build()
+            .unwrap();
+        let thread_names = Arc::new(Mutex::new(Vec::new()));
+        let options = CodecOptions::builder()
+            .thread_pool(Arc::new(thread_pool))
+            .progress_callback(codec::ProgressCallback::new({
+                let thread_names = thread_names.clone();
+                move |_| {
+                    thread_names
+                        .lock()
+                        .unwrap()
+                        .push(std::thread::current().name().map(ToString::to_string));
+                }
+            }))
+            .build();
+
+        let array_subset = ArraySubset::new_with_ranges(&[1..7, 0..8]);
+        array
+            .store_array_subset_opt(&array_subset, vec![1; 96], &options)
+            .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_opt(&array_subset, &options)
+                .unwrap(),
+            vec![1; 96]
+        );
+        let thread_names = thread_names.lock().unwrap();
+        assert_eq!(thread_names.len(), 32);
+        assert!(thread_names.iter().all(|name| name
+            .as_deref()
+            .is_some_and(|name| name.starts_with("zarrs-test-pool-"))));
+    }
+
+    #[test]
+    fn array_progress() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let options = CodecOptions::builder()
+            .progress_callback(codec::ProgressCallback::new({
+                let reports = reports.clone();
+                move |progress| reports.lock().unwrap().push(progress)
+            }))
+            .build();
+        let array_subset = ArraySubset::new_with_ranges(&[2..6, 2..8]);
+        array
+            .store_array_subset_opt(&array_subset, vec![1; 48], &options)
+            .unwrap();
+        {
+            let mut reports = reports.lock().unwrap();
+            assert_eq!(reports.len(), 4);
+            assert!(reports
+                .iter()
+                .enumerate()
+                .all(|(i, p)| p.chunks_completed == i + 1 && p.chunks_total == 4));
+            assert_eq!(reports.last().unwrap().bytes_transferred, 48);
+            reports.clear();
+        }
+
+        for chunk_prefetch in [0, 2] {
+            let mut options = options.clone();
+            options.set_chunk_prefetch(chunk_prefetch);
+            array
+                .retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..8, 2..8]), &options)
+                .unwrap();
+            let mut reports = reports.lock().unwrap();
+            assert_eq!(
+                reports.last().unwrap(),
+                &codec::ChunkProgress {
+                    chunks_completed: 4,
+                    chunks_total: 4,
+                    bytes_transferred: 96,
+                }
+            );
+            reports.clear();
+        }
+
+        // Only multi-chunk requests report progress, even if they contain a single chunk
+        array.retrieve_chunk_opt(&[0, 0], &options).unwrap();
+        assert!(reports.lock().unwrap().is_empty());
+        array
+            .retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..1, 0..1]), &options)
+            .unwrap();
+        assert_eq!(reports.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(all(feature = "async", feature = "object_store"))]
+    #[tokio::test]
+    async fn async_array_store_variants() {
+        let builder = || {
+            ArrayBuilder::new(
+                vec![8, 8],
+                DataType::UInt16,
+                vec![4, 4].try_into().unwrap(),
+                FillValue::from(0u16),
+            )
+        };
+        let array = builder()
+            .build(Arc::new(MemoryStore::default()), "/array")
+            .unwrap();
+        let async_array = builder()
+            .build(
+                Arc::new(crate::storage::store::AsyncObjectStore::new(
+                    object_store::memory::InMemory::new(),
+                )),
+                "/array",
+            )
+            .unwrap();
+
+        let strided = ArraySubset::new_with_ranges(&[1..8, 0..8])
+            .with_step(&[
+                std::num::NonZeroU64::new(3).unwrap(),
+                std::num::NonZeroU64::new(2).unwrap(),
+            ])
+            .unwrap();
+        let strided_elements: Vec<u16> =
+            (1..=u16::try_from(strided.num_elements()).unwrap()).collect();
+        array
+            .store_array_subset_strided_elements(&strided, strided_elements.clone())
+            .unwrap();
+        async_array
+            .async_store_array_subset_strided_elements(&strided, strided_elements)
+            .await
+            .unwrap();
+
+        let points = [[0, 0], [5, 6], [7, 7]];
+        array
+            .store_elements_at::<u16, _>(&points, vec![100, 101, 102])
+            .unwrap();
+        async_array
+            .async_store_elements_at::<u16, _>(&points, vec![100, 101, 102])
+            .await
+            .unwrap();
+
+        let masked_subset = ArraySubset::new_with_ranges(&[2..6, 2..6]);
+        let mask: Vec<bool> = (0..16).map(|i| i % 3 == 0).collect();
+        array
+            .store_array_subset_masked_elements::<u16>(&masked_subset, &mask, vec![200; 6])
+            .unwrap();
+        async_array
+            .async_store_array_subset_masked_elements::<u16>(&masked_subset, &mask, vec![200; 6])
+            .await
+            .unwrap();
+
+        let selection = [vec![7, 6, 5, 4], vec![0, 1, 2, 3]];
+        array
+            .store_orthogonal_elements::<u16, _>(&selection, (300..316).collect())
+            .unwrap();
+        async_array
+            .async_store_orthogonal_elements::<u16, _>(&selection, (300..316).collect())
+            .await
+            .unwrap();
+
+        let subset = ArraySubset::new_with_ranges(&[6..7, 4..6]);
+        let big_endian = vec![1, 144, 1, 145];
+        array
+            .store_array_subset_endian(
+                &subset,
+                big_endian.clone(),
+                codec::array_to_bytes::bytes::Endianness::Big,
+            )
+            .unwrap();
+        async_array
+            .async_store_array_subset_endian(
+                &subset,
+                big_endian,
+                codec::array_to_bytes::bytes::Endianness::Big,
+            )
+            .await
+            .unwrap();
+
+        #[cfg(feature = "transpose")]
+        {
+            let subset = ArraySubset::new_with_ranges(&[0..2, 5..8]);
+            array
+                .store_array_subset_elements_fortran::<u16>(&subset, (500..506).collect())
+                .unwrap();
+            async_array
+                .async_store_array_subset_elements_fortran::<u16>(&subset, (500..506).collect())
+                .await
+                .unwrap();
+        }
+
+        let all = ArraySubset::new_with_shape(vec![8, 8]);
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u16>(&all).unwrap(),
+            async_array
+                .async_retrieve_array_subset_elements::<u16>(&all)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[cfg(all(feature = "async", feature = "object_store"))]
+    #[tokio::test]
+    async fn async_array_executor() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Debug, Default)]
+        struct CountingExecutor {
+            tasks: AtomicUsize,
+            inner: codec::RayonAsyncExecutor,
+        }
+
+        impl codec::AsyncExecutorTraits for CountingExecutor {
+            fn spawn_blocking(&self, task: codec::BlockingTask) {
+                self.tasks.fetch_add(1, Ordering::Relaxed);
+                self.inner.spawn_blocking(task);
+            }
+        }
+
+        #[derive(Debug)]
+        struct DroppingExecutor;
+
+        impl codec::AsyncExecutorTraits for DroppingExecutor {
+            fn spawn_blocking(&self, _task: codec::BlockingTask) {}
+        }
+
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(
+            Arc::new(crate::storage::store::AsyncObjectStore::new(
+                object_store::memory::InMemory::new(),
+            )),
+            "/array",
+        )
+        .unwrap();
+
+        let executor = Arc::new(CountingExecutor::default());
+        let options = CodecOptions::builder()
+            .async_executor(executor.clone())
+            .build();
+        let subset = ArraySubset::new_with_shape(vec![8, 8]);
+        let elements: Vec<u16> = (0..64).collect();
+        array
+            .async_store_array_subset_elements_opt(&subset, elements.clone(), &options)
+            .await
+            .unwrap();
+        assert_eq!(executor.tasks.load(Ordering::Relaxed), 4);
+        assert_eq!(
+            array
+                .async_retrieve_array_subset_elements_opt::<u16>(&subset, &options)
+                .await
+                .unwrap(),
+            elements
+        );
+        assert_eq!(executor.tasks.load(Ordering::Relaxed), 8);
+        assert_eq!(
+            array
+                .async_retrieve_chunk_opt(&[1, 1], &options)
+                .await
+                .unwrap(),
+            array.async_retrieve_chunk(&[1, 1]).await.unwrap()
+        );
+        assert_eq!(executor.tasks.load(Ordering::Relaxed), 9);
+
+        // A dropped task fails the operation rather than hanging
+        let options = CodecOptions::builder()
+            .async_executor(Arc::new(DroppingExecutor))
+            .build();
+        assert!(array
+            .async_retrieve_chunk_opt(&[0, 0], &options)
+            .await
+            .is_err());
+    }
+
+    #[cfg(all(feature = "async", feature = "object_store"))]
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
+    async fn async_array_prefetch_overlap() {
+        use std::{
+            sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+            time::{Duration, Instant},
+        };
+
+        use crate::{
+            array::MaybeBytes,
+            byte_range::ByteRange,
+            storage::{
+                storage_transformer::sleep::Sleep, AsyncReadableStorageTraits, StorageError,
+                StoreKey, StoreKeyRange, StorePrefix,
+            },
+        };
+
+        /// A store with slow gets that counts the completed gets.
+        struct SlowStore {
+            inner: Arc<crate::storage::store::AsyncObjectStore<object_store::memory::InMemory>>,
+            gets: Arc<AtomicUsize>,
+        }
+
+        #[async_trait::async_trait]
+        impl AsyncReadableStorageTraits for SlowStore {
+            async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+                Sleep::new(Duration::from_millis(10)).await;
+                let value = self.inner.get(key).await;
+                self.gets.fetch_add(1, Ordering::SeqCst);
+                value
+            }
+
+            async fn get_partial_values_key(
+                &self,
+                key: &StoreKey,
+                byte_ranges: &[ByteRange],
+            ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+                self.inner.get_partial_values_key(key, byte_ranges).await
+            }
+
+            async fn get_partial_values(
+                &self,
+                key_ranges: &[StoreKeyRange],
+            ) -> Result<Vec<MaybeBytes>, StorageError> {
+                self.inner.get_partial_values(key_ranges).await
+            }
+
+            async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+                self.inner.size_prefix(prefix).await
+            }
+
+            async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+                self.inner.size_key(key).await
+            }
+
+            async fn size(&self) -> Result<u64, StorageError> {
+                self.inner.size().await
+            }
+        }
+
+        /// An executor that holds back the first decode until another chunk has been fetched.
+        #[derive(Debug)]
+        struct OverlapExecutor {
+            gets: Arc<AtomicUsize>,
+            first: AtomicBool,
+            overlapped: Arc<AtomicBool>,
+        }
+
+        impl codec::AsyncExecutorTraits for OverlapExecutor {
+            fn spawn_blocking(&self, task: codec::BlockingTask) {
+                let first = self.first.swap(false, Ordering::SeqCst);
+                let gets = self.gets.clone();
+                let overlapped = self.overlapped.clone();
+                std::thread::spawn(move || {
+                    if first {
+                        let start = Instant::now();
+                        while gets.load(Ordering::SeqCst) < 2
+                            && start.elapsed() < Duration::from_secs(2)
+                        {
+                            std::thread::sleep(Duration::from_millis(1));
+                        }
+                        overlapped.store(gets.load(Ordering::SeqCst) >= 2, Ordering::SeqCst);
+                    }
+                    task();
+                });
+            }
+        }
+
+        let store = Arc::new(crate::storage::store::AsyncObjectStore::new(
+            object_store::memory::InMemory::new(),
+        ));
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let subset = ArraySubset::new_with_shape(vec![8, 8]);
+        let elements: Vec<u16> = (0..64).collect();
+        array
+            .async_store_array_subset_elements(&subset, elements.clone())
+            .await
+            .unwrap();
+
+        let gets = Arc::new(AtomicUsize::new(0));
+        let overlapped = Arc::new(AtomicBool::new(false));
+        let slow_store = SlowStore {
+            inner: store,
+            gets: gets.clone(),
+        };
+        let array =
+            Array::new_with_metadata(Arc::new(slow_store), "/array", array.metadata()).unwrap();
+        let options = CodecOptions::builder()
+            .chunk_prefetch(2)
+            .async_executor(Arc::new(OverlapExecutor {
+                gets: gets.clone(),
+                first: AtomicBool::new(true),
+                overlapped: overlapped.clone(),
+            }))
+            .build();
+        assert_eq!(
+            array
+                .async_retrieve_array_subset_elements_opt::<u16>(&subset, &options)
+                .await
+                .unwrap(),
+            elements
+        );
+        assert_eq!(gets.load(Ordering::SeqCst), 4);
+        // The next chunk was fetched while the first chunk was decoded
+        assert!(overlapped.load(Ordering::SeqCst));
+    }
+
+    fn array_subset_locking(locks: StoreLocks, expect_equal: bool) {
+        let store = Arc::new(MemoryStore::new());
+
+        let array_path = "/array";
+        let mut array = ArrayBuilder::new(
+            vec![100, 4],
+            DataType::UInt8,
+            vec![10, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, array_path)
+        .unwrap();
+        array.set_chunk_locks(Some(locks));
+
+        let mut any_not_equal = false;
+        for j in 1..10 {
+            (0..100).into_par_iter().for_each(|i| {
+                let subset = ArraySubset::new_with_ranges(&[i..i + 1, 0..4]);
+                array.store_array_subset(&subset, vec![j; 4]).unwrap();
+            });
+            let subset_all = ArraySubset::new_with_shape(array.shape().to_vec());
+            let data_all = array.retrieve_array_subset(&subset_all).unwrap();
+            let all_equal = data_all.iter().all_equal_value() == Ok(&j);
+            if expect_equal {
+                assert!(all_equal);
+            } else {
+                any_not_equal |= !all_equal;
+            }
+        }
+        if !expect_equal {
+            assert!(any_not_equal);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn array_subset_locking_default() {
+        array_subset_locking(
+            Arc::new(crate::storage::store_lock::DefaultStoreLocks::default()),
+            true,
+        );
+    }
+
+    #[cfg(all(feature = "sharding", feature = "gzip"))]
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn array_subset_locking_parallel_codec() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![64, 1024],
+            DataType::UInt8,
+            vec![64, 1024].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .array_to_bytes_codec(Box::new(
+            codec::array_to_bytes::sharding::ShardingCodecBuilder::new(
+                vec![8, 128].try_into().unwrap(),
+            )
+            .bytes_to_bytes_codecs(vec![Box::new(codec::GzipCodec::new(5).unwrap())])
+            .build(),
+        ))
+        .build(store, "/array")
+        .unwrap();
+        array.set_chunk_locks(Some(Arc::new(
+            crate::storage::store_lock::DefaultStoreLocks::default(),
+        )));
+        let array = Arc::new(array);
+
+        // Every row is in the same shard, which the sharding codec would otherwise decode and encode in parallel.
+        // A thread holding the chunk lock could then steal the update of another row while waiting on its inner chunks.
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap(),
+        );
+        let options = CodecOptions::builder()
+            .concurrent_target(4)
+            .thread_pool(thread_pool.clone())
+            .build();
+        let store_row = {
+            let array = array.clone();
+            move |i: u8| {
+                let subset =
+                    ArraySubset::new_with_ranges(&[u64::from(i)..u64::from(i) + 1, 0..1024]);
+                array
+                    .store_array_subset_elements_opt::<u8>(&subset, vec![i; 1024], &options)
+                    .unwrap();
+            }
+        };
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for _ in 0..5 {
+                thread_pool.install(|| {
+                    rayon::join(
+                        || store_row(0),
+                        || (1..64u8).into_par_iter().for_each(&store_row),
+                    )
+                });
+            }
+            sender.send(()).unwrap();
+        });
+        receiver
+            .recv_timeout(std::time::Duration::from_secs(60))
+            .expect("overlapping locked updates deadlocked");
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_shape(vec![64, 1024]))
+                .unwrap(),
+            (0..64u8).flat_map(|i| [i; 1024]).collect::<Vec<_>>()
+        );
+    }
 
     // // Due to the nature of this test, it can fail sometimes. It was used for development but is now disabled.
     // #[test]
@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    array_subset::ArraySubset,
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, StorageError, StorageHandle, StoreKey,
+        StorePrefix, WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncWritableStorageTraits,
+};
+
+use super::{ravel_indices, Array, ArrayError, ArrayIndices, ArrayShape};
+
+/// The name of the key below the array prefix holding a stored [`AllocationMap`].
+pub const ALLOCATION_MAP_KEY_NAME: &str = "allocation_map.json";
+
+/// A bitmap of the chunks of an array that are stored.
+///
+/// The allocation of a chunk can be checked with [`contains`](AllocationMap::contains) without a request to the store, which is useful for sparse arrays on stores where listing or requesting missing chunks is expensive.
+/// An allocation map is maintained by an array if set with [`Array::set_allocation_map`], built from a listing with [`Array::build_allocation_map`], and persisted with [`Array::store_allocation_map`].
+///
+/// Allocation maps are serialised as JSON with the bitmap encoded as base64, where bit `i % 8` of byte `i / 8` is set if the chunk with the raveled (C order) index `i` is stored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "AllocationMapMetadata", try_from = "AllocationMapMetadata")]
+pub struct AllocationMap {
+    chunk_grid_shape: ArrayShape,
+    bitmap: Vec<u8>,
+}
+
+impl AllocationMap {
+    /// Create an allocation map for the chunk grid with shape `chunk_grid_shape` with no chunks stored.
+    #[must_use]
+    pub fn new(chunk_grid_shape: ArrayShape) -> Self {
+        let num_chunks = chunk_grid_shape.iter().product::<u64>();
+        let bitmap = vec![0; usize::try_from(num_chunks.div_ceil(8)).unwrap_or_default()];
+        Self {
+            chunk_grid_shape,
+            bitmap,
+        }
+    }
+
+    /// Create an allocation map for the chunk grid with shape `chunk_grid_shape` with the chunks in `chunks` stored.
+    #[must_use]
+    pub fn from_chunks<'a>(
+        chunk_grid_shape: ArrayShape,
+        chunks: impl IntoIterator<Item = &'a ArrayIndices>,
+    ) -> Self {
+        let mut allocation_map = Self::new(chunk_grid_shape);
+        for chunk_indices in chunks {
+            allocation_map.set(chunk_indices, true);
+        }
+        allocation_map
+    }
+
+    /// Return the shape of the chunk grid.
+    #[must_use]
+    pub fn chunk_grid_shape(&self) -> &[u64] {
+        &self.chunk_grid_shape
+    }
+
+    /// Return the position of the bit of the chunk at `chunk_indices`, or [`None`] if it is outside the chunk grid.
+    fn bit(&self, chunk_indices: &[u64]) -> Option<(usize, u8)> {
+        if chunk_indices.len() != self.chunk_grid_shape.len()
+            || std::iter::zip(chunk_indices, &self.chunk_grid_shape)
+                .any(|(index, shape)| index >= shape)
+        {
+            return None;
+        }
+        let index = ravel_indices(chunk_indices, &self.chunk_grid_shape);
+        let byte = usize::try_from(index / 8).ok()?;
+        Some((byte, 1 << (index % 8)))
+    }
+
+    /// Return true if the chunk at `chunk_indices` is stored.
+    ///
+    /// Returns false if `chunk_indices` are outside the chunk grid.
+    #[must_use]
+    pub fn contains(&self, chunk_indices: &[u64]) -> bool {
+        self.bit(chunk_indices)
+            .is_some_and(|(byte, mask)| self.bitmap[byte] & mask != 0)
+    }
+
+    /// Set whether the chunk at `chunk_indices` is stored.
+    ///
+    /// Chunks outside the chunk grid are ignored.
+    pub fn set(&mut self, chunk_indices: &[u64], stored: bool) {
+        if let Some((byte, mask)) = self.bit(chunk_indices) {
+            if stored {
+                self.bitmap[byte] |= mask;
+            } else {
+                self.bitmap[byte] &= !mask;
+            }
+        }
+    }
+
+    /// Return the number of stored chunks.
+    #[must_use]
+    pub fn num_stored(&self) -> u64 {
+        self.bitmap
+            .iter()
+            .map(|byte| u64::from(byte.count_ones()))
+            .sum()
+    }
+
+    /// Return the indices of the stored chunks in C order.
+    #[must_use]
+    pub fn chunks_stored(&self) -> Vec<ArrayIndices> {
+        ArraySubset::new_with_shape(self.chunk_grid_shape.clone())
+            .indices()
+            .into_iter()
+            .filter(|chunk_indices| self.contains(chunk_indices))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllocationMapMetadata {
+    chunk_grid_shape: ArrayShape,
+    bitmap: String,
+}
+
+impl From<AllocationMap> for AllocationMapMetadata {
+    fn from(allocation_map: AllocationMap) -> Self {
+        Self {
+            chunk_grid_shape: allocation_map.chunk_grid_shape,
+            bitmap: STANDARD.encode(allocation_map.bitmap),
+        }
+    }
+}
+
+impl TryFrom<AllocationMapMetadata> for AllocationMap {
+    type Error = String;
+
+    fn try_from(metadata: AllocationMapMetadata) -> Result<Self, Self::Error> {
+        let bitmap = STANDARD
+            .decode(metadata.bitmap)
+            .map_err(|err| err.to_string())?;
+        let allocation_map = Self::new(metadata.chunk_grid_shape);
+        if bitmap.len() == allocation_map.bitmap.len() {
+            Ok(Self {
+                bitmap,
+                ..allocation_map
+            })
+        } else {
+            Err(format!(
+                "allocation map bitmap has {} bytes, expected {}",
+                bitmap.len(),
+                allocation_map.bitmap.len()
+            ))
+        }
+    }
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return a copy of the allocation map maintained by the array.
+    ///
+    /// Returns [`None`] if the array does not maintain an allocation map.
+    #[must_use]
+    pub fn allocation_map(&self) -> Option<AllocationMap> {
+        self.allocation_map
+            .as_ref()
+            .map(|allocation_map| allocation_map.lock().clone())
+    }
+
+    /// Set the allocation map maintained by the array. Disabled by default.
+    ///
+    /// If set, the allocation map is updated whenever the array stores or erases a chunk, and it can be persisted with [`store_allocation_map`](Array::store_allocation_map).
+    /// Use [`retrieve_allocation_map`](Array::retrieve_allocation_map) to continue from a stored allocation map, or [`build_allocation_map`](Array::build_allocation_map) to create one from the stored chunks.
+    ///
+    /// The allocation map is not updated by other writers of the array, and chunks outside of its chunk grid (e.g. after the array is resized) are not tracked.
+    pub fn set_allocation_map(&mut self, allocation_map: Option<AllocationMap>) {
+        self.allocation_map =
+            allocation_map.map(|allocation_map| Arc::new(parking_lot::Mutex::new(allocation_map)));
+    }
+
+    /// Return the key of the stored [`AllocationMap`].
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the array path is not a valid store prefix.
+    pub fn allocation_map_key(&self) -> Result<StoreKey, StorageError> {
+        let prefix: StorePrefix = self.path().try_into()?;
+        Ok(StoreKey::new(
+            prefix.as_str().to_string() + ALLOCATION_MAP_KEY_NAME,
+        )?)
+    }
+
+    /// Mark the chunk at `chunk_indices` as stored or erased in the allocation map, if the array maintains one.
+    pub(crate) fn update_allocation_map(&self, chunk_indices: &[u64], stored: bool) {
+        if let Some(allocation_map) = &self.allocation_map {
+            allocation_map.lock().set(chunk_indices, stored);
+        }
+    }
+
+    /// Parse the [`AllocationMap`] stored at `key`.
+    fn allocation_map_from_bytes(
+        key: &StoreKey,
+        bytes: Option<&[u8]>,
+    ) -> Result<Option<AllocationMap>, ArrayError> {
+        bytes
+            .map(|bytes| {
+                serde_json::from_slice(bytes).map_err(|err| {
+                    StorageError::InvalidMetadata(key.clone(), err.to_string()).into()
+                })
+            })
+            .transpose()
+    }
+
+    /// Serialise the allocation map maintained by the array.
+    fn allocation_map_to_bytes(&self) -> Result<Vec<u8>, StorageError> {
+        let allocation_map = self.allocation_map().ok_or_else(|| {
+            StorageError::Unsupported("the array does not maintain an allocation map".to_string())
+        })?;
+        Ok(serde_json::to_vec(&allocation_map).expect("allocation maps are serialisable"))
+    }
+
+    /// Return the shape of the chunk grid.
+    fn allocation_map_chunk_grid_shape(&self) -> Result<ArrayShape, StorageError> {
+        self.chunk_grid_shape().ok_or_else(|| {
+            StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+        })
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Retrieve the stored [`AllocationMap`].
+    ///
+    /// Returns [`None`] if no allocation map is stored.
+    /// The stored allocation map is only accurate if every writer of the array maintained it.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying store error or the allocation map is invalid.
+    pub fn retrieve_allocation_map(&self) -> Result<Option<AllocationMap>, ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let key = self.allocation_map_key()?;
+        Self::allocation_map_from_bytes(&key, storage_transformer.get(&key)?.as_deref())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + 'static> Array<TStorage> {
+    /// Build an [`AllocationMap`] from the [stored chunks](Array::chunks_stored).
+    ///
+    /// This lists the array prefix once, and can be used to rebuild an allocation map that is missing or stale.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk grid shape is unknown or there is an underlying store error.
+    pub fn build_allocation_map(&self) -> Result<AllocationMap, ArrayError> {
+        Ok(AllocationMap::from_chunks(
+            self.allocation_map_chunk_grid_shape()?,
+            &self.chunks_stored()?,
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
+    /// Store the allocation map maintained by the array at [`allocation_map_key`](Array::allocation_map_key).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the array does not maintain an allocation map or there is an underlying store error.
+    pub fn store_allocation_map(&self) -> Result<(), StorageError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_writable_transformer(storage_handle);
+        storage_transformer.set(
+            &self.allocation_map_key()?,
+            &self.allocation_map_to_bytes()?,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`retrieve_allocation_map`](Array::retrieve_allocation_map).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_allocation_map(&self) -> Result<Option<AllocationMap>, ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        let key = self.allocation_map_key()?;
+        Self::allocation_map_from_bytes(&key, storage_transformer.get(&key).await?.as_deref())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits + 'static>
+    Array<TStorage>
+{
+    /// Async variant of [`build_allocation_map`](Array::build_allocation_map).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_build_allocation_map(&self) -> Result<AllocationMap, ArrayError> {
+        Ok(AllocationMap::from_chunks(
+            self.allocation_map_chunk_grid_shape()?,
+            &self.async_chunks_stored().await?,
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`store_allocation_map`](Array::store_allocation_map).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_allocation_map(&self) -> Result<(), StorageError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_writable_transformer(storage_handle);
+        storage_transformer
+            .set(
+                &self.allocation_map_key()?,
+                self.allocation_map_to_bytes()?.into(),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn allocation_map() {
+        let mut allocation_map = AllocationMap::from_chunks(vec![3, 4], &[vec![0, 1], vec![2, 3]]);
+        assert!(allocation_map.contains(&[0, 1]));
+        assert!(!allocation_map.contains(&[1, 1]));
+        assert!(!allocation_map.contains(&[3, 0]));
+        allocation_map.set(&[3, 0], true);
+        allocation_map.set(&[0, 1], false);
+        assert_eq!(allocation_map.num_stored(), 1);
+        assert_eq!(allocation_map.chunks_stored(), vec![vec![2, 3]]);
+
+        let json = serde_json::to_string(&allocation_map).unwrap();
+        assert_eq!(json, r#"{"chunk_grid_shape":[3,4],"bitmap":"AAg="}"#);
+        assert_eq!(
+            serde_json::from_str::<AllocationMap>(&json).unwrap(),
+            allocation_map
+        );
+        assert!(serde_json::from_str::<AllocationMap>(
+            r#"{"chunk_grid_shape":[3,4],"bitmap":"AA=="}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn array_allocation_map() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        assert!(array.allocation_map().is_none());
+        assert!(array.store_allocation_map().is_err());
+        assert!(array.retrieve_allocation_map().unwrap().is_none());
+
+        array.store_chunk_elements(&[0, 0], vec![1u8; 4]).unwrap();
+        array.set_allocation_map(Some(array.build_allocation_map().unwrap()));
+        array
+            .store_array_subset_elements(
+                &ArraySubset::new_with_ranges(&[2..4, 0..4]),
+                vec![0u8, 0, 1, 1, 0, 0, 1, 1],
+            )
+            .unwrap();
+        array.store_chunk_elements(&[1, 1], vec![0u8; 4]).unwrap();
+        assert_eq!(
+            array.allocation_map().unwrap().chunks_stored(),
+            vec![vec![0, 0]]
+        );
+        array.store_chunk_elements(&[1, 0], vec![2u8; 4]).unwrap();
+        array.erase_chunk(&[0, 0]).unwrap();
+        let allocation_map = array.allocation_map().unwrap();
+        assert_eq!(allocation_map.chunks_stored(), vec![vec![1, 0]]);
+        assert_eq!(array.build_allocation_map().unwrap(), allocation_map);
+
+        array.store_allocation_map().unwrap();
+        assert_eq!(
+            array.allocation_map_key().unwrap().as_str(),
+            "array/allocation_map.json"
+        );
+        assert_eq!(
+            array.retrieve_allocation_map().unwrap(),
+            Some(allocation_map)
+        );
+    }
+}
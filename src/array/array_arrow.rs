@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use arrow_array::{
+    types::{
+        DurationMicrosecondType, DurationMillisecondType, DurationNanosecondType,
+        DurationSecondType, Float16Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+        Int8Type, TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+        TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    },
+    Array, ArrayRef, ArrowPrimitiveType, BooleanArray, FixedSizeBinaryArray, PrimitiveArray,
+};
+use arrow_buffer::{BooleanBuffer, Buffer, NullBuffer, ScalarBuffer};
+use arrow_schema::TimeUnit;
+
+use super::{
+    data_type::{DateTimeUnit, NAT},
+    ArrayError, DataType, FillValue,
+};
+
+/// The Arrow time unit of a `numpy.datetime64` or `numpy.timedelta64` unit, if it has one.
+const fn arrow_time_unit(unit: DateTimeUnit) -> Option<TimeUnit> {
+    match unit {
+        DateTimeUnit::Second => Some(TimeUnit::Second),
+        DateTimeUnit::Millisecond => Some(TimeUnit::Millisecond),
+        DateTimeUnit::Microsecond => Some(TimeUnit::Microsecond),
+        DateTimeUnit::Nanosecond => Some(TimeUnit::Nanosecond),
+        DateTimeUnit::Week | DateTimeUnit::Day | DateTimeUnit::Hour | DateTimeUnit::Minute => None,
+    }
+}
+
+/// Return the [Arrow data type](arrow_schema::DataType) of the elements of `data_type`.
+///
+/// Data types map to Arrow data types as follows:
+///  - `bool`: `Boolean`,
+///  - integer and floating point data types: the Arrow data type of the same name,
+///  - `bfloat16`, complex, and `r*` raw bits data types: `FixedSizeBinary` with the element size,
+///  - `numpy.datetime64` and `numpy.timedelta64` with `s`, `ms`, `us`, or `ns` units: `Timestamp` (without a time zone) and `Duration` with the same unit. "Not a time" elements are null.
+///
+/// Returns [`None`] if the data type has no Arrow representation.
+#[must_use]
+pub fn arrow_data_type(data_type: &DataType) -> Option<arrow_schema::DataType> {
+    use arrow_schema::DataType as A;
+    match data_type {
+        DataType::Bool => Some(A::Boolean),
+        DataType::Int8 => Some(A::Int8),
+        DataType::Int16 => Some(A::Int16),
+        DataType::Int32 => Some(A::Int32),
+        DataType::Int64 => Some(A::Int64),
+        DataType::UInt8 => Some(A::UInt8),
+        DataType::UInt16 => Some(A::UInt16),
+        DataType::UInt32 => Some(A::UInt32),
+        DataType::UInt64 => Some(A::UInt64),
+        DataType::Float16 => Some(A::Float16),
+        DataType::Float32 => Some(A::Float32),
+        DataType::Float64 => Some(A::Float64),
+        DataType::BFloat16 | DataType::Complex64 | DataType::Complex128 | DataType::RawBits(_) => {
+            i32::try_from(data_type.size()).ok().map(A::FixedSizeBinary)
+        }
+        DataType::DateTime64(unit) => arrow_time_unit(*unit).map(|unit| A::Timestamp(unit, None)),
+        DataType::TimeDelta64(unit) => arrow_time_unit(*unit).map(A::Duration),
+        DataType::Extension(_) => None,
+    }
+}
+
+/// Create a primitive Arrow array from native endian `bytes`, reusing the allocation of `bytes` if it is suitably aligned.
+fn primitive_array<T: ArrowPrimitiveType>(bytes: Vec<u8>, nulls: Option<NullBuffer>) -> ArrayRef {
+    let len = bytes.len() / std::mem::size_of::<T::Native>();
+    let mut buffer = Buffer::from_vec(bytes);
+    if buffer
+        .as_ptr()
+        .align_offset(std::mem::align_of::<T::Native>())
+        != 0
+    {
+        buffer = Buffer::from_slice_ref(buffer.as_slice());
+    }
+    Arc::new(PrimitiveArray::<T>::new(
+        ScalarBuffer::new(buffer, 0, len),
+        nulls,
+    ))
+}
+
+/// Return a null buffer for the "not a time" elements of `numpy.datetime64` or `numpy.timedelta64` `bytes`, if there are any.
+fn nat_nulls(bytes: &[u8]) -> Option<NullBuffer> {
+    let nat = NAT.to_ne_bytes();
+    let nulls: NullBuffer = bytes
+        .chunks_exact(nat.len())
+        .map(|value| value != nat)
+        .collect();
+    (nulls.null_count() > 0).then_some(nulls)
+}
+
+/// Convert the native endian `bytes` of elements with `data_type` (e.g. a retrieved chunk or array subset) to an Arrow array.
+///
+/// See [`arrow_data_type`] for the Arrow data type of each data type.
+/// The allocation of `bytes` is reused for primitive Arrow arrays if it is suitably aligned.
+///
+/// # Errors
+/// Returns [`ArrayError::IncompatibleElementType`] if `data_type` has no Arrow representation or [`ArrayError::InvalidBytesInputSize`] if `bytes` is not a multiple of the data type size.
+pub fn bytes_to_arrow_array(bytes: Vec<u8>, data_type: &DataType) -> Result<ArrayRef, ArrayError> {
+    if bytes.len() % data_type.size() != 0 {
+        return Err(ArrayError::InvalidBytesInputSize(
+            bytes.len(),
+            (bytes.len() / data_type.size() * data_type.size()) as u64,
+        ));
+    }
+    let array: ArrayRef = match arrow_data_type(data_type) {
+        Some(arrow_schema::DataType::Boolean) => Arc::new(BooleanArray::new(
+            bytes
+                .iter()
+                .map(|&byte| byte != 0)
+                .collect::<BooleanBuffer>(),
+            None,
+        )),
+        Some(arrow_schema::DataType::Int8) => primitive_array::<Int8Type>(bytes, None),
+        Some(arrow_schema::DataType::Int16) => primitive_array::<Int16Type>(bytes, None),
+        Some(arrow_schema::DataType::Int32) => primitive_array::<Int32Type>(bytes, None),
+        Some(arrow_schema::DataType::Int64) => primitive_array::<Int64Type>(bytes, None),
+        Some(arrow_schema::DataType::UInt8) => primitive_array::<UInt8Type>(bytes, None),
+        Some(arrow_schema::DataType::UInt16) => primitive_array::<UInt16Type>(bytes, None),
+        Some(arrow_schema::DataType::UInt32) => primitive_array::<UInt32Type>(bytes, None),
+        Some(arrow_schema::DataType::UInt64) => primitive_array::<UInt64Type>(bytes, None),
+        Some(arrow_schema::DataType::Float16) => primitive_array::<Float16Type>(bytes, None),
+        Some(arrow_schema::DataType::Float32) => primitive_array::<Float32Type>(bytes, None),
+        Some(arrow_schema::DataType::Float64) => primitive_array::<Float64Type>(bytes, None),
+        Some(arrow_schema::DataType::FixedSizeBinary(size)) => Arc::new(
+            FixedSizeBinaryArray::try_new(size, Buffer::from_vec(bytes), None)
+                .map_err(|_| ArrayError::IncompatibleElementType(data_type.clone(), "arrow"))?,
+        ),
+        Some(arrow_schema::DataType::Timestamp(unit, None)) => {
+            let nulls = nat_nulls(&bytes);
+            match unit {
+                TimeUnit::Second => primitive_array::<TimestampSecondType>(bytes, nulls),
+                TimeUnit::Millisecond => primitive_array::<TimestampMillisecondType>(bytes, nulls),
+                TimeUnit::Microsecond => primitive_array::<TimestampMicrosecondType>(bytes, nulls),
+                TimeUnit::Nanosecond => primitive_array::<TimestampNanosecondType>(bytes, nulls),
+            }
+        }
+        Some(arrow_schema::DataType::Duration(unit)) => {
+            let nulls = nat_nulls(&bytes);
+            match unit {
+                TimeUnit::Second => primitive_array::<DurationSecondType>(bytes, nulls),
+                TimeUnit::Millisecond => primitive_array::<DurationMillisecondType>(bytes, nulls),
+                TimeUnit::Microsecond => primitive_array::<DurationMicrosecondType>(bytes, nulls),
+                TimeUnit::Nanosecond => primitive_array::<DurationNanosecondType>(bytes, nulls),
+            }
+        }
+        _ => {
+            return Err(ArrayError::IncompatibleElementType(
+                data_type.clone(),
+                "arrow",
+            ))
+        }
+    };
+    Ok(array)
+}
+
+/// Convert an Arrow `array` to the native endian bytes of elements with `data_type`.
+///
+/// The Arrow data type of `array` must match [`arrow_data_type`] of `data_type`.
+/// Null elements are replaced with `fill_value`.
+///
+/// # Errors
+/// Returns [`ArrayError::IncompatibleElementType`] if the Arrow data type of `array` does not match `data_type`, or [`ArrayError::IncompatibleElementSize`] if the size of `fill_value` does not match `data_type`.
+pub fn arrow_array_to_bytes(
+    array: &dyn Array,
+    data_type: &DataType,
+    fill_value: &FillValue,
+) -> Result<Vec<u8>, ArrayError> {
+    if arrow_data_type(data_type).as_ref() != Some(array.data_type()) {
+        return Err(ArrayError::IncompatibleElementType(
+            data_type.clone(),
+            "arrow",
+        ));
+    }
+    let element_size = data_type.size();
+    if fill_value.size() != element_size {
+        return Err(ArrayError::IncompatibleElementSize(
+            fill_value.size(),
+            element_size,
+        ));
+    }
+
+    let mut bytes = if let Some(array) = array.as_any().downcast_ref::<BooleanArray>() {
+        array.values().iter().map(u8::from).collect()
+    } else {
+        let data = array.to_data();
+        let start = data.offset() * element_size;
+        data.buffers()[0].as_slice()[start..start + data.len() * element_size].to_vec()
+    };
+
+    if let Some(nulls) = array.nulls() {
+        for index in nulls
+            .iter()
+            .enumerate()
+            .filter_map(|(i, valid)| (!valid).then_some(i))
+        {
+            bytes[index * element_size..(index + 1) * element_size]
+                .copy_from_slice(fill_value.as_ne_bytes());
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{
+        cast::AsArray, types::TimestampMillisecondType, Float32Array, TimestampMillisecondArray,
+    };
+
+    use super::*;
+
+    #[test]
+    fn arrow_bytes_round_trip() {
+        let elements = [1.0f32, 2.0, 3.5, -4.0];
+        let bytes: Vec<u8> = elements.iter().flat_map(|e| e.to_ne_bytes()).collect();
+        let array = bytes_to_arrow_array(bytes.clone(), &DataType::Float32).unwrap();
+        assert_eq!(array.as_primitive::<Float32Type>().values(), &elements);
+        assert_eq!(
+            arrow_array_to_bytes(&array, &DataType::Float32, &FillValue::from(0.0f32)).unwrap(),
+            bytes
+        );
+
+        let array = bytes_to_arrow_array(vec![0, 1, 1, 0], &DataType::Bool).unwrap();
+        assert_eq!(
+            array.as_boolean().iter().collect::<Vec<_>>(),
+            [Some(false), Some(true), Some(true), Some(false)]
+        );
+        assert_eq!(
+            arrow_array_to_bytes(&array, &DataType::Bool, &FillValue::from(false)).unwrap(),
+            [0, 1, 1, 0]
+        );
+
+        let array = bytes_to_arrow_array(vec![0, 1, 2, 3], &DataType::RawBits(2)).unwrap();
+        assert_eq!(array.as_fixed_size_binary().value(1), [2, 3]);
+        assert_eq!(
+            arrow_array_to_bytes(&array, &DataType::RawBits(2), &FillValue::new(vec![0, 0]))
+                .unwrap(),
+            [0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn arrow_datetime_nulls() {
+        let data_type = DataType::DateTime64(DateTimeUnit::Millisecond);
+        let bytes: Vec<u8> = [1i64, NAT, 3]
+            .iter()
+            .flat_map(|e| e.to_ne_bytes())
+            .collect();
+        let array = bytes_to_arrow_array(bytes.clone(), &data_type).unwrap();
+        assert_eq!(
+            array
+                .as_primitive::<TimestampMillisecondType>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [Some(1), None, Some(3)]
+        );
+        assert_eq!(
+            arrow_array_to_bytes(&array, &data_type, &FillValue::from(NAT)).unwrap(),
+            bytes
+        );
+
+        let array = TimestampMillisecondArray::from(vec![Some(1), None]);
+        assert_eq!(
+            arrow_array_to_bytes(&array, &data_type, &FillValue::from(0i64)).unwrap(),
+            [1i64.to_ne_bytes(), 0i64.to_ne_bytes()].concat()
+        );
+    }
+
+    #[test]
+    fn arrow_incompatible() {
+        assert!(arrow_data_type(&DataType::DateTime64(DateTimeUnit::Day)).is_none());
+        assert!(bytes_to_arrow_array(vec![0; 3], &DataType::Float32).is_err());
+        assert!(arrow_array_to_bytes(
+            &Float32Array::from(vec![1.0]),
+            &DataType::Float64,
+            &FillValue::from(0.0f64)
+        )
+        .is_err());
+        let array = Float32Array::from(vec![1.0, 2.0, 3.0]).slice(1, 2);
+        assert_eq!(
+            arrow_array_to_bytes(&array, &DataType::Float32, &FillValue::from(0.0f32)).unwrap(),
+            [2.0f32.to_ne_bytes(), 3.0f32.to_ne_bytes()].concat()
+        );
+    }
+}
@@ -1,27 +1,35 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use futures::StreamExt;
 
 use crate::{
     array_subset::ArraySubset,
     node::NodePath,
-    storage::{data_key, meta_key, AsyncReadableStorageTraits, StorageHandle},
+    storage::{
+        data_key, meta_key, AsyncListableStorageTraits, AsyncReadableStorageTraits, StorageError,
+        StorageHandle, StoreKey,
+    },
 };
 
 use super::{
+    aligned_elements_vec, check_cancelled,
     codec::{
         options::CodecOptions, ArrayCodecTraits, ArrayToBytesCodecTraits,
         AsyncArrayPartialDecoderTraits, AsyncStoragePartialDecoder, CodecError,
     },
     concurrency::concurrency_chunks_and_codec,
-    transmute_from_bytes_vec,
+    fill_value_on_decode_error, transmute_from_bytes_vec,
     unsafe_cell_slice::UnsafeCellSlice,
-    validate_element_size, Array, ArrayCreateError, ArrayError, ArrayMetadata, ArrayView,
+    validate_element_size, Array, ArrayCreateError, ArrayError, ArrayIndices, ArrayMetadata,
+    ArrayView, ChunkProgressTracker, ChunkRepresentation, MaybeBytes, RawBytes,
 };
 
 #[cfg(feature = "ndarray")]
 use super::elements_to_ndarray;
 
+#[cfg(feature = "arrow")]
+use super::bytes_to_arrow_array;
+
 impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
     /// Async variant of [`new`](Array::new).
     #[allow(clippy::missing_errors_doc)]
@@ -86,6 +94,16 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    /// Async variant of [`retrieve_chunk_elements_aligned`](Array::retrieve_chunk_elements_aligned).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_retrieve_chunk_elements_aligned<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.async_retrieve_chunk_elements_aligned_opt(chunk_indices, &CodecOptions::default())
+            .await
+    }
+
     #[cfg(feature = "ndarray")]
     /// Async variant of [`retrieve_chunk_ndarray`](Array::retrieve_chunk_ndarray).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
@@ -97,6 +115,19 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    #[cfg(feature = "arrow")]
+    /// Async variant of [`retrieve_chunk_arrow`](Array::retrieve_chunk_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_chunk_arrow(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<arrow_array::ArrayRef, ArrayError> {
+        bytes_to_arrow_array(
+            self.async_retrieve_chunk(chunk_indices).await?,
+            self.data_type(),
+        )
+    }
+
     /// Async variant of [`retrieve_chunk_into_array_view`](Array::retrieve_chunk_into_array_view).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_retrieve_chunk_into_array_view(
@@ -241,6 +272,19 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    #[cfg(feature = "arrow")]
+    /// Async variant of [`retrieve_array_subset_arrow`](Array::retrieve_array_subset_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_array_subset_arrow(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<arrow_array::ArrayRef, ArrayError> {
+        bytes_to_arrow_array(
+            self.async_retrieve_array_subset(array_subset).await?,
+            self.data_type(),
+        )
+    }
+
     /// Async variant of [`retrieve_array_subset_into_array_view`](Array::retrieve_array_subset_into_array_view).
     #[allow(clippy::missing_errors_doc)]
     pub async fn async_retrieve_array_subset_into_array_view(
@@ -256,6 +300,93 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         .await
     }
 
+    /// Async variant of [`retrieve_array_subset_into`](Array::retrieve_array_subset_into).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_array_subset_into(
+        &self,
+        array_subset: &ArraySubset,
+        bytes: &mut [u8],
+    ) -> Result<(), ArrayError> {
+        self.async_retrieve_array_subset_into_opt(array_subset, bytes, &CodecOptions::default())
+            .await
+    }
+
+    /// Async variant of [`retrieve_array_subset_elements_into`](Array::retrieve_array_subset_elements_into).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_array_subset_elements_into<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        elements: &mut [T],
+    ) -> Result<(), ArrayError> {
+        self.async_retrieve_array_subset_elements_into_opt(
+            array_subset,
+            elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`chunk_exists`](Array::chunk_exists).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_chunk_exists(&self, chunk_indices: &[u64]) -> Result<bool, ArrayError> {
+        Ok(self.async_chunk_stored_size(chunk_indices).await?.is_some())
+    }
+
+    /// Async variant of [`chunk_stored_size`](Array::chunk_stored_size).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_chunk_stored_size(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Option<u64>, ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        Ok(storage_transformer
+            .size_key(&self.chunk_key(chunk_indices))
+            .await?)
+    }
+
+    /// Async variant of [`retrieve_encoded_chunk`](Array::retrieve_encoded_chunk).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_encoded_chunk(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<MaybeBytes, ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        crate::storage::async_retrieve_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+        )
+        .await
+        .map_err(|err| self.chunk_error(chunk_indices, err))
+    }
+
+    /// Async variant of [`prefetch_array_subset`](Array::prefetch_array_subset).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_prefetch_array_subset(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<(), ArrayError> {
+        self.async_prefetch_array_subset_opt(array_subset, &CodecOptions::default())
+            .await
+    }
+
     /// Async variant of [`partial_decoder`](Array::partial_decoder).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_partial_decoder<'a>(
@@ -282,6 +413,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                 chunk_indices.to_vec(),
             ));
         }
+        check_cancelled(options)?;
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
             .storage_transformers()
@@ -293,13 +425,23 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             self.chunk_key_encoding(),
         )
         .await
-        .map_err(ArrayError::StorageError)?;
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
         if let Some(chunk_encoded) = chunk_encoded {
             let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-            let chunk_decoded = self
-                .codecs()
-                .decode(chunk_encoded, &chunk_representation, options)
-                .map_err(ArrayError::CodecError)?;
+            let chunk_decoded = match self
+                .async_decode_chunk(chunk_encoded, &chunk_representation, options)
+                .await
+            {
+                Ok(chunk_decoded) => chunk_decoded,
+                Err(err) if fill_value_on_decode_error(&err, options) => {
+                    return Ok(Some(
+                        self.fill_value()
+                            .as_ne_bytes()
+                            .repeat(chunk_representation.num_elements_usize()),
+                    ));
+                }
+                Err(err) => return Err(self.chunk_error(chunk_indices, err)),
+            };
             let chunk_decoded_size =
                 chunk_representation.num_elements_usize() * chunk_representation.data_type().size();
             if chunk_decoded.len() == chunk_decoded_size {
@@ -317,6 +459,17 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
 
     /// Async variant of [`retrieve_chunk_opt`](Array::retrieve_chunk_opt).
     #[allow(clippy::missing_errors_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+            )
+        )
+    )]
     pub async fn async_retrieve_chunk_opt(
         &self,
         chunk_indices: &[u64],
@@ -362,6 +515,28 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         Ok(transmute_from_bytes_vec::<T>(bytes))
     }
 
+    /// Async variant of [`retrieve_chunk_elements_aligned_opt`](Array::retrieve_chunk_elements_aligned_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_chunk_elements_aligned_opt<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        let mut elements = aligned_elements_vec::<T>(&chunk_representation);
+        let shape = chunk_representation.shape_u64();
+        let array_view = ArrayView::new(
+            bytemuck::cast_slice_mut(&mut elements),
+            &shape,
+            ArraySubset::new_with_shape(shape.clone()),
+        )
+        .map_err(|err| CodecError::from(err.to_string()))?;
+        self.async_retrieve_chunk_into_array_view_opt(chunk_indices, &array_view, options)
+            .await?;
+        Ok(elements)
+    }
+
     #[cfg(feature = "ndarray")]
     /// Async variant of [`retrieve_chunk_ndarray_if_exists_opt`](Array::retrieve_chunk_ndarray_if_exists_opt).
     #[allow(clippy::missing_errors_doc)]
@@ -420,6 +595,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                 chunk_shape_u64,
             ));
         }
+        check_cancelled(options)?;
 
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
@@ -432,34 +608,15 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             self.chunk_key_encoding(),
         )
         .await
-        .map_err(ArrayError::StorageError)?;
-        if let Some(chunk_encoded) = chunk_encoded {
-            self.codecs()
-                .decode_into_array_view(&chunk_encoded, &chunk_representation, array_view, options)
-                .map_err(ArrayError::CodecError)
-        } else {
-            // fill array_view with fill value
-            let contiguous_indices = unsafe {
-                array_view
-                    .subset()
-                    .contiguous_linearised_indices_unchecked(array_view.array_shape())
-            };
-            let element_size = chunk_representation.element_size();
-            let length = contiguous_indices.contiguous_elements_usize() * element_size;
-            let fill = self
-                .fill_value()
-                .as_ne_bytes()
-                .repeat(contiguous_indices.contiguous_elements_usize());
-            // FIXME: Par iteration?
-            let output = unsafe { array_view.bytes_mut() };
-            for (array_subset_element_index, _num_elements) in &contiguous_indices {
-                let output_offset =
-                    usize::try_from(array_subset_element_index).unwrap() * element_size;
-                debug_assert!((output_offset + length) <= output.len());
-                output[output_offset..output_offset + length].copy_from_slice(&fill);
-            }
-            Ok(())
-        }
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        self.async_decode_chunk_into_array_view(
+            chunk_indices,
+            chunk_encoded,
+            &chunk_representation,
+            array_view,
+            options,
+        )
+        .await
     }
 
     /// Async variant of [`retrieve_chunks_opt`](Array::retrieve_chunks_opt).
@@ -480,11 +637,16 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
 
         // Retrieve chunk bytes
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         match num_chunks {
             0 => Ok(vec![]),
             1 => {
                 let chunk_indices = chunks.start();
-                self.async_retrieve_chunk_opt(chunk_indices, options).await
+                let chunk_bytes = self
+                    .async_retrieve_chunk_opt(chunk_indices, options)
+                    .await?;
+                progress.chunk_completed(chunk_bytes.len() as u64);
+                Ok(chunk_bytes)
             }
             _ => {
                 // Decode chunks and copy to output
@@ -509,31 +671,48 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                     let output_slice =
                         UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
                     let indices = chunks.indices();
-                    let chunk0_subset = self.chunk_subset(chunks.start())?;
-                    let futures = indices.into_iter().map(|chunk_indices| {
-                        let options = options.clone();
-                        let array_subset = array_subset.clone();
-                        let chunk_subset = self.chunk_subset(&chunk_indices).unwrap(); // FIXME: unwrap
-                        let array_view_subset =
-                            unsafe { chunk_subset.relative_to_unchecked(chunk0_subset.start()) };
-                        async move {
-                            self.async_retrieve_chunk_into_array_view_opt(
-                                &chunk_indices,
-                                &ArrayView::new(
-                                    unsafe { output_slice.get() },
-                                    array_subset.shape(),
-                                    array_view_subset,
+                    if options.chunk_prefetch() == 0 {
+                        let chunk0_subset = self.chunk_subset(chunks.start())?;
+                        let futures = indices.into_iter().map(|chunk_indices| {
+                            let options = options.clone();
+                            let array_subset = array_subset.clone();
+                            let progress = &progress;
+                            let chunk_subset = self.chunk_subset(&chunk_indices).unwrap(); // FIXME: unwrap
+                            let array_view_subset = unsafe {
+                                chunk_subset.relative_to_unchecked(chunk0_subset.start())
+                            };
+                            async move {
+                                self.async_retrieve_chunk_into_array_view_opt(
+                                    &chunk_indices,
+                                    &ArrayView::new(
+                                        unsafe { output_slice.get() },
+                                        array_subset.shape(),
+                                        array_view_subset,
+                                    )
+                                    .unwrap(), // FIXME: unwrap
+                                    &options,
                                 )
-                                .unwrap(), // FIXME: unwrap
-                                &options,
-                            )
-                            .await
+                                .await?;
+                                progress.chunk_completed(
+                                    chunk_subset.num_elements() * self.data_type().size() as u64,
+                                );
+                                Ok::<_, ArrayError>(())
+                            }
+                        });
+                        let mut stream =
+                            futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
+                        while let Some(item) = stream.next().await {
+                            item?;
                         }
-                    });
-                    let mut stream =
-                        futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
-                    while let Some(item) = stream.next().await {
-                        item?;
+                    } else {
+                        self.async_retrieve_chunks_prefetch(
+                            indices.into_iter().collect(),
+                            &array_subset,
+                            &output_slice,
+                            &progress,
+                            &options,
+                        )
+                        .await?;
                     }
                 }
                 unsafe { output.set_len(size_output) };
@@ -573,6 +752,17 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
     /// Async variant of [`retrieve_array_subset_opt`](Array::retrieve_array_subset_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     #[allow(clippy::too_many_lines)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                array_subset = %array_subset,
+            )
+        )
+    )]
     pub async fn async_retrieve_array_subset_opt(
         &self,
         array_subset: &ArraySubset,
@@ -596,6 +786,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
 
         // Retrieve chunk bytes
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         match num_chunks {
             0 => Ok(self
                 .fill_value()
@@ -604,9 +795,10 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             1 => {
                 let chunk_indices = chunks.start();
                 let chunk_subset = self.chunk_subset(chunk_indices)?;
-                if &chunk_subset == array_subset {
+                let subset_bytes = if &chunk_subset == array_subset {
                     // Single chunk fast path if the array subset domain matches the chunk domain
-                    self.async_retrieve_chunk_opt(chunk_indices, options).await
+                    self.async_retrieve_chunk_opt(chunk_indices, options)
+                        .await?
                 } else {
                     let array_subset_in_chunk_subset =
                         unsafe { array_subset.relative_to_unchecked(chunk_subset.start()) };
@@ -615,8 +807,10 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                         &array_subset_in_chunk_subset,
                         options,
                     )
-                    .await
-                }
+                    .await?
+                };
+                progress.chunk_completed(subset_bytes.len() as u64);
+                Ok(subset_bytes)
             }
             _ => {
                 // Decode chunks and copy to output
@@ -643,6 +837,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                     let output = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
                     let retrieve_chunk = |chunk_indices: Vec<u64>| {
                         let options = options.clone();
+                        let progress = &progress;
                         let chunk_subset = self.chunk_subset(&chunk_indices).unwrap(); // FIXME: unwrap
                         let chunk_subset_in_array_subset =
                             unsafe { chunk_subset.overlap_unchecked(array_subset) };
@@ -665,15 +860,39 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                                 &array_view,
                                 &options,
                             )
-                            .await
+                            .await?;
+                            progress.chunk_completed(
+                                chunk_subset.num_elements() * self.data_type().size() as u64,
+                            );
+                            Ok::<_, ArrayError>(())
                         }
                     };
                     let indices = chunks.indices();
-                    let futures = indices.into_iter().map(retrieve_chunk);
-                    let mut stream =
-                        futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
-                    while let Some(item) = stream.next().await {
-                        item?;
+                    if options.chunk_prefetch() == 0 {
+                        let futures = indices.into_iter().map(retrieve_chunk);
+                        let mut stream =
+                            futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
+                        while let Some(item) = stream.next().await {
+                            item?;
+                        }
+                    } else {
+                        // Prefetch chunks entirely within the array subset, partially decode the rest
+                        let (chunks_whole, chunks_partial) =
+                            self.partition_chunks_in_array_subset(&indices, array_subset)?;
+                        self.async_retrieve_chunks_prefetch(
+                            chunks_whole,
+                            array_subset,
+                            &output,
+                            &progress,
+                            &options,
+                        )
+                        .await?;
+                        let futures = chunks_partial.into_iter().map(retrieve_chunk);
+                        let mut stream =
+                            futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
+                        while let Some(item) = stream.next().await {
+                            item?;
+                        }
                     }
                 }
                 unsafe { output.set_len(size_output) };
@@ -682,6 +901,45 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         }
     }
 
+    /// Async variant of [`retrieve_array_subset_into_opt`](Array::retrieve_array_subset_into_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_array_subset_into_opt(
+        &self,
+        array_subset: &ArraySubset,
+        bytes: &mut [u8],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let expected_size = array_subset.num_elements() * self.data_type().size() as u64;
+        if bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                bytes.len(),
+                expected_size,
+            ));
+        }
+        let shape = array_subset.shape().to_vec();
+        let array_view = ArrayView::new(bytes, &shape, ArraySubset::new_with_shape(shape.clone()))
+            .map_err(|err| CodecError::from(err.to_string()))?;
+        self.async_retrieve_array_subset_into_array_view_opt(array_subset, &array_view, options)
+            .await
+    }
+
+    /// Async variant of [`retrieve_array_subset_elements_into_opt`](Array::retrieve_array_subset_elements_into_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_array_subset_elements_into_opt<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        elements: &mut [T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        self.async_retrieve_array_subset_into_opt(
+            array_subset,
+            bytemuck::cast_slice_mut(elements),
+            options,
+        )
+        .await
+    }
+
     /// Async variant of [`retrieve_array_subset_elements_opt`](Array::retrieve_array_subset_elements_opt).
     #[allow(clippy::missing_errors_doc)]
     pub async fn async_retrieve_array_subset_elements_opt<T: bytemuck::Pod + Send + Sync>(
@@ -711,6 +969,142 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         elements_to_ndarray(array_subset.shape(), elements)
     }
 
+    /// Retrieve whole chunks within `array_subset` into `output` with a bounded fetch-ahead pipeline.
+    ///
+    /// Up to [`CodecOptions::chunk_prefetch`] encoded chunks are fetched concurrently, and up to as many fetched chunks are decoded concurrently while the next chunks are fetched.
+    /// Decoding only overlaps with fetching if chunks are decoded with an [async executor](CodecOptions::async_executor).
+    async fn async_retrieve_chunks_prefetch(
+        &self,
+        chunks: Vec<ArrayIndices>,
+        array_subset: &ArraySubset,
+        output: &UnsafeCellSlice<'_, u8>,
+        progress: &ChunkProgressTracker<'_>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        let fetch_chunk = |chunk_indices: ArrayIndices| {
+            let storage_transformer = &storage_transformer;
+            async move {
+                let chunk_encoded = crate::storage::async_retrieve_chunk(
+                    &**storage_transformer,
+                    self.path(),
+                    &chunk_indices,
+                    self.chunk_key_encoding(),
+                )
+                .await;
+                (chunk_indices, chunk_encoded)
+            }
+        };
+        let decode_chunk = |(chunk_indices, chunk_encoded): (ArrayIndices, Result<_, _>)| async move {
+            let chunk_encoded =
+                chunk_encoded.map_err(|err| self.chunk_error(&chunk_indices, err))?;
+            let chunk_representation = self.chunk_array_representation(&chunk_indices)?;
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let array_view =
+                ArrayView::new(unsafe { output.get() }, array_subset.shape(), unsafe {
+                    chunk_subset.relative_to_unchecked(array_subset.start())
+                })
+                .map_err(|err| CodecError::from(err.to_string()))?;
+            self.async_decode_chunk_into_array_view(
+                &chunk_indices,
+                chunk_encoded,
+                &chunk_representation,
+                &array_view,
+                options,
+            )
+            .await?;
+            progress.chunk_completed(chunk_representation.size());
+            Ok::<_, ArrayError>(())
+        };
+        // Decoding polls the fetch stream, so fetches progress while chunks are decoded
+        let mut stream = futures::stream::iter(chunks.into_iter().map(fetch_chunk))
+            .buffer_unordered(options.chunk_prefetch())
+            .map(decode_chunk)
+            .buffer_unordered(options.chunk_prefetch());
+        while let Some(result) = stream.next().await {
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Decode an encoded chunk with the [async executor](CodecOptions::async_executor) of `options`.
+    async fn async_decode_chunk(
+        &self,
+        chunk_encoded: bytes::Bytes,
+        chunk_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, CodecError> {
+        let codecs = self.codecs.clone();
+        let chunk_representation = chunk_representation.clone();
+        let decode_options = options.clone();
+        options
+            .spawn_blocking(move || {
+                codecs
+                    .decode(
+                        RawBytes::Borrowed(&chunk_encoded),
+                        &chunk_representation,
+                        &decode_options,
+                    )
+                    .map(RawBytes::into_owned)
+            })
+            .await?
+    }
+
+    /// Decode an encoded chunk into `array_view` with the [async executor](CodecOptions::async_executor) of `options`, or fill `array_view` with the fill value if the chunk does not exist.
+    ///
+    /// A chunk decoded by an async executor is decoded into an owned buffer and then copied into `array_view`.
+    async fn async_decode_chunk_into_array_view(
+        &self,
+        chunk_indices: &[u64],
+        chunk_encoded: MaybeBytes,
+        chunk_representation: &ChunkRepresentation,
+        array_view: &ArrayView<'_>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        match chunk_encoded {
+            Some(chunk_encoded) if options.async_executor().is_some() => {
+                check_cancelled(options)?;
+                match self
+                    .async_decode_chunk(chunk_encoded, chunk_representation, options)
+                    .await
+                {
+                    Ok(chunk_decoded) => {
+                        let chunk_decoded_size = chunk_representation.num_elements_usize()
+                            * chunk_representation.element_size();
+                        if chunk_decoded.len() == chunk_decoded_size {
+                            array_view.copy_from_bytes(
+                                &chunk_decoded,
+                                chunk_representation.element_size(),
+                                options,
+                            );
+                            Ok(())
+                        } else {
+                            Err(ArrayError::UnexpectedChunkDecodedSize(
+                                chunk_decoded.len(),
+                                chunk_decoded_size,
+                            ))
+                        }
+                    }
+                    Err(err) if fill_value_on_decode_error(&err, options) => {
+                        self.fill_array_view(array_view);
+                        Ok(())
+                    }
+                    Err(err) => Err(self.chunk_error(chunk_indices, err)),
+                }
+            }
+            chunk_encoded => self.decode_chunk_into_array_view(
+                chunk_indices,
+                chunk_encoded.as_deref(),
+                chunk_representation,
+                array_view,
+                options,
+            ),
+        }
+    }
+
     /// Async variant of [`retrieve_chunks_into_array_view_opt`](Array::retrieve_chunks_into_array_view_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_retrieve_chunks_into_array_view_opt(
@@ -892,6 +1286,18 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
 
     /// Async variant of [`retrieve_chunk_subset_opt`](Array::retrieve_chunk_subset_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                chunk_subset = %chunk_subset,
+            )
+        )
+    )]
     pub async fn async_retrieve_chunk_subset_opt(
         &self,
         chunk_indices: &[u64],
@@ -906,6 +1312,12 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             ));
         }
 
+        if chunk_subset.is_empty() {
+            // Skip decoding if `chunk_subset` has no elements
+            return Ok(vec![]);
+        }
+        check_cancelled(options)?;
+
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
             .storage_transformers()
@@ -915,14 +1327,26 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             data_key(self.path(), chunk_indices, self.chunk_key_encoding()),
         ));
 
-        let decoded_bytes = self
+        let decoded_bytes = match self
             .codecs()
             .async_partial_decoder(input_handle, &chunk_representation, options)
-            .await?
-            .partial_decode_opt(&[chunk_subset.clone()], options)
-            .await?
-            .pop()
-            .unwrap();
+            .await
+        {
+            Ok(partial_decoder) => {
+                partial_decoder
+                    .partial_decode_opt(std::slice::from_ref(chunk_subset), options)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        let decoded_bytes = match decoded_bytes {
+            Ok(mut decoded_bytes) => decoded_bytes.pop().unwrap(),
+            Err(err) if fill_value_on_decode_error(&err, options) => self
+                .fill_value()
+                .as_ne_bytes()
+                .repeat(chunk_subset.num_elements_usize()),
+            Err(err) => return Err(self.chunk_error(chunk_indices, err)),
+        };
 
         let expected_size = chunk_subset.num_elements_usize() * self.data_type().size();
         if decoded_bytes.len() == chunk_subset.num_elements_usize() * self.data_type().size() {
@@ -987,6 +1411,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             self.async_retrieve_chunk_into_array_view_opt(chunk_indices, array_view, options)
                 .await
         } else {
+            check_cancelled(options)?;
             let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
             let storage_transformer = self
                 .storage_transformers()
@@ -996,13 +1421,75 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
                 data_key(self.path(), chunk_indices, self.chunk_key_encoding()),
             ));
 
-            self.codecs()
+            let result = match self
+                .codecs()
                 .async_partial_decoder(input_handle, &chunk_representation, options)
-                .await?
-                .partial_decode_into_array_view_opt(chunk_subset, array_view, options)
                 .await
-                .map_err(ArrayError::CodecError)
+            {
+                Ok(partial_decoder) => {
+                    partial_decoder
+                        .partial_decode_into_array_view_opt(chunk_subset, array_view, options)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+            match result {
+                Err(err) if fill_value_on_decode_error(&err, options) => {
+                    self.fill_array_view(array_view);
+                    Ok(())
+                }
+                result => result.map_err(|err| self.chunk_error(chunk_indices, err)),
+            }
+        }
+    }
+
+    /// Async variant of [`prefetch_array_subset_opt`](Array::prefetch_array_subset_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_prefetch_array_subset_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        let concurrent_limit = match (options.chunk_prefetch(), options.concurrent_target()) {
+            (0, 0) => chunks.num_elements_usize().max(1),
+            (0, concurrent_target) => concurrent_target,
+            (chunk_prefetch, _) => chunk_prefetch,
+        };
+        let indices = chunks.indices();
+        let futures = indices.into_iter().map(|chunk_indices| {
+            let storage_transformer = &storage_transformer;
+            async move {
+                crate::storage::async_retrieve_chunk(
+                    &**storage_transformer,
+                    self.path(),
+                    &chunk_indices,
+                    self.chunk_key_encoding(),
+                )
+                .await
+            }
+        });
+        let mut stream = futures::stream::iter(futures).buffer_unordered(concurrent_limit);
+        while let Some(item) = stream.next().await {
+            item?;
         }
+        Ok(())
     }
 
     /// Async variant of [`partial_decoder_opt`](Array::partial_decoder_opt).
@@ -1012,6 +1499,7 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
         chunk_indices: &[u64],
         options: &CodecOptions,
     ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, ArrayError> {
+        check_cancelled(options)?;
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
             .storage_transformers()
@@ -1027,3 +1515,32 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
             .await?)
     }
 }
+
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits + 'static>
+    Array<TStorage>
+{
+    /// Async variant of [`chunks_stored`](Array::chunks_stored).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_chunks_stored(&self) -> Result<Vec<ArrayIndices>, ArrayError> {
+        let chunk_grid_shape = self.chunk_grid_shape().ok_or_else(|| {
+            StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+        })?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_listable_transformer(storage_handle);
+        let keys: HashSet<StoreKey> = storage_transformer
+            .list_prefix(&self.path().try_into().map_err(StorageError::from)?)
+            .await?
+            .into_iter()
+            .collect();
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(ArraySubset::new_with_shape(chunk_grid_shape)
+            .indices()
+            .into_iter()
+            .filter(|chunk_indices| keys.contains(&self.chunk_key(chunk_indices)))
+            .collect())
+    }
+}
@@ -1,12 +1,53 @@
+use std::{collections::BTreeSet, sync::Arc};
+
 use futures::StreamExt;
 
-use crate::{array_subset::ArraySubset, storage::AsyncReadableWritableStorageTraits};
+use crate::{
+    array_subset::{ArraySubset, StridedArraySubset},
+    storage::{meta_key, AsyncReadableWritableStorageTraits, StorageError, StorageHandle},
+};
 
 use super::{
-    codec::options::CodecOptions, concurrency::concurrency_chunks_and_codec, Array, ArrayError,
+    chunk_update_options,
+    codec::{
+        array_to_bytes::bytes::{supports_endianness_conversion, Endianness},
+        options::CodecOptions,
+        CodecError,
+    },
+    concurrency::concurrency_chunks_and_codec,
+    orthogonal_selection_offsets, ravel_indices, transmute_to_bytes_vec, validate_element_size,
+    Array, ArrayError, ArrayIndices, ArrayMetadata, ArrayView, ChunkProgressTracker,
 };
 
+#[cfg(feature = "arrow")]
+use super::arrow_array_to_bytes;
+
 impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`store_attributes`](Array::store_attributes).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_attributes(&self) -> Result<(), StorageError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer_read = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle.clone());
+        let storage_transformer_write = self
+            .storage_transformers()
+            .create_async_writable_transformer(storage_handle);
+        let key = meta_key(self.path());
+        let metadata = if let Some(metadata) = storage_transformer_read.get(&key).await? {
+            let mut metadata: ArrayMetadata = serde_json::from_slice(&metadata)
+                .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))?;
+            let (ArrayMetadata::V3(metadata_stored), ArrayMetadata::V3(metadata_array)) =
+                (&mut metadata, self.metadata());
+            metadata_stored.attributes = metadata_array.attributes;
+            metadata
+        } else {
+            self.metadata()
+        };
+        crate::storage::async_create_array(&*storage_transformer_write, self.path(), &metadata)
+            .await
+    }
+
     /// Async variant of [`store_chunk_subset`](Array::store_chunk_subset).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_store_chunk_subset(
@@ -109,12 +150,224 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
         .await
     }
 
+    /// Async variant of [`store_array_subset_strided`](Array::store_array_subset_strided).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_strided(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_strided_opt(
+            strided_array_subset,
+            subset_bytes,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_array_subset_strided_elements`](Array::store_array_subset_strided_elements).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_strided_elements<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_strided_elements_opt(
+            strided_array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_elements_at`](Array::store_elements_at).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_elements_at<
+        T: bytemuck::Pod + Send + Sync,
+        TIndices: AsRef<[u64]> + Sync,
+    >(
+        &self,
+        array_indices: &[TIndices],
+        elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_elements_at_opt(array_indices, elements, &CodecOptions::default())
+            .await
+    }
+
+    /// Async variant of [`store_array_subset_masked`](Array::store_array_subset_masked).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_masked(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_masked_opt(
+            array_subset,
+            mask,
+            masked_bytes,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_array_subset_masked_elements`](Array::store_array_subset_masked_elements).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_masked_elements<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_masked_elements_opt(
+            array_subset,
+            mask,
+            masked_elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_orthogonal`](Array::store_orthogonal).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_orthogonal<TIndices: AsRef<[u64]> + Sync>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_orthogonal_opt(
+            dimension_indices,
+            selection_bytes,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_orthogonal_elements`](Array::store_orthogonal_elements).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_orthogonal_elements<
+        T: bytemuck::Pod + Send + Sync,
+        TIndices: AsRef<[u64]> + Sync,
+    >(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_orthogonal_elements_opt(
+            dimension_indices,
+            selection_elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    /// Async variant of [`store_array_subset_endian`](Array::store_array_subset_endian).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_endian(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+        endianness: Endianness,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_endian_opt(
+            array_subset,
+            subset_bytes,
+            endianness,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Async variant of [`store_array_subset_fortran`](Array::store_array_subset_fortran).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_fortran(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_fortran_opt(
+            array_subset,
+            subset_bytes,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Async variant of [`store_array_subset_elements_fortran`](Array::store_array_subset_elements_fortran).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_elements_fortran<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.async_store_array_subset_elements_fortran_opt(
+            array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+        .await
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Async variant of [`store_array_subset_datetimes`](Array::store_array_subset_datetimes).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_datetimes(
+        &self,
+        array_subset: &ArraySubset,
+        subset_datetimes: &[Option<chrono::DateTime<chrono::Utc>>],
+    ) -> Result<(), ArrayError> {
+        let elements = self.datetimes_to_elements(subset_datetimes)?;
+        self.async_store_array_subset_elements(array_subset, elements)
+            .await
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Async variant of [`store_array_subset_timedeltas`](Array::store_array_subset_timedeltas).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_timedeltas(
+        &self,
+        array_subset: &ArraySubset,
+        subset_timedeltas: &[Option<chrono::TimeDelta>],
+    ) -> Result<(), ArrayError> {
+        let elements = self.timedeltas_to_elements(subset_timedeltas)?;
+        self.async_store_array_subset_elements(array_subset, elements)
+            .await
+    }
+
+    #[cfg(feature = "arrow")]
+    /// Async variant of [`store_array_subset_arrow`](Array::store_array_subset_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_arrow(
+        &self,
+        array_subset: &ArraySubset,
+        subset_array: &dyn arrow_array::Array,
+    ) -> Result<(), ArrayError> {
+        let subset_bytes = arrow_array_to_bytes(subset_array, self.data_type(), self.fill_value())?;
+        self.async_store_array_subset(array_subset, subset_bytes)
+            .await
+    }
+
     /////////////////////////////////////////////////////////////////////////////
     // Advanced methods
     /////////////////////////////////////////////////////////////////////////////
 
     /// Async variant of [`store_chunk_subset_opt`](Array::store_chunk_subset_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                chunk_subset = %chunk_subset,
+                size = chunk_subset_bytes.len(),
+            )
+        )
+    )]
     pub async fn async_store_chunk_subset_opt(
         &self,
         chunk_indices: &[u64],
@@ -144,38 +397,41 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
             ));
         }
 
+        if chunk_subset.is_empty() {
+            // Nothing to store, so skip decoding and encoding the chunk
+            return Ok(());
+        }
+
         if chunk_subset.shape() == chunk_shape && chunk_subset.start().iter().all(|&x| x == 0) {
             // The subset spans the whole chunk, so store the bytes directly and skip decoding
             self.async_store_chunk_opt(chunk_indices, chunk_subset_bytes, options)
                 .await
         } else {
             // Lock the chunk
-            // let key = data_key(self.path(), chunk_indices, self.chunk_key_encoding());
-            // let mutex = self.storage.mutex(&key).await?;
-            // let _lock = mutex.lock();
+            let mutex = if let Some(chunk_locks) = &self.async_chunk_locks {
+                Some(chunk_locks.mutex(&self.chunk_key(chunk_indices)).await)
+            } else {
+                None
+            };
+            let _lock = if let Some(mutex) = &mutex {
+                Some(mutex.lock().await)
+            } else {
+                None
+            };
+            let options = chunk_update_options(mutex.is_some(), options);
 
             // Decode the entire chunk
             let mut chunk_bytes = self
-                .async_retrieve_chunk_opt(chunk_indices, options)
+                .async_retrieve_chunk_opt(chunk_indices, &options)
                 .await?;
 
             // Update the intersecting subset of the chunk
-            let element_size = self.data_type().size();
-            let mut offset = 0;
-            let contiguous_indices =
-                unsafe { chunk_subset.contiguous_linearised_indices_unchecked(&chunk_shape) };
-            let length = contiguous_indices.contiguous_elements_usize() * element_size;
-            for (chunk_element_index, _num_elements) in &contiguous_indices {
-                let chunk_offset = usize::try_from(chunk_element_index).unwrap() * element_size;
-                debug_assert!(chunk_offset + length <= chunk_bytes.len());
-                debug_assert!(offset + length <= chunk_subset_bytes.len());
-                chunk_bytes[chunk_offset..chunk_offset + length]
-                    .copy_from_slice(&chunk_subset_bytes[offset..offset + length]);
-                offset += length;
-            }
+            let chunk_view = ArrayView::new(&mut chunk_bytes, &chunk_shape, chunk_subset.clone())
+                .map_err(|err| CodecError::from(err.to_string()))?;
+            chunk_view.copy_from_bytes(&chunk_subset_bytes, self.data_type().size(), &options);
 
             // Store the updated chunk
-            self.async_store_chunk_opt(chunk_indices, chunk_bytes, options)
+            self.async_store_chunk_opt(chunk_indices, chunk_bytes, &options)
                 .await
         }
     }
@@ -201,6 +457,7 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
         )
     }
 
+    #[cfg(feature = "ndarray")]
     /// Async variant of [`store_chunk_subset_ndarray_opt`](Array::store_chunk_subset_ndarray_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub async fn async_store_chunk_subset_ndarray_opt<
@@ -238,6 +495,18 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
     /// Async variant of [`store_array_subset_opt`](Array::store_array_subset_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     #[allow(clippy::too_many_lines)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                array_subset = %array_subset,
+                size = subset_bytes.len(),
+            )
+        )
+    )]
     pub async fn async_store_array_subset_opt(
         &self,
         array_subset: &ArraySubset,
@@ -268,6 +537,7 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
             ));
         };
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         if num_chunks == 1 {
             let chunk_indices = chunks.start();
             let chunk_subset_in_array = unsafe {
@@ -280,6 +550,7 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
                 // This skips the internal decoding occurring in store_chunk_subset
                 self.async_store_chunk_opt(chunk_indices, subset_bytes, options)
                     .await?;
+                progress.chunk_completed(expected_size);
             } else {
                 let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset_in_array) };
                 let chunk_subset_in_array_subset =
@@ -295,6 +566,7 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
                 // Store the chunk subset
                 let array_subset_in_chunk_subset =
                     unsafe { overlap.relative_to_unchecked(chunk_subset_in_array.start()) };
+                let chunk_subset_size = chunk_subset_bytes.len() as u64;
                 self.async_store_chunk_subset_opt(
                     chunk_indices,
                     &array_subset_in_chunk_subset,
@@ -302,6 +574,7 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
                     options,
                 )
                 .await?;
+                progress.chunk_completed(chunk_subset_size);
             }
         } else {
             // Calculate chunk/codec concurrency
@@ -334,14 +607,18 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
                     )
                 };
                 let options = options.clone();
+                let progress = &progress;
                 async move {
+                    let chunk_subset_size = chunk_subset_bytes.len() as u64;
                     self.async_store_chunk_subset_opt(
                         &chunk_indices,
                         &array_subset_in_chunk_subset,
                         chunk_subset_bytes,
                         &options,
                     )
-                    .await
+                    .await?;
+                    progress.chunk_completed(chunk_subset_size);
+                    Ok::<_, ArrayError>(())
                 }
             };
 
@@ -395,4 +672,492 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits + 'static> Array<TSto
             async_store_array_subset_elements_opt(&subset, subset_array, options)
         )
     }
+
+    /// Async variant of [`store_array_subset_strided_opt`](Array::store_array_subset_strided_opt).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_array_subset_strided_opt(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if strided_array_subset.is_contiguous() {
+            return self
+                .async_store_array_subset_opt(
+                    &strided_array_subset.bounding_subset(),
+                    subset_bytes,
+                    options,
+                )
+                .await;
+        }
+
+        // Find the chunks containing an element of the strided array subset
+        let chunks = self.strided_array_subset_chunks(strided_array_subset)?;
+
+        let element_size = self.data_type().size();
+        let expected_size = strided_array_subset.num_elements() * element_size as u64;
+        if subset_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                subset_bytes.len(),
+                expected_size,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk = |(chunk_indices, overlap): (ArrayIndices, StridedArraySubset)| {
+            let subset_bytes = &subset_bytes;
+            let options = options.clone();
+            async move {
+                let overlap_bytes = unsafe {
+                    strided_array_subset
+                        .relative_to_strided(&overlap)?
+                        .extract_bytes_unchecked(
+                            subset_bytes,
+                            strided_array_subset.shape(),
+                            element_size,
+                        )
+                };
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                if overlap.num_elements() == chunk_subset.num_elements() {
+                    // The strided array subset selects every element of the chunk
+                    return self
+                        .async_store_chunk_opt(&chunk_indices, overlap_bytes, &options)
+                        .await;
+                }
+                let overlap_in_chunk = overlap.relative_to(chunk_subset.start())?;
+                self.async_update_chunk_opt(
+                    &chunk_indices,
+                    false,
+                    |chunk_bytes| {
+                        overlap_in_chunk
+                            .store_bytes(
+                                &overlap_bytes,
+                                chunk_bytes,
+                                chunk_subset.shape(),
+                                element_size,
+                            )
+                            .map_err(|err| CodecError::from(err.to_string()).into())
+                    },
+                    &options,
+                )
+                .await
+            }
+        };
+        let mut stream = futures::stream::iter(chunks.into_iter().map(store_chunk))
+            .buffer_unordered(chunk_concurrent_limit);
+        while let Some(item) = stream.next().await {
+            item?;
+        }
+        Ok(())
+    }
+
+    /// Async variant of [`store_array_subset_strided_elements_opt`](Array::store_array_subset_strided_elements_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_strided_elements_opt<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_async_store_elements!(
+            self,
+            subset_elements,
+            async_store_array_subset_strided_opt(strided_array_subset, subset_elements, options)
+        )
+    }
+
+    /// Async variant of [`store_elements_at_opt`](Array::store_elements_at_opt).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub async fn async_store_elements_at_opt<
+        T: bytemuck::Pod + Send + Sync,
+        TIndices: AsRef<[u64]> + Sync,
+    >(
+        &self,
+        array_indices: &[TIndices],
+        elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        if elements.len() != array_indices.len() {
+            return Err(ArrayError::InvalidBytesInputSize(
+                elements.len() * self.data_type().size(),
+                (array_indices.len() * self.data_type().size()) as u64,
+            ));
+        }
+        let chunk_elements = self.group_array_indices_by_chunk(array_indices)?;
+        let element_size = self.data_type().size();
+        let elements = transmute_to_bytes_vec(elements);
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunk_elements.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_elements =
+            |(chunk_indices, chunk_elements): (ArrayIndices, Vec<(usize, ArrayIndices)>)| {
+                let elements = &elements;
+                let options = options.clone();
+                async move {
+                    let chunk_shape = self.chunk_subset(&chunk_indices)?.shape().to_vec();
+                    self.async_update_chunk_opt(
+                        &chunk_indices,
+                        false,
+                        |chunk_bytes| {
+                            for (index, chunk_element_indices) in chunk_elements {
+                                let chunk_offset = usize::try_from(ravel_indices(
+                                    &chunk_element_indices,
+                                    &chunk_shape,
+                                ))
+                                .unwrap()
+                                    * element_size;
+                                chunk_bytes[chunk_offset..chunk_offset + element_size]
+                                    .copy_from_slice(
+                                        &elements[index * element_size..(index + 1) * element_size],
+                                    );
+                            }
+                            Ok(())
+                        },
+                        &options,
+                    )
+                    .await
+                }
+            };
+        let mut stream =
+            futures::stream::iter(chunk_elements.into_iter().map(store_chunk_elements))
+                .buffer_unordered(chunk_concurrent_limit);
+        while let Some(item) = stream.next().await {
+            item?;
+        }
+        Ok(())
+    }
+
+    /// Async variant of [`store_array_subset_masked_opt`](Array::store_array_subset_masked_opt).
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::missing_panics_doc,
+        clippy::needless_pass_by_value
+    )]
+    pub async fn async_store_array_subset_masked_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let (masked_chunks, positions) = self.masked_array_subset_chunks(array_subset, mask)?;
+        let element_size = self.data_type().size();
+        let num_masked = mask.iter().filter(|&&selected| selected).count();
+        if masked_bytes.len() != num_masked * element_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                masked_bytes.len(),
+                (num_masked * element_size) as u64,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = masked_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_masked = |(chunk_indices, overlap): (ArrayIndices, ArraySubset)| {
+            let masked_bytes = &masked_bytes;
+            let positions = &positions;
+            let options = options.clone();
+            async move {
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let overlap_in_chunk =
+                    unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+                let overlap_in_array_subset =
+                    unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+
+                // The offset in the chunk and position in masked_bytes of each selected element
+                let chunk_indices_linearised =
+                    unsafe { overlap_in_chunk.linearised_indices_unchecked(chunk_subset.shape()) };
+                let array_subset_indices_linearised = unsafe {
+                    overlap_in_array_subset.linearised_indices_unchecked(array_subset.shape())
+                };
+                let selected: Vec<(usize, usize)> =
+                    std::iter::zip(&chunk_indices_linearised, &array_subset_indices_linearised)
+                        .filter_map(|(chunk_index, array_subset_index)| {
+                            let array_subset_index = usize::try_from(array_subset_index).unwrap();
+                            mask[array_subset_index].then(|| {
+                                (
+                                    usize::try_from(chunk_index).unwrap(),
+                                    positions[array_subset_index],
+                                )
+                            })
+                        })
+                        .collect();
+
+                self.async_update_chunk_opt(
+                    &chunk_indices,
+                    selected.len() == chunk_subset.num_elements_usize(),
+                    |chunk_bytes| {
+                        for (chunk_index, position) in selected {
+                            chunk_bytes
+                                [chunk_index * element_size..(chunk_index + 1) * element_size]
+                                .copy_from_slice(
+                                    &masked_bytes
+                                        [position * element_size..(position + 1) * element_size],
+                                );
+                        }
+                        Ok(())
+                    },
+                    &options,
+                )
+                .await
+            }
+        };
+        let mut stream = futures::stream::iter(masked_chunks.into_iter().map(store_chunk_masked))
+            .buffer_unordered(chunk_concurrent_limit);
+        while let Some(item) = stream.next().await {
+            item?;
+        }
+        Ok(())
+    }
+
+    /// Async variant of [`store_array_subset_masked_elements_opt`](Array::store_array_subset_masked_elements_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_masked_elements_opt<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_async_store_elements!(
+            self,
+            masked_elements,
+            async_store_array_subset_masked_opt(array_subset, mask, masked_elements, options)
+        )
+    }
+
+    /// Async variant of [`store_orthogonal_opt`](Array::store_orthogonal_opt).
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::missing_panics_doc,
+        clippy::needless_pass_by_value
+    )]
+    pub async fn async_store_orthogonal_opt<TIndices: AsRef<[u64]> + Sync>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let (selection_chunks, selection_shape) =
+            self.orthogonal_selection_chunks(dimension_indices)?;
+        let element_size = self.data_type().size();
+        let expected_size = selection_shape.iter().product::<u64>() * element_size as u64;
+        if selection_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                selection_bytes.len(),
+                expected_size,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = selection_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_selection =
+            |(chunk_indices, selection): (ArrayIndices, Vec<Vec<(u64, u64)>>)| {
+                let selection_bytes = &selection_bytes;
+                let selection_shape = &selection_shape;
+                let options = options.clone();
+                async move {
+                    let chunk_shape = self.chunk_subset(&chunk_indices)?.shape().to_vec();
+                    let chunk_offsets = orthogonal_selection_offsets(
+                        &selection,
+                        selection_shape,
+                        &ArraySubset::new_with_shape(chunk_shape.clone()),
+                    );
+                    let chunk_selected =
+                        std::iter::zip(&selection, &chunk_shape).all(|(elements, &chunk_size)| {
+                            elements
+                                .iter()
+                                .map(|&(_, chunk_element_index)| chunk_element_index)
+                                .collect::<BTreeSet<_>>()
+                                .len() as u64
+                                == chunk_size
+                        });
+                    self.async_update_chunk_opt(
+                        &chunk_indices,
+                        chunk_selected,
+                        |chunk_bytes| {
+                            for (selection_offset, chunk_offset) in chunk_offsets {
+                                chunk_bytes[chunk_offset * element_size
+                                    ..(chunk_offset + 1) * element_size]
+                                    .copy_from_slice(
+                                        &selection_bytes[selection_offset * element_size
+                                            ..(selection_offset + 1) * element_size],
+                                    );
+                            }
+                            Ok(())
+                        },
+                        &options,
+                    )
+                    .await
+                }
+            };
+        let mut stream =
+            futures::stream::iter(selection_chunks.into_iter().map(store_chunk_selection))
+                .buffer_unordered(chunk_concurrent_limit);
+        while let Some(item) = stream.next().await {
+            item?;
+        }
+        Ok(())
+    }
+
+    /// Async variant of [`store_orthogonal_elements_opt`](Array::store_orthogonal_elements_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_orthogonal_elements_opt<
+        T: bytemuck::Pod + Send + Sync,
+        TIndices: AsRef<[u64]> + Sync,
+    >(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_async_store_elements!(
+            self,
+            selection_elements,
+            async_store_orthogonal_opt(dimension_indices, selection_elements, options)
+        )
+    }
+
+    /// Async variant of [`store_array_subset_endian_opt`](Array::store_array_subset_endian_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_endian_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mut subset_bytes: Vec<u8>,
+        endianness: Endianness,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if !endianness.is_native() && !supports_endianness_conversion(self.data_type()) {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "endianness conversion",
+            ));
+        }
+        endianness.to_native(&mut subset_bytes, self.data_type());
+        self.async_store_array_subset_opt(array_subset, subset_bytes, options)
+            .await
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Async variant of [`store_array_subset_fortran_opt`](Array::store_array_subset_fortran_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_fortran_opt(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if array_subset.dimensionality() != self.shape().len() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        let expected_size = array_subset.num_elements() * self.data_type().size() as u64;
+        if subset_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                subset_bytes.len(),
+                expected_size,
+            ));
+        }
+        let subset_bytes = super::codec::array_to_array::transpose::f_order_to_c_order(
+            subset_bytes,
+            array_subset.shape(),
+            self.data_type().size(),
+        )?;
+        self.async_store_array_subset_opt(array_subset, subset_bytes, options)
+            .await
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Async variant of [`store_array_subset_elements_fortran_opt`](Array::store_array_subset_elements_fortran_opt).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_array_subset_elements_fortran_opt<T: bytemuck::Pod + Send + Sync>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_async_store_elements!(
+            self,
+            subset_elements,
+            async_store_array_subset_fortran_opt(array_subset, subset_elements, options)
+        )
+    }
+
+    /// Apply `update` to the bytes of the chunk at `chunk_indices` and store the updated chunk.
+    ///
+    /// The chunk is locked while it is updated.
+    /// It is decoded before `update` is applied unless `update` replaces every element, in which case `update` is applied to uninitialised (zeroed) bytes.
+    async fn async_update_chunk_opt(
+        &self,
+        chunk_indices: &[u64],
+        update_replaces_chunk: bool,
+        update: impl FnOnce(&mut [u8]) -> Result<(), ArrayError>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        // Lock the chunk
+        let mutex = if let Some(chunk_locks) = &self.async_chunk_locks {
+            Some(chunk_locks.mutex(&self.chunk_key(chunk_indices)).await)
+        } else {
+            None
+        };
+        let _lock = if let Some(mutex) = &mutex {
+            Some(mutex.lock().await)
+        } else {
+            None
+        };
+        let options = chunk_update_options(mutex.is_some(), options);
+
+        let mut chunk_bytes = if update_replaces_chunk {
+            vec![0; self.chunk_array_representation(chunk_indices)?.size_usize()]
+        } else {
+            self.async_retrieve_chunk_opt(chunk_indices, &options)
+                .await?
+        };
+        update(&mut chunk_bytes)?;
+        self.async_store_chunk_opt(chunk_indices, chunk_bytes, &options)
+            .await
+    }
 }
@@ -8,11 +8,15 @@ use crate::{
 };
 
 use super::{
+    check_cancelled,
     codec::{options::CodecOptions, ArrayCodecTraits},
     concurrency::concurrency_chunks_and_codec,
-    Array, ArrayError,
+    Array, ArrayError, ChunkProgressTracker,
 };
 
+#[cfg(feature = "arrow")]
+use super::arrow_array_to_bytes;
+
 impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
     /// Async variant of [`store_metadata`](Array::store_metadata).
     #[allow(clippy::missing_errors_doc)]
@@ -63,6 +67,18 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    #[cfg(feature = "arrow")]
+    /// Async variant of [`store_chunk_arrow`](Array::store_chunk_arrow).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_chunk_arrow(
+        &self,
+        chunk_indices: &[u64],
+        chunk_array: &dyn arrow_array::Array,
+    ) -> Result<(), ArrayError> {
+        let chunk_bytes = arrow_array_to_bytes(chunk_array, self.data_type(), self.fill_value())?;
+        self.async_store_chunk(chunk_indices, chunk_bytes).await
+    }
+
     /// Async variant of [`store_chunks`](Array::store_chunks).
     #[allow(clippy::missing_errors_doc)]
     #[allow(clippy::similar_names)]
@@ -102,6 +118,40 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             .await
     }
 
+    /// Async variant of [`store_encoded_chunk`](Array::store_encoded_chunk).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_store_encoded_chunk(
+        &self,
+        chunk_indices: &[u64],
+        chunk_encoded: bytes::Bytes,
+    ) -> Result<(), ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_writable_transformer(storage_handle);
+        crate::storage::async_store_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+            chunk_encoded,
+        )
+        .await
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        self.update_allocation_map(chunk_indices, true);
+        if self.include_chunk_statistics() {
+            storage_transformer
+                .erase(&self.chunk_statistics_key(chunk_indices))
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Async variant of [`erase_metadata`](Array::erase_metadata).
     #[allow(clippy::missing_errors_doc)]
     pub async fn async_erase_metadata(&self) -> Result<(), StorageError> {
@@ -125,7 +175,14 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             chunk_indices,
             self.chunk_key_encoding(),
         )
-        .await
+        .await?;
+        self.update_allocation_map(chunk_indices, false);
+        if self.include_chunk_statistics() {
+            storage_transformer
+                .erase(&self.chunk_statistics_key(chunk_indices))
+                .await?;
+        }
+        Ok(())
     }
 
     /// Async variant of [`erase_chunks`](Array::erase_chunks).
@@ -144,7 +201,14 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                     &chunk_indices,
                     self.chunk_key_encoding(),
                 )
-                .await
+                .await?;
+                self.update_allocation_map(&chunk_indices, false);
+                if self.include_chunk_statistics() {
+                    storage_transformer
+                        .erase(&self.chunk_statistics_key(&chunk_indices))
+                        .await?;
+                }
+                Ok::<_, StorageError>(())
             }
         };
 
@@ -165,6 +229,18 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
 
     /// Async variant of [`store_chunk_opt`](Array::store_chunk_opt).
     #[allow(clippy::missing_errors_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                size = chunk_bytes.len(),
+            )
+        )
+    )]
     pub async fn async_store_chunk_opt(
         &self,
         chunk_indices: &[u64],
@@ -180,18 +256,15 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
             ));
         }
 
-        if !options.store_empty_chunks() && self.fill_value().equals_all(&chunk_bytes) {
-            self.async_erase_chunk(chunk_indices).await?;
-            Ok(())
-        } else {
+        let statistics = self.chunk_statistics_key_value(chunk_indices, &chunk_bytes);
+        if let Some(chunk_encoded) = self
+            .async_encode_chunk_for_store(chunk_indices, chunk_bytes, options)
+            .await?
+        {
             let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
             let storage_transformer = self
                 .storage_transformers()
                 .create_async_writable_transformer(storage_handle);
-            let chunk_encoded: Vec<u8> = self
-                .codecs()
-                .encode(chunk_bytes, &chunk_array_representation, options)
-                .map_err(ArrayError::CodecError)?;
             crate::storage::async_store_chunk(
                 &*storage_transformer,
                 self.path(),
@@ -200,10 +273,51 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                 chunk_encoded.into(),
             )
             .await
-            .map_err(ArrayError::StorageError)
+            .map_err(|err| self.chunk_error(chunk_indices, err))?;
+            self.update_allocation_map(chunk_indices, true);
+            if let Some((key, value)) = statistics {
+                storage_transformer.set(&key, value.into()).await?;
+            }
+            Ok(())
+        } else {
+            self.async_erase_chunk(chunk_indices)
+                .await
+                .map_err(|err| self.chunk_error(chunk_indices, err))?;
+            Ok(())
         }
     }
 
+    /// Async variant of [`encode_chunk_for_store`](Array::encode_chunk_for_store) which encodes with the [async executor](CodecOptions::async_executor) of `options`.
+    async fn async_encode_chunk_for_store(
+        &self,
+        chunk_indices: &[u64],
+        chunk_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<u8>>, ArrayError> {
+        check_cancelled(options)?;
+        let chunk_array_representation = self.chunk_array_representation(chunk_indices)?;
+        let codecs = self.codecs.clone();
+        let fill_value = self.fill_value().clone();
+        let encode_options = options.clone();
+        options
+            .spawn_blocking(move || {
+                if !encode_options.store_empty_chunks() && fill_value.equals_all(&chunk_bytes) {
+                    Ok(None)
+                } else {
+                    codecs
+                        .encode(
+                            chunk_bytes.into(),
+                            &chunk_array_representation,
+                            &encode_options,
+                        )
+                        .map(|chunk_encoded| Some(chunk_encoded.into_owned()))
+                }
+            })
+            .await
+            .and_then(|chunk_encoded| chunk_encoded)
+            .map_err(|err| self.chunk_error(chunk_indices, err))
+    }
+
     /// Async variant of [`store_chunk_elements_opt`](Array::store_chunk_elements_opt).
     #[allow(clippy::missing_errors_doc)]
     pub async fn async_store_chunk_elements_opt<T: bytemuck::Pod + Send + Sync>(
@@ -250,7 +364,7 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
 
     /// Async variant of [`store_chunks_opt`](Array::store_chunks_opt).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
-    #[allow(clippy::similar_names)]
+    #[allow(clippy::similar_names, clippy::too_many_lines)]
     pub async fn async_store_chunks_opt(
         &self,
         chunks: &ArraySubset,
@@ -258,12 +372,15 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
         options: &CodecOptions,
     ) -> Result<(), ArrayError> {
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         match num_chunks {
             0 => {}
             1 => {
                 let chunk_indices = chunks.start();
+                let chunk_size = chunks_bytes.len() as u64;
                 self.async_store_chunk_opt(chunk_indices, chunks_bytes, options)
                     .await?;
+                progress.chunk_completed(chunk_size);
             }
             _ => {
                 let array_subset = self.chunks_subset(chunks)?;
@@ -288,7 +405,8 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
                     &codec_concurrency,
                 );
 
-                let store_chunk = |chunk_indices: Vec<u64>| {
+                // Encode the chunks concurrently, chunks equal to the fill value are erased rather than stored
+                let encode_chunk = |chunk_indices: Vec<u64>| {
                     let chunk_subset_in_array = unsafe {
                         self.chunk_grid()
                             .subset_unchecked(&chunk_indices, self.shape())
@@ -312,16 +430,58 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits + 'static> Array<TStorage> {
 
                     let options = options.clone();
                     async move {
-                        self.async_store_chunk_opt(&chunk_indices, chunk_bytes, &options)
-                            .await
+                        let chunk_size = chunk_bytes.len() as u64;
+                        let statistics =
+                            self.chunk_statistics_key_value(&chunk_indices, &chunk_bytes);
+                        let chunk_encoded = self
+                            .async_encode_chunk_for_store(&chunk_indices, chunk_bytes, &options)
+                            .await?;
+                        Ok::<_, ArrayError>((chunk_indices, chunk_size, chunk_encoded, statistics))
                     }
                 };
                 let indices = chunks.indices();
-                let futures = indices.into_iter().map(store_chunk);
+                let futures = indices.into_iter().map(encode_chunk);
                 let mut stream =
                     futures::stream::iter(futures).buffer_unordered(chunk_concurrent_limit);
+                let mut key_values = Vec::with_capacity(num_chunks);
+                let mut erase_keys = Vec::new();
+                let mut chunk_sizes = Vec::with_capacity(num_chunks);
+                let mut chunks_allocated = Vec::with_capacity(num_chunks);
                 while let Some(item) = stream.next().await {
-                    item?;
+                    let (chunk_indices, chunk_size, chunk_encoded, statistics) = item?;
+                    let key = self.chunk_key(&chunk_indices);
+                    let allocated = chunk_encoded.is_some();
+                    if let Some(chunk_encoded) = chunk_encoded {
+                        key_values.push((key, chunk_encoded.into()));
+                        if let Some((key, value)) = statistics {
+                            key_values.push((key, value.into()));
+                        }
+                    } else {
+                        erase_keys.push(key);
+                        if self.include_chunk_statistics() {
+                            erase_keys.push(self.chunk_statistics_key(&chunk_indices));
+                        }
+                    }
+                    chunk_sizes.push(chunk_size);
+                    chunks_allocated.push((chunk_indices, allocated));
+                }
+
+                // Store the encoded chunks with a single batched write
+                let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+                let storage_transformer = self
+                    .storage_transformers()
+                    .create_async_writable_transformer(storage_handle);
+                if !key_values.is_empty() {
+                    storage_transformer.set_values(key_values).await?;
+                }
+                if !erase_keys.is_empty() {
+                    storage_transformer.erase_values(&erase_keys).await?;
+                }
+                for (chunk_indices, allocated) in chunks_allocated {
+                    self.update_allocation_map(&chunk_indices, allocated);
+                }
+                for chunk_size in chunk_sizes {
+                    progress.chunk_completed(chunk_size);
                 }
             }
         }
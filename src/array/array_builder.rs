@@ -8,7 +8,8 @@ use super::{
         ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesCodec, BytesToBytesCodecTraits,
     },
     data_type::IncompatibleFillValueError,
-    Array, ArrayCreateError, ArrayShape, ChunkGrid, CodecChain, DataType, DimensionName, FillValue,
+    Array, ArrayCreateError, ArrayShape, AutoCodec, AutoCodecError, ChunkGrid, ChunkRepresentation,
+    CodecChain, DataType, DimensionName, FillValue, AUTO_CODEC_ATTRIBUTE,
 };
 
 /// An [`Array`] builder.
@@ -249,6 +250,22 @@ impl ArrayBuilder {
         self
     }
 
+    /// Set the dimension names and the xarray `_ARRAY_DIMENSIONS` attribute.
+    ///
+    /// This declares the dimension names in both the Zarr V3 and xarray conventions, so that sibling coordinate arrays with the same names are resolved by [`Array::retrieve_coordinates`](crate::array::Array::retrieve_coordinates) and xarray.
+    pub fn xarray_dimension_names<I, D>(&mut self, dimension_names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = D>,
+        D: Into<String>,
+    {
+        let dimension_names: Vec<String> = dimension_names.into_iter().map(Into::into).collect();
+        self.attributes.insert(
+            super::ARRAY_DIMENSIONS_ATTRIBUTE.to_string(),
+            serde_json::Value::from(dimension_names.clone()),
+        );
+        self.dimension_names(Some(dimension_names))
+    }
+
     /// Set the storage transformers.
     ///
     /// If left unmodified, there are no storage transformers.
@@ -260,6 +277,42 @@ impl ArrayBuilder {
         self
     }
 
+    /// Set the codecs to those of the [`AutoCodec`] candidate best suited to `sample_chunk`.
+    ///
+    /// `sample_chunk` must be the decoded bytes of a representative chunk with the shape of the first chunk of the chunk grid.
+    /// The name of the chosen candidate is recorded in the [`AUTO_CODEC_ATTRIBUTE`] attribute.
+    ///
+    /// # Errors
+    /// Returns an [`AutoCodecError`] if the chunk grid is incompatible with the array shape, or a candidate cannot be chosen.
+    pub fn auto_codec(
+        &mut self,
+        auto_codec: &AutoCodec,
+        sample_chunk: Vec<u8>,
+    ) -> Result<&mut Self, AutoCodecError> {
+        let chunk_shape = self
+            .chunk_grid
+            .chunk_shape(&vec![0; self.shape.len()], &self.shape)
+            .map_err(|err| AutoCodecError::InvalidChunkRepresentation(err.to_string()))?
+            .ok_or_else(|| {
+                AutoCodecError::InvalidChunkRepresentation(
+                    "the chunk grid does not define the first chunk".to_string(),
+                )
+            })?;
+        let representation = ChunkRepresentation::new(
+            chunk_shape.to_vec(),
+            self.data_type.clone(),
+            self.fill_value.clone(),
+        )
+        .map_err(|err| AutoCodecError::InvalidChunkRepresentation(err.to_string()))?;
+        let (name, codec_chain) = auto_codec.select(&representation, sample_chunk)?;
+        self.attributes
+            .insert(AUTO_CODEC_ATTRIBUTE.to_string(), name.into());
+        self.array_to_array_codecs = codec_chain.array_to_array_codecs().to_vec();
+        self.array_to_bytes_codec = codec_chain.array_to_bytes_codec().clone();
+        self.bytes_to_bytes_codecs = codec_chain.bytes_to_bytes_codecs().to_vec();
+        Ok(self)
+    }
+
     /// Build into an [`Array`].
     ///
     /// # Errors
@@ -304,16 +357,21 @@ impl ArrayBuilder {
             chunk_grid: self.chunk_grid.clone(),
             chunk_key_encoding: self.chunk_key_encoding.clone(),
             fill_value: self.fill_value.clone(),
-            codecs: CodecChain::new(
+            codecs: Arc::new(CodecChain::new(
                 self.array_to_array_codecs.clone(),
                 self.array_to_bytes_codec.clone(),
                 self.bytes_to_bytes_codecs.clone(),
-            ),
+            )),
             storage_transformers: self.storage_transformers.clone(),
             attributes: self.attributes.clone(),
             dimension_names: self.dimension_names.clone(),
             additional_fields: self.additional_fields.clone(),
             include_zarrs_metadata: true,
+            include_chunk_statistics: false,
+            allocation_map: None,
+            chunk_locks: None,
+            #[cfg(feature = "async")]
+            async_chunk_locks: None,
         })
     }
 }
@@ -416,4 +474,45 @@ mod tests {
         builder.dimension_names(["z", "y", "x"].into());
         assert!(builder.build(storage.clone(), "/").is_err());
     }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn array_builder_auto_codec() {
+        use crate::array::codec::GzipCodec;
+
+        let mut builder = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        );
+        let mut auto_codec = AutoCodec::new();
+        auto_codec
+            .candidate(
+                "bytes",
+                CodecChain::new(vec![], Box::new(BytesCodec::default()), vec![]),
+            )
+            .candidate(
+                "gzip",
+                CodecChain::new(
+                    vec![],
+                    Box::new(BytesCodec::default()),
+                    vec![Box::new(GzipCodec::new(5).unwrap())],
+                ),
+            );
+        builder.auto_codec(&auto_codec, vec![0; 32]).unwrap();
+        assert_eq!(builder.bytes_to_bytes_codecs.len(), 1);
+        assert_eq!(
+            builder.attributes.get(AUTO_CODEC_ATTRIBUTE),
+            Some(&"gzip".into())
+        );
+
+        // Incompatible sample chunk
+        assert!(builder.auto_codec(&auto_codec, vec![0; 8]).is_err());
+        // No candidates
+        assert!(matches!(
+            builder.auto_codec(&AutoCodec::new(), vec![0; 32]),
+            Err(AutoCodecError::NoCandidate)
+        ));
+    }
 }
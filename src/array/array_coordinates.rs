@@ -0,0 +1,661 @@
+use std::{cmp::Ordering, ops::Range};
+
+use thiserror::Error;
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{transmute_from_bytes_vec, Array, ArrayCreateError, ArrayError, ArrayShape, DataType};
+
+/// The attribute holding the dimension names of an array in the [xarray](https://docs.xarray.dev/) Zarr convention.
+pub const ARRAY_DIMENSIONS_ATTRIBUTE: &str = "_ARRAY_DIMENSIONS";
+
+/// A coordinate label.
+///
+/// `numpy.datetime64` and `numpy.timedelta64` labels are integers in the unit of the coordinate array data type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Label {
+    /// An integer label.
+    Int(i64),
+    /// A floating point label.
+    Float(f64),
+}
+
+macro_rules! label_from {
+    ($variant:ident, $cast:ty, $($type:ty),*) => {
+        $(
+            impl From<$type> for Label {
+                fn from(value: $type) -> Self {
+                    Self::$variant(<$cast>::from(value))
+                }
+            }
+        )*
+    };
+}
+
+label_from!(Int, i64, i8, i16, i32, i64, u8, u16, u32);
+label_from!(Float, f64, f32, f64);
+
+/// A label-based selection of a dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LabelSelection {
+    /// The element with a label equal to the label.
+    Label(Label),
+    /// The elements with labels between the start and end labels (inclusive), like an xarray label slice.
+    ///
+    /// An unbounded start or end selects from the first or to the last element.
+    /// The coordinates must be monotonic and the start and end labels are in the order of the coordinates.
+    Range(Option<Label>, Option<Label>),
+    /// The element with the label nearest to the label.
+    Nearest(Label),
+}
+
+impl LabelSelection {
+    /// Create a [`LabelSelection::Range`] from `start` to `end` (inclusive).
+    #[must_use]
+    pub fn range(start: impl Into<Label>, end: impl Into<Label>) -> Self {
+        Self::Range(Some(start.into()), Some(end.into()))
+    }
+}
+
+/// The coordinate values (labels) of a dimension.
+///
+/// Integer, `numpy.datetime64`, and `numpy.timedelta64` coordinates are [`CoordinateValues::Int`] and floating point coordinates are [`CoordinateValues::Float`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CoordinateValues {
+    /// Integer coordinates.
+    Int(Vec<i64>),
+    /// Floating point coordinates.
+    Float(Vec<f64>),
+}
+
+impl CoordinateValues {
+    /// Convert the `bytes` of elements with `data_type` to coordinate values.
+    ///
+    /// # Errors
+    /// Returns [`CoordinateError::UnsupportedDataType`] if `data_type` is not an integer, floating point, `numpy.datetime64`, or `numpy.timedelta64` data type, or a `uint64` value exceeds [`i64::MAX`].
+    #[allow(clippy::cast_possible_wrap)]
+    pub fn from_bytes(bytes: Vec<u8>, data_type: &DataType) -> Result<Self, CoordinateError> {
+        fn int<T: bytemuck::Pod + Into<i64>>(bytes: Vec<u8>) -> CoordinateValues {
+            CoordinateValues::Int(
+                transmute_from_bytes_vec::<T>(bytes)
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            )
+        }
+        fn float<T: bytemuck::Pod + Into<f64>>(bytes: Vec<u8>) -> CoordinateValues {
+            CoordinateValues::Float(
+                transmute_from_bytes_vec::<T>(bytes)
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            )
+        }
+        match data_type {
+            DataType::Int8 => Ok(int::<i8>(bytes)),
+            DataType::Int16 => Ok(int::<i16>(bytes)),
+            DataType::Int32 => Ok(int::<i32>(bytes)),
+            DataType::Int64 | DataType::DateTime64(_) | DataType::TimeDelta64(_) => {
+                Ok(int::<i64>(bytes))
+            }
+            DataType::UInt8 => Ok(int::<u8>(bytes)),
+            DataType::UInt16 => Ok(int::<u16>(bytes)),
+            DataType::UInt32 => Ok(int::<u32>(bytes)),
+            DataType::UInt64 => transmute_from_bytes_vec::<u64>(bytes)
+                .into_iter()
+                .map(i64::try_from)
+                .collect::<Result<_, _>>()
+                .map(CoordinateValues::Int)
+                .map_err(|_| CoordinateError::UnsupportedDataType(data_type.clone())),
+            DataType::Float16 => Ok(float::<half::f16>(bytes)),
+            DataType::BFloat16 => Ok(float::<half::bf16>(bytes)),
+            DataType::Float32 => Ok(float::<f32>(bytes)),
+            DataType::Float64 => Ok(float::<f64>(bytes)),
+            _ => Err(CoordinateError::UnsupportedDataType(data_type.clone())),
+        }
+    }
+
+    /// Return the number of coordinates.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Int(values) => values.len(),
+            Self::Float(values) => values.len(),
+        }
+    }
+
+    /// Returns true if there are no coordinates.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compare the coordinate at `index` to `label`.
+    #[allow(clippy::cast_precision_loss)]
+    fn cmp(&self, index: usize, label: Label) -> Option<Ordering> {
+        match (self, label) {
+            (Self::Int(values), Label::Int(label)) => Some(values[index].cmp(&label)),
+            (Self::Int(values), Label::Float(label)) => (values[index] as f64).partial_cmp(&label),
+            (Self::Float(values), Label::Int(label)) => values[index].partial_cmp(&(label as f64)),
+            (Self::Float(values), Label::Float(label)) => values[index].partial_cmp(&label),
+        }
+    }
+
+    /// Return the distance between the coordinate at `index` and `label`.
+    #[allow(clippy::cast_precision_loss)]
+    fn distance(&self, index: usize, label: Label) -> f64 {
+        let value = match self {
+            Self::Int(values) => values[index] as f64,
+            Self::Float(values) => values[index],
+        };
+        let label = match label {
+            Label::Int(label) => label as f64,
+            Label::Float(label) => label,
+        };
+        (value - label).abs()
+    }
+
+    /// Return true if the coordinates are monotonically increasing, false if they are monotonically decreasing, or [`None`] if they are not monotonic.
+    fn increasing(&self) -> Option<bool> {
+        let order = |ordering: Ordering| {
+            (1..self.len()).all(|i| {
+                let previous = match self {
+                    Self::Int(values) => Label::Int(values[i - 1]),
+                    Self::Float(values) => Label::Float(values[i - 1]),
+                };
+                self.cmp(i, previous)
+                    .is_some_and(|o| o == ordering || o == Ordering::Equal)
+            })
+        };
+        if order(Ordering::Greater) {
+            Some(true)
+        } else if order(Ordering::Less) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Return the index of the first coordinate for which `predicate` is false, assuming the coordinates are partitioned by `predicate`.
+    fn partition_point(&self, predicate: impl Fn(Option<Ordering>) -> bool, label: Label) -> u64 {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if predicate(self.cmp(mid, label)) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo as u64
+    }
+
+    /// Return the index range of the coordinates selected by `selection`.
+    ///
+    /// # Errors
+    /// Returns a [`CoordinateError`] if a label is not found or a range selection is applied to non-monotonic coordinates.
+    pub fn index_range(&self, selection: &LabelSelection) -> Result<Range<u64>, CoordinateError> {
+        match selection {
+            LabelSelection::Label(label) => (0..self.len())
+                .find(|&i| self.cmp(i, *label) == Some(Ordering::Equal))
+                .map(|i| i as u64..i as u64 + 1)
+                .ok_or(CoordinateError::LabelNotFound(*label)),
+            LabelSelection::Nearest(label) => (0..self.len())
+                .min_by(|&a, &b| {
+                    self.distance(a, *label)
+                        .total_cmp(&self.distance(b, *label))
+                })
+                .map(|i| i as u64..i as u64 + 1)
+                .ok_or(CoordinateError::LabelNotFound(*label)),
+            LabelSelection::Range(start, end) => {
+                let increasing = self.increasing().ok_or(CoordinateError::NotMonotonic)?;
+                let (before, after) = if increasing {
+                    (Ordering::Less, Ordering::Greater)
+                } else {
+                    (Ordering::Greater, Ordering::Less)
+                };
+                let start = start.map_or(0, |start| {
+                    self.partition_point(|ordering| ordering == Some(before), start)
+                });
+                let end = end.map_or(self.len() as u64, |end| {
+                    self.partition_point(|ordering| ordering != Some(after), end)
+                });
+                Ok(start..std::cmp::max(start, end))
+            }
+        }
+    }
+}
+
+/// The coordinates of the dimensions of an array.
+///
+/// Returned by [`Array::retrieve_coordinates`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coordinates {
+    shape: ArrayShape,
+    dimension_names: Vec<Option<String>>,
+    values: Vec<Option<CoordinateValues>>,
+}
+
+impl Coordinates {
+    /// Create coordinates for an array with `shape` and `dimension_names`, where `values` are the coordinate values of each dimension (if any).
+    ///
+    /// # Errors
+    /// Returns [`CoordinateError::IncompatibleShape`] if the lengths of `dimension_names` or `values` do not match the dimensionality of `shape`, or the coordinate values of a dimension have a different length to the dimension.
+    pub fn new(
+        shape: ArrayShape,
+        dimension_names: Vec<Option<String>>,
+        values: Vec<Option<CoordinateValues>>,
+    ) -> Result<Self, CoordinateError> {
+        let compatible = dimension_names.len() == shape.len()
+            && values.len() == shape.len()
+            && std::iter::zip(&shape, &values).all(|(&size, values)| {
+                values
+                    .as_ref()
+                    .map_or(true, |values| values.len() as u64 == size)
+            });
+        if compatible {
+            Ok(Self {
+                shape,
+                dimension_names,
+                values,
+            })
+        } else {
+            Err(CoordinateError::IncompatibleShape(shape))
+        }
+    }
+
+    /// Return the dimension names.
+    #[must_use]
+    pub fn dimension_names(&self) -> &[Option<String>] {
+        &self.dimension_names
+    }
+
+    /// Return the coordinate values of the dimension with `name`, if it has coordinates.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&CoordinateValues> {
+        self.dimension_index(name)
+            .and_then(|dimension| self.values[dimension].as_ref())
+    }
+
+    fn dimension_index(&self, name: &str) -> Option<usize> {
+        self.dimension_names
+            .iter()
+            .position(|dimension_name| dimension_name.as_deref() == Some(name))
+    }
+
+    /// Return the array subset selected by label-based `selections` of named dimensions, like xarray `sel`.
+    ///
+    /// Dimensions without a selection are selected in their entirety.
+    ///
+    /// # Errors
+    /// Returns a [`CoordinateError`] if
+    ///  - a dimension name is unknown or has no coordinates,
+    ///  - a label is not found, or
+    ///  - a range selection is applied to non-monotonic coordinates.
+    pub fn sel(
+        &self,
+        selections: &[(&str, LabelSelection)],
+    ) -> Result<ArraySubset, CoordinateError> {
+        let mut ranges: Vec<Range<u64>> = self.shape.iter().map(|&size| 0..size).collect();
+        for (name, selection) in selections {
+            let dimension = self
+                .dimension_index(name)
+                .ok_or_else(|| CoordinateError::UnknownDimension((*name).to_string()))?;
+            let values = self.values[dimension]
+                .as_ref()
+                .ok_or_else(|| CoordinateError::MissingCoordinates((*name).to_string()))?;
+            ranges[dimension] = values.index_range(selection)?;
+        }
+        Ok(ArraySubset::new_with_ranges(&ranges))
+    }
+}
+
+/// A coordinate error.
+#[derive(Debug, Error)]
+pub enum CoordinateError {
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// The data type is not supported for coordinates.
+    #[error("the {0} data type is not supported for coordinates")]
+    UnsupportedDataType(DataType),
+    /// A coordinate array is incompatible with its dimension.
+    #[error("coordinates are incompatible with the array shape {0:?}")]
+    IncompatibleShape(ArrayShape),
+    /// The dimension name is unknown.
+    #[error("unknown dimension {0}")]
+    UnknownDimension(String),
+    /// The dimension has no coordinates.
+    #[error("dimension {0} has no coordinates")]
+    MissingCoordinates(String),
+    /// A label was not found.
+    #[error("label {0:?} not found")]
+    LabelNotFound(Label),
+    /// A range selection was applied to non-monotonic coordinates.
+    #[error("a range selection requires monotonic coordinates")]
+    NotMonotonic,
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return the dimension names of the array.
+    ///
+    /// These are the `dimension_names` of the array metadata if set, otherwise the xarray `_ARRAY_DIMENSIONS` attribute if set, otherwise all [`None`].
+    #[must_use]
+    pub fn dimension_names_or_attribute(&self) -> Vec<Option<String>> {
+        if let Some(dimension_names) = &self.dimension_names {
+            if dimension_names.iter().any(|name| name.as_str().is_some()) {
+                return dimension_names
+                    .iter()
+                    .map(|name| name.as_str().map(str::to_string))
+                    .collect();
+            }
+        }
+        match self.attributes.get(ARRAY_DIMENSIONS_ATTRIBUTE) {
+            Some(serde_json::Value::Array(names)) if names.len() == self.dimensionality() => names
+                .iter()
+                .map(|name| name.as_str().map(str::to_string))
+                .collect(),
+            _ => vec![None; self.dimensionality()],
+        }
+    }
+
+    /// Return the path of the coordinate array of the dimension `name`, which is a sibling of this array.
+    fn coordinate_array_path(&self, name: &str) -> String {
+        let parent = self
+            .path
+            .as_str()
+            .rsplit_once('/')
+            .map_or("", |(parent, _)| parent);
+        format!("{parent}/{name}")
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Retrieve the coordinates of the dimensions of the array.
+    ///
+    /// The coordinates of a named dimension (see [`dimension_names_or_attribute`](Array::dimension_names_or_attribute)) are the elements of the one-dimensional array with the same name in the same group, as in the xarray Zarr convention.
+    /// Dimensions that are unnamed or without a coordinate array have no coordinates.
+    ///
+    /// # Errors
+    /// Returns a [`CoordinateError`] if a coordinate array has an unsupported data type, a different length to its dimension, or there is an underlying array or store error.
+    pub fn retrieve_coordinates(&self) -> Result<Coordinates, CoordinateError> {
+        let dimension_names = self.dimension_names_or_attribute();
+        let mut values = Vec::with_capacity(dimension_names.len());
+        for (name, &size) in std::iter::zip(&dimension_names, self.shape()) {
+            let Some(name) = name else {
+                values.push(None);
+                continue;
+            };
+            let array = match Array::new(self.storage.clone(), &self.coordinate_array_path(name)) {
+                Ok(array) => array,
+                Err(ArrayCreateError::MissingMetadata) => {
+                    values.push(None);
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if array.shape() != [size] {
+                return Err(CoordinateError::IncompatibleShape(self.shape().to_vec()));
+            }
+            let bytes = array.retrieve_array_subset(&ArraySubset::new_with_shape(vec![size]))?;
+            values.push(Some(CoordinateValues::from_bytes(
+                bytes,
+                array.data_type(),
+            )?));
+        }
+        Coordinates::new(self.shape().to_vec(), dimension_names, values)
+    }
+
+    /// Return the array subset selected by label-based `selections` of named dimensions, like xarray `sel`.
+    ///
+    /// See [`Array::retrieve_coordinates`] and [`Coordinates::sel`].
+    ///
+    /// # Errors
+    /// Returns a [`CoordinateError`] if the coordinates cannot be retrieved or a [`Coordinates::sel`] error condition is met.
+    pub fn sel(
+        &self,
+        selections: &[(&str, LabelSelection)],
+    ) -> Result<ArraySubset, CoordinateError> {
+        self.retrieve_coordinates()?.sel(selections)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + crate::storage::AsyncReadableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`retrieve_coordinates`](Array::retrieve_coordinates).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_coordinates(&self) -> Result<Coordinates, CoordinateError> {
+        let dimension_names = self.dimension_names_or_attribute();
+        let mut values = Vec::with_capacity(dimension_names.len());
+        for (name, &size) in std::iter::zip(&dimension_names, self.shape()) {
+            let Some(name) = name else {
+                values.push(None);
+                continue;
+            };
+            let array =
+                match Array::async_new(self.storage.clone(), &self.coordinate_array_path(name))
+                    .await
+                {
+                    Ok(array) => array,
+                    Err(ArrayCreateError::MissingMetadata) => {
+                        values.push(None);
+                        continue;
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+            if array.shape() != [size] {
+                return Err(CoordinateError::IncompatibleShape(self.shape().to_vec()));
+            }
+            let bytes = array
+                .async_retrieve_array_subset(&ArraySubset::new_with_shape(vec![size]))
+                .await?;
+            values.push(Some(CoordinateValues::from_bytes(
+                bytes,
+                array.data_type(),
+            )?));
+        }
+        Coordinates::new(self.shape().to_vec(), dimension_names, values)
+    }
+
+    /// Async variant of [`sel`](Array::sel).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_sel(
+        &self,
+        selections: &[(&str, LabelSelection)],
+    ) -> Result<ArraySubset, CoordinateError> {
+        self.async_retrieve_coordinates().await?.sel(selections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn store_coordinates<T: bytemuck::Pod>(
+        store: &Arc<MemoryStore>,
+        path: &str,
+        data_type: DataType,
+        fill_value: FillValue,
+        values: Vec<T>,
+    ) {
+        let array = ArrayBuilder::new(
+            vec![values.len() as u64],
+            data_type,
+            vec![3].try_into().unwrap(),
+            fill_value,
+        )
+        .build(store.clone(), path)
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements(
+                &ArraySubset::new_with_shape(array.shape().to_vec()),
+                values,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn coordinates_index_range() {
+        let increasing = CoordinateValues::Int(vec![0, 10, 20, 30, 40]);
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::range(10, 30))
+                .unwrap(),
+            1..4
+        );
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::range(5.0, 35.0))
+                .unwrap(),
+            1..4
+        );
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::Range(None, Some(Label::Int(20))))
+                .unwrap(),
+            0..3
+        );
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::range(50, 60))
+                .unwrap(),
+            5..5
+        );
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::Label(Label::Int(20)))
+                .unwrap(),
+            2..3
+        );
+        assert!(matches!(
+            increasing.index_range(&LabelSelection::Label(Label::Int(25))),
+            Err(CoordinateError::LabelNotFound(Label::Int(25)))
+        ));
+        assert_eq!(
+            increasing
+                .index_range(&LabelSelection::Nearest(Label::Float(26.0)))
+                .unwrap(),
+            3..4
+        );
+
+        let decreasing = CoordinateValues::Float(vec![90.0, 45.0, 0.0, -45.0, -90.0]);
+        assert_eq!(
+            decreasing
+                .index_range(&LabelSelection::range(50, -10))
+                .unwrap(),
+            1..3
+        );
+        assert_eq!(
+            decreasing
+                .index_range(&LabelSelection::Range(Some(Label::Float(0.0)), None))
+                .unwrap(),
+            2..5
+        );
+
+        let unordered = CoordinateValues::Int(vec![0, 2, 1]);
+        assert!(matches!(
+            unordered.index_range(&LabelSelection::range(0, 1)),
+            Err(CoordinateError::NotMonotonic)
+        ));
+    }
+
+    #[test]
+    fn array_coordinates_sel() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 5, 2],
+            DataType::Float32,
+            vec![2, 2, 2].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .xarray_dimension_names(["time", "lat", "band"])
+        .build(store.clone(), "/group/temperature")
+        .unwrap();
+        assert_eq!(
+            array.attributes().get(ARRAY_DIMENSIONS_ATTRIBUTE),
+            Some(&serde_json::json!(["time", "lat", "band"]))
+        );
+        store_coordinates(
+            &store,
+            "/group/time",
+            DataType::Int64,
+            FillValue::from(0i64),
+            vec![100i64, 200, 300, 400],
+        );
+        store_coordinates(
+            &store,
+            "/group/lat",
+            DataType::Float64,
+            FillValue::from(0.0f64),
+            vec![60.0f64, 30.0, 0.0, -30.0, -60.0],
+        );
+
+        let coordinates = array.retrieve_coordinates().unwrap();
+        assert_eq!(
+            coordinates.get("time"),
+            Some(&CoordinateValues::Int(vec![100, 200, 300, 400]))
+        );
+        assert!(coordinates.get("band").is_none());
+
+        let subset = array
+            .sel(&[
+                ("time", LabelSelection::range(200, 300)),
+                ("lat", LabelSelection::range(40.0, -40.0)),
+            ])
+            .unwrap();
+        assert_eq!(subset, ArraySubset::new_with_ranges(&[1..3, 1..4, 0..2]));
+        assert!(matches!(
+            array.sel(&[("band", LabelSelection::Label(Label::Int(0)))]),
+            Err(CoordinateError::MissingCoordinates(_))
+        ));
+        assert!(matches!(
+            array.sel(&[("depth", LabelSelection::Label(Label::Int(0)))]),
+            Err(CoordinateError::UnknownDimension(_))
+        ));
+    }
+
+    #[test]
+    fn array_coordinates_attribute() {
+        let store = Arc::new(MemoryStore::new());
+        let mut attributes = serde_json::Map::new();
+        attributes.insert(
+            ARRAY_DIMENSIONS_ATTRIBUTE.to_string(),
+            serde_json::json!(["x"]),
+        );
+        let array = ArrayBuilder::new(
+            vec![3],
+            DataType::UInt8,
+            vec![3].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .attributes(attributes)
+        .build(store.clone(), "/data")
+        .unwrap();
+        assert_eq!(
+            array.dimension_names_or_attribute(),
+            vec![Some("x".to_string())]
+        );
+        store_coordinates(
+            &store,
+            "/x",
+            DataType::UInt16,
+            FillValue::from(0u16),
+            vec![1u16, 2],
+        );
+        assert!(matches!(
+            array.retrieve_coordinates(),
+            Err(CoordinateError::IncompatibleShape(_))
+        ));
+    }
+}
@@ -3,9 +3,9 @@ use thiserror::Error;
 use crate::{
     array_subset::{ArraySubset, IncompatibleDimensionalityError},
     metadata::UnsupportedAdditionalFieldError,
-    node::NodePathError,
+    node::{NodePath, NodePathError},
     plugin::PluginCreateError,
-    storage::StorageError,
+    storage::{MetadataWriteError, StorageError, StoreKey},
 };
 
 use super::{
@@ -13,7 +13,7 @@ use super::{
     data_type::{
         IncompatibleFillValueError, IncompatibleFillValueMetadataError, UnsupportedDataTypeError,
     },
-    ArrayIndices, ArrayShape,
+    ArrayIndices, ArrayShape, DataType,
 };
 
 /// An array creation error.
@@ -64,6 +64,9 @@ pub enum ArrayCreateError {
     /// Missing metadata.
     #[error("array metadata is missing")]
     MissingMetadata,
+    /// Metadata write error.
+    #[error(transparent)]
+    MetadataWriteError(#[from] MetadataWriteError),
 }
 
 /// Array errors.
@@ -105,4 +108,109 @@ pub enum ArrayError {
     /// Invalid data shape.
     #[error("data has shape {_0:?}, expected {_1:?}")]
     InvalidDataShape(Vec<usize>, Vec<usize>),
+    /// Incompatible element type.
+    #[error("element type {_1} is incompatible with data type {_0}")]
+    IncompatibleElementType(DataType, &'static str),
+    /// Invalid array indices.
+    #[error("array indices {_0:?} are not compatible with array shape {_1:?}")]
+    InvalidArrayIndices(ArrayIndices, ArrayShape),
+    /// Invalid mask size.
+    #[error("got mask with {_0} elements, expected {_1}")]
+    InvalidMaskSize(usize, u64),
+    /// Invalid orthogonal index.
+    #[error("index {_1} along axis {_0} is out of bounds of array shape {_2:?}")]
+    InvalidOrthogonalIndex(usize, u64, ArrayShape),
+    /// An axis cannot be squeezed.
+    #[error("axis {_0} of shape {_1:?} cannot be squeezed, it must have length one")]
+    InvalidSqueezeAxis(usize, ArrayShape),
+    /// The request was cancelled with a [`CancellationToken`](crate::array::codec::CancellationToken).
+    #[error("the request was cancelled")]
+    Cancelled,
+    /// An element cannot be represented by the data type.
+    #[error("element {_0} cannot be represented by data type {_1}")]
+    ElementOutOfRange(String, DataType),
+    /// A storage or codec error retrieving, decoding, encoding, or storing a chunk.
+    #[error("chunk {chunk_indices:?} of array {array_path} at key {key}: {source}")]
+    ChunkError {
+        /// The path of the array.
+        array_path: NodePath,
+        /// The indices of the chunk.
+        chunk_indices: ArrayIndices,
+        /// The store key of the chunk.
+        key: StoreKey,
+        /// The underlying error.
+        source: Box<ArrayError>,
+    },
+}
+
+impl ArrayError {
+    /// Return the indices of the chunk in the error context, if any.
+    #[must_use]
+    pub fn chunk_indices(&self) -> Option<&[u64]> {
+        match self {
+            Self::ChunkError { chunk_indices, .. } => Some(chunk_indices),
+            _ => None,
+        }
+    }
+
+    /// Return the store key of the chunk in the error context, if any.
+    #[must_use]
+    pub fn key(&self) -> Option<&StoreKey> {
+        match self {
+            Self::ChunkError { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Return the underlying error without its chunk context.
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::ChunkError { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// Convert into the underlying error without its chunk context.
+    #[must_use]
+    pub fn into_root(self) -> Self {
+        match self {
+            Self::ChunkError { source, .. } => source.into_root(),
+            err => err,
+        }
+    }
+}
+
+/// A chunk that failed to decode during a lenient retrieval.
+///
+/// See [`Array::retrieve_array_subset_lenient`](crate::array::Array::retrieve_array_subset_lenient).
+#[derive(Debug, Error)]
+#[error("chunk {chunk_indices:?} failed to decode: {error}")]
+pub struct ChunkDecodeError {
+    chunk_indices: ArrayIndices,
+    #[source]
+    error: CodecError,
+}
+
+impl ChunkDecodeError {
+    /// Create a new [`ChunkDecodeError`].
+    #[must_use]
+    pub fn new(chunk_indices: ArrayIndices, error: CodecError) -> Self {
+        Self {
+            chunk_indices,
+            error,
+        }
+    }
+
+    /// Return the indices of the chunk that failed to decode.
+    #[must_use]
+    pub fn chunk_indices(&self) -> &[u64] {
+        &self.chunk_indices
+    }
+
+    /// Return the decoding error.
+    #[must_use]
+    pub fn error(&self) -> &CodecError {
+        &self.error
+    }
 }
@@ -4,13 +4,19 @@
 
 use derive_more::{Display, From};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{
     array::FillValueMetadata,
     metadata::{AdditionalFields, Metadata},
+    storage::storage_transformer::StorageTransformerChain,
 };
 
-use super::{ArrayShape, DimensionName};
+use super::{
+    chunk_key_encoding::ChunkKeyEncoding,
+    codec::{Codec, CodecChain},
+    ArrayCreateError, ArrayShape, ChunkGrid, DataType, DimensionName,
+};
 
 /// Zarr array metadata.
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Display, From)]
@@ -20,6 +26,19 @@ pub enum ArrayMetadata {
     V3(ArrayMetadataV3),
 }
 
+impl ArrayMetadata {
+    /// Validate the metadata and return a diagnostic for every problem identified.
+    ///
+    /// Unlike [`Array::new_with_metadata`](crate::array::Array::new_with_metadata), which fails on the first problem, this reports all problems at once.
+    /// The metadata is valid if no diagnostics are returned.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ArrayMetadataDiagnostic> {
+        match self {
+            Self::V3(metadata) => metadata.validate(),
+        }
+    }
+}
+
 impl TryFrom<&str> for ArrayMetadata {
     type Error = serde_json::Error;
     fn try_from(metadata_json: &str) -> Result<Self, Self::Error> {
@@ -163,4 +182,200 @@ impl ArrayMetadataV3 {
     pub fn validate_node_type(&self) -> bool {
         self.node_type == "array"
     }
+
+    /// Validate the metadata and return a diagnostic for every problem identified.
+    ///
+    /// See [`ArrayMetadata::validate`].
+    #[must_use]
+    pub fn validate(&self) -> Vec<ArrayMetadataDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut diagnose = |field: &str, error: ArrayCreateError| {
+            diagnostics.push(ArrayMetadataDiagnostic::new(field.to_string(), error));
+        };
+
+        if !self.validate_format() {
+            diagnose(
+                "zarr_format",
+                ArrayCreateError::InvalidZarrFormat(self.zarr_format),
+            );
+        }
+        if !self.validate_node_type() {
+            diagnose(
+                "node_type",
+                ArrayCreateError::InvalidNodeType(self.node_type.clone()),
+            );
+        }
+
+        match DataType::from_metadata(&self.data_type) {
+            Ok(data_type) => {
+                if let Err(err) = data_type.fill_value_from_metadata(&self.fill_value) {
+                    diagnose(
+                        "fill_value",
+                        ArrayCreateError::InvalidFillValueMetadata(err),
+                    );
+                }
+            }
+            Err(err) => diagnose("data_type", ArrayCreateError::DataTypeCreateError(err)),
+        }
+
+        match ChunkGrid::from_metadata(&self.chunk_grid) {
+            Ok(chunk_grid) => {
+                if chunk_grid.dimensionality() != self.shape.len() {
+                    diagnose(
+                        "chunk_grid",
+                        ArrayCreateError::InvalidChunkGridDimensionality(
+                            chunk_grid.dimensionality(),
+                            self.shape.len(),
+                        ),
+                    );
+                }
+            }
+            Err(err) => diagnose("chunk_grid", ArrayCreateError::ChunkGridCreateError(err)),
+        }
+
+        if let Err(err) = ChunkKeyEncoding::from_metadata(&self.chunk_key_encoding) {
+            diagnose(
+                "chunk_key_encoding",
+                ArrayCreateError::ChunkKeyEncodingCreateError(err),
+            );
+        }
+
+        // Check each codec, then the composition of the codec chain if every codec is valid
+        let mut codecs_valid = true;
+        for (i, codec) in self.codecs.iter().enumerate() {
+            if let Err(err) = Codec::from_metadata(codec) {
+                codecs_valid = false;
+                diagnose(
+                    &format!("codecs/{i}"),
+                    ArrayCreateError::CodecsCreateError(err),
+                );
+            }
+        }
+        if codecs_valid {
+            if let Err(err) = CodecChain::from_metadata(&self.codecs) {
+                diagnose("codecs", ArrayCreateError::CodecsCreateError(err));
+            }
+        }
+
+        for (i, storage_transformer) in self.storage_transformers.iter().enumerate() {
+            if let Err(err) =
+                StorageTransformerChain::from_metadata(std::slice::from_ref(storage_transformer))
+            {
+                diagnose(
+                    &format!("storage_transformers/{i}"),
+                    ArrayCreateError::StorageTransformersCreateError(err),
+                );
+            }
+        }
+
+        if let Some(dimension_names) = &self.dimension_names {
+            if dimension_names.len() != self.shape.len() {
+                diagnose(
+                    "dimension_names",
+                    ArrayCreateError::InvalidDimensionNames(
+                        dimension_names.len(),
+                        self.shape.len(),
+                    ),
+                );
+            }
+        }
+
+        for err in self.additional_fields.unsupported_fields() {
+            let field = err.name().to_string();
+            diagnose(
+                &field,
+                ArrayCreateError::UnsupportedAdditionalFieldError(err),
+            );
+        }
+
+        diagnostics
+    }
+}
+
+/// A problem with array metadata identified by [`ArrayMetadata::validate`].
+#[derive(Debug, Error)]
+#[error("{field}: {error}")]
+pub struct ArrayMetadataDiagnostic {
+    field: String,
+    #[source]
+    error: ArrayCreateError,
+}
+
+impl ArrayMetadataDiagnostic {
+    /// Create a new [`ArrayMetadataDiagnostic`].
+    #[must_use]
+    pub fn new(field: String, error: ArrayCreateError) -> Self {
+        Self { field, error }
+    }
+
+    /// Return the metadata field with the problem.
+    ///
+    /// Elements of the `codecs` and `storage_transformers` lists are identified by their index (e.g. `codecs/1`).
+    #[must_use]
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Return the error describing the problem.
+    #[must_use]
+    pub fn error(&self) -> &ArrayCreateError {
+        &self.error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_metadata_validate() {
+        let json = r#"{
+            "zarr_format": 3,
+            "node_type": "array",
+            "shape": [8, 8],
+            "data_type": "uint8",
+            "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": [4, 4]}},
+            "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+            "fill_value": 0,
+            "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+            "dimension_names": ["y", "x"]
+        }"#;
+        let metadata = ArrayMetadata::try_from(json).unwrap();
+        assert!(metadata.validate().is_empty());
+
+        let json = r#"{
+            "zarr_format": 3,
+            "node_type": "array",
+            "shape": [8, 8],
+            "data_type": "uint8",
+            "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": [4, 4]}},
+            "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+            "fill_value": "NaN",
+            "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}, {"name": "unknown"}],
+            "dimension_names": ["x"],
+            "unknown_extension": {"must_understand": true},
+            "ignored_extension": {"must_understand": false}
+        }"#;
+        let metadata = ArrayMetadata::try_from(json).unwrap();
+        let diagnostics = metadata.validate();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(ArrayMetadataDiagnostic::field)
+                .collect::<Vec<_>>(),
+            vec![
+                "fill_value",
+                "codecs/1",
+                "dimension_names",
+                "unknown_extension"
+            ]
+        );
+        assert!(matches!(
+            diagnostics[2].error(),
+            ArrayCreateError::InvalidDimensionNames(1, 2)
+        ));
+        assert!(diagnostics[3]
+            .to_string()
+            .starts_with("unknown_extension: "));
+    }
 }
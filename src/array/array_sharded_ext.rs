@@ -1,4 +1,9 @@
-use super::{codec::ShardingCodecConfiguration, Array, ChunkGrid, ChunkShape};
+use crate::array_subset::ArraySubset;
+
+use super::{
+    codec::{array_to_bytes::sharding::IDENTIFIER, ShardingCodecConfiguration},
+    Array, ArrayError, ArrayShape, ChunkGrid, ChunkShape,
+};
 
 /// An [`Array`] extension trait to simplify working with arrays using the `sharding_indexed` codec.
 pub trait ArrayShardedExt {
@@ -14,6 +19,29 @@ pub trait ArrayShardedExt {
     ///
     /// Returns the normal chunk grid for an unsharded array.
     fn inner_chunk_grid(&self) -> ChunkGrid;
+
+    /// Return the shape of the inner chunk grid.
+    ///
+    /// Returns the normal chunk grid shape for an unsharded array.
+    /// Returns [`None`] if the inner chunk grid is incompatible with the array shape.
+    fn inner_chunk_grid_shape(&self) -> Option<ArrayShape>;
+
+    /// Return the inner chunk shapes of nested `sharding_indexed` codecs, from the outermost to the innermost.
+    ///
+    /// The first element is the [`inner_chunk_shape`](ArrayShardedExt::inner_chunk_shape).
+    /// Subsequent elements are present if the inner chunks are themselves encoded with the `sharding_indexed` codec.
+    /// Returns an empty vector for an unsharded array.
+    fn inner_chunk_shapes(&self) -> Vec<ChunkShape>;
+
+    /// Return the indices of the inner chunks within the shard at `shard_indices`.
+    ///
+    /// Iterate over the inner chunks of a shard with [`ArraySubset::indices`] to align access patterns with the shard layout, e.g. with [`ArrayShardedReadableExt::retrieve_inner_chunk_opt`](crate::array::ArrayShardedReadableExt::retrieve_inner_chunk_opt).
+    /// Inner chunks entirely outside the array shape are excluded.
+    /// Returns the chunk at `shard_indices` for an unsharded array.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidChunkGridIndicesError`] if `shard_indices` are incompatible with the chunk grid.
+    fn inner_chunks_in_shard(&self, shard_indices: &[u64]) -> Result<ArraySubset, ArrayError>;
 }
 
 impl<TStorage: ?Sized> ArrayShardedExt for Array<TStorage> {
@@ -50,4 +78,142 @@ impl<TStorage: ?Sized> ArrayShardedExt for Array<TStorage> {
             self.chunk_grid().clone()
         }
     }
+
+    fn inner_chunk_grid_shape(&self) -> Option<ArrayShape> {
+        self.inner_chunk_grid()
+            .grid_shape(self.shape())
+            .ok()
+            .flatten()
+    }
+
+    fn inner_chunk_shapes(&self) -> Vec<ChunkShape> {
+        let mut inner_chunk_shapes = Vec::new();
+        let mut codec_metadata = self.codecs.array_to_bytes_codec().create_metadata();
+        while let Some(metadata) = codec_metadata.take() {
+            if metadata.name() != IDENTIFIER {
+                break;
+            }
+            if let Ok(ShardingCodecConfiguration::V1(sharding_configuration)) =
+                metadata.to_configuration()
+            {
+                inner_chunk_shapes.push(sharding_configuration.chunk_shape);
+                codec_metadata = sharding_configuration
+                    .codecs
+                    .into_iter()
+                    .find(|metadata| metadata.name() == IDENTIFIER);
+            }
+        }
+        inner_chunk_shapes
+    }
+
+    fn inner_chunks_in_shard(&self, shard_indices: &[u64]) -> Result<ArraySubset, ArrayError> {
+        let invalid = || ArrayError::InvalidChunkGridIndicesError(shard_indices.to_vec());
+        let in_grid = self.chunk_grid_shape().is_some_and(|grid_shape| {
+            grid_shape.len() == shard_indices.len()
+                && std::iter::zip(shard_indices, &grid_shape).all(|(index, size)| index < size)
+        });
+        if !in_grid {
+            return Err(invalid());
+        }
+        let shard_subset = self.chunk_subset_bounded(shard_indices)?;
+        let inner_chunk_grid = self.inner_chunk_grid();
+        let Some(end) = shard_subset.end_inc() else {
+            return Ok(ArraySubset::new_empty(self.dimensionality()));
+        };
+        let start = inner_chunk_grid
+            .chunk_indices(shard_subset.start(), self.shape())
+            .map_err(|_| invalid())?
+            .ok_or_else(invalid)?;
+        let end = inner_chunk_grid
+            .chunk_indices(&end, self.shape())
+            .map_err(|_| invalid())?
+            .ok_or_else(invalid)?;
+        ArraySubset::new_with_start_end_inc(start, end).map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{
+            codec::{array_to_bytes::sharding::ShardingCodecBuilder, CodecOptions},
+            ArrayBuilder, ArrayShardedReadableExt, ArrayShardedReadableExtCache, DataType,
+            FillValue,
+        },
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_sharded_ext_nested() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![10, 8],
+            DataType::UInt16,
+            vec![8, 8].try_into()?,
+            FillValue::from(0u16),
+        )
+        .array_to_bytes_codec(Box::new(
+            ShardingCodecBuilder::new(vec![4, 4].try_into()?)
+                .array_to_bytes_codec(Box::new(
+                    ShardingCodecBuilder::new(vec![2, 2].try_into()?).build(),
+                ))
+                .build(),
+        ))
+        .build(store, "/array")?;
+        let data: Vec<u16> = (0..80).collect();
+        array.store_array_subset_elements(&ArraySubset::new_with_shape(vec![10, 8]), data)?;
+
+        assert_eq!(
+            array.inner_chunk_shapes(),
+            vec![vec![4, 4].try_into()?, vec![2, 2].try_into()?]
+        );
+        assert_eq!(array.inner_chunk_grid_shape(), Some(vec![3, 2]));
+        assert_eq!(
+            array.inner_chunks_in_shard(&[0, 0])?,
+            ArraySubset::new_with_ranges(&[0..2, 0..2])
+        );
+        let inner_chunks = array.inner_chunks_in_shard(&[1, 0])?;
+        assert_eq!(inner_chunks, ArraySubset::new_with_ranges(&[2..3, 0..2]));
+        assert!(array.inner_chunks_in_shard(&[2, 0]).is_err());
+
+        let cache = ArrayShardedReadableExtCache::new(&array);
+        for inner_chunk_indices in &inner_chunks.indices() {
+            let subset = array
+                .inner_chunk_grid()
+                .subset(&inner_chunk_indices, array.shape())?
+                .unwrap();
+            assert_eq!(
+                array.retrieve_inner_chunk_elements_opt::<u16>(
+                    &cache,
+                    &inner_chunk_indices,
+                    &CodecOptions::default()
+                )?,
+                array.retrieve_array_subset_elements::<u16>(&subset)?
+            );
+        }
+        assert_eq!(cache.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn array_sharded_ext_unsharded() -> Result<(), Box<dyn std::error::Error>> {
+        let array = ArrayBuilder::new(
+            vec![10, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into()?,
+            FillValue::from(0u16),
+        )
+        .build(Arc::new(MemoryStore::default()), "/array")?;
+        assert!(array.inner_chunk_shapes().is_empty());
+        assert_eq!(array.inner_chunk_grid_shape(), Some(vec![3, 2]));
+        assert_eq!(
+            array.inner_chunks_in_shard(&[2, 1])?,
+            ArraySubset::new_with_ranges(&[2..3, 1..2])
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,283 @@
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{Array, ArrayError, ArrayShape};
+
+#[cfg(feature = "ndarray")]
+use super::elements_to_ndarray;
+
+/// A lightweight read-only handle to a subset of an [`Array`].
+///
+/// A view has its own shape, and array subsets passed to its retrieval methods are relative to the start of the view.
+/// Axes of length one can be removed from the view with [`squeeze`](ArraySubsetView::squeeze) or [`squeeze_axes`](ArraySubsetView::squeeze_axes).
+/// Create a view with [`Array::view`].
+#[derive(Debug)]
+pub struct ArraySubsetView<'a, TStorage: ?Sized> {
+    array: &'a Array<TStorage>,
+    subset: ArraySubset,
+    /// Whether each axis of `subset` is squeezed out of the view.
+    squeezed: Vec<bool>,
+}
+
+impl<TStorage: ?Sized> Clone for ArraySubsetView<'_, TStorage> {
+    fn clone(&self) -> Self {
+        Self {
+            array: self.array,
+            subset: self.subset.clone(),
+            squeezed: self.squeezed.clone(),
+        }
+    }
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return a view of the `array_subset` of the array.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidArraySubset`] if `array_subset` is incompatible with the array.
+    pub fn view(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ArraySubsetView<'_, TStorage>, ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(self.shape())
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        Ok(ArraySubsetView {
+            array: self,
+            subset: array_subset.clone(),
+            squeezed: vec![false; array_subset.dimensionality()],
+        })
+    }
+}
+
+impl<'a, TStorage: ?Sized> ArraySubsetView<'a, TStorage> {
+    /// Return the underlying array.
+    #[must_use]
+    pub const fn array(&self) -> &'a Array<TStorage> {
+        self.array
+    }
+
+    /// Return the subset of the underlying array covered by the view.
+    #[must_use]
+    pub const fn subset(&self) -> &ArraySubset {
+        &self.subset
+    }
+
+    /// Return the shape of the view.
+    ///
+    /// Squeezed axes are excluded.
+    #[must_use]
+    pub fn shape(&self) -> ArrayShape {
+        std::iter::zip(self.subset.shape(), &self.squeezed)
+            .filter_map(|(size, squeezed)| (!squeezed).then_some(*size))
+            .collect()
+    }
+
+    /// Return the dimensionality of the view.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.squeezed.iter().filter(|squeezed| !**squeezed).count()
+    }
+
+    /// Remove all axes of length one from the view.
+    #[must_use]
+    pub fn squeeze(mut self) -> Self {
+        for (squeezed, size) in std::iter::zip(&mut self.squeezed, self.subset.shape()) {
+            *squeezed |= *size == 1;
+        }
+        self
+    }
+
+    /// Remove `axes` of the view, which must have length one.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidSqueezeAxis`] if an axis is out of bounds or does not have length one.
+    pub fn squeeze_axes(mut self, axes: &[usize]) -> Result<Self, ArrayError> {
+        let view_axes = self.view_axes();
+        for axis in axes {
+            match view_axes.get(*axis) {
+                Some(array_axis) if self.subset.shape()[*array_axis] == 1 => {
+                    self.squeezed[*array_axis] = true;
+                }
+                _ => return Err(ArrayError::InvalidSqueezeAxis(*axis, self.shape())),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Return a view of the `array_subset` of this view.
+    ///
+    /// Squeezed axes remain squeezed.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidArraySubset`] if `array_subset` is incompatible with the view.
+    pub fn view(&self, array_subset: &ArraySubset) -> Result<Self, ArrayError> {
+        Ok(Self {
+            array: self.array,
+            subset: self.array_subset(array_subset)?,
+            squeezed: self.squeezed.clone(),
+        })
+    }
+
+    /// Return the axes of the underlying array corresponding to the axes of the view.
+    fn view_axes(&self) -> Vec<usize> {
+        self.squeezed
+            .iter()
+            .enumerate()
+            .filter_map(|(axis, squeezed)| (!squeezed).then_some(axis))
+            .collect()
+    }
+
+    /// Map `array_subset` relative to the view to a subset of the underlying array.
+    fn array_subset(&self, array_subset: &ArraySubset) -> Result<ArraySubset, ArrayError> {
+        let shape = self.shape();
+        if array_subset.dimensionality() != shape.len() || !array_subset.inbounds(&shape) {
+            return Err(ArrayError::InvalidArraySubset(array_subset.clone(), shape));
+        }
+        let mut start = self.subset.start().to_vec();
+        let mut subset_shape = vec![1; self.subset.dimensionality()];
+        for (view_axis, axis) in self.view_axes().into_iter().enumerate() {
+            start[axis] += array_subset.start()[view_axis];
+            subset_shape[axis] = array_subset.shape()[view_axis];
+        }
+        Ok(unsafe { ArraySubset::new_with_start_shape_unchecked(start, subset_shape) })
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> ArraySubsetView<'_, TStorage> {
+    /// Read and decode the `array_subset` of the view into its bytes.
+    ///
+    /// `array_subset` is relative to the start of the view and has the dimensionality of the view.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the view or an [`Array::retrieve_array_subset`] error condition is met.
+    pub fn retrieve_array_subset(&self, array_subset: &ArraySubset) -> Result<Vec<u8>, ArrayError> {
+        self.array
+            .retrieve_array_subset(&self.array_subset(array_subset)?)
+    }
+
+    /// Read and decode the `array_subset` of the view into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the view or an [`Array::retrieve_array_subset_elements`] error condition is met.
+    pub fn retrieve_array_subset_elements<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.array
+            .retrieve_array_subset_elements(&self.array_subset(array_subset)?)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `array_subset` of the view into an [`ndarray::ArrayD`].
+    ///
+    /// The shape of the returned array excludes squeezed axes.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the view or an [`Array::retrieve_array_subset_elements`] error condition is met.
+    pub fn retrieve_array_subset_ndarray<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements = self.retrieve_array_subset_elements(array_subset)?;
+        elements_to_ndarray(array_subset.shape(), elements)
+    }
+
+    /// Read and decode the entire view into its bytes.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if an [`Array::retrieve_array_subset`] error condition is met.
+    pub fn retrieve(&self) -> Result<Vec<u8>, ArrayError> {
+        self.array.retrieve_array_subset(&self.subset)
+    }
+
+    /// Read and decode the entire view into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if an [`Array::retrieve_array_subset_elements`] error condition is met.
+    pub fn retrieve_elements<T: bytemuck::Pod>(&self) -> Result<Vec<T>, ArrayError> {
+        self.array.retrieve_array_subset_elements(&self.subset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn array_subset_view() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![3, 4, 5],
+            DataType::UInt8,
+            vec![2, 2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(vec![3, 4, 5]),
+                (0..60).collect(),
+            )
+            .unwrap();
+
+        let view = array
+            .view(&ArraySubset::new_with_ranges(&[1..2, 1..4, 2..5]))
+            .unwrap();
+        assert_eq!(view.shape(), vec![1, 3, 3]);
+        assert_eq!(
+            view.retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[
+                0..1,
+                1..3,
+                0..2
+            ]))
+            .unwrap(),
+            vec![32, 33, 37, 38]
+        );
+        assert!(view
+            .retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..1, 0..4, 0..1]))
+            .is_err());
+
+        let squeezed = view.clone().squeeze();
+        assert_eq!(squeezed.shape(), vec![3, 3]);
+        assert_eq!(squeezed.dimensionality(), 2);
+        assert_eq!(
+            squeezed
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[2..3, 0..3]))
+                .unwrap(),
+            vec![37, 38, 39]
+        );
+        assert_eq!(
+            view.clone().squeeze_axes(&[0]).unwrap().shape(),
+            squeezed.shape()
+        );
+        assert!(view.clone().squeeze_axes(&[1]).is_err());
+        assert!(view.clone().squeeze_axes(&[3]).is_err());
+
+        let nested = squeezed
+            .view(&ArraySubset::new_with_ranges(&[1..3, 2..3]))
+            .unwrap()
+            .squeeze();
+        assert_eq!(nested.shape(), vec![2]);
+        assert_eq!(
+            nested.subset(),
+            &ArraySubset::new_with_ranges(&[1..2, 2..4, 4..5])
+        );
+        assert_eq!(nested.retrieve_elements::<u8>().unwrap(), vec![34, 39]);
+
+        assert!(array
+            .view(&ArraySubset::new_with_ranges(&[0..4, 0..4, 0..5]))
+            .is_err());
+    }
+}
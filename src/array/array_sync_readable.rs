@@ -1,28 +1,45 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{mpsc::sync_channel, Arc, Mutex},
+};
 
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 use rayon_iter_concurrent_limit::iter_concurrent_limit;
 
 use crate::{
-    array_subset::ArraySubset,
+    array_subset::{ArraySubset, StridedArraySubset},
     node::NodePath,
-    storage::{data_key, meta_key, ReadableStorageTraits, StorageError, StorageHandle},
+    storage::{
+        data_key, meta_key, ListableStorageTraits, ReadableStorageTraits, StorageError,
+        StorageHandle, StoreKey,
+    },
 };
 
 use super::{
+    aligned_elements_vec, check_cancelled,
     codec::{
-        options::CodecOptions, ArrayCodecTraits, ArrayPartialDecoderTraits,
-        ArrayToBytesCodecTraits, CodecError, StoragePartialDecoder,
+        array_to_bytes::bytes::{supports_endianness_conversion, Endianness},
+        options::CodecOptions,
+        ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
+        BytesPartialDecoderCache, BytesPartialDecoderTraits, CodecError, StoragePartialDecoder,
     },
     concurrency::concurrency_chunks_and_codec,
+    fill_value_on_decode_error, is_decode_error, orthogonal_selection_offsets,
     transmute_from_bytes_vec,
     unsafe_cell_slice::UnsafeCellSlice,
-    validate_element_size, Array, ArrayCreateError, ArrayError, ArrayMetadata, ArrayView,
+    validate_element_size, Array, ArrayCreateError, ArrayError, ArrayIndices, ArrayMetadata,
+    ArrayView, ChunkDecodeError, ChunkProgressTracker, MaybeBytes, RawBytes,
 };
 
 #[cfg(feature = "ndarray")]
 use super::elements_to_ndarray;
 
+#[cfg(feature = "chrono")]
+use super::DataType;
+
+#[cfg(feature = "arrow")]
+use super::bytes_to_arrow_array;
+
 impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
     /// Create an array in `storage` at `path`. The metadata is read from the store.
     ///
@@ -123,6 +140,24 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_chunk_elements_opt(chunk_indices, &CodecOptions::default())
     }
 
+    /// Read and decode the chunk at `chunk_indices` directly into a vector of its elements or the fill value if it does not exist.
+    ///
+    /// Unlike [`retrieve_chunk_elements`](Array::retrieve_chunk_elements), the chunk is decoded into an allocation aligned for `T`.
+    /// This avoids the copy in [`transmute_from_bytes_vec`](crate::array::transmute_from_bytes_vec) for elements with an alignment greater than 1 (e.g. `f32`).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size,
+    ///  - `chunk_indices` are invalid,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_chunk_elements_aligned<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_chunk_elements_aligned_opt(chunk_indices, &CodecOptions::default())
+    }
+
     #[cfg(feature = "ndarray")]
     /// Read and decode the chunk at `chunk_indices` into an [`ndarray::ArrayD`]. It is filled with the fill value if it does not exist.
     ///
@@ -143,6 +178,22 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_chunk_ndarray_opt(chunk_indices, &CodecOptions::default())
     }
 
+    #[cfg(feature = "arrow")]
+    /// Read and decode the chunk at `chunk_indices` into an Arrow array of its elements in C order. It is filled with the fill value if it does not exist.
+    ///
+    /// See [`arrow_data_type`](crate::array::arrow_data_type) for the Arrow data type of each data type.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the data type has no Arrow representation, or
+    ///  - a [`retrieve_chunk`](Array::retrieve_chunk) error condition is met.
+    pub fn retrieve_chunk_arrow(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<arrow_array::ArrayRef, ArrayError> {
+        bytes_to_arrow_array(self.retrieve_chunk(chunk_indices)?, self.data_type())
+    }
+
     /// Retrieve a chunk and output into an existing array.
     ///
     /// # Errors
@@ -337,6 +388,58 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_array_subset_elements_opt(array_subset, &CodecOptions::default())
     }
 
+    #[cfg(feature = "chrono")]
+    /// Read and decode the `array_subset` of a `numpy.datetime64` array into a vector of [`chrono::DateTime`].
+    ///
+    /// Elements that are "not a time" ([`NAT`](crate::array::data_type::NAT)) or out of the range of [`chrono::DateTime`] are [`None`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the data type is not `numpy.datetime64`, or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_datetimes(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<Option<chrono::DateTime<chrono::Utc>>>, ArrayError> {
+        let DataType::DateTime64(unit) = *self.data_type() else {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "chrono::DateTime",
+            ));
+        };
+        let elements = self.retrieve_array_subset_elements::<i64>(array_subset)?;
+        Ok(elements
+            .into_iter()
+            .map(|value| unit.to_datetime(value))
+            .collect())
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Read and decode the `array_subset` of a `numpy.timedelta64` array into a vector of [`chrono::TimeDelta`].
+    ///
+    /// Elements that are "not a time" ([`NAT`](crate::array::data_type::NAT)) or out of the range of [`chrono::TimeDelta`] are [`None`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the data type is not `numpy.timedelta64`, or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_timedeltas(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<Option<chrono::TimeDelta>>, ArrayError> {
+        let DataType::TimeDelta64(unit) = *self.data_type() else {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "chrono::TimeDelta",
+            ));
+        };
+        let elements = self.retrieve_array_subset_elements::<i64>(array_subset)?;
+        Ok(elements
+            .into_iter()
+            .map(|value| unit.to_timedelta(value))
+            .collect())
+    }
+
     #[cfg(feature = "ndarray")]
     /// Read and decode the `array_subset` of array into an [`ndarray::ArrayD`].
     ///
@@ -355,6 +458,22 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         self.retrieve_array_subset_ndarray_opt(array_subset, &CodecOptions::default())
     }
 
+    #[cfg(feature = "arrow")]
+    /// Read and decode the `array_subset` of array into an Arrow array of its elements in C order.
+    ///
+    /// See [`arrow_data_type`](crate::array::arrow_data_type) for the Arrow data type of each data type.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the data type has no Arrow representation, or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_arrow(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<arrow_array::ArrayRef, ArrayError> {
+        bytes_to_arrow_array(self.retrieve_array_subset(array_subset)?, self.data_type())
+    }
+
     /// Retrieve an array subset into an array view.
     ///
     /// # Errors
@@ -375,208 +494,603 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         )
     }
 
-    /// Initialises a partial decoder for the chunk at `chunk_indices`.
+    /// Read and decode multiple `array_subsets` of array into their bytes.
+    ///
+    /// A single partial decoder is initialised for each chunk intersecting any of the array subsets, and all regions of that chunk are decoded together.
+    /// This avoids repeatedly decoding shard indexes or bytes to bytes codecs when several subsets touch the same chunk, such as disjoint regions of a shard.
     ///
     /// # Errors
-    /// Returns an [`ArrayError`] if initialisation of the partial decoder fails.
-    pub fn partial_decoder<'a>(
-        &'a self,
-        chunk_indices: &[u64],
-    ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, ArrayError> {
-        self.partial_decoder_opt(chunk_indices, &CodecOptions::default())
+    /// Returns an [`ArrayError`] if a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met for any array subset.
+    pub fn retrieve_array_subsets(
+        &self,
+        array_subsets: &[ArraySubset],
+    ) -> Result<Vec<Vec<u8>>, ArrayError> {
+        self.retrieve_array_subsets_opt(array_subsets, &CodecOptions::default())
     }
 
-    /////////////////////////////////////////////////////////////////////////////
-    // Advanced methods
-    /////////////////////////////////////////////////////////////////////////////
-
-    /// Explicit options version of [`retrieve_chunk_if_exists`](Array::retrieve_chunk_if_exists).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_if_exists_opt(
+    /// Read and decode the elements at `array_indices` into a vector of elements in the same order.
+    ///
+    /// The elements are grouped by chunk, and a single partial decoder is initialised for each chunk containing any of the elements.
+    /// This is suited to point sampling, such as extracting the values at a set of station locations.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size,
+    ///  - any array indices do not match the array dimensionality or are out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_elements_at<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
-    ) -> Result<Option<Vec<u8>>, ArrayError> {
-        if chunk_indices.len() != self.dimensionality() {
-            return Err(ArrayError::InvalidChunkGridIndicesError(
-                chunk_indices.to_vec(),
-            ));
-        }
-        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-        let storage_transformer = self
-            .storage_transformers()
-            .create_readable_transformer(storage_handle);
-        let chunk_encoded = crate::storage::retrieve_chunk(
-            &*storage_transformer,
-            self.path(),
-            chunk_indices,
-            self.chunk_key_encoding(),
-        )
-        .map_err(ArrayError::StorageError)?;
-        if let Some(chunk_encoded) = chunk_encoded {
-            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-            let chunk_decoded = self
-                .codecs()
-                .decode(chunk_encoded, &chunk_representation, options)
-                .map_err(ArrayError::CodecError)?;
-            let chunk_decoded_size =
-                chunk_representation.num_elements_usize() * chunk_representation.data_type().size();
-            if chunk_decoded.len() == chunk_decoded_size {
-                Ok(Some(chunk_decoded))
-            } else {
-                Err(ArrayError::UnexpectedChunkDecodedSize(
-                    chunk_decoded.len(),
-                    chunk_decoded_size,
-                ))
-            }
-        } else {
-            Ok(None)
-        }
+        array_indices: &[TIndices],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_elements_at_opt(array_indices, &CodecOptions::default())
     }
 
-    /// Explicit options version of [`retrieve_chunk`](Array::retrieve_chunk).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_opt(
+    /// Read and decode the elements of `array_subset` selected by `mask` into their bytes.
+    ///
+    /// `mask` has an entry for each element of `array_subset` in C order, and the selected elements are returned packed in the same order.
+    /// Only chunks containing a selected element are decoded.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the `array_subset` dimensionality does not match the chunk grid dimensionality,
+    ///  - the `array_subset` is out of bounds of the array,
+    ///  - the length of `mask` does not match the number of elements in `array_subset`,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_masked(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
+        array_subset: &ArraySubset,
+        mask: &[bool],
     ) -> Result<Vec<u8>, ArrayError> {
-        let chunk = self.retrieve_chunk_if_exists_opt(chunk_indices, options)?;
-        if let Some(chunk) = chunk {
-            Ok(chunk)
-        } else {
-            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-            let fill_value = chunk_representation.fill_value().as_ne_bytes();
-            Ok(fill_value.repeat(chunk_representation.num_elements_usize()))
-        }
+        self.retrieve_array_subset_masked_opt(array_subset, mask, &CodecOptions::default())
     }
 
-    /// Explicit options version of [`retrieve_chunk_elements_if_exists`](Array::retrieve_chunk_elements_if_exists).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_elements_if_exists_opt<T: bytemuck::Pod>(
+    /// Read and decode the elements of `array_subset` selected by `mask` into a vector of elements.
+    ///
+    /// See [`retrieve_array_subset_masked`](Array::retrieve_array_subset_masked).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_masked`](Array::retrieve_array_subset_masked) error condition is met.
+    pub fn retrieve_array_subset_masked_elements<T: bytemuck::Pod>(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
-    ) -> Result<Option<Vec<T>>, ArrayError> {
-        validate_element_size::<T>(self.data_type())?;
-        let bytes = self.retrieve_chunk_if_exists_opt(chunk_indices, options)?;
-        Ok(bytes.map(|bytes| transmute_from_bytes_vec::<T>(bytes)))
+        array_subset: &ArraySubset,
+        mask: &[bool],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_masked_elements_opt(array_subset, mask, &CodecOptions::default())
     }
 
-    /// Explicit options version of [`retrieve_chunk_elements`](Array::retrieve_chunk_elements).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_elements_opt<T: bytemuck::Pod>(
+    /// Read and decode the orthogonal selection `dimension_indices` of array into its bytes.
+    ///
+    /// `dimension_indices` holds an independent list of indices along each dimension, and the selection is their outer product (like `oindex` in `zarr-python`).
+    /// The output has the shape of the lengths of the index lists, with elements in C order.
+    /// Indices need not be sorted or unique, and a range can be selected with e.g. `(start..end).collect::<Vec<u64>>()`.
+    /// Only the region of each chunk bounding its selected elements is decoded, and chunks without a selected element are not read.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the length of `dimension_indices` does not match the array dimensionality,
+    ///  - an index is out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_orthogonal<TIndices: AsRef<[u64]>>(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
-    ) -> Result<Vec<T>, ArrayError> {
-        validate_element_size::<T>(self.data_type())?;
-        let bytes = self.retrieve_chunk_opt(chunk_indices, options)?;
-        Ok(transmute_from_bytes_vec::<T>(bytes))
+        dimension_indices: &[TIndices],
+    ) -> Result<Vec<u8>, ArrayError> {
+        self.retrieve_orthogonal_opt(dimension_indices, &CodecOptions::default())
     }
 
-    #[cfg(feature = "ndarray")]
-    /// Explicit options version of [`retrieve_chunk_ndarray_if_exists`](Array::retrieve_chunk_ndarray_if_exists).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_ndarray_if_exists_opt<T: bytemuck::Pod>(
+    /// Read and decode the orthogonal selection `dimension_indices` of array into a vector of its elements.
+    ///
+    /// See [`retrieve_orthogonal`](Array::retrieve_orthogonal).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_orthogonal`](Array::retrieve_orthogonal) error condition is met.
+    pub fn retrieve_orthogonal_elements<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
-    ) -> Result<Option<ndarray::ArrayD<T>>, ArrayError> {
-        // validate_element_size::<T>(self.data_type())?; // in retrieve_chunk_elements_if_exists
-        let shape = self
-            .chunk_grid()
-            .chunk_shape_u64(chunk_indices, self.shape())?
-            .ok_or_else(|| ArrayError::InvalidChunkGridIndicesError(chunk_indices.to_vec()))?;
-        let elements = self.retrieve_chunk_elements_if_exists_opt::<T>(chunk_indices, options)?;
-        if let Some(elements) = elements {
-            Ok(Some(elements_to_ndarray(&shape, elements)?))
-        } else {
-            Ok(None)
-        }
+        dimension_indices: &[TIndices],
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_orthogonal_elements_opt(dimension_indices, &CodecOptions::default())
     }
 
     #[cfg(feature = "ndarray")]
-    /// Explicit options version of [`retrieve_chunk_ndarray`](Array::retrieve_chunk_ndarray).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_ndarray_opt<T: bytemuck::Pod>(
+    /// Read and decode the orthogonal selection `dimension_indices` of array into an [`ndarray::ArrayD`].
+    ///
+    /// See [`retrieve_orthogonal`](Array::retrieve_orthogonal).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_orthogonal`](Array::retrieve_orthogonal) error condition is met.
+    pub fn retrieve_orthogonal_ndarray<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
         &self,
-        chunk_indices: &[u64],
-        options: &CodecOptions,
+        dimension_indices: &[TIndices],
     ) -> Result<ndarray::ArrayD<T>, ArrayError> {
-        // validate_element_size::<T>(self.data_type())?; // in retrieve_chunk_elements
-        let shape = self
-            .chunk_grid()
-            .chunk_shape_u64(chunk_indices, self.shape())?
-            .ok_or_else(|| ArrayError::InvalidChunkGridIndicesError(chunk_indices.to_vec()))?;
-        elements_to_ndarray(
-            &shape,
-            self.retrieve_chunk_elements_opt::<T>(chunk_indices, options)?,
-        )
+        self.retrieve_orthogonal_ndarray_opt(dimension_indices, &CodecOptions::default())
     }
 
-    /// Explicit options version of [`retrieve_chunk_into_array_view`](Array::retrieve_chunk_into_array_view).
-    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
-    pub fn retrieve_chunk_into_array_view_opt(
+    #[cfg(feature = "transpose")]
+    /// Read and decode the `array_subset` of array into its bytes in F (column-major) order.
+    ///
+    /// If the first codec of the array is a `transpose` to F order, chunks are decoded without that codec and are not transposed back to C (row-major) order.
+    /// Otherwise, the array subset is retrieved in C order and converted.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_fortran(
         &self,
-        chunk_indices: &[u64],
-        array_view: &ArrayView,
-        options: &CodecOptions,
-    ) -> Result<(), ArrayError> {
-        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-        let chunk_shape_u64 = chunk_representation.shape_u64();
-        if chunk_shape_u64 != array_view.subset().shape() {
-            return Err(ArrayError::InvalidArraySubset(
-                array_view.subset().clone(),
-                chunk_shape_u64,
-            ));
-        }
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<u8>, ArrayError> {
+        self.retrieve_array_subset_fortran_opt(array_subset, &CodecOptions::default())
+    }
 
-        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-        let storage_transformer = self
-            .storage_transformers()
-            .create_readable_transformer(storage_handle);
-        let chunk_encoded = crate::storage::retrieve_chunk(
-            &*storage_transformer,
-            self.path(),
-            chunk_indices,
-            self.chunk_key_encoding(),
-        )
-        .map_err(ArrayError::StorageError)?;
-        if let Some(chunk_encoded) = chunk_encoded {
-            self.codecs()
-                .decode_into_array_view(&chunk_encoded, &chunk_representation, array_view, options)
-                .map_err(ArrayError::CodecError)
-        } else {
-            // fill array_view with fill value
-            let contiguous_indices = unsafe {
-                array_view
-                    .subset()
-                    .contiguous_linearised_indices_unchecked(array_view.array_shape())
-            };
-            let element_size = chunk_representation.element_size();
-            let length = contiguous_indices.contiguous_elements_usize() * element_size;
-            let fill = self
-                .fill_value()
-                .as_ne_bytes()
-                .repeat(contiguous_indices.contiguous_elements_usize());
-            // FIXME: Par iteration?
-            let output = unsafe { array_view.bytes_mut() };
-            for (array_subset_element_index, _num_elements) in &contiguous_indices {
-                let output_offset =
-                    usize::try_from(array_subset_element_index).unwrap() * element_size;
-                debug_assert!((output_offset + length) <= output.len());
-                output[output_offset..output_offset + length].copy_from_slice(&fill);
-            }
-            Ok(())
-        }
+    #[cfg(feature = "transpose")]
+    /// Read and decode the `array_subset` of array into a vector of its elements in F (column-major) order.
+    ///
+    /// See [`retrieve_array_subset_fortran`](Array::retrieve_array_subset_fortran).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_fortran`](Array::retrieve_array_subset_fortran) error condition is met.
+    pub fn retrieve_array_subset_elements_fortran<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_elements_fortran_opt(array_subset, &CodecOptions::default())
     }
 
-    /// Explicit options version of [`retrieve_chunk_subset_into_array_view`](Array::retrieve_chunk_subset_into_array_view).
-    #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunk_subset_into_array_view_opt(
+    #[cfg(all(feature = "ndarray", feature = "transpose"))]
+    /// Read and decode the `array_subset` of array into an [`ndarray::ArrayD`] with F (column-major) memory layout.
+    ///
+    /// The returned array is indexed like that of [`retrieve_array_subset_ndarray`](Array::retrieve_array_subset_ndarray), but its elements are stored in F order.
+    /// See [`retrieve_array_subset_fortran`](Array::retrieve_array_subset_fortran).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_fortran`](Array::retrieve_array_subset_fortran) error condition is met.
+    pub fn retrieve_array_subset_ndarray_fortran<T: bytemuck::Pod>(
         &self,
-        chunk_indices: &[u64],
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.retrieve_array_subset_ndarray_fortran_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_subset` of array into its bytes, substituting the fill value for chunks that cannot be decoded.
+    ///
+    /// Unlike [`retrieve_array_subset`](Array::retrieve_array_subset), a corrupt or otherwise undecodable chunk does not fail the request.
+    /// The elements of that chunk are set to the fill value and a [`ChunkDecodeError`] is returned for it, ordered by chunk indices.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the `array_subset` dimensionality does not match the chunk grid dimensionality,
+    ///  - an array subset is invalid or out of bounds of the array, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_lenient(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<(Vec<u8>, Vec<ChunkDecodeError>), ArrayError> {
+        self.retrieve_array_subset_lenient_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_subset` of array into a vector of its elements, substituting the fill value for chunks that cannot be decoded.
+    ///
+    /// See [`retrieve_array_subset_lenient`](Array::retrieve_array_subset_lenient).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_lenient`](Array::retrieve_array_subset_lenient) error condition is met.
+    pub fn retrieve_array_subset_elements_lenient<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<(Vec<T>, Vec<ChunkDecodeError>), ArrayError> {
+        self.retrieve_array_subset_elements_lenient_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Read and decode the `strided_array_subset` of array into its bytes.
+    ///
+    /// Only the chunks containing an element of `strided_array_subset` are retrieved and decoded, and each is partially decoded where supported by the codecs.
+    /// This is useful for downsampling reads (e.g. every `n`th element along each dimension).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the `strided_array_subset` dimensionality does not match the chunk grid dimensionality,
+    ///  - the `strided_array_subset` is out of bounds of the array,
+    ///  - there is a codec decoding error, or
+    ///  - an underlying store error.
+    pub fn retrieve_array_subset_strided(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+    ) -> Result<Vec<u8>, ArrayError> {
+        self.retrieve_array_subset_strided_opt(strided_array_subset, &CodecOptions::default())
+    }
+
+    /// Read and decode the `strided_array_subset` of array into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_strided`](Array::retrieve_array_subset_strided) error condition is met.
+    pub fn retrieve_array_subset_strided_elements<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        self.retrieve_array_subset_strided_elements_opt(
+            strided_array_subset,
+            &CodecOptions::default(),
+        )
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `strided_array_subset` of array into an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a [`retrieve_array_subset_strided_elements`](Array::retrieve_array_subset_strided_elements) error condition is met.
+    pub fn retrieve_array_subset_strided_ndarray<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        self.retrieve_array_subset_strided_ndarray_opt(
+            strided_array_subset,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Read and decode the `array_subset` of array into its bytes with elements in `endianness` byte order.
+    ///
+    /// Decoded bytes are always in the native byte order of the CPU, regardless of the endianness of the `bytes` codec.
+    /// This converts them to `endianness`, e.g. for writing to a big endian file format.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the endianness of the data type cannot be converted (e.g. a multi-byte extension data type), or
+    ///  - a [`retrieve_array_subset`](Array::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_endian(
+        &self,
+        array_subset: &ArraySubset,
+        endianness: Endianness,
+    ) -> Result<Vec<u8>, ArrayError> {
+        self.retrieve_array_subset_endian_opt(array_subset, endianness, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_subset` of array into the preallocated `bytes`.
+    ///
+    /// Chunks are decoded directly into `bytes`, so repeated reads of equally sized subsets do not allocate an output buffer per call.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the length of `bytes` does not match the size of `array_subset` in bytes, or
+    ///  - a [`retrieve_array_subset_into_array_view`](Array::retrieve_array_subset_into_array_view) error condition is met.
+    pub fn retrieve_array_subset_into(
+        &self,
+        array_subset: &ArraySubset,
+        bytes: &mut [u8],
+    ) -> Result<(), ArrayError> {
+        self.retrieve_array_subset_into_opt(array_subset, bytes, &CodecOptions::default())
+    }
+
+    /// Read and decode the `array_subset` of array into the preallocated `elements`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`retrieve_array_subset_into`](Array::retrieve_array_subset_into) error condition is met.
+    pub fn retrieve_array_subset_elements_into<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        elements: &mut [T],
+    ) -> Result<(), ArrayError> {
+        self.retrieve_array_subset_elements_into_opt(
+            array_subset,
+            elements,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Returns true if the chunk at `chunk_indices` is stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` are invalid or there is an underlying store error.
+    pub fn chunk_exists(&self, chunk_indices: &[u64]) -> Result<bool, ArrayError> {
+        Ok(self.chunk_stored_size(chunk_indices)?.is_some())
+    }
+
+    /// Return the stored (encoded) size in bytes of the chunk at `chunk_indices`.
+    ///
+    /// Returns [`None`] if the chunk is not stored.
+    /// The chunk is retrieved if the store does not [support sizes](ReadableStorageTraits::supports_sizes).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` are invalid or there is an underlying store error.
+    pub fn chunk_stored_size(&self, chunk_indices: &[u64]) -> Result<Option<u64>, ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let key = self.chunk_key(chunk_indices);
+        if storage_transformer.supports_sizes() {
+            Ok(storage_transformer.size_key(&key)?)
+        } else {
+            Ok(storage_transformer
+                .get(&key)?
+                .map(|chunk| chunk.len() as u64))
+        }
+    }
+
+    /// Read the encoded chunk at `chunk_indices` without decoding it.
+    ///
+    /// Returns [`None`] if the chunk is not stored.
+    /// The bytes are exactly as stored (after storage transformers), so they can be copied verbatim to another array with identical codecs using [`store_encoded_chunk`](Array::store_encoded_chunk).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` are invalid or there is an underlying store error.
+    pub fn retrieve_encoded_chunk(&self, chunk_indices: &[u64]) -> Result<MaybeBytes, ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        crate::storage::retrieve_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+        )
+        .map_err(|err| self.chunk_error(chunk_indices, err))
+    }
+
+    /// Fetch the encoded chunks intersecting `array_subset` from the store without decoding or returning them.
+    ///
+    /// Chunks are read through the storage transformers of the array and discarded, which warms any cache along the way (e.g. a [`CacheStorageTransformer`](crate::storage::storage_transformer::CacheStorageTransformer)).
+    /// Interactive applications can call this on a background thread to hide storage latency for regions likely to be retrieved next, such as while a viewer is panning.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the dimensionality of `array_subset` does not match the array or there is an underlying store error.
+    pub fn prefetch_array_subset(&self, array_subset: &ArraySubset) -> Result<(), ArrayError> {
+        self.prefetch_array_subset_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Initialises a partial decoder for the chunk at `chunk_indices`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if initialisation of the partial decoder fails.
+    pub fn partial_decoder<'a>(
+        &'a self,
+        chunk_indices: &[u64],
+    ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, ArrayError> {
+        self.partial_decoder_opt(chunk_indices, &CodecOptions::default())
+    }
+
+    /////////////////////////////////////////////////////////////////////////////
+    // Advanced methods
+    /////////////////////////////////////////////////////////////////////////////
+
+    /// Explicit options version of [`retrieve_chunk_if_exists`](Array::retrieve_chunk_if_exists).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_if_exists_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<u8>>, ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        check_cancelled(options)?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let chunk_encoded = crate::storage::retrieve_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+        )
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        if let Some(chunk_encoded) = chunk_encoded {
+            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+            let chunk_decoded = match self.codecs().decode(
+                RawBytes::Borrowed(&chunk_encoded),
+                &chunk_representation,
+                options,
+            ) {
+                Ok(chunk_decoded) => chunk_decoded,
+                Err(err) if fill_value_on_decode_error(&err, options) => {
+                    return Ok(Some(
+                        self.fill_value()
+                            .as_ne_bytes()
+                            .repeat(chunk_representation.num_elements_usize()),
+                    ));
+                }
+                Err(err) => return Err(self.chunk_error(chunk_indices, err)),
+            };
+            let chunk_decoded_size =
+                chunk_representation.num_elements_usize() * chunk_representation.data_type().size();
+            if chunk_decoded.len() == chunk_decoded_size {
+                Ok(Some(chunk_decoded.into_owned()))
+            } else {
+                Err(ArrayError::UnexpectedChunkDecodedSize(
+                    chunk_decoded.len(),
+                    chunk_decoded_size,
+                ))
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Explicit options version of [`retrieve_chunk`](Array::retrieve_chunk).
+    #[allow(clippy::missing_errors_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+            )
+        )
+    )]
+    pub fn retrieve_chunk_opt(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        let chunk = self.retrieve_chunk_if_exists_opt(chunk_indices, options)?;
+        if let Some(chunk) = chunk {
+            Ok(chunk)
+        } else {
+            let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+            let fill_value = chunk_representation.fill_value().as_ne_bytes();
+            Ok(fill_value.repeat(chunk_representation.num_elements_usize()))
+        }
+    }
+
+    /// Explicit options version of [`retrieve_chunk_elements_if_exists`](Array::retrieve_chunk_elements_if_exists).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_elements_if_exists_opt<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Option<Vec<T>>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_chunk_if_exists_opt(chunk_indices, options)?;
+        Ok(bytes.map(|bytes| transmute_from_bytes_vec::<T>(bytes)))
+    }
+
+    /// Explicit options version of [`retrieve_chunk_elements`](Array::retrieve_chunk_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_elements_opt<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_chunk_opt(chunk_indices, options)?;
+        Ok(transmute_from_bytes_vec::<T>(bytes))
+    }
+
+    /// Explicit options version of [`retrieve_chunk_elements_aligned`](Array::retrieve_chunk_elements_aligned).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_elements_aligned_opt<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        let mut elements = aligned_elements_vec::<T>(&chunk_representation);
+        let shape = chunk_representation.shape_u64();
+        let array_view = ArrayView::new(
+            bytemuck::cast_slice_mut(&mut elements),
+            &shape,
+            ArraySubset::new_with_shape(shape.clone()),
+        )
+        .map_err(|err| CodecError::from(err.to_string()))?;
+        self.retrieve_chunk_into_array_view_opt(chunk_indices, &array_view, options)?;
+        Ok(elements)
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_chunk_ndarray_if_exists`](Array::retrieve_chunk_ndarray_if_exists).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_ndarray_if_exists_opt<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Option<ndarray::ArrayD<T>>, ArrayError> {
+        // validate_element_size::<T>(self.data_type())?; // in retrieve_chunk_elements_if_exists
+        let shape = self
+            .chunk_grid()
+            .chunk_shape_u64(chunk_indices, self.shape())?
+            .ok_or_else(|| ArrayError::InvalidChunkGridIndicesError(chunk_indices.to_vec()))?;
+        let elements = self.retrieve_chunk_elements_if_exists_opt::<T>(chunk_indices, options)?;
+        if let Some(elements) = elements {
+            Ok(Some(elements_to_ndarray(&shape, elements)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_chunk_ndarray`](Array::retrieve_chunk_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_ndarray_opt<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        // validate_element_size::<T>(self.data_type())?; // in retrieve_chunk_elements
+        let shape = self
+            .chunk_grid()
+            .chunk_shape_u64(chunk_indices, self.shape())?
+            .ok_or_else(|| ArrayError::InvalidChunkGridIndicesError(chunk_indices.to_vec()))?;
+        elements_to_ndarray(
+            &shape,
+            self.retrieve_chunk_elements_opt::<T>(chunk_indices, options)?,
+        )
+    }
+
+    /// Explicit options version of [`retrieve_chunk_into_array_view`](Array::retrieve_chunk_into_array_view).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_chunk_into_array_view_opt(
+        &self,
+        chunk_indices: &[u64],
+        array_view: &ArrayView,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        let chunk_shape_u64 = chunk_representation.shape_u64();
+        if chunk_shape_u64 != array_view.subset().shape() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_view.subset().clone(),
+                chunk_shape_u64,
+            ));
+        }
+
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let chunk_encoded = crate::storage::retrieve_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+        )
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        self.decode_chunk_into_array_view(
+            chunk_indices,
+            chunk_encoded.as_deref(),
+            &chunk_representation,
+            array_view,
+            options,
+        )
+    }
+
+    /// Explicit options version of [`retrieve_chunk_subset_into_array_view`](Array::retrieve_chunk_subset_into_array_view).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunk_subset_into_array_view_opt(
+        &self,
+        chunk_indices: &[u64],
         chunk_subset: &ArraySubset,
         array_view: &ArrayView,
         options: &CodecOptions,
@@ -592,19 +1106,26 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         if chunk_subset.shape() == chunk_representation.shape_u64() {
             self.retrieve_chunk_into_array_view_opt(chunk_indices, array_view, options)
         } else {
-            let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-            let storage_transformer = self
-                .storage_transformers()
-                .create_readable_transformer(storage_handle);
-            let input_handle = Box::new(StoragePartialDecoder::new(
-                storage_transformer,
-                data_key(self.path(), chunk_indices, self.chunk_key_encoding()),
-            ));
-
-            self.codecs()
-                .partial_decoder(input_handle, &chunk_representation, options)?
-                .partial_decode_into_array_view_opt(chunk_subset, array_view, options)
-                .map_err(ArrayError::CodecError)
+            check_cancelled(options)?;
+            match self
+                .chunk_input_handle(chunk_indices, options)
+                .and_then(|input_handle| {
+                    self.codecs()
+                        .partial_decoder(input_handle, &chunk_representation, options)
+                })
+                .and_then(|partial_decoder| {
+                    partial_decoder.partial_decode_into_array_view_opt(
+                        chunk_subset,
+                        array_view,
+                        options,
+                    )
+                }) {
+                Err(err) if fill_value_on_decode_error(&err, options) => {
+                    self.fill_array_view(array_view);
+                    Ok(())
+                }
+                result => result.map_err(|err| self.chunk_error(chunk_indices, err)),
+            }
         }
     }
 
@@ -626,11 +1147,14 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
 
         // Retrieve chunk bytes
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         match num_chunks {
             0 => Ok(vec![]),
             1 => {
                 let chunk_indices = chunks.start();
-                self.retrieve_chunk_opt(chunk_indices, options)
+                let chunk_bytes = self.retrieve_chunk_opt(chunk_indices, options)?;
+                progress.chunk_completed(chunk_bytes.len() as u64);
+                Ok(chunk_bytes)
             }
             _ => {
                 // Calculate chunk/codec concurrency
@@ -653,28 +1177,45 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
                     let output_slice =
                         UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
                     let indices = chunks.indices();
-                    let chunk0_subset = self.chunk_subset(chunks.start())?;
-                    rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                        chunk_concurrent_limit,
-                        indices,
-                        try_for_each,
-                        |chunk_indices: Vec<u64>| {
-                            let chunk_subset = self.chunk_subset(&chunk_indices)?;
-                            let array_view_subset = unsafe {
-                                chunk_subset.relative_to_unchecked(chunk0_subset.start())
-                            };
-                            self.retrieve_chunk_into_array_view_opt(
-                                &chunk_indices,
-                                &ArrayView::new(
-                                    unsafe { output_slice.get() },
-                                    array_subset.shape(),
-                                    array_view_subset,
-                                )
-                                .map_err(|err| CodecError::from(err.to_string()))?,
-                                &options,
+                    if options.chunk_prefetch() == 0 {
+                        let chunk0_subset = self.chunk_subset(chunks.start())?;
+                        options.install(|| {
+                            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                                chunk_concurrent_limit,
+                                indices,
+                                try_for_each,
+                                |chunk_indices: Vec<u64>| {
+                                    let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                                    let array_view_subset = unsafe {
+                                        chunk_subset.relative_to_unchecked(chunk0_subset.start())
+                                    };
+                                    self.retrieve_chunk_into_array_view_opt(
+                                        &chunk_indices,
+                                        &ArrayView::new(
+                                            unsafe { output_slice.get() },
+                                            array_subset.shape(),
+                                            array_view_subset,
+                                        )
+                                        .map_err(|err| CodecError::from(err.to_string()))?,
+                                        &options,
+                                    )?;
+                                    progress.chunk_completed(
+                                        chunk_subset.num_elements()
+                                            * self.data_type().size() as u64,
+                                    );
+                                    Ok::<_, ArrayError>(())
+                                }
                             )
-                        }
-                    )?;
+                        })?;
+                    } else {
+                        self.retrieve_chunks_prefetch(
+                            indices.into_iter().collect(),
+                            &array_subset,
+                            &output_slice,
+                            &progress,
+                            &options,
+                        )?;
+                    }
                 }
                 unsafe { output.set_len(size_output) };
                 Ok(output)
@@ -708,17 +1249,311 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         elements_to_ndarray(array_subset.shape(), elements)
     }
 
-    /// Explicit options version of [`retrieve_array_subset`](Array::retrieve_array_subset).
+    /// Explicit options version of [`retrieve_array_subset`](Array::retrieve_array_subset).
+    #[allow(clippy::missing_errors_doc, clippy::too_many_lines)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                array_subset = %array_subset,
+            )
+        )
+    )]
+    pub fn retrieve_array_subset_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+
+        // Find the chunks intersecting this array subset
+        let chunks = self.chunks_in_array_subset(array_subset)?;
+        let Some(chunks) = chunks else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        // Retrieve chunk bytes
+        let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
+        match num_chunks {
+            0 => Ok(self
+                .fill_value()
+                .as_ne_bytes()
+                .repeat(array_subset.num_elements_usize())),
+            1 => {
+                let chunk_indices = chunks.start();
+                let chunk_subset = self.chunk_subset(chunk_indices)?;
+                let subset_bytes = if &chunk_subset == array_subset {
+                    // Single chunk fast path if the array subset domain matches the chunk domain
+                    self.retrieve_chunk_opt(chunk_indices, options)?
+                } else {
+                    let array_subset_in_chunk_subset =
+                        unsafe { array_subset.relative_to_unchecked(chunk_subset.start()) };
+                    self.retrieve_chunk_subset_opt(
+                        chunk_indices,
+                        &array_subset_in_chunk_subset,
+                        options,
+                    )?
+                };
+                progress.chunk_completed(subset_bytes.len() as u64);
+                Ok(subset_bytes)
+            }
+            _ => {
+                // Allocate the output
+                let size_output = array_subset.num_elements_usize() * self.data_type().size();
+                let mut output = Vec::with_capacity(size_output);
+
+                // Calculate chunk/codec concurrency
+                let chunk_representation =
+                    self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+                let codec_concurrency =
+                    self.recommended_codec_concurrency(&chunk_representation)?;
+                let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+                    options.concurrent_target(),
+                    num_chunks,
+                    options,
+                    &codec_concurrency,
+                );
+
+                {
+                    let output = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
+                    let retrieve_chunk = |chunk_indices: Vec<u64>| {
+                        let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                        let chunk_subset_in_array_subset =
+                            unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                        let chunk_subset = unsafe {
+                            chunk_subset_in_array_subset.relative_to_unchecked(chunk_subset.start())
+                        };
+                        let array_view_subset = unsafe {
+                            chunk_subset_in_array_subset.relative_to_unchecked(array_subset.start())
+                        };
+                        let array_view = ArrayView::new(
+                            unsafe { output.get() },
+                            array_subset.shape(),
+                            array_view_subset,
+                        )
+                        .map_err(|err| CodecError::from(err.to_string()))?;
+                        self.retrieve_chunk_subset_into_array_view_opt(
+                            &chunk_indices,
+                            &chunk_subset,
+                            &array_view,
+                            &options,
+                        )?;
+                        progress.chunk_completed(
+                            chunk_subset.num_elements() * self.data_type().size() as u64,
+                        );
+                        Ok::<_, ArrayError>(())
+                    };
+                    let indices = chunks.indices();
+                    if options.chunk_prefetch() == 0 {
+                        options.install(|| {
+                            iter_concurrent_limit!(
+                                chunk_concurrent_limit,
+                                indices,
+                                try_for_each,
+                                retrieve_chunk
+                            )
+                        })?;
+                    } else {
+                        // Prefetch chunks entirely within the array subset, partially decode the rest
+                        let (chunks_whole, chunks_partial) =
+                            self.partition_chunks_in_array_subset(&indices, array_subset)?;
+                        self.retrieve_chunks_prefetch(
+                            chunks_whole,
+                            array_subset,
+                            &output,
+                            &progress,
+                            &options,
+                        )?;
+                        options.install(|| {
+                            iter_concurrent_limit!(
+                                chunk_concurrent_limit,
+                                chunks_partial,
+                                try_for_each,
+                                retrieve_chunk
+                            )
+                        })?;
+                    }
+                }
+                unsafe { output.set_len(size_output) };
+                Ok(output)
+            }
+        }
+    }
+
+    /// Retrieve whole chunks within `array_subset` into `output` with a bounded fetch-ahead pipeline.
+    ///
+    /// [`CodecOptions::chunk_prefetch`] dedicated storage threads fetch encoded chunks into a bounded queue, which are decoded concurrently in the [thread pool](CodecOptions::thread_pool) as they arrive.
+    /// Fetches do not occupy the thread pool, so storage I/O overlaps with decoding.
+    fn retrieve_chunks_prefetch(
+        &self,
+        chunks: Vec<ArrayIndices>,
+        array_subset: &ArraySubset,
+        output: &UnsafeCellSlice<u8>,
+        progress: &ChunkProgressTracker,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let num_fetch_threads = options.chunk_prefetch().min(chunks.len());
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let chunks = Mutex::new(chunks.into_iter());
+        let (sender, receiver) = sync_channel(options.chunk_prefetch());
+        std::thread::scope(|scope| {
+            for _ in 0..num_fetch_threads {
+                let sender = sender.clone();
+                let chunks = &chunks;
+                let storage_transformer = &storage_transformer;
+                std::thread::Builder::new()
+                    .name("zarrs-prefetch".to_string())
+                    .spawn_scoped(scope, move || loop {
+                        let chunk_indices = chunks.lock().unwrap().next();
+                        let Some(chunk_indices) = chunk_indices else {
+                            break;
+                        };
+                        if options.is_cancelled() {
+                            // Stop fetching, the decoder returns the cancellation error
+                            break;
+                        }
+                        let chunk_encoded = crate::storage::retrieve_chunk(
+                            &**storage_transformer,
+                            self.path(),
+                            &chunk_indices,
+                            self.chunk_key_encoding(),
+                        );
+                        if sender.send((chunk_indices, chunk_encoded)).is_err() {
+                            // The decoder has stopped due to an error
+                            break;
+                        }
+                    })
+                    .map_err(|err| ArrayError::StorageError(StorageError::from(err)))?;
+            }
+            drop(sender);
+
+            // The receiver blocks until a chunk is fetched or all fetch threads have finished
+            options.install(|| {
+                receiver
+                    .into_iter()
+                    .par_bridge()
+                    .try_for_each(|(chunk_indices, chunk_encoded)| {
+                        let chunk_encoded =
+                            chunk_encoded.map_err(|err| self.chunk_error(&chunk_indices, err))?;
+                        let chunk_representation =
+                            self.chunk_array_representation(&chunk_indices)?;
+                        let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                        let array_view =
+                            ArrayView::new(unsafe { output.get() }, array_subset.shape(), unsafe {
+                                chunk_subset.relative_to_unchecked(array_subset.start())
+                            })
+                            .map_err(|err| CodecError::from(err.to_string()))?;
+                        self.decode_chunk_into_array_view(
+                            &chunk_indices,
+                            chunk_encoded.as_deref(),
+                            &chunk_representation,
+                            &array_view,
+                            options,
+                        )?;
+                        progress.chunk_completed(chunk_representation.size());
+                        Ok::<_, ArrayError>(())
+                    })
+            })?;
+            // Chunks are not fetched once cancelled, so the output may be incomplete
+            check_cancelled(options)
+        })
+    }
+
+    /// Explicit options version of [`retrieve_chunks_into_array_view`](Array::retrieve_chunks_into_array_view).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_chunks_into_array_view_opt(
+        &self,
+        chunks: &ArraySubset,
+        array_view: &ArrayView,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if chunks.dimensionality() != self.dimensionality() {
+            return Err(ArrayError::InvalidArraySubset(
+                chunks.clone(),
+                self.chunk_grid_shape()
+                    .unwrap_or_else(|| vec![0u64; self.dimensionality()]),
+            ));
+        }
+        let num_chunks = chunks.num_elements_usize();
+        if num_chunks == 0 {
+            return Ok(());
+        }
+
+        let array_subset = self.chunks_subset(chunks)?;
+        if array_subset.shape() != array_view.subset().shape() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset,
+                array_view.subset().shape().to_vec(),
+            ));
+        }
+
+        if num_chunks == 1 {
+            let chunk_indices = chunks.start();
+            self.retrieve_chunk_into_array_view_opt(chunk_indices, array_view, options)
+        } else {
+            // Calculate chunk/codec concurrency
+            let chunk_representation =
+                self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+            let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+            let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+                options.concurrent_target(),
+                num_chunks,
+                options,
+                &codec_concurrency,
+            );
+
+            {
+                let indices = chunks.indices();
+                options.install(|| {
+                    iter_concurrent_limit!(
+                        chunk_concurrent_limit,
+                        indices,
+                        try_for_each,
+                        |chunk_indices: Vec<u64>| {
+                            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                            let array_view_subset =
+                                unsafe { chunk_subset.relative_to_unchecked(array_subset.start()) };
+                            self.retrieve_chunk_into_array_view_opt(
+                                &chunk_indices,
+                                &unsafe { array_view.subset_view(&array_view_subset) }
+                                    .map_err(|err| CodecError::from(err.to_string()))?,
+                                &options,
+                            )
+                        }
+                    )
+                })?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_into_array_view`](Array::retrieve_array_subset_into_array_view).
     #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_array_subset_opt(
+    pub fn retrieve_array_subset_into_array_view_opt(
         &self,
         array_subset: &ArraySubset,
+        array_view: &ArrayView,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, ArrayError> {
-        if array_subset.dimensionality() != self.dimensionality() {
+    ) -> Result<(), ArrayError> {
+        if array_subset.shape() != array_view.subset().shape() {
             return Err(ArrayError::InvalidArraySubset(
                 array_subset.clone(),
-                self.shape().to_vec(),
+                array_view.subset().shape().to_vec(),
             ));
         }
 
@@ -734,31 +1569,39 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         // Retrieve chunk bytes
         let num_chunks = chunks.num_elements_usize();
         match num_chunks {
-            0 => Ok(self
-                .fill_value()
-                .as_ne_bytes()
-                .repeat(array_subset.num_elements_usize())),
+            0 => Ok(()),
             1 => {
                 let chunk_indices = chunks.start();
                 let chunk_subset = self.chunk_subset(chunk_indices)?;
                 if &chunk_subset == array_subset {
                     // Single chunk fast path if the array subset domain matches the chunk domain
-                    self.retrieve_chunk_opt(chunk_indices, options)
+                    let array_view_subset =
+                        unsafe { chunk_subset.relative_to_unchecked(array_subset.start()) };
+                    self.retrieve_chunk_into_array_view_opt(
+                        chunk_indices,
+                        &unsafe { array_view.subset_view(&array_view_subset) }
+                            .map_err(|err| CodecError::from(err.to_string()))?,
+                        options,
+                    )
                 } else {
-                    let array_subset_in_chunk_subset =
-                        unsafe { array_subset.relative_to_unchecked(chunk_subset.start()) };
-                    self.retrieve_chunk_subset_opt(
+                    let chunk_subset_in_array_subset =
+                        unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                    let chunk_subset = unsafe {
+                        chunk_subset_in_array_subset.relative_to_unchecked(chunk_subset.start())
+                    };
+                    let array_view_subset = unsafe {
+                        chunk_subset_in_array_subset.relative_to_unchecked(array_subset.start())
+                    };
+                    self.retrieve_chunk_subset_into_array_view_opt(
                         chunk_indices,
-                        &array_subset_in_chunk_subset,
+                        &chunk_subset,
+                        &unsafe { array_view.subset_view(&array_view_subset) }
+                            .map_err(|err| CodecError::from(err.to_string()))?,
                         options,
                     )
                 }
             }
             _ => {
-                // Allocate the output
-                let size_output = array_subset.num_elements_usize() * self.data_type().size();
-                let mut output = Vec::with_capacity(size_output);
-
                 // Calculate chunk/codec concurrency
                 let chunk_representation =
                     self.chunk_array_representation(&vec![0; self.dimensionality()])?;
@@ -772,213 +1615,851 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
                 );
 
                 {
-                    let output = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
-                    let retrieve_chunk = |chunk_indices: Vec<u64>| {
-                        let chunk_subset = self.chunk_subset(&chunk_indices)?;
-                        let chunk_subset_in_array_subset =
-                            unsafe { chunk_subset.overlap_unchecked(array_subset) };
-                        let chunk_subset = unsafe {
-                            chunk_subset_in_array_subset.relative_to_unchecked(chunk_subset.start())
-                        };
-                        let array_view_subset = unsafe {
-                            chunk_subset_in_array_subset.relative_to_unchecked(array_subset.start())
-                        };
-                        let array_view = ArrayView::new(
-                            unsafe { output.get() },
-                            array_subset.shape(),
-                            array_view_subset,
-                        )
-                        .map_err(|err| CodecError::from(err.to_string()))?;
-                        self.retrieve_chunk_subset_into_array_view_opt(
-                            &chunk_indices,
-                            &chunk_subset,
-                            &array_view,
-                            &options,
-                        )
-                    };
                     let indices = chunks.indices();
-                    iter_concurrent_limit!(
-                        chunk_concurrent_limit,
-                        indices,
-                        try_for_each,
-                        retrieve_chunk
-                    )?;
+                    options.install(|| {
+                        iter_concurrent_limit!(
+                            chunk_concurrent_limit,
+                            indices,
+                            try_for_each,
+                            |chunk_indices: Vec<u64>| {
+                                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                                let chunk_subset_in_array_subset =
+                                    unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                                let chunk_subset = unsafe {
+                                    chunk_subset_in_array_subset
+                                        .relative_to_unchecked(chunk_subset.start())
+                                };
+                                let array_view_subset = unsafe {
+                                    chunk_subset_in_array_subset
+                                        .relative_to_unchecked(array_subset.start())
+                                };
+                                self.retrieve_chunk_subset_into_array_view_opt(
+                                    &chunk_indices,
+                                    &chunk_subset,
+                                    &unsafe { array_view.subset_view(&array_view_subset) }
+                                        .map_err(|err| CodecError::from(err.to_string()))?,
+                                    &options,
+                                )
+                            }
+                        )
+                    })?;
                 }
-                unsafe { output.set_len(size_output) };
-                Ok(output)
+                Ok(())
             }
         }
     }
 
-    /// Explicit options version of [`retrieve_chunks_into_array_view`](Array::retrieve_chunks_into_array_view).
+    /// Explicit options version of [`retrieve_array_subsets`](Array::retrieve_array_subsets).
     #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_chunks_into_array_view_opt(
+    pub fn retrieve_array_subsets_opt(
         &self,
-        chunks: &ArraySubset,
-        array_view: &ArrayView,
+        array_subsets: &[ArraySubset],
         options: &CodecOptions,
-    ) -> Result<(), ArrayError> {
-        if chunks.dimensionality() != self.dimensionality() {
-            return Err(ArrayError::InvalidArraySubset(
-                chunks.clone(),
-                self.chunk_grid_shape()
-                    .unwrap_or_else(|| vec![0u64; self.dimensionality()]),
-            ));
+    ) -> Result<Vec<Vec<u8>>, ArrayError> {
+        // Find the region of each array subset in each chunk
+        // (subset index, region in chunk, region in subset)
+        let mut chunk_regions: BTreeMap<ArrayIndices, Vec<(usize, ArraySubset, ArraySubset)>> =
+            BTreeMap::new();
+        for (subset_index, array_subset) in array_subsets.iter().enumerate() {
+            if array_subset.dimensionality() != self.dimensionality() {
+                return Err(ArrayError::InvalidArraySubset(
+                    array_subset.clone(),
+                    self.shape().to_vec(),
+                ));
+            }
+            let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+                return Err(ArrayError::InvalidArraySubset(
+                    array_subset.clone(),
+                    self.shape().to_vec(),
+                ));
+            };
+            if chunks.is_empty() {
+                continue;
+            }
+            for chunk_indices in &chunks.indices() {
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let overlap = unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                let region_in_chunk =
+                    unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+                let region_in_subset =
+                    unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+                chunk_regions.entry(chunk_indices).or_default().push((
+                    subset_index,
+                    region_in_chunk,
+                    region_in_subset,
+                ));
+            }
         }
-        let num_chunks = chunks.num_elements_usize();
-        if num_chunks == 0 {
-            return Ok(());
+
+        // Allocate the outputs
+        let element_size = self.data_type().size();
+        let mut outputs: Vec<Vec<u8>> = array_subsets
+            .iter()
+            .map(|array_subset| vec![0; array_subset.num_elements_usize() * element_size])
+            .collect();
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunk_regions.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        {
+            let outputs: Vec<UnsafeCellSlice<u8>> = outputs
+                .iter_mut()
+                .map(|output| UnsafeCellSlice::new(output))
+                .collect();
+            let retrieve_chunk_regions = |(chunk_indices, regions): (
+                ArrayIndices,
+                Vec<(usize, ArraySubset, ArraySubset)>,
+            )| {
+                let regions_in_chunk: Vec<ArraySubset> = regions
+                    .iter()
+                    .map(|(_, region_in_chunk, _)| region_in_chunk.clone())
+                    .collect();
+                let decoded_regions = match self
+                    .partial_decoder_opt(&chunk_indices, &options)?
+                    .partial_decode_opt(&regions_in_chunk, &options)
+                {
+                    Ok(decoded_regions) => decoded_regions,
+                    Err(err) if fill_value_on_decode_error(&err, &options) => regions_in_chunk
+                        .iter()
+                        .map(|region| {
+                            self.fill_value()
+                                .as_ne_bytes()
+                                .repeat(region.num_elements_usize())
+                        })
+                        .collect(),
+                    Err(err) => return Err(self.chunk_error(&chunk_indices, err)),
+                };
+                for ((subset_index, _, region_in_subset), decoded_region) in
+                    std::iter::zip(regions, decoded_regions)
+                {
+                    unsafe {
+                        region_in_subset.store_bytes_unchecked(
+                            &decoded_region,
+                            outputs[subset_index].get(),
+                            array_subsets[subset_index].shape(),
+                            element_size,
+                        );
+                    }
+                }
+                Ok::<_, ArrayError>(())
+            };
+            let chunk_regions: Vec<_> = chunk_regions.into_iter().collect();
+            options.install(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    chunk_regions,
+                    try_for_each,
+                    retrieve_chunk_regions
+                )
+            })?;
         }
+        Ok(outputs)
+    }
 
-        let array_subset = self.chunks_subset(chunks)?;
-        if array_subset.shape() != array_view.subset().shape() {
+    /// Explicit options version of [`retrieve_elements_at`](Array::retrieve_elements_at).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_elements_at_opt<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        array_indices: &[TIndices],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let chunk_elements = self.group_array_indices_by_chunk(array_indices)?;
+
+        // Allocate the output
+        let element_size = self.data_type().size();
+        let mut output = vec![0; array_indices.len() * element_size];
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunk_elements.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        {
+            let output = UnsafeCellSlice::new(&mut output);
+            let retrieve_chunk_elements =
+                |(chunk_indices, elements): (ArrayIndices, Vec<(usize, ArrayIndices)>)| {
+                    let regions_in_chunk: Vec<ArraySubset> = elements
+                        .iter()
+                        .map(|(_, chunk_element_indices)| unsafe {
+                            ArraySubset::new_with_start_shape_unchecked(
+                                chunk_element_indices.clone(),
+                                vec![1; chunk_element_indices.len()],
+                            )
+                        })
+                        .collect();
+                    let decoded_elements = match self
+                        .partial_decoder_opt(&chunk_indices, &options)?
+                        .partial_decode_opt(&regions_in_chunk, &options)
+                    {
+                        Ok(decoded_elements) => decoded_elements,
+                        Err(err) if fill_value_on_decode_error(&err, &options) => {
+                            vec![self.fill_value().as_ne_bytes().to_vec(); elements.len()]
+                        }
+                        Err(err) => return Err(self.chunk_error(&chunk_indices, err)),
+                    };
+                    let output = unsafe { output.get() };
+                    for ((index, _), decoded_element) in std::iter::zip(elements, decoded_elements)
+                    {
+                        if decoded_element.len() != element_size {
+                            return Err(ArrayError::UnexpectedChunkDecodedSize(
+                                decoded_element.len(),
+                                element_size,
+                            ));
+                        }
+                        output[index * element_size..(index + 1) * element_size]
+                            .copy_from_slice(&decoded_element);
+                    }
+                    Ok::<_, ArrayError>(())
+                };
+            let chunk_elements: Vec<_> = chunk_elements.into_iter().collect();
+            options.install(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    chunk_elements,
+                    try_for_each,
+                    retrieve_chunk_elements
+                )
+            })?;
+        }
+        Ok(transmute_from_bytes_vec::<T>(output))
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_masked`](Array::retrieve_array_subset_masked).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_array_subset_masked_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        let (masked_chunks, positions) = self.masked_array_subset_chunks(array_subset, mask)?;
+
+        // Allocate the output
+        let element_size = self.data_type().size();
+        let num_masked = mask.iter().filter(|&&selected| selected).count();
+        let mut output = vec![0; num_masked * element_size];
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = masked_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        {
+            let output = UnsafeCellSlice::new(&mut output);
+            let retrieve_chunk_masked = |(chunk_indices, overlap): (ArrayIndices, ArraySubset)| {
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let overlap_in_chunk =
+                    unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+                let overlap_in_array_subset =
+                    unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+                let chunk_subset_bytes =
+                    self.retrieve_chunk_subset_opt(&chunk_indices, &overlap_in_chunk, &options)?;
+                let output = unsafe { output.get() };
+                let array_subset_indices = unsafe {
+                    overlap_in_array_subset.linearised_indices_unchecked(array_subset.shape())
+                };
+                for (overlap_index, array_subset_index) in array_subset_indices.iter().enumerate() {
+                    let array_subset_index = usize::try_from(array_subset_index).unwrap();
+                    if mask[array_subset_index] {
+                        let position = positions[array_subset_index];
+                        output[position * element_size..(position + 1) * element_size]
+                            .copy_from_slice(
+                                &chunk_subset_bytes[overlap_index * element_size
+                                    ..(overlap_index + 1) * element_size],
+                            );
+                    }
+                }
+                Ok::<_, ArrayError>(())
+            };
+            options.install(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    masked_chunks,
+                    try_for_each,
+                    retrieve_chunk_masked
+                )
+            })?;
+        }
+        Ok(output)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_masked_elements`](Array::retrieve_array_subset_masked_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_masked_elements_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_array_subset_masked_opt(array_subset, mask, options)?;
+        Ok(transmute_from_bytes_vec::<T>(bytes))
+    }
+
+    /// Explicit options version of [`retrieve_orthogonal`](Array::retrieve_orthogonal).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_orthogonal_opt<TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        let (selection_chunks, selection_shape) =
+            self.orthogonal_selection_chunks(dimension_indices)?;
+
+        // Allocate the output
+        let element_size = self.data_type().size();
+        let num_elements = usize::try_from(selection_shape.iter().product::<u64>()).unwrap();
+        let mut output = vec![0; num_elements * element_size];
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = selection_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        {
+            let output = UnsafeCellSlice::new(&mut output);
+            let retrieve_chunk_selection =
+                |(chunk_indices, selection): (ArrayIndices, Vec<Vec<(u64, u64)>>)| {
+                    // Decode the region of the chunk bounding the selected elements
+                    let (region_start, region_end): (Vec<u64>, Vec<u64>) = selection
+                        .iter()
+                        .map(|elements| {
+                            elements.iter().fold(
+                                (u64::MAX, 0),
+                                |(start, end), &(_, chunk_element_index)| {
+                                    (
+                                        start.min(chunk_element_index),
+                                        end.max(chunk_element_index + 1),
+                                    )
+                                },
+                            )
+                        })
+                        .unzip();
+                    let region = unsafe {
+                        ArraySubset::new_with_start_end_exc_unchecked(region_start, region_end)
+                    };
+                    let region_bytes =
+                        self.retrieve_chunk_subset_opt(&chunk_indices, &region, &options)?;
+
+                    let output = unsafe { output.get() };
+                    for (selection_offset, region_offset) in
+                        orthogonal_selection_offsets(&selection, &selection_shape, &region)
+                    {
+                        output[selection_offset * element_size
+                            ..(selection_offset + 1) * element_size]
+                            .copy_from_slice(
+                                &region_bytes[region_offset * element_size
+                                    ..(region_offset + 1) * element_size],
+                            );
+                    }
+                    Ok::<_, ArrayError>(())
+                };
+            options.install(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    selection_chunks,
+                    try_for_each,
+                    retrieve_chunk_selection
+                )
+            })?;
+        }
+        Ok(output)
+    }
+
+    /// Explicit options version of [`retrieve_orthogonal_elements`](Array::retrieve_orthogonal_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_orthogonal_elements_opt<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_orthogonal_opt(dimension_indices, options)?;
+        Ok(transmute_from_bytes_vec::<T>(bytes))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_orthogonal_ndarray`](Array::retrieve_orthogonal_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_orthogonal_ndarray_opt<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let shape: Vec<u64> = dimension_indices
+            .iter()
+            .map(|indices| indices.as_ref().len() as u64)
+            .collect();
+        let elements =
+            self.retrieve_orthogonal_elements_opt::<T, TIndices>(dimension_indices, options)?;
+        elements_to_ndarray(&shape, elements)
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Explicit options version of [`retrieve_array_subset_fortran`](Array::retrieve_array_subset_fortran).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_array_subset_fortran_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        let element_size = self.data_type().size();
+        let Some(codecs_fortran) = self.codecs_without_fortran_transpose() else {
+            let bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+            return Ok(super::codec::array_to_array::transpose::c_order_to_f_order(
+                bytes,
+                array_subset.shape(),
+                element_size,
+            )?);
+        };
+        if array_subset.dimensionality() != self.dimensionality() {
             return Err(ArrayError::InvalidArraySubset(
-                array_subset,
-                array_view.subset().shape().to_vec(),
+                array_subset.clone(),
+                self.shape().to_vec(),
             ));
         }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
 
-        if num_chunks == 1 {
-            let chunk_indices = chunks.start();
-            self.retrieve_chunk_into_array_view_opt(chunk_indices, array_view, options)
-        } else {
-            // Calculate chunk/codec concurrency
-            let chunk_representation =
-                self.chunk_array_representation(&vec![0; self.dimensionality()])?;
-            let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
-            let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
-                options.concurrent_target(),
-                num_chunks,
-                options,
-                &codec_concurrency,
-            );
+        // The F order bytes of an array are the C order bytes of the array with its dimensions reversed
+        let reversed = |subset: &ArraySubset| unsafe {
+            ArraySubset::new_with_start_shape_unchecked(
+                subset.start().iter().rev().copied().collect(),
+                subset.shape().iter().rev().copied().collect(),
+            )
+        };
+        let array_subset_reversed = reversed(array_subset);
 
-            {
-                let indices = chunks.indices();
+        // Allocate the output
+        let mut output = vec![0; array_subset.num_elements_usize() * element_size];
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunks.num_elements_usize();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        {
+            let output = UnsafeCellSlice::new(&mut output);
+            let retrieve_chunk_fortran = |chunk_indices: ArrayIndices| {
+                let (chunk_bytes, chunk_shape_reversed) = self.retrieve_chunk_fortran_opt(
+                    &codecs_fortran,
+                    &*storage_transformer,
+                    &chunk_indices,
+                    &options,
+                )?;
+
+                // Copy the elements in the array subset to the output
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let overlap = unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                let overlap_in_chunk =
+                    reversed(&unsafe { overlap.relative_to_unchecked(chunk_subset.start()) });
+                let overlap_in_array_subset =
+                    reversed(&unsafe { overlap.relative_to_unchecked(array_subset.start()) });
+                let overlap_bytes = unsafe {
+                    overlap_in_chunk.extract_bytes_unchecked(
+                        &chunk_bytes,
+                        &chunk_shape_reversed,
+                        element_size,
+                    )
+                };
+                unsafe {
+                    overlap_in_array_subset.store_bytes_unchecked(
+                        &overlap_bytes,
+                        output.get(),
+                        array_subset_reversed.shape(),
+                        element_size,
+                    );
+                }
+                Ok::<_, ArrayError>(())
+            };
+            let indices = chunks.indices();
+            options.install(|| {
                 iter_concurrent_limit!(
                     chunk_concurrent_limit,
                     indices,
                     try_for_each,
-                    |chunk_indices: Vec<u64>| {
-                        let chunk_subset = self.chunk_subset(&chunk_indices)?;
-                        let array_view_subset =
-                            unsafe { chunk_subset.relative_to_unchecked(array_subset.start()) };
-                        self.retrieve_chunk_into_array_view_opt(
-                            &chunk_indices,
-                            &unsafe { array_view.subset_view(&array_view_subset) }
-                                .map_err(|err| CodecError::from(err.to_string()))?,
-                            &options,
-                        )
-                    }
-                )?;
+                    retrieve_chunk_fortran
+                )
+            })?;
+        }
+        Ok(output)
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Read and decode the chunk at `chunk_indices` in F (column-major) order with `codecs_fortran`.
+    ///
+    /// Returns the chunk bytes and the reversed chunk shape.
+    fn retrieve_chunk_fortran_opt(
+        &self,
+        codecs_fortran: &super::codec::CodecChain,
+        storage_transformer: &dyn ReadableStorageTraits,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<(Vec<u8>, super::ArrayShape), ArrayError> {
+        check_cancelled(options)?;
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        let chunk_representation_reversed =
+            self.codecs().array_to_array_codecs()[0].compute_encoded_size(&chunk_representation)?;
+        let chunk_encoded = crate::storage::retrieve_chunk(
+            storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+        )
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        let fill_chunk = || {
+            self.fill_value()
+                .as_ne_bytes()
+                .repeat(chunk_representation.num_elements_usize())
+        };
+        let chunk_bytes = if let Some(chunk_encoded) = chunk_encoded {
+            match codecs_fortran.decode(
+                RawBytes::Borrowed(&chunk_encoded),
+                &chunk_representation_reversed,
+                options,
+            ) {
+                Ok(chunk_bytes) => chunk_bytes.into_owned(),
+                Err(err) if fill_value_on_decode_error(&err, options) => fill_chunk(),
+                Err(err) => return Err(self.chunk_error(chunk_indices, err)),
             }
-            Ok(())
+        } else {
+            fill_chunk()
+        };
+        if chunk_bytes.len() == chunk_representation.size_usize() {
+            Ok((chunk_bytes, chunk_representation_reversed.shape_u64()))
+        } else {
+            Err(ArrayError::UnexpectedChunkDecodedSize(
+                chunk_bytes.len(),
+                chunk_representation.size_usize(),
+            ))
         }
     }
 
-    /// Explicit options version of [`retrieve_array_subset_into_array_view`](Array::retrieve_array_subset_into_array_view).
+    #[cfg(feature = "transpose")]
+    /// Explicit options version of [`retrieve_array_subset_elements_fortran`](Array::retrieve_array_subset_elements_fortran).
     #[allow(clippy::missing_errors_doc)]
-    pub fn retrieve_array_subset_into_array_view_opt(
+    pub fn retrieve_array_subset_elements_fortran_opt<T: bytemuck::Pod>(
         &self,
         array_subset: &ArraySubset,
-        array_view: &ArrayView,
         options: &CodecOptions,
-    ) -> Result<(), ArrayError> {
-        if array_subset.shape() != array_view.subset().shape() {
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_array_subset_fortran_opt(array_subset, options)?;
+        Ok(transmute_from_bytes_vec::<T>(bytes))
+    }
+
+    #[cfg(all(feature = "ndarray", feature = "transpose"))]
+    /// Explicit options version of [`retrieve_array_subset_ndarray_fortran`](Array::retrieve_array_subset_ndarray_fortran).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_ndarray_fortran_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements =
+            self.retrieve_array_subset_elements_fortran_opt::<T>(array_subset, options)?;
+        super::elements_to_ndarray_fortran(array_subset.shape(), elements)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_lenient`](Array::retrieve_array_subset_lenient).
+    ///
+    /// [`CodecOptions::fill_value_on_decode_error`] is ignored, since chunks that fail to decode are always filled and reported.
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn retrieve_array_subset_lenient_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<(Vec<u8>, Vec<ChunkDecodeError>), ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality() {
             return Err(ArrayError::InvalidArraySubset(
                 array_subset.clone(),
-                array_view.subset().shape().to_vec(),
+                self.shape().to_vec(),
             ));
         }
-
-        // Find the chunks intersecting this array subset
-        let chunks = self.chunks_in_array_subset(array_subset)?;
-        let Some(chunks) = chunks else {
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
             return Err(ArrayError::InvalidArraySubset(
                 array_subset.clone(),
                 self.shape().to_vec(),
             ));
         };
 
-        // Retrieve chunk bytes
+        let size_output = array_subset.num_elements_usize() * self.data_type().size();
+        let mut output = Vec::with_capacity(size_output);
+        let chunk_decode_errors = Mutex::new(Vec::new());
+
         let num_chunks = chunks.num_elements_usize();
-        match num_chunks {
-            0 => Ok(()),
-            1 => {
-                let chunk_indices = chunks.start();
-                let chunk_subset = self.chunk_subset(chunk_indices)?;
-                if &chunk_subset == array_subset {
-                    // Single chunk fast path if the array subset domain matches the chunk domain
-                    let array_view_subset =
-                        unsafe { chunk_subset.relative_to_unchecked(array_subset.start()) };
-                    self.retrieve_chunk_into_array_view_opt(
-                        chunk_indices,
-                        &unsafe { array_view.subset_view(&array_view_subset) }
-                            .map_err(|err| CodecError::from(err.to_string()))?,
-                        options,
-                    )
-                } else {
-                    let chunk_subset_in_array_subset =
-                        unsafe { chunk_subset.overlap_unchecked(array_subset) };
-                    let chunk_subset = unsafe {
-                        chunk_subset_in_array_subset.relative_to_unchecked(chunk_subset.start())
-                    };
-                    let array_view_subset = unsafe {
-                        chunk_subset_in_array_subset.relative_to_unchecked(array_subset.start())
-                    };
-                    self.retrieve_chunk_subset_into_array_view_opt(
-                        chunk_indices,
-                        &chunk_subset,
-                        &unsafe { array_view.subset_view(&array_view_subset) }
-                            .map_err(|err| CodecError::from(err.to_string()))?,
-                        options,
-                    )
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            &options
+                .into_builder()
+                .fill_value_on_decode_error(false)
+                .build(),
+            &codec_concurrency,
+        );
+
+        {
+            let output = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
+            let retrieve_chunk = |chunk_indices: Vec<u64>| {
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let chunk_subset_in_array_subset =
+                    unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                let chunk_subset = unsafe {
+                    chunk_subset_in_array_subset.relative_to_unchecked(chunk_subset.start())
+                };
+                let array_view_subset = unsafe {
+                    chunk_subset_in_array_subset.relative_to_unchecked(array_subset.start())
+                };
+                let array_view = ArrayView::new(
+                    unsafe { output.get() },
+                    array_subset.shape(),
+                    array_view_subset,
+                )
+                .map_err(|err| CodecError::from(err.to_string()))?;
+                match self.retrieve_chunk_subset_into_array_view_opt(
+                    &chunk_indices,
+                    &chunk_subset,
+                    &array_view,
+                    &options,
+                ) {
+                    Err(err) if matches!(err.root(), ArrayError::CodecError(err) if is_decode_error(err)) =>
+                    {
+                        let ArrayError::CodecError(err) = err.into_root() else {
+                            unreachable!()
+                        };
+                        self.fill_array_view(&array_view);
+                        chunk_decode_errors
+                            .lock()
+                            .unwrap()
+                            .push(ChunkDecodeError::new(chunk_indices, err));
+                        Ok(())
+                    }
+                    result => result,
                 }
-            }
-            _ => {
-                // Calculate chunk/codec concurrency
-                let chunk_representation =
-                    self.chunk_array_representation(&vec![0; self.dimensionality()])?;
-                let codec_concurrency =
-                    self.recommended_codec_concurrency(&chunk_representation)?;
-                let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
-                    options.concurrent_target(),
-                    num_chunks,
-                    options,
-                    &codec_concurrency,
-                );
+            };
+            let indices = chunks.indices();
+            options.install(|| {
+                iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    indices,
+                    try_for_each,
+                    retrieve_chunk
+                )
+            })?;
+        }
+        unsafe { output.set_len(size_output) };
 
-                {
-                    let indices = chunks.indices();
-                    iter_concurrent_limit!(
-                        chunk_concurrent_limit,
-                        indices,
-                        try_for_each,
-                        |chunk_indices: Vec<u64>| {
-                            let chunk_subset = self.chunk_subset(&chunk_indices)?;
-                            let chunk_subset_in_array_subset =
-                                unsafe { chunk_subset.overlap_unchecked(array_subset) };
-                            let chunk_subset = unsafe {
-                                chunk_subset_in_array_subset
-                                    .relative_to_unchecked(chunk_subset.start())
-                            };
-                            let array_view_subset = unsafe {
-                                chunk_subset_in_array_subset
-                                    .relative_to_unchecked(array_subset.start())
-                            };
-                            self.retrieve_chunk_subset_into_array_view_opt(
-                                &chunk_indices,
-                                &chunk_subset,
-                                &unsafe { array_view.subset_view(&array_view_subset) }
-                                    .map_err(|err| CodecError::from(err.to_string()))?,
-                                &options,
-                            )
-                        }
-                    )?;
+        let mut chunk_decode_errors = chunk_decode_errors.into_inner().unwrap();
+        chunk_decode_errors.sort_by(|a, b| a.chunk_indices().cmp(b.chunk_indices()));
+        Ok((output, chunk_decode_errors))
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_elements_lenient`](Array::retrieve_array_subset_elements_lenient).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_elements_lenient_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<(Vec<T>, Vec<ChunkDecodeError>), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let (bytes, chunk_decode_errors) =
+            self.retrieve_array_subset_lenient_opt(array_subset, options)?;
+        Ok((transmute_from_bytes_vec::<T>(bytes), chunk_decode_errors))
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_strided`](Array::retrieve_array_subset_strided).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_strided_opt(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        if strided_array_subset.is_contiguous() {
+            return self
+                .retrieve_array_subset_opt(&strided_array_subset.bounding_subset(), options);
+        }
+
+        // Find the chunks containing an element of the strided array subset
+        let chunks = self.strided_array_subset_chunks(strided_array_subset)?;
+
+        let element_size = self.data_type().size();
+        let size_output = strided_array_subset.num_elements_usize() * element_size;
+        let mut output = Vec::with_capacity(size_output);
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        {
+            let output = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut output);
+            let retrieve_chunk = |(chunk_indices, overlap): (ArrayIndices, StridedArraySubset)| {
+                // Decode the region of the chunk spanning the overlap and extract its elements
+                let chunk_subset = self.chunk_subset(&chunk_indices)?;
+                let overlap_in_chunk = overlap.relative_to(chunk_subset.start())?;
+                let region_in_chunk = overlap_in_chunk.bounding_subset();
+                let region_bytes =
+                    self.retrieve_chunk_subset_opt(&chunk_indices, &region_in_chunk, &options)?;
+                let overlap_bytes = overlap_in_chunk
+                    .relative_to(region_in_chunk.start())?
+                    .extract_bytes(&region_bytes, region_in_chunk.shape(), element_size)
+                    .map_err(|err| CodecError::from(err.to_string()))?;
+
+                // Write the elements to the output
+                let overlap_in_output = strided_array_subset.relative_to_strided(&overlap)?;
+                unsafe {
+                    overlap_in_output.store_bytes_unchecked(
+                        &overlap_bytes,
+                        output.get(),
+                        strided_array_subset.shape(),
+                        element_size,
+                    );
                 }
-                Ok(())
-            }
+                Ok::<_, ArrayError>(())
+            };
+            options.install(|| {
+                iter_concurrent_limit!(chunk_concurrent_limit, chunks, try_for_each, retrieve_chunk)
+            })?;
+        }
+        unsafe { output.set_len(size_output) };
+        Ok(output)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_strided_elements`](Array::retrieve_array_subset_strided_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_strided_elements_opt<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        options: &CodecOptions,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let bytes = self.retrieve_array_subset_strided_opt(strided_array_subset, options)?;
+        Ok(transmute_from_bytes_vec::<T>(bytes))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Explicit options version of [`retrieve_array_subset_strided_ndarray`](Array::retrieve_array_subset_strided_ndarray).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_strided_ndarray_opt<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        options: &CodecOptions,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements =
+            self.retrieve_array_subset_strided_elements_opt::<T>(strided_array_subset, options)?;
+        elements_to_ndarray(strided_array_subset.shape(), elements)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_endian`](Array::retrieve_array_subset_endian).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_endian_opt(
+        &self,
+        array_subset: &ArraySubset,
+        endianness: Endianness,
+        options: &CodecOptions,
+    ) -> Result<Vec<u8>, ArrayError> {
+        if !endianness.is_native() && !supports_endianness_conversion(self.data_type()) {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "endianness conversion",
+            ));
+        }
+        let mut bytes = self.retrieve_array_subset_opt(array_subset, options)?;
+        endianness.from_native(&mut bytes, self.data_type());
+        Ok(bytes)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_into`](Array::retrieve_array_subset_into).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_into_opt(
+        &self,
+        array_subset: &ArraySubset,
+        bytes: &mut [u8],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let expected_size = array_subset.num_elements() * self.data_type().size() as u64;
+        if bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                bytes.len(),
+                expected_size,
+            ));
         }
+        let shape = array_subset.shape().to_vec();
+        let array_view = ArrayView::new(bytes, &shape, ArraySubset::new_with_shape(shape.clone()))
+            .map_err(|err| CodecError::from(err.to_string()))?;
+        self.retrieve_array_subset_into_array_view_opt(array_subset, &array_view, options)
+    }
+
+    /// Explicit options version of [`retrieve_array_subset_elements_into`](Array::retrieve_array_subset_elements_into).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn retrieve_array_subset_elements_into_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        elements: &mut [T],
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        self.retrieve_array_subset_into_opt(
+            array_subset,
+            bytemuck::cast_slice_mut(elements),
+            options,
+        )
     }
 
     /// Explicit options version of [`retrieve_array_subset_elements`](Array::retrieve_array_subset_elements).
@@ -1008,6 +2489,18 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
 
     /// Explicit options version of [`retrieve_chunk_subset`](Array::retrieve_chunk_subset).
     #[allow(clippy::missing_errors_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                chunk_subset = %chunk_subset,
+            )
+        )
+    )]
     pub fn retrieve_chunk_subset_opt(
         &self,
         chunk_indices: &[u64],
@@ -1022,27 +2515,33 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
             ));
         }
 
+        if chunk_subset.is_empty() {
+            // Skip decoding if `chunk_subset` has no elements
+            return Ok(vec![]);
+        }
+
         let decoded_bytes = if chunk_subset.start().iter().all(|&o| o == 0)
             && chunk_subset.shape() == chunk_representation.shape_u64()
         {
             // Fast path if `chunk_subset` encompasses the whole chunk
-            self.retrieve_chunk(chunk_indices)?
+            self.retrieve_chunk_opt(chunk_indices, options)?
         } else {
-            let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
-            let storage_transformer = self
-                .storage_transformers()
-                .create_readable_transformer(storage_handle);
-            let input_handle = Box::new(StoragePartialDecoder::new(
-                storage_transformer,
-                data_key(self.path(), chunk_indices, self.chunk_key_encoding()),
-            ));
-
-            unsafe {
-                self.codecs()
-                    .partial_decoder(input_handle, &chunk_representation, options)?
-                    .partial_decode_opt(&[chunk_subset.clone()], options)?
-                    .pop()
-                    .unwrap_unchecked()
+            check_cancelled(options)?;
+            match self
+                .chunk_input_handle(chunk_indices, options)
+                .and_then(|input_handle| {
+                    self.codecs()
+                        .partial_decoder(input_handle, &chunk_representation, options)
+                })
+                .and_then(|partial_decoder| {
+                    partial_decoder.partial_decode_opt(std::slice::from_ref(chunk_subset), options)
+                }) {
+                Ok(mut decoded) => unsafe { decoded.pop().unwrap_unchecked() },
+                Err(err) if fill_value_on_decode_error(&err, options) => self
+                    .fill_value()
+                    .as_ne_bytes()
+                    .repeat(chunk_subset.num_elements_usize()),
+                Err(err) => return Err(self.chunk_error(chunk_indices, err)),
             }
         };
 
@@ -1086,6 +2585,57 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         elements_to_ndarray(chunk_subset.shape(), elements)
     }
 
+    /// Explicit options version of [`prefetch_array_subset`](Array::prefetch_array_subset).
+    ///
+    /// Up to [`CodecOptions::chunk_prefetch`] chunks are fetched concurrently, or [`CodecOptions::concurrent_target`] chunks if the chunk prefetch is zero.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn prefetch_array_subset_opt(
+        &self,
+        array_subset: &ArraySubset,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        let Some(chunks) = self.chunks_in_array_subset(array_subset)? else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        };
+
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let concurrent_limit = if options.chunk_prefetch() == 0 {
+            options.concurrent_target()
+        } else {
+            options.chunk_prefetch()
+        };
+        let indices = chunks.indices();
+        options.install(|| {
+            iter_concurrent_limit!(
+                concurrent_limit,
+                indices,
+                try_for_each,
+                |chunk_indices: Vec<u64>| {
+                    crate::storage::retrieve_chunk(
+                        &*storage_transformer,
+                        self.path(),
+                        &chunk_indices,
+                        self.chunk_key_encoding(),
+                    )
+                    .map(|_| ())
+                    .map_err(|err| self.chunk_error(&chunk_indices, err))
+                }
+            )
+        })
+    }
+
     /// Explicit options version of [`partial_decoder`](Array::partial_decoder).
     #[allow(clippy::missing_errors_doc)]
     pub fn partial_decoder_opt<'a>(
@@ -1093,17 +2643,169 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
         chunk_indices: &[u64],
         options: &CodecOptions,
     ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, ArrayError> {
+        check_cancelled(options)?;
+        let input_handle = self.chunk_input_handle(chunk_indices, options)?;
+        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
+        Ok(self
+            .codecs()
+            .partial_decoder(input_handle, &chunk_representation, options)?)
+    }
+
+    /// Return an input handle to the encoded chunk at `chunk_indices` for a partial decoder.
+    ///
+    /// If the store does not [support partial reads](ReadableStorageTraits::supports_partial_reads), the whole chunk is read once rather than for each byte range requested by the partial decoder.
+    fn chunk_input_handle(
+        &self,
+        chunk_indices: &[u64],
+        options: &CodecOptions,
+    ) -> Result<Box<dyn BytesPartialDecoderTraits>, CodecError> {
         let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
         let storage_transformer = self
             .storage_transformers()
             .create_readable_transformer(storage_handle);
-        let input_handle = Box::new(StoragePartialDecoder::new(
+        let supports_partial_reads = storage_transformer.supports_partial_reads();
+        let input_handle = StoragePartialDecoder::new(
             storage_transformer,
             data_key(self.path(), chunk_indices, self.chunk_key_encoding()),
-        ));
-        let chunk_representation = self.chunk_array_representation(chunk_indices)?;
-        Ok(self
-            .codecs()
-            .partial_decoder(input_handle, &chunk_representation, options)?)
+        );
+        if supports_partial_reads {
+            Ok(Box::new(input_handle))
+        } else {
+            Ok(Box::new(BytesPartialDecoderCache::new(
+                &input_handle,
+                options,
+            )?))
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + 'static> Array<TStorage> {
+    /// Return the indices of all stored chunks.
+    ///
+    /// The store keys under the array prefix are listed once and matched against the key of every chunk in the chunk grid.
+    /// This can be used to compute the sparsity of an array or skip missing chunks without attempting to retrieve them.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the chunk grid shape is unknown or there is an underlying store error.
+    pub fn chunks_stored(&self) -> Result<Vec<ArrayIndices>, ArrayError> {
+        let chunk_grid_shape = self.chunk_grid_shape().ok_or_else(|| {
+            StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+        })?;
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_listable_transformer(storage_handle);
+        let keys: HashSet<StoreKey> = storage_transformer
+            .list_prefix(&self.path().try_into().map_err(StorageError::from)?)?
+            .into_iter()
+            .collect();
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(ArraySubset::new_with_shape(chunk_grid_shape)
+            .indices()
+            .into_iter()
+            .filter(|chunk_indices| keys.contains(&self.chunk_key(chunk_indices)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        byte_range::ByteRange,
+        storage::{store::MemoryStore, StoreKeyRange, StorePrefix},
+    };
+
+    use super::*;
+
+    /// A store that records the names of the threads that chunks are retrieved on.
+    #[derive(Debug)]
+    struct ThreadRecordingStore {
+        inner: Arc<MemoryStore>,
+        threads: Mutex<Vec<Option<String>>>,
+    }
+
+    impl ReadableStorageTraits for ThreadRecordingStore {
+        fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+            self.threads
+                .lock()
+                .unwrap()
+                .push(std::thread::current().name().map(ToString::to_string));
+            self.inner.get(key)
+        }
+
+        fn get_partial_values_key(
+            &self,
+            key: &StoreKey,
+            byte_ranges: &[ByteRange],
+        ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+            self.inner.get_partial_values_key(key, byte_ranges)
+        }
+
+        fn get_partial_values(
+            &self,
+            key_ranges: &[StoreKeyRange],
+        ) -> Result<Vec<MaybeBytes>, StorageError> {
+            self.inner.get_partial_values(key_ranges)
+        }
+
+        fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+            self.inner.size_prefix(prefix)
+        }
+
+        fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+            self.inner.size_key(key)
+        }
+    }
+
+    #[test]
+    fn array_retrieve_chunks_prefetch_threads() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![8, 8],
+            DataType::UInt16,
+            vec![4, 4].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        let subset = ArraySubset::new_with_shape(vec![8, 8]);
+        let elements: Vec<u16> = (0..64).collect();
+        array
+            .store_array_subset_elements(&subset, elements.clone())
+            .unwrap();
+
+        let recording_store = Arc::new(ThreadRecordingStore {
+            inner: store,
+            threads: Mutex::new(Vec::new()),
+        });
+        let array =
+            Array::new_with_metadata(recording_store.clone(), "/array", array.metadata()).unwrap();
+
+        // Chunks are fetched on dedicated threads and decoded in a single thread pool thread
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .thread_name(|_| "decode".to_string())
+                .build()
+                .unwrap(),
+        );
+        let options = CodecOptions::builder()
+            .chunk_prefetch(2)
+            .thread_pool(thread_pool)
+            .build();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements_opt::<u16>(&subset, &options)
+                .unwrap(),
+            elements
+        );
+        let threads = recording_store.threads.lock().unwrap();
+        assert_eq!(threads.len(), 4);
+        assert!(threads
+            .iter()
+            .all(|thread| thread.as_deref() == Some("zarrs-prefetch")));
     }
 }
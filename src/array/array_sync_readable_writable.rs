@@ -1,12 +1,207 @@
+use std::{collections::BTreeSet, sync::Arc};
+
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::{array_subset::ArraySubset, storage::ReadableWritableStorageTraits};
+use crate::{
+    array_subset::{ArraySubset, StridedArraySubset},
+    node::NodeMetadata,
+    storage::{
+        meta_key, MetadataWriteError, MetadataWriteOptions, ReadableWritableStorageTraits,
+        StorageError, StorageHandle,
+    },
+};
 
 use super::{
-    codec::options::CodecOptions, concurrency::concurrency_chunks_and_codec, Array, ArrayError,
+    chunk_update_options,
+    codec::{
+        array_to_bytes::bytes::{supports_endianness_conversion, Endianness},
+        options::CodecOptions,
+        CodecError,
+    },
+    concurrency::concurrency_chunks_and_codec,
+    orthogonal_selection_offsets, ravel_indices, transmute_to_bytes_vec, validate_element_size,
+    Array, ArrayCreateError, ArrayError, ArrayIndices, ArrayMetadata, ArrayView,
+    ChunkProgressTracker,
 };
 
+#[cfg(feature = "arrow")]
+use super::arrow_array_to_bytes;
+
 impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage> {
+    /// Store metadata with non-default [`MetadataWriteOptions`].
+    ///
+    /// Use this instead of [`store_metadata`](Array::store_metadata) to verify the written metadata and retry failed writes.
+    ///
+    /// # Errors
+    /// Returns a [`MetadataWriteError`] if the final write attempt fails with a storage error or a verification mismatch.
+    pub fn store_metadata_opt(
+        &self,
+        options: &MetadataWriteOptions,
+    ) -> Result<(), MetadataWriteError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_writable_transformer(storage_handle);
+        crate::storage::store_metadata_opt(
+            &*storage_transformer,
+            self.path(),
+            &NodeMetadata::Array(self.metadata()),
+            options,
+        )
+    }
+
+    /// Store the array attributes, leaving the rest of the stored metadata unchanged.
+    ///
+    /// The stored metadata is read, its attributes are replaced with the [`attributes`](Array::attributes) of this array, and it is written back.
+    /// The full metadata is stored as in [`store_metadata`](Array::store_metadata) if there is no stored metadata.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or the stored metadata is invalid.
+    pub fn store_attributes(&self) -> Result<(), StorageError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_writable_transformer(storage_handle);
+        let key = meta_key(self.path());
+        let metadata = if let Some(metadata) = storage_transformer.get(&key)? {
+            let mut metadata: ArrayMetadata = serde_json::from_slice(&metadata)
+                .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))?;
+            let (ArrayMetadata::V3(metadata_stored), ArrayMetadata::V3(metadata_array)) =
+                (&mut metadata, self.metadata());
+            metadata_stored.attributes = metadata_array.attributes;
+            metadata
+        } else {
+            self.metadata()
+        };
+        crate::storage::create_array(&*storage_transformer, self.path(), &metadata)
+    }
+
+    /// Store metadata if the array does not already have metadata.
+    ///
+    /// Returns true if the metadata was stored, or false if metadata already exists.
+    /// Unlike [`store_metadata`](Array::store_metadata), this does not overwrite metadata created concurrently by another writer if the store supports [`set_if_not_exists`](crate::storage::WritableStorageTraits::set_if_not_exists).
+    /// See [`create_metadata_if_not_exists`](crate::storage::create_metadata_if_not_exists).
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error.
+    pub fn store_metadata_if_not_exists(&self) -> Result<bool, StorageError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_writable_transformer(storage_handle);
+        crate::storage::create_metadata_if_not_exists(
+            &*storage_transformer,
+            self.path(),
+            &NodeMetadata::Array(self.metadata()),
+        )
+    }
+
+    /// Update the stored array metadata with `f` and update this array to match.
+    ///
+    /// See [`mutate_metadata_opt`](Array::mutate_metadata_opt).
+    ///
+    /// # Errors
+    /// See [`mutate_metadata_opt`](Array::mutate_metadata_opt).
+    pub fn mutate_metadata(
+        &mut self,
+        f: impl FnMut(&mut ArrayMetadata),
+    ) -> Result<(), ArrayCreateError> {
+        self.mutate_metadata_opt(f, &MetadataWriteOptions::default())
+    }
+
+    /// Update the stored array metadata with `f` and update this array to match, with non-default [`MetadataWriteOptions`].
+    ///
+    /// The current metadata is read from the store and `f` is applied to it.
+    /// The result is written back only if the stored metadata is unchanged since it was read, otherwise `f` is applied again to the newly stored metadata.
+    /// This is attempted up to [`max_retries`](MetadataWriteOptions::max_retries) more times, with the delay between attempts starting at [`retry_delay`](MetadataWriteOptions::retry_delay) and doubling after each attempt.
+    /// The metadata key is locked with the [`chunk_locks`](Array::chunk_locks) of the array (if set) for the duration of the update.
+    ///
+    /// Unlike [`store_metadata`](Array::store_metadata), this does not overwrite changes to the stored metadata made by other writers (e.g. other [`Array`]s of the same array) since this array was opened.
+    /// If the store [supports](crate::storage::WritableStorageTraits::supports_set_if_match) [`set_if_match`](crate::storage::WritableStorageTraits::set_if_match), the metadata is written only if its [`ETag`](crate::storage::ETag) is unchanged since it was read.
+    /// Otherwise, the stored metadata is compared before it is written with `options`, and a concurrent update between the comparison and the write in another process can still be lost.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayCreateError`] if
+    ///  - there is no stored metadata or it is invalid,
+    ///  - the metadata updated by `f` is invalid,
+    ///  - the stored metadata was modified concurrently on every attempt ([`MetadataWriteError::Conflict`]), or
+    ///  - there is an underlying store error.
+    pub fn mutate_metadata_opt(
+        &mut self,
+        mut f: impl FnMut(&mut ArrayMetadata),
+        options: &MetadataWriteOptions,
+    ) -> Result<(), ArrayCreateError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_writable_transformer(storage_handle);
+        let key = meta_key(self.path());
+        let mutex = self
+            .chunk_locks
+            .as_ref()
+            .map(|chunk_locks| chunk_locks.mutex(&key));
+        let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+
+        let supports_set_if_match = storage_transformer.supports_set_if_match();
+        let mut attempts = 0;
+        let mut retry_delay = options.retry_delay();
+        loop {
+            attempts += 1;
+            // The entity tag is read first, so a write before the metadata is read fails the conditional write
+            let etag = if supports_set_if_match {
+                storage_transformer.etag(&key)?
+            } else {
+                None
+            };
+            let metadata_stored = storage_transformer
+                .get(&key)?
+                .ok_or(ArrayCreateError::MissingMetadata)?;
+            let mut metadata: ArrayMetadata = serde_json::from_slice(&metadata_stored)
+                .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+            f(&mut metadata);
+            let mut array =
+                Self::new_with_metadata(self.storage.clone(), self.path().as_str(), metadata)?;
+            array.include_zarrs_metadata = self.include_zarrs_metadata;
+            let metadata = NodeMetadata::Array(array.metadata());
+
+            // Optimistic concurrency check: the stored metadata must be unchanged since it was read
+            let stored = if let Some(etag) = etag {
+                let json = crate::storage::metadata_to_json_opt(
+                    &metadata,
+                    Some(&metadata_stored),
+                    options,
+                )
+                .map(String::into_bytes)
+                .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+                storage_transformer.set_if_match(&key, &json, &etag)?
+            } else if storage_transformer.get(&key)?.as_deref() == Some(metadata_stored.as_ref()) {
+                crate::storage::store_metadata_opt(
+                    &*storage_transformer,
+                    self.path(),
+                    &metadata,
+                    options,
+                )?;
+                true
+            } else {
+                false
+            };
+
+            if stored {
+                array.chunk_locks = self.chunk_locks.take();
+                #[cfg(feature = "async")]
+                {
+                    array.async_chunk_locks = self.async_chunk_locks.take();
+                }
+                *self = array;
+                return Ok(());
+            } else if attempts > options.max_retries() {
+                return Err(MetadataWriteError::Conflict { key, attempts }.into());
+            }
+            std::thread::sleep(retry_delay);
+            retry_delay = retry_delay.saturating_mul(2);
+        }
+    }
+
     /// Encode `chunk_subset_bytes` and store in `chunk_subset` of the chunk at `chunk_indices` with default codec options.
     ///
     /// Use [`store_chunk_subset_opt`](Array::store_chunk_subset_opt) to control codec options.
@@ -127,6 +322,284 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         )
     }
 
+    /// Encode `subset_bytes` and store in `strided_array_subset`.
+    ///
+    /// Use [`store_array_subset_strided_opt`](Array::store_array_subset_strided_opt) to control codec options.
+    /// Only the chunks containing an element of `strided_array_subset` are decoded, updated, and encoded.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the dimensionality of `strided_array_subset` does not match the chunk grid dimensionality,
+    ///  - the `strided_array_subset` is out of bounds of the array,
+    ///  - the length of `subset_bytes` does not match the expected length governed by the shape of the strided array subset and the data type size,
+    ///  - there is a codec encoding error, or
+    ///  - an underlying store error.
+    pub fn store_array_subset_strided(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_strided_opt(
+            strided_array_subset,
+            subset_bytes,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode `subset_elements` and store in `strided_array_subset`.
+    ///
+    /// Use [`store_array_subset_strided_elements_opt`](Array::store_array_subset_strided_elements_opt) to control codec options.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`store_array_subset_strided`](Array::store_array_subset_strided) error condition is met.
+    pub fn store_array_subset_strided_elements<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_strided_elements_opt(
+            strided_array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode `elements` and store them at `array_indices`, where each element corresponds to the array indices at the same position.
+    ///
+    /// Use [`store_elements_at_opt`](Array::store_elements_at_opt) to control codec options.
+    /// The elements are grouped by chunk, and each chunk containing any of the elements is decoded, updated, and encoded once.
+    /// If `array_indices` contains duplicates, the last corresponding element is stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size,
+    ///  - the number of `elements` does not match the number of `array_indices`,
+    ///  - any array indices do not match the array dimensionality or are out of bounds of the array,
+    ///  - there is a codec encoding error, or
+    ///  - an underlying store error.
+    pub fn store_elements_at<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        array_indices: &[TIndices],
+        elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.store_elements_at_opt(array_indices, elements, &CodecOptions::default())
+    }
+
+    /// Encode `masked_bytes` and store in the elements of `array_subset` selected by `mask`.
+    ///
+    /// Use [`store_array_subset_masked_opt`](Array::store_array_subset_masked_opt) to control codec options.
+    /// `mask` has an entry for each element of `array_subset` in C order, and `masked_bytes` holds only the selected elements packed in the same order.
+    /// Each chunk containing a selected element is decoded, updated, and reencoded, unless every element of the chunk is selected.
+    /// Elements that are not selected are left unchanged.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the `array_subset` dimensionality does not match the chunk grid dimensionality,
+    ///  - the `array_subset` is out of bounds of the array,
+    ///  - the length of `mask` does not match the number of elements in `array_subset`,
+    ///  - the length of `masked_bytes` does not match the number of selected elements,
+    ///  - there is a codec encoding or decoding error, or
+    ///  - an underlying store error.
+    pub fn store_array_subset_masked(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_masked_opt(
+            array_subset,
+            mask,
+            masked_bytes,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode `masked_elements` and store in the elements of `array_subset` selected by `mask`.
+    ///
+    /// Use [`store_array_subset_masked_elements_opt`](Array::store_array_subset_masked_elements_opt) to control codec options.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`store_array_subset_masked`](Array::store_array_subset_masked) error condition is met.
+    pub fn store_array_subset_masked_elements<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_masked_elements_opt(
+            array_subset,
+            mask,
+            masked_elements,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode `selection_bytes` and store in the orthogonal selection `dimension_indices`.
+    ///
+    /// Use [`store_orthogonal_opt`](Array::store_orthogonal_opt) to control codec options.
+    /// See [`retrieve_orthogonal`](Array::retrieve_orthogonal) for the layout of the selection.
+    /// Each chunk containing a selected element is decoded, updated, and reencoded, unless every element of the chunk is selected.
+    /// If an element is selected more than once, the last of its values in C order is stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the length of `dimension_indices` does not match the array dimensionality,
+    ///  - an index is out of bounds of the array,
+    ///  - the length of `selection_bytes` does not match the number of elements in the selection,
+    ///  - there is a codec encoding or decoding error, or
+    ///  - an underlying store error.
+    pub fn store_orthogonal<TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.store_orthogonal_opt(dimension_indices, selection_bytes, &CodecOptions::default())
+    }
+
+    /// Encode `selection_elements` and store in the orthogonal selection `dimension_indices`.
+    ///
+    /// Use [`store_orthogonal_elements_opt`](Array::store_orthogonal_elements_opt) to control codec options.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`store_orthogonal`](Array::store_orthogonal) error condition is met.
+    pub fn store_orthogonal_elements<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.store_orthogonal_elements_opt(
+            dimension_indices,
+            selection_elements,
+            &CodecOptions::default(),
+        )
+    }
+
+    /// Encode `subset_bytes` with elements in `endianness` byte order and store in `array_subset`.
+    ///
+    /// Use [`store_array_subset_endian_opt`](Array::store_array_subset_endian_opt) to control codec options.
+    /// The bytes are converted to the native byte order of the CPU before they are encoded, e.g. when copying from a big endian file format.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if:
+    ///  - the endianness of the data type cannot be converted (e.g. a multi-byte extension data type), or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn store_array_subset_endian(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+        endianness: Endianness,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_endian_opt(
+            array_subset,
+            subset_bytes,
+            endianness,
+            &CodecOptions::default(),
+        )
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Encode `subset_bytes` in F (column-major) order and store in `array_subset`.
+    ///
+    /// Use [`store_array_subset_fortran_opt`](Array::store_array_subset_fortran_opt) to control codec options.
+    /// The bytes are converted to C (row-major) order before they are stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_array_subset_fortran(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_fortran_opt(array_subset, subset_bytes, &CodecOptions::default())
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Encode `subset_elements` in F (column-major) order and store in `array_subset`.
+    ///
+    /// Use [`store_array_subset_elements_fortran_opt`](Array::store_array_subset_elements_fortran_opt) to control codec options.
+    /// The elements are converted to C (row-major) order before they are stored.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the size of `T` does not match the data type size, or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_array_subset_elements_fortran<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: Vec<T>,
+    ) -> Result<(), ArrayError> {
+        self.store_array_subset_elements_fortran_opt(
+            array_subset,
+            subset_elements,
+            &CodecOptions::default(),
+        )
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Encode `subset_datetimes` and store in `array_subset` of a `numpy.datetime64` array.
+    ///
+    /// [`None`] elements are stored as "not a time" ([`NAT`](crate::array::data_type::NAT)).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the data type is not `numpy.datetime64`,
+    ///  - a datetime cannot be represented in the unit of the data type, or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    pub fn store_array_subset_datetimes(
+        &self,
+        array_subset: &ArraySubset,
+        subset_datetimes: &[Option<chrono::DateTime<chrono::Utc>>],
+    ) -> Result<(), ArrayError> {
+        let elements = self.datetimes_to_elements(subset_datetimes)?;
+        self.store_array_subset_elements(array_subset, elements)
+    }
+
+    #[cfg(feature = "chrono")]
+    /// Encode `subset_timedeltas` and store in `array_subset` of a `numpy.timedelta64` array.
+    ///
+    /// [`None`] elements are stored as "not a time" ([`NAT`](crate::array::data_type::NAT)).
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the data type is not `numpy.timedelta64`,
+    ///  - a timedelta cannot be represented in the unit of the data type, or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    pub fn store_array_subset_timedeltas(
+        &self,
+        array_subset: &ArraySubset,
+        subset_timedeltas: &[Option<chrono::TimeDelta>],
+    ) -> Result<(), ArrayError> {
+        let elements = self.timedeltas_to_elements(subset_timedeltas)?;
+        self.store_array_subset_elements(array_subset, elements)
+    }
+
+    #[cfg(feature = "arrow")]
+    /// Encode the Arrow array `subset_array` of elements in C order and store in `array_subset`.
+    ///
+    /// Null elements are stored as the fill value.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the Arrow data type of `subset_array` does not match the data type (see [`arrow_data_type`](crate::array::arrow_data_type)), or
+    ///  - a [`store_array_subset`](Array::store_array_subset) error condition is met.
+    pub fn store_array_subset_arrow(
+        &self,
+        array_subset: &ArraySubset,
+        subset_array: &dyn arrow_array::Array,
+    ) -> Result<(), ArrayError> {
+        let subset_bytes = arrow_array_to_bytes(subset_array, self.data_type(), self.fill_value())?;
+        self.store_array_subset(array_subset, subset_bytes)
+    }
+
     #[cfg(feature = "ndarray")]
     /// Encode `subset_array` and store in the array subset starting at `subset_start`.
     ///
@@ -154,6 +627,19 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
 
     /// Explicit options version of [`store_chunk_subset`](Array::store_chunk_subset).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                chunk_subset = %chunk_subset,
+                size = chunk_subset_bytes.len(),
+            )
+        )
+    )]
     pub fn store_chunk_subset_opt(
         &self,
         chunk_indices: &[u64],
@@ -183,36 +669,33 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
             ));
         }
 
+        if chunk_subset.is_empty() {
+            // Nothing to store, so skip decoding and encoding the chunk
+            return Ok(());
+        }
+
         if chunk_subset.shape() == chunk_shape && chunk_subset.start().iter().all(|&x| x == 0) {
             // The subset spans the whole chunk, so store the bytes directly and skip decoding
             self.store_chunk_opt(chunk_indices, chunk_subset_bytes, options)
         } else {
             // Lock the chunk
-            // let key = data_key(self.path(), chunk_indices, self.chunk_key_encoding());
-            // let mutex = self.storage.mutex(&key)?;
-            // let _lock = mutex.lock();
+            let mutex = self
+                .chunk_locks
+                .as_ref()
+                .map(|chunk_locks| chunk_locks.mutex(&self.chunk_key(chunk_indices)));
+            let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+            let options = chunk_update_options(mutex.is_some(), options);
 
             // Decode the entire chunk
-            let mut chunk_bytes = self.retrieve_chunk_opt(chunk_indices, options)?;
+            let mut chunk_bytes = self.retrieve_chunk_opt(chunk_indices, &options)?;
 
             // Update the intersecting subset of the chunk
-            let element_size = self.data_type().size();
-            let mut offset = 0;
-            let contiguous_indices =
-                unsafe { chunk_subset.contiguous_linearised_indices_unchecked(&chunk_shape) };
-            let length = contiguous_indices.contiguous_elements_usize() * element_size;
-            // FIXME: Par iter?
-            for (chunk_element_index, _num_elements) in &contiguous_indices {
-                let chunk_offset = usize::try_from(chunk_element_index).unwrap() * element_size;
-                debug_assert!(chunk_offset + length <= chunk_bytes.len());
-                debug_assert!(offset + length <= chunk_subset_bytes.len());
-                chunk_bytes[chunk_offset..chunk_offset + length]
-                    .copy_from_slice(&chunk_subset_bytes[offset..offset + length]);
-                offset += length;
-            }
+            let chunk_view = ArrayView::new(&mut chunk_bytes, &chunk_shape, chunk_subset.clone())
+                .map_err(|err| CodecError::from(err.to_string()))?;
+            chunk_view.copy_from_bytes(&chunk_subset_bytes, self.data_type().size(), &options);
 
             // Store the updated chunk
-            self.store_chunk_opt(chunk_indices, chunk_bytes, options)
+            self.store_chunk_opt(chunk_indices, chunk_bytes, &options)
         }
     }
 
@@ -264,6 +747,19 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
 
     /// Explicit options version of [`store_array_subset`](Array::store_array_subset).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    #[allow(clippy::too_many_lines)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                array_subset = %array_subset,
+                size = subset_bytes.len(),
+            )
+        )
+    )]
     pub fn store_array_subset_opt(
         &self,
         array_subset: &ArraySubset,
@@ -294,6 +790,7 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
             ));
         };
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         if num_chunks == 1 {
             let chunk_indices = chunks.start();
             let chunk_subset_in_array = unsafe {
@@ -305,6 +802,7 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
                 // A fast path if the array subset matches the chunk subset
                 // This skips the internal decoding occurring in store_chunk_subset
                 self.store_chunk_opt(chunk_indices, subset_bytes, options)?;
+                progress.chunk_completed(expected_size);
             } else {
                 let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset_in_array) };
                 let chunk_subset_in_array_subset =
@@ -320,12 +818,14 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
                 // Store the chunk subset
                 let array_subset_in_chunk_subset =
                     unsafe { overlap.relative_to_unchecked(chunk_subset_in_array.start()) };
+                let chunk_subset_size = chunk_subset_bytes.len() as u64;
                 self.store_chunk_subset_opt(
                     chunk_indices,
                     &array_subset_in_chunk_subset,
                     chunk_subset_bytes,
                     options,
                 )?;
+                progress.chunk_completed(chunk_subset_size);
             }
         } else {
             // Calculate chunk/codec concurrency
@@ -357,21 +857,26 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
                         self.data_type().size(),
                     )
                 };
+                let chunk_subset_size = chunk_subset_bytes.len() as u64;
                 self.store_chunk_subset_opt(
                     &chunk_indices,
                     &array_subset_in_chunk_subset,
                     chunk_subset_bytes,
                     &options,
-                )
+                )?;
+                progress.chunk_completed(chunk_subset_size);
+                Ok(())
             };
 
             let indices = chunks.indices();
-            rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                chunk_concurrent_limit,
-                indices,
-                try_for_each,
-                store_chunk
-            )?;
+            options.install(|| {
+                rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                    chunk_concurrent_limit,
+                    indices,
+                    try_for_each,
+                    store_chunk
+                )
+            })?;
         }
         Ok(())
     }
@@ -391,6 +896,446 @@ impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Array<TStorage>
         )
     }
 
+    /// Explicit options version of [`store_array_subset_strided`](Array::store_array_subset_strided).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_strided_opt(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if strided_array_subset.is_contiguous() {
+            return self.store_array_subset_opt(
+                &strided_array_subset.bounding_subset(),
+                subset_bytes,
+                options,
+            );
+        }
+
+        // Find the chunks containing an element of the strided array subset
+        let chunks = self.strided_array_subset_chunks(strided_array_subset)?;
+
+        let element_size = self.data_type().size();
+        let expected_size = strided_array_subset.num_elements() * element_size as u64;
+        if subset_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                subset_bytes.len(),
+                expected_size,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk = |(chunk_indices, overlap): (ArrayIndices, StridedArraySubset)| {
+            let overlap_bytes = unsafe {
+                strided_array_subset
+                    .relative_to_strided(&overlap)?
+                    .extract_bytes_unchecked(
+                        &subset_bytes,
+                        strided_array_subset.shape(),
+                        element_size,
+                    )
+            };
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            if overlap.num_elements() == chunk_subset.num_elements() {
+                // The strided array subset selects every element of the chunk
+                return self.store_chunk_opt(&chunk_indices, overlap_bytes, &options);
+            }
+
+            // Lock the chunk
+            let mutex = self
+                .chunk_locks
+                .as_ref()
+                .map(|chunk_locks| chunk_locks.mutex(&self.chunk_key(&chunk_indices)));
+            let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+            let options = chunk_update_options(mutex.is_some(), &options);
+
+            // Decode the entire chunk, update the strided elements, and store the updated chunk
+            let mut chunk_bytes = self.retrieve_chunk_opt(&chunk_indices, &options)?;
+            overlap
+                .relative_to(chunk_subset.start())?
+                .store_bytes(
+                    &overlap_bytes,
+                    &mut chunk_bytes,
+                    chunk_subset.shape(),
+                    element_size,
+                )
+                .map_err(|err| CodecError::from(err.to_string()))?;
+            self.store_chunk_opt(&chunk_indices, chunk_bytes, &options)
+        };
+        options.install(|| {
+            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                chunk_concurrent_limit,
+                chunks,
+                try_for_each,
+                store_chunk
+            )
+        })
+    }
+
+    /// Explicit options version of [`store_array_subset_strided_elements`](Array::store_array_subset_strided_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_strided_elements_opt<T: bytemuck::Pod>(
+        &self,
+        strided_array_subset: &StridedArraySubset,
+        subset_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_store_elements!(
+            self,
+            subset_elements,
+            store_array_subset_strided_opt(strided_array_subset, subset_elements, options)
+        )
+    }
+
+    /// Explicit options version of [`store_elements_at`](Array::store_elements_at).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_elements_at_opt<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        array_indices: &[TIndices],
+        elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        if elements.len() != array_indices.len() {
+            return Err(ArrayError::InvalidBytesInputSize(
+                elements.len() * self.data_type().size(),
+                (array_indices.len() * self.data_type().size()) as u64,
+            ));
+        }
+        let chunk_elements = self.group_array_indices_by_chunk(array_indices)?;
+        let element_size = self.data_type().size();
+        let elements = transmute_to_bytes_vec(elements);
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = chunk_elements.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_elements =
+            |(chunk_indices, chunk_elements): (ArrayIndices, Vec<(usize, ArrayIndices)>)| {
+                // Lock the chunk
+                let mutex = self
+                    .chunk_locks
+                    .as_ref()
+                    .map(|chunk_locks| chunk_locks.mutex(&self.chunk_key(&chunk_indices)));
+                let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+                let options = chunk_update_options(mutex.is_some(), &options);
+
+                // Decode the entire chunk, update the elements, and store the updated chunk
+                let chunk_shape = self.chunk_subset(&chunk_indices)?.shape().to_vec();
+                let mut chunk_bytes = self.retrieve_chunk_opt(&chunk_indices, &options)?;
+                for (index, chunk_element_indices) in chunk_elements {
+                    let chunk_offset =
+                        usize::try_from(ravel_indices(&chunk_element_indices, &chunk_shape))
+                            .unwrap()
+                            * element_size;
+                    chunk_bytes[chunk_offset..chunk_offset + element_size].copy_from_slice(
+                        &elements[index * element_size..(index + 1) * element_size],
+                    );
+                }
+                self.store_chunk_opt(&chunk_indices, chunk_bytes, &options)
+            };
+        let chunk_elements: Vec<_> = chunk_elements.into_iter().collect();
+        options.install(|| {
+            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                chunk_concurrent_limit,
+                chunk_elements,
+                try_for_each,
+                store_chunk_elements
+            )
+        })
+    }
+
+    /// Explicit options version of [`store_array_subset_masked`](Array::store_array_subset_masked).
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::missing_panics_doc,
+        clippy::needless_pass_by_value
+    )]
+    pub fn store_array_subset_masked_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let (masked_chunks, positions) = self.masked_array_subset_chunks(array_subset, mask)?;
+        let element_size = self.data_type().size();
+        let num_masked = mask.iter().filter(|&&selected| selected).count();
+        if masked_bytes.len() != num_masked * element_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                masked_bytes.len(),
+                (num_masked * element_size) as u64,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = masked_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_masked = |(chunk_indices, overlap): (ArrayIndices, ArraySubset)| {
+            let chunk_subset = self.chunk_subset(&chunk_indices)?;
+            let overlap_in_chunk = unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+            let overlap_in_array_subset =
+                unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+
+            // The offset in the chunk and position in masked_bytes of each selected element
+            let chunk_indices_linearised =
+                unsafe { overlap_in_chunk.linearised_indices_unchecked(chunk_subset.shape()) };
+            let array_subset_indices_linearised = unsafe {
+                overlap_in_array_subset.linearised_indices_unchecked(array_subset.shape())
+            };
+            let selected: Vec<(usize, usize)> =
+                std::iter::zip(&chunk_indices_linearised, &array_subset_indices_linearised)
+                    .filter_map(|(chunk_index, array_subset_index)| {
+                        let array_subset_index = usize::try_from(array_subset_index).unwrap();
+                        mask[array_subset_index].then(|| {
+                            (
+                                usize::try_from(chunk_index).unwrap(),
+                                positions[array_subset_index],
+                            )
+                        })
+                    })
+                    .collect();
+
+            // Lock the chunk
+            let mutex = self
+                .chunk_locks
+                .as_ref()
+                .map(|chunk_locks| chunk_locks.mutex(&self.chunk_key(&chunk_indices)));
+            let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+            let options = chunk_update_options(mutex.is_some(), &options);
+
+            // Decode the chunk unless every element is replaced, update the selected elements, and store the updated chunk
+            let mut chunk_bytes = if selected.len() == chunk_subset.num_elements_usize() {
+                vec![0; selected.len() * element_size]
+            } else {
+                self.retrieve_chunk_opt(&chunk_indices, &options)?
+            };
+            for (chunk_index, position) in selected {
+                chunk_bytes[chunk_index * element_size..(chunk_index + 1) * element_size]
+                    .copy_from_slice(
+                        &masked_bytes[position * element_size..(position + 1) * element_size],
+                    );
+            }
+            self.store_chunk_opt(&chunk_indices, chunk_bytes, &options)
+        };
+        options.install(|| {
+            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                chunk_concurrent_limit,
+                masked_chunks,
+                try_for_each,
+                store_chunk_masked
+            )
+        })
+    }
+
+    /// Explicit options version of [`store_array_subset_masked_elements`](Array::store_array_subset_masked_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_array_subset_masked_elements_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        mask: &[bool],
+        masked_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let masked_bytes = transmute_to_bytes_vec(masked_elements);
+        self.store_array_subset_masked_opt(array_subset, mask, masked_bytes, options)
+    }
+
+    /// Explicit options version of [`store_orthogonal`](Array::store_orthogonal).
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::missing_panics_doc,
+        clippy::needless_pass_by_value
+    )]
+    pub fn store_orthogonal_opt<TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        let (selection_chunks, selection_shape) =
+            self.orthogonal_selection_chunks(dimension_indices)?;
+        let element_size = self.data_type().size();
+        let expected_size = selection_shape.iter().product::<u64>() * element_size as u64;
+        if selection_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                selection_bytes.len(),
+                expected_size,
+            ));
+        }
+
+        // Calculate chunk/codec concurrency
+        let num_chunks = selection_chunks.len();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            options,
+            &codec_concurrency,
+        );
+
+        let store_chunk_selection =
+            |(chunk_indices, selection): (ArrayIndices, Vec<Vec<(u64, u64)>>)| {
+                let chunk_shape = self.chunk_subset(&chunk_indices)?.shape().to_vec();
+                let chunk_offsets = orthogonal_selection_offsets(
+                    &selection,
+                    &selection_shape,
+                    &ArraySubset::new_with_shape(chunk_shape.clone()),
+                );
+                let chunk_selected =
+                    std::iter::zip(&selection, &chunk_shape).all(|(elements, &chunk_size)| {
+                        elements
+                            .iter()
+                            .map(|&(_, chunk_element_index)| chunk_element_index)
+                            .collect::<BTreeSet<_>>()
+                            .len() as u64
+                            == chunk_size
+                    });
+
+                // Lock the chunk
+                let mutex = self
+                    .chunk_locks
+                    .as_ref()
+                    .map(|chunk_locks| chunk_locks.mutex(&self.chunk_key(&chunk_indices)));
+                let _lock = mutex.as_ref().map(|mutex| mutex.lock());
+                let options = chunk_update_options(mutex.is_some(), &options);
+
+                // Decode the chunk unless every element is replaced, update the selected elements, and store the updated chunk
+                let mut chunk_bytes = if chunk_selected {
+                    let chunk_num_elements =
+                        usize::try_from(chunk_shape.iter().product::<u64>()).unwrap();
+                    vec![0; chunk_num_elements * element_size]
+                } else {
+                    self.retrieve_chunk_opt(&chunk_indices, &options)?
+                };
+                for (selection_offset, chunk_offset) in chunk_offsets {
+                    chunk_bytes[chunk_offset * element_size..(chunk_offset + 1) * element_size]
+                        .copy_from_slice(
+                            &selection_bytes[selection_offset * element_size
+                                ..(selection_offset + 1) * element_size],
+                        );
+                }
+                self.store_chunk_opt(&chunk_indices, chunk_bytes, &options)
+            };
+        options.install(|| {
+            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                chunk_concurrent_limit,
+                selection_chunks,
+                try_for_each,
+                store_chunk_selection
+            )
+        })
+    }
+
+    /// Explicit options version of [`store_orthogonal_elements`](Array::store_orthogonal_elements).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn store_orthogonal_elements_opt<T: bytemuck::Pod, TIndices: AsRef<[u64]>>(
+        &self,
+        dimension_indices: &[TIndices],
+        selection_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        let selection_bytes = transmute_to_bytes_vec(selection_elements);
+        self.store_orthogonal_opt(dimension_indices, selection_bytes, options)
+    }
+
+    /// Explicit options version of [`store_array_subset_endian`](Array::store_array_subset_endian).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_array_subset_endian_opt(
+        &self,
+        array_subset: &ArraySubset,
+        mut subset_bytes: Vec<u8>,
+        endianness: Endianness,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if !endianness.is_native() && !supports_endianness_conversion(self.data_type()) {
+            return Err(ArrayError::IncompatibleElementType(
+                self.data_type().clone(),
+                "endianness conversion",
+            ));
+        }
+        endianness.to_native(&mut subset_bytes, self.data_type());
+        self.store_array_subset_opt(array_subset, subset_bytes, options)
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Explicit options version of [`store_array_subset_fortran`](Array::store_array_subset_fortran).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_array_subset_fortran_opt(
+        &self,
+        array_subset: &ArraySubset,
+        subset_bytes: Vec<u8>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        if array_subset.dimensionality() != self.shape().len() {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape().to_vec(),
+            ));
+        }
+        let expected_size = array_subset.num_elements() * self.data_type().size() as u64;
+        if subset_bytes.len() as u64 != expected_size {
+            return Err(ArrayError::InvalidBytesInputSize(
+                subset_bytes.len(),
+                expected_size,
+            ));
+        }
+        let subset_bytes = super::codec::array_to_array::transpose::f_order_to_c_order(
+            subset_bytes,
+            array_subset.shape(),
+            self.data_type().size(),
+        )?;
+        self.store_array_subset_opt(array_subset, subset_bytes, options)
+    }
+
+    #[cfg(feature = "transpose")]
+    /// Explicit options version of [`store_array_subset_elements_fortran`](Array::store_array_subset_elements_fortran).
+    #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
+    pub fn store_array_subset_elements_fortran_opt<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+        subset_elements: Vec<T>,
+        options: &CodecOptions,
+    ) -> Result<(), ArrayError> {
+        array_store_elements!(
+            self,
+            subset_elements,
+            store_array_subset_fortran_opt(array_subset, subset_elements, options)
+        )
+    }
+
     #[cfg(feature = "ndarray")]
     /// Explicit options version of [`store_array_subset_ndarray`](Array::store_array_subset_ndarray).
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
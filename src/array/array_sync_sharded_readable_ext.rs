@@ -6,8 +6,8 @@ use rayon_iter_concurrent_limit::iter_concurrent_limit;
 use super::{
     codec::{CodecError, CodecOptions},
     concurrency::concurrency_chunks_and_codec,
-    transmute_from_bytes_vec, validate_element_size, Array, ArrayError, ArrayShardedExt, ArrayView,
-    ChunkGrid, UnsafeCellSlice,
+    fill_value_on_decode_error, transmute_from_bytes_vec, validate_element_size, Array, ArrayError,
+    ArrayShardedExt, ArrayView, ChunkGrid, UnsafeCellSlice,
 };
 use crate::storage::ReadableStorageTraits;
 use crate::{array::codec::ArrayPartialDecoderTraits, array_subset::ArraySubset};
@@ -234,10 +234,16 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> ArrayShardedReadableExt
             let shard_subset = array_subset.relative_to(&shard_origin)?;
 
             let partial_decoder = cache.retrieve(self, shard_indices)?;
-            Ok(partial_decoder
-                .partial_decode_opt(&[shard_subset], options)?
-                .pop()
-                .expect("partial_decode_opt called with one subset, returned without error"))
+            match partial_decoder.partial_decode_opt(std::slice::from_ref(&shard_subset), options) {
+                Ok(mut decoded) => Ok(decoded
+                    .pop()
+                    .expect("partial_decode_opt called with one subset, returned without error")),
+                Err(err) if fill_value_on_decode_error(&err, options) => Ok(self
+                    .fill_value()
+                    .as_ne_bytes()
+                    .repeat(shard_subset.num_elements_usize())),
+                Err(err) => Err(self.chunk_error(shard_indices, err)),
+            }
         } else {
             self.retrieve_chunk_opt(inner_chunk_indices, options)
         }
@@ -398,12 +404,14 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> ArrayShardedReadableExt
                         )
                     };
                     let indices = shards.indices();
-                    iter_concurrent_limit!(
-                        chunk_concurrent_limit,
-                        indices,
-                        try_for_each,
-                        retrieve_shard
-                    )?;
+                    options.install(|| {
+                        iter_concurrent_limit!(
+                            chunk_concurrent_limit,
+                            indices,
+                            try_for_each,
+                            retrieve_shard
+                        )
+                    })?;
                 }
                 unsafe { output.set_len(size_output) };
                 Ok(output)
@@ -444,14 +452,21 @@ impl<TStorage: ?Sized + ReadableStorageTraits + 'static> ArrayShardedReadableExt
         array_view: &ArrayView,
         options: &CodecOptions,
     ) -> Result<(), ArrayError> {
-        cache
+        match cache
             .retrieve(self, shard_indices)?
             .partial_decode_into_array_view_opt(shard_subset, array_view, options)
-            .map_err(ArrayError::CodecError)
+        {
+            Err(err) if fill_value_on_decode_error(&err, options) => {
+                self.fill_array_view(array_view);
+                Ok(())
+            }
+            result => result.map_err(|err| self.chunk_error(shard_indices, err)),
+        }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation, clippy::too_many_lines)]
 mod tests {
     use std::sync::Arc;
 
@@ -487,7 +502,7 @@ mod tests {
         }
         let array = builder.build(store, array_path)?;
 
-        let data: Vec<u16> = (0..array.shape().into_iter().product())
+        let data: Vec<u16> = (0..array.shape().iter().product())
             .map(|i| i as u16)
             .collect();
 
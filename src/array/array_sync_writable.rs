@@ -5,15 +5,19 @@ use rayon_iter_concurrent_limit::iter_concurrent_limit;
 
 use crate::{
     array_subset::ArraySubset,
-    storage::{StorageError, StorageHandle, WritableStorageTraits},
+    storage::{StorageError, StorageHandle, StoreKeyValue, WritableStorageTraits},
 };
 
 use super::{
+    check_cancelled,
     codec::{options::CodecOptions, ArrayCodecTraits},
     concurrency::concurrency_chunks_and_codec,
-    Array, ArrayError,
+    Array, ArrayError, ChunkProgressTracker,
 };
 
+#[cfg(feature = "arrow")]
+use super::arrow_array_to_bytes;
+
 impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
     /// Store metadata.
     ///
@@ -30,7 +34,8 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
     /// Encode `chunk_bytes` and store at `chunk_indices`.
     ///
     /// Use [`store_chunk_opt`](Array::store_chunk_opt) to control codec options.
-    /// A chunk composed entirely of the fill value will not be written to the store.
+    /// A chunk composed entirely of the fill value will not be written to the store, and any existing chunk is erased.
+    /// This can be disabled with [`CodecOptions::set_store_empty_chunks`].
     ///
     /// # Errors
     /// Returns an [`ArrayError`] if
@@ -49,7 +54,8 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
     /// Encode `chunk_elements` and store at `chunk_indices`.
     ///
     /// Use [`store_chunk_elements_opt`](Array::store_chunk_elements_opt) to control codec options.
-    /// A chunk composed entirely of the fill value will not be written to the store.
+    /// A chunk composed entirely of the fill value will not be written to the store, and any existing chunk is erased.
+    /// This can be disabled with [`CodecOptions::set_store_empty_chunks`].
     ///
     /// # Errors
     /// Returns an [`ArrayError`] if
@@ -85,10 +91,30 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
         self.store_chunk_ndarray_opt(chunk_indices, chunk_array, &CodecOptions::default())
     }
 
+    #[cfg(feature = "arrow")]
+    /// Encode the Arrow array `chunk_array` of elements in C order and store at `chunk_indices`.
+    ///
+    /// Null elements are stored as the fill value.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if
+    ///  - the Arrow data type of `chunk_array` does not match the data type (see [`arrow_data_type`](crate::array::arrow_data_type)), or
+    ///  - a [`store_chunk`](Array::store_chunk) error condition is met.
+    pub fn store_chunk_arrow(
+        &self,
+        chunk_indices: &[u64],
+        chunk_array: &dyn arrow_array::Array,
+    ) -> Result<(), ArrayError> {
+        let chunk_bytes = arrow_array_to_bytes(chunk_array, self.data_type(), self.fill_value())?;
+        self.store_chunk(chunk_indices, chunk_bytes)
+    }
+
     /// Encode `chunks_bytes` and store at the chunks with indices represented by the `chunks` array subset.
     ///
     /// Use [`store_chunks_opt`](Array::store_chunks_opt) to control codec options.
-    /// A chunk composed entirely of the fill value will not be written to the store.
+    /// The chunks are encoded in parallel and stored with a single batched write ([`WritableStorageTraits::set_values`]), so stores supporting batched writes can store them in one operation.
+    /// A chunk composed entirely of the fill value will not be written to the store, and any existing chunk is erased.
+    /// This can be disabled with [`CodecOptions::set_store_empty_chunks`].
     ///
     /// # Errors
     /// Returns an [`ArrayError`] if
@@ -141,6 +167,42 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
         self.store_chunks_ndarray_opt(chunks, chunks_array, &CodecOptions::default())
     }
 
+    /// Store the encoded chunk `chunk_encoded` at `chunk_indices` without encoding it.
+    ///
+    /// This is intended for copying chunks verbatim between arrays with identical codecs (e.g. from [`retrieve_encoded_chunk`](Array::retrieve_encoded_chunk)).
+    /// `chunk_encoded` is not validated, so a chunk that was not encoded with the codecs of this array will fail to decode when retrieved.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` are invalid or there is an underlying store error.
+    pub fn store_encoded_chunk(
+        &self,
+        chunk_indices: &[u64],
+        chunk_encoded: &[u8],
+    ) -> Result<(), ArrayError> {
+        if chunk_indices.len() != self.dimensionality() {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_writable_transformer(storage_handle);
+        crate::storage::store_chunk(
+            &*storage_transformer,
+            self.path(),
+            chunk_indices,
+            self.chunk_key_encoding(),
+            chunk_encoded,
+        )
+        .map_err(|err| self.chunk_error(chunk_indices, err))?;
+        self.update_allocation_map(chunk_indices, true);
+        if self.include_chunk_statistics() {
+            storage_transformer.erase(&self.chunk_statistics_key(chunk_indices))?;
+        }
+        Ok(())
+    }
+
     /// Erase the metadata.
     ///
     /// Succeeds if the metadata does not exist.
@@ -171,7 +233,12 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
             self.path(),
             chunk_indices,
             self.chunk_key_encoding(),
-        )
+        )?;
+        self.update_allocation_map(chunk_indices, false);
+        if self.include_chunk_statistics() {
+            storage_transformer.erase(&self.chunk_statistics_key(chunk_indices))?;
+        }
+        Ok(())
     }
 
     /// Erase the chunks in `chunks`.
@@ -189,7 +256,12 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                 self.path(),
                 &chunk_indices,
                 self.chunk_key_encoding(),
-            )
+            )?;
+            self.update_allocation_map(&chunk_indices, false);
+            if self.include_chunk_statistics() {
+                storage_transformer.erase(&self.chunk_statistics_key(&chunk_indices))?;
+            }
+            Ok(())
         };
 
         chunks.indices().into_par_iter().try_for_each(erase_chunk)
@@ -201,6 +273,18 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
 
     /// Explicit options version of [`store_chunk`](Array::store_chunk).
     #[allow(clippy::missing_errors_doc)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                path = %self.path(),
+                chunk_indices = ?chunk_indices,
+                size = chunk_bytes.len(),
+            )
+        )
+    )]
     pub fn store_chunk_opt(
         &self,
         chunk_indices: &[u64],
@@ -215,19 +299,23 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                 chunk_array_representation.size(),
             ));
         }
+        check_cancelled(options)?;
 
         if !options.store_empty_chunks() && self.fill_value().equals_all(&chunk_bytes) {
-            self.erase_chunk(chunk_indices)?;
+            self.erase_chunk(chunk_indices)
+                .map_err(|err| self.chunk_error(chunk_indices, err))?;
             Ok(())
         } else {
             let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
             let storage_transformer = self
                 .storage_transformers()
                 .create_writable_transformer(storage_handle);
+            let statistics = self.chunk_statistics_key_value(chunk_indices, &chunk_bytes);
             let chunk_encoded: Vec<u8> = self
                 .codecs()
-                .encode(chunk_bytes, &chunk_array_representation, options)
-                .map_err(ArrayError::CodecError)?;
+                .encode(chunk_bytes.into(), &chunk_array_representation, options)
+                .map_err(|err| self.chunk_error(chunk_indices, err))?
+                .into_owned();
             crate::storage::store_chunk(
                 &*storage_transformer,
                 self.path(),
@@ -235,7 +323,12 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                 self.chunk_key_encoding(),
                 &chunk_encoded,
             )
-            .map_err(ArrayError::StorageError)
+            .map_err(|err| self.chunk_error(chunk_indices, err))?;
+            self.update_allocation_map(chunk_indices, true);
+            if let Some((key, value)) = statistics {
+                storage_transformer.set(&key, &value)?;
+            }
+            Ok(())
         }
     }
 
@@ -293,11 +386,14 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
         options: &CodecOptions,
     ) -> Result<(), ArrayError> {
         let num_chunks = chunks.num_elements_usize();
+        let progress = ChunkProgressTracker::new(options, num_chunks);
         match num_chunks {
             0 => {}
             1 => {
                 let chunk_indices = chunks.start();
+                let chunk_size = chunks_bytes.len() as u64;
                 self.store_chunk_opt(chunk_indices, chunks_bytes, options)?;
+                progress.chunk_completed(chunk_size);
             }
             _ => {
                 let array_subset = self.chunks_subset(chunks)?;
@@ -322,7 +418,8 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                     &codec_concurrency,
                 );
 
-                let store_chunk = |chunk_indices: Vec<u64>| -> Result<(), ArrayError> {
+                // Encode the chunks in parallel, chunks equal to the fill value are erased rather than stored
+                let encode_chunk = |chunk_indices: Vec<u64>| {
                     let chunk_subset_in_array = unsafe {
                         self.chunk_grid()
                             .subset_unchecked(&chunk_indices, self.shape())
@@ -347,10 +444,49 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Array<TStorage> {
                         chunk_subset_in_array_subset.num_elements()
                     );
 
-                    self.store_chunk_opt(&chunk_indices, chunk_bytes, &options)
+                    let chunk_size = chunk_bytes.len() as u64;
+                    let statistics = self.chunk_statistics_key_value(&chunk_indices, &chunk_bytes);
+                    let chunk_encoded =
+                        self.encode_chunk_for_store(&chunk_indices, chunk_bytes, &options)?;
+                    Ok::<_, ArrayError>((chunk_indices, chunk_size, chunk_encoded, statistics))
                 };
                 let indices = chunks.indices();
-                iter_concurrent_limit!(chunk_concurrent_limit, indices, try_for_each, store_chunk)?;
+                let chunks_encoded = options.install(|| {
+                    iter_concurrent_limit!(chunk_concurrent_limit, indices, map, encode_chunk)
+                        .collect::<Result<Vec<_>, _>>()
+                })?;
+
+                // Store the encoded chunks with a single batched write
+                let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+                let storage_transformer = self
+                    .storage_transformers()
+                    .create_writable_transformer(storage_handle);
+                let mut key_values = Vec::with_capacity(chunks_encoded.len());
+                let mut erase_keys = Vec::new();
+                for (chunk_indices, _, chunk_encoded, statistics) in &chunks_encoded {
+                    let key = self.chunk_key(chunk_indices);
+                    if let Some(chunk_encoded) = chunk_encoded {
+                        key_values.push(StoreKeyValue::new(key, chunk_encoded));
+                        if let Some((key, value)) = statistics {
+                            key_values.push(StoreKeyValue::new(key.clone(), value));
+                        }
+                    } else {
+                        erase_keys.push(key);
+                        if self.include_chunk_statistics() {
+                            erase_keys.push(self.chunk_statistics_key(chunk_indices));
+                        }
+                    }
+                }
+                if !key_values.is_empty() {
+                    storage_transformer.set_values(&key_values)?;
+                }
+                if !erase_keys.is_empty() {
+                    storage_transformer.erase_values(&erase_keys)?;
+                }
+                for (chunk_indices, chunk_size, chunk_encoded, _) in chunks_encoded {
+                    self.update_allocation_map(&chunk_indices, chunk_encoded.is_some());
+                    progress.chunk_completed(chunk_size);
+                }
             }
         }
 
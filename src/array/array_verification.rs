@@ -0,0 +1,285 @@
+//! Chunk integrity verification.
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon_iter_concurrent_limit::iter_concurrent_limit;
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{
+    codec::{CodecError, CodecOptions},
+    concurrency::concurrency_chunks_and_codec,
+    Array, ArrayError, ArrayIndices,
+};
+
+/// The verification status of a chunk.
+#[derive(Debug)]
+pub enum ChunkVerificationStatus {
+    /// The chunk is stored and decodes successfully.
+    Valid,
+    /// The chunk is not stored, so it is implicitly the fill value.
+    Missing,
+    /// The chunk failed checksum validation.
+    InvalidChecksum(ArrayError),
+    /// The chunk is stored, but it could not be decoded.
+    Undecodable(ArrayError),
+    /// The chunk could not be read from the store.
+    Unreadable(ArrayError),
+}
+
+impl ChunkVerificationStatus {
+    fn new(result: Result<Option<Vec<u8>>, ArrayError>) -> Self {
+        match result {
+            Ok(Some(_)) => Self::Valid,
+            Ok(None) => Self::Missing,
+            Err(err) => match err.root() {
+                ArrayError::CodecError(codec_err)
+                    if matches!(codec_err.root(), CodecError::InvalidChecksum) =>
+                {
+                    Self::InvalidChecksum(err)
+                }
+                ArrayError::StorageError(_) => Self::Unreadable(err),
+                _ => Self::Undecodable(err),
+            },
+        }
+    }
+
+    /// Returns true if the chunk is valid or missing.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        matches!(self, Self::Valid | Self::Missing)
+    }
+}
+
+/// The verification status of a chunk and its indices.
+#[derive(Debug)]
+pub struct ChunkVerification {
+    chunk_indices: ArrayIndices,
+    status: ChunkVerificationStatus,
+}
+
+impl ChunkVerification {
+    /// Return the chunk indices.
+    #[must_use]
+    pub fn chunk_indices(&self) -> &[u64] {
+        &self.chunk_indices
+    }
+
+    /// Return the verification status.
+    #[must_use]
+    pub const fn status(&self) -> &ChunkVerificationStatus {
+        &self.status
+    }
+}
+
+/// A report of the integrity of the chunks of an array.
+///
+/// See [`Array::verify_chunks`].
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    chunks: Vec<ChunkVerification>,
+}
+
+impl VerificationReport {
+    /// Return the verification of every checked chunk, ordered by chunk indices.
+    #[must_use]
+    pub fn chunks(&self) -> &[ChunkVerification] {
+        &self.chunks
+    }
+
+    /// Return the chunks that are stored but failed verification.
+    pub fn failed_chunks(&self) -> impl Iterator<Item = &ChunkVerification> {
+        self.chunks.iter().filter(|chunk| !chunk.status.is_ok())
+    }
+
+    /// Return the number of chunks that are stored and decode successfully.
+    #[must_use]
+    pub fn num_valid(&self) -> usize {
+        self.count(|status| matches!(status, ChunkVerificationStatus::Valid))
+    }
+
+    /// Return the number of chunks that are not stored.
+    #[must_use]
+    pub fn num_missing(&self) -> usize {
+        self.count(|status| matches!(status, ChunkVerificationStatus::Missing))
+    }
+
+    /// Return the number of chunks that failed verification.
+    #[must_use]
+    pub fn num_failed(&self) -> usize {
+        self.count(|status| !status.is_ok())
+    }
+
+    /// Returns true if no chunk failed verification.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.chunks.iter().all(|chunk| chunk.status.is_ok())
+    }
+
+    fn count(&self, predicate: impl Fn(&ChunkVerificationStatus) -> bool) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| predicate(&chunk.status))
+            .count()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Verify the integrity of the chunks intersecting `array_subset`, or all chunks if `array_subset` is [`None`].
+    ///
+    /// Every stored chunk is read and fully decoded in parallel, with checksums validated regardless of [`CodecOptions::validate_checksums`].
+    /// A chunk that fails verification does not fail the request, it is recorded in the returned [`VerificationReport`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the dimensionality of `array_subset` does not match the array, or the chunk grid shape of the array is unknown.
+    pub fn verify_chunks(
+        &self,
+        array_subset: Option<&ArraySubset>,
+    ) -> Result<VerificationReport, ArrayError> {
+        self.verify_chunks_opt(array_subset, &CodecOptions::default())
+    }
+
+    /// Explicit options version of [`verify_chunks`](Array::verify_chunks).
+    #[allow(clippy::missing_errors_doc)]
+    pub fn verify_chunks_opt(
+        &self,
+        array_subset: Option<&ArraySubset>,
+        options: &CodecOptions,
+    ) -> Result<VerificationReport, ArrayError> {
+        let chunks = if let Some(array_subset) = array_subset {
+            if array_subset.dimensionality() != self.dimensionality() {
+                return Err(ArrayError::InvalidArraySubset(
+                    array_subset.clone(),
+                    self.shape().to_vec(),
+                ));
+            }
+            self.chunks_in_array_subset(array_subset)?
+        } else {
+            self.chunk_grid_shape().map(ArraySubset::new_with_shape)
+        };
+        let Some(chunks) = chunks else {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset
+                    .cloned()
+                    .unwrap_or_else(|| ArraySubset::new_with_shape(self.shape().to_vec())),
+                self.shape().to_vec(),
+            ));
+        };
+
+        let num_chunks = chunks.num_elements_usize();
+        let chunk_representation =
+            self.chunk_array_representation(&vec![0; self.dimensionality()])?;
+        let codec_concurrency = self.recommended_codec_concurrency(&chunk_representation)?;
+        let mut options = options
+            .into_builder()
+            .fill_value_on_decode_error(false)
+            .build();
+        options.set_validate_checksums(true);
+        let (chunk_concurrent_limit, options) = concurrency_chunks_and_codec(
+            options.concurrent_target(),
+            num_chunks,
+            &options,
+            &codec_concurrency,
+        );
+
+        let verify_chunk = |chunk_indices: ArrayIndices| {
+            let status = ChunkVerificationStatus::new(
+                self.retrieve_chunk_if_exists_opt(&chunk_indices, &options),
+            );
+            ChunkVerification {
+                chunk_indices,
+                status,
+            }
+        };
+        let indices = chunks.indices();
+        let mut chunks: Vec<ChunkVerification> = options.install(|| {
+            iter_concurrent_limit!(chunk_concurrent_limit, indices, map, verify_chunk).collect()
+        });
+        chunks.sort_by(|a, b| a.chunk_indices.cmp(&b.chunk_indices));
+        Ok(VerificationReport { chunks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{codec, ArrayBuilder, DataType, FillValue},
+        storage::{store::MemoryStore, ReadableStorageTraits, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn array_verify_chunks() {
+        let store = Arc::new(MemoryStore::default());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .bytes_to_bytes_codecs(vec![Box::new(codec::Crc32cCodec::new())])
+        .build(store.clone(), "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_ranges(&[0..4, 0..2]),
+                (1..=8).collect(),
+            )
+            .unwrap();
+        array
+            .store_chunk_elements::<u8>(&[1, 1], vec![1; 4])
+            .unwrap();
+
+        let report = array.verify_chunks(None).unwrap();
+        assert!(report.is_ok());
+        assert_eq!(
+            (
+                report.num_valid(),
+                report.num_missing(),
+                report.num_failed()
+            ),
+            (3, 1, 0)
+        );
+
+        // Corrupt the checksum of chunk [0, 0] and truncate chunk [1, 1]
+        let key = array.chunk_key(&[0, 0]);
+        let mut bytes = Vec::from(store.get(&key).unwrap().unwrap());
+        *bytes.last_mut().unwrap() ^= 1;
+        store.set(&key, &bytes).unwrap();
+        let key = array.chunk_key(&[1, 1]);
+        store.erase(&key).unwrap();
+        store.set(&key, &[0]).unwrap();
+
+        let options = CodecOptions::builder().validate_checksums(false).build();
+        let report = array.verify_chunks_opt(None, &options).unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(
+            (
+                report.num_valid(),
+                report.num_missing(),
+                report.num_failed()
+            ),
+            (1, 1, 2)
+        );
+        let failed: Vec<_> = report.failed_chunks().collect();
+        assert_eq!(failed[0].chunk_indices(), &[0, 0]);
+        assert!(matches!(
+            failed[0].status(),
+            ChunkVerificationStatus::InvalidChecksum(_)
+        ));
+        assert_eq!(failed[1].chunk_indices(), &[1, 1]);
+        assert!(matches!(
+            failed[1].status(),
+            ChunkVerificationStatus::Undecodable(_)
+        ));
+
+        let report = array
+            .verify_chunks(Some(&ArraySubset::new_with_ranges(&[2..4, 0..2])))
+            .unwrap();
+        assert_eq!(report.chunks().len(), 1);
+        assert!(report.is_ok());
+    }
+}
@@ -1,8 +1,16 @@
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use thiserror::Error;
 
-use crate::array_subset::{ArraySubset, IncompatibleDimensionalityError};
+use crate::array_subset::{
+    iterators::ContiguousLinearisedIndices, ArraySubset, IncompatibleDimensionalityError,
+};
 
-use super::{unsafe_cell_slice::UnsafeCellSlice, ArrayShape};
+use super::{codec::CodecOptions, unsafe_cell_slice::UnsafeCellSlice, ArrayShape};
+
+/// The minimum number of bytes written by each task when assembling an [`ArrayView`] in parallel.
+///
+/// Copying fewer bytes than this per task is dominated by the overhead of spawning tasks.
+const PARALLEL_ASSEMBLY_MIN_BYTES_PER_TASK: usize = 64 * 1024;
 
 /// A view of a subset of an array.
 ///
@@ -108,4 +116,77 @@ impl<'a> ArrayView<'a> {
             ))
         }
     }
+
+    /// Copy `bytes`, the C-contiguous elements of the view subset with `element_size` bytes each, into the array referenced by the view.
+    ///
+    /// The copy runs in the thread pool of `options`, and is only parallelised if the concurrent target of `options` is greater than one.
+    pub(crate) fn copy_from_bytes(
+        &self,
+        bytes: &[u8],
+        element_size: usize,
+        options: &CodecOptions,
+    ) {
+        let contiguous_indices = self.contiguous_linearised_indices();
+        let parallel = options.concurrent_target() > 1;
+        options.install(|| {
+            self.for_each_contiguous(
+                &contiguous_indices,
+                element_size,
+                parallel,
+                |run, output| {
+                    let offset = run * output.len();
+                    debug_assert!(offset + output.len() <= bytes.len());
+                    output.copy_from_slice(&bytes[offset..offset + output.len()]);
+                },
+            );
+        });
+    }
+
+    /// Fill the view subset of the array referenced by the view with `element`.
+    pub(crate) fn fill(&self, element: &[u8]) {
+        let contiguous_indices = self.contiguous_linearised_indices();
+        let fill = element.repeat(contiguous_indices.contiguous_elements_usize());
+        self.for_each_contiguous(&contiguous_indices, element.len(), true, |_run, output| {
+            output.copy_from_slice(&fill);
+        });
+    }
+
+    fn contiguous_linearised_indices(&self) -> ContiguousLinearisedIndices {
+        // SAFETY: the subset is within the array shape, which is validated on construction
+        unsafe {
+            self.subset
+                .contiguous_linearised_indices_unchecked(self.shape)
+        }
+    }
+
+    /// Call `f` with the index of each contiguous run of elements in the view subset and the corresponding bytes of the array.
+    ///
+    /// The runs are disjoint, so they are visited across [`rayon`] tasks if `parallel` and there are enough bytes to make it worthwhile.
+    fn for_each_contiguous<F>(
+        &self,
+        contiguous_indices: &ContiguousLinearisedIndices,
+        element_size: usize,
+        parallel: bool,
+        f: F,
+    ) where
+        F: Fn(usize, &mut [u8]) + Send + Sync,
+    {
+        let length = contiguous_indices.contiguous_elements_usize() * element_size;
+        let visit = |(run, (array_element_index, _num_elements)): (usize, (u64, u64))| {
+            let output_offset = usize::try_from(array_element_index).unwrap() * element_size;
+            let output = unsafe { self.bytes_mut() };
+            debug_assert!(output_offset + length <= output.len());
+            f(run, &mut output[output_offset..output_offset + length]);
+        };
+        let min_runs_per_task = PARALLEL_ASSEMBLY_MIN_BYTES_PER_TASK.div_ceil(length.max(1));
+        if parallel && contiguous_indices.len() > min_runs_per_task {
+            contiguous_indices
+                .into_par_iter()
+                .enumerate()
+                .with_min_len(min_runs_per_task)
+                .for_each(visit);
+        } else {
+            contiguous_indices.iter().enumerate().for_each(visit);
+        }
+    }
 }
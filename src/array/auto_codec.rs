@@ -0,0 +1,103 @@
+//! Automatic codec selection.
+
+use thiserror::Error;
+
+use crate::bench::{CodecBenchmark, CodecBenchmarkResult};
+
+use super::{codec::CodecError, ChunkRepresentation, CodecChain};
+
+/// The attribute recording the name of the codec chain chosen by an [`AutoCodec`].
+pub const AUTO_CODEC_ATTRIBUTE: &str = "_zarrs_auto_codec";
+
+/// The objective of an [`AutoCodec`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AutoCodecObjective {
+    /// Choose the codec chain with the smallest encoded size.
+    #[default]
+    Size,
+    /// Choose the codec chain with the fastest encoding.
+    EncodeSpeed,
+    /// Choose the codec chain with the fastest decoding.
+    DecodeSpeed,
+}
+
+/// Automatic codec selection from a set of candidate codec chains.
+///
+/// An [`AutoCodec`] benchmarks each candidate on a sample chunk and chooses the lossless candidate which best meets its [`AutoCodecObjective`].
+/// Ties are resolved in favour of the earliest candidate.
+/// See [`ArrayBuilder::auto_codec`](crate::array::ArrayBuilder::auto_codec).
+#[derive(Clone, Debug, Default)]
+pub struct AutoCodec {
+    candidates: Vec<(String, CodecChain)>,
+    objective: AutoCodecObjective,
+}
+
+impl AutoCodec {
+    /// Create a new automatic codec selection with no candidates and the [`Size`](AutoCodecObjective::Size) objective.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a candidate `codec_chain` identified by `name`.
+    pub fn candidate(&mut self, name: impl Into<String>, codec_chain: CodecChain) -> &mut Self {
+        self.candidates.push((name.into(), codec_chain));
+        self
+    }
+
+    /// Set the objective.
+    pub fn objective(&mut self, objective: AutoCodecObjective) -> &mut Self {
+        self.objective = objective;
+        self
+    }
+
+    /// Choose a candidate for chunks with `representation` given a `sample_chunk`.
+    ///
+    /// Returns the name and codec chain of the chosen candidate.
+    ///
+    /// # Errors
+    /// Returns an [`AutoCodecError`] if the sample chunk is incompatible with `representation`, a candidate fails to encode or decode the sample chunk, or no candidate is lossless.
+    pub fn select(
+        &self,
+        representation: &ChunkRepresentation,
+        sample_chunk: Vec<u8>,
+    ) -> Result<(&str, &CodecChain), AutoCodecError> {
+        let mut benchmark = CodecBenchmark::new(representation.clone());
+        for (name, codec_chain) in &self.candidates {
+            benchmark.candidate(name.clone(), codec_chain.clone());
+        }
+        let mut sample_chunk = Some(sample_chunk);
+        let report = benchmark
+            .samples(1)
+            .run(|_, _| sample_chunk.take().unwrap_or_default())?;
+        let chosen: Option<&CodecBenchmarkResult> = match self.objective {
+            AutoCodecObjective::Size => report.smallest(),
+            AutoCodecObjective::EncodeSpeed => report.fastest_encode(),
+            AutoCodecObjective::DecodeSpeed => report.fastest_decode(),
+        };
+        let index = chosen
+            .and_then(|chosen| {
+                report
+                    .results()
+                    .iter()
+                    .position(|result| std::ptr::eq(result, chosen))
+            })
+            .ok_or(AutoCodecError::NoCandidate)?;
+        let (name, codec_chain) = &self.candidates[index];
+        Ok((name, codec_chain))
+    }
+}
+
+/// An automatic codec selection error.
+#[derive(Debug, Error)]
+pub enum AutoCodecError {
+    /// A codec error.
+    #[error(transparent)]
+    CodecError(#[from] CodecError),
+    /// The chunk representation of the array is invalid.
+    #[error("the chunk representation of the array is invalid: {0}")]
+    InvalidChunkRepresentation(String),
+    /// There are no lossless candidates.
+    #[error("there are no lossless codec chain candidates")]
+    NoCandidate,
+}
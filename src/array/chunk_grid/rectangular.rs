@@ -257,7 +257,6 @@ impl ChunkGridTraits for RectangularChunkGrid {
                 }
             })
             .collect::<Option<Vec<_>>>()
-            .map(std::convert::Into::into)
     }
 
     unsafe fn chunk_origin_unchecked(
@@ -341,7 +340,7 @@ impl ChunkGridTraits for RectangularChunkGrid {
                             RectangularChunkGridDimension::Fixed(_) => true,
                             RectangularChunkGridDimension::Varying(offsets_sizes) => offsets_sizes
                                 .last()
-                                .map_or(false, |last| *array_index < last.offset + last.size.get()),
+                                .is_some_and(|last| *array_index < last.offset + last.size.get()),
                         }
                 },
             )
@@ -221,6 +221,7 @@ impl ChunkGridTraits for RegularChunkGrid {
 }
 
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use crate::array_subset::ArraySubset;
 
@@ -234,7 +235,7 @@ mod tests {
         assert_eq!(
             configuration.to_string(),
             r#"regular chunk grid {"chunk_shape":[1,2,3]}"#
-        )
+        );
     }
 
     #[test]
@@ -306,10 +307,7 @@ mod tests {
             .is_err());
 
         assert!(chunk_grid
-            .chunks_subset(
-                &ArraySubset::new_with_ranges(&[1..3, 1..2, 5..8]),
-                &vec![0; 1]
-            )
+            .chunks_subset(&ArraySubset::new_with_ranges(&[1..3, 1..2, 5..8]), &[0; 1])
             .is_err());
 
         assert!(chunk_grid
@@ -9,6 +9,7 @@ pub use default::{DefaultChunkKeyEncoding, DefaultChunkKeyEncodingConfiguration}
 pub use v2::{V2ChunkKeyEncoding, V2ChunkKeyEncodingConfiguration};
 
 use crate::{
+    array::ArrayIndices,
     metadata::Metadata,
     plugin::{Plugin, PluginCreateError},
     storage::StoreKey,
@@ -78,6 +79,15 @@ pub trait ChunkKeyEncodingTraits: dyn_clone::DynClone + core::fmt::Debug + Send
 
     /// Encode chunk grid indices (grid cell coordinates) into a store key.
     fn encode(&self, chunk_grid_indices: &[u64]) -> StoreKey;
+
+    /// Decode a store key relative to an array into chunk grid indices.
+    ///
+    /// Returns [`None`] if `key` is not a chunk key of this encoding.
+    /// The default implementation returns [`None`] for every key, so encodings should override this to support decoding.
+    fn decode(&self, key: &str) -> Option<ArrayIndices> {
+        let _ = key;
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(ChunkKeyEncodingTraits);
@@ -4,7 +4,10 @@ use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    array::chunk_key_encoding::{ChunkKeyEncodingPlugin, ChunkKeySeparator},
+    array::{
+        chunk_key_encoding::{ChunkKeyEncodingPlugin, ChunkKeySeparator},
+        ArrayIndices,
+    },
     metadata::Metadata,
     plugin::{PluginCreateError, PluginMetadataInvalidError},
     storage::StoreKey,
@@ -115,6 +118,19 @@ impl ChunkKeyEncodingTraits for DefaultChunkKeyEncoding {
         }
         unsafe { StoreKey::new_unchecked(key) }
     }
+
+    fn decode(&self, key: &str) -> Option<ArrayIndices> {
+        let chunk_grid_indices = match key.strip_prefix('c')? {
+            "" => vec![],
+            indices => indices
+                .strip_prefix(&self.separator.to_string())?
+                .split(&self.separator.to_string())
+                .map(|index| index.parse().ok())
+                .collect::<Option<Vec<u64>>>()?,
+        };
+        // Reject non-canonical indices (e.g. leading zeros)
+        (self.encode(&chunk_grid_indices).as_str() == key).then_some(chunk_grid_indices)
+    }
 }
 
 #[cfg(test)]
@@ -123,6 +139,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn decode() {
+        let encoding = DefaultChunkKeyEncoding::new_slash();
+        assert_eq!(encoding.decode("c/1/23/45"), Some(vec![1, 23, 45]));
+        assert_eq!(encoding.decode("c"), Some(vec![]));
+        assert_eq!(encoding.decode("c/01"), None);
+        assert_eq!(encoding.decode("c.1"), None);
+        assert_eq!(encoding.decode("c/1/"), None);
+        assert_eq!(encoding.decode("1/2"), None);
+        let encoding = DefaultChunkKeyEncoding::new_dot();
+        assert_eq!(encoding.decode("c.1.23.45"), Some(vec![1, 23, 45]));
+        assert_eq!(encoding.decode("c/1"), None);
+    }
+
     #[test]
     fn slash_nd() {
         let key = data_key(
@@ -4,7 +4,7 @@ use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    array::chunk_key_encoding::ChunkKeyEncodingPlugin,
+    array::{chunk_key_encoding::ChunkKeyEncodingPlugin, ArrayIndices},
     metadata::Metadata,
     plugin::{PluginCreateError, PluginMetadataInvalidError},
     storage::StoreKey,
@@ -114,6 +114,18 @@ impl ChunkKeyEncodingTraits for V2ChunkKeyEncoding {
         };
         unsafe { StoreKey::new_unchecked(key) }
     }
+
+    /// Decode a store key relative to an array into chunk grid indices.
+    ///
+    /// The key of a scalar chunk (`0`) is decoded as a one-dimensional chunk.
+    fn decode(&self, key: &str) -> Option<ArrayIndices> {
+        let chunk_grid_indices = key
+            .split(&self.separator.to_string())
+            .map(|index| index.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        // Reject non-canonical indices (e.g. leading zeros)
+        (self.encode(&chunk_grid_indices).as_str() == key).then_some(chunk_grid_indices)
+    }
 }
 
 #[cfg(test)]
@@ -122,6 +134,18 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn decode() {
+        let encoding = V2ChunkKeyEncoding::new_dot();
+        assert_eq!(encoding.decode("1.23.45"), Some(vec![1, 23, 45]));
+        assert_eq!(encoding.decode("0"), Some(vec![0]));
+        assert_eq!(encoding.decode("1.023"), None);
+        assert_eq!(encoding.decode("1/23"), None);
+        assert_eq!(encoding.decode("c.1"), None);
+        let encoding = V2ChunkKeyEncoding::new_slash();
+        assert_eq!(encoding.decode("1/23/45"), Some(vec![1, 23, 45]));
+    }
+
     #[test]
     fn slash_nd() {
         let key = data_key(
@@ -0,0 +1,465 @@
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, StorageError, StorageHandle, StoreKey},
+};
+
+#[cfg(feature = "async")]
+use crate::storage::AsyncReadableStorageTraits;
+
+use super::{Array, ArrayError, ArrayIndices, DataType, FillValue};
+
+/// The suffix appended to a chunk key to form the key of its [`ChunkStatistics`].
+pub const CHUNK_STATISTICS_KEY_SUFFIX: &str = ".stats.json";
+
+/// A minimum or maximum value of [`ChunkStatistics`].
+///
+/// Unsigned integers are [`StatisticValue::Int`] unless they exceed [`i64::MAX`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(into = "StatisticValueMetadata", try_from = "StatisticValueMetadata")]
+pub enum StatisticValue {
+    /// A signed integer.
+    Int(i64),
+    /// An unsigned integer greater than [`i64::MAX`].
+    UInt(u64),
+    /// A floating point number, which is never NaN.
+    Float(f64),
+}
+
+impl StatisticValue {
+    /// Return the value as a [`f64`], which is lossy for integers with a magnitude greater than 2<sup>53</sup>.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(value) => value as f64,
+            Self::UInt(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+}
+
+impl From<i64> for StatisticValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u64> for StatisticValue {
+    fn from(value: u64) -> Self {
+        i64::try_from(value).map_or(Self::UInt(value), Self::Int)
+    }
+}
+
+impl From<f64> for StatisticValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+/// The JSON representation of a [`StatisticValue`], with infinities encoded as strings like fill values.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StatisticValueMetadata {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    NonFinite(String),
+}
+
+impl From<StatisticValue> for StatisticValueMetadata {
+    fn from(value: StatisticValue) -> Self {
+        match value {
+            StatisticValue::Int(value) => Self::Int(value),
+            StatisticValue::UInt(value) => Self::UInt(value),
+            StatisticValue::Float(value) if value == f64::INFINITY => {
+                Self::NonFinite("Infinity".to_string())
+            }
+            StatisticValue::Float(value) if value == f64::NEG_INFINITY => {
+                Self::NonFinite("-Infinity".to_string())
+            }
+            StatisticValue::Float(value) => Self::Float(value),
+        }
+    }
+}
+
+impl TryFrom<StatisticValueMetadata> for StatisticValue {
+    type Error = String;
+
+    fn try_from(value: StatisticValueMetadata) -> Result<Self, Self::Error> {
+        match value {
+            StatisticValueMetadata::Int(value) => Ok(Self::Int(value)),
+            StatisticValueMetadata::UInt(value) => Ok(Self::UInt(value)),
+            StatisticValueMetadata::Float(value) => Ok(Self::Float(value)),
+            StatisticValueMetadata::NonFinite(value) => match value.as_str() {
+                "Infinity" => Ok(Self::Float(f64::INFINITY)),
+                "-Infinity" => Ok(Self::Float(f64::NEG_INFINITY)),
+                _ => Err(format!("invalid statistic value {value}")),
+            },
+        }
+    }
+}
+
+/// Statistics of the elements of a chunk.
+///
+/// Chunk statistics are computed at write time if enabled with [`Array::set_include_chunk_statistics`], and stored as JSON next to the chunk at [`Array::chunk_statistics_key`].
+/// They are supported for integer and floating point data types.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChunkStatistics {
+    min: Option<StatisticValue>,
+    max: Option<StatisticValue>,
+    null_count: u64,
+}
+
+impl ChunkStatistics {
+    /// Compute the statistics of the elements in `bytes` with `data_type` and `fill_value`.
+    ///
+    /// Returns [`None`] if `data_type` is not an integer or floating point data type.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8], data_type: &DataType, fill_value: &FillValue) -> Option<Self> {
+        let fill_value = fill_value.as_ne_bytes();
+        let statistics = match data_type {
+            DataType::Int8 => int_statistics(bytes, fill_value, i8::from_ne_bytes, i64::from),
+            DataType::Int16 => int_statistics(bytes, fill_value, i16::from_ne_bytes, i64::from),
+            DataType::Int32 => int_statistics(bytes, fill_value, i32::from_ne_bytes, i64::from),
+            DataType::Int64 => int_statistics(bytes, fill_value, i64::from_ne_bytes, i64::from),
+            DataType::UInt8 => int_statistics(bytes, fill_value, u8::from_ne_bytes, u64::from),
+            DataType::UInt16 => int_statistics(bytes, fill_value, u16::from_ne_bytes, u64::from),
+            DataType::UInt32 => int_statistics(bytes, fill_value, u32::from_ne_bytes, u64::from),
+            DataType::UInt64 => int_statistics(bytes, fill_value, u64::from_ne_bytes, u64::from),
+            DataType::Float16 => float_statistics(bytes, fill_value, |bytes| {
+                half::f16::from_ne_bytes(bytes).to_f64()
+            }),
+            DataType::BFloat16 => float_statistics(bytes, fill_value, |bytes| {
+                half::bf16::from_ne_bytes(bytes).to_f64()
+            }),
+            DataType::Float32 => float_statistics(bytes, fill_value, |bytes| {
+                f64::from(f32::from_ne_bytes(bytes))
+            }),
+            DataType::Float64 => float_statistics(bytes, fill_value, f64::from_ne_bytes),
+            _ => return None,
+        };
+        Some(statistics)
+    }
+
+    /// Return the minimum element, excluding NaN.
+    ///
+    /// Returns [`None`] if all elements are NaN.
+    #[must_use]
+    pub fn min(&self) -> Option<StatisticValue> {
+        self.min
+    }
+
+    /// Return the maximum element, excluding NaN.
+    ///
+    /// Returns [`None`] if all elements are NaN.
+    #[must_use]
+    pub fn max(&self) -> Option<StatisticValue> {
+        self.max
+    }
+
+    /// Return the number of null elements.
+    ///
+    /// An element is null if it is equal to the fill value, which includes any NaN if the fill value is NaN.
+    /// Null elements are included in the [`min`](ChunkStatistics::min) and [`max`](ChunkStatistics::max).
+    #[must_use]
+    pub fn null_count(&self) -> u64 {
+        self.null_count
+    }
+}
+
+fn elements<const N: usize>(bytes: &[u8]) -> impl Iterator<Item = [u8; N]> + '_ {
+    bytes.chunks_exact(N).map(|element| {
+        let mut array = [0; N];
+        array.copy_from_slice(element);
+        array
+    })
+}
+
+fn int_statistics<T: Copy + Ord, U: Into<StatisticValue>, const N: usize>(
+    bytes: &[u8],
+    fill_value: &[u8],
+    from_ne_bytes: fn([u8; N]) -> T,
+    widen: fn(T) -> U,
+) -> ChunkStatistics {
+    let mut min_max: Option<(T, T)> = None;
+    let mut null_count = 0;
+    for element in elements::<N>(bytes) {
+        if element == fill_value {
+            null_count += 1;
+        }
+        let value = from_ne_bytes(element);
+        min_max = Some(min_max.map_or((value, value), |(min, max)| {
+            (min.min(value), max.max(value))
+        }));
+    }
+    ChunkStatistics {
+        min: min_max.map(|(min, _)| widen(min).into()),
+        max: min_max.map(|(_, max)| widen(max).into()),
+        null_count,
+    }
+}
+
+fn float_statistics<const N: usize>(
+    bytes: &[u8],
+    fill_value: &[u8],
+    to_f64: fn([u8; N]) -> f64,
+) -> ChunkStatistics {
+    let fill_value_is_nan =
+        <[u8; N]>::try_from(fill_value).is_ok_and(|bytes| to_f64(bytes).is_nan());
+    let mut min_max: Option<(f64, f64)> = None;
+    let mut null_count = 0;
+    for element in elements::<N>(bytes) {
+        let value = to_f64(element);
+        if element == fill_value || (fill_value_is_nan && value.is_nan()) {
+            null_count += 1;
+        }
+        if !value.is_nan() {
+            min_max = Some(min_max.map_or((value, value), |(min, max)| {
+                (min.min(value), max.max(value))
+            }));
+        }
+    }
+    ChunkStatistics {
+        min: min_max.map(|(min, _)| min.into()),
+        max: min_max.map(|(_, max)| max.into()),
+        null_count,
+    }
+}
+
+impl<TStorage: ?Sized> Array<TStorage> {
+    /// Return the key of the [`ChunkStatistics`] of the chunk at `chunk_indices`.
+    ///
+    /// This is the [chunk key](Array::chunk_key) with the [`CHUNK_STATISTICS_KEY_SUFFIX`].
+    #[must_use]
+    pub fn chunk_statistics_key(&self, chunk_indices: &[u64]) -> StoreKey {
+        let key = self.chunk_key(chunk_indices);
+        unsafe { StoreKey::new_unchecked(key.as_str().to_string() + CHUNK_STATISTICS_KEY_SUFFIX) }
+    }
+
+    /// Return the key and serialised [`ChunkStatistics`] of `chunk_bytes` to store alongside the chunk at `chunk_indices`.
+    ///
+    /// Returns [`None`] if chunk statistics are disabled or unsupported by the data type.
+    pub(crate) fn chunk_statistics_key_value(
+        &self,
+        chunk_indices: &[u64],
+        chunk_bytes: &[u8],
+    ) -> Option<(StoreKey, Vec<u8>)> {
+        if !self.include_chunk_statistics() {
+            return None;
+        }
+        let statistics =
+            ChunkStatistics::from_bytes(chunk_bytes, self.data_type(), self.fill_value())?;
+        let value = serde_json::to_vec(&statistics).expect("chunk statistics are serialisable");
+        Some((self.chunk_statistics_key(chunk_indices), value))
+    }
+
+    /// Parse the [`ChunkStatistics`] stored at `key`.
+    fn chunk_statistics_from_bytes(
+        key: &StoreKey,
+        bytes: Option<&[u8]>,
+    ) -> Result<Option<ChunkStatistics>, ArrayError> {
+        bytes
+            .map(|bytes| {
+                serde_json::from_slice(bytes).map_err(|err| {
+                    StorageError::InvalidMetadata(key.clone(), err.to_string()).into()
+                })
+            })
+            .transpose()
+    }
+
+    /// Return the indices of all chunks in the chunk grid.
+    fn chunk_grid_indices(&self) -> Result<ArraySubset, ArrayError> {
+        let chunk_grid_shape = self.chunk_grid_shape().ok_or_else(|| {
+            StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+        })?;
+        Ok(ArraySubset::new_with_shape(chunk_grid_shape))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + 'static> Array<TStorage> {
+    /// Retrieve the [`ChunkStatistics`] of the chunk at `chunk_indices`.
+    ///
+    /// Returns [`None`] if no statistics are stored for the chunk.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying store error or the statistics are invalid.
+    pub fn retrieve_chunk_statistics(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Option<ChunkStatistics>, ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_readable_transformer(storage_handle);
+        let key = self.chunk_statistics_key(chunk_indices);
+        Self::chunk_statistics_from_bytes(&key, storage_transformer.get(&key)?.as_deref())
+    }
+
+    /// Return the indices of the chunks that may contain elements matching `predicate`.
+    ///
+    /// `predicate` is evaluated on the [`ChunkStatistics`] of every chunk, which are retrieved in parallel.
+    /// Chunks without statistics (including chunks that are not stored) are always returned, so the chunks that are not returned can be safely skipped when reading.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if there is an underlying store error or any statistics are invalid.
+    pub fn chunks_where(
+        &self,
+        predicate: impl Fn(&ChunkStatistics) -> bool + Sync,
+    ) -> Result<Vec<ArrayIndices>, ArrayError> {
+        let chunks = self.chunk_grid_indices()?;
+        let chunks = chunks
+            .indices()
+            .into_par_iter()
+            .map(|chunk_indices| {
+                let statistics = self.retrieve_chunk_statistics(&chunk_indices)?;
+                Ok((chunk_indices, statistics))
+            })
+            .collect::<Result<Vec<_>, ArrayError>>()?;
+        Ok(chunks
+            .into_iter()
+            .filter(|(_, statistics)| statistics.as_ref().map_or(true, &predicate))
+            .map(|(chunk_indices, _)| chunk_indices)
+            .collect())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + 'static> Array<TStorage> {
+    /// Async variant of [`retrieve_chunk_statistics`](Array::retrieve_chunk_statistics).
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_retrieve_chunk_statistics(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Option<ChunkStatistics>, ArrayError> {
+        let storage_handle = Arc::new(StorageHandle::new(self.storage.clone()));
+        let storage_transformer = self
+            .storage_transformers()
+            .create_async_readable_transformer(storage_handle);
+        let key = self.chunk_statistics_key(chunk_indices);
+        Self::chunk_statistics_from_bytes(&key, storage_transformer.get(&key).await?.as_deref())
+    }
+
+    /// Async variant of [`chunks_where`](Array::chunks_where).
+    ///
+    /// The statistics of the chunks are retrieved concurrently.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_chunks_where(
+        &self,
+        predicate: impl Fn(&ChunkStatistics) -> bool,
+    ) -> Result<Vec<ArrayIndices>, ArrayError> {
+        let chunks = self.chunk_grid_indices()?;
+        let indices = chunks.indices();
+        let futures = indices.into_iter().map(|chunk_indices| async move {
+            let statistics = self.async_retrieve_chunk_statistics(&chunk_indices).await?;
+            Ok::<_, ArrayError>((chunk_indices, statistics))
+        });
+        let chunks = futures::future::try_join_all(futures).await?;
+        Ok(chunks
+            .into_iter()
+            .filter(|(_, statistics)| statistics.as_ref().map_or(true, &predicate))
+            .map(|(chunk_indices, _)| chunk_indices)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn chunk_statistics_from_bytes() {
+        let bytes: Vec<u8> = [f32::NAN, -1.0, f32::INFINITY, 0.0]
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        let statistics =
+            ChunkStatistics::from_bytes(&bytes, &DataType::Float32, &FillValue::from(f32::NAN))
+                .unwrap();
+        assert_eq!(statistics.min(), Some(StatisticValue::Float(-1.0)));
+        assert_eq!(statistics.max(), Some(StatisticValue::Float(f64::INFINITY)));
+        assert_eq!(statistics.null_count(), 1);
+        let json = serde_json::to_string(&statistics).unwrap();
+        assert_eq!(json, r#"{"min":-1.0,"max":"Infinity","null_count":1}"#);
+        assert_eq!(
+            serde_json::from_str::<ChunkStatistics>(&json).unwrap(),
+            statistics
+        );
+
+        let bytes: Vec<u8> = [u64::MAX, 0, 0]
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect();
+        let statistics =
+            ChunkStatistics::from_bytes(&bytes, &DataType::UInt64, &FillValue::from(0u64)).unwrap();
+        assert_eq!(statistics.min(), Some(StatisticValue::Int(0)));
+        assert_eq!(statistics.max(), Some(StatisticValue::UInt(u64::MAX)));
+        assert_eq!(statistics.null_count(), 2);
+
+        assert!(
+            ChunkStatistics::from_bytes(&[0], &DataType::Bool, &FillValue::from(false)).is_none()
+        );
+    }
+
+    #[test]
+    fn chunk_statistics_chunks_where() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::Int16,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0i16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.set_include_chunk_statistics(true);
+        array
+            .store_array_subset_elements::<i16>(
+                &ArraySubset::new_with_shape(vec![4, 2]),
+                vec![1, 2, 3, 4, -5, 6, 7, 8],
+            )
+            .unwrap();
+        array
+            .store_chunk_elements::<i16>(&[0, 1], vec![10, 0, 0, 20])
+            .unwrap();
+
+        let statistics = array.retrieve_chunk_statistics(&[1, 0]).unwrap().unwrap();
+        assert_eq!(statistics.min(), Some(StatisticValue::Int(-5)));
+        assert_eq!(statistics.max(), Some(StatisticValue::Int(8)));
+        assert_eq!(
+            array
+                .retrieve_chunk_statistics(&[0, 1])
+                .unwrap()
+                .unwrap()
+                .null_count(),
+            2
+        );
+        assert!(array.retrieve_chunk_statistics(&[1, 1]).unwrap().is_none());
+
+        // Chunks without statistics are always returned
+        let chunks = array
+            .chunks_where(|statistics| statistics.max().is_some_and(|max| max.as_f64() >= 10.0))
+            .unwrap();
+        assert_eq!(chunks, vec![vec![0, 1], vec![1, 1]]);
+
+        // Statistics are erased with their chunk
+        array
+            .store_chunk_elements::<i16>(&[0, 1], vec![0; 4])
+            .unwrap();
+        assert!(array.retrieve_chunk_statistics(&[0, 1]).unwrap().is_none());
+
+        store
+            .set(&array.chunk_statistics_key(&[0, 0]), b"{")
+            .unwrap();
+        assert!(array.chunks_where(|_| true).is_err());
+    }
+}
@@ -15,13 +15,24 @@ pub mod array_to_bytes;
 pub mod bytes_to_bytes;
 pub mod options;
 
-pub use options::{CodecOptions, CodecOptionsBuilder};
+pub use options::{
+    CancellationToken, ChunkProgress, CodecOptions, CodecOptionsBuilder, ProgressCallback,
+};
+
+#[cfg(feature = "async")]
+mod async_executor;
+#[cfg(feature = "async")]
+pub use async_executor::{AsyncExecutor, AsyncExecutorTraits, BlockingTask, RayonAsyncExecutor};
 
 // Array to array
 #[cfg(feature = "bitround")]
 pub use array_to_array::bitround::{
     BitroundCodec, BitroundCodecConfiguration, BitroundCodecConfigurationV1,
 };
+#[cfg(feature = "quantize")]
+pub use array_to_array::quantize::{
+    QuantizeCodec, QuantizeCodecConfiguration, QuantizeCodecConfigurationV1,
+};
 #[cfg(feature = "transpose")]
 pub use array_to_array::transpose::{
     TransposeCodec, TransposeCodecConfiguration, TransposeCodecConfigurationV1,
@@ -30,6 +41,14 @@ pub use array_to_array::transpose::{
 // Array to bytes
 pub use array_to_bytes::bytes::{BytesCodec, BytesCodecConfiguration, BytesCodecConfigurationV1};
 pub use array_to_bytes::codec_chain::CodecChain;
+#[cfg(feature = "n5")]
+pub use array_to_bytes::n5::{
+    N5Codec, N5CodecConfiguration, N5CodecConfigurationV1, N5Compression,
+};
+#[cfg(feature = "packbits")]
+pub use array_to_bytes::packbits::{
+    PackBitsCodec, PackBitsCodecConfiguration, PackBitsCodecConfigurationV1,
+};
 #[cfg(feature = "pcodec")]
 pub use array_to_bytes::pcodec::{
     PcodecCodec, PcodecCodecConfiguration, PcodecCodecConfigurationV1,
@@ -66,6 +85,9 @@ pub use bytes_partial_decoder_cache::BytesPartialDecoderCache;
 mod byte_interval_partial_decoder;
 pub use byte_interval_partial_decoder::ByteIntervalPartialDecoder;
 
+mod partial_decode_plan;
+pub use partial_decode_plan::PartialDecodePlan;
+
 #[cfg(feature = "async")]
 pub use byte_interval_partial_decoder::AsyncByteIntervalPartialDecoder;
 
@@ -82,12 +104,12 @@ use crate::storage::AsyncReadableStorage;
 
 use std::{
     collections::{BTreeMap, BTreeSet},
-    io::{Read, Seek, SeekFrom},
+    io::{Read, Seek, SeekFrom, Write},
 };
 
 use super::{
     concurrency::RecommendedConcurrency, ArrayMetadataOptions, ArrayView, BytesRepresentation,
-    ChunkRepresentation, ChunkShape, DataType, MaybeBytes,
+    ChunkRepresentation, ChunkShape, DataType, MaybeBytes, RawBytes,
 };
 
 /// A codec plugin.
@@ -128,9 +150,21 @@ impl Codec {
                 array_to_array::bitround::IDENTIFIER => {
                     return array_to_array::bitround::create_codec_bitround(metadata);
                 }
+                #[cfg(feature = "quantize")]
+                array_to_array::quantize::IDENTIFIER => {
+                    return array_to_array::quantize::create_codec_quantize(metadata);
+                }
                 array_to_bytes::bytes::IDENTIFIER => {
                     return array_to_bytes::bytes::create_codec_bytes(metadata);
                 }
+                #[cfg(feature = "n5")]
+                array_to_bytes::n5::IDENTIFIER => {
+                    return array_to_bytes::n5::create_codec_n5(metadata);
+                }
+                #[cfg(feature = "packbits")]
+                array_to_bytes::packbits::IDENTIFIER => {
+                    return array_to_bytes::packbits::create_codec_packbits(metadata);
+                }
                 #[cfg(feature = "pcodec")]
                 array_to_bytes::pcodec::IDENTIFIER => {
                     return array_to_bytes::pcodec::create_codec_pcodec(metadata);
@@ -211,23 +245,23 @@ pub trait ArrayCodecTraits: CodecTraits {
     ///
     /// # Errors
     /// Returns [`CodecError`] if a codec fails or `decoded_value` is incompatible with `decoded_representation`.
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError>;
+    ) -> Result<RawBytes<'a>, CodecError>;
 
     /// Decode a chunk.
     ///
     /// # Errors
     /// Returns [`CodecError`] if a codec fails or the decoded output is incompatible with `decoded_representation`.
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError>;
+    ) -> Result<RawBytes<'a>, CodecError>;
 
     /// Decode into the subset of an array.
     ///
@@ -243,25 +277,12 @@ pub trait ArrayCodecTraits: CodecTraits {
         array_view: &ArrayView,
         options: &CodecOptions,
     ) -> Result<(), CodecError> {
-        let decoded_bytes = self.decode(encoded_value.to_vec(), decoded_representation, options)?;
-        let contiguous_indices = unsafe {
-            array_view
-                .subset()
-                .contiguous_linearised_indices_unchecked(array_view.array_shape())
-        };
-        let element_size = decoded_representation.element_size();
-        let length = contiguous_indices.contiguous_elements_usize() * element_size;
-        let mut decoded_offset = 0;
-        // FIXME: Par iteration?
-        let output = unsafe { array_view.bytes_mut() };
-        for (array_subset_element_index, _num_elements) in &contiguous_indices {
-            let output_offset = usize::try_from(array_subset_element_index).unwrap() * element_size;
-            debug_assert!((output_offset + length) <= output.len());
-            debug_assert!((decoded_offset + length) <= decoded_bytes.len());
-            output[output_offset..output_offset + length]
-                .copy_from_slice(&decoded_bytes[decoded_offset..decoded_offset + length]);
-            decoded_offset += length;
-        }
+        let decoded_bytes = self.decode(encoded_value.into(), decoded_representation, options)?;
+        array_view.copy_from_bytes(
+            &decoded_bytes,
+            decoded_representation.element_size(),
+            options,
+        );
         Ok(())
     }
 
@@ -319,7 +340,7 @@ pub trait BytesPartialDecoderTraits: Send + Sync {
     fn decode(&self, options: &CodecOptions) -> Result<MaybeBytes, CodecError> {
         Ok(self
             .partial_decode(&[ByteRange::FromStart(0, None)], options)?
-            .map(|mut v| v.remove(0)))
+            .map(|mut v| v.remove(0).into()))
     }
 }
 
@@ -367,7 +388,7 @@ pub trait AsyncBytesPartialDecoderTraits: Send + Sync {
         Ok(self
             .partial_decode(&[ByteRange::FromStart(0, None)], options)
             .await?
-            .map(|mut v| v.remove(0)))
+            .map(|mut v| v.remove(0).into()))
     }
 }
 
@@ -428,27 +449,10 @@ pub trait ArrayPartialDecoderTraits: Send + Sync {
         }
 
         let decoded_bytes = self
-            .partial_decode_opt(&[array_subset.clone()], options)?
+            .partial_decode_opt(std::slice::from_ref(array_subset), options)?
             .pop()
             .unwrap();
-        let contiguous_indices = unsafe {
-            array_view
-                .subset()
-                .contiguous_linearised_indices_unchecked(array_view.array_shape())
-        };
-        let element_size = self.element_size();
-        let length = contiguous_indices.contiguous_elements_usize() * element_size;
-        let mut decoded_offset = 0;
-        // FIXME: Par iteration?
-        let output = unsafe { array_view.bytes_mut() };
-        for (array_subset_element_index, _num_elements) in &contiguous_indices {
-            let output_offset = usize::try_from(array_subset_element_index).unwrap() * element_size;
-            debug_assert!((output_offset + length) <= output.len());
-            debug_assert!((decoded_offset + length) <= decoded_bytes.len());
-            output[output_offset..output_offset + length]
-                .copy_from_slice(&decoded_bytes[decoded_offset..decoded_offset + length]);
-            decoded_offset += length;
-        }
+        array_view.copy_from_bytes(&decoded_bytes, self.element_size(), options);
         Ok(())
     }
 }
@@ -510,28 +514,11 @@ pub trait AsyncArrayPartialDecoderTraits: Send + Sync {
         }
 
         let decoded_bytes = self
-            .partial_decode_opt(&[array_subset.clone()], options)
+            .partial_decode_opt(std::slice::from_ref(array_subset), options)
             .await?
             .pop()
             .unwrap();
-        let contiguous_indices = unsafe {
-            array_view
-                .subset()
-                .contiguous_linearised_indices_unchecked(array_view.array_shape())
-        };
-        let element_size = self.element_size();
-        let length = contiguous_indices.contiguous_elements_usize() * element_size;
-        let mut decoded_offset = 0;
-        // FIXME: Par iteration?
-        let output = unsafe { array_view.bytes_mut() };
-        for (array_subset_element_index, _num_elements) in &contiguous_indices {
-            let output_offset = usize::try_from(array_subset_element_index).unwrap() * element_size;
-            debug_assert!((output_offset + length) <= output.len());
-            debug_assert!((decoded_offset + length) <= decoded_bytes.len());
-            output[output_offset..output_offset + length]
-                .copy_from_slice(&decoded_bytes[decoded_offset..decoded_offset + length]);
-            decoded_offset += length;
-        }
+        array_view.copy_from_bytes(&decoded_bytes, self.element_size(), options);
         Ok(())
     }
 }
@@ -555,9 +542,9 @@ impl BytesPartialDecoderTraits for StoragePartialDecoder {
         decoded_regions: &[ByteRange],
         _options: &CodecOptions,
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
-        Ok(self
-            .storage
-            .get_partial_values_key(&self.key, decoded_regions)?)
+        self.storage
+            .get_partial_values_key(&self.key, decoded_regions)
+            .map_err(|err| err.with_key(self.key.clone()).into())
     }
 }
 
@@ -584,10 +571,10 @@ impl AsyncBytesPartialDecoderTraits for AsyncStoragePartialDecoder {
         decoded_regions: &[ByteRange],
         _options: &CodecOptions,
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
-        Ok(self
-            .storage
+        self.storage
             .get_partial_values_key(&self.key, decoded_regions)
-            .await?)
+            .await
+            .map_err(|err| err.with_key(self.key.clone()).into())
     }
 }
 
@@ -674,6 +661,22 @@ pub trait ArrayToBytesCodecTraits:
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
     ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, CodecError>;
+
+    /// Plan the byte ranges of an encoded value required to decode `decoded_regions`, without reading the encoded value.
+    ///
+    /// `index` is the encoded index read at the byte range of a previous [`PartialDecodePlan::Index`] plan, if any.
+    /// The default implementation requires the whole encoded value.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if a region is incompatible with the decoded representation or `index` is invalid.
+    fn partial_decode_plan(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+        _decoded_regions: &[ArraySubset],
+        _index: Option<&[u8]>,
+    ) -> Result<PartialDecodePlan, CodecError> {
+        Ok(PartialDecodePlan::Whole)
+    }
 }
 
 dyn_clone::clone_trait_object!(ArrayToBytesCodecTraits);
@@ -700,19 +703,72 @@ pub trait BytesToBytesCodecTraits: CodecTraits + dyn_clone::DynClone + core::fmt
     ///
     /// # Errors
     /// Returns [`CodecError`] if a codec fails.
-    fn encode(&self, decoded_value: Vec<u8>, options: &CodecOptions)
-        -> Result<Vec<u8>, CodecError>;
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError>;
 
     /// Decode chunk bytes.
     //
     /// # Errors
     /// Returns [`CodecError`] if a codec fails.
-    fn decode(
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError>;
+
+    /// Returns true if [`encode_stream`](BytesToBytesCodecTraits::encode_stream) and [`decode_stream`](BytesToBytesCodecTraits::decode_stream) use bounded memory.
+    ///
+    /// If false, the streaming methods hold the entire value in memory.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Encode the bytes read from `decoded_reader` and write them to `encoded_writer`.
+    ///
+    /// This allows values that are larger than memory to be encoded if the codec [supports streaming](BytesToBytesCodecTraits::supports_streaming).
+    /// The default implementation reads the entire value into memory and calls [`encode`](BytesToBytesCodecTraits::encode).
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or reading or writing fails.
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn Read,
+        encoded_writer: &mut dyn Write,
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut decoded_value = Vec::new();
+        decoded_reader.read_to_end(&mut decoded_value)?;
+        encoded_writer.write_all(&self.encode(decoded_value.into(), options)?)?;
+        Ok(())
+    }
+
+    /// Decode the bytes read from `encoded_reader` and write them to `decoded_writer`.
+    ///
+    /// This allows values that are larger than memory to be decoded if the codec [supports streaming](BytesToBytesCodecTraits::supports_streaming).
+    /// The default implementation reads the entire value into memory and calls [`decode`](BytesToBytesCodecTraits::decode).
+    ///
+    /// # Errors
+    /// Returns [`CodecError`] if a codec fails or reading or writing fails.
+    fn decode_stream(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_reader: &mut dyn Read,
+        decoded_writer: &mut dyn Write,
         decoded_representation: &BytesRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError>;
+    ) -> Result<(), CodecError> {
+        let mut encoded_value = Vec::new();
+        encoded_reader.read_to_end(&mut encoded_value)?;
+        decoded_writer.write_all(&self.decode(
+            encoded_value.into(),
+            decoded_representation,
+            options,
+        )?)?;
+        Ok(())
+    }
 
     /// Initialises a partial decoder.
     ///
@@ -766,6 +822,19 @@ impl BytesPartialDecoderTraits for std::io::Cursor<Vec<u8>> {
     }
 }
 
+impl BytesPartialDecoderTraits for std::io::Cursor<RawBytes<'_>> {
+    fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        _parallel: &CodecOptions,
+    ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
+        Ok(Some(extract_byte_ranges_read_seek(
+            &mut self.clone(),
+            decoded_regions,
+        )?))
+    }
+}
+
 #[cfg(feature = "async")]
 #[async_trait::async_trait]
 impl AsyncBytesPartialDecoderTraits for std::io::Cursor<&[u8]> {
@@ -796,6 +865,21 @@ impl AsyncBytesPartialDecoderTraits for std::io::Cursor<Vec<u8>> {
     }
 }
 
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncBytesPartialDecoderTraits for std::io::Cursor<RawBytes<'_>> {
+    async fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+        _parallel: &CodecOptions,
+    ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
+        Ok(Some(extract_byte_ranges_read_seek(
+            &mut self.clone(),
+            decoded_regions,
+        )?))
+    }
+}
+
 /// A codec error.
 #[derive(Debug, Error)]
 pub enum CodecError {
@@ -826,6 +910,40 @@ pub enum CodecError {
     /// Other
     #[error("{_0}")]
     Other(String),
+    /// An error in a codec, identified by its name.
+    #[error("codec {_0}: {_1}")]
+    Codec(String, #[source] Box<CodecError>),
+}
+
+impl CodecError {
+    /// Add the codec `name` to the error context.
+    ///
+    /// Nested codecs (e.g. the inner codecs of `sharding_indexed`) add their names from the innermost to the outermost codec.
+    #[must_use]
+    pub fn with_codec(self, name: &str) -> Self {
+        Self::Codec(name.to_string(), Box::new(self))
+    }
+
+    /// Return the names of the codecs in the error context, from the outermost to the innermost codec.
+    #[must_use]
+    pub fn codecs(&self) -> Vec<&str> {
+        let mut codecs = Vec::new();
+        let mut err = self;
+        while let Self::Codec(name, source) = err {
+            codecs.push(name.as_str());
+            err = source;
+        }
+        codecs
+    }
+
+    /// Return the underlying error without its context.
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Codec(_, err) => err.root(),
+            err => err,
+        }
+    }
 }
 
 impl From<&str> for CodecError {
@@ -16,7 +16,7 @@ pub struct ArrayPartialDecoderCache<'a> {
     phantom: PhantomData<&'a ()>,
 }
 
-impl<'a> ArrayPartialDecoderCache<'a> {
+impl ArrayPartialDecoderCache<'_> {
     /// Create a new partial decoder cache.
     ///
     /// # Errors
@@ -50,7 +50,7 @@ impl<'a> ArrayPartialDecoderCache<'a> {
         input_handle: &dyn AsyncArrayPartialDecoderTraits,
         decoded_representation: ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<ArrayPartialDecoderCache<'a>, CodecError> {
+    ) -> Result<Self, CodecError> {
         let cache = input_handle
             .partial_decode_opt(
                 &[ArraySubset::new_with_shape(
@@ -68,7 +68,7 @@ impl<'a> ArrayPartialDecoderCache<'a> {
     }
 }
 
-impl<'a> ArrayPartialDecoderTraits for ArrayPartialDecoderCache<'a> {
+impl ArrayPartialDecoderTraits for ArrayPartialDecoderCache<'_> {
     fn element_size(&self) -> usize {
         self.decoded_representation.element_size()
     }
@@ -99,7 +99,7 @@ impl<'a> ArrayPartialDecoderTraits for ArrayPartialDecoderCache<'a> {
 
 #[cfg(feature = "async")]
 #[async_trait::async_trait]
-impl<'a> AsyncArrayPartialDecoderTraits for ArrayPartialDecoderCache<'a> {
+impl AsyncArrayPartialDecoderTraits for ArrayPartialDecoderCache<'_> {
     fn element_size(&self) -> usize {
         self.decoded_representation.element_size()
     }
@@ -2,5 +2,7 @@
 
 #[cfg(feature = "bitround")]
 pub mod bitround;
+#[cfg(feature = "quantize")]
+pub mod quantize;
 #[cfg(feature = "transpose")]
 pub mod transpose;
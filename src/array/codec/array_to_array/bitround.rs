@@ -48,6 +48,38 @@ pub(crate) fn create_codec_bitround(metadata: &Metadata) -> Result<Codec, Plugin
     Ok(Codec::ArrayToArray(codec))
 }
 
+/// Return the maximum number of bits that can be kept for `data_type`.
+///
+/// This is the number of mantissa bits for a floating point data type (excluding the implicit bit) and the number of bits for an integer data type.
+/// Returns [`None`] if the data type is not supported.
+#[must_use]
+pub const fn bitround_max_keepbits(data_type: &DataType) -> Option<u32> {
+    match data_type {
+        DataType::BFloat16 => Some(7),
+        DataType::Float16 => Some(10),
+        DataType::Float32 | DataType::Complex64 => Some(23),
+        DataType::Float64 | DataType::Complex128 => Some(52),
+        DataType::UInt8 | DataType::Int8 => Some(8),
+        DataType::UInt16 | DataType::Int16 => Some(16),
+        DataType::UInt32 | DataType::Int32 => Some(32),
+        DataType::UInt64 | DataType::Int64 => Some(64),
+        _ => None,
+    }
+}
+
+/// Validate that `data_type` is supported and `keepbits` does not exceed [`bitround_max_keepbits`].
+fn validate_keepbits(data_type: &DataType, keepbits: u32) -> Result<(), CodecError> {
+    let max_keepbits = bitround_max_keepbits(data_type).ok_or_else(|| {
+        CodecError::UnsupportedDataType(data_type.clone(), IDENTIFIER.to_string())
+    })?;
+    if keepbits > max_keepbits {
+        return Err(CodecError::Other(format!(
+            "bitround keepbits {keepbits} exceeds the maximum of {max_keepbits} for data type {data_type}"
+        )));
+    }
+    Ok(())
+}
+
 fn round_bits8(mut input: u8, keepbits: u32, maxbits: u32) -> u8 {
     if keepbits < maxbits {
         let maskbits = maxbits - keepbits;
@@ -101,7 +133,7 @@ fn round_bytes(bytes: &mut [u8], data_type: &DataType, keepbits: u32) -> Result<
             bytes.iter_mut().for_each(round);
             Ok(())
         }
-        DataType::Float16 | DataType::BFloat16 => {
+        DataType::Float16 => {
             let round = |chunk: &mut [u8]| {
                 let element = u16::from_ne_bytes(chunk.try_into().unwrap());
                 let element = u16::to_ne_bytes(round_bits16(element, keepbits, 10));
@@ -110,6 +142,15 @@ fn round_bytes(bytes: &mut [u8], data_type: &DataType, keepbits: u32) -> Result<
             bytes.chunks_exact_mut(2).for_each(round);
             Ok(())
         }
+        DataType::BFloat16 => {
+            let round = |chunk: &mut [u8]| {
+                let element = u16::from_ne_bytes(chunk.try_into().unwrap());
+                let element = u16::to_ne_bytes(round_bits16(element, keepbits, 7));
+                chunk.copy_from_slice(&element);
+            };
+            bytes.chunks_exact_mut(2).for_each(round);
+            Ok(())
+        }
         DataType::UInt16 | DataType::Int16 => {
             let round = |chunk: &mut [u8]| {
                 let element = u16::from_ne_bytes(chunk.try_into().unwrap());
@@ -175,6 +216,12 @@ fn round_bytes(bytes: &mut [u8], data_type: &DataType, keepbits: u32) -> Result<
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::excessive_precision,
+    clippy::single_range_in_vec_init,
+    clippy::unreadable_literal
+)]
 mod tests {
     use std::num::NonZeroU64;
 
@@ -197,7 +244,7 @@ mod tests {
     #[test]
     fn codec_bitround_float() {
         // 1 sign bit, 8 exponent, 3 mantissa
-        const JSON: &'static str = r#"{ "keepbits": 3 }"#;
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
         let chunk_representation = ChunkRepresentation::new(
             vec![NonZeroU64::new(4).unwrap()],
             DataType::Float32,
@@ -224,7 +271,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -236,13 +283,50 @@ mod tests {
                 &CodecOptions::default(),
             )
             .unwrap();
-        let decoded_elements = crate::array::transmute_from_bytes_vec::<f32>(decoded);
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<f32>(decoded.into_owned());
+        assert_eq!(decoded_elements, &[0.0f32, 1.25f32, -8.0f32, 98304.0f32]);
+    }
+
+    #[test]
+    fn codec_bitround_bfloat16() {
+        // 1 sign bit, 8 exponent, 3 mantissa
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(4).unwrap()],
+            DataType::BFloat16,
+            half::bf16::ZERO.into(),
+        )
+        .unwrap();
+        let elements: Vec<half::bf16> = [0.0, 1.23456789, -8.3587192834, 98765.43210]
+            .into_iter()
+            .map(half::bf16::from_f32)
+            .collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+
+        let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
+        let codec = BitroundCodec::new_with_configuration(&codec_configuration);
+
+        let encoded = codec
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements: Vec<f32> =
+            crate::array::transmute_from_bytes_vec::<half::bf16>(decoded.into_owned())
+                .into_iter()
+                .map(half::bf16::to_f32)
+                .collect();
         assert_eq!(decoded_elements, &[0.0f32, 1.25f32, -8.0f32, 98304.0f32]);
     }
 
     #[test]
     fn codec_bitround_uint() {
-        const JSON: &'static str = r#"{ "keepbits": 3 }"#;
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
         let chunk_representation = ChunkRepresentation::new(
             vec![NonZeroU64::new(4).unwrap()],
             DataType::UInt32,
@@ -257,7 +341,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -269,7 +353,7 @@ mod tests {
                 &CodecOptions::default(),
             )
             .unwrap();
-        let decoded_elements = crate::array::transmute_from_bytes_vec::<u32>(decoded);
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<u32>(decoded.into_owned());
         for element in &decoded_elements {
             println!("{element} -> {element:#b}");
         }
@@ -281,7 +365,7 @@ mod tests {
 
     #[test]
     fn codec_bitround_uint8() {
-        const JSON: &'static str = r#"{ "keepbits": 3 }"#;
+        const JSON: &str = r#"{ "keepbits": 3 }"#;
         let chunk_representation = ChunkRepresentation::new(
             vec![NonZeroU64::new(4).unwrap()],
             DataType::UInt8,
@@ -296,7 +380,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -308,16 +392,82 @@ mod tests {
                 &CodecOptions::default(),
             )
             .unwrap();
-        let decoded_elements = crate::array::transmute_from_bytes_vec::<u32>(decoded);
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<u32>(decoded.into_owned());
         for element in &decoded_elements {
             println!("{element} -> {element:#b}");
         }
         assert_eq!(decoded_elements, &[0, 3, 7, 16, 16, 56, 96, 128, 224]);
     }
 
+    #[test]
+    fn codec_bitround_uint16() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(6).unwrap()],
+            DataType::UInt16,
+            0u16.into(),
+        )
+        .unwrap();
+        let elements: Vec<u16> = vec![0, 5, 1000, 1023, 40000, u16::MAX];
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+
+        let codec = BitroundCodec::new(4);
+        let encoded = codec
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        let decoded_elements = crate::array::transmute_from_bytes_vec::<u16>(decoded.into_owned());
+        assert_eq!(decoded_elements, &[0, 5, 1024, 1024, 40960, 61440]);
+    }
+
+    #[test]
+    fn codec_bitround_keepbits_validation() {
+        assert_eq!(bitround_max_keepbits(&DataType::BFloat16), Some(7));
+        assert_eq!(bitround_max_keepbits(&DataType::UInt16), Some(16));
+        assert_eq!(bitround_max_keepbits(&DataType::Bool), None);
+
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(4).unwrap()],
+            DataType::UInt8,
+            0u8.into(),
+        )
+        .unwrap();
+        assert!(BitroundCodec::new(8)
+            .compute_encoded_size(&chunk_representation)
+            .is_ok());
+        assert!(BitroundCodec::new(9)
+            .compute_encoded_size(&chunk_representation)
+            .is_err());
+        assert!(BitroundCodec::new(9)
+            .encode(
+                vec![0; 4].into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(4).unwrap()],
+            DataType::Float16,
+            half::f16::ZERO.into(),
+        )
+        .unwrap();
+        assert!(BitroundCodec::new(10)
+            .compute_encoded_size(&chunk_representation)
+            .is_ok());
+        assert!(BitroundCodec::new(11)
+            .compute_encoded_size(&chunk_representation)
+            .is_err());
+    }
+
     #[test]
     fn codec_bitround_partial_decode() {
-        const JSON: &'static str = r#"{ "keepbits": 2 }"#;
+        const JSON: &str = r#"{ "keepbits": 2 }"#;
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
         let codec = BitroundCodec::new_with_configuration(&codec_configuration);
 
@@ -332,7 +482,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -362,7 +512,7 @@ mod tests {
             .unwrap();
         let decoded_partial_chunk = decoded_partial_chunk
             .into_iter()
-            .map(|bytes| crate::array::transmute_from_bytes_vec::<f32>(bytes))
+            .map(crate::array::transmute_from_bytes_vec::<f32>)
             .collect_vec();
         let answer: &[Vec<f32>] = &[vec![3.0, 4.0], vec![16.0, 16.0, 20.0, 20.0]];
         assert_eq!(answer, decoded_partial_chunk);
@@ -371,7 +521,7 @@ mod tests {
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn codec_bitround_async_partial_decode() {
-        const JSON: &'static str = r#"{ "keepbits": 2 }"#;
+        const JSON: &str = r#"{ "keepbits": 2 }"#;
         let codec_configuration: BitroundCodecConfiguration = serde_json::from_str(JSON).unwrap();
         let codec = BitroundCodec::new_with_configuration(&codec_configuration);
 
@@ -386,7 +536,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -419,7 +569,7 @@ mod tests {
             .unwrap();
         let decoded_partial_chunk = decoded_partial_chunk
             .into_iter()
-            .map(|bytes| crate::array::transmute_from_bytes_vec::<f32>(bytes))
+            .map(crate::array::transmute_from_bytes_vec::<f32>)
             .collect_vec();
         let answer: &[Vec<f32>] = &[vec![3.0, 4.0], vec![16.0, 16.0, 20.0, 20.0]];
         assert_eq!(answer, decoded_partial_chunk);
@@ -1,11 +1,14 @@
+use std::borrow::Cow;
+
 use crate::{
     array::{
         codec::{
             options::CodecOptions, ArrayCodecTraits, ArrayPartialDecoderTraits,
             ArrayToArrayCodecTraits, CodecError, CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, ChunkRepresentation, DataType,
+        ArrayMetadataOptions, ChunkRepresentation, RawBytes,
     },
+    audit::{audit, AuditEvent},
     metadata::Metadata,
 };
 
@@ -13,7 +16,7 @@ use crate::{
 use crate::array::codec::AsyncArrayPartialDecoderTraits;
 
 use super::{
-    bitround_partial_decoder, round_bytes, BitroundCodecConfiguration,
+    bitround_partial_decoder, round_bytes, validate_keepbits, BitroundCodecConfiguration,
     BitroundCodecConfigurationV1, IDENTIFIER,
 };
 
@@ -26,12 +29,19 @@ pub struct BitroundCodec {
 impl BitroundCodec {
     /// Create a new `bitround` codec.
     ///
-    /// `keepbits` is the number of bits to round to in the floating point mantissa.
+    /// `keepbits` is the number of bits to round to in the floating point mantissa, or from the most significant set bit of an integer.
+    /// It must not exceed the [`bitround_max_keepbits`](super::bitround_max_keepbits) of the data type of the array.
     #[must_use]
     pub const fn new(keepbits: u32) -> Self {
         Self { keepbits }
     }
 
+    /// Return the number of bits to keep.
+    #[must_use]
+    pub const fn keepbits(&self) -> u32 {
+        self.keepbits
+    }
+
     /// Create a new `bitround` codec from a configuration.
     #[must_use]
     pub const fn new_with_configuration(configuration: &BitroundCodecConfiguration) -> Self {
@@ -72,26 +82,29 @@ impl ArrayCodecTraits for BitroundCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        mut decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
+        validate_keepbits(decoded_representation.data_type(), self.keepbits)?;
+        audit(&AuditEvent::LossyEncode { codec: IDENTIFIER });
+        let mut decoded_value = decoded_value.into_owned();
         round_bytes(
             &mut decoded_value,
             decoded_representation.data_type(),
             self.keepbits,
         )?;
-        Ok(decoded_value)
+        Ok(Cow::Owned(decoded_value))
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         _decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         Ok(encoded_value)
     }
 }
@@ -133,26 +146,7 @@ impl ArrayToArrayCodecTraits for BitroundCodec {
         &self,
         decoded_representation: &ChunkRepresentation,
     ) -> Result<ChunkRepresentation, CodecError> {
-        let data_type = decoded_representation.data_type();
-        match data_type {
-            DataType::Float16
-            | DataType::BFloat16
-            | DataType::Float32
-            | DataType::Float64
-            | DataType::UInt8
-            | DataType::Int8
-            | DataType::UInt16
-            | DataType::Int16
-            | DataType::UInt32
-            | DataType::Int32
-            | DataType::UInt64
-            | DataType::Int64
-            | DataType::Complex64
-            | DataType::Complex128 => Ok(decoded_representation.clone()),
-            _ => Err(CodecError::UnsupportedDataType(
-                data_type.clone(),
-                IDENTIFIER.to_string(),
-            )),
-        }
+        validate_keepbits(decoded_representation.data_type(), self.keepbits)?;
+        Ok(decoded_representation.clone())
     }
 }
@@ -23,7 +23,7 @@ pub enum BitroundCodecConfiguration {
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
 #[serde(deny_unknown_fields)]
 pub struct BitroundCodecConfigurationV1 {
-    /// The number of mantissa bits to keep for a floating point data type.
+    /// The number of mantissa bits to keep for a floating point data type, or the number of bits to keep from the most significant set bit for an integer data type.
     pub keepbits: u32,
 }
 
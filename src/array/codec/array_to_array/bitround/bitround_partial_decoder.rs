@@ -9,7 +9,7 @@ use crate::{
 #[cfg(feature = "async")]
 use crate::array::codec::AsyncArrayPartialDecoderTraits;
 
-use super::{round_bytes, IDENTIFIER};
+use super::{round_bytes, validate_keepbits};
 
 /// Partial decoder for the `bitround` codec.
 pub struct BitroundPartialDecoder<'a> {
@@ -25,28 +25,12 @@ impl<'a> BitroundPartialDecoder<'a> {
         data_type: &DataType,
         keepbits: u32,
     ) -> Result<Self, CodecError> {
-        match data_type {
-            DataType::Float16
-            | DataType::BFloat16
-            | DataType::UInt16
-            | DataType::Int16
-            | DataType::Float32
-            | DataType::Complex64
-            | DataType::UInt32
-            | DataType::Int32
-            | DataType::Float64
-            | DataType::Complex128
-            | DataType::UInt64
-            | DataType::Int64 => Ok(Self {
-                input_handle,
-                data_type: data_type.clone(),
-                keepbits,
-            }),
-            _ => Err(CodecError::UnsupportedDataType(
-                data_type.clone(),
-                IDENTIFIER.to_string(),
-            )),
-        }
+        validate_keepbits(data_type, keepbits)?;
+        Ok(Self {
+            input_handle,
+            data_type: data_type.clone(),
+            keepbits,
+        })
     }
 }
 
@@ -88,28 +72,12 @@ impl<'a> AsyncBitroundPartialDecoder<'a> {
         data_type: &DataType,
         keepbits: u32,
     ) -> Result<Self, CodecError> {
-        match data_type {
-            DataType::Float16
-            | DataType::BFloat16
-            | DataType::UInt16
-            | DataType::Int16
-            | DataType::Float32
-            | DataType::Complex64
-            | DataType::UInt32
-            | DataType::Int32
-            | DataType::Float64
-            | DataType::Complex128
-            | DataType::UInt64
-            | DataType::Int64 => Ok(Self {
-                input_handle,
-                data_type: data_type.clone(),
-                keepbits,
-            }),
-            _ => Err(CodecError::UnsupportedDataType(
-                data_type.clone(),
-                IDENTIFIER.to_string(),
-            )),
-        }
+        validate_keepbits(data_type, keepbits)?;
+        Ok(Self {
+            input_handle,
+            data_type: data_type.clone(),
+            keepbits,
+        })
     }
 }
 
@@ -0,0 +1,265 @@
+//! The `quantize` array to array codec.
+//!
+//! Quantizes floating point data to keep the specified number of decimal digits.
+//! This matches the [numcodecs Quantize](https://numcodecs.readthedocs.io/en/stable/quantize.html) codec, so the encoded values are identical to those written by zarr-python with the same number of `digits`.
+//!
+//! <div class="warning">
+//! This codec is experimental and is incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `quantize` feature, which is disabled by default.
+//!
+//! See [`QuantizeCodecConfigurationV1`] for example `JSON` metadata.
+
+mod quantize_codec;
+mod quantize_configuration;
+
+pub use quantize_codec::QuantizeCodec;
+pub use quantize_configuration::{QuantizeCodecConfiguration, QuantizeCodecConfigurationV1};
+
+use crate::{
+    array::{
+        codec::{Codec, CodecError, CodecPlugin},
+        DataType,
+    },
+    metadata::Metadata,
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+/// The identifier for the `quantize` codec.
+pub const IDENTIFIER: &str = "https://codec.zarrs.dev/array_to_array/quantize";
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_quantize, create_codec_quantize)
+}
+
+fn is_name_quantize(name: &str) -> bool {
+    name.eq(IDENTIFIER) || name == "quantize"
+}
+
+pub(crate) fn create_codec_quantize(metadata: &Metadata) -> Result<Codec, PluginCreateError> {
+    let configuration: QuantizeCodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Box::new(QuantizeCodec::new_with_configuration(&configuration));
+    Ok(Codec::ArrayToArray(codec))
+}
+
+fn validate_data_type(data_type: &DataType) -> Result<(), CodecError> {
+    match data_type {
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => Ok(()),
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+/// Return the power of two scale used to quantize to `digits` decimal digits.
+///
+/// This follows the numcodecs implementation exactly, including the use of `log(x) / log(base)` for logarithms.
+fn quantize_scale(digits: i32) -> f64 {
+    let precision = 10f64.powf(-f64::from(digits));
+    let exp = precision.ln() / 10f64.ln();
+    let exp = if exp < 0.0 { exp.floor() } else { exp.ceil() };
+    let bits = (10f64.powf(-exp).ln() / 2f64.ln()).ceil();
+    2f64.powf(bits)
+}
+
+macro_rules! round_ties_even {
+    ( $x:expr ) => {{
+        let x = $x;
+        let rounded = x.round();
+        #[allow(clippy::float_cmp)]
+        if (rounded - x).abs() == 0.5 && rounded % 2.0 != 0.0 {
+            rounded - x.signum()
+        } else {
+            rounded
+        }
+    }};
+}
+
+fn quantize_bytes(bytes: &mut [u8], data_type: &DataType, digits: i32) -> Result<(), CodecError> {
+    let scale = quantize_scale(digits);
+    match data_type {
+        DataType::Float16 => {
+            // half precision arithmetic is performed in single precision and rounded, like numpy
+            let scale = half::f16::from_f64(scale).to_f32();
+            let quantize = |chunk: &mut [u8]| {
+                let element = half::f16::from_ne_bytes(chunk.try_into().unwrap()).to_f32();
+                let scaled = half::f16::from_f32(scale * element).to_f32();
+                let element = half::f16::from_f32(round_ties_even!(scaled) / scale);
+                chunk.copy_from_slice(&element.to_ne_bytes());
+            };
+            bytes.chunks_exact_mut(2).for_each(quantize);
+            Ok(())
+        }
+        DataType::Float32 => {
+            #[allow(clippy::cast_possible_truncation)]
+            let scale = scale as f32;
+            let quantize = |chunk: &mut [u8]| {
+                let element = f32::from_ne_bytes(chunk.try_into().unwrap());
+                let element = round_ties_even!(scale * element) / scale;
+                chunk.copy_from_slice(&element.to_ne_bytes());
+            };
+            bytes.chunks_exact_mut(4).for_each(quantize);
+            Ok(())
+        }
+        DataType::Float64 => {
+            let quantize = |chunk: &mut [u8]| {
+                let element = f64::from_ne_bytes(chunk.try_into().unwrap());
+                let element = round_ties_even!(scale * element) / scale;
+                chunk.copy_from_slice(&element.to_ne_bytes());
+            };
+            bytes.chunks_exact_mut(8).for_each(quantize);
+            Ok(())
+        }
+        _ => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use crate::array::{
+        codec::{ArrayCodecTraits, ArrayToArrayCodecTraits, CodecOptions},
+        ChunkRepresentation,
+    };
+
+    use super::*;
+
+    fn linspace() -> Vec<f64> {
+        (0..10).map(|i| f64::from(i) / 9.0).collect()
+    }
+
+    #[test]
+    fn codec_quantize_float64() {
+        // Reference values from numcodecs.Quantize(digits, dtype='f8').encode(np.linspace(0, 1, 10))
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(10).unwrap()],
+            DataType::Float64,
+            0.0f64.into(),
+        )
+        .unwrap();
+        for (digits, expected) in [
+            (
+                1,
+                [
+                    0.0, 0.125, 0.25, 0.3125, 0.4375, 0.5625, 0.6875, 0.75, 0.875, 1.0,
+                ],
+            ),
+            (
+                2,
+                [
+                    0.0,
+                    0.109_375,
+                    0.218_75,
+                    0.335_937_5,
+                    0.445_312_5,
+                    0.554_687_5,
+                    0.664_062_5,
+                    0.781_25,
+                    0.890_625,
+                    1.0,
+                ],
+            ),
+        ] {
+            let codec = QuantizeCodec::new(digits);
+            let bytes = crate::array::transmute_to_bytes_vec(linspace());
+            let encoded = codec
+                .encode(
+                    bytes.into(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded = codec
+                .decode(encoded, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            let decoded_elements =
+                crate::array::transmute_from_bytes_vec::<f64>(decoded.into_owned());
+            assert_eq!(decoded_elements, expected);
+        }
+    }
+
+    #[test]
+    fn codec_quantize_float32_float16() {
+        let codec_configuration: QuantizeCodecConfiguration =
+            serde_json::from_str(r#"{ "digits": 1 }"#).unwrap();
+        let codec = QuantizeCodec::new_with_configuration(&codec_configuration);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let elements: Vec<f32> = linspace().into_iter().map(|x| x as f32).collect();
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(10).unwrap()],
+            DataType::Float32,
+            0.0f32.into(),
+        )
+        .unwrap();
+        let encoded = codec
+            .encode(
+                crate::array::transmute_to_bytes_vec(elements.clone()).into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            crate::array::transmute_from_bytes_vec::<f32>(encoded.into_owned()),
+            [0.0, 0.125, 0.25, 0.3125, 0.4375, 0.5625, 0.6875, 0.75, 0.875, 1.0]
+        );
+
+        let elements: Vec<half::f16> = elements.into_iter().map(half::f16::from_f32).collect();
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(10).unwrap()],
+            DataType::Float16,
+            half::f16::ZERO.into(),
+        )
+        .unwrap();
+        let encoded = codec
+            .encode(
+                crate::array::transmute_to_bytes_vec(elements).into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let encoded: Vec<f32> =
+            crate::array::transmute_from_bytes_vec::<half::f16>(encoded.into_owned())
+                .into_iter()
+                .map(half::f16::to_f32)
+                .collect();
+        assert_eq!(
+            encoded,
+            [0.0, 0.125, 0.25, 0.3125, 0.4375, 0.5625, 0.6875, 0.75, 0.875, 1.0]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn codec_quantize_round_ties_even() {
+        assert_eq!(round_ties_even!(2.5f64), 2.0);
+        assert_eq!(round_ties_even!(3.5f64), 4.0);
+        assert_eq!(round_ties_even!(-2.5f32), -2.0);
+        assert_eq!(round_ties_even!(-2.4f32), -2.0);
+        assert_eq!(quantize_scale(0), 1.0);
+        assert_eq!(quantize_scale(3), 1024.0);
+        assert_eq!(quantize_scale(-1), 0.125);
+    }
+
+    #[test]
+    fn codec_quantize_unsupported() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(1).unwrap()],
+            DataType::Int32,
+            0i32.into(),
+        )
+        .unwrap();
+        assert!(QuantizeCodec::new(2)
+            .compute_encoded_size(&chunk_representation)
+            .is_err());
+    }
+}
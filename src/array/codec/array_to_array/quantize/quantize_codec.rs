@@ -0,0 +1,141 @@
+use std::borrow::Cow;
+
+use crate::{
+    array::{
+        codec::{
+            options::CodecOptions, ArrayCodecTraits, ArrayPartialDecoderTraits,
+            ArrayToArrayCodecTraits, CodecError, CodecTraits, RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, ChunkRepresentation, RawBytes,
+    },
+    audit::{audit, AuditEvent},
+    metadata::Metadata,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncArrayPartialDecoderTraits;
+
+use super::{
+    quantize_bytes, validate_data_type, QuantizeCodecConfiguration, QuantizeCodecConfigurationV1,
+    IDENTIFIER,
+};
+
+/// A `quantize` codec implementation.
+#[derive(Clone, Debug, Default)]
+pub struct QuantizeCodec {
+    digits: i32,
+}
+
+impl QuantizeCodec {
+    /// Create a new `quantize` codec.
+    ///
+    /// `digits` is the number of decimal digits to keep.
+    #[must_use]
+    pub const fn new(digits: i32) -> Self {
+        Self { digits }
+    }
+
+    /// Create a new `quantize` codec from a configuration.
+    #[must_use]
+    pub const fn new_with_configuration(configuration: &QuantizeCodecConfiguration) -> Self {
+        let QuantizeCodecConfiguration::V1(configuration) = configuration;
+        Self {
+            digits: configuration.digits,
+        }
+    }
+
+    /// Return the number of decimal digits to keep.
+    #[must_use]
+    pub const fn digits(&self) -> i32 {
+        self.digits
+    }
+}
+
+impl CodecTraits for QuantizeCodec {
+    fn create_metadata_opt(&self, options: &ArrayMetadataOptions) -> Option<Metadata> {
+        if options.experimental_codec_store_metadata_if_encode_only() {
+            let configuration = QuantizeCodecConfigurationV1 {
+                digits: self.digits,
+            };
+            Some(Metadata::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap())
+        } else {
+            None
+        }
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for QuantizeCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        audit(&AuditEvent::LossyEncode { codec: IDENTIFIER });
+        let mut decoded_value = decoded_value.into_owned();
+        quantize_bytes(
+            &mut decoded_value,
+            decoded_representation.data_type(),
+            self.digits,
+        )?;
+        Ok(Cow::Owned(decoded_value))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(encoded_value)
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToArrayCodecTraits for QuantizeCodec {
+    fn partial_decoder<'a>(
+        &'a self,
+        input_handle: Box<dyn ArrayPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, CodecError> {
+        // Decoding is the identity transform
+        validate_data_type(decoded_representation.data_type())?;
+        Ok(input_handle)
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder<'a>(
+        &'a self,
+        input_handle: Box<dyn AsyncArrayPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, CodecError> {
+        // Decoding is the identity transform
+        validate_data_type(decoded_representation.data_type())?;
+        Ok(input_handle)
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<ChunkRepresentation, CodecError> {
+        validate_data_type(decoded_representation.data_type())?;
+        Ok(decoded_representation.clone())
+    }
+}
@@ -0,0 +1,65 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// A wrapper to handle various versions of `quantize` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum QuantizeCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(QuantizeCodecConfigurationV1),
+}
+
+/// `quantize` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example: Keep 3 decimal digits
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "digits": 3
+/// }
+/// # "#;
+/// # let configuration: zarrs::array::codec::QuantizeCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+pub struct QuantizeCodecConfigurationV1 {
+    /// The number of decimal digits to keep.
+    pub digits: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metadata::Metadata;
+
+    use super::*;
+
+    #[test]
+    fn codec_quantize_metadata() {
+        serde_json::from_str::<Metadata>(
+            r#"{
+            "name": "quantize",
+            "configuration": {
+                "digits": 3
+            }
+        }"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn codec_quantize_config() {
+        serde_json::from_str::<QuantizeCodecConfiguration>(
+            r#"{
+                "digits": -1
+            }"#,
+        )
+        .unwrap();
+        assert!(serde_json::from_str::<QuantizeCodecConfiguration>(
+            r#"{
+                "digits": 3,
+                "dtype": "<f8"
+            }"#,
+        )
+        .is_err());
+    }
+}
@@ -9,12 +9,13 @@ mod transpose_configuration;
 mod transpose_partial_decoder;
 
 pub use transpose_codec::{InvalidPermutationError, TransposeCodec};
+
 pub use transpose_configuration::{
     TransposeCodecConfiguration, TransposeCodecConfigurationV1, TransposeOrder,
 };
 
 use crate::{
-    array::codec::{Codec, CodecPlugin},
+    array::codec::{Codec, CodecError, CodecPlugin},
     metadata::Metadata,
     plugin::{PluginCreateError, PluginMetadataInvalidError},
 };
@@ -97,6 +98,74 @@ fn transpose_array(
     }
 }
 
+/// Transpose `bytes` of an array with `shape` in C (row-major) order with `order`.
+///
+/// Returns the bytes of the transposed array in C order, which has the shape of `shape` permuted by `order`.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the dimensionality of `order` does not match `shape`, or the length of `bytes` does not match `shape` and `element_size`.
+pub fn transpose_bytes(
+    bytes: Vec<u8>,
+    shape: &[u64],
+    order: &TransposeOrder,
+    element_size: usize,
+) -> Result<Vec<u8>, CodecError> {
+    if order.0.len() != shape.len() {
+        return Err(CodecError::Other(format!(
+            "transpose order {:?} is incompatible with shape {shape:?}",
+            order.0
+        )));
+    }
+    let expected_size = shape.iter().product::<u64>() * element_size as u64;
+    if bytes.len() as u64 != expected_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            bytes.len(),
+            expected_size,
+        ));
+    }
+    transpose_array(
+        &calculate_order_encode(order, shape.len()),
+        shape,
+        element_size,
+        bytes,
+    )
+    .map_err(|err| CodecError::Other(err.to_string()))
+}
+
+/// Convert `bytes` of an array with `shape` from F (column-major) order to C (row-major) order.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the length of `bytes` does not match `shape` and `element_size`.
+pub fn f_order_to_c_order(
+    bytes: Vec<u8>,
+    shape: &[u64],
+    element_size: usize,
+) -> Result<Vec<u8>, CodecError> {
+    if shape.is_empty() {
+        return Ok(bytes);
+    }
+    // F order bytes are the C order bytes of the array with reversed shape
+    let shape_reversed: Vec<u64> = shape.iter().rev().copied().collect();
+    let order = TransposeOrder((0..shape.len()).rev().collect());
+    transpose_bytes(bytes, &shape_reversed, &order, element_size)
+}
+
+/// Convert `bytes` of an array with `shape` from C (row-major) order to F (column-major) order.
+///
+/// # Errors
+/// Returns a [`CodecError`] if the length of `bytes` does not match `shape` and `element_size`.
+pub fn c_order_to_f_order(
+    bytes: Vec<u8>,
+    shape: &[u64],
+    element_size: usize,
+) -> Result<Vec<u8>, CodecError> {
+    if shape.is_empty() {
+        return Ok(bytes);
+    }
+    let order = TransposeOrder((0..shape.len()).rev().collect());
+    transpose_bytes(bytes, shape, &order, element_size)
+}
+
 fn permute<T: Copy>(v: &[T], order: &TransposeOrder) -> Vec<T> {
     let mut vec = Vec::<T>::with_capacity(v.len());
     for axis in &order.0 {
@@ -106,6 +175,7 @@ fn permute<T: Copy>(v: &[T], order: &TransposeOrder) -> Vec<T> {
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
 mod tests {
     use std::num::NonZeroU64;
 
@@ -140,7 +210,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -148,7 +218,7 @@ mod tests {
         let decoded = codec
             .decode(encoded, &chunk_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
 
         // let array = ndarray::ArrayViewD::from_shape(array_representation.shape(), &bytes).unwrap();
         // let array_representation_transpose =
@@ -178,6 +248,25 @@ mod tests {
         codec_transpose_round_trip_impl(JSON, DataType::UInt16, FillValue::from(0u16));
     }
 
+    #[test]
+    fn codec_transpose_c_f_order() {
+        // [[0, 1, 2], [3, 4, 5]] in C order
+        let c: Vec<u8> = (0..6).collect();
+        let f = c_order_to_f_order(c.clone(), &[2, 3], 1).unwrap();
+        assert_eq!(f, vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(f_order_to_c_order(f, &[2, 3], 1).unwrap(), c);
+
+        let order = TransposeOrder::new(&[1, 0]).unwrap();
+        let transposed = transpose_bytes(c.clone(), &[2, 3], &order, 1).unwrap();
+        assert_eq!(transposed, vec![0, 3, 1, 4, 2, 5]);
+        assert_eq!(
+            transpose_bytes(transposed, &[3, 2], &order.inverse(), 1).unwrap(),
+            c
+        );
+        assert!(transpose_bytes(c.clone(), &[2, 2], &order, 1).is_err());
+        assert!(transpose_bytes(c, &[6], &order, 1).is_err());
+    }
+
     #[test]
     fn codec_transpose_partial_decode() {
         let codec = TransposeCodec::new(TransposeOrder::new(&[1, 0]).unwrap());
@@ -193,7 +282,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -224,7 +313,7 @@ mod tests {
             .unwrap();
         let decoded_partial_chunk = decoded_partial_chunk
             .into_iter()
-            .map(|bytes| crate::array::transmute_from_bytes_vec::<f32>(bytes))
+            .map(crate::array::transmute_from_bytes_vec::<f32>)
             .collect::<Vec<_>>();
         let answer: &[Vec<f32>] = &[
             vec![
@@ -253,7 +342,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -287,7 +376,7 @@ mod tests {
             .unwrap();
         let decoded_partial_chunk = decoded_partial_chunk
             .into_iter()
-            .map(|bytes| crate::array::transmute_from_bytes_vec::<f32>(bytes))
+            .map(crate::array::transmute_from_bytes_vec::<f32>)
             .collect::<Vec<_>>();
         let answer: &[Vec<f32>] = &[
             vec![
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use derive_more::From;
 use thiserror::Error;
 
@@ -7,7 +9,7 @@ use crate::{
             options::CodecOptions, ArrayCodecTraits, ArrayPartialDecoderTraits,
             ArrayToArrayCodecTraits, CodecError, CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, ChunkRepresentation,
+        ArrayMetadataOptions, ChunkRepresentation, RawBytes,
     },
     metadata::Metadata,
     plugin::PluginCreateError,
@@ -127,12 +129,12 @@ impl ArrayCodecTraits for TransposeCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         if decoded_value.len() as u64 != decoded_representation.size() {
             return Err(CodecError::UnexpectedChunkDecodedSize(
                 decoded_value.len(),
@@ -146,17 +148,18 @@ impl ArrayCodecTraits for TransposeCodec {
             &order_encode,
             &decoded_representation.shape_u64(),
             decoded_representation.element_size(),
-            decoded_value,
+            decoded_value.into_owned(),
         )
+        .map(Cow::Owned)
         .map_err(|_| CodecError::UnexpectedChunkDecodedSize(len, decoded_representation.size()))
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         let order_decode =
             calculate_order_decode(&self.order, decoded_representation.shape().len());
         let transposed_shape = permute(&decoded_representation.shape_u64(), &self.order);
@@ -165,8 +168,9 @@ impl ArrayCodecTraits for TransposeCodec {
             &order_decode,
             &transposed_shape,
             decoded_representation.element_size(),
-            encoded_value,
+            encoded_value.into_owned(),
         )
+        .map(Cow::Owned)
         .map_err(|_| CodecError::UnexpectedChunkDecodedSize(len, decoded_representation.size()))
     }
 }
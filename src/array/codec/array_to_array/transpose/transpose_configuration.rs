@@ -46,6 +46,60 @@ impl TransposeOrder {
             Err(InvalidPermutationError::from(order.to_vec()))
         }
     }
+
+    /// Create the identity [`TransposeOrder`] `[0, 1, …, n-1]` for `dimensionality` n.
+    ///
+    /// Transposing with this order preserves C (row-major) order.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPermutationError`] if `dimensionality` is zero.
+    pub fn new_c(dimensionality: usize) -> Result<Self, InvalidPermutationError> {
+        Self::new(&(0..dimensionality).collect::<Vec<_>>())
+    }
+
+    /// Create the reversed [`TransposeOrder`] `[n-1, …, 1, 0]` for `dimensionality` n.
+    ///
+    /// Transposing with this order converts between C (row-major) and F (column-major) order.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPermutationError`] if `dimensionality` is zero.
+    pub fn new_f(dimensionality: usize) -> Result<Self, InvalidPermutationError> {
+        Self::new(&(0..dimensionality).rev().collect::<Vec<_>>())
+    }
+
+    /// Return the dimensionality of the order.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if this is the identity order.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        self.0.iter().enumerate().all(|(i, axis)| i == *axis)
+    }
+
+    /// Return the inverse order, which undoes a transpose with this order.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let mut inverse = vec![0; self.0.len()];
+        for (i, axis) in self.0.iter().enumerate() {
+            inverse[*axis] = i;
+        }
+        Self(inverse)
+    }
+
+    /// Return the order equivalent to transposing with this order followed by `other`.
+    ///
+    /// # Errors
+    /// Returns [`InvalidPermutationError`] if the dimensionality of `other` does not match.
+    pub fn then(&self, other: &Self) -> Result<Self, InvalidPermutationError> {
+        if self.0.len() == other.0.len() {
+            Ok(Self(other.0.iter().map(|axis| self.0[*axis]).collect()))
+        } else {
+            Err(InvalidPermutationError::from(other.0.clone()))
+        }
+    }
 }
 
 impl<'de> serde::Deserialize<'de> for TransposeOrder {
@@ -73,6 +127,24 @@ mod tests {
         serde_json::from_str::<TransposeCodecConfiguration>(json).unwrap();
     }
 
+    #[test]
+    fn codec_transpose_order() {
+        let order = TransposeOrder::new(&[2, 0, 1]).unwrap();
+        assert_eq!(order.dimensionality(), 3);
+        assert!(!order.is_identity());
+        assert_eq!(order.inverse(), TransposeOrder::new(&[1, 2, 0]).unwrap());
+        assert!(order.then(&order.inverse()).unwrap().is_identity());
+        assert!(order.inverse().then(&order).unwrap().is_identity());
+        assert_eq!(
+            order.then(&TransposeOrder::new_f(3).unwrap()).unwrap(),
+            TransposeOrder::new(&[1, 0, 2]).unwrap()
+        );
+        assert!(order.then(&TransposeOrder::new_f(2).unwrap()).is_err());
+        assert!(TransposeOrder::new_c(3).unwrap().is_identity());
+        assert!(TransposeOrder::new_c(0).is_err());
+        assert!(TransposeOrder::new_f(0).is_err());
+    }
+
     #[test]
     fn codec_transpose_invalid1() {
         let json = r#"{
@@ -3,6 +3,10 @@
 pub mod bytes;
 pub mod codec_chain;
 
+#[cfg(feature = "n5")]
+pub mod n5;
+#[cfg(feature = "packbits")]
+pub mod packbits;
 #[cfg(feature = "pcodec")]
 pub mod pcodec;
 #[cfg(feature = "sharding")]
@@ -59,6 +59,16 @@ impl Endianness {
     pub fn is_native(self) -> bool {
         self == NATIVE_ENDIAN
     }
+
+    /// Convert the elements in `v` of `data_type` from this endianness to the endianness of the CPU.
+    pub fn to_native(self, v: &mut [u8], data_type: &DataType) {
+        convert_endianness(v, self, NATIVE_ENDIAN, data_type);
+    }
+
+    /// Convert the elements in `v` of `data_type` from the endianness of the CPU to this endianness.
+    pub fn from_native(self, v: &mut [u8], data_type: &DataType) {
+        convert_endianness(v, NATIVE_ENDIAN, self, data_type);
+    }
 }
 
 impl serde::Serialize for Endianness {
@@ -93,10 +103,33 @@ pub const NATIVE_ENDIAN: Endianness = if cfg!(target_endian = "big") {
     Endianness::Little
 };
 
+/// Convert the endianness of bytes for a given data type from `from` to `to`.
+///
+/// This is a no-op if `from` and `to` are equal.
+pub fn convert_endianness(v: &mut [u8], from: Endianness, to: Endianness, data_type: &DataType) {
+    if from != to {
+        reverse_endianness(v, data_type);
+    }
+}
+
+/// Returns true if the endianness of elements of `data_type` can be reversed by [`reverse_endianness`].
+///
+/// Returns false for multi-byte extension data types, which have no known element layout.
+#[must_use]
+pub fn supports_endianness_conversion(data_type: &DataType) -> bool {
+    !matches!(data_type, DataType::Extension(_)) || data_type.size() <= 1
+}
+
 /// Reverse the endianness of bytes for a given data type.
+///
+/// Extension data types are left unchanged, see [`supports_endianness_conversion`].
 pub fn reverse_endianness(v: &mut [u8], data_type: &DataType) {
     match data_type {
-        DataType::Bool | DataType::Int8 | DataType::UInt8 | DataType::RawBits(_) => {}
+        DataType::Bool
+        | DataType::Int8
+        | DataType::UInt8
+        | DataType::RawBits(_)
+        | DataType::Extension(_) => {}
         DataType::Int16 | DataType::UInt16 | DataType::Float16 | DataType::BFloat16 => {
             let swap = |chunk: &mut [u8]| {
                 let bytes = u16::from_ne_bytes(unsafe { chunk.try_into().unwrap_unchecked() });
@@ -111,7 +144,12 @@ pub fn reverse_endianness(v: &mut [u8], data_type: &DataType) {
             };
             v.chunks_exact_mut(4).for_each(swap);
         }
-        DataType::Int64 | DataType::UInt64 | DataType::Float64 | DataType::Complex128 => {
+        DataType::Int64
+        | DataType::UInt64
+        | DataType::Float64
+        | DataType::Complex128
+        | DataType::DateTime64(_)
+        | DataType::TimeDelta64(_) => {
             let swap = |chunk: &mut [u8]| {
                 let bytes = u64::from_ne_bytes(unsafe { chunk.try_into().unwrap_unchecked() });
                 chunk.copy_from_slice(bytes.swap_bytes().to_ne_bytes().as_slice());
@@ -122,6 +160,7 @@ pub fn reverse_endianness(v: &mut [u8], data_type: &DataType) {
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation)]
 mod tests {
     use std::num::NonZeroU64;
 
@@ -161,7 +200,7 @@ mod tests {
 
     #[test]
     fn codec_bytes_configuration_none() {
-        let codec_configuration: BytesCodecConfiguration = serde_json::from_str(r#"{}"#).unwrap();
+        let codec_configuration: BytesCodecConfiguration = serde_json::from_str("{}").unwrap();
         let codec = BytesCodec::new_with_configuration(&codec_configuration);
         let metadata = codec.create_metadata().unwrap();
         assert_eq!(
@@ -183,14 +222,14 @@ mod tests {
         let codec = BytesCodec::new(endianness);
 
         let encoded = codec.encode(
-            bytes.clone(),
+            bytes.clone().into(),
             &chunk_representation,
             &CodecOptions::default(),
         )?;
         let decoded = codec
             .decode(encoded, &chunk_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
         Ok(())
     }
 
@@ -267,6 +306,101 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn codec_bytes_encoded_byte_order() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(2).unwrap()],
+            DataType::UInt32,
+            FillValue::from(0u32),
+        )
+        .unwrap();
+        let elements: Vec<u32> = vec![0x0102_0304, 0x0506_0708];
+        let bytes = crate::array::transmute_to_bytes_vec(elements.clone());
+
+        let encoded = BytesCodec::big()
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(*encoded, [1, 2, 3, 4, 5, 6, 7, 8]);
+        let encoded = BytesCodec::little()
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(*encoded, [4, 3, 2, 1, 8, 7, 6, 5]);
+
+        let decoded = BytesCodec::big()
+            .decode(
+                vec![1, 2, 3, 4, 5, 6, 7, 8].into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            crate::array::transmute_from_bytes_vec::<u32>(decoded.into_owned()),
+            elements
+        );
+    }
+
+    #[test]
+    fn codec_bytes_encoded_byte_order_complex64() {
+        let chunk_representation = ChunkRepresentation::new(
+            vec![NonZeroU64::new(1).unwrap()],
+            DataType::Complex64,
+            FillValue::from(num::complex::Complex32::new(0.0, 0.0)),
+        )
+        .unwrap();
+        let bytes = crate::array::transmute_to_bytes_vec(vec![1.0f32, 2.0f32]);
+        let encoded = BytesCodec::big()
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            encoded,
+            [1.0f32.to_be_bytes(), 2.0f32.to_be_bytes()].concat()
+        );
+    }
+
+    #[test]
+    fn endianness_convert() {
+        let mut bytes = 0x0102u16.to_ne_bytes().to_vec();
+        Endianness::Big.from_native(&mut bytes, &DataType::UInt16);
+        assert_eq!(bytes, [1, 2]);
+        Endianness::Big.to_native(&mut bytes, &DataType::UInt16);
+        assert_eq!(bytes, 0x0102u16.to_ne_bytes());
+        convert_endianness(
+            &mut bytes,
+            Endianness::Little,
+            Endianness::Little,
+            &DataType::UInt16,
+        );
+        assert_eq!(bytes, 0x0102u16.to_ne_bytes());
+    }
+
+    #[cfg(target_endian = "big")]
+    #[test]
+    fn endianness_native_big() {
+        assert_eq!(NATIVE_ENDIAN, Endianness::Big);
+        assert!(Endianness::Big.is_native());
+        assert!(!Endianness::Little.is_native());
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn endianness_native_little() {
+        assert_eq!(NATIVE_ENDIAN, Endianness::Little);
+        assert!(Endianness::Little.is_native());
+        assert!(!Endianness::Big.is_native());
+    }
+
     #[test]
     fn codec_bytes_round_trip_i32_endianness_none() {
         assert!(codec_bytes_round_trip_impl(None, DataType::Int32, FillValue::from(0)).is_err());
@@ -316,7 +450,11 @@ mod tests {
         let codec = BytesCodec::new(None);
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -355,7 +493,11 @@ mod tests {
         let codec = BytesCodec::new(None);
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
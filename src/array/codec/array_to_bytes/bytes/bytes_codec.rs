@@ -1,14 +1,17 @@
+use std::borrow::Cow;
+
 // Note: No validation that this codec is created *without* a specified endianness for multi-byte data types.
 
 use crate::{
     array::{
         codec::{
             ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
-            BytesPartialDecoderTraits, CodecError, CodecOptions, CodecTraits,
+            BytesPartialDecoderTraits, CodecError, CodecOptions, CodecTraits, PartialDecodePlan,
             RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType, RawBytes,
     },
+    array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
     metadata::Metadata,
 };
 
@@ -17,7 +20,7 @@ use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecod
 
 use super::{
     bytes_configuration::BytesCodecConfigurationV1, bytes_partial_decoder, reverse_endianness,
-    BytesCodecConfiguration, Endianness, IDENTIFIER, NATIVE_ENDIAN,
+    supports_endianness_conversion, BytesCodecConfiguration, Endianness, IDENTIFIER, NATIVE_ENDIAN,
 };
 
 /// A `bytes` codec implementation.
@@ -60,11 +63,22 @@ impl BytesCodec {
         Self::new(configuration.endian)
     }
 
-    fn do_encode_or_decode(
+    fn validate_endianness(&self, data_type: &DataType) -> Result<(), CodecError> {
+        match self.endian {
+            Some(endian) if !endian.is_native() && !supports_endianness_conversion(data_type) => {
+                Err(CodecError::Other(format!(
+                    "cannot convert data type {data_type} to {endian} endian"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn do_encode_or_decode<'a>(
         &self,
-        mut value: Vec<u8>,
+        value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         if value.len() as u64 != decoded_representation.size() {
             return Err(CodecError::UnexpectedChunkDecodedSize(
                 value.len(),
@@ -73,13 +87,16 @@ impl BytesCodec {
         } else if decoded_representation.element_size() > 1 && self.endian.is_none() {
             return Err(CodecError::Other(format!(
                 "tried to encode an array with element size {} with endianness None",
-                decoded_representation.size()
+                decoded_representation.element_size()
             )));
         }
+        self.validate_endianness(decoded_representation.data_type())?;
 
         if let Some(endian) = &self.endian {
             if !endian.is_native() {
+                let mut value = value.into_owned();
                 reverse_endianness(&mut value, decoded_representation.data_type());
+                return Ok(Cow::Owned(value));
             }
         }
         Ok(value)
@@ -123,21 +140,21 @@ impl ArrayCodecTraits for BytesCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         self.do_encode_or_decode(decoded_value, decoded_representation)
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         self.do_encode_or_decode(encoded_value, decoded_representation)
     }
 }
@@ -150,6 +167,7 @@ impl ArrayToBytesCodecTraits for BytesCodec {
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, CodecError> {
+        self.validate_endianness(decoded_representation.data_type())?;
         Ok(Box::new(bytes_partial_decoder::BytesPartialDecoder::new(
             input_handle,
             decoded_representation.clone(),
@@ -164,6 +182,7 @@ impl ArrayToBytesCodecTraits for BytesCodec {
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
     ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, CodecError> {
+        self.validate_endianness(decoded_representation.data_type())?;
         Ok(Box::new(
             bytes_partial_decoder::AsyncBytesPartialDecoder::new(
                 input_handle,
@@ -181,4 +200,26 @@ impl ArrayToBytesCodecTraits for BytesCodec {
             decoded_representation.num_elements() * decoded_representation.element_size() as u64,
         ))
     }
+    fn partial_decode_plan(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+        decoded_regions: &[ArraySubset],
+        _index: Option<&[u8]>,
+    ) -> Result<PartialDecodePlan, CodecError> {
+        let chunk_shape = decoded_representation.shape_u64();
+        let mut byte_ranges = Vec::new();
+        for decoded_region in decoded_regions {
+            byte_ranges.extend(
+                decoded_region
+                    .byte_ranges(&chunk_shape, decoded_representation.element_size())
+                    .map_err(|_| {
+                        IncompatibleArraySubsetAndShapeError::from((
+                            decoded_region.clone(),
+                            chunk_shape.clone(),
+                        ))
+                    })?,
+            );
+        }
+        Ok(PartialDecodePlan::ByteRanges(byte_ranges))
+    }
 }
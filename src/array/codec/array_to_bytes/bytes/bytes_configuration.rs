@@ -46,7 +46,7 @@ mod tests {
 
     #[test]
     fn codec_bytes_empty() {
-        serde_json::from_str::<BytesCodecConfiguration>(r#"{}"#).unwrap();
+        serde_json::from_str::<BytesCodecConfiguration>("{}").unwrap();
     }
 
     #[test]
@@ -1,16 +1,20 @@
 //! An array to bytes codec formed by joining an array to array sequence, array to bytes, and bytes to bytes sequence of codecs.
 
+use std::borrow::Cow;
+
 use crate::{
     array::{
         codec::{
             ArrayCodecTraits, ArrayPartialDecoderCache, ArrayPartialDecoderTraits,
             ArrayToArrayCodecTraits, ArrayToBytesCodecTraits, BytesPartialDecoderCache,
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, Codec, CodecError, CodecOptions,
-            CodecTraits,
+            CodecTraits, PartialDecodePlan,
         },
         concurrency::RecommendedConcurrency,
         ArrayMetadataOptions, ArrayView, BytesRepresentation, ChunkRepresentation, ChunkShape,
+        RawBytes,
     },
+    array_subset::ArraySubset,
     metadata::Metadata,
     plugin::PluginCreateError,
 };
@@ -18,6 +22,16 @@ use crate::{
 #[cfg(feature = "async")]
 use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
 
+/// Add the name of `codec` to the context of a codec error.
+///
+/// Hidden codecs without metadata (e.g. a cache) are not added.
+fn codec_error<T: CodecTraits + ?Sized>(codec: &T, err: CodecError) -> CodecError {
+    match codec.create_metadata() {
+        Some(metadata) => err.with_codec(metadata.name()),
+        None => err,
+    }
+}
+
 /// A codec chain is a sequence of array to array, a bytes to bytes, and a sequence of array to bytes codecs.
 ///
 /// A codec chain partial decoder may insert a cache: [`ArrayPartialDecoderCache`] or [`BytesPartialDecoderCache`].
@@ -248,7 +262,7 @@ impl ArrayToBytesCodecTraits for CodecChain {
 
         if Some(codec_index) == self.cache_index {
             input_handle = Box::new(BytesPartialDecoderCache::new(&*input_handle, options)?);
-        };
+        }
 
         let mut input_handle = {
             let array_representation = array_representations.last().unwrap();
@@ -257,14 +271,17 @@ impl ArrayToBytesCodecTraits for CodecChain {
             codec.partial_decoder(input_handle, array_representation, options)?
         };
 
-        for (codec, array_representation) in std::iter::zip(
+        for (codec, array_representations) in std::iter::zip(
             self.array_to_array.iter().rev(),
-            array_representations.iter().rev().skip(1),
+            array_representations.windows(2).rev(),
         ) {
+            let [array_representation, encoded_representation] = array_representations else {
+                unreachable!()
+            };
             if Some(codec_index) == self.cache_index {
                 input_handle = Box::new(ArrayPartialDecoderCache::new(
                     &*input_handle,
-                    array_representation.clone(),
+                    encoded_representation.clone(),
                     options,
                 )?);
             }
@@ -313,7 +330,7 @@ impl ArrayToBytesCodecTraits for CodecChain {
         if Some(codec_index) == self.cache_index {
             input_handle =
                 Box::new(BytesPartialDecoderCache::async_new(&*input_handle, options).await?);
-        };
+        }
 
         let mut input_handle = {
             let array_representation = array_representations.last().unwrap();
@@ -324,15 +341,18 @@ impl ArrayToBytesCodecTraits for CodecChain {
                 .await?
         };
 
-        for (codec, array_representation) in std::iter::zip(
+        for (codec, array_representations) in std::iter::zip(
             self.array_to_array.iter().rev(),
-            array_representations.iter().rev().skip(1),
+            array_representations.windows(2).rev(),
         ) {
+            let [array_representation, encoded_representation] = array_representations else {
+                unreachable!()
+            };
             if Some(codec_index) == self.cache_index {
                 input_handle = Box::new(
                     ArrayPartialDecoderCache::async_new(
                         &*input_handle,
-                        array_representation.clone(),
+                        encoded_representation.clone(),
                         options,
                     )
                     .await?,
@@ -377,6 +397,22 @@ impl ArrayToBytesCodecTraits for CodecChain {
 
         Ok(bytes_representation)
     }
+
+    fn partial_decode_plan(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+        decoded_regions: &[ArraySubset],
+        index: Option<&[u8]>,
+    ) -> Result<PartialDecodePlan, CodecError> {
+        // Byte ranges can only be planned if the array to bytes codec output is stored as is
+        if self.array_to_array.is_empty() && self.bytes_to_bytes.is_empty() {
+            self.array_to_bytes
+                .partial_decode_plan(decoded_representation, decoded_regions, index)
+                .map_err(|err| codec_error(&*self.array_to_bytes, err))
+        } else {
+            Ok(PartialDecodePlan::Whole)
+        }
+    }
 }
 
 impl ArrayCodecTraits for CodecChain {
@@ -426,12 +462,23 @@ impl ArrayCodecTraits for CodecChain {
         Ok(recommended_concurrency)
     }
 
-    fn encode(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(
+                decoded_size = decoded_representation.size(),
+                encoded_size = tracing::field::Empty,
+            )
+        )
+    )]
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         if decoded_value.len() as u64 != decoded_representation.size() {
             return Err(CodecError::UnexpectedChunkDecodedSize(
                 decoded_value.len(),
@@ -444,33 +491,51 @@ impl ArrayCodecTraits for CodecChain {
         let mut value = decoded_value;
         // array->array
         for codec in &self.array_to_array {
-            value = codec.encode(value, &decoded_representation, options)?;
+            value = codec
+                .encode(value, &decoded_representation, options)
+                .map_err(|err| codec_error(&**codec, err))?;
             decoded_representation = codec.compute_encoded_size(&decoded_representation)?;
         }
 
         // array->bytes
         value = self
             .array_to_bytes
-            .encode(value, &decoded_representation, options)?;
+            .encode(value, &decoded_representation, options)
+            .map_err(|err| codec_error(&*self.array_to_bytes, err))?;
         let mut decoded_representation = self
             .array_to_bytes
             .compute_encoded_size(&decoded_representation)?;
 
         // bytes->bytes
         for codec in &self.bytes_to_bytes {
-            value = codec.encode(value, options)?;
+            value = codec
+                .encode(value, options)
+                .map_err(|err| codec_error(&**codec, err))?;
             decoded_representation = codec.compute_encoded_size(&decoded_representation);
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("encoded_size", value.len());
         Ok(value)
     }
 
-    fn decode(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(
+                decoded_size = decoded_representation.size(),
+                encoded_size = encoded_value.len(),
+            )
+        )
+    )]
+    fn decode<'a>(
         &self,
-        mut encoded_value: Vec<u8>,
+        mut encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         let array_representations =
             self.get_array_representations(decoded_representation.clone())?;
         let bytes_representations =
@@ -481,22 +546,29 @@ impl ArrayCodecTraits for CodecChain {
             self.bytes_to_bytes.iter().rev(),
             bytes_representations.iter().rev().skip(1),
         ) {
-            encoded_value = codec.decode(encoded_value, bytes_representation, options)?;
+            encoded_value = codec
+                .decode(encoded_value, bytes_representation, options)
+                .map_err(|err| codec_error(&**codec, err))?;
         }
 
         // bytes->array
-        encoded_value = self.array_to_bytes.decode(
-            encoded_value,
-            array_representations.last().unwrap(),
-            options,
-        )?;
+        encoded_value = self
+            .array_to_bytes
+            .decode(
+                encoded_value,
+                array_representations.last().unwrap(),
+                options,
+            )
+            .map_err(|err| codec_error(&*self.array_to_bytes, err))?;
 
         // array->array
         for (codec, array_representation) in std::iter::zip(
             self.array_to_array.iter().rev(),
             array_representations.iter().rev().skip(1),
         ) {
-            encoded_value = codec.decode(encoded_value, array_representation, options)?;
+            encoded_value = codec
+                .decode(encoded_value, array_representation, options)
+                .map_err(|err| codec_error(&**codec, err))?;
         }
 
         if encoded_value.len() as u64 != decoded_representation.size() {
@@ -509,6 +581,17 @@ impl ArrayCodecTraits for CodecChain {
         Ok(encoded_value)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(
+                decoded_size = decoded_representation.size(),
+                encoded_size = encoded_value.len(),
+            )
+        )
+    )]
     fn decode_into_array_view(
         &self,
         encoded_value: &[u8],
@@ -524,47 +607,59 @@ impl ArrayCodecTraits for CodecChain {
         if self.bytes_to_bytes.is_empty() && self.array_to_array.is_empty() {
             // Shortcut path if no bytes to bytes or array to array codecs
             // TODO: This shouldn't be necessary with appropriate optimisations detailed in below FIXME
-            return self.array_to_bytes.decode_into_array_view(
-                encoded_value,
-                array_representations.last().unwrap(),
-                array_view,
-                options,
-            );
+            return self
+                .array_to_bytes
+                .decode_into_array_view(
+                    encoded_value,
+                    array_representations.last().unwrap(),
+                    array_view,
+                    options,
+                )
+                .map_err(|err| codec_error(&*self.array_to_bytes, err));
         }
 
         // Default path
-        let mut encoded_value = encoded_value.to_vec();
+        let mut encoded_value = Cow::Borrowed(encoded_value);
 
         // bytes->bytes
         for (codec, bytes_representation) in std::iter::zip(
             self.bytes_to_bytes.iter().rev(),
             bytes_representations.iter().rev().skip(1),
         ) {
-            encoded_value = codec.decode(encoded_value, bytes_representation, options)?;
+            encoded_value = codec
+                .decode(encoded_value, bytes_representation, options)
+                .map_err(|err| codec_error(&**codec, err))?;
         }
 
         if self.array_to_array.is_empty() {
             // bytes->array
-            self.array_to_bytes.decode_into_array_view(
-                &encoded_value,
-                array_representations.last().unwrap(),
-                array_view,
-                options,
-            )
+            self.array_to_bytes
+                .decode_into_array_view(
+                    &encoded_value,
+                    array_representations.last().unwrap(),
+                    array_view,
+                    options,
+                )
+                .map_err(|err| codec_error(&*self.array_to_bytes, err))
         } else {
             // bytes->array
-            encoded_value = self.array_to_bytes.decode(
-                encoded_value,
-                array_representations.last().unwrap(),
-                options,
-            )?;
+            encoded_value = self
+                .array_to_bytes
+                .decode(
+                    encoded_value,
+                    array_representations.last().unwrap(),
+                    options,
+                )
+                .map_err(|err| codec_error(&*self.array_to_bytes, err))?;
 
             // array->array
             for (codec, array_representation) in std::iter::zip(
                 self.array_to_array.iter().rev(),
                 array_representations.iter().rev().skip(1),
             ) {
-                encoded_value = codec.decode(encoded_value, array_representation, options)?;
+                encoded_value = codec
+                    .decode(encoded_value, array_representation, options)
+                    .map_err(|err| codec_error(&**codec, err))?;
             }
 
             if encoded_value.len() as u64 != decoded_representation.size() {
@@ -576,26 +671,11 @@ impl ArrayCodecTraits for CodecChain {
 
             // FIXME: the last array to array can decode into array_view
             //        Could also identify which filters are passthrough (e.g. bytes if endianness is native/none, transpose in C order, etc.)
-            let decoded_value = encoded_value;
-            let contiguous_indices = unsafe {
-                array_view
-                    .subset()
-                    .contiguous_linearised_indices_unchecked(array_view.array_shape())
-            };
-            let element_size = decoded_representation.element_size();
-            let length = contiguous_indices.contiguous_elements_usize() * element_size;
-            let mut decoded_offset = 0;
-            // FIXME: Par iteration?
-            let output = unsafe { array_view.bytes_mut() };
-            for (array_subset_element_index, _num_elements) in &contiguous_indices {
-                let output_offset =
-                    usize::try_from(array_subset_element_index).unwrap() * element_size;
-                debug_assert!((output_offset + length) <= output.len());
-                debug_assert!((decoded_offset + length) <= decoded_value.len());
-                output[output_offset..output_offset + length]
-                    .copy_from_slice(&decoded_value[decoded_offset..decoded_offset + length]);
-                decoded_offset += length;
-            }
+            array_view.copy_from_bytes(
+                &encoded_value,
+                decoded_representation.element_size(),
+                options,
+            );
             Ok(())
         }
     }
@@ -614,6 +694,7 @@ impl ArrayCodecTraits for CodecChain {
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_precision_loss, clippy::needless_pass_by_value)]
 mod tests {
     use std::num::NonZeroU64;
 
@@ -726,7 +807,7 @@ mod tests {
 
         let encoded = codec
             .encode(
-                bytes.clone(),
+                bytes.clone().into(),
                 &chunk_representation,
                 &CodecOptions::default(),
             )
@@ -741,7 +822,7 @@ mod tests {
         if not_just_bytes {
             assert_ne!(encoded, decoded);
         }
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
 
         // let encoded = codec
         //     .par_encode(bytes.clone(), &chunk_representation)
@@ -752,7 +833,7 @@ mod tests {
         // if not_just_bytes {
         //     assert_ne!(encoded, decoded);
         // }
-        // assert_eq!(bytes, decoded);
+        // assert_eq!(bytes, *decoded);
 
         let input_handle = Box::new(std::io::Cursor::new(encoded));
         let partial_decoder = codec
@@ -763,7 +844,7 @@ mod tests {
             )
             .unwrap();
         let decoded_partial_chunk = partial_decoder
-            .partial_decode_opt(&decoded_regions, &CodecOptions::default())
+            .partial_decode_opt(decoded_regions, &CodecOptions::default())
             .unwrap();
 
         let decoded_partial_chunk: Vec<f32> = decoded_partial_chunk
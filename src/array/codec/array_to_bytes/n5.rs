@@ -0,0 +1,490 @@
+//! The `n5` array to bytes codec.
+//!
+//! Encodes a chunk as an [N5](https://github.com/saalfeldlab/n5) block, enabling N5 datasets to be read through the [`Array`](crate::array::Array) API (see [`zarrs::n5`](crate::n5)).
+//!
+//! An N5 block has a big endian header with the block mode, the number of dimensions, and the shape of the block.
+//! `varlength` blocks (mode `1`) additionally store the number of elements in the header.
+//! The header is followed by the (optionally compressed) big endian elements of the block in column-major order.
+//!
+//! The decoded representation of this codec is in the *reverse* dimension order of the N5 block, such that column-major N5 blocks are row-major chunks.
+//! A `transpose` codec that reverses the dimensions precedes this codec to present arrays in N5 dimension order.
+//! N5 blocks at the edge of a dataset may be smaller than the chunk shape, in which case the remaining elements are the fill value.
+//! Encoded blocks are always the full chunk shape.
+//!
+//! <div class="warning">
+//! This codec is experimental and is incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `n5` feature, which is disabled by default.
+//!
+//! See [`N5CodecConfigurationV1`] for example `JSON` metadata.
+
+mod n5_codec;
+mod n5_configuration;
+mod n5_partial_decoder;
+
+pub use n5_codec::N5Codec;
+pub use n5_configuration::{N5CodecConfiguration, N5CodecConfigurationV1, N5Compression};
+
+use crate::{
+    array::{
+        codec::{BytesToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin},
+        BytesRepresentation, ChunkRepresentation, DataType, RawBytes,
+    },
+    metadata::Metadata,
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+use super::bytes::{reverse_endianness, Endianness};
+
+/// The identifier for the `n5` codec.
+pub const IDENTIFIER: &str = "https://codec.zarrs.dev/array_to_bytes/n5";
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_n5, create_codec_n5)
+}
+
+fn is_name_n5(name: &str) -> bool {
+    name.eq(IDENTIFIER) || name == "n5"
+}
+
+pub(crate) fn create_codec_n5(metadata: &Metadata) -> Result<Codec, PluginCreateError> {
+    let configuration: N5CodecConfiguration = metadata
+        .to_configuration()
+        .map_err(|_| PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()))?;
+    let codec = Box::new(N5Codec::new_with_configuration(&configuration)?);
+    Ok(Codec::ArrayToBytes(codec))
+}
+
+/// The default N5 block mode.
+const MODE_DEFAULT: u16 = 0;
+
+/// The N5 block mode with the number of elements in the header.
+const MODE_VARLENGTH: u16 = 1;
+
+/// An N5 block header.
+struct N5BlockHeader {
+    /// The block shape in N5 (column-major) dimension order.
+    shape: Vec<u64>,
+    /// The number of elements in the block.
+    num_elements: u64,
+    /// The length of the header in bytes.
+    len: usize,
+}
+
+impl N5BlockHeader {
+    /// Parse the header of an encoded N5 block.
+    fn parse(bytes: &[u8]) -> Result<Self, CodecError> {
+        let invalid = || CodecError::Other("n5 block header is invalid".to_string());
+        let read_u16 = |offset: usize| -> Result<u16, CodecError> {
+            bytes
+                .get(offset..offset + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .ok_or_else(invalid)
+        };
+        let read_u32 = |offset: usize| -> Result<u32, CodecError> {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or_else(invalid)
+        };
+
+        let mode = read_u16(0)?;
+        let dimensionality = usize::from(read_u16(2)?);
+        let shape = (0..dimensionality)
+            .map(|i| read_u32(4 + 4 * i).map(u64::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut len = 4 + 4 * dimensionality;
+        let num_elements = match mode {
+            MODE_DEFAULT => shape.iter().product(),
+            MODE_VARLENGTH => {
+                let num_elements = read_u32(len)?;
+                len += 4;
+                u64::from(num_elements)
+            }
+            _ => {
+                return Err(CodecError::Other(format!(
+                    "n5 block mode {mode} is not supported"
+                )))
+            }
+        };
+        Ok(Self {
+            shape,
+            num_elements,
+            len,
+        })
+    }
+
+    /// Encode a default mode N5 block header for a block with `shape` in N5 dimension order.
+    fn encode(shape: &[u64]) -> Result<Vec<u8>, CodecError> {
+        let too_large = || CodecError::Other("n5 block shape is too large".to_string());
+        let mut header = Vec::with_capacity(4 + 4 * shape.len());
+        header.extend(MODE_DEFAULT.to_be_bytes());
+        header.extend(
+            u16::try_from(shape.len())
+                .map_err(|_| too_large())?
+                .to_be_bytes(),
+        );
+        for &size in shape {
+            header.extend(u32::try_from(size).map_err(|_| too_large())?.to_be_bytes());
+        }
+        Ok(header)
+    }
+}
+
+/// Return an error if the data type of `decoded_representation` is not supported by N5.
+fn validate_data_type(decoded_representation: &ChunkRepresentation) -> Result<(), CodecError> {
+    match decoded_representation.data_type() {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64 => Ok(()),
+        data_type => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+/// Create the bytes to bytes codec for N5 `compression` of elements with `data_type`, or [`None`] if uncompressed.
+fn compressor(
+    compression: &N5Compression,
+    #[allow(unused_variables)] data_type: &DataType,
+) -> Result<Option<Box<dyn BytesToBytesCodecTraits>>, CodecError> {
+    #[allow(unused_variables)]
+    let invalid = |err: &dyn std::fmt::Display| {
+        CodecError::Other(format!("n5 compression is invalid: {err}"))
+    };
+    match compression {
+        N5Compression::Raw => Ok(None),
+        #[cfg(feature = "gzip")]
+        N5Compression::Gzip {
+            level,
+            use_zlib: false,
+        } => {
+            let level = u32::try_from(*level).unwrap_or(6);
+            let codec = crate::array::codec::GzipCodec::new(level).map_err(|err| invalid(&err))?;
+            Ok(Some(Box::new(codec)))
+        }
+        #[cfg(feature = "bz2")]
+        N5Compression::Bzip2 { block_size } => {
+            use crate::array::codec::bytes_to_bytes::bz2::Bz2CompressionLevel;
+            let level = Bz2CompressionLevel::new(*block_size).map_err(|err| invalid(&err))?;
+            Ok(Some(Box::new(crate::array::codec::Bz2Codec::new(level))))
+        }
+        #[cfg(feature = "blosc")]
+        N5Compression::Blosc {
+            cname,
+            clevel,
+            shuffle,
+            blocksize,
+            ..
+        } => {
+            use crate::array::codec::bytes_to_bytes::blosc::{
+                BloscCompressionLevel, BloscCompressor, BloscShuffleMode,
+            };
+            let cname: BloscCompressor =
+                serde_json::from_value(serde_json::Value::String(cname.clone()))
+                    .map_err(|err| invalid(&err))?;
+            let clevel = BloscCompressionLevel::try_from(*clevel).map_err(|err| invalid(&err))?;
+            let shuffle = match shuffle {
+                0 => BloscShuffleMode::NoShuffle,
+                1 => BloscShuffleMode::Shuffle,
+                2 => BloscShuffleMode::BitShuffle,
+                shuffle => return Err(invalid(shuffle)),
+            };
+            let blocksize = (*blocksize != 0).then_some(*blocksize);
+            let codec = crate::array::codec::BloscCodec::new(
+                cname,
+                clevel,
+                blocksize,
+                shuffle,
+                Some(data_type.size()),
+            )
+            .map_err(|err| invalid(&err))?;
+            Ok(Some(Box::new(codec)))
+        }
+        #[cfg(feature = "zstd")]
+        N5Compression::Zstd { level } => Ok(Some(Box::new(crate::array::codec::ZstdCodec::new(
+            *level, false,
+        )))),
+        compression => Err(CodecError::Other(format!(
+            "n5 compression {} is not supported",
+            serde_json::to_string(compression).unwrap_or_default()
+        ))),
+    }
+}
+
+/// Decode an N5 block into a chunk with `decoded_representation`.
+fn decode_block(
+    compression: &N5Compression,
+    encoded_value: &[u8],
+    decoded_representation: &ChunkRepresentation,
+    options: &CodecOptions,
+) -> Result<Vec<u8>, CodecError> {
+    validate_data_type(decoded_representation)?;
+    let header = N5BlockHeader::parse(encoded_value)?;
+    let dimensionality = decoded_representation.dimensionality();
+    if header.shape.len() != dimensionality {
+        return Err(CodecError::Other(format!(
+            "n5 block has {} dimensions, expected {dimensionality}",
+            header.shape.len()
+        )));
+    }
+
+    let data_type = decoded_representation.data_type();
+    let element_size = data_type.size();
+    let data = RawBytes::Borrowed(&encoded_value[header.len..]);
+    let mut elements = match compressor(compression, data_type)? {
+        Some(compressor) => compressor
+            .decode(data, &BytesRepresentation::UnboundedSize, options)?
+            .into_owned(),
+        None => data.into_owned(),
+    };
+    let num_elements = usize::try_from(header.num_elements).unwrap();
+    if elements.len() < num_elements * element_size {
+        return Err(CodecError::UnexpectedChunkDecodedSize(
+            elements.len(),
+            header.num_elements * element_size as u64,
+        ));
+    }
+    elements.truncate(num_elements * element_size);
+    if !Endianness::Big.is_native() {
+        reverse_endianness(&mut elements, data_type);
+    }
+
+    // Reverse the N5 block shape to match the row-major chunk
+    let block_shape: Vec<u64> = header.shape.into_iter().rev().collect();
+    let chunk_shape = decoded_representation.shape_u64();
+    if block_shape == chunk_shape && elements.len() as u64 == decoded_representation.size() {
+        return Ok(elements);
+    }
+
+    // Pad varlength blocks and copy edge blocks into a chunk filled with the fill value
+    let fill_value = decoded_representation.fill_value().as_ne_bytes();
+    let block_num_elements = usize::try_from(block_shape.iter().product::<u64>()).unwrap();
+    for _ in num_elements..block_num_elements {
+        elements.extend_from_slice(fill_value);
+    }
+    let mut chunk = fill_value.repeat(decoded_representation.num_elements_usize());
+    copy_overlap(
+        &elements,
+        &block_shape,
+        &mut chunk,
+        &chunk_shape,
+        element_size,
+    );
+    Ok(chunk)
+}
+
+/// Copy the elements in the overlapping region of a row-major `src` and `dst` array.
+fn copy_overlap(
+    src: &[u8],
+    src_shape: &[u64],
+    dst: &mut [u8],
+    dst_shape: &[u64],
+    element_size: usize,
+) {
+    let overlap: Vec<usize> = std::iter::zip(src_shape, dst_shape)
+        .map(|(&src, &dst)| usize::try_from(src.min(dst)).unwrap())
+        .collect();
+    if overlap.contains(&0) {
+        return;
+    }
+    let strides = |shape: &[u64]| -> Vec<usize> {
+        let mut strides = vec![element_size; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * usize::try_from(shape[i + 1]).unwrap();
+        }
+        strides
+    };
+    let src_strides = strides(src_shape);
+    let dst_strides = strides(dst_shape);
+    let Some((&row_len, outer)) = overlap.split_last() else {
+        dst[..element_size].copy_from_slice(&src[..element_size]);
+        return;
+    };
+    let row_len = row_len * element_size;
+
+    // Iterate over the rows of the overlap
+    let mut indices = vec![0; outer.len()];
+    loop {
+        let src_offset: usize = std::iter::zip(&indices, &src_strides)
+            .map(|(i, s)| i * s)
+            .sum();
+        let dst_offset: usize = std::iter::zip(&indices, &dst_strides)
+            .map(|(i, s)| i * s)
+            .sum();
+        dst[dst_offset..dst_offset + row_len]
+            .copy_from_slice(&src[src_offset..src_offset + row_len]);
+        let Some(dim) = (0..outer.len())
+            .rev()
+            .find(|&dim| indices[dim] + 1 < outer[dim])
+        else {
+            break;
+        };
+        indices[dim] += 1;
+        indices[dim + 1..].fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use crate::{
+        array::{
+            codec::{ArrayCodecTraits, ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayMetadataOptions, ChunkRepresentation, DataType, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    fn chunk_representation(shape: &[u64]) -> ChunkRepresentation {
+        let shape = shape
+            .iter()
+            .map(|&size| NonZeroU64::new(size).unwrap())
+            .collect();
+        ChunkRepresentation::new(shape, DataType::UInt16, FillValue::from(7u16)).unwrap()
+    }
+
+    fn elements(num_elements: u16) -> Vec<u8> {
+        (0..num_elements).flat_map(u16::to_ne_bytes).collect()
+    }
+
+    #[test]
+    fn codec_n5_metadata() {
+        let codec = N5Codec::new(N5Compression::Raw);
+        let metadata = codec
+            .create_metadata_opt(&ArrayMetadataOptions::default())
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&metadata).unwrap(),
+            r#"{"name":"https://codec.zarrs.dev/array_to_bytes/n5","configuration":{"compression":{"type":"raw"}}}"#
+        );
+        assert!(create_codec_n5(&metadata).is_ok());
+    }
+
+    #[test]
+    fn codec_n5_round_trip() {
+        let chunk_representation = chunk_representation(&[3, 4]);
+        let bytes = elements(12);
+        for compression in [
+            N5Compression::Raw,
+            #[cfg(feature = "gzip")]
+            N5Compression::Gzip {
+                level: -1,
+                use_zlib: false,
+            },
+            #[cfg(feature = "zstd")]
+            N5Compression::Zstd { level: 3 },
+            #[cfg(feature = "blosc")]
+            N5Compression::Blosc {
+                cname: "lz4".to_string(),
+                clevel: 5,
+                shuffle: 1,
+                blocksize: 0,
+                nthreads: 1,
+            },
+        ] {
+            let codec = N5Codec::new(compression);
+            let encoded = codec
+                .encode(
+                    bytes.clone().into(),
+                    &chunk_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            // mode 0, 2 dimensions, N5 block shape [4, 3]
+            assert_eq!(&encoded[..12], &[0, 0, 0, 2, 0, 0, 0, 4, 0, 0, 0, 3]);
+            let decoded = codec
+                .decode(encoded, &chunk_representation, &CodecOptions::default())
+                .unwrap();
+            assert_eq!(bytes, *decoded);
+        }
+    }
+
+    #[test]
+    fn codec_n5_decode_edge_and_varlength() {
+        let chunk_representation = chunk_representation(&[3, 4]);
+        let codec = N5Codec::new(N5Compression::Raw);
+
+        // An edge block with N5 shape [2, 2], i.e. a 2x2 row-major block
+        let mut encoded = vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 2];
+        encoded.extend([0, 1, 0, 2, 0, 3, 0, 4]);
+        let decoded = codec
+            .decode(
+                encoded.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let expected: Vec<u8> = [1u16, 2, 7, 7, 3, 4, 7, 7, 7, 7, 7, 7]
+            .into_iter()
+            .flat_map(u16::to_ne_bytes)
+            .collect();
+        assert_eq!(decoded, expected);
+
+        // A varlength block with 3 elements
+        let mut encoded = vec![0, 1, 0, 2, 0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0, 3];
+        encoded.extend([0, 1, 0, 2, 0, 3]);
+        let decoded = codec
+            .decode(
+                encoded.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let expected: Vec<u8> = [1u16, 2, 3, 7, 7, 7, 7, 7, 7, 7, 7, 7]
+            .into_iter()
+            .flat_map(u16::to_ne_bytes)
+            .collect();
+        assert_eq!(decoded, expected);
+
+        // An object block
+        let encoded = vec![0, 2, 0, 2, 0, 0, 0, 4, 0, 0, 0, 3];
+        assert!(codec
+            .decode(
+                encoded.into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn codec_n5_partial_decode() {
+        let chunk_representation = chunk_representation(&[3, 4]);
+        let bytes = elements(12);
+        let codec = N5Codec::new(N5Compression::Raw);
+        let encoded = codec
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let input_handle = Box::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded = partial_decoder
+            .partial_decode(&[ArraySubset::new_with_ranges(&[1..3, 1..2])])
+            .unwrap();
+        let expected: Vec<u8> = [5u16, 9].into_iter().flat_map(u16::to_ne_bytes).collect();
+        assert_eq!(decoded[0], expected);
+    }
+}
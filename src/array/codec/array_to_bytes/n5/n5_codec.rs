@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
+            BytesPartialDecoderTraits, CodecError, CodecOptions, CodecTraits,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType, RawBytes,
+    },
+    metadata::Metadata,
+    plugin::PluginCreateError,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    super::bytes::{reverse_endianness, Endianness},
+    compressor, decode_block, n5_partial_decoder, validate_data_type, N5BlockHeader,
+    N5CodecConfiguration, N5CodecConfigurationV1, N5Compression, IDENTIFIER,
+};
+
+/// An `n5` codec implementation.
+#[derive(Debug, Clone, Default)]
+pub struct N5Codec {
+    compression: N5Compression,
+}
+
+impl N5Codec {
+    /// Create a new `n5` codec with N5 `compression`.
+    #[must_use]
+    pub const fn new(compression: N5Compression) -> Self {
+        Self { compression }
+    }
+
+    /// Create a new `n5` codec from configuration.
+    ///
+    /// # Errors
+    /// Returns [`PluginCreateError`] if the compression is invalid or not supported.
+    pub fn new_with_configuration(
+        configuration: &N5CodecConfiguration,
+    ) -> Result<Self, PluginCreateError> {
+        let N5CodecConfiguration::V1(configuration) = configuration;
+        // Validate the compression
+        compressor(&configuration.compression, &DataType::UInt8)
+            .map_err(|err| PluginCreateError::Other(err.to_string()))?;
+        Ok(Self::new(configuration.compression.clone()))
+    }
+
+    /// Return the N5 compression.
+    #[must_use]
+    pub const fn compression(&self) -> &N5Compression {
+        &self.compression
+    }
+}
+
+impl CodecTraits for N5Codec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<Metadata> {
+        let configuration = N5CodecConfigurationV1 {
+            compression: self.compression.clone(),
+        };
+        Some(Metadata::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        true
+    }
+}
+
+impl ArrayCodecTraits for N5Codec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        if decoded_value.len() as u64 != decoded_representation.size() {
+            return Err(CodecError::UnexpectedChunkDecodedSize(
+                decoded_value.len(),
+                decoded_representation.size(),
+            ));
+        }
+
+        let data_type = decoded_representation.data_type();
+        let elements = if Endianness::Big.is_native() {
+            decoded_value
+        } else {
+            let mut elements = decoded_value.into_owned();
+            reverse_endianness(&mut elements, data_type);
+            Cow::Owned(elements)
+        };
+        let elements = match compressor(&self.compression, data_type)? {
+            Some(compressor) => compressor.encode(elements, options)?,
+            None => elements,
+        };
+
+        let block_shape: Vec<u64> = decoded_representation
+            .shape_u64()
+            .into_iter()
+            .rev()
+            .collect();
+        let mut encoded_value = N5BlockHeader::encode(&block_shape)?;
+        encoded_value.extend_from_slice(&elements);
+        Ok(Cow::Owned(encoded_value))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(decode_block(
+            &self.compression,
+            &encoded_value,
+            decoded_representation,
+            options,
+        )?))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for N5Codec {
+    fn partial_decoder<'a>(
+        &self,
+        input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        Ok(Box::new(n5_partial_decoder::N5PartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+            self.compression.clone(),
+        )))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder<'a>(
+        &'a self,
+        input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        Ok(Box::new(n5_partial_decoder::AsyncN5PartialDecoder::new(
+            input_handle,
+            decoded_representation.clone(),
+            self.compression.clone(),
+        )))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        validate_data_type(decoded_representation)?;
+        let header_size = 4 + 4 * decoded_representation.dimensionality() as u64;
+        Ok(match self.compression {
+            N5Compression::Raw => {
+                BytesRepresentation::FixedSize(header_size + decoded_representation.size())
+            }
+            _ => BytesRepresentation::UnboundedSize,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// A wrapper to handle various versions of `n5` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum N5CodecConfiguration {
+    /// Version 1.0 draft.
+    V1(N5CodecConfigurationV1),
+}
+
+/// `n5` codec configuration parameters (version 1.0 draft).
+///
+/// ### Example
+/// ```rust
+/// # let JSON = r#"
+/// {
+///     "compression": {
+///         "type": "gzip",
+///         "level": 5
+///     }
+/// }
+/// # "#;
+/// # let configuration: zarrs::array::codec::N5CodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display(fmt = "{}", "serde_json::to_string(self).unwrap_or_default()")]
+pub struct N5CodecConfigurationV1 {
+    /// The compression of the N5 block data.
+    pub compression: N5Compression,
+}
+
+/// The compression of N5 block data, matching the `compression` field of N5 dataset attributes.
+///
+/// See <https://github.com/saalfeldlab/n5#file-system-specification>.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Default)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum N5Compression {
+    /// No compression.
+    #[default]
+    Raw,
+    /// Gzip compression. Requires the `gzip` feature.
+    Gzip {
+        /// The compression level, where `-1` is the default level.
+        #[serde(default = "n5_gzip_level_default")]
+        level: i32,
+        /// Use the zlib format instead of the gzip format. This is not supported.
+        #[serde(default, rename = "useZlib")]
+        use_zlib: bool,
+    },
+    /// Bzip2 compression. Requires the `bz2` feature.
+    Bzip2 {
+        /// The block size in units of 100 kB, which is also the compression level.
+        #[serde(default = "n5_bzip2_block_size_default", rename = "blockSize")]
+        block_size: u32,
+    },
+    /// Blosc compression. Requires the `blosc` feature.
+    Blosc {
+        /// The blosc compressor name.
+        cname: String,
+        /// The blosc compression level.
+        clevel: u8,
+        /// The blosc shuffle mode, where `0` is no shuffle, `1` is byte shuffle, and `2` is bit shuffle.
+        shuffle: u8,
+        /// The blosc block size, where `0` is automatic.
+        #[serde(default)]
+        blocksize: usize,
+        /// The number of blosc threads. This is ignored.
+        #[serde(default = "n5_blosc_nthreads_default")]
+        nthreads: usize,
+    },
+    /// Zstd compression. Requires the `zstd` feature.
+    Zstd {
+        /// The compression level.
+        #[serde(default = "n5_zstd_level_default")]
+        level: i32,
+    },
+    /// Xz compression. This is not supported.
+    Xz {
+        /// The compression preset.
+        #[serde(default = "n5_xz_preset_default")]
+        preset: u32,
+    },
+    /// Lz4 compression. This is not supported.
+    Lz4 {
+        /// The lz4 block size.
+        #[serde(default = "n5_lz4_block_size_default", rename = "blockSize")]
+        block_size: u32,
+    },
+}
+
+const fn n5_gzip_level_default() -> i32 {
+    -1
+}
+
+const fn n5_bzip2_block_size_default() -> u32 {
+    9
+}
+
+const fn n5_blosc_nthreads_default() -> usize {
+    1
+}
+
+const fn n5_zstd_level_default() -> i32 {
+    3
+}
+
+const fn n5_xz_preset_default() -> u32 {
+    6
+}
+
+const fn n5_lz4_block_size_default() -> u32 {
+    65536
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_n5_config() {
+        let configuration: N5CodecConfiguration =
+            serde_json::from_str(r#"{"compression":{"type":"raw"}}"#).unwrap();
+        assert_eq!(
+            configuration,
+            N5CodecConfiguration::V1(N5CodecConfigurationV1 {
+                compression: N5Compression::Raw
+            })
+        );
+        let configuration: N5CodecConfiguration = serde_json::from_str(
+            r#"{"compression":{"type":"blosc","cname":"lz4","clevel":5,"shuffle":1,"blocksize":0,"nthreads":1}}"#,
+        )
+        .unwrap();
+        let N5CodecConfiguration::V1(configuration) = configuration;
+        assert!(matches!(
+            configuration.compression,
+            N5Compression::Blosc { clevel: 5, .. }
+        ));
+        assert!(serde_json::from_str::<N5CodecConfiguration>(
+            r#"{"compression":{"type":"unknown"}}"#
+        )
+        .is_err());
+    }
+}
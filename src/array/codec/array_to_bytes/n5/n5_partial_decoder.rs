@@ -0,0 +1,145 @@
+use crate::{
+    array::{
+        codec::{
+            ArrayPartialDecoderTraits, ArraySubset, BytesPartialDecoderTraits, CodecError,
+            CodecOptions,
+        },
+        ChunkRepresentation, MaybeBytes,
+    },
+    array_subset::IncompatibleArraySubsetAndShapeError,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{decode_block, N5Compression};
+
+/// Partial decoder for the `n5` codec.
+pub struct N5PartialDecoder<'a> {
+    input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+    compression: N5Compression,
+}
+
+impl<'a> N5PartialDecoder<'a> {
+    /// Create a new partial decoder for the `n5` codec.
+    pub fn new(
+        input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+        compression: N5Compression,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+            compression,
+        }
+    }
+}
+
+fn do_partial_decode(
+    encoded: MaybeBytes,
+    decoded_regions: &[ArraySubset],
+    decoded_representation: &ChunkRepresentation,
+    compression: &N5Compression,
+    options: &CodecOptions,
+) -> Result<Vec<Vec<u8>>, CodecError> {
+    let Some(encoded) = encoded else {
+        return Ok(decoded_regions
+            .iter()
+            .map(|array_subset| {
+                decoded_representation
+                    .fill_value()
+                    .as_ne_bytes()
+                    .repeat(array_subset.num_elements_usize())
+            })
+            .collect());
+    };
+    let chunk_shape = decoded_representation.shape_u64();
+    let decoded = decode_block(compression, &encoded, decoded_representation, options)?;
+    decoded_regions
+        .iter()
+        .map(|array_subset| {
+            array_subset
+                .extract_bytes(
+                    &decoded,
+                    &chunk_shape,
+                    decoded_representation.element_size(),
+                )
+                .map_err(|_| {
+                    IncompatibleArraySubsetAndShapeError::from((
+                        array_subset.clone(),
+                        chunk_shape.clone(),
+                    ))
+                    .into()
+                })
+        })
+        .collect()
+}
+
+impl ArrayPartialDecoderTraits for N5PartialDecoder<'_> {
+    fn element_size(&self) -> usize {
+        self.decoded_representation.element_size()
+    }
+
+    fn partial_decode_opt(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<Vec<u8>>, CodecError> {
+        let encoded = self.input_handle.decode(options)?;
+        do_partial_decode(
+            encoded,
+            decoded_regions,
+            &self.decoded_representation,
+            &self.compression,
+            options,
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `n5` codec.
+pub struct AsyncN5PartialDecoder<'a> {
+    input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+    compression: N5Compression,
+}
+
+#[cfg(feature = "async")]
+impl<'a> AsyncN5PartialDecoder<'a> {
+    /// Create a new asynchronous partial decoder for the `n5` codec.
+    pub fn new(
+        input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+        compression: N5Compression,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+            compression,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncN5PartialDecoder<'_> {
+    fn element_size(&self) -> usize {
+        self.decoded_representation.element_size()
+    }
+
+    async fn partial_decode_opt(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<Vec<u8>>, CodecError> {
+        let encoded = self.input_handle.decode(options).await?;
+        do_partial_decode(
+            encoded,
+            decoded_regions,
+            &self.decoded_representation,
+            &self.compression,
+            options,
+        )
+    }
+}
@@ -0,0 +1,228 @@
+//! The `packbits` array to bytes codec.
+//!
+//! Packs the elements of a `bool` array into bits, storing 8 elements per byte.
+//! Element `i` is stored in bit `i % 8` (least significant bit first) of byte `i / 8`.
+//! The unused bits of the last byte are zero.
+//!
+//! <div class="warning">
+//! This codec is experimental and is incompatible with other Zarr V3 implementations.
+//! </div>
+//!
+//! This codec requires the `packbits` feature, which is disabled by default.
+//!
+//! See [`PackBitsCodecConfigurationV1`] for example `JSON` metadata.
+
+mod packbits_codec;
+mod packbits_configuration;
+mod packbits_partial_decoder;
+
+pub use packbits_codec::PackBitsCodec;
+pub use packbits_configuration::{PackBitsCodecConfiguration, PackBitsCodecConfigurationV1};
+
+use crate::{
+    array::codec::{Codec, CodecPlugin},
+    metadata::Metadata,
+    plugin::{PluginCreateError, PluginMetadataInvalidError},
+};
+
+/// The identifier for the `packbits` codec.
+pub const IDENTIFIER: &str = "https://codec.zarrs.dev/array_to_bytes/packbits";
+
+// Register the codec.
+inventory::submit! {
+    CodecPlugin::new(IDENTIFIER, is_name_packbits, create_codec_packbits)
+}
+
+fn is_name_packbits(name: &str) -> bool {
+    name.eq(IDENTIFIER) || name == "packbits"
+}
+
+pub(crate) fn create_codec_packbits(metadata: &Metadata) -> Result<Codec, PluginCreateError> {
+    if metadata.configuration_is_none_or_empty() {
+        let codec = Box::new(PackBitsCodec::new());
+        Ok(Codec::ArrayToBytes(codec))
+    } else {
+        Err(PluginCreateError::MetadataInvalid(
+            PluginMetadataInvalidError::new(IDENTIFIER, "codec", metadata.clone()),
+        ))
+    }
+}
+
+/// Return the number of bytes needed to pack `num_elements` bits.
+const fn packed_size(num_elements: u64) -> u64 {
+    num_elements.div_ceil(8)
+}
+
+/// Pack `bools` into bits. Nonzero elements are `true`.
+fn pack_bits(bools: &[u8]) -> Vec<u8> {
+    bools
+        .chunks(8)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0u8, |byte, (bit, &value)| {
+                byte | (u8::from(value != 0) << bit)
+            })
+        })
+        .collect()
+}
+
+/// Unpack `num_elements` bits starting at bit `bit_offset` of `bytes` into elements of `0` or `1`.
+fn unpack_bits(bytes: &[u8], bit_offset: usize, num_elements: usize) -> Vec<u8> {
+    (bit_offset..bit_offset + num_elements)
+        .map(|bit| (bytes[bit / 8] >> (bit % 8)) & 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use crate::{
+        array::{
+            codec::{ArrayCodecTraits, ArrayToBytesCodecTraits, CodecOptions, CodecTraits},
+            ArrayMetadataOptions, ChunkRepresentation, ChunkShape, DataType, FillValue,
+        },
+        array_subset::ArraySubset,
+    };
+
+    use super::*;
+
+    fn elements(num_elements: u64) -> Vec<u8> {
+        (0..num_elements).map(|i| u8::from(i % 3 == 0)).collect()
+    }
+
+    #[test]
+    fn codec_packbits_metadata() {
+        let codec = PackBitsCodec::new();
+        let metadata = codec
+            .create_metadata_opt(&ArrayMetadataOptions::default())
+            .unwrap();
+        assert_eq!(
+            serde_json::to_string(&metadata).unwrap(),
+            r#"{"name":"https://codec.zarrs.dev/array_to_bytes/packbits"}"#
+        );
+        assert!(create_codec_packbits(&Metadata::new("packbits")).is_ok());
+    }
+
+    #[test]
+    fn codec_packbits_round_trip() {
+        let chunk_shape = vec![NonZeroU64::new(5).unwrap(), NonZeroU64::new(5).unwrap()];
+        let chunk_representation =
+            ChunkRepresentation::new(chunk_shape, DataType::Bool, FillValue::from(false)).unwrap();
+        let bytes = elements(chunk_representation.num_elements());
+
+        let codec = PackBitsCodec::new();
+        let encoded_size = codec.compute_encoded_size(&chunk_representation).unwrap();
+        let encoded = codec
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(encoded.len(), 4);
+        assert_eq!(encoded_size.size(), Some(4));
+        assert_eq!(encoded[0], 0b0100_1001);
+        let decoded = codec
+            .decode(encoded, &chunk_representation, &CodecOptions::default())
+            .unwrap();
+        assert_eq!(bytes, *decoded);
+    }
+
+    #[test]
+    fn codec_packbits_unsupported_data_type() {
+        let chunk_shape = vec![NonZeroU64::new(8).unwrap()];
+        let chunk_representation =
+            ChunkRepresentation::new(chunk_shape, DataType::UInt8, FillValue::from(0u8)).unwrap();
+        let codec = PackBitsCodec::new();
+        assert!(codec
+            .encode(
+                vec![0; 8].into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+        assert!(codec.compute_encoded_size(&chunk_representation).is_err());
+    }
+
+    #[test]
+    fn codec_packbits_partial_decode() {
+        let chunk_shape: ChunkShape = vec![6, 7].try_into().unwrap();
+        let chunk_representation =
+            ChunkRepresentation::new(chunk_shape.to_vec(), DataType::Bool, FillValue::from(false))
+                .unwrap();
+        let bytes = elements(chunk_representation.num_elements());
+
+        let codec = PackBitsCodec::new();
+        let encoded = codec
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_regions = [
+            ArraySubset::new_with_ranges(&[1..4, 2..7]),
+            ArraySubset::new_with_ranges(&[5..6, 0..7]),
+        ];
+        let input_handle = Box::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode_opt(&decoded_regions, &CodecOptions::default())
+            .unwrap();
+
+        for (decoded, subset) in decoded_partial_chunk.iter().zip(&decoded_regions) {
+            let expected: Vec<u8> = subset
+                .indices()
+                .iter()
+                .map(|indices| bytes[usize::try_from(indices[0] * 7 + indices[1]).unwrap()])
+                .collect();
+            assert_eq!(decoded, &expected);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn codec_packbits_async_partial_decode() {
+        let chunk_shape: ChunkShape = vec![6, 7].try_into().unwrap();
+        let chunk_representation =
+            ChunkRepresentation::new(chunk_shape.to_vec(), DataType::Bool, FillValue::from(false))
+                .unwrap();
+        let bytes = elements(chunk_representation.num_elements());
+
+        let codec = PackBitsCodec::new();
+        let encoded = codec
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_regions = [ArraySubset::new_with_ranges(&[1..4, 2..7])];
+        let input_handle = Box::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .async_partial_decoder(
+                input_handle,
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .await
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode_opt(&decoded_regions, &CodecOptions::default())
+            .await
+            .unwrap();
+
+        let expected: Vec<u8> = decoded_regions[0]
+            .indices()
+            .iter()
+            .map(|indices| bytes[usize::try_from(indices[0] * 7 + indices[1]).unwrap()])
+            .collect();
+        assert_eq!(decoded_partial_chunk[0], expected);
+    }
+}
@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+
+use crate::{
+    array::{
+        codec::{
+            ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
+            BytesPartialDecoderTraits, CodecError, CodecOptions, CodecTraits,
+            RecommendedConcurrency,
+        },
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType, RawBytes,
+    },
+    metadata::Metadata,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{
+    pack_bits, packbits_partial_decoder, packed_size, unpack_bits, PackBitsCodecConfigurationV1,
+    IDENTIFIER,
+};
+
+/// A `packbits` codec implementation.
+#[derive(Debug, Clone, Default)]
+pub struct PackBitsCodec;
+
+impl PackBitsCodec {
+    /// Create a new `packbits` codec.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Return an error if the data type of `decoded_representation` is not `bool`.
+pub(super) fn validate_data_type(
+    decoded_representation: &ChunkRepresentation,
+) -> Result<(), CodecError> {
+    match decoded_representation.data_type() {
+        DataType::Bool => Ok(()),
+        data_type => Err(CodecError::UnsupportedDataType(
+            data_type.clone(),
+            IDENTIFIER.to_string(),
+        )),
+    }
+}
+
+impl CodecTraits for PackBitsCodec {
+    fn create_metadata_opt(&self, _options: &ArrayMetadataOptions) -> Option<Metadata> {
+        let configuration = PackBitsCodecConfigurationV1 {};
+        Some(Metadata::new_with_serializable_configuration(IDENTIFIER, &configuration).unwrap())
+    }
+
+    fn partial_decoder_should_cache_input(&self) -> bool {
+        false
+    }
+
+    fn partial_decoder_decodes_all(&self) -> bool {
+        false
+    }
+}
+
+impl ArrayCodecTraits for PackBitsCodec {
+    fn recommended_concurrency(
+        &self,
+        _decoded_representation: &ChunkRepresentation,
+    ) -> Result<RecommendedConcurrency, CodecError> {
+        Ok(RecommendedConcurrency::new_maximum(1))
+    }
+
+    fn encode<'a>(
+        &self,
+        decoded_value: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        if decoded_value.len() as u64 != decoded_representation.size() {
+            return Err(CodecError::UnexpectedChunkDecodedSize(
+                decoded_value.len(),
+                decoded_representation.size(),
+            ));
+        }
+        Ok(Cow::Owned(pack_bits(&decoded_value)))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        let num_elements = decoded_representation.num_elements();
+        if encoded_value.len() as u64 != packed_size(num_elements) {
+            return Err(CodecError::UnexpectedChunkDecodedSize(
+                encoded_value.len(),
+                packed_size(num_elements),
+            ));
+        }
+        Ok(Cow::Owned(unpack_bits(
+            &encoded_value,
+            0,
+            decoded_representation.num_elements_usize(),
+        )))
+    }
+}
+
+#[cfg_attr(feature = "async", async_trait::async_trait)]
+impl ArrayToBytesCodecTraits for PackBitsCodec {
+    fn partial_decoder<'a>(
+        &self,
+        input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn ArrayPartialDecoderTraits + 'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        Ok(Box::new(
+            packbits_partial_decoder::PackBitsPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+            ),
+        ))
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_partial_decoder<'a>(
+        &'a self,
+        input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+        decoded_representation: &ChunkRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<Box<dyn AsyncArrayPartialDecoderTraits + 'a>, CodecError> {
+        validate_data_type(decoded_representation)?;
+        Ok(Box::new(
+            packbits_partial_decoder::AsyncPackBitsPartialDecoder::new(
+                input_handle,
+                decoded_representation.clone(),
+            ),
+        ))
+    }
+
+    fn compute_encoded_size(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+    ) -> Result<BytesRepresentation, CodecError> {
+        validate_data_type(decoded_representation)?;
+        Ok(BytesRepresentation::FixedSize(packed_size(
+            decoded_representation.num_elements(),
+        )))
+    }
+}
@@ -0,0 +1,37 @@
+use derive_more::{Display, From};
+use serde::{Deserialize, Serialize};
+
+/// A wrapper to handle various versions of `packbits` codec configuration parameters.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display, From)]
+#[serde(untagged)]
+pub enum PackBitsCodecConfiguration {
+    /// Version 1.0 draft.
+    V1(PackBitsCodecConfigurationV1),
+}
+
+/// `packbits` codec configuration parameters (version 1.0 draft).
+///
+/// The `packbits` codec has no configuration parameters.
+///
+/// ### Example
+/// ```rust
+/// # let JSON = r#"
+/// {}
+/// # "#;
+/// # let configuration: zarrs::array::codec::PackBitsCodecConfigurationV1 = serde_json::from_str(JSON).unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug, Display)]
+#[serde(deny_unknown_fields)]
+#[display(fmt = "{}", "serde_json::to_string(self).unwrap_or_default()")]
+pub struct PackBitsCodecConfigurationV1 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_packbits_config() {
+        serde_json::from_str::<PackBitsCodecConfiguration>("{}").unwrap();
+        assert!(serde_json::from_str::<PackBitsCodecConfiguration>(r#"{"order":"big"}"#).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    array::{
+        codec::{
+            ArrayPartialDecoderTraits, ArraySubset, BytesPartialDecoderTraits, CodecError,
+            CodecOptions,
+        },
+        ChunkRepresentation,
+    },
+    array_subset::IncompatibleArraySubsetAndShapeError,
+    byte_range::ByteRange,
+};
+
+#[cfg(feature = "async")]
+use crate::array::codec::{AsyncArrayPartialDecoderTraits, AsyncBytesPartialDecoderTraits};
+
+use super::{packed_size, unpack_bits};
+
+/// Return the contiguous element ranges of `array_subset` and the packed byte ranges that hold them.
+fn element_and_packed_byte_ranges(
+    array_subset: &ArraySubset,
+    decoded_representation: &ChunkRepresentation,
+) -> Result<(Vec<ByteRange>, Vec<ByteRange>), CodecError> {
+    let chunk_shape = decoded_representation.shape_u64();
+    let element_ranges = array_subset.byte_ranges(&chunk_shape, 1).map_err(|_| {
+        IncompatibleArraySubsetAndShapeError::from((array_subset.clone(), chunk_shape.clone()))
+    })?;
+    let num_elements = decoded_representation.num_elements();
+    let packed_ranges = element_ranges
+        .iter()
+        .map(|range| {
+            let start = range.start(num_elements) / 8;
+            let end = packed_size(range.end(num_elements));
+            ByteRange::FromStart(start, Some(end - start))
+        })
+        .collect();
+    Ok((element_ranges, packed_ranges))
+}
+
+/// Unpack the elements of `element_ranges` from the corresponding `packed` bytes.
+#[allow(clippy::cast_possible_truncation)]
+fn unpack_element_ranges(
+    element_ranges: &[ByteRange],
+    packed: &[Vec<u8>],
+    decoded_representation: &ChunkRepresentation,
+    num_elements: usize,
+) -> Vec<u8> {
+    let chunk_elements = decoded_representation.num_elements();
+    let mut decoded = Vec::with_capacity(num_elements);
+    for (range, bytes) in element_ranges.iter().zip(packed) {
+        let bit_offset = (range.start(chunk_elements) % 8) as usize;
+        let length = range.length(chunk_elements) as usize;
+        decoded.extend(unpack_bits(bytes, bit_offset, length));
+    }
+    decoded
+}
+
+/// Partial decoder for the `packbits` codec.
+pub struct PackBitsPartialDecoder<'a> {
+    input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+}
+
+impl<'a> PackBitsPartialDecoder<'a> {
+    /// Create a new partial decoder for the `packbits` codec.
+    pub fn new(
+        input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+        }
+    }
+}
+
+impl ArrayPartialDecoderTraits for PackBitsPartialDecoder<'_> {
+    fn element_size(&self) -> usize {
+        self.decoded_representation.element_size()
+    }
+
+    fn partial_decode_opt(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<Vec<u8>>, CodecError> {
+        let mut bytes = Vec::with_capacity(decoded_regions.len());
+        for array_subset in decoded_regions {
+            let (element_ranges, packed_ranges) =
+                element_and_packed_byte_ranges(array_subset, &self.decoded_representation)?;
+
+            // Decode
+            let decoded = self
+                .input_handle
+                .partial_decode(&packed_ranges, options)?
+                .map_or_else(
+                    || {
+                        self.decoded_representation
+                            .fill_value()
+                            .as_ne_bytes()
+                            .repeat(array_subset.num_elements_usize())
+                    },
+                    |packed| {
+                        unpack_element_ranges(
+                            &element_ranges,
+                            &packed,
+                            &self.decoded_representation,
+                            array_subset.num_elements_usize(),
+                        )
+                    },
+                );
+
+            bytes.push(decoded);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "async")]
+/// Asynchronous partial decoder for the `packbits` codec.
+pub struct AsyncPackBitsPartialDecoder<'a> {
+    input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+    decoded_representation: ChunkRepresentation,
+}
+
+#[cfg(feature = "async")]
+impl<'a> AsyncPackBitsPartialDecoder<'a> {
+    /// Create a new partial decoder for the `packbits` codec.
+    pub fn new(
+        input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+        decoded_representation: ChunkRepresentation,
+    ) -> Self {
+        Self {
+            input_handle,
+            decoded_representation,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncArrayPartialDecoderTraits for AsyncPackBitsPartialDecoder<'_> {
+    fn element_size(&self) -> usize {
+        self.decoded_representation.element_size()
+    }
+
+    async fn partial_decode_opt(
+        &self,
+        decoded_regions: &[ArraySubset],
+        options: &CodecOptions,
+    ) -> Result<Vec<Vec<u8>>, CodecError> {
+        let mut bytes = Vec::with_capacity(decoded_regions.len());
+        for array_subset in decoded_regions {
+            let (element_ranges, packed_ranges) =
+                element_and_packed_byte_ranges(array_subset, &self.decoded_representation)?;
+
+            // Decode
+            let decoded = self
+                .input_handle
+                .partial_decode(&packed_ranges, options)
+                .await?
+                .map_or_else(
+                    || {
+                        self.decoded_representation
+                            .fill_value()
+                            .as_ne_bytes()
+                            .repeat(array_subset.num_elements_usize())
+                    },
+                    |packed| {
+                        unpack_element_ranges(
+                            &element_ranges,
+                            &packed,
+                            &self.decoded_representation,
+                            array_subset.num_elements_usize(),
+                        )
+                    },
+                );
+
+            bytes.push(decoded);
+        }
+        Ok(bytes)
+    }
+}
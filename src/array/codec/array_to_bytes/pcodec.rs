@@ -200,6 +200,7 @@ impl PcodecDeltaEncodingOrder {
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation)]
 mod tests {
     use std::num::NonZeroU64;
 
@@ -240,7 +241,7 @@ mod tests {
 
         let max_encoded_size = codec.compute_encoded_size(&chunk_representation)?;
         let encoded = codec.encode(
-            bytes.clone(),
+            bytes.clone().into(),
             &chunk_representation,
             &CodecOptions::default(),
         )?;
@@ -248,7 +249,7 @@ mod tests {
         let decoded = codec
             .decode(encoded, &chunk_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
         Ok(())
     }
 
@@ -357,7 +358,11 @@ mod tests {
         let codec = PcodecCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -399,7 +404,11 @@ mod tests {
         let codec = PcodecCodec::new_with_configuration(&serde_json::from_str(JSON_VALID).unwrap());
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
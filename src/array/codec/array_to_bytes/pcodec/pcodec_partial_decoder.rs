@@ -4,7 +4,7 @@ use crate::{
             ArrayPartialDecoderTraits, ArraySubset, BytesPartialDecoderTraits, CodecError,
             CodecOptions,
         },
-        ChunkRepresentation, DataType,
+        ChunkRepresentation, DataType, MaybeBytes,
     },
     array_subset::IncompatibleArraySubsetAndShapeError,
 };
@@ -32,7 +32,7 @@ impl<'a> PcodecPartialDecoder<'a> {
 }
 
 fn do_partial_decode(
-    decoded: Option<Vec<u8>>,
+    decoded: MaybeBytes,
     decoded_regions: &[ArraySubset],
     decoded_representation: &ChunkRepresentation,
 ) -> Result<Vec<Vec<u8>>, CodecError> {
@@ -51,10 +51,9 @@ fn do_partial_decode(
         Some(decoded_value) => {
             macro_rules! pcodec_partial_decode {
                 ( $t:ty ) => {
-                    let decoded_chunk =
-                        pco::standalone::simple_decompress(decoded_value.as_slice())
-                            .map(|bytes| crate::array::transmute_to_bytes_vec::<$t>(bytes))
-                            .map_err(|err| CodecError::Other(err.to_string()))?;
+                    let decoded_chunk = pco::standalone::simple_decompress(&decoded_value)
+                        .map(|bytes| crate::array::transmute_to_bytes_vec::<$t>(bytes))
+                        .map_err(|err| CodecError::Other(err.to_string()))?;
                     for array_subset in decoded_regions {
                         let bytes_subset = array_subset
                             .extract_bytes(
@@ -99,7 +98,7 @@ fn do_partial_decode(
                         super::IDENTIFIER.to_string(),
                     ))
                 }
-            };
+            }
         }
     }
     Ok(decoded_bytes)
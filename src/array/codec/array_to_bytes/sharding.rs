@@ -28,7 +28,7 @@ use thiserror::Error;
 use crate::{
     array::{
         codec::{ArrayToBytesCodecTraits, Codec, CodecError, CodecOptions, CodecPlugin},
-        BytesRepresentation, ChunkRepresentation, ChunkShape, DataType, FillValue,
+        BytesRepresentation, ChunkRepresentation, ChunkShape, DataType, FillValue, RawBytes,
     },
     metadata::Metadata,
     plugin::{PluginCreateError, PluginMetadataInvalidError},
@@ -71,7 +71,7 @@ fn calculate_chunks_per_shard(
         .map(|(s, c)| {
             let s = s.get();
             let c = c.get();
-            if s.is_multiple_of(&c) {
+            if Integer::is_multiple_of(&s, &c) {
                 Ok(unsafe { NonZeroU64::new_unchecked(s / c) })
             } else {
                 Err(ChunksPerShardError {
@@ -105,7 +105,7 @@ fn compute_index_encoded_size(
 }
 
 fn decode_shard_index(
-    encoded_shard_index: Vec<u8>,
+    encoded_shard_index: RawBytes<'_>,
     index_array_representation: &ChunkRepresentation,
     index_codecs: &dyn ArrayToBytesCodecTraits,
     options: &CodecOptions,
@@ -120,6 +120,7 @@ fn decode_shard_index(
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation)]
 mod tests {
     use crate::{
         array::codec::{
@@ -209,7 +210,7 @@ mod tests {
         let bytes = crate::array::transmute_to_bytes_vec(elements);
 
         if unbounded {
-            bytes_to_bytes_codecs.push(Box::new(TestUnboundedCodec::new()))
+            bytes_to_bytes_codecs.push(Box::new(TestUnboundedCodec::new()));
         }
         let codec = ShardingCodecBuilder::new(vec![2, 2].try_into().unwrap())
             .index_location(if index_at_end {
@@ -221,13 +222,13 @@ mod tests {
             .build();
 
         let encoded = codec
-            .encode(bytes.clone(), &chunk_representation, options)
+            .encode(bytes.clone().into(), &chunk_representation, options)
             .unwrap();
         let decoded = codec
             .decode(encoded.clone(), &chunk_representation, options)
             .unwrap();
         assert_ne!(encoded, decoded);
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
     }
 
     #[test]
@@ -281,7 +282,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn codec_sharding_deterministic_encode() {
+        let chunk_representation = ChunkRepresentation::new(
+            ChunkShape::try_from(vec![16, 16]).unwrap().into(),
+            DataType::UInt16,
+            FillValue::from(0u16),
+        )
+        .unwrap();
+        let elements: Vec<u16> = (0..chunk_representation.num_elements() as u16).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let codec = ShardingCodecBuilder::new(vec![2, 2].try_into().unwrap()).build();
+
+        // Inner chunks encoded sequentially are written in chunk order
+        let sequential = codec
+            .encode(
+                bytes.clone().into(),
+                &chunk_representation,
+                &CodecOptionsBuilder::new().concurrent_target(1).build(),
+            )
+            .unwrap();
+        let options = CodecOptionsBuilder::new()
+            .concurrent_target(get_concurrent_target(true))
+            .deterministic_encode(true)
+            .build();
+        for _ in 0..4 {
+            let encoded = codec
+                .encode(bytes.clone().into(), &chunk_representation, &options)
+                .unwrap();
+            assert_eq!(encoded, sequential);
+        }
+    }
+
     #[cfg(feature = "async")]
+    #[allow(clippy::unused_async)]
     async fn codec_sharding_async_round_trip_impl(
         options: &CodecOptions,
         unbounded: bool,
@@ -303,7 +337,7 @@ mod tests {
         let bytes = crate::array::transmute_to_bytes_vec(elements);
 
         if unbounded {
-            bytes_to_bytes_codecs.push(Box::new(TestUnboundedCodec::new()))
+            bytes_to_bytes_codecs.push(Box::new(TestUnboundedCodec::new()));
         }
         let codec = ShardingCodecBuilder::new(vec![2, 2].try_into().unwrap())
             .index_location(if index_at_end {
@@ -315,13 +349,13 @@ mod tests {
             .build();
 
         let encoded = codec
-            .encode(bytes.clone(), &chunk_representation, options)
+            .encode(bytes.clone().into(), &chunk_representation, options)
             .unwrap();
         let decoded = codec
             .decode(encoded.clone(), &chunk_representation, options)
             .unwrap();
         assert_ne!(encoded, decoded);
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
     }
 
     #[cfg(feature = "async")]
@@ -386,7 +420,7 @@ mod tests {
             .build();
 
         let encoded = codec
-            .encode(bytes.clone(), &chunk_representation, options)
+            .encode(bytes.clone().into(), &chunk_representation, options)
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -467,7 +501,7 @@ mod tests {
             .build();
 
         let encoded = codec
-            .encode(bytes.clone(), &chunk_representation, options)
+            .encode(bytes.clone().into(), &chunk_representation, options)
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -534,7 +568,11 @@ mod tests {
         let codec = ShardingCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..2, 0..2, 0..3])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -575,7 +613,11 @@ mod tests {
         let codec = ShardingCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
-            .encode(bytes, &chunk_representation, &CodecOptions::default())
+            .encode(
+                bytes.into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
             .unwrap();
         let decoded_regions = [ArraySubset::new_with_ranges(&[1..3, 0..1])];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
@@ -600,4 +642,71 @@ mod tests {
         let answer: Vec<u8> = vec![4, 8];
         assert_eq!(answer, decoded_partial_chunk);
     }
+
+    /// A shard with the index at the start and inner chunks stored out of order, as may be written by other implementations.
+    #[cfg(feature = "crc32c")]
+    #[test]
+    fn codec_sharding_index_start_external() {
+        let chunk_representation = ChunkRepresentation::new(
+            ChunkShape::try_from(vec![2, 4]).unwrap().into(),
+            DataType::UInt8,
+            FillValue::from(0u8),
+        )
+        .unwrap();
+        let codec = ShardingCodec::new_with_configuration(
+            &serde_json::from_str(
+                r#"{
+    "chunk_shape": [1, 2],
+    "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+    "index_codecs": [{"name": "bytes", "configuration": {"endian": "little"}}, {"name": "crc32c"}],
+    "index_location": "start"
+}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        // The index is 2x2 (offset, nbytes) pairs and a checksum, followed by inner chunks (1, 1), (0, 1), and (0, 0)
+        let index: Vec<u8> = [72, 2, 70, 2, u64::MAX, u64::MAX, 68, 2]
+            .iter()
+            .flat_map(|v: &u64| v.to_le_bytes())
+            .collect();
+        let mut encoded = index.clone();
+        encoded.extend(crc32c::crc32c(&index).to_le_bytes());
+        encoded.extend([7, 8, 3, 4, 1, 2]);
+
+        let decoded = codec
+            .decode(
+                encoded.clone().into(),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(&*decoded, &[1, 2, 3, 4, 0, 0, 7, 8]);
+
+        let partial_decoder = codec
+            .partial_decoder(
+                Box::new(std::io::Cursor::new(encoded.clone())),
+                &chunk_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded = partial_decoder
+            .partial_decode_opt(
+                &[ArraySubset::new_with_ranges(&[0..2, 1..3])],
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(&*decoded[0], &[2, 3, 0, 7]);
+
+        // A corrupt index fails checksum validation
+        encoded[0] = 0;
+        assert!(codec
+            .decode(
+                encoded.into(),
+                &chunk_representation,
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
 }
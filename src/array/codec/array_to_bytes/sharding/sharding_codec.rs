@@ -1,4 +1,4 @@
-use std::{num::NonZeroU64, sync::atomic::AtomicUsize};
+use std::{borrow::Cow, num::NonZeroU64, sync::atomic::AtomicUsize};
 
 use crate::{
     array::{
@@ -6,14 +6,16 @@ use crate::{
         codec::{
             ArrayCodecTraits, ArrayPartialDecoderTraits, ArrayToBytesCodecTraits,
             BytesPartialDecoderTraits, CodecChain, CodecError, CodecOptions, CodecTraits,
-            RecommendedConcurrency,
+            PartialDecodePlan, RecommendedConcurrency,
         },
         concurrency::calc_concurrency_outer_inner,
-        transmute_to_bytes_vec, unravel_index,
+        ravel_indices, transmute_to_bytes_vec, unravel_index,
         unsafe_cell_slice::UnsafeCellSlice,
         ArrayMetadataOptions, ArrayView, BytesRepresentation, ChunkRepresentation, ChunkShape,
+        RawBytes,
     },
     array_subset::ArraySubset,
+    byte_range::ByteRange,
     metadata::Metadata,
     plugin::PluginCreateError,
 };
@@ -111,12 +113,12 @@ impl ArrayCodecTraits for ShardingCodec {
         Ok(RecommendedConcurrency::new_maximum(num_elements.into()))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         shard_rep: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         if decoded_value.len() as u64 != shard_rep.size() {
             return Err(CodecError::UnexpectedChunkDecodedSize(
                 decoded_value.len(),
@@ -134,21 +136,22 @@ impl ArrayCodecTraits for ShardingCodec {
         };
         let chunk_bytes_representation = self.inner_codecs.compute_encoded_size(&chunk_rep)?;
         match chunk_bytes_representation {
-            BytesRepresentation::BoundedSize(size) | BytesRepresentation::FixedSize(size) => {
+            BytesRepresentation::BoundedSize(size) | BytesRepresentation::FixedSize(size)
+                if !options.deterministic_encode() =>
+            {
                 self.encode_bounded(&decoded_value, shard_rep, &chunk_rep, size, options)
             }
-            BytesRepresentation::UnboundedSize => {
-                self.encode_unbounded(&decoded_value, shard_rep, &chunk_rep, options)
-            }
+            _ => self.encode_unbounded(&decoded_value, shard_rep, &chunk_rep, options),
         }
+        .map(Cow::Owned)
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         // Allocate an array for the output
         let len = decoded_representation.size_usize();
         let mut decoded_shard = Vec::<u8>::with_capacity(len);
@@ -168,7 +171,7 @@ impl ArrayCodecTraits for ShardingCodec {
             options,
         )?;
         unsafe { decoded_shard.set_len(len) };
-        Ok(decoded_shard)
+        Ok(Cow::Owned(decoded_shard))
     }
 
     fn decode_into_array_view(
@@ -192,9 +195,15 @@ impl ArrayCodecTraits for ShardingCodec {
             )
         };
 
-        let any_empty = shard_index
-            .par_iter()
-            .any(|offset_or_size| *offset_or_size == u64::MAX);
+        let any_empty = if options.concurrent_target() > 1 {
+            options.install(|| {
+                shard_index
+                    .par_iter()
+                    .any(|offset_or_size| *offset_or_size == u64::MAX)
+            })
+        } else {
+            shard_index.contains(&u64::MAX)
+        };
         let fill_value_chunk = if any_empty {
             Some(
                 chunk_representation
@@ -228,65 +237,55 @@ impl ArrayCodecTraits for ShardingCodec {
             .iter()
             .map(|i| usize::try_from(i.get()).unwrap())
             .product::<usize>();
-        let element_size = chunk_representation.element_size() as u64;
-
-        rayon_iter_concurrent_limit::iter_concurrent_limit!(
-            shard_concurrent_limit,
-            (0..num_chunks),
-            try_for_each,
-            |chunk_index: usize| {
-                let chunk_subset =
-                    self.chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
-                let array_slice = unsafe { array_view.bytes_mut() };
-
-                // Read the offset/size
-                let offset = shard_index[chunk_index * 2];
-                let size = shard_index[chunk_index * 2 + 1];
-                if offset == u64::MAX && size == u64::MAX {
-                    if let Some(fill_value_chunk) = &fill_value_chunk {
-                        let array_view_chunk = unsafe { array_view.subset_view(&chunk_subset) }
-                            .map_err(|err| CodecError::from(err.to_string()))?;
-                        let contiguous_iterator = unsafe {
-                            array_view_chunk
-                                .subset()
-                                .contiguous_linearised_indices_unchecked(array_view.array_shape())
-                        };
-                        let length = usize::try_from(
-                            contiguous_iterator.contiguous_elements() * element_size,
-                        )
-                        .unwrap();
-                        let mut data_idx = 0;
-                        for (index, _) in &contiguous_iterator {
-                            let shard_offset = usize::try_from(index * element_size).unwrap();
-                            array_slice[shard_offset..shard_offset + length]
-                                .copy_from_slice(&fill_value_chunk[data_idx..data_idx + length]);
-                            data_idx += length;
+        let element_size = chunk_representation.element_size();
+
+        options.install(|| {
+            rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                shard_concurrent_limit,
+                (0..num_chunks),
+                try_for_each,
+                |chunk_index: usize| {
+                    let chunk_subset =
+                        self.chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
+
+                    // Read the offset/size
+                    let offset = shard_index[chunk_index * 2];
+                    let size = shard_index[chunk_index * 2 + 1];
+                    if offset == u64::MAX && size == u64::MAX {
+                        if let Some(fill_value_chunk) = &fill_value_chunk {
+                            let array_view_chunk = unsafe { array_view.subset_view(&chunk_subset) }
+                                .map_err(|err| CodecError::from(err.to_string()))?;
+                            array_view_chunk.copy_from_bytes(
+                                fill_value_chunk,
+                                element_size,
+                                &options,
+                            );
+                        } else {
+                            unreachable!();
                         }
+                    } else if usize::try_from(offset + size).unwrap() > encoded_shard.len() {
+                        return Err(CodecError::Other(
+                            "The shard index references out-of-bounds bytes. The chunk may be corrupted."
+                                .to_string(),
+                        ));
                     } else {
-                        unreachable!();
+                        let offset: usize = offset.try_into().unwrap();
+                        let size: usize = size.try_into().unwrap();
+                        let encoded_chunk_slice = &encoded_shard[offset..offset + size];
+                        let array_view_chunk = unsafe { array_view.subset_view(&chunk_subset) }
+                            .map_err(|err| CodecError::from(err.to_string()))?;
+                        self.inner_codecs.decode_into_array_view(
+                            encoded_chunk_slice,
+                            &chunk_representation,
+                            &array_view_chunk,
+                            &options,
+                        )?;
                     }
-                } else if usize::try_from(offset + size).unwrap() > encoded_shard.len() {
-                    return Err(CodecError::Other(
-                        "The shard index references out-of-bounds bytes. The chunk may be corrupted."
-                            .to_string(),
-                    ));
-                } else {
-                    let offset: usize = offset.try_into().unwrap();
-                    let size: usize = size.try_into().unwrap();
-                    let encoded_chunk_slice = &encoded_shard[offset..offset + size];
-                    let array_view_chunk = unsafe { array_view.subset_view(&chunk_subset) }
-                        .map_err(|err| CodecError::from(err.to_string()))?;
-                    self.inner_codecs.decode_into_array_view(
-                        encoded_chunk_slice,
-                        &chunk_representation,
-                        &array_view_chunk,
-                        &options,
-                    )?;
-                };
-
-                Ok::<_, CodecError>(())
-            }
-        )?;
+
+                    Ok::<_, CodecError>(())
+                }
+            )
+        })?;
         Ok(())
     }
 
@@ -340,6 +339,77 @@ impl ArrayToBytesCodecTraits for ShardingCodec {
         ))
     }
 
+    fn partial_decode_plan(
+        &self,
+        decoded_representation: &ChunkRepresentation,
+        decoded_regions: &[ArraySubset],
+        index: Option<&[u8]>,
+    ) -> Result<PartialDecodePlan, CodecError> {
+        for decoded_region in decoded_regions {
+            if decoded_region.dimensionality() != decoded_representation.dimensionality() {
+                return Err(CodecError::InvalidArraySubsetDimensionalityError(
+                    decoded_region.clone(),
+                    decoded_representation.dimensionality(),
+                ));
+            }
+        }
+
+        let chunk_representation = unsafe {
+            ChunkRepresentation::new_unchecked(
+                self.chunk_shape.as_slice().to_vec(),
+                decoded_representation.data_type().clone(),
+                decoded_representation.fill_value().clone(),
+            )
+        };
+        let chunks_per_shard =
+            calculate_chunks_per_shard(decoded_representation.shape(), self.chunk_shape.as_slice())
+                .map_err(|e| CodecError::Other(e.to_string()))?;
+        let index_array_representation =
+            sharding_index_decoded_representation(chunks_per_shard.as_slice());
+
+        // The byte ranges of inner chunks are only known once the shard index is read
+        let Some(index) = index else {
+            let index_encoded_size =
+                compute_index_encoded_size(&self.index_codecs, &index_array_representation)?;
+            return Ok(PartialDecodePlan::Index(match self.index_location {
+                ShardingIndexLocation::Start => ByteRange::FromStart(0, Some(index_encoded_size)),
+                ShardingIndexLocation::End => ByteRange::FromEnd(0, Some(index_encoded_size)),
+            }));
+        };
+        let shard_index = decode_shard_index(
+            index.into(),
+            &index_array_representation,
+            &self.index_codecs,
+            &CodecOptions::default(),
+        )?;
+        let chunks_per_shard = chunk_shape_to_array_shape(chunks_per_shard.as_slice());
+
+        let mut byte_ranges = Vec::new();
+        for decoded_region in decoded_regions {
+            let chunks = unsafe { decoded_region.chunks_unchecked(chunk_representation.shape()) };
+            for (chunk_indices, chunk_subset) in &chunks {
+                let shard_index_idx =
+                    usize::try_from(ravel_indices(&chunk_indices, &chunks_per_shard) * 2).unwrap();
+                let offset = shard_index[shard_index_idx];
+                let size = shard_index[shard_index_idx + 1];
+                if offset == u64::MAX && size == u64::MAX {
+                    // The inner chunk is just the fill value
+                    continue;
+                }
+                let overlap = unsafe { decoded_region.overlap_unchecked(&chunk_subset) };
+                let decoded_region_in_chunk =
+                    unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+                let inner_plan = self.inner_codecs.partial_decode_plan(
+                    &chunk_representation,
+                    &[decoded_region_in_chunk],
+                    None,
+                )?;
+                byte_ranges.extend(inner_plan.byte_ranges_within(offset, size));
+            }
+        }
+        Ok(PartialDecodePlan::ByteRanges(byte_ranges))
+    }
+
     fn compute_encoded_size(
         &self,
         decoded_representation: &ChunkRepresentation,
@@ -397,6 +467,7 @@ impl ShardingCodec {
     /// Computed the bounded size of an encoded shard from
     ///  - the chunk bytes representation, and
     ///  - the number of chunks per shard.
+    ///
     /// Equal to `num chunks * max chunk size + index size`
     fn encoded_shard_bounded_size(
         index_encoded_size: u64,
@@ -470,49 +541,55 @@ impl ShardingCodec {
                 .iter()
                 .map(|i| usize::try_from(i.get()).unwrap())
                 .product::<usize>();
-            rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                shard_concurrent_limit,
-                (0..n_chunks),
-                try_for_each,
-                |chunk_index: usize| {
-                    let chunk_subset =
-                        self.chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
-                    let bytes = unsafe {
-                        chunk_subset.extract_bytes_unchecked(
-                            decoded_value,
-                            &shard_shape,
-                            shard_representation.element_size(),
-                        )
-                    };
-                    if !chunk_representation.fill_value().equals_all(&bytes) {
-                        let chunk_encoded =
-                            self.inner_codecs
-                                .encode(bytes, chunk_representation, &options)?;
-
-                        let chunk_offset = encoded_shard_offset
-                            .fetch_add(chunk_encoded.len(), std::sync::atomic::Ordering::Relaxed);
-                        if chunk_offset + chunk_encoded.len() > shard_size_bounded {
-                            // This is a dev error, indicates the codec bounded size is not correct
-                            return Err(CodecError::from(
-                                "Sharding did not allocate a large enough buffer",
-                            ));
-                        }
-
-                        unsafe {
-                            let shard_index_unsafe = shard_index_slice.get();
-                            shard_index_unsafe[chunk_index * 2] =
-                                u64::try_from(chunk_offset).unwrap();
-                            shard_index_unsafe[chunk_index * 2 + 1] =
-                                u64::try_from(chunk_encoded.len()).unwrap();
-
-                            let shard_unsafe = shard_slice.get();
-                            shard_unsafe[chunk_offset..chunk_offset + chunk_encoded.len()]
-                                .copy_from_slice(&chunk_encoded);
+            options.install(|| {
+                rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                    shard_concurrent_limit,
+                    (0..n_chunks),
+                    try_for_each,
+                    |chunk_index: usize| {
+                        let chunk_subset = self
+                            .chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
+                        let bytes = unsafe {
+                            chunk_subset.extract_bytes_unchecked(
+                                decoded_value,
+                                &shard_shape,
+                                shard_representation.element_size(),
+                            )
+                        };
+                        if !chunk_representation.fill_value().equals_all(&bytes) {
+                            let chunk_encoded = self.inner_codecs.encode(
+                                bytes.into(),
+                                chunk_representation,
+                                &options,
+                            )?;
+
+                            let chunk_offset = encoded_shard_offset.fetch_add(
+                                chunk_encoded.len(),
+                                std::sync::atomic::Ordering::Relaxed,
+                            );
+                            if chunk_offset + chunk_encoded.len() > shard_size_bounded {
+                                // This is a dev error, indicates the codec bounded size is not correct
+                                return Err(CodecError::from(
+                                    "Sharding did not allocate a large enough buffer",
+                                ));
+                            }
+
+                            unsafe {
+                                let shard_index_unsafe = shard_index_slice.get();
+                                shard_index_unsafe[chunk_index * 2] =
+                                    u64::try_from(chunk_offset).unwrap();
+                                shard_index_unsafe[chunk_index * 2 + 1] =
+                                    u64::try_from(chunk_encoded.len()).unwrap();
+
+                                let shard_unsafe = shard_slice.get();
+                                shard_unsafe[chunk_offset..chunk_offset + chunk_encoded.len()]
+                                    .copy_from_slice(&chunk_encoded);
+                            }
                         }
+                        Ok(())
                     }
-                    Ok(())
-                }
-            )?;
+                )
+            })?;
         }
 
         // Truncate shard
@@ -524,7 +601,7 @@ impl ShardingCodec {
 
         // Encode and write array index
         let encoded_array_index = self.index_codecs.encode(
-            transmute_to_bytes_vec(shard_index),
+            transmute_to_bytes_vec(shard_index).into(),
             &index_decoded_representation,
             &options,
         )?;
@@ -590,34 +667,37 @@ impl ShardingCodec {
             .build();
         // println!("{shard_concurrent_limit} {concurrency_limit_inner_chunks:?}"); // FIXME: log debug?
 
-        let encoded_chunks: Vec<(usize, Vec<u8>)> =
-            rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                shard_concurrent_limit,
-                (0..n_chunks).into_par_iter(),
-                filter_map,
-                |chunk_index| {
-                    let chunk_subset =
-                        self.chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
-                    let bytes = unsafe {
-                        chunk_subset.extract_bytes_unchecked(
-                            decoded_value,
-                            &shard_shape,
-                            shard_representation.element_size(),
-                        )
-                    };
-                    if chunk_representation.fill_value().equals_all(&bytes) {
-                        None
-                    } else {
-                        let encoded_chunk =
-                            self.inner_codecs
-                                .encode(bytes, chunk_representation, &options_inner);
-                        match encoded_chunk {
-                            Ok(encoded_chunk) => Some(Ok((chunk_index, encoded_chunk))),
-                            Err(err) => Some(Err(err)),
+        let encoded_chunks: Vec<(usize, Vec<u8>)> = options
+            .install(|| {
+                rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                    shard_concurrent_limit,
+                    (0..n_chunks).into_par_iter(),
+                    filter_map,
+                    |chunk_index| {
+                        let chunk_subset = self
+                            .chunk_index_to_subset(chunk_index as u64, chunks_per_shard.as_slice());
+                        let bytes = unsafe {
+                            chunk_subset.extract_bytes_unchecked(
+                                decoded_value,
+                                &shard_shape,
+                                shard_representation.element_size(),
+                            )
+                        };
+                        if chunk_representation.fill_value().equals_all(&bytes) {
+                            None
+                        } else {
+                            let encoded_chunk = self
+                                .inner_codecs
+                                .encode(bytes.into(), chunk_representation, &options_inner)
+                                .map(Cow::into_owned);
+                            match encoded_chunk {
+                                Ok(encoded_chunk) => Some(Ok((chunk_index, encoded_chunk))),
+                                Err(err) => Some(Err(err)),
+                            }
                         }
                     }
-                }
-            )
+                )
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         // Allocate the shard
@@ -630,39 +710,50 @@ impl ShardingCodec {
 
         // Allocate the decoded shard index
         let mut shard_index = vec![u64::MAX; index_decoded_representation.num_elements_usize()];
-        let encoded_shard_offset: AtomicUsize = match self.index_location {
-            ShardingIndexLocation::Start => index_encoded_size.into(),
-            ShardingIndexLocation::End => 0.into(),
+
+        // The encoded chunks are in chunk order, so the layout of the shard is deterministic
+        let mut encoded_shard_offset = match self.index_location {
+            ShardingIndexLocation::Start => index_encoded_size,
+            ShardingIndexLocation::End => 0,
         };
+        let encoded_chunks: Vec<(usize, usize, Vec<u8>)> = encoded_chunks
+            .into_iter()
+            .map(|(chunk_index, chunk_encoded)| {
+                let chunk_offset = encoded_shard_offset;
+                encoded_shard_offset += chunk_encoded.len();
+                (chunk_index, chunk_offset, chunk_encoded)
+            })
+            .collect();
 
         // Write shard and update shard index
         if !encoded_chunks.is_empty() {
             let shard_slice = UnsafeCellSlice::new_from_vec_with_spare_capacity(&mut shard);
             let shard_index_slice = UnsafeCellSlice::new(&mut shard_index);
-            rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                options.concurrent_target(),
-                encoded_chunks,
-                for_each,
-                |(chunk_index, chunk_encoded): (usize, Vec<u8>)| {
-                    let chunk_offset = encoded_shard_offset
-                        .fetch_add(chunk_encoded.len(), std::sync::atomic::Ordering::Relaxed);
-                    unsafe {
-                        let shard_index_unsafe = shard_index_slice.get();
-                        shard_index_unsafe[chunk_index * 2] = u64::try_from(chunk_offset).unwrap();
-                        shard_index_unsafe[chunk_index * 2 + 1] =
-                            u64::try_from(chunk_encoded.len()).unwrap();
-
-                        let shard_unsafe = shard_slice.get();
-                        shard_unsafe[chunk_offset..chunk_offset + chunk_encoded.len()]
-                            .copy_from_slice(&chunk_encoded);
+            options.install(|| {
+                rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                    options.concurrent_target(),
+                    encoded_chunks,
+                    for_each,
+                    |(chunk_index, chunk_offset, chunk_encoded): (usize, usize, Vec<u8>)| {
+                        unsafe {
+                            let shard_index_unsafe = shard_index_slice.get();
+                            shard_index_unsafe[chunk_index * 2] =
+                                u64::try_from(chunk_offset).unwrap();
+                            shard_index_unsafe[chunk_index * 2 + 1] =
+                                u64::try_from(chunk_encoded.len()).unwrap();
+
+                            let shard_unsafe = shard_slice.get();
+                            shard_unsafe[chunk_offset..chunk_offset + chunk_encoded.len()]
+                                .copy_from_slice(&chunk_encoded);
+                        }
                     }
-                }
-            );
+                );
+            });
         }
 
         // Write shard index
         let encoded_array_index = self.index_codecs.encode(
-            transmute_to_bytes_vec(shard_index),
+            transmute_to_bytes_vec(shard_index).into(),
             &index_decoded_representation,
             options,
         )?;
@@ -713,7 +804,7 @@ impl ShardingCodec {
 
         // Decode the shard index
         decode_shard_index(
-            encoded_shard_index.to_vec(),
+            encoded_shard_index.into(),
             &index_array_representation,
             &self.index_codecs,
             options,
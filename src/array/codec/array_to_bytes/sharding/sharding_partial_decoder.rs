@@ -109,7 +109,7 @@ impl<'a> ShardingPartialDecoder<'a> {
 
         Ok(match encoded_shard_index {
             Some(encoded_shard_index) => Some(decode_shard_index(
-                encoded_shard_index,
+                encoded_shard_index.into(),
                 &index_array_representation,
                 index_codecs,
                 options,
@@ -193,72 +193,74 @@ impl ArrayPartialDecoderTraits for ShardingPartialDecoder<'_> {
             let out_array_subset_slice = UnsafeCellSlice::new(out_array_subset.as_mut_slice());
 
             let chunks = unsafe { array_subset.chunks_unchecked(chunk_representation.shape()) };
-            rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                inner_chunk_concurrent_limit,
-                chunks,
-                try_for_each,
-                |(chunk_indices, chunk_subset): (Vec<u64>, _)| {
-                    let out_array_subset_slice = unsafe { out_array_subset_slice.get() };
-
-                    let shard_index_idx: usize =
-                        usize::try_from(ravel_indices(&chunk_indices, &chunks_per_shard) * 2)
-                            .unwrap();
-                    let offset = shard_index[shard_index_idx];
-                    let size = shard_index[shard_index_idx + 1];
+            options.install(|| {
+                rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                    inner_chunk_concurrent_limit,
+                    chunks,
+                    try_for_each,
+                    |(chunk_indices, chunk_subset): (Vec<u64>, _)| {
+                        let out_array_subset_slice = unsafe { out_array_subset_slice.get() };
 
-                    // Get the subset of bytes from the chunk which intersect the array
-                    let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset) };
-                    let array_subset_in_chunk_subset =
-                        unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+                        let shard_index_idx: usize =
+                            usize::try_from(ravel_indices(&chunk_indices, &chunks_per_shard) * 2)
+                                .unwrap();
+                        let offset = shard_index[shard_index_idx];
+                        let size = shard_index[shard_index_idx + 1];
 
-                    let decoded_bytes = if offset == u64::MAX && size == u64::MAX {
-                        // The chunk is just the fill value
-                        fill_value.repeat(array_subset_in_chunk_subset.num_elements_usize())
-                    } else {
-                        // Partially decode the inner chunk
-                        let partial_decoder = self.inner_codecs.partial_decoder(
-                            Box::new(ByteIntervalPartialDecoder::new(
-                                &*self.input_handle,
-                                offset,
-                                size,
-                            )),
-                            &chunk_representation,
-                            &options,
-                        )
-                        .map_err(|err| if let CodecError::InvalidByteRangeError(_) = err {
-                            CodecError::Other(
-                                "The shard index references out-of-bounds bytes. The chunk may be corrupted."
-                                    .to_string(),
-                            )
+                        // Get the subset of bytes from the chunk which intersect the array
+                        let overlap = unsafe { array_subset.overlap_unchecked(&chunk_subset) };
+                        let array_subset_in_chunk_subset =
+                            unsafe { overlap.relative_to_unchecked(chunk_subset.start()) };
+
+                        let decoded_bytes = if offset == u64::MAX && size == u64::MAX {
+                            // The chunk is just the fill value
+                            fill_value.repeat(array_subset_in_chunk_subset.num_elements_usize())
                         } else {
-                            err
-                        })?;
-                        partial_decoder
-                            .partial_decode_opt(&[array_subset_in_chunk_subset], &options)?
-                            .remove(0)
-                    };
-
-                    // Copy decoded bytes to the output
-                    let chunk_subset_in_array_subset =
-                        unsafe { overlap.relative_to_unchecked(array_subset.start()) };
-                    let mut decoded_offset = 0;
-                    let contiguous_iterator = unsafe {
-                        chunk_subset_in_array_subset
-                            .contiguous_linearised_indices_unchecked(array_subset.shape())
-                    };
-                    let length = contiguous_iterator.contiguous_elements_usize() * element_size;
-                    for (array_subset_element_index, _num_elements) in &contiguous_iterator {
-                        let output_offset =
-                            usize::try_from(array_subset_element_index).unwrap() * element_size;
-                        out_array_subset_slice[output_offset..output_offset + length]
-                            .copy_from_slice(
-                                &decoded_bytes[decoded_offset..decoded_offset + length],
-                            );
-                        decoded_offset += length;
+                            // Partially decode the inner chunk
+                            let partial_decoder = self.inner_codecs.partial_decoder(
+                                Box::new(ByteIntervalPartialDecoder::new(
+                                    &*self.input_handle,
+                                    offset,
+                                    size,
+                                )),
+                                &chunk_representation,
+                                &options,
+                            )
+                            .map_err(|err| if let CodecError::InvalidByteRangeError(_) = err {
+                                CodecError::Other(
+                                    "The shard index references out-of-bounds bytes. The chunk may be corrupted."
+                                        .to_string(),
+                                )
+                            } else {
+                                err
+                            })?;
+                            partial_decoder
+                                .partial_decode_opt(&[array_subset_in_chunk_subset], &options)?
+                                .remove(0)
+                        };
+
+                        // Copy decoded bytes to the output
+                        let chunk_subset_in_array_subset =
+                            unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+                        let mut decoded_offset = 0;
+                        let contiguous_iterator = unsafe {
+                            chunk_subset_in_array_subset
+                                .contiguous_linearised_indices_unchecked(array_subset.shape())
+                        };
+                        let length = contiguous_iterator.contiguous_elements_usize() * element_size;
+                        for (array_subset_element_index, _num_elements) in &contiguous_iterator {
+                            let output_offset =
+                                usize::try_from(array_subset_element_index).unwrap() * element_size;
+                            out_array_subset_slice[output_offset..output_offset + length]
+                                .copy_from_slice(
+                                    &decoded_bytes[decoded_offset..decoded_offset + length],
+                                );
+                            decoded_offset += length;
+                        }
+                        Ok::<_, CodecError>(())
                     }
-                    Ok::<_, CodecError>(())
-                }
-            )?;
+                )
+            })?;
             out.push(out_array_subset);
         }
         Ok(out)
@@ -348,7 +350,7 @@ impl<'a> AsyncShardingPartialDecoder<'a> {
 
         Ok(match encoded_shard_index {
             Some(encoded_shard_index) => Some(decode_shard_index(
-                encoded_shard_index,
+                encoded_shard_index.into(),
                 &index_array_representation,
                 index_codecs,
                 options,
@@ -494,33 +496,37 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder<'_> {
             // FIXME: Concurrency limit for futures
 
             if !results.is_empty() {
-                rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                    options.concurrent_target(),
-                    results,
-                    try_for_each,
-                    |subset_and_decoded_chunk| {
-                        let (chunk_subset_in_array_subset, decoded_chunk): (ArraySubset, Vec<u8>) =
-                            subset_and_decoded_chunk?;
-                        let mut data_idx = 0;
-                        let element_size = element_size as u64;
-                        let shard_slice = unsafe { shard_slice.get() };
-                        let contiguous_iterator = unsafe {
-                            chunk_subset_in_array_subset
-                                .contiguous_linearised_indices_unchecked(array_subset.shape())
-                        };
-                        let length = usize::try_from(
-                            contiguous_iterator.contiguous_elements() * element_size,
-                        )
-                        .unwrap();
-                        for (index, _num_elements) in &contiguous_iterator {
-                            let shard_offset = usize::try_from(index * element_size).unwrap();
-                            shard_slice[shard_offset..shard_offset + length]
-                                .copy_from_slice(&decoded_chunk[data_idx..data_idx + length]);
-                            data_idx += length;
+                options.install(|| {
+                    rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                        options.concurrent_target(),
+                        results,
+                        try_for_each,
+                        |subset_and_decoded_chunk| {
+                            let (chunk_subset_in_array_subset, decoded_chunk): (
+                                ArraySubset,
+                                Vec<u8>,
+                            ) = subset_and_decoded_chunk?;
+                            let mut data_idx = 0;
+                            let element_size = element_size as u64;
+                            let shard_slice = unsafe { shard_slice.get() };
+                            let contiguous_iterator = unsafe {
+                                chunk_subset_in_array_subset
+                                    .contiguous_linearised_indices_unchecked(array_subset.shape())
+                            };
+                            let length = usize::try_from(
+                                contiguous_iterator.contiguous_elements() * element_size,
+                            )
+                            .unwrap();
+                            for (index, _num_elements) in &contiguous_iterator {
+                                let shard_offset = usize::try_from(index * element_size).unwrap();
+                                shard_slice[shard_offset..shard_offset + length]
+                                    .copy_from_slice(&decoded_chunk[data_idx..data_idx + length]);
+                                data_idx += length;
+                            }
+                            Ok::<_, CodecError>(())
                         }
-                        Ok::<_, CodecError>(())
-                    }
-                )?;
+                    )
+                })?;
             }
 
             // Write filled chunks
@@ -543,34 +549,36 @@ impl AsyncArrayPartialDecoderTraits for AsyncShardingPartialDecoder<'_> {
                     .repeat(chunk_array_ss.num_elements_usize());
 
                 // Write filled chunks
-                rayon_iter_concurrent_limit::iter_concurrent_limit!(
-                    options.concurrent_target(),
-                    filled_chunks,
-                    for_each,
-                    |chunk_subset: &ArraySubset| {
-                        let overlap = unsafe { array_subset.overlap_unchecked(chunk_subset) };
-                        let chunk_subset_in_array_subset =
-                            unsafe { overlap.relative_to_unchecked(array_subset.start()) };
-                        let mut data_idx = 0;
-                        let element_size = self.decoded_representation.element_size() as u64;
-                        let shard_slice = unsafe { shard_slice.get() };
-                        let contiguous_iterator = unsafe {
-                            chunk_subset_in_array_subset
-                                .contiguous_linearised_indices_unchecked(array_subset.shape())
-                        };
-                        let length = usize::try_from(
-                            contiguous_iterator.contiguous_elements() * element_size,
-                        )
-                        .unwrap();
-                        for (index, _num_elements) in &contiguous_iterator {
-                            let shard_offset = usize::try_from(index * element_size).unwrap();
-                            shard_slice[shard_offset..shard_offset + length]
-                                .copy_from_slice(&filled_chunk[data_idx..data_idx + length]);
-                            data_idx += length;
+                options.install(|| {
+                    rayon_iter_concurrent_limit::iter_concurrent_limit!(
+                        options.concurrent_target(),
+                        filled_chunks,
+                        for_each,
+                        |chunk_subset: &ArraySubset| {
+                            let overlap = unsafe { array_subset.overlap_unchecked(chunk_subset) };
+                            let chunk_subset_in_array_subset =
+                                unsafe { overlap.relative_to_unchecked(array_subset.start()) };
+                            let mut data_idx = 0;
+                            let element_size = self.decoded_representation.element_size() as u64;
+                            let shard_slice = unsafe { shard_slice.get() };
+                            let contiguous_iterator = unsafe {
+                                chunk_subset_in_array_subset
+                                    .contiguous_linearised_indices_unchecked(array_subset.shape())
+                            };
+                            let length = usize::try_from(
+                                contiguous_iterator.contiguous_elements() * element_size,
+                            )
+                            .unwrap();
+                            for (index, _num_elements) in &contiguous_iterator {
+                                let shard_offset = usize::try_from(index * element_size).unwrap();
+                                shard_slice[shard_offset..shard_offset + length]
+                                    .copy_from_slice(&filled_chunk[data_idx..data_idx + length]);
+                                data_idx += length;
+                            }
                         }
-                    }
-                );
-            };
+                    );
+                });
+            }
             unsafe { shard.set_len(shard_size) };
             out.push(shard);
         }
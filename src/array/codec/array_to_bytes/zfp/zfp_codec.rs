@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use zfp_sys::{
     zfp_compress,
     zfp_stream_maximum_size,
@@ -13,8 +15,9 @@ use crate::{
             BytesPartialDecoderTraits, CodecError, CodecOptions, CodecTraits,
             RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType,
+        ArrayMetadataOptions, BytesRepresentation, ChunkRepresentation, DataType, RawBytes,
     },
+    audit::{audit, AuditEvent},
     metadata::Metadata,
 };
 
@@ -134,14 +137,17 @@ impl ArrayCodecTraits for ZfpCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
+        if !matches!(self.mode, ZfpMode::Reversible) {
+            audit(&AuditEvent::LossyEncode { codec: IDENTIFIER });
+        }
         let mut decoded_value_promoted =
-            promote_before_zfp_encoding(decoded_value, decoded_representation)?;
+            promote_before_zfp_encoding(decoded_value.into_owned(), decoded_representation)?;
         let zfp_type = decoded_value_promoted.zfp_type();
         let Some(field) = ZfpField::new(
             &mut decoded_value_promoted,
@@ -183,22 +189,23 @@ impl ArrayCodecTraits for ZfpCodec {
             Err(CodecError::from("zfp compression failed"))
         } else {
             encoded_value.truncate(size);
-            Ok(encoded_value)
+            Ok(Cow::Owned(encoded_value))
         }
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         decoded_representation: &ChunkRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         zfp_decode(
             &self.mode,
-            encoded_value,
+            encoded_value.into_owned(),
             decoded_representation,
             false, // FIXME
         )
+        .map(Cow::Owned)
     }
 }
 
@@ -0,0 +1,72 @@
+//! Asynchronous executors for blocking codec work.
+
+use std::sync::Arc;
+
+use rayon::ThreadPool;
+
+/// A blocking task run by an [`AsyncExecutor`].
+pub type BlockingTask = Box<dyn FnOnce() + Send + 'static>;
+
+/// An asynchronous executor for blocking codec work.
+///
+/// See [`AsyncExecutorTraits`].
+pub type AsyncExecutor = Arc<dyn AsyncExecutorTraits>;
+
+/// Traits for an asynchronous executor for blocking codec work.
+///
+/// Encoding and decoding chunks is CPU-bound and would otherwise run on the thread polling an async array operation, stalling other tasks of the asynchronous runtime.
+/// If [`CodecOptions::async_executor`](super::CodecOptions::async_executor) is set, async array operations instead encode and decode chunks in blocking tasks spawned by the executor and await their completion.
+///
+/// zarrs does not depend on a specific asynchronous runtime.
+/// The [`RayonAsyncExecutor`] runs blocking tasks in a [`rayon`] thread pool and works with any runtime.
+/// Runtime specific executors are straightforward to implement, for example:
+/// ```rust,ignore
+/// #[derive(Debug)]
+/// struct TokioAsyncExecutor;
+///
+/// impl AsyncExecutorTraits for TokioAsyncExecutor {
+///     fn spawn_blocking(&self, task: BlockingTask) {
+///         tokio::task::spawn_blocking(task);
+///     }
+/// }
+/// ```
+pub trait AsyncExecutorTraits: Send + Sync + core::fmt::Debug {
+    /// Spawn the blocking `task` without waiting for it to complete.
+    ///
+    /// The task is awaited through a channel, so an executor must eventually either run or drop the task.
+    /// A dropped task (e.g. if the runtime is shutting down or the task panics) fails the operation that spawned it with a [`CodecError`](super::CodecError).
+    fn spawn_blocking(&self, task: BlockingTask);
+}
+
+/// An [`AsyncExecutor`] which runs blocking tasks in a [`rayon`] thread pool.
+///
+/// Tasks run in the global [`rayon`] thread pool unless a thread pool is set with [`RayonAsyncExecutor::with_thread_pool`].
+#[derive(Debug, Default)]
+pub struct RayonAsyncExecutor {
+    thread_pool: Option<Arc<ThreadPool>>,
+}
+
+impl RayonAsyncExecutor {
+    /// Create a new [`rayon`] executor which runs blocking tasks in the global [`rayon`] thread pool.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`rayon`] executor which runs blocking tasks in `thread_pool`.
+    #[must_use]
+    pub fn with_thread_pool(thread_pool: Arc<ThreadPool>) -> Self {
+        Self {
+            thread_pool: Some(thread_pool),
+        }
+    }
+}
+
+impl AsyncExecutorTraits for RayonAsyncExecutor {
+    fn spawn_blocking(&self, task: BlockingTask) {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.spawn(task),
+            None => rayon::spawn(task),
+        }
+    }
+}
@@ -5,6 +5,28 @@ use super::{BytesPartialDecoderTraits, CodecError, CodecOptions};
 #[cfg(feature = "async")]
 use super::AsyncBytesPartialDecoderTraits;
 
+/// Map a byte range within the interval at `byte_offset` with `byte_length` to a byte range from the start of the inner bytes.
+fn interval_byte_range(
+    byte_offset: ByteOffset,
+    byte_length: ByteLength,
+    byte_range: &ByteRange,
+) -> ByteRange {
+    match byte_range {
+        ByteRange::FromStart(offset, None) => {
+            ByteRange::FromStart(byte_offset + offset, Some(byte_length - offset))
+        }
+        ByteRange::FromStart(offset, Some(length)) => {
+            ByteRange::FromStart(byte_offset + offset, Some(*length))
+        }
+        ByteRange::FromEnd(offset, None) => {
+            ByteRange::FromStart(byte_offset, Some(byte_length - offset))
+        }
+        ByteRange::FromEnd(offset, Some(length)) => {
+            ByteRange::FromStart(byte_offset + byte_length - offset - length, Some(*length))
+        }
+    }
+}
+
 /// A partial decoder for a byte interval of a [`BytesPartialDecoderTraits`] partial decoder.
 ///
 /// Modifies byte range requests to a specific byte interval in an inner bytes partial decoder.
@@ -29,7 +51,7 @@ impl<'a> ByteIntervalPartialDecoder<'a> {
     }
 }
 
-impl<'a> BytesPartialDecoderTraits for ByteIntervalPartialDecoder<'a> {
+impl BytesPartialDecoderTraits for ByteIntervalPartialDecoder<'_> {
     fn partial_decode(
         &self,
         byte_ranges: &[ByteRange],
@@ -37,21 +59,7 @@ impl<'a> BytesPartialDecoderTraits for ByteIntervalPartialDecoder<'a> {
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
         let byte_ranges: Vec<ByteRange> = byte_ranges
             .iter()
-            .map(|byte_range| match byte_range {
-                ByteRange::FromStart(offset, None) => {
-                    ByteRange::FromStart(self.byte_offset + offset, Some(self.byte_length))
-                }
-                ByteRange::FromStart(offset, Some(length)) => {
-                    ByteRange::FromStart(self.byte_offset + offset, Some(*length))
-                }
-                ByteRange::FromEnd(offset, None) => {
-                    ByteRange::FromStart(self.byte_offset, Some(self.byte_length - *offset))
-                }
-                ByteRange::FromEnd(offset, Some(length)) => ByteRange::FromEnd(
-                    self.byte_offset + self.byte_length - offset - *length,
-                    Some(*length),
-                ),
-            })
+            .map(|byte_range| interval_byte_range(self.byte_offset, self.byte_length, byte_range))
             .collect();
         self.inner.partial_decode(&byte_ranges, options)
     }
@@ -85,7 +93,7 @@ impl<'a> AsyncByteIntervalPartialDecoder<'a> {
 
 #[cfg(feature = "async")]
 #[async_trait::async_trait]
-impl<'a> AsyncBytesPartialDecoderTraits for AsyncByteIntervalPartialDecoder<'a> {
+impl AsyncBytesPartialDecoderTraits for AsyncByteIntervalPartialDecoder<'_> {
     async fn partial_decode(
         &self,
         byte_ranges: &[ByteRange],
@@ -93,22 +101,41 @@ impl<'a> AsyncBytesPartialDecoderTraits for AsyncByteIntervalPartialDecoder<'a>
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
         let byte_ranges: Vec<ByteRange> = byte_ranges
             .iter()
-            .map(|byte_range| match byte_range {
-                ByteRange::FromStart(offset, None) => {
-                    ByteRange::FromStart(self.byte_offset + offset, Some(self.byte_length))
-                }
-                ByteRange::FromStart(offset, Some(length)) => {
-                    ByteRange::FromStart(self.byte_offset + offset, Some(*length))
-                }
-                ByteRange::FromEnd(offset, None) => {
-                    ByteRange::FromStart(self.byte_offset, Some(self.byte_length - *offset))
-                }
-                ByteRange::FromEnd(offset, Some(length)) => ByteRange::FromEnd(
-                    self.byte_offset + self.byte_length - offset - *length,
-                    Some(*length),
-                ),
-            })
+            .map(|byte_range| interval_byte_range(self.byte_offset, self.byte_length, byte_range))
             .collect();
         self.inner.partial_decode(&byte_ranges, options).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_interval_partial_decoder() {
+        let bytes: Vec<u8> = (0..10).collect();
+        let input = std::io::Cursor::new(bytes.as_slice());
+        let partial_decoder = ByteIntervalPartialDecoder::new(&input, 2, 6);
+        let decoded = partial_decoder
+            .partial_decode(
+                &[
+                    ByteRange::FromStart(1, None),
+                    ByteRange::FromStart(1, Some(2)),
+                    ByteRange::FromEnd(1, None),
+                    ByteRange::FromEnd(1, Some(2)),
+                ],
+                &CodecOptions::default(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                vec![3, 4, 5, 6, 7],
+                vec![3, 4],
+                vec![2, 3, 4, 5, 6],
+                vec![5, 6]
+            ]
+        );
+    }
+}
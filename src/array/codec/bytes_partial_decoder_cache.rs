@@ -18,7 +18,7 @@ pub struct BytesPartialDecoderCache<'a> {
     phantom: PhantomData<&'a ()>,
 }
 
-impl<'a> BytesPartialDecoderCache<'a> {
+impl BytesPartialDecoderCache<'_> {
     /// Create a new partial decoder cache.
     ///
     /// # Errors
@@ -29,7 +29,7 @@ impl<'a> BytesPartialDecoderCache<'a> {
     ) -> Result<Self, CodecError> {
         let cache = input_handle
             .partial_decode(&[ByteRange::FromStart(0, None)], options)?
-            .map(|mut bytes| bytes.remove(0));
+            .map(|mut bytes| bytes.remove(0).into());
         Ok(Self {
             cache,
             phantom: PhantomData,
@@ -44,11 +44,11 @@ impl<'a> BytesPartialDecoderCache<'a> {
     pub async fn async_new(
         input_handle: &dyn AsyncBytesPartialDecoderTraits,
         options: &CodecOptions,
-    ) -> Result<BytesPartialDecoderCache<'a>, CodecError> {
+    ) -> Result<Self, CodecError> {
         let cache = input_handle
             .partial_decode(&[ByteRange::FromStart(0, None)], options)
             .await?
-            .map(|mut bytes| bytes.remove(0));
+            .map(|mut bytes| bytes.remove(0).into());
         Ok(Self {
             cache,
             phantom: PhantomData,
@@ -303,6 +303,7 @@ fn blosc_decompress_bytes_partial(
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation, clippy::should_panic_without_expect)]
 mod tests {
     use crate::{
         array::{
@@ -359,12 +360,12 @@ mod tests {
         let codec = BloscCodec::new_with_configuration(&codec_configuration).unwrap();
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(encoded, &bytes_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
     }
 
     #[test]
@@ -373,6 +374,38 @@ mod tests {
         codec_blosc_round_trip(JSON_VALID1);
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_blosc_round_trip_stream() {
+        let elements: Vec<u16> = (0..32).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let codec_configuration: BloscCodecConfiguration =
+            serde_json::from_str(JSON_VALID1).unwrap();
+        let codec = BloscCodec::new_with_configuration(&codec_configuration).unwrap();
+        assert!(!codec.supports_streaming());
+
+        let mut encoded = Vec::new();
+        codec
+            .encode_stream(
+                &mut bytes.as_slice(),
+                &mut encoded,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let mut decoded = Vec::new();
+        codec
+            .decode_stream(
+                &mut encoded.as_slice(),
+                &mut decoded,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, *decoded);
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn codec_blosc_round_trip2() {
@@ -407,7 +440,9 @@ mod tests {
             serde_json::from_str(JSON_VALID2).unwrap();
         let codec = BloscCodec::new_with_configuration(&codec_configuration).unwrap();
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions: Vec<ByteRange> = ArraySubset::new_with_ranges(&[0..2, 1..2, 0..1])
             .byte_ranges(
                 array_representation.shape(),
@@ -455,7 +490,9 @@ mod tests {
             serde_json::from_str(JSON_VALID2).unwrap();
         let codec = BloscCodec::new_with_configuration(&codec_configuration).unwrap();
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions: Vec<ByteRange> = ArraySubset::new_with_ranges(&[0..2, 1..2, 0..1])
             .byte_ranges(
                 array_representation.shape(),
@@ -12,9 +12,7 @@ mod bz2_codec;
 mod bz2_configuration;
 mod bz2_partial_decoder;
 
-use derive_more::From;
 use serde::{Deserialize, Deserializer, Serialize};
-use thiserror::Error;
 
 use crate::{
     array::codec::{Codec, CodecPlugin},
@@ -48,16 +46,6 @@ pub(crate) fn create_codec_bz2(metadata: &Metadata) -> Result<Codec, PluginCreat
     Ok(Codec::BytesToBytes(codec))
 }
 
-#[derive(Debug, Error, From)]
-#[error("{0}")]
-struct Bz2Error(String);
-
-impl From<&str> for Bz2Error {
-    fn from(err: &str) -> Self {
-        Self(err.to_string())
-    }
-}
-
 /// An integer from 0 to 9 controlling the compression level
 ///
 /// A level of 1 is the fastest compression method and produces the least compression, while 9 is slowest and produces the most compression.
@@ -123,6 +111,7 @@ impl Bz2CompressionLevel {
 }
 
 #[cfg(test)]
+#[allow(clippy::cast_possible_truncation)]
 mod tests {
     use crate::{
         array::{
@@ -151,12 +140,12 @@ mod tests {
         let codec = Bz2Codec::new_with_configuration(&codec_configuration);
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(encoded, &bytes_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
     }
 
     #[test]
@@ -173,7 +162,9 @@ mod tests {
         let codec_configuration: Bz2CodecConfiguration = serde_json::from_str(JSON_VALID1).unwrap();
         let codec = Bz2Codec::new_with_configuration(&codec_configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions: Vec<ByteRange> = ArraySubset::new_with_ranges(&[0..2, 1..2, 0..1])
             .byte_ranges(
                 array_representation.shape(),
@@ -220,7 +211,9 @@ mod tests {
         let codec_configuration: Bz2CodecConfiguration = serde_json::from_str(JSON_VALID1).unwrap();
         let codec = Bz2Codec::new_with_configuration(&codec_configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions: Vec<ByteRange> = ArraySubset::new_with_ranges(&[0..2, 1..2, 0..1])
             .byte_ranges(
                 array_representation.shape(),
@@ -1,4 +1,6 @@
-use std::io::Read;
+use std::borrow::Cow;
+
+use std::io::{Read, Write};
 
 use crate::{
     array::{
@@ -6,7 +8,7 @@ use crate::{
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions,
             CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::Metadata,
 };
@@ -68,27 +70,55 @@ impl BytesToBytesCodecTraits for Bz2Codec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
-        let mut encoder = bzip2::read::BzEncoder::new(decoded_value.as_slice(), self.compression);
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let mut encoder = bzip2::read::BzEncoder::new(decoded_value.as_ref(), self.compression);
         let mut out: Vec<u8> = Vec::new();
         encoder.read_to_end(&mut out)?;
-        Ok(out)
+        Ok(Cow::Owned(out))
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         _decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
-        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_slice());
+    ) -> Result<RawBytes<'a>, CodecError> {
+        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_ref());
         let mut out: Vec<u8> = Vec::new();
         decoder.read_to_end(&mut out)?;
-        Ok(out)
+        Ok(Cow::Owned(out))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn Read,
+        encoded_writer: &mut dyn Write,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut encoder = bzip2::write::BzEncoder::new(encoded_writer, self.compression);
+        std::io::copy(decoded_reader, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    fn decode_stream(
+        &self,
+        encoded_reader: &mut dyn Read,
+        decoded_writer: &mut dyn Write,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut decoder = bzip2::read::BzDecoder::new(encoded_reader);
+        std::io::copy(&mut decoder, decoded_writer)?;
+        Ok(())
     }
 
     fn partial_decoder<'a>(
@@ -123,7 +153,7 @@ impl BytesToBytesCodecTraits for Bz2Codec {
             .map_or(BytesRepresentation::UnboundedSize, |size| {
                 // https://en.wikipedia.org/wiki/Bzip2#Implementation
                 // TODO: Below assumes a maximum expansion of 1.25 for the blocks + header (4 byte) + footer (11 byte), but need to read spec
-                BytesRepresentation::BoundedSize(4 + 11 + size + (size + 3) / 4)
+                BytesRepresentation::BoundedSize(4 + 11 + size + size.div_ceil(4))
             })
     }
 }
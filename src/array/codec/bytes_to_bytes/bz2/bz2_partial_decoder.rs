@@ -30,7 +30,7 @@ impl BytesPartialDecoderTraits for Bz2PartialDecoder<'_> {
             return Ok(None);
         };
 
-        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_slice());
+        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_ref());
         let mut decompressed = Vec::new();
         decoder.read_to_end(&mut decompressed)?;
 
@@ -67,7 +67,7 @@ impl AsyncBytesPartialDecoderTraits for AsyncBz2PartialDecoder<'_> {
             return Ok(None);
         };
 
-        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_slice());
+        let mut decoder = bzip2::read::BzDecoder::new(encoded_value.as_ref());
         let mut decompressed = Vec::new();
         decoder.read_to_end(&mut decompressed)?;
 
@@ -46,19 +46,19 @@ const CHECKSUM_SIZE: usize = core::mem::size_of::<u32>();
 mod tests {
     use crate::{
         array::{
-            codec::{BytesToBytesCodecTraits, CodecOptions, CodecTraits},
-            BytesRepresentation,
+            codec::{BytesToBytesCodecTraits, CodecError, CodecOptions, CodecTraits},
+            BytesRepresentation, RawBytes,
         },
         byte_range::ByteRange,
     };
 
     use super::*;
 
-    const JSON1: &str = r#"{}"#;
+    const JSON1: &str = "{}";
 
     #[test]
     fn codec_crc32c_configuration_none() {
-        let codec_configuration: Crc32cCodecConfiguration = serde_json::from_str(r#"{}"#).unwrap();
+        let codec_configuration: Crc32cCodecConfiguration = serde_json::from_str("{}").unwrap();
         let codec = Crc32cCodec::new_with_configuration(&codec_configuration);
         let metadata = codec.create_metadata().unwrap();
         assert_eq!(
@@ -77,7 +77,7 @@ mod tests {
         let codec = Crc32cCodec::new_with_configuration(&codec_configuration);
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(
@@ -86,7 +86,7 @@ mod tests {
                 &CodecOptions::default(),
             )
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
 
         // Check that the checksum is correct
         let checksum: &[u8; 4] = &encoded
@@ -97,6 +97,82 @@ mod tests {
         assert_eq!(checksum, &[20, 133, 9, 65]);
     }
 
+    #[test]
+    fn codec_crc32c_decode_borrowed() {
+        let bytes: Vec<u8> = (0..6).collect();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+        let codec = Crc32cCodec::new();
+
+        let encoded = codec
+            .encode(RawBytes::Borrowed(&bytes), &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(
+                RawBytes::Borrowed(&encoded),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        // Decoding strips the checksum without copying
+        assert!(matches!(decoded, RawBytes::Borrowed(_)));
+        assert_eq!(bytes, *decoded);
+    }
+
+    #[test]
+    fn codec_crc32c_stream() {
+        // Larger than the stream buffer, so the checksum spans reads
+        let bytes: Vec<u8> = (0..200_000u32).map(|i| i.to_le_bytes()[0]).collect();
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+        let codec = Crc32cCodec::new();
+        assert!(codec.supports_streaming());
+
+        let mut encoded = Vec::new();
+        codec
+            .encode_stream(
+                &mut bytes.as_slice(),
+                &mut encoded,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            encoded,
+            codec
+                .encode(bytes.clone().into(), &CodecOptions::default())
+                .unwrap()
+                .into_owned()
+        );
+
+        let mut decoded = Vec::new();
+        codec
+            .decode_stream(
+                &mut encoded.as_slice(),
+                &mut decoded,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, *decoded);
+
+        *encoded.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            codec.decode_stream(
+                &mut encoded.as_slice(),
+                &mut Vec::new(),
+                &bytes_representation,
+                &CodecOptions::default(),
+            ),
+            Err(CodecError::InvalidChecksum)
+        ));
+        assert!(codec
+            .decode_stream(
+                &mut [0u8; 3].as_slice(),
+                &mut Vec::new(),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .is_err());
+    }
+
     #[test]
     fn codec_crc32c_partial_decode() {
         let elements: Vec<u8> = (0..32).collect();
@@ -106,7 +182,9 @@ mod tests {
         let codec_configuration: Crc32cCodecConfiguration = serde_json::from_str(JSON1).unwrap();
         let codec = Crc32cCodec::new_with_configuration(&codec_configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [ByteRange::FromStart(3, Some(2))];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
         let partial_decoder = codec
@@ -134,7 +212,9 @@ mod tests {
         let codec_configuration: Crc32cCodecConfiguration = serde_json::from_str(JSON1).unwrap();
         let codec = Crc32cCodec::new_with_configuration(&codec_configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [ByteRange::FromStart(3, Some(2))];
         let input_handle = Box::new(std::io::Cursor::new(encoded));
         let partial_decoder = codec
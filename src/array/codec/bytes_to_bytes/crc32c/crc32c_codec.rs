@@ -1,10 +1,14 @@
+use std::borrow::Cow;
+
+use std::io::{ErrorKind, Read, Write};
+
 use crate::{
     array::{
         codec::{
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions,
             CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::Metadata,
 };
@@ -17,6 +21,9 @@ use super::{
     Crc32cCodecConfiguration, CHECKSUM_SIZE, IDENTIFIER,
 };
 
+/// The size of the buffer used by [`Crc32cCodec`] when streaming.
+const STREAM_BUFFER_SIZE: usize = 65536;
+
 /// A `CRC32C checksum` codec implementation.
 #[derive(Clone, Debug, Default)]
 pub struct Crc32cCodec;
@@ -59,23 +66,24 @@ impl BytesToBytesCodecTraits for Crc32cCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        mut decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         let checksum = crc32c::crc32c(&decoded_value).to_le_bytes();
-        decoded_value.reserve_exact(checksum.len());
-        decoded_value.extend(&checksum);
-        Ok(decoded_value)
+        let mut encoded_value = decoded_value.into_owned();
+        encoded_value.reserve_exact(checksum.len());
+        encoded_value.extend(&checksum);
+        Ok(Cow::Owned(encoded_value))
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        mut encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         _decoded_representation: &BytesRepresentation,
         options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         if encoded_value.len() >= CHECKSUM_SIZE {
             if options.validate_checksums() {
                 let decoded_value = &encoded_value[..encoded_value.len() - CHECKSUM_SIZE];
@@ -84,8 +92,14 @@ impl BytesToBytesCodecTraits for Crc32cCodec {
                     return Err(CodecError::InvalidChecksum);
                 }
             }
-            encoded_value.resize_with(encoded_value.len() - CHECKSUM_SIZE, Default::default);
-            Ok(encoded_value)
+            let decoded_len = encoded_value.len() - CHECKSUM_SIZE;
+            Ok(match encoded_value {
+                Cow::Borrowed(encoded_value) => Cow::Borrowed(&encoded_value[..decoded_len]),
+                Cow::Owned(mut encoded_value) => {
+                    encoded_value.truncate(decoded_len);
+                    Cow::Owned(encoded_value)
+                }
+            })
         } else {
             Err(CodecError::Other(
                 "CRC32C checksum decoder expects a 32 bit input".to_string(),
@@ -93,6 +107,68 @@ impl BytesToBytesCodecTraits for Crc32cCodec {
         }
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn Read,
+        encoded_writer: &mut dyn Write,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut checksum = 0;
+        let mut buffer = vec![0; STREAM_BUFFER_SIZE];
+        loop {
+            let n = match decoded_reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            checksum = crc32c::crc32c_append(checksum, &buffer[..n]);
+            encoded_writer.write_all(&buffer[..n])?;
+        }
+        encoded_writer.write_all(&checksum.to_le_bytes())?;
+        Ok(())
+    }
+
+    fn decode_stream(
+        &self,
+        encoded_reader: &mut dyn Read,
+        decoded_writer: &mut dyn Write,
+        _decoded_representation: &BytesRepresentation,
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        // The last CHECKSUM_SIZE bytes read are held back until the end of the stream is reached
+        let mut checksum = 0;
+        let mut buffer = vec![0; CHECKSUM_SIZE + STREAM_BUFFER_SIZE];
+        let mut held = 0;
+        loop {
+            let n = match encoded_reader.read(&mut buffer[held..]) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let len = held + n;
+            let decoded_len = len.saturating_sub(CHECKSUM_SIZE);
+            checksum = crc32c::crc32c_append(checksum, &buffer[..decoded_len]);
+            decoded_writer.write_all(&buffer[..decoded_len])?;
+            buffer.copy_within(decoded_len..len, 0);
+            held = len - decoded_len;
+        }
+        if held != CHECKSUM_SIZE {
+            return Err(CodecError::Other(
+                "CRC32C checksum decoder expects a 32 bit input".to_string(),
+            ));
+        }
+        if options.validate_checksums() && checksum.to_le_bytes() != buffer[..CHECKSUM_SIZE] {
+            return Err(CodecError::InvalidChecksum);
+        }
+        Ok(())
+    }
+
     fn partial_decoder<'a>(
         &'a self,
         input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
@@ -44,7 +44,7 @@ impl BytesPartialDecoderTraits for Crc32cPartialDecoder<'_> {
                         bytes.resize(usize::try_from(length).unwrap(), 0);
                     }
                 }
-            };
+            }
         }
 
         Ok(Some(bytes))
@@ -94,7 +94,7 @@ impl AsyncBytesPartialDecoderTraits for AsyncCrc32cPartialDecoder<'_> {
                         bytes.resize(usize::try_from(length).unwrap(), 0);
                     }
                 }
-            };
+            }
         }
 
         Ok(Some(bytes))
@@ -2,13 +2,17 @@
 //!
 //! Applies [gzip](https://datatracker.ietf.org/doc/html/rfc1952) compression.
 //!
+//! Values can optionally be encoded as a blocked (BGZF-style) gzip stream with [`GzipCodec::with_block_size`] for efficient partial decoding.
+//!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/codecs/gzip/v1.0.html>.
 
+mod gzip_blocks;
 mod gzip_codec;
 mod gzip_compression_level;
 mod gzip_configuration;
 mod gzip_partial_decoder;
 
+pub use gzip_blocks::GZIP_MAX_BLOCK_SIZE;
 pub use gzip_codec::GzipCodec;
 pub use gzip_compression_level::{GzipCompressionLevel, GzipCompressionLevelError};
 pub use gzip_configuration::{GzipCodecConfiguration, GzipCodecConfigurationV1};
@@ -86,12 +90,53 @@ mod tests {
         let codec = GzipCodec::new_with_configuration(&configuration);
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(encoded, &bytes_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
+    }
+
+    #[test]
+    fn codec_gzip_round_trip_stream() {
+        let elements: Vec<u16> = (0..1000).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        for codec in [
+            GzipCodec::new(5).unwrap(),
+            GzipCodec::new(5).unwrap().with_block_size(Some(300)),
+        ] {
+            assert!(codec.supports_streaming());
+            let mut encoded = Vec::new();
+            codec
+                .encode_stream(
+                    &mut bytes.as_slice(),
+                    &mut encoded,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            let decoded = codec
+                .decode(
+                    encoded.clone().into(),
+                    &bytes_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(bytes, *decoded);
+
+            let mut decoded = Vec::new();
+            codec
+                .decode_stream(
+                    &mut encoded.as_slice(),
+                    &mut decoded,
+                    &bytes_representation,
+                    &CodecOptions::default(),
+                )
+                .unwrap();
+            assert_eq!(bytes, *decoded);
+        }
     }
 
     #[test]
@@ -103,7 +148,9 @@ mod tests {
         let configuration: GzipCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
         let codec = GzipCodec::new_with_configuration(&configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
@@ -133,6 +180,96 @@ mod tests {
         assert_eq!(answer, decoded_partial_chunk);
     }
 
+    #[test]
+    fn codec_gzip_blocked_partial_decode() {
+        let elements: Vec<u16> = (0..1000).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let codec = GzipCodec::new(5).unwrap().with_block_size(Some(300));
+        assert_eq!(codec.block_size(), Some(300));
+        let encoded = codec
+            .encode(bytes.clone().into(), &CodecOptions::default())
+            .unwrap();
+
+        // A blocked stream is decodable by a codec without a block size
+        let decoded = GzipCodec::new(5)
+            .unwrap()
+            .decode(
+                encoded.clone(),
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, *decoded);
+
+        let decoded_regions = [
+            ByteRange::FromStart(4, Some(4)),
+            ByteRange::FromStart(298, Some(4)),
+            ByteRange::FromStart(1000, Some(700)),
+            ByteRange::FromEnd(0, Some(2)),
+            ByteRange::FromStart(0, Some(0)),
+        ];
+        let input_handle = Box::new(std::io::Cursor::new(encoded));
+        let partial_decoder = codec
+            .partial_decoder(
+                input_handle,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let decoded_partial_chunk = partial_decoder
+            .partial_decode(&decoded_regions, &CodecOptions::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            decoded_partial_chunk,
+            crate::byte_range::extract_byte_ranges(&bytes, &decoded_regions).unwrap()
+        );
+        assert!(partial_decoder
+            .partial_decode(
+                &[ByteRange::FromStart(1999, Some(2))],
+                &CodecOptions::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn codec_gzip_blocked_empty() {
+        let codec = GzipCodec::new(5).unwrap().with_block_size(Some(16));
+        let encoded = codec
+            .encode(vec![].into(), &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(
+                encoded,
+                &BytesRepresentation::FixedSize(0),
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn codec_gzip_blocked_incompressible() {
+        let bytes: Vec<u8> = (0..200_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13).to_le_bytes()[0])
+            .collect();
+        let codec = GzipCodec::new(0).unwrap().with_block_size(Some(usize::MAX));
+        assert_eq!(codec.block_size(), Some(GZIP_MAX_BLOCK_SIZE));
+        let encoded = codec
+            .encode(bytes.clone().into(), &CodecOptions::default())
+            .unwrap();
+        let decoded = codec
+            .decode(
+                encoded,
+                &BytesRepresentation::FixedSize(bytes.len() as u64),
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, *decoded);
+    }
+
     #[cfg(feature = "async")]
     #[tokio::test]
     async fn codec_gzip_async_partial_decode() {
@@ -143,7 +280,9 @@ mod tests {
         let configuration: GzipCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
         let codec = GzipCodec::new_with_configuration(&configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
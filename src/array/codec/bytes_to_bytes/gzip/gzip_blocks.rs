@@ -0,0 +1,250 @@
+//! Blocked (BGZF-style) gzip encoding and indexed decoding.
+//!
+//! A blocked gzip stream is a concatenation of independent gzip members, each holding at most [`GZIP_MAX_BLOCK_SIZE`] decoded bytes.
+//! Every member carries a `BC` extra subfield with its compressed size, as in the [BGZF](https://samtools.github.io/hts-specs/SAMv1.pdf) format.
+//! This is still a valid gzip stream, but the members can be located and decompressed independently.
+
+use std::io::{Cursor, Read, Write};
+
+use flate2::bufread::{GzDecoder, MultiGzDecoder};
+
+use crate::{
+    array::codec::CodecError,
+    byte_range::{ByteRange, InvalidByteRangeError},
+};
+
+/// The maximum number of decoded bytes in a block of a blocked gzip stream.
+///
+/// This matches BGZF and guarantees that the compressed size of a block fits in the 16-bit `BC` subfield.
+pub const GZIP_MAX_BLOCK_SIZE: usize = 65280;
+
+const HEADER_SIZE: usize = 18;
+const TRAILER_SIZE: usize = 4 + 4;
+
+/// Append `block` encoded as a gzip member with a `BC` extra subfield to `out`.
+fn encode_block(block: &[u8], compression_level: u32, out: &mut Vec<u8>) -> Result<(), CodecError> {
+    let mut encoder = flate2::GzBuilder::new()
+        .extra(vec![b'B', b'C', 2, 0, 0, 0])
+        .buf_read(block, flate2::Compression::new(compression_level));
+    let offset = out.len();
+    encoder.read_to_end(out)?;
+    let bsize = u16::try_from(out.len() - offset - 1).map_err(|_| {
+        CodecError::Other("gzip block exceeds the maximum compressed size".to_string())
+    })?;
+    out[offset + 16..offset + 18].copy_from_slice(&bsize.to_le_bytes());
+    Ok(())
+}
+
+/// Encode `decoded_value` as a blocked gzip stream with `block_size` decoded bytes per member.
+pub(super) fn encode_blocked(
+    decoded_value: &[u8],
+    block_size: usize,
+    compression_level: u32,
+) -> Result<Vec<u8>, CodecError> {
+    let block_size = block_size.clamp(1, GZIP_MAX_BLOCK_SIZE);
+    let mut out = Vec::new();
+    if decoded_value.is_empty() {
+        encode_block(&[], compression_level, &mut out)?;
+    } else {
+        for block in decoded_value.chunks(block_size) {
+            encode_block(block, compression_level, &mut out)?;
+        }
+    }
+    Ok(out)
+}
+
+/// Encode the bytes read from `decoded_reader` as a blocked gzip stream with `block_size` decoded bytes per member.
+///
+/// Only one block is held in memory at a time.
+pub(super) fn encode_blocked_stream(
+    decoded_reader: &mut dyn Read,
+    encoded_writer: &mut dyn Write,
+    block_size: usize,
+    compression_level: u32,
+) -> Result<(), CodecError> {
+    let block_size = block_size.clamp(1, GZIP_MAX_BLOCK_SIZE);
+    let mut block = Vec::with_capacity(block_size);
+    let mut encoded_block = Vec::new();
+    let mut first = true;
+    loop {
+        block.clear();
+        decoded_reader
+            .take(block_size as u64)
+            .read_to_end(&mut block)?;
+        if block.is_empty() && !first {
+            return Ok(());
+        }
+        encoded_block.clear();
+        encode_block(&block, compression_level, &mut encoded_block)?;
+        encoded_writer.write_all(&encoded_block)?;
+        first = false;
+    }
+}
+
+/// Decode all members of a gzip stream.
+pub(super) fn decode_all(encoded_value: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let mut decoder = MultiGzDecoder::new(Cursor::new(encoded_value));
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Return the total size of the gzip member starting at `header` if it has a `BC` extra subfield.
+fn bgzf_member_size(header: &[u8]) -> Option<usize> {
+    const FEXTRA: u8 = 4;
+    if header.len() < 12 || header[..3] != [31, 139, 8] || header[3] & FEXTRA == 0 {
+        return None;
+    }
+    let xlen = usize::from(u16::from_le_bytes([header[10], header[11]]));
+    let mut extra = header.get(12..12 + xlen)?;
+    while extra.len() >= 4 {
+        let slen = usize::from(u16::from_le_bytes([extra[2], extra[3]]));
+        if extra[..2] == *b"BC" && slen == 2 {
+            let bsize = u16::from_le_bytes([*extra.get(4)?, *extra.get(5)?]);
+            return Some(usize::from(bsize) + 1);
+        }
+        extra = extra.get(4 + slen..)?;
+    }
+    None
+}
+
+/// The location of a member in a blocked gzip stream.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GzipBlock {
+    encoded_offset: usize,
+    encoded_size: usize,
+    decoded_offset: usize,
+    decoded_size: usize,
+}
+
+/// An index of the members of a gzip stream, built once by a partial decoder.
+#[derive(Debug)]
+pub(super) enum GzipIndex {
+    /// The stream is blocked, members are decompressed on demand.
+    Blocked {
+        encoded_value: bytes::Bytes,
+        blocks: Vec<GzipBlock>,
+        decoded_size: usize,
+    },
+    /// The stream is not blocked, so it was decompressed in full.
+    Decoded(Vec<u8>),
+}
+
+impl GzipIndex {
+    /// Index `encoded_value`.
+    ///
+    /// The members of a blocked stream are located from their headers and trailers without decompression.
+    /// Otherwise, the entire stream is decompressed.
+    pub(super) fn new(encoded_value: bytes::Bytes) -> Result<Self, CodecError> {
+        match Self::blocks(&encoded_value) {
+            Some(blocks) => {
+                let decoded_size = blocks
+                    .last()
+                    .map_or(0, |block| block.decoded_offset + block.decoded_size);
+                Ok(Self::Blocked {
+                    encoded_value,
+                    blocks,
+                    decoded_size,
+                })
+            }
+            None => Ok(Self::Decoded(decode_all(&encoded_value)?)),
+        }
+    }
+
+    fn blocks(encoded_value: &[u8]) -> Option<Vec<GzipBlock>> {
+        let mut blocks = Vec::new();
+        let mut encoded_offset = 0;
+        let mut decoded_offset = 0;
+        while encoded_offset < encoded_value.len() {
+            let encoded_size = bgzf_member_size(&encoded_value[encoded_offset..])?;
+            let encoded_end = encoded_offset + encoded_size;
+            if encoded_size < HEADER_SIZE + TRAILER_SIZE || encoded_end > encoded_value.len() {
+                return None;
+            }
+            let isize = u32::from_le_bytes(
+                encoded_value[encoded_end - 4..encoded_end]
+                    .try_into()
+                    .unwrap(),
+            );
+            let decoded_size = usize::try_from(isize).ok()?;
+            blocks.push(GzipBlock {
+                encoded_offset,
+                encoded_size,
+                decoded_offset,
+                decoded_size,
+            });
+            encoded_offset = encoded_end;
+            decoded_offset += decoded_size;
+        }
+        if blocks.is_empty() {
+            None
+        } else {
+            Some(blocks)
+        }
+    }
+
+    /// Decode `decoded_regions`, decompressing only the blocks they intersect.
+    pub(super) fn partial_decode(
+        &self,
+        decoded_regions: &[ByteRange],
+    ) -> Result<Vec<Vec<u8>>, CodecError> {
+        match self {
+            Self::Decoded(decoded) => {
+                crate::byte_range::extract_byte_ranges(decoded, decoded_regions)
+                    .map_err(CodecError::InvalidByteRangeError)
+            }
+            Self::Blocked {
+                encoded_value,
+                blocks,
+                decoded_size,
+            } => {
+                let decoded_size_u64 = *decoded_size as u64;
+                let mut decoded_blocks: Vec<Option<Vec<u8>>> = vec![None; blocks.len()];
+                let mut out = Vec::with_capacity(decoded_regions.len());
+                for byte_range in decoded_regions {
+                    let (start, length) = match byte_range {
+                        ByteRange::FromStart(offset, length)
+                        | ByteRange::FromEnd(offset, length) => (offset, length),
+                    };
+                    if start + length.unwrap_or(0) > decoded_size_u64 {
+                        return Err(
+                            InvalidByteRangeError::new(*byte_range, decoded_size_u64).into()
+                        );
+                    }
+                    let range = byte_range.to_range_usize(decoded_size_u64);
+                    let mut bytes = Vec::with_capacity(range.len());
+                    let first = blocks.partition_point(|block| {
+                        block.decoded_offset + block.decoded_size <= range.start
+                    });
+                    for (block, decoded_block) in std::iter::zip(blocks, &mut decoded_blocks)
+                        .skip(first)
+                        .take_while(|(block, _)| block.decoded_offset < range.end)
+                    {
+                        if decoded_block.is_none() {
+                            let encoded = &encoded_value
+                                [block.encoded_offset..block.encoded_offset + block.encoded_size];
+                            let mut gz_decoder = GzDecoder::new(encoded);
+                            let mut decoded = Vec::with_capacity(block.decoded_size);
+                            gz_decoder.read_to_end(&mut decoded)?;
+                            if decoded.len() != block.decoded_size {
+                                return Err(CodecError::UnexpectedChunkDecodedSize(
+                                    decoded.len(),
+                                    block.decoded_size as u64,
+                                ));
+                            }
+                            *decoded_block = Some(decoded);
+                        }
+                        let decoded = decoded_block.as_deref().unwrap_or_default();
+                        let block_start =
+                            range.start.max(block.decoded_offset) - block.decoded_offset;
+                        let block_end = range.end.min(block.decoded_offset + block.decoded_size)
+                            - block.decoded_offset;
+                        bytes.extend_from_slice(&decoded[block_start..block_end]);
+                    }
+                    out.push(bytes);
+                }
+                Ok(out)
+            }
+        }
+    }
+}
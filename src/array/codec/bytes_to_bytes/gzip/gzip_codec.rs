@@ -1,6 +1,8 @@
-use std::io::{Cursor, Read};
+use std::borrow::Cow;
 
-use flate2::bufread::{GzDecoder, GzEncoder};
+use std::io::{Cursor, Read, Write};
+
+use flate2::bufread::GzEncoder;
 
 use crate::{
     array::{
@@ -8,7 +10,7 @@ use crate::{
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions,
             CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::Metadata,
 };
@@ -17,15 +19,17 @@ use crate::{
 use crate::array::codec::AsyncBytesPartialDecoderTraits;
 
 use super::{
+    gzip_blocks::{self, GZIP_MAX_BLOCK_SIZE},
     gzip_compression_level::GzipCompressionLevelError,
-    gzip_configuration::GzipCodecConfigurationV1, gzip_partial_decoder, GzipCodecConfiguration,
-    GzipCompressionLevel, IDENTIFIER,
+    gzip_configuration::GzipCodecConfigurationV1,
+    gzip_partial_decoder, GzipCodecConfiguration, GzipCompressionLevel, IDENTIFIER,
 };
 
 /// A `gzip` codec implementation.
 #[derive(Clone, Debug)]
 pub struct GzipCodec {
     compression_level: GzipCompressionLevel,
+    block_size: Option<usize>,
 }
 
 impl GzipCodec {
@@ -35,7 +39,30 @@ impl GzipCodec {
     /// Returns [`GzipCompressionLevelError`] if `compression_level` is not valid.
     pub fn new(compression_level: u32) -> Result<Self, GzipCompressionLevelError> {
         let compression_level: GzipCompressionLevel = compression_level.try_into()?;
-        Ok(Self { compression_level })
+        Ok(Self {
+            compression_level,
+            block_size: None,
+        })
+    }
+
+    /// Set the number of decoded bytes per independently compressed block, or [`None`] for a single gzip member.
+    ///
+    /// A blocked (BGZF-style) gzip stream is a concatenation of gzip members, so it remains readable by any gzip decoder.
+    /// Its partial decoder only decompresses the blocks intersecting the requested byte ranges, rather than the entire value.
+    /// The block size is clamped to [`GZIP_MAX_BLOCK_SIZE`].
+    ///
+    /// The block size is an encoding option and is not stored in the codec metadata.
+    /// Blocked gzip streams are detected when decoding regardless of this setting.
+    #[must_use]
+    pub fn with_block_size(mut self, block_size: Option<usize>) -> Self {
+        self.block_size = block_size.map(|block_size| block_size.clamp(1, GZIP_MAX_BLOCK_SIZE));
+        self
+    }
+
+    /// Return the number of decoded bytes per independently compressed block, if blocked.
+    #[must_use]
+    pub const fn block_size(&self) -> Option<usize> {
+        self.block_size
     }
 
     /// Create a new `gzip` codec from configuration.
@@ -44,6 +71,7 @@ impl GzipCodec {
         let GzipCodecConfiguration::V1(configuration) = configuration;
         Self {
             compression_level: configuration.level,
+            block_size: None,
         }
     }
 }
@@ -61,7 +89,8 @@ impl CodecTraits for GzipCodec {
     }
 
     fn partial_decoder_decodes_all(&self) -> bool {
-        true
+        // The partial decoder caches its own index (or decoded value if not blocked)
+        false
     }
 }
 
@@ -74,30 +103,73 @@ impl BytesToBytesCodecTraits for GzipCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
+        if let Some(block_size) = self.block_size {
+            return Ok(Cow::Owned(gzip_blocks::encode_blocked(
+                &decoded_value,
+                block_size,
+                self.compression_level.as_u32(),
+            )?));
+        }
         let mut encoder = GzEncoder::new(
             Cursor::new(decoded_value),
             flate2::Compression::new(self.compression_level.as_u32()),
         );
         let mut out: Vec<u8> = Vec::new();
         encoder.read_to_end(&mut out)?;
-        Ok(out)
+        Ok(Cow::Owned(out))
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         _decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
-        let mut decoder = GzDecoder::new(Cursor::new(encoded_value));
-        let mut out: Vec<u8> = Vec::new();
-        decoder.read_to_end(&mut out)?;
-        Ok(out)
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(gzip_blocks::decode_all(&encoded_value)?))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn Read,
+        encoded_writer: &mut dyn Write,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        if let Some(block_size) = self.block_size {
+            return gzip_blocks::encode_blocked_stream(
+                decoded_reader,
+                encoded_writer,
+                block_size,
+                self.compression_level.as_u32(),
+            );
+        }
+        let mut encoder = flate2::write::GzEncoder::new(
+            encoded_writer,
+            flate2::Compression::new(self.compression_level.as_u32()),
+        );
+        std::io::copy(decoded_reader, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    fn decode_stream(
+        &self,
+        encoded_reader: &mut dyn Read,
+        decoded_writer: &mut dyn Write,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut decoder = flate2::read::MultiGzDecoder::new(encoded_reader);
+        std::io::copy(&mut decoder, decoded_writer)?;
+        Ok(())
     }
 
     fn partial_decoder<'a>(
@@ -1,24 +1,42 @@
-use std::io::{Cursor, Read};
-
-use flate2::bufread::GzDecoder;
+use std::sync::{Arc, Mutex};
 
 use crate::{
     array::codec::{BytesPartialDecoderTraits, CodecError, CodecOptions},
-    byte_range::{extract_byte_ranges, ByteRange},
+    byte_range::ByteRange,
 };
 
 #[cfg(feature = "async")]
 use crate::array::codec::AsyncBytesPartialDecoderTraits;
 
+use super::gzip_blocks::GzipIndex;
+
 /// Partial decoder for the `gzip` codec.
+///
+/// An index of the gzip members is built on first access.
+/// Subsequent partial decodes of a blocked gzip stream only decompress the blocks intersecting the decoded regions.
 pub struct GzipPartialDecoder<'a> {
     input_handle: Box<dyn BytesPartialDecoderTraits + 'a>,
+    index: Mutex<Option<Arc<GzipIndex>>>,
 }
 
 impl<'a> GzipPartialDecoder<'a> {
     /// Create a new partial decoder for the `gzip` codec.
     pub fn new(input_handle: Box<dyn BytesPartialDecoderTraits + 'a>) -> Self {
-        Self { input_handle }
+        Self {
+            input_handle,
+            index: Mutex::new(None),
+        }
+    }
+
+    fn index(&self, options: &CodecOptions) -> Result<Option<Arc<GzipIndex>>, CodecError> {
+        let mut index = self.index.lock().unwrap();
+        if index.is_none() {
+            let Some(encoded_value) = self.input_handle.decode(options)? else {
+                return Ok(None);
+            };
+            *index = Some(Arc::new(GzipIndex::new(encoded_value)?));
+        }
+        Ok(index.clone())
     }
 }
 
@@ -28,19 +46,9 @@ impl BytesPartialDecoderTraits for GzipPartialDecoder<'_> {
         decoded_regions: &[ByteRange],
         options: &CodecOptions,
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
-        let encoded_value = self.input_handle.decode(options)?;
-        let Some(encoded_value) = encoded_value else {
-            return Ok(None);
-        };
-
-        let mut decoder = GzDecoder::new(Cursor::new(&encoded_value));
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed)?;
-
-        Ok(Some(
-            extract_byte_ranges(&decompressed, decoded_regions)
-                .map_err(CodecError::InvalidByteRangeError)?,
-        ))
+        self.index(options)?
+            .map(|index| index.partial_decode(decoded_regions))
+            .transpose()
     }
 }
 
@@ -48,13 +56,29 @@ impl BytesPartialDecoderTraits for GzipPartialDecoder<'_> {
 /// Asynchronous partial decoder for the `gzip` codec.
 pub struct AsyncGzipPartialDecoder<'a> {
     input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>,
+    index: Mutex<Option<Arc<GzipIndex>>>,
 }
 
 #[cfg(feature = "async")]
 impl<'a> AsyncGzipPartialDecoder<'a> {
     /// Create a new partial decoder for the `gzip` codec.
     pub fn new(input_handle: Box<dyn AsyncBytesPartialDecoderTraits + 'a>) -> Self {
-        Self { input_handle }
+        Self {
+            input_handle,
+            index: Mutex::new(None),
+        }
+    }
+
+    async fn index(&self, options: &CodecOptions) -> Result<Option<Arc<GzipIndex>>, CodecError> {
+        if let Some(index) = self.index.lock().unwrap().as_ref() {
+            return Ok(Some(index.clone()));
+        }
+        let Some(encoded_value) = self.input_handle.decode(options).await? else {
+            return Ok(None);
+        };
+        let index = Arc::new(GzipIndex::new(encoded_value)?);
+        *self.index.lock().unwrap() = Some(index.clone());
+        Ok(Some(index))
     }
 }
 
@@ -66,18 +90,9 @@ impl AsyncBytesPartialDecoderTraits for AsyncGzipPartialDecoder<'_> {
         decoded_regions: &[ByteRange],
         options: &CodecOptions,
     ) -> Result<Option<Vec<Vec<u8>>>, CodecError> {
-        let encoded_value = self.input_handle.decode(options).await?;
-        let Some(encoded_value) = encoded_value else {
-            return Ok(None);
-        };
-
-        let mut decoder = GzDecoder::new(Cursor::new(&encoded_value));
-        let mut decompressed = Vec::new();
-        decoder.read_to_end(&mut decompressed)?;
-
-        Ok(Some(
-            extract_byte_ranges(&decompressed, decoded_regions)
-                .map_err(CodecError::InvalidByteRangeError)?,
-        ))
+        self.index(options)
+            .await?
+            .map(|index| index.partial_decode(decoded_regions))
+            .transpose()
     }
 }
@@ -28,12 +28,12 @@ mod tests {
         let codec: TestUnboundedCodec = TestUnboundedCodec::new();
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(encoded, &bytes_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
     }
 
     #[test]
@@ -44,7 +44,9 @@ mod tests {
 
         let codec: TestUnboundedCodec = TestUnboundedCodec::new();
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
@@ -85,7 +87,9 @@ mod tests {
 
         let codec: TestUnboundedCodec = TestUnboundedCodec::new();
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
@@ -4,7 +4,7 @@ use crate::{
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions,
             CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::Metadata,
 };
@@ -23,6 +23,8 @@ impl TestUnboundedCodec {
     ///
     /// # Errors
     /// Returns [`TestUnboundedCompressionLevelError`] if `compression_level` is not valid.
+    #[must_use]
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {}
     }
@@ -52,23 +54,48 @@ impl BytesToBytesCodecTraits for TestUnboundedCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
+        decoded_value: RawBytes<'a>,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         Ok(decoded_value)
     }
 
-    fn decode(
+    fn decode<'a>(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_value: RawBytes<'a>,
         _decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+    ) -> Result<RawBytes<'a>, CodecError> {
         Ok(encoded_value)
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn std::io::Read,
+        encoded_writer: &mut dyn std::io::Write,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        std::io::copy(decoded_reader, encoded_writer)?;
+        Ok(())
+    }
+
+    fn decode_stream(
+        &self,
+        encoded_reader: &mut dyn std::io::Read,
+        decoded_writer: &mut dyn std::io::Write,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        std::io::copy(encoded_reader, decoded_writer)?;
+        Ok(())
+    }
+
     fn partial_decoder<'a>(
         &self,
         r: Box<dyn BytesPartialDecoderTraits + 'a>,
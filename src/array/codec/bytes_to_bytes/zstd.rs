@@ -67,16 +67,45 @@ mod tests {
         let codec = ZstdCodec::new_with_configuration(&configuration);
 
         let encoded = codec
-            .encode(bytes.clone(), &CodecOptions::default())
+            .encode(bytes.clone().into(), &CodecOptions::default())
             .unwrap();
         let decoded = codec
             .decode(encoded, &bytes_representation, &CodecOptions::default())
             .unwrap();
-        assert_eq!(bytes, decoded);
+        assert_eq!(bytes, *decoded);
 
         // let encoded = codec.par_encode(bytes.clone()).unwrap();
         // let decoded = codec.par_decode(encoded, &bytes_representation).unwrap();
-        // assert_eq!(bytes, decoded);
+        // assert_eq!(bytes, *decoded);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn codec_zstd_round_trip_stream() {
+        let elements: Vec<u16> = (0..1000).collect();
+        let bytes = crate::array::transmute_to_bytes_vec(elements);
+        let bytes_representation = BytesRepresentation::FixedSize(bytes.len() as u64);
+
+        let codec = ZstdCodec::new(5, true);
+        assert!(codec.supports_streaming());
+        let mut encoded = Vec::new();
+        codec
+            .encode_stream(
+                &mut bytes.as_slice(),
+                &mut encoded,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        let mut decoded = Vec::new();
+        codec
+            .decode_stream(
+                &mut encoded.as_slice(),
+                &mut decoded,
+                &bytes_representation,
+                &CodecOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(bytes, *decoded);
     }
 
     #[test]
@@ -89,7 +118,9 @@ mod tests {
         let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
         let codec = ZstdCodec::new_with_configuration(&configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
@@ -130,7 +161,9 @@ mod tests {
         let configuration: ZstdCodecConfiguration = serde_json::from_str(JSON_VALID).unwrap();
         let codec = ZstdCodec::new_with_configuration(&configuration);
 
-        let encoded = codec.encode(bytes, &CodecOptions::default()).unwrap();
+        let encoded = codec
+            .encode(bytes.into(), &CodecOptions::default())
+            .unwrap();
         let decoded_regions = [
             ByteRange::FromStart(4, Some(4)),
             ByteRange::FromStart(10, Some(2)),
@@ -1,3 +1,7 @@
+use std::borrow::Cow;
+
+use std::io::{Read, Write};
+
 use zstd::zstd_safe;
 
 use crate::{
@@ -6,7 +10,7 @@ use crate::{
             BytesPartialDecoderTraits, BytesToBytesCodecTraits, CodecError, CodecOptions,
             CodecTraits, RecommendedConcurrency,
         },
-        ArrayMetadataOptions, BytesRepresentation,
+        ArrayMetadataOptions, BytesRepresentation, RawBytes,
     },
     metadata::Metadata,
 };
@@ -72,30 +76,67 @@ impl BytesToBytesCodecTraits for ZstdCodec {
         Ok(RecommendedConcurrency::new_maximum(1))
     }
 
-    fn encode(
+    fn encode<'a>(
         &self,
-        decoded_value: Vec<u8>,
-        _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
+        decoded_value: RawBytes<'a>,
+        options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
         let mut result = Vec::<u8>::new();
         let mut encoder = zstd::Encoder::new(&mut result, self.compression)?;
         encoder.include_checksum(self.checksum)?;
+        if options.deterministic_encode() {
+            // The output of multithreaded compression differs from single-threaded compression
+            encoder.multithread(0)?;
+        }
         // if parallel {
         //     let n_threads = std::thread::available_parallelism().unwrap().get();
         //     encoder.multithread(u32::try_from(n_threads).unwrap())?; // TODO: Check overhead of zstd par_encode
         // }
-        std::io::copy(&mut decoded_value.as_slice(), &mut encoder)?;
+        std::io::copy(&mut decoded_value.as_ref(), &mut encoder)?;
+        encoder.finish()?;
+        Ok(Cow::Owned(result))
+    }
+
+    fn decode<'a>(
+        &self,
+        encoded_value: RawBytes<'a>,
+        _decoded_representation: &BytesRepresentation,
+        _options: &CodecOptions,
+    ) -> Result<RawBytes<'a>, CodecError> {
+        Ok(Cow::Owned(
+            zstd::decode_all(encoded_value.as_ref()).map_err(CodecError::IOError)?,
+        ))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn encode_stream(
+        &self,
+        decoded_reader: &mut dyn Read,
+        encoded_writer: &mut dyn Write,
+        options: &CodecOptions,
+    ) -> Result<(), CodecError> {
+        let mut encoder = zstd::Encoder::new(encoded_writer, self.compression)?;
+        encoder.include_checksum(self.checksum)?;
+        if options.deterministic_encode() {
+            encoder.multithread(0)?;
+        }
+        std::io::copy(decoded_reader, &mut encoder)?;
         encoder.finish()?;
-        Ok(result)
+        Ok(())
     }
 
-    fn decode(
+    fn decode_stream(
         &self,
-        encoded_value: Vec<u8>,
+        encoded_reader: &mut dyn Read,
+        decoded_writer: &mut dyn Write,
         _decoded_representation: &BytesRepresentation,
         _options: &CodecOptions,
-    ) -> Result<Vec<u8>, CodecError> {
-        zstd::decode_all(encoded_value.as_slice()).map_err(CodecError::IOError)
+    ) -> Result<(), CodecError> {
+        zstd::stream::copy_decode(encoded_reader, decoded_writer)?;
+        Ok(())
     }
 
     fn partial_decoder<'a>(
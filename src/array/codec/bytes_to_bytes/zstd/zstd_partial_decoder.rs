@@ -29,8 +29,7 @@ impl BytesPartialDecoderTraits for ZstdPartialDecoder<'_> {
             return Ok(None);
         };
 
-        let decompressed =
-            zstd::decode_all(encoded_value.as_slice()).map_err(CodecError::IOError)?;
+        let decompressed = zstd::decode_all(encoded_value.as_ref()).map_err(CodecError::IOError)?;
 
         Ok(Some(
             extract_byte_ranges(&decompressed, decoded_regions)
@@ -66,8 +65,7 @@ impl AsyncBytesPartialDecoderTraits for AsyncZstdPartialDecoder<'_> {
             return Ok(None);
         };
 
-        let decompressed =
-            zstd::decode_all(encoded_value.as_slice()).map_err(CodecError::IOError)?;
+        let decompressed = zstd::decode_all(encoded_value.as_ref()).map_err(CodecError::IOError)?;
 
         Ok(Some(
             extract_byte_ranges(&decompressed, decoded_regions)
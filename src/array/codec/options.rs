@@ -1,15 +1,96 @@
 //! Codec options for encoding and decoding.
 
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use rayon::ThreadPool;
+
 use crate::config::global_config;
 
+#[cfg(feature = "async")]
+use super::{AsyncExecutor, CodecError};
+
+/// A token for cancelling a request that encodes or decodes multiple chunks.
+///
+/// Clones of a token share its state.
+/// A request with a token in its [`CodecOptions`] returns [`ArrayError::Cancelled`](crate::array::ArrayError::Cancelled) before decoding or encoding its next chunk once the token is cancelled, which allows an application to abort a large request from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new cancellation token.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel requests using this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The progress of a request that retrieves or stores multiple chunks.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct ChunkProgress {
+    /// The number of chunks completed.
+    pub chunks_completed: usize,
+    /// The total number of chunks.
+    pub chunks_total: usize,
+    /// The number of decoded bytes retrieved or stored.
+    pub bytes_transferred: u64,
+}
+
+/// A callback reporting the [`ChunkProgress`] of a request that retrieves or stores multiple chunks.
+///
+/// The callback is called after each chunk of a request with a [`CodecOptions`] holding the callback is completed.
+/// Calls are serialised and report a strictly increasing number of completed chunks, even if chunks are processed concurrently.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(ChunkProgress) + Send + Sync>);
+
+impl std::fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressCallback").finish_non_exhaustive()
+    }
+}
+
+impl ProgressCallback {
+    /// Create a new progress callback.
+    pub fn new(callback: impl Fn(ChunkProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Report `progress` to the callback.
+    pub fn call(&self, progress: ChunkProgress) {
+        (self.0)(progress);
+    }
+}
+
 /// Codec options for encoding/decoding.
 ///
 /// Default values for these options are set by the global [`Config`](crate::config::Config).
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CodecOptions {
     validate_checksums: bool,
     store_empty_chunks: bool,
     concurrent_target: usize,
+    chunk_prefetch: usize,
+    fill_value_on_decode_error: bool,
+    deterministic_encode: bool,
+    cancellation_token: Option<CancellationToken>,
+    progress_callback: Option<ProgressCallback>,
+    thread_pool: Option<Arc<ThreadPool>>,
+    #[cfg(feature = "async")]
+    async_executor: Option<AsyncExecutor>,
 }
 
 impl Default for CodecOptions {
@@ -18,6 +99,14 @@ impl Default for CodecOptions {
             validate_checksums: global_config().validate_checksums(),
             store_empty_chunks: global_config().store_empty_chunks(),
             concurrent_target: global_config().codec_concurrent_target(),
+            chunk_prefetch: global_config().chunk_prefetch(),
+            fill_value_on_decode_error: global_config().fill_value_on_decode_error(),
+            deterministic_encode: global_config().deterministic_encode(),
+            cancellation_token: None,
+            progress_callback: None,
+            thread_pool: global_config().thread_pool().cloned(),
+            #[cfg(feature = "async")]
+            async_executor: global_config().async_executor().cloned(),
         }
     }
 }
@@ -36,6 +125,14 @@ impl CodecOptions {
             validate_checksums: self.validate_checksums,
             store_empty_chunks: self.store_empty_chunks,
             concurrent_target: self.concurrent_target,
+            chunk_prefetch: self.chunk_prefetch,
+            fill_value_on_decode_error: self.fill_value_on_decode_error,
+            deterministic_encode: self.deterministic_encode,
+            cancellation_token: self.cancellation_token.clone(),
+            progress_callback: self.progress_callback.clone(),
+            thread_pool: self.thread_pool.clone(),
+            #[cfg(feature = "async")]
+            async_executor: self.async_executor.clone(),
         }
     }
 
@@ -71,16 +168,166 @@ impl CodecOptions {
     pub fn set_concurrent_target(&mut self, concurrent_target: usize) {
         self.concurrent_target = concurrent_target;
     }
+
+    /// Return the chunk prefetch.
+    #[must_use]
+    pub fn chunk_prefetch(&self) -> usize {
+        self.chunk_prefetch
+    }
+
+    /// Set the number of encoded chunks to fetch ahead of decoding.
+    ///
+    /// See the [chunk prefetch](crate::config::Config#chunk-prefetch) configuration.
+    pub fn set_chunk_prefetch(&mut self, chunk_prefetch: usize) {
+        self.chunk_prefetch = chunk_prefetch;
+    }
+
+    /// Return the fill value on decode error setting.
+    #[must_use]
+    pub fn fill_value_on_decode_error(&self) -> bool {
+        self.fill_value_on_decode_error
+    }
+
+    /// Set whether or not chunks that fail to decode are retrieved as the fill value.
+    ///
+    /// See the [fill value on decode error](crate::config::Config#fill-value-on-decode-error) configuration.
+    pub fn set_fill_value_on_decode_error(&mut self, fill_value_on_decode_error: bool) {
+        self.fill_value_on_decode_error = fill_value_on_decode_error;
+    }
+
+    /// Return the deterministic encode setting.
+    #[must_use]
+    pub fn deterministic_encode(&self) -> bool {
+        self.deterministic_encode
+    }
+
+    /// Set whether or not encoding must produce byte-identical output across runs and machines.
+    ///
+    /// This does not apply to metadata, which is written with [`MetadataWriteOptions::set_deterministic_encode`](crate::storage::MetadataWriteOptions::set_deterministic_encode).
+    /// See the [deterministic encode](crate::config::Config#deterministic-encode) configuration.
+    pub fn set_deterministic_encode(&mut self, deterministic_encode: bool) {
+        self.deterministic_encode = deterministic_encode;
+    }
+
+    /// Return the cancellation token.
+    #[must_use]
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Set the cancellation token.
+    ///
+    /// See [`CancellationToken`].
+    pub fn set_cancellation_token(&mut self, cancellation_token: Option<CancellationToken>) {
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Returns true if the cancellation token has been cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Return the progress callback.
+    #[must_use]
+    pub fn progress_callback(&self) -> Option<&ProgressCallback> {
+        self.progress_callback.as_ref()
+    }
+
+    /// Set the progress callback.
+    ///
+    /// See [`ProgressCallback`].
+    pub fn set_progress_callback(&mut self, progress_callback: Option<ProgressCallback>) {
+        self.progress_callback = progress_callback;
+    }
+
+    /// Return the thread pool.
+    #[must_use]
+    pub fn thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
+    /// Set the thread pool that parallel operations run in, or [`None`] to use the global [`rayon`] thread pool.
+    ///
+    /// See the [thread pool](crate::config::Config#thread-pool) configuration.
+    pub fn set_thread_pool(&mut self, thread_pool: Option<Arc<ThreadPool>>) {
+        self.thread_pool = thread_pool;
+    }
+
+    /// Run `op` in the [thread pool](CodecOptions::thread_pool), or on the current thread if there is no thread pool.
+    ///
+    /// Parallel iterators and other [`rayon`] operations within `op` then run in the thread pool rather than the global [`rayon`] thread pool.
+    /// Codecs and array operations which use [`rayon`] internally call this with their options.
+    pub fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Return the async executor.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn async_executor(&self) -> Option<&AsyncExecutor> {
+        self.async_executor.as_ref()
+    }
+
+    /// Set the async executor that async array operations encode and decode chunks with, or [`None`] to encode and decode on the current thread.
+    ///
+    /// See the [async executor](crate::config::Config#async-executor) configuration.
+    #[cfg(feature = "async")]
+    pub fn set_async_executor(&mut self, async_executor: Option<AsyncExecutor>) {
+        self.async_executor = async_executor;
+    }
+
+    /// Run the blocking `f` with the [async executor](CodecOptions::async_executor) and await its output, or run it on the current thread if there is no async executor.
+    ///
+    /// # Errors
+    /// Returns [`CodecError::Other`] if the async executor drops the task before it completes.
+    #[cfg(feature = "async")]
+    pub async fn spawn_blocking<F, R>(&self, f: F) -> Result<R, CodecError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        if let Some(async_executor) = &self.async_executor {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            async_executor.spawn_blocking(Box::new(move || {
+                // The receiver is only dropped if the awaiting operation was dropped
+                let _ = sender.send(f());
+            }));
+            receiver.await.map_err(|_| {
+                CodecError::Other("the async executor dropped a blocking task".to_string())
+            })
+        } else {
+            Ok(f())
+        }
+    }
 }
 
 /// Builder for [`CodecOptions`].
 ///
 /// Default values for these options are set by the global [`Config`](crate::config::Config).
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct CodecOptionsBuilder {
     validate_checksums: bool,
     store_empty_chunks: bool,
     concurrent_target: usize,
+    chunk_prefetch: usize,
+    fill_value_on_decode_error: bool,
+    deterministic_encode: bool,
+    cancellation_token: Option<CancellationToken>,
+    progress_callback: Option<ProgressCallback>,
+    thread_pool: Option<Arc<ThreadPool>>,
+    #[cfg(feature = "async")]
+    async_executor: Option<AsyncExecutor>,
 }
 
 impl Default for CodecOptionsBuilder {
@@ -97,6 +344,14 @@ impl CodecOptionsBuilder {
             validate_checksums: global_config().validate_checksums(),
             store_empty_chunks: global_config().store_empty_chunks(),
             concurrent_target: global_config().codec_concurrent_target(),
+            chunk_prefetch: global_config().chunk_prefetch(),
+            fill_value_on_decode_error: global_config().fill_value_on_decode_error(),
+            deterministic_encode: global_config().deterministic_encode(),
+            cancellation_token: None,
+            progress_callback: None,
+            thread_pool: global_config().thread_pool().cloned(),
+            #[cfg(feature = "async")]
+            async_executor: global_config().async_executor().cloned(),
         }
     }
 
@@ -107,6 +362,14 @@ impl CodecOptionsBuilder {
             validate_checksums: self.validate_checksums,
             store_empty_chunks: self.store_empty_chunks,
             concurrent_target: self.concurrent_target,
+            chunk_prefetch: self.chunk_prefetch,
+            fill_value_on_decode_error: self.fill_value_on_decode_error,
+            deterministic_encode: self.deterministic_encode,
+            cancellation_token: self.cancellation_token.clone(),
+            progress_callback: self.progress_callback.clone(),
+            thread_pool: self.thread_pool.clone(),
+            #[cfg(feature = "async")]
+            async_executor: self.async_executor.clone(),
         }
     }
 
@@ -130,4 +393,83 @@ impl CodecOptionsBuilder {
         self.concurrent_target = concurrent_target;
         self
     }
+
+    /// Set the number of encoded chunks to fetch ahead of decoding.
+    #[must_use]
+    pub fn chunk_prefetch(mut self, chunk_prefetch: usize) -> Self {
+        self.chunk_prefetch = chunk_prefetch;
+        self
+    }
+
+    /// Set whether or not chunks that fail to decode are retrieved as the fill value.
+    #[must_use]
+    pub fn fill_value_on_decode_error(mut self, fill_value_on_decode_error: bool) -> Self {
+        self.fill_value_on_decode_error = fill_value_on_decode_error;
+        self
+    }
+
+    /// Set whether or not encoding must produce byte-identical output across runs and machines.
+    #[must_use]
+    pub fn deterministic_encode(mut self, deterministic_encode: bool) -> Self {
+        self.deterministic_encode = deterministic_encode;
+        self
+    }
+
+    /// Set the cancellation token.
+    #[must_use]
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Set the progress callback.
+    #[must_use]
+    pub fn progress_callback(mut self, progress_callback: ProgressCallback) -> Self {
+        self.progress_callback = Some(progress_callback);
+        self
+    }
+
+    /// Set the thread pool that parallel operations run in.
+    #[must_use]
+    pub fn thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Set the async executor that async array operations encode and decode chunks with.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn async_executor(mut self, async_executor: AsyncExecutor) -> Self {
+        self.async_executor = Some(async_executor);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codec_options_install() {
+        let options = CodecOptions::default();
+        assert!(options.thread_pool().is_none());
+        assert_eq!(options.install(|| 1 + 1), 2);
+
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap(),
+        );
+        let options = CodecOptions::builder()
+            .thread_pool(thread_pool.clone())
+            .build();
+        assert!(options
+            .into_builder()
+            .build()
+            .thread_pool()
+            .is_some_and(|pool| Arc::ptr_eq(pool, &thread_pool)));
+        assert_eq!(options.install(rayon::current_thread_index), Some(0));
+        assert_eq!(options.install(rayon::current_num_threads), 1);
+    }
 }
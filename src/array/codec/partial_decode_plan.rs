@@ -0,0 +1,64 @@
+use crate::byte_range::ByteRange;
+
+/// The byte ranges of an encoded value required to decode regions of it, planned without reading the encoded value.
+///
+/// See [`ArrayToBytesCodecTraits::partial_decode_plan`](super::ArrayToBytesCodecTraits::partial_decode_plan).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialDecodePlan {
+    /// The whole encoded value is required.
+    Whole,
+    /// Only these byte ranges of the encoded value are required.
+    ///
+    /// The byte ranges may be empty if the regions are entirely the fill value (e.g. missing inner chunks of a shard).
+    ByteRanges(Vec<ByteRange>),
+    /// The required byte ranges are recorded in an index stored in the encoded value at this byte range (e.g. a shard index).
+    ///
+    /// The encoded index must be read and passed to a subsequent plan to resolve the required byte ranges.
+    Index(ByteRange),
+}
+
+impl PartialDecodePlan {
+    /// Return the byte ranges of the plan for an encoded value stored at `offset` with `size` bytes within another value.
+    ///
+    /// An [`Index`](PartialDecodePlan::Index) plan cannot be resolved, so the whole encoded value is required.
+    pub(crate) fn byte_ranges_within(&self, offset: u64, size: u64) -> Vec<ByteRange> {
+        match self {
+            Self::Whole | Self::Index(_) => vec![ByteRange::FromStart(offset, Some(size))],
+            Self::ByteRanges(byte_ranges) => byte_ranges
+                .iter()
+                .map(|byte_range| {
+                    ByteRange::FromStart(
+                        offset + byte_range.start(size),
+                        Some(byte_range.length(size)),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_decode_plan_byte_ranges_within() {
+        assert_eq!(
+            PartialDecodePlan::Whole.byte_ranges_within(10, 5),
+            vec![ByteRange::FromStart(10, Some(5))]
+        );
+        assert_eq!(
+            PartialDecodePlan::ByteRanges(vec![
+                ByteRange::FromStart(1, Some(2)),
+                ByteRange::FromStart(3, None),
+                ByteRange::FromEnd(0, Some(1)),
+            ])
+            .byte_ranges_within(10, 5),
+            vec![
+                ByteRange::FromStart(11, Some(2)),
+                ByteRange::FromStart(13, Some(2)),
+                ByteRange::FromStart(14, Some(1)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! Read-only concatenation of arrays along an axis.
+
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use thiserror::Error;
+
+use crate::{array_subset::ArraySubset, storage::ReadableStorageTraits};
+
+use super::{
+    transmute_from_bytes_vec, validate_element_size, Array, ArrayError, ArrayShape, DataType,
+    VirtualArray,
+};
+
+#[cfg(feature = "ndarray")]
+use super::elements_to_ndarray;
+
+/// A read-only view of several arrays concatenated along an axis.
+///
+/// The arrays must have the same data type and dimensionality, and the same shape in every dimension other than the concatenation axis.
+/// Retrieving an array subset retrieves the overlapping region of each underlying array in parallel.
+/// This is useful when data arrive as separate arrays (e.g. one per day), but consumers expect a single array (e.g. with one time axis).
+///
+/// A [`ConcatenatedArray`] can be converted into a [`VirtualArray`] to derive further arrays from it.
+#[derive(Clone, Debug)]
+pub struct ConcatenatedArray {
+    arrays: Vec<VirtualArray>,
+    axis: usize,
+    /// The start of each array along the axis, followed by the length of the axis.
+    offsets: Vec<u64>,
+    shape: ArrayShape,
+}
+
+impl ConcatenatedArray {
+    /// Concatenate `arrays` along `axis`.
+    ///
+    /// # Errors
+    /// Returns a [`ConcatenatedArrayError`] if `arrays` is empty, `axis` is out of bounds, or the arrays have incompatible data types or shapes.
+    pub fn new(arrays: Vec<VirtualArray>, axis: usize) -> Result<Self, ConcatenatedArrayError> {
+        let first = arrays.first().ok_or(ConcatenatedArrayError::NoArrays)?;
+        if axis >= first.dimensionality() {
+            return Err(ConcatenatedArrayError::InvalidAxis(
+                axis,
+                first.dimensionality(),
+            ));
+        }
+        let mut offsets = Vec::with_capacity(arrays.len() + 1);
+        offsets.push(0);
+        for (i, array) in arrays.iter().enumerate() {
+            if array.data_type() != first.data_type() {
+                return Err(ConcatenatedArrayError::IncompatibleDataType(
+                    i,
+                    array.data_type().clone(),
+                    first.data_type().clone(),
+                ));
+            }
+            let compatible = array.dimensionality() == first.dimensionality()
+                && std::iter::zip(array.shape(), first.shape())
+                    .enumerate()
+                    .all(|(dim, (a, b))| dim == axis || a == b);
+            if !compatible {
+                return Err(ConcatenatedArrayError::IncompatibleShape(
+                    i,
+                    array.shape().to_vec(),
+                    first.shape().to_vec(),
+                ));
+            }
+            offsets.push(offsets[i] + array.shape()[axis]);
+        }
+        let mut shape = first.shape().to_vec();
+        shape[axis] = offsets[arrays.len()];
+        Ok(Self {
+            arrays,
+            axis,
+            offsets,
+            shape,
+        })
+    }
+
+    /// Concatenate stored `arrays` along `axis`.
+    ///
+    /// # Errors
+    /// Returns a [`ConcatenatedArrayError`] if a [`new`](ConcatenatedArray::new) error condition is met.
+    pub fn from_arrays<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+        arrays: Vec<Arc<Array<TStorage>>>,
+        axis: usize,
+    ) -> Result<Self, ConcatenatedArrayError> {
+        Self::new(
+            arrays.into_iter().map(VirtualArray::from_array).collect(),
+            axis,
+        )
+    }
+
+    /// Return the concatenated arrays.
+    #[must_use]
+    pub fn arrays(&self) -> &[VirtualArray] {
+        &self.arrays
+    }
+
+    /// Return the concatenation axis.
+    #[must_use]
+    pub const fn axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Return the shape of the concatenated array.
+    #[must_use]
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Return the dimensionality of the concatenated array.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Return the data type of the concatenated array.
+    #[must_use]
+    pub fn data_type(&self) -> &DataType {
+        self.arrays[0].data_type()
+    }
+
+    /// Read and decode the `array_subset` of the concatenated array into its bytes.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the concatenated array or there is an underlying array error.
+    pub fn retrieve_array_subset(&self, array_subset: &ArraySubset) -> Result<Vec<u8>, ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(&self.shape)
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape.clone(),
+            ));
+        }
+        let element_size = self.data_type().size();
+        let (start, end) = (
+            array_subset.start()[self.axis],
+            array_subset.end_exc()[self.axis],
+        );
+
+        // The overlap of each array with the subset, relative to the array and to the subset
+        let parts: Vec<_> = self
+            .arrays
+            .iter()
+            .zip(self.offsets.windows(2))
+            .filter(|(_, offsets)| offsets[0] < end && offsets[1] > start)
+            .map(|(array, offsets)| {
+                let mut array_start = array_subset.start().to_vec();
+                let mut array_shape = array_subset.shape().to_vec();
+                let part_start = start.max(offsets[0]);
+                array_start[self.axis] = part_start - offsets[0];
+                array_shape[self.axis] = end.min(offsets[1]) - part_start;
+                let mut output_start = vec![0; self.dimensionality()];
+                output_start[self.axis] = part_start - start;
+                let output_subset = unsafe {
+                    ArraySubset::new_with_start_shape_unchecked(output_start, array_shape.clone())
+                };
+                let array_subset = unsafe {
+                    ArraySubset::new_with_start_shape_unchecked(array_start, array_shape)
+                };
+                (array, array_subset, output_subset)
+            })
+            .collect();
+        if let [(array, array_subset, _)] = parts.as_slice() {
+            return array.retrieve_array_subset(array_subset);
+        }
+
+        let part_bytes = parts
+            .into_par_iter()
+            .map(|(array, array_subset, output_subset)| {
+                Ok((output_subset, array.retrieve_array_subset(&array_subset)?))
+            })
+            .collect::<Result<Vec<_>, ArrayError>>()?;
+        let mut output = vec![0; array_subset.num_elements_usize() * element_size];
+        for (output_subset, bytes) in part_bytes {
+            unsafe {
+                output_subset.store_bytes_unchecked(
+                    &bytes,
+                    &mut output,
+                    array_subset.shape(),
+                    element_size,
+                );
+            }
+        }
+        Ok(output)
+    }
+
+    /// Read and decode the `array_subset` of the concatenated array into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the size of `T` does not match the data type size or a [`retrieve_array_subset`](ConcatenatedArray::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_elements<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(self.data_type())?;
+        Ok(transmute_from_bytes_vec(
+            self.retrieve_array_subset(array_subset)?,
+        ))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Read and decode the `array_subset` of the concatenated array into an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a [`retrieve_array_subset_elements`](ConcatenatedArray::retrieve_array_subset_elements) error condition is met.
+    pub fn retrieve_array_subset_ndarray<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements = self.retrieve_array_subset_elements(array_subset)?;
+        elements_to_ndarray(array_subset.shape(), elements)
+    }
+}
+
+impl From<ConcatenatedArray> for VirtualArray {
+    /// Convert a concatenated array into a virtual array with the chunk shape of its first array.
+    fn from(array: ConcatenatedArray) -> Self {
+        let shape = array.shape.clone();
+        let data_type = array.data_type().clone();
+        let chunk_shape = array.arrays[0].chunk_shape().clone();
+        Self::new(shape, data_type, chunk_shape, move |array_subset| {
+            array.retrieve_array_subset(array_subset)
+        })
+        .expect("the chunk shape has the dimensionality of the array")
+    }
+}
+
+/// A [`ConcatenatedArray`] creation error.
+#[derive(Debug, Error)]
+pub enum ConcatenatedArrayError {
+    /// No arrays.
+    #[error("at least one array is required")]
+    NoArrays,
+    /// The axis is out of bounds.
+    #[error("axis {0} is out of bounds for arrays with dimensionality {1}")]
+    InvalidAxis(usize, usize),
+    /// An array has a different data type to the first array.
+    #[error("array {0} has data type {1}, expected {2}")]
+    IncompatibleDataType(usize, DataType, DataType),
+    /// An array has a shape incompatible with the first array.
+    #[error("array {0} has shape {1:?} which is incompatible with shape {2:?}")]
+    IncompatibleShape(usize, ArrayShape, ArrayShape),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn array(
+        store: &Arc<MemoryStore>,
+        path: &str,
+        shape: ArrayShape,
+        first: u8,
+    ) -> Arc<Array<MemoryStore>> {
+        let array = ArrayBuilder::new(
+            shape.clone(),
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), path)
+        .unwrap();
+        let num_elements = u8::try_from(shape.iter().product::<u64>()).unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(shape),
+                (first..first + num_elements).collect(),
+            )
+            .unwrap();
+        Arc::new(array)
+    }
+
+    #[test]
+    fn concatenated_array() {
+        let store = Arc::new(MemoryStore::new());
+        let arrays = vec![
+            array(&store, "/a", vec![1, 3], 0),
+            array(&store, "/b", vec![2, 3], 10),
+            array(&store, "/c", vec![3, 3], 20),
+        ];
+        let concatenated = ConcatenatedArray::from_arrays(arrays.clone(), 0).unwrap();
+        assert_eq!(concatenated.shape(), &[6, 3]);
+        assert_eq!(concatenated.axis(), 0);
+        assert_eq!(
+            concatenated
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[0..6, 1..3]))
+                .unwrap(),
+            vec![1, 2, 11, 12, 14, 15, 21, 22, 24, 25, 27, 28]
+        );
+        assert_eq!(
+            concatenated
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_ranges(&[1..3, 0..1]))
+                .unwrap(),
+            vec![10, 13]
+        );
+        assert!(concatenated
+            .retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..7, 0..3]))
+            .is_err());
+
+        let virtual_array = VirtualArray::from(
+            ConcatenatedArray::from_arrays(vec![arrays[1].clone(), arrays[0].clone()], 0).unwrap(),
+        );
+        assert_eq!(
+            virtual_array
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_shape(vec![3, 3]))
+                .unwrap(),
+            vec![10, 11, 12, 13, 14, 15, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn concatenated_array_axis_1() {
+        let store = Arc::new(MemoryStore::new());
+        let concatenated = ConcatenatedArray::from_arrays(
+            vec![
+                array(&store, "/a", vec![2, 1], 0),
+                array(&store, "/b", vec![2, 2], 10),
+            ],
+            1,
+        )
+        .unwrap();
+        assert_eq!(concatenated.shape(), &[2, 3]);
+        assert_eq!(
+            concatenated
+                .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_shape(vec![2, 3]))
+                .unwrap(),
+            vec![0, 10, 11, 1, 12, 13]
+        );
+    }
+
+    #[test]
+    fn concatenated_array_errors() {
+        let store = Arc::new(MemoryStore::new());
+        let a = array(&store, "/a", vec![2, 2], 0);
+        let b = array(&store, "/b", vec![2, 3], 0);
+        assert!(matches!(
+            ConcatenatedArray::from_arrays(Vec::<Arc<Array<MemoryStore>>>::new(), 0),
+            Err(ConcatenatedArrayError::NoArrays)
+        ));
+        assert!(matches!(
+            ConcatenatedArray::from_arrays(vec![a.clone(), b.clone()], 2),
+            Err(ConcatenatedArrayError::InvalidAxis(2, 2))
+        ));
+        assert!(matches!(
+            ConcatenatedArray::from_arrays(vec![a.clone(), b.clone()], 0),
+            Err(ConcatenatedArrayError::IncompatibleShape(1, _, _))
+        ));
+        assert!(ConcatenatedArray::from_arrays(vec![a.clone(), b], 1).is_ok());
+        let c = VirtualArray::from_array(a.clone())
+            .cast(DataType::UInt16)
+            .unwrap();
+        assert!(matches!(
+            ConcatenatedArray::new(vec![VirtualArray::from_array(a), c], 0),
+            Err(ConcatenatedArrayError::IncompatibleDataType(1, _, _))
+        ));
+    }
+}
@@ -1,6 +1,12 @@
 //! Zarr data types.
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#data-types>.
+//!
+//! Additional data types can be supported by implementing [`DataTypeExtension`] and registering a [`DataTypePlugin`].
+
+mod datetime;
+
+pub use datetime::{DateTimeDataTypeConfiguration, DateTimeUnit, NAT};
 
 use derive_more::From;
 use half::{bf16, f16};
@@ -10,6 +16,7 @@ use thiserror::Error;
 use crate::{
     array::{ZARR_NAN_BF16, ZARR_NAN_F16, ZARR_NAN_F32, ZARR_NAN_F64},
     metadata::Metadata,
+    plugin::Plugin,
 };
 
 use super::{
@@ -54,10 +61,17 @@ pub enum DataType {
     /// `complex128` real and complex components are each IEEE 754 double-precision floating point.
     Complex128,
     /// `r*` raw bits, variable size given by *, limited to be a multiple of 8.
+    ///
+    /// Elements are opaque fixed-size byte arrays (e.g. `[u8; N]` in the element APIs) and are never byte swapped by codecs.
     RawBits(usize), // the stored usize is the size in bytes
-
-                    // /// An extension data type.
-                    // Extension(Box<dyn DataTypeExtension>),
+    /// `numpy.datetime64` a 64-bit signed integer count of [units](DateTimeUnit) since the Unix epoch. The value [`NAT`] is "not a time".
+    DateTime64(DateTimeUnit),
+    /// `numpy.timedelta64` a 64-bit signed integer count of [units](DateTimeUnit). The value [`NAT`] is "not a time".
+    TimeDelta64(DateTimeUnit),
+    /// An extension data type registered with a [`DataTypePlugin`].
+    ///
+    /// Elements are never byte swapped by codecs.
+    Extension(Box<dyn DataTypeExtension>),
 }
 
 /// An unsupported data type error.
@@ -67,7 +81,7 @@ pub struct UnsupportedDataTypeError(String);
 
 impl PartialEq for DataType {
     fn eq(&self, other: &Self) -> bool {
-        self.name() == other.name()
+        self.metadata() == other.metadata()
     }
 }
 
@@ -80,15 +94,23 @@ impl<'de> serde::Deserialize<'de> for DataType {
     }
 }
 
-// /// A data type plugin.
-// pub type DataTypePlugin = Plugin<Box<dyn DataTypeExtension>>;
-// inventory::collect!(DataTypePlugin);
+/// A data type plugin.
+pub type DataTypePlugin = Plugin<Box<dyn DataTypeExtension>>;
+inventory::collect!(DataTypePlugin);
 
 /// A fill value metadata incompatibility error.
 #[derive(Debug, Error)]
 #[error("incompatible fill value {1} for data type {0}")]
 pub struct IncompatibleFillValueMetadataError(String, FillValueMetadata);
 
+impl IncompatibleFillValueMetadataError {
+    /// Create a new incompatible fill value metadata error.
+    #[must_use]
+    pub const fn new(data_type_name: String, fill_value_metadata: FillValueMetadata) -> Self {
+        Self(data_type_name, fill_value_metadata)
+    }
+}
+
 /// A fill value incompatibility error.
 #[derive(Debug, Error)]
 #[error("incompatible fill value {1} for data type {0}")]
@@ -103,6 +125,8 @@ impl IncompatibleFillValueError {
 }
 
 /// Extension data type traits.
+///
+/// Implement this trait and register a [`DataTypePlugin`] with [`inventory::submit!`] to add a data type that [`DataType::from_metadata`] resolves to [`DataType::Extension`].
 pub trait DataTypeExtension: dyn_clone::DynClone + core::fmt::Debug + Send + Sync {
     /// Returns the identifier.
     fn identifier(&self) -> &'static str;
@@ -136,7 +160,7 @@ dyn_clone::clone_trait_object!(DataTypeExtension);
 impl DataType {
     /// Returns the identifier.
     #[must_use]
-    pub const fn identifier(&self) -> &'static str {
+    pub fn identifier(&self) -> &'static str {
         match self {
             Self::Bool => "bool",
             Self::Int8 => "int8",
@@ -154,7 +178,9 @@ impl DataType {
             Self::Complex64 => "complex64",
             Self::Complex128 => "complex128",
             Self::RawBits(_usize) => "r*",
-            // Self::Extension(extension) => extension.identifier(),
+            Self::DateTime64(_) => "numpy.datetime64",
+            Self::TimeDelta64(_) => "numpy.timedelta64",
+            Self::Extension(extension) => extension.identifier(),
         }
     }
 
@@ -163,32 +189,51 @@ impl DataType {
     pub fn name(&self) -> String {
         match self {
             Self::RawBits(size) => format!("r{}", size * 8),
-            // Self::Extension(extension) => extension.name(),
+            Self::Extension(extension) => extension.name(),
             _ => self.identifier().to_string(),
         }
     }
 
     /// Returns the metadata.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data type configuration cannot be serialised.
+    /// This would indicate an implementation error with a data type.
     #[must_use]
     pub fn metadata(&self) -> Metadata {
-        Metadata::new(&self.name())
-        // match self {
-        //     // Self::Extension(extension) => extension.metadata(),
-        //     _ => Metadata::new(&self.name()),
-        // }
+        match self {
+            Self::DateTime64(unit) | Self::TimeDelta64(unit) => {
+                Metadata::new_with_serializable_configuration(
+                    &self.name(),
+                    &DateTimeDataTypeConfiguration {
+                        unit: *unit,
+                        scale_factor: 1,
+                    },
+                )
+                .unwrap()
+            }
+            Self::Extension(extension) => extension.metadata(),
+            _ => Metadata::new(&self.name()),
+        }
     }
 
     /// Returns the size in bytes.
     #[must_use]
-    pub const fn size(&self) -> usize {
+    pub fn size(&self) -> usize {
         match self {
             Self::Bool | Self::Int8 | Self::UInt8 => 1,
             Self::Int16 | Self::UInt16 | Self::Float16 | Self::BFloat16 => 2,
             Self::Int32 | Self::UInt32 | Self::Float32 => 4,
-            Self::Int64 | Self::UInt64 | Self::Float64 | Self::Complex64 => 8,
+            Self::Int64
+            | Self::UInt64
+            | Self::Float64
+            | Self::Complex64
+            | Self::DateTime64(_)
+            | Self::TimeDelta64(_) => 8,
             Self::Complex128 => 16,
             Self::RawBits(size) => *size,
-            // Self::Extension(extension) => extension.size(),
+            Self::Extension(extension) => extension.size(),
         }
     }
 
@@ -216,29 +261,47 @@ impl DataType {
             "bfloat16" => return Ok(Self::BFloat16),
             "complex64" => return Ok(Self::Complex64),
             "complex128" => return Ok(Self::Complex128),
+            "numpy.datetime64" | "numpy.timedelta64" => {
+                let err = || UnsupportedDataTypeError(metadata.to_string());
+                let configuration: DateTimeDataTypeConfiguration =
+                    metadata.to_configuration().map_err(|_| err())?;
+                if configuration.scale_factor != 1 {
+                    return Err(err());
+                }
+                return Ok(if name == "numpy.datetime64" {
+                    Self::DateTime64(configuration.unit)
+                } else {
+                    Self::TimeDelta64(configuration.unit)
+                });
+            }
             _ => {}
-        };
+        }
 
-        if name.starts_with('r') {
-            if let Ok(size_bits) = metadata.name()[1..].parse::<usize>() {
-                if size_bits % 8 == 0 {
-                    let size_bytes = size_bits / 8;
-                    return Ok(Self::RawBits(size_bytes));
+        if let Some(size_bits) = name.strip_prefix('r') {
+            // The size must be a canonical positive integer (no sign or leading zeros) so that the name round trips
+            let is_canonical = !size_bits.is_empty()
+                && !size_bits.starts_with('0')
+                && size_bits.bytes().all(|c| c.is_ascii_digit());
+            if is_canonical {
+                if let Ok(size_bits) = size_bits.parse::<usize>() {
+                    if size_bits % 8 == 0 {
+                        let size_bytes = size_bits / 8;
+                        return Ok(Self::RawBits(size_bytes));
+                    }
                 }
             }
         }
 
-        Err(UnsupportedDataTypeError(name.to_string()))
+        for plugin in inventory::iter::<DataTypePlugin> {
+            if plugin.match_name(name) {
+                return plugin
+                    .create(metadata)
+                    .map(Self::Extension)
+                    .map_err(|err| UnsupportedDataTypeError(err.to_string()));
+            }
+        }
 
-        // for plugin in inventory::iter::<DataTypePlugin> {
-        //     if plugin.match_name(metadata.name()) {
-        //         return Ok(DataType::Extension(plugin.create(metadata)?));
-        //     }
-        // }
-        // Err(PluginCreateError::Unsupported {
-        //     name: metadata.name().to_string(),
-        //     plugin_type: "data type".to_string(),
-        // })
+        Err(UnsupportedDataTypeError(name.to_string()))
     }
 
     /// Create a fill value from metadata.
@@ -257,7 +320,9 @@ impl DataType {
             Self::Int8 => Ok(FV::from(fill_value.try_as_int::<i8>().ok_or_else(err)?)),
             Self::Int16 => Ok(FV::from(fill_value.try_as_int::<i16>().ok_or_else(err)?)),
             Self::Int32 => Ok(FV::from(fill_value.try_as_int::<i32>().ok_or_else(err)?)),
-            Self::Int64 => Ok(FV::from(fill_value.try_as_int::<i64>().ok_or_else(err)?)),
+            Self::Int64 | Self::DateTime64(_) | Self::TimeDelta64(_) => {
+                Ok(FV::from(fill_value.try_as_int::<i64>().ok_or_else(err)?))
+            }
             Self::UInt8 => Ok(FV::from(fill_value.try_as_uint::<u8>().ok_or_else(err)?)),
             Self::UInt16 => Ok(FV::from(fill_value.try_as_uint::<u16>().ok_or_else(err)?)),
             Self::UInt32 => Ok(FV::from(fill_value.try_as_uint::<u32>().ok_or_else(err)?)),
@@ -284,7 +349,8 @@ impl DataType {
                     self.name(),
                     fill_value.clone(),
                 ))
-            } // Self::Extension(extension) => extension.fill_value_from_metadata(fill_value),
+            }
+            Self::Extension(extension) => extension.fill_value_from_metadata(fill_value),
         }
     }
 
@@ -308,7 +374,9 @@ impl DataType {
             Self::Int32 => {
                 FillValueMetadata::Int(i64::from(i32::from_ne_bytes(bytes.try_into().unwrap())))
             }
-            Self::Int64 => FillValueMetadata::Int(i64::from_ne_bytes(bytes.try_into().unwrap())),
+            Self::Int64 | Self::DateTime64(_) | Self::TimeDelta64(_) => {
+                FillValueMetadata::Int(i64::from_ne_bytes(bytes.try_into().unwrap()))
+            }
             Self::UInt8 => {
                 FillValueMetadata::UInt(u64::from(u8::from_ne_bytes(bytes.try_into().unwrap())))
             }
@@ -346,7 +414,8 @@ impl DataType {
             Self::RawBits(size) => {
                 debug_assert_eq!(fill_value.as_ne_bytes().len(), *size);
                 FillValueMetadata::ByteArray(fill_value.as_ne_bytes().to_vec())
-            } // DataType::Extension(extension) => extension.metadata_fill_value(fill_value),
+            }
+            Self::Extension(extension) => extension.metadata_fill_value(fill_value),
         }
     }
 }
@@ -422,9 +491,109 @@ impl core::fmt::Display for DataType {
 }
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings, unknown_lints, unnecessary_transmutes)]
 mod tests {
     use super::*;
 
+    /// A little endian 24-bit unsigned integer extension data type.
+    #[derive(Clone, Debug)]
+    struct UInt24DataType;
+
+    impl DataTypeExtension for UInt24DataType {
+        fn identifier(&self) -> &'static str {
+            "zarrs.test.uint24"
+        }
+
+        fn name(&self) -> String {
+            self.identifier().to_string()
+        }
+
+        fn size(&self) -> usize {
+            3
+        }
+
+        fn metadata(&self) -> Metadata {
+            Metadata::new(self.identifier())
+        }
+
+        fn fill_value_from_metadata(
+            &self,
+            fill_value: &FillValueMetadata,
+        ) -> Result<FillValue, IncompatibleFillValueMetadataError> {
+            match fill_value.try_as_uint::<u32>() {
+                Some(value) if value < (1 << 24) => {
+                    Ok(FillValue::new(value.to_le_bytes()[..3].to_vec()))
+                }
+                _ => Err(IncompatibleFillValueMetadataError::new(
+                    self.name(),
+                    fill_value.clone(),
+                )),
+            }
+        }
+
+        fn metadata_fill_value(&self, fill_value: &FillValue) -> FillValueMetadata {
+            let bytes = fill_value.as_ne_bytes();
+            FillValueMetadata::UInt(u64::from(u32::from_le_bytes([
+                bytes[0], bytes[1], bytes[2], 0,
+            ])))
+        }
+    }
+
+    fn is_name_uint24(name: &str) -> bool {
+        name == "zarrs.test.uint24"
+    }
+
+    fn create_data_type_uint24(
+        metadata: &Metadata,
+    ) -> Result<Box<dyn DataTypeExtension>, crate::plugin::PluginCreateError> {
+        if metadata.configuration_is_none_or_empty() {
+            Ok(Box::new(UInt24DataType))
+        } else {
+            Err(crate::plugin::PluginMetadataInvalidError::new(
+                "zarrs.test.uint24",
+                "data type",
+                metadata.clone(),
+            )
+            .into())
+        }
+    }
+
+    inventory::submit! {
+        DataTypePlugin::new("zarrs.test.uint24", is_name_uint24, create_data_type_uint24)
+    }
+
+    #[test]
+    fn data_type_extension() {
+        let json = r#""zarrs.test.uint24""#;
+        let metadata: Metadata = serde_json::from_str(json).unwrap();
+        let data_type = DataType::from_metadata(&metadata).unwrap();
+        assert_eq!(json, serde_json::to_string(&data_type.metadata()).unwrap());
+        assert_eq!(data_type, DataType::Extension(Box::new(UInt24DataType)));
+        assert_ne!(data_type, DataType::RawBits(3));
+        assert_eq!(data_type.identifier(), "zarrs.test.uint24");
+        assert_eq!(data_type.name(), "zarrs.test.uint24");
+        assert_eq!(data_type.size(), 3);
+        assert!(DataType::from_metadata(
+            &serde_json::from_str(r#"{"name":"zarrs.test.uint24","configuration":{"a":1}}"#)
+                .unwrap()
+        )
+        .is_err());
+
+        let metadata = serde_json::from_str::<FillValueMetadata>("65538").unwrap();
+        let fill_value = data_type.fill_value_from_metadata(&metadata).unwrap();
+        assert_eq!(fill_value.as_ne_bytes(), &[2, 0, 1]);
+        assert_eq!(metadata, data_type.metadata_fill_value(&fill_value));
+
+        let metadata = serde_json::from_str::<FillValueMetadata>("16777216").unwrap();
+        assert_eq!(
+            data_type
+                .fill_value_from_metadata(&metadata)
+                .unwrap_err()
+                .to_string(),
+            "incompatible fill value 16777216 for data type zarrs.test.uint24"
+        );
+    }
+
     #[test]
     fn data_type_unknown() {
         let json = r#""unknown""#;
@@ -441,7 +610,7 @@ mod tests {
         let json = r#""bool""#;
         let metadata: Metadata = serde_json::from_str(json).unwrap();
         let data_type = DataType::from_metadata(&metadata).unwrap();
-        assert_eq!(format!("{}", data_type), "bool");
+        assert_eq!(format!("{data_type}"), "bool");
         assert_eq!(json, serde_json::to_string(&data_type.metadata()).unwrap());
         assert_eq!(data_type, DataType::Bool);
 
@@ -450,10 +619,10 @@ mod tests {
         assert_eq!(fill_value.as_ne_bytes(), u8::from(true).to_ne_bytes());
         assert_eq!(metadata, data_type.metadata_fill_value(&fill_value));
 
-        let fillvalue = data_type
+        let fill_value = data_type
             .fill_value_from_metadata(&serde_json::from_str::<FillValueMetadata>("false").unwrap())
             .unwrap();
-        assert_eq!(fillvalue.as_ne_bytes(), u8::from(false).to_ne_bytes());
+        assert_eq!(fill_value.as_ne_bytes(), u8::from(false).to_ne_bytes());
     }
 
     #[test]
@@ -973,6 +1142,77 @@ mod tests {
         assert!(DataType::from_metadata(&metadata).is_err());
     }
 
+    #[test]
+    fn data_type_raw_bits_failure2() {
+        for name in ["r", "r0", "r08", "r+8", "r-8", "r 8"] {
+            let metadata = Metadata::new(name);
+            assert!(DataType::from_metadata(&metadata).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn data_type_r24() {
+        let data_type = DataType::from_metadata(&Metadata::new("r24")).unwrap();
+        assert_eq!(data_type, DataType::RawBits(3));
+        assert_eq!(data_type.size(), 3);
+
+        let fill_value = FillValue::from([1u8, 2, 3]);
+        let metadata = data_type.metadata_fill_value(&fill_value);
+        assert_eq!(serde_json::to_string(&metadata).unwrap(), "[1,2,3]");
+        assert_eq!(
+            data_type.fill_value_from_metadata(&metadata).unwrap(),
+            fill_value
+        );
+    }
+
+    #[test]
+    fn data_type_datetime64() {
+        let json = r#"{"name":"numpy.datetime64","configuration":{"unit":"ns","scale_factor":1}}"#;
+        let metadata: Metadata = serde_json::from_str(json).unwrap();
+        let data_type = DataType::from_metadata(&metadata).unwrap();
+        assert_eq!(json, serde_json::to_string(&data_type.metadata()).unwrap());
+        assert_eq!(data_type, DataType::DateTime64(DateTimeUnit::Nanosecond));
+        assert_eq!(data_type.name(), "numpy.datetime64");
+        assert_eq!(data_type.size(), 8);
+
+        let metadata = serde_json::from_str::<FillValueMetadata>(&NAT.to_string()).unwrap();
+        assert_eq!(
+            data_type.fill_value_from_metadata(&metadata).unwrap(),
+            FillValue::from(NAT)
+        );
+        assert_eq!(
+            metadata,
+            data_type.metadata_fill_value(&FillValue::from(NAT))
+        );
+    }
+
+    #[test]
+    fn data_type_timedelta64() {
+        let json = r#"{"name":"numpy.timedelta64","configuration":{"unit":"D"}}"#;
+        let metadata: Metadata = serde_json::from_str(json).unwrap();
+        let data_type = DataType::from_metadata(&metadata).unwrap();
+        assert_eq!(data_type, DataType::TimeDelta64(DateTimeUnit::Day));
+        assert_eq!(
+            r#"{"name":"numpy.timedelta64","configuration":{"unit":"D","scale_factor":1}}"#,
+            serde_json::to_string(&data_type.metadata()).unwrap()
+        );
+        assert_ne!(data_type, DataType::DateTime64(DateTimeUnit::Day));
+        assert_ne!(data_type, DataType::TimeDelta64(DateTimeUnit::Hour));
+    }
+
+    #[test]
+    fn data_type_datetime64_failure() {
+        for json in [
+            r#"{"name":"numpy.datetime64","configuration":{"unit":"ns","scale_factor":2}}"#,
+            r#"{"name":"numpy.datetime64","configuration":{"unit":"Y","scale_factor":1}}"#,
+            r#"{"name":"numpy.timedelta64","configuration":{"scale_factor":1}}"#,
+            r#""numpy.timedelta64""#,
+        ] {
+            let metadata: Metadata = serde_json::from_str(json).unwrap();
+            assert!(DataType::from_metadata(&metadata).is_err(), "{json}");
+        }
+    }
+
     #[test]
     fn incompatible_fill_value_metadata() {
         let json = r#""bool""#;
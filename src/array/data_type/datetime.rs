@@ -0,0 +1,195 @@
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+/// The integer value representing "not a time" (`NaT`) in `numpy.datetime64` and `numpy.timedelta64` data types.
+pub const NAT: i64 = i64::MIN;
+
+/// The unit of a `numpy.datetime64` or `numpy.timedelta64` data type.
+///
+/// Only units with a fixed duration are supported.
+#[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, Debug, Display)]
+pub enum DateTimeUnit {
+    /// Weeks.
+    #[serde(rename = "W")]
+    #[display(fmt = "W")]
+    Week,
+    /// Days.
+    #[serde(rename = "D")]
+    #[display(fmt = "D")]
+    Day,
+    /// Hours.
+    #[serde(rename = "h")]
+    #[display(fmt = "h")]
+    Hour,
+    /// Minutes.
+    #[serde(rename = "m")]
+    #[display(fmt = "m")]
+    Minute,
+    /// Seconds.
+    #[serde(rename = "s")]
+    #[display(fmt = "s")]
+    Second,
+    /// Milliseconds.
+    #[serde(rename = "ms")]
+    #[display(fmt = "ms")]
+    Millisecond,
+    /// Microseconds.
+    #[serde(rename = "us")]
+    #[display(fmt = "us")]
+    Microsecond,
+    /// Nanoseconds.
+    #[serde(rename = "ns")]
+    #[display(fmt = "ns")]
+    Nanosecond,
+}
+
+/// Configuration parameters for the `numpy.datetime64` and `numpy.timedelta64` data types.
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DateTimeDataTypeConfiguration {
+    /// The unit.
+    pub unit: DateTimeUnit,
+    /// The number of units per increment. Only `1` is supported.
+    #[serde(default = "scale_factor_default")]
+    pub scale_factor: u32,
+}
+
+const fn scale_factor_default() -> u32 {
+    1
+}
+
+#[cfg(feature = "chrono")]
+impl DateTimeUnit {
+    /// The number of seconds in this unit, or [`None`] if the unit is shorter than a second.
+    const fn seconds(self) -> Option<i64> {
+        match self {
+            Self::Week => Some(7 * 86400),
+            Self::Day => Some(86400),
+            Self::Hour => Some(3600),
+            Self::Minute => Some(60),
+            Self::Second => Some(1),
+            Self::Millisecond | Self::Microsecond | Self::Nanosecond => None,
+        }
+    }
+
+    /// The number of this unit in a second, or [`None`] if the unit is longer than a second.
+    const fn per_second(self) -> Option<i64> {
+        match self {
+            Self::Second => Some(1),
+            Self::Millisecond => Some(1_000),
+            Self::Microsecond => Some(1_000_000),
+            Self::Nanosecond => Some(1_000_000_000),
+            Self::Week | Self::Day | Self::Hour | Self::Minute => None,
+        }
+    }
+
+    /// Convert a `numpy.timedelta64` value in this unit to a [`chrono::TimeDelta`].
+    ///
+    /// Returns [`None`] if `value` is [`NAT`] or out of range.
+    #[must_use]
+    pub fn to_timedelta(self, value: i64) -> Option<chrono::TimeDelta> {
+        if value == NAT {
+            return None;
+        }
+        match self {
+            Self::Week => chrono::TimeDelta::try_weeks(value),
+            Self::Day => chrono::TimeDelta::try_days(value),
+            Self::Hour => chrono::TimeDelta::try_hours(value),
+            Self::Minute => chrono::TimeDelta::try_minutes(value),
+            Self::Second => chrono::TimeDelta::try_seconds(value),
+            Self::Millisecond => chrono::TimeDelta::try_milliseconds(value),
+            Self::Microsecond => Some(chrono::TimeDelta::microseconds(value)),
+            Self::Nanosecond => Some(chrono::TimeDelta::nanoseconds(value)),
+        }
+    }
+
+    /// Convert a [`chrono::TimeDelta`] to a `numpy.timedelta64` value in this unit.
+    ///
+    /// The conversion truncates towards zero.
+    /// Returns [`None`] if the result is out of range.
+    #[must_use]
+    pub fn from_timedelta(self, timedelta: &chrono::TimeDelta) -> Option<i64> {
+        let value = match self {
+            Self::Week => Some(timedelta.num_weeks()),
+            Self::Day => Some(timedelta.num_days()),
+            Self::Hour => Some(timedelta.num_hours()),
+            Self::Minute => Some(timedelta.num_minutes()),
+            Self::Second => Some(timedelta.num_seconds()),
+            Self::Millisecond => Some(timedelta.num_milliseconds()),
+            Self::Microsecond => timedelta.num_microseconds(),
+            Self::Nanosecond => timedelta.num_nanoseconds(),
+        }?;
+        (value != NAT).then_some(value)
+    }
+
+    /// Convert a `numpy.datetime64` value in this unit to a [`chrono::DateTime`].
+    ///
+    /// The value is relative to the Unix epoch.
+    /// Returns [`None`] if `value` is [`NAT`] or out of range.
+    #[must_use]
+    pub fn to_datetime(self, value: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::UNIX_EPOCH.checked_add_signed(self.to_timedelta(value)?)
+    }
+
+    /// Convert a [`chrono::DateTime`] to a `numpy.datetime64` value in this unit.
+    ///
+    /// The conversion rounds towards negative infinity (e.g. a time during a day maps to the start of that day).
+    /// Returns [`None`] if the result is out of range.
+    #[must_use]
+    pub fn from_datetime(self, datetime: &chrono::DateTime<chrono::Utc>) -> Option<i64> {
+        let seconds = datetime.timestamp();
+        let subsec_nanos = i64::from(datetime.timestamp_subsec_nanos());
+        let value = if let Some(unit_seconds) = self.seconds() {
+            Some(seconds.div_euclid(unit_seconds))
+        } else {
+            let per_second = self.per_second()?;
+            seconds
+                .checked_mul(per_second)?
+                .checked_add(subsec_nanos / (1_000_000_000 / per_second))
+        }?;
+        (value != NAT).then_some(value)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "chrono")]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+
+    use super::*;
+
+    #[test]
+    fn datetime_unit_datetime() {
+        let datetime = Utc.with_ymd_and_hms(2024, 5, 17, 12, 30, 15).unwrap();
+        let value = DateTimeUnit::Second.from_datetime(&datetime).unwrap();
+        assert_eq!(value, datetime.timestamp());
+        assert_eq!(DateTimeUnit::Second.to_datetime(value), Some(datetime));
+        assert_eq!(
+            DateTimeUnit::Nanosecond
+                .to_datetime(DateTimeUnit::Nanosecond.from_datetime(&datetime).unwrap()),
+            Some(datetime)
+        );
+
+        let day = DateTimeUnit::Day.from_datetime(&datetime).unwrap();
+        assert_eq!(
+            DateTimeUnit::Day.to_datetime(day),
+            Some(Utc.with_ymd_and_hms(2024, 5, 17, 0, 0, 0).unwrap())
+        );
+
+        let before_epoch = Utc.with_ymd_and_hms(1969, 12, 31, 23, 0, 0).unwrap();
+        assert_eq!(DateTimeUnit::Day.from_datetime(&before_epoch), Some(-1));
+        assert_eq!(DateTimeUnit::Second.to_datetime(NAT), None);
+    }
+
+    #[test]
+    fn datetime_unit_timedelta() {
+        let timedelta = TimeDelta::try_hours(-25).unwrap();
+        assert_eq!(DateTimeUnit::Day.from_timedelta(&timedelta), Some(-1));
+        assert_eq!(DateTimeUnit::Hour.from_timedelta(&timedelta), Some(-25));
+        assert_eq!(
+            DateTimeUnit::Microsecond.to_timedelta(-90_000_000_000),
+            Some(timedelta)
+        );
+        assert_eq!(DateTimeUnit::Week.to_timedelta(i64::MAX), None);
+    }
+}
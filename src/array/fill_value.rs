@@ -2,6 +2,9 @@
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#fill-value>.
 
+/// The size of the largest block compared at once by [`FillValue::equals_all`].
+const EQUALS_ALL_MAX_BLOCK_SIZE: usize = 64 * 1024;
+
 /// The fill value of the Zarr array.
 ///
 /// Provides an element value to use for uninitialised portions of the Zarr array.
@@ -26,6 +29,12 @@ impl From<Vec<u8>> for FillValue {
     }
 }
 
+impl<const N: usize> From<[u8; N]> for FillValue {
+    fn from(value: [u8; N]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
 impl From<bool> for FillValue {
     fn from(value: bool) -> Self {
         Self(vec![u8::from(value)])
@@ -142,61 +151,38 @@ impl FillValue {
     }
 
     /// Check if the bytes are equal to a sequence of the fill value.
-    #[allow(clippy::missing_panics_doc)]
+    ///
+    /// Returns early on the first mismatch.
+    /// Trailing bytes which do not form a complete element are ignored.
     #[must_use]
     pub fn equals_all(&self, bytes: &[u8]) -> bool {
-        match self.0.len() {
-            1 => {
-                let fill_value = self.0[0];
-                let fill_value_128 = u128::from_ne_bytes([self.0[0]; 16]);
-                let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u128>() };
-                prefix.iter().all(|x| x == &fill_value)
-                    && suffix.iter().all(|x| x == &fill_value)
-                    && aligned.iter().all(|x| x == &fill_value_128)
-            }
-            2 => {
-                let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u128>() };
-                if prefix.is_empty() && suffix.is_empty() {
-                    let fill_value_128 =
-                        u128::from_ne_bytes(self.0[..2].repeat(8).try_into().unwrap());
-                    aligned.iter().all(|x| x == &fill_value_128)
-                } else {
-                    bytes.chunks_exact(2).all(|x| x == self.0)
-                }
-            }
-            4 => {
-                let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u128>() };
-                if prefix.is_empty() && suffix.is_empty() {
-                    let fill_value_128 =
-                        u128::from_ne_bytes(self.0[..4].repeat(4).try_into().unwrap());
-                    aligned.iter().all(|x| x == &fill_value_128)
-                } else {
-                    bytes.chunks_exact(4).all(|x| x == self.0)
-                }
-            }
-            8 => {
-                let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u128>() };
-                if prefix.is_empty() && suffix.is_empty() {
-                    let fill_value_128 =
-                        u128::from_ne_bytes(self.0[..8].repeat(2).try_into().unwrap());
-                    aligned.iter().all(|x| x == &fill_value_128)
-                } else {
-                    bytes.chunks_exact(8).all(|x| x == self.0)
-                }
+        let element_size = self.0.len();
+        if element_size == 0 {
+            return true;
+        }
+        let len = bytes.len() - bytes.len() % element_size;
+        if len == 0 {
+            return true;
+        }
+        if bytes[..element_size] != self.0 {
+            return false;
+        }
+
+        // The bytes are compared in blocks against the verified prefix of the bytes, a sequence of the fill value.
+        // Slice comparisons are vectorised (memcmp), and the block size doubles up to a cache-friendly limit.
+        let mut block_size = element_size;
+        let mut offset = element_size;
+        while offset < len {
+            let size = block_size.min(len - offset);
+            if bytes[offset..offset + size] != bytes[..size] {
+                return false;
             }
-            16 => {
-                let (prefix, aligned, suffix) = unsafe { bytes.align_to::<u128>() };
-                if prefix.is_empty() && suffix.is_empty() {
-                    let fill_value_128 = u128::from_ne_bytes(self.0[..16].try_into().unwrap());
-                    aligned.iter().all(|x| x == &fill_value_128)
-                } else {
-                    bytes.chunks_exact(16).all(|x| x == self.0)
-                }
+            offset += size;
+            if block_size < EQUALS_ALL_MAX_BLOCK_SIZE {
+                block_size = offset;
             }
-            _ => bytes
-                .chunks_exact(self.0.len())
-                .all(|element| element == self.0),
         }
+        true
     }
 }
 
@@ -254,7 +240,31 @@ mod tests {
 
     #[test]
     fn fill_value_equals_u8() {
-        assert!(FillValue::from(vec![1u8; 32]).equals_all(&vec![1u8; 32 * 5]));
+        assert!(FillValue::from(vec![1u8; 32]).equals_all(&[1u8; 32 * 5]));
+    }
+
+    #[test]
+    fn fill_value_equals_all_mismatch() {
+        for element_size in [1u8, 2, 3, 4, 8, 16, 24] {
+            let fill_value = FillValue::new((1..=element_size).collect());
+            let element_size = usize::from(element_size);
+            let num_elements = 100_000 / element_size;
+            let bytes = fill_value.as_ne_bytes().repeat(num_elements + 1);
+            // Unaligned and with trailing bytes which do not form a complete element
+            let bytes = &bytes[element_size..bytes.len() - 1];
+            assert!(fill_value.equals_all(bytes));
+            assert!(fill_value.equals_all(&[]));
+            for offset in [
+                0,
+                1,
+                element_size * num_elements / 2,
+                element_size * (num_elements - 1) - 1,
+            ] {
+                let mut bytes = bytes.to_vec();
+                bytes[offset] = 0;
+                assert!(!fill_value.equals_all(&bytes));
+            }
+        }
     }
 
     #[test]
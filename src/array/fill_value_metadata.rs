@@ -55,20 +55,21 @@ pub enum FillValueFloat {
 }
 
 impl FillValueFloat {
+    /// Convert the float fill value to a float.
+    ///
+    /// A hex string must have the same size as `T`, and its bytes are reinterpreted exactly (including any NaN payload).
+    /// `"NaN"` is converted to the Zarr NaN representation of `T` (see [`ZARR_NAN_F32`] and [`ZARR_NAN_F64`]).
     fn to_float<T: FloatCore>(&self) -> Option<T> {
         match self {
             Self::Float(float) => T::from(*float),
             Self::HexString(hex_string) => {
                 let bytes: &[u8] = hex_string.as_be_bytes();
-                if bytes.len() == core::mem::size_of::<T>() {
-                    // NOTE: Cleaner way of doing this?
-                    if core::mem::size_of::<T>() == core::mem::size_of::<f32>() {
-                        T::from(f32::from_be_bytes(bytes.try_into().unwrap_or_default()))
-                    } else if core::mem::size_of::<T>() == core::mem::size_of::<f64>() {
-                        T::from(f64::from_be_bytes(bytes.try_into().unwrap_or_default()))
-                    } else {
-                        None
-                    }
+                if bytes.len() != core::mem::size_of::<T>() {
+                    None
+                } else if let Ok(bytes) = <[u8; 4]>::try_from(bytes) {
+                    T::from(f32::from_be_bytes(bytes))
+                } else if let Ok(bytes) = <[u8; 8]>::try_from(bytes) {
+                    T::from(f64::from_be_bytes(bytes))
                 } else {
                     None
                 }
@@ -78,7 +79,15 @@ impl FillValueFloat {
                 match nonfinite {
                     NF::PosInfinity => Some(T::infinity()),
                     NF::NegInfinity => Some(T::neg_infinity()),
-                    NF::NaN => Some(T::nan()),
+                    NF::NaN => {
+                        if core::mem::size_of::<T>() == core::mem::size_of::<f32>() {
+                            T::from(ZARR_NAN_F32)
+                        } else if core::mem::size_of::<T>() == core::mem::size_of::<f64>() {
+                            T::from(ZARR_NAN_F64)
+                        } else {
+                            Some(T::nan())
+                        }
+                    }
                 }
             }
         }
@@ -104,7 +113,7 @@ impl HexString {
 
 impl core::fmt::Display for HexString {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{:?}", self.0)
+        write!(f, "{}", bytes_to_hex_string(&self.0))
     }
 }
 
@@ -120,10 +129,11 @@ fn bytes_to_hex_string(v: &[u8]) -> String {
 }
 
 fn hex_string_to_be_bytes(s: &str) -> Option<Vec<u8>> {
-    if s.starts_with("0x") && s.len() % 2 == 0 {
-        (2..s.len())
+    let hex = s.strip_prefix("0x")?;
+    if hex.len() % 2 == 0 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        (0..hex.len())
             .step_by(2)
-            .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
             .collect::<Result<Vec<_>, _>>()
             .ok()
     } else {
@@ -201,43 +211,7 @@ impl FillValueMetadata {
         match self {
             Self::Int(int) => num::traits::cast(*int),
             Self::UInt(uint) => num::traits::cast(*uint),
-            Self::Float(float) => {
-                use FillValueFloat as F;
-                match float {
-                    F::Float(float) => T::from(*float),
-                    F::HexString(hex_string) => {
-                        let bytes = hex_string.as_be_bytes();
-                        if bytes.len() == core::mem::size_of::<T>() {
-                            // NOTE: Cleaner way of doing this?
-                            if core::mem::size_of::<T>() == core::mem::size_of::<f32>() {
-                                T::from(f32::from_be_bytes(bytes.try_into().unwrap_or_default()))
-                            } else if core::mem::size_of::<T>() == core::mem::size_of::<f64>() {
-                                T::from(f64::from_be_bytes(bytes.try_into().unwrap_or_default()))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    F::NonFinite(nonfinite) => {
-                        use FillValueFloatStringNonFinite as NF;
-                        match nonfinite {
-                            NF::PosInfinity => Some(T::infinity()),
-                            NF::NegInfinity => Some(T::neg_infinity()),
-                            NF::NaN => {
-                                if core::mem::size_of::<T>() == core::mem::size_of::<f32>() {
-                                    T::from(ZARR_NAN_F32)
-                                } else if core::mem::size_of::<T>() == core::mem::size_of::<f64>() {
-                                    T::from(ZARR_NAN_F64)
-                                } else {
-                                    None
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            Self::Float(float) => float.to_float::<T>(),
             _ => None,
         }
     }
@@ -335,6 +309,7 @@ impl FillValueMetadata {
 }
 
 #[cfg(test)]
+#[allow(clippy::float_cmp)]
 mod tests {
     use crate::array::{DataType, FillValue};
 
@@ -342,7 +317,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_bool_false() {
-        let json = r#"false"#;
+        let json = "false";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -355,7 +330,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_bool_true() {
-        let json = r#"true"#;
+        let json = "true";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -368,7 +343,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_uint() {
-        let json = r#"7"#;
+        let json = "7";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -381,7 +356,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_int() {
-        let json = r#"-7"#;
+        let json = "-7";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -394,7 +369,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_float_number() {
-        let json = r#"7.5"#;
+        let json = "7.5";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -472,7 +447,7 @@ mod tests {
         assert_eq!(fill_value, FillValueFloatStringNonFinite::NaN);
 
         assert!(FillValueFloat::HexString(HexString(
-            hex_string_to_be_bytes(&"0x7fc00000").unwrap()
+            hex_string_to_be_bytes("0x7fc00000").unwrap()
         ))
         .to_float::<f32>()
         .unwrap()
@@ -521,13 +496,13 @@ mod tests {
                         assert_eq!(fill_value.0, f32::NAN.to_be_bytes());
                     }
                     _ => unreachable!(),
-                };
+                }
                 match im {
                     FillValueFloat::NonFinite(fill_value) => {
                         assert_eq!(fill_value, FillValueFloatStringNonFinite::NaN);
                     }
                     _ => unreachable!(),
-                };
+                }
             }
             _ => unreachable!(),
         }
@@ -535,7 +510,7 @@ mod tests {
 
     #[test]
     fn fill_value_metadata_raw_bytes() {
-        let json = r#"[0,1,2,3]"#;
+        let json = "[0,1,2,3]";
         let metadata: FillValueMetadata = json.try_into().unwrap();
         assert_eq!(json, serde_json::to_string(&metadata).unwrap());
         match metadata {
@@ -545,4 +520,166 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    fn fill_value_round_trip(data_type: &DataType, fill_value: &FillValue) -> String {
+        let json = serde_json::to_string(&data_type.metadata_fill_value(fill_value)).unwrap();
+        let metadata: FillValueMetadata = json.as_str().try_into().unwrap();
+        assert_eq!(
+            &data_type.fill_value_from_metadata(&metadata).unwrap(),
+            fill_value,
+            "{data_type} {json}"
+        );
+        json
+    }
+
+    #[test]
+    fn fill_value_metadata_float_round_trip() {
+        for bits in [
+            0x0000, 0x8000, 0x3c00, 0x0001, 0x7bff, 0x7c00, 0xfc00, 0x7e00, 0x7e01, 0x7d00, 0xfe00,
+        ] {
+            let float16 = FillValue::from(f16::from_bits(bits));
+            fill_value_round_trip(&DataType::Float16, &float16);
+            let bfloat16 = FillValue::from(bf16::from_bits(bits));
+            fill_value_round_trip(&DataType::BFloat16, &bfloat16);
+        }
+        for bits in [
+            0x0000_0000,
+            0x8000_0000,
+            0x3fc0_0000,
+            0x0000_0001,
+            0x7f7f_ffff,
+            0x7f80_0000,
+            0xff80_0000,
+            0x7fc0_0000,
+            0x7fc0_0001,
+            0x7fa0_0000,
+            0xffc0_0000,
+            0xffff_ffff,
+        ] {
+            fill_value_round_trip(&DataType::Float32, &FillValue::from(f32::from_bits(bits)));
+            let complex = num::complex::Complex32::new(f32::from_bits(bits), 1.0);
+            fill_value_round_trip(&DataType::Complex64, &FillValue::from(complex));
+        }
+        for bits in [
+            0x0000_0000_0000_0000,
+            0x8000_0000_0000_0000,
+            0x3ff8_0000_0000_0000,
+            0x0000_0000_0000_0001,
+            0x7fef_ffff_ffff_ffff,
+            0x7ff0_0000_0000_0000,
+            0xfff0_0000_0000_0000,
+            0x7ff8_0000_0000_0000,
+            0x7ff8_0000_0000_0001,
+            0x7ff4_0000_0000_0000,
+            0xfff8_0000_0000_0000,
+        ] {
+            fill_value_round_trip(&DataType::Float64, &FillValue::from(f64::from_bits(bits)));
+            let complex = num::complex::Complex64::new(-1.0, f64::from_bits(bits));
+            fill_value_round_trip(&DataType::Complex128, &FillValue::from(complex));
+        }
+    }
+
+    #[test]
+    fn fill_value_metadata_float_special_encodings() {
+        for (data_type, json, bytes) in [
+            (
+                DataType::Float16,
+                r#""NaN""#,
+                ZARR_NAN_F16.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float16,
+                r#""-Infinity""#,
+                f16::NEG_INFINITY.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float16,
+                r#""0x7e01""#,
+                0x7e01u16.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::BFloat16,
+                r#""NaN""#,
+                ZARR_NAN_BF16.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::BFloat16,
+                r#""Infinity""#,
+                bf16::INFINITY.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::BFloat16,
+                r#""0xffc1""#,
+                0xffc1u16.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float32,
+                r#""NaN""#,
+                ZARR_NAN_F32.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float32,
+                r#""Infinity""#,
+                f32::INFINITY.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float32,
+                r#""0xffc00000""#,
+                0xffc0_0000u32.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float64,
+                r#""NaN""#,
+                ZARR_NAN_F64.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float64,
+                r#""-Infinity""#,
+                f64::NEG_INFINITY.to_ne_bytes().to_vec(),
+            ),
+            (
+                DataType::Float64,
+                r#""0x7ff0000000000001""#,
+                0x7ff0_0000_0000_0001u64.to_ne_bytes().to_vec(),
+            ),
+        ] {
+            let metadata: FillValueMetadata = json.try_into().unwrap();
+            let fill_value = data_type.fill_value_from_metadata(&metadata).unwrap();
+            assert_eq!(fill_value.as_ne_bytes(), bytes, "{data_type} {json}");
+            assert_eq!(fill_value_round_trip(&data_type, &fill_value), json);
+        }
+
+        // Hex strings are case insensitive and must match the size of the data type
+        let metadata: FillValueMetadata = r#""0x7FC00001""#.try_into().unwrap();
+        assert_eq!(
+            DataType::Float32
+                .fill_value_from_metadata(&metadata)
+                .unwrap()
+                .as_ne_bytes(),
+            0x7fc0_0001u32.to_ne_bytes()
+        );
+        assert_eq!(metadata.to_string(), "0x7fc00001");
+        assert!(DataType::Float64
+            .fill_value_from_metadata(&metadata)
+            .is_err());
+        assert!(DataType::Float16
+            .fill_value_from_metadata(&metadata)
+            .is_err());
+
+        // Invalid hex strings
+        for json in [
+            r#""0x7fc0000g""#,
+            r#""0x7fc0000""#,
+            r#""0x+fc00000""#,
+            r#""0x€a""#,
+        ] {
+            let metadata: Result<FillValueMetadata, _> = json.try_into();
+            assert!(
+                metadata.map_or(true, |metadata| DataType::Float32
+                    .fill_value_from_metadata(&metadata)
+                    .is_err()),
+                "{json}"
+            );
+        }
+    }
 }
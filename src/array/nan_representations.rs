@@ -5,14 +5,14 @@ use half::{bf16, f16};
 // https://github.com/rust-lang/rust/issues/72447
 
 /// The Zarr "NaN" fill value for a 64-bit IEEE 754 floating point number.
-#[allow(clippy::unusual_byte_groupings)]
+#[allow(clippy::unusual_byte_groupings, unknown_lints, unnecessary_transmutes)]
 pub const ZARR_NAN_F64: f64 = unsafe {
     transmute::<u64, f64>(0b0_11111111111_1000000000000000000000000000000000000000000000000000)
 };
 // const ZARR_NAN_F64: f64 = f64::from_bits(0b0_11111111111_1000000000000000000000000000000000000000000000000000);
 
 /// The Zarr "NaN" fill value for a 32-bit IEEE 754 floating point number.
-#[allow(clippy::unusual_byte_groupings)]
+#[allow(clippy::unusual_byte_groupings, unknown_lints, unnecessary_transmutes)]
 pub const ZARR_NAN_F32: f32 =
     unsafe { transmute::<u32, f32>(0b0_11111111_10000000000000000000000) };
 // const ZARR_NAN_F32: f32 = f32::from_bits(0b0_11111111_10000000000000000000000);
@@ -24,6 +24,7 @@ pub const ZARR_NAN_F16: f16 = f16::NAN;
 pub const ZARR_NAN_BF16: bf16 = bf16::NAN;
 
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -7,8 +7,8 @@ use crate::vec_spare_capacity_to_mut_slice;
 #[derive(Copy, Clone)]
 pub struct UnsafeCellSlice<'a, T>(&'a [std::cell::UnsafeCell<T>]);
 
-unsafe impl<'a, T: Send + Sync> Send for UnsafeCellSlice<'a, T> {}
-unsafe impl<'a, T: Send + Sync> Sync for UnsafeCellSlice<'a, T> {}
+unsafe impl<T: Send + Sync> Send for UnsafeCellSlice<'_, T> {}
+unsafe impl<T: Send + Sync> Sync for UnsafeCellSlice<'_, T> {}
 
 impl<'a, T: Copy> UnsafeCellSlice<'a, T> {
     /// Create a new [`UnsafeCellSlice`].
@@ -0,0 +1,564 @@
+//! Virtual arrays with elements computed on demand.
+
+use std::{num::NonZeroU64, sync::Arc};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    array_subset::{ArraySubset, IncompatibleDimensionalityError},
+    storage::ReadableStorageTraits,
+};
+
+use super::{
+    transmute_from_bytes_vec, transmute_to_bytes_vec, validate_element_size, Array, ArrayError,
+    ArrayShape, ChunkShape, DataType,
+};
+
+#[cfg(feature = "ndarray")]
+use super::elements_to_ndarray;
+
+/// The function computing the bytes of the elements of a [`VirtualArray`] in an array subset.
+pub type VirtualArrayFn = dyn Fn(&ArraySubset) -> Result<Vec<u8>, ArrayError> + Send + Sync;
+
+/// A read-only array with elements computed on demand.
+///
+/// The elements of a virtual array are computed by a function of an array subset, which may be a user closure or derived from other arrays (e.g. [`from_array`](VirtualArray::from_array), [`slice`](VirtualArray::slice), [`cast`](VirtualArray::cast), [`zip_with`](VirtualArray::zip_with)).
+/// Nothing is stored, so derived datasets can be exposed without materialising them.
+///
+/// Retrieval methods mirror those of [`Array`].
+/// A virtual array has a regular grid of chunks, and retrieving an array subset computes the overlap of each chunk in parallel.
+#[derive(Clone)]
+pub struct VirtualArray {
+    shape: ArrayShape,
+    data_type: DataType,
+    chunk_shape: ChunkShape,
+    compute: Arc<VirtualArrayFn>,
+}
+
+impl core::fmt::Debug for VirtualArray {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VirtualArray")
+            .field("shape", &self.shape)
+            .field("data_type", &self.data_type)
+            .field("chunk_shape", &self.chunk_shape)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VirtualArray {
+    /// Create a new virtual array with `shape`, `data_type`, and `chunk_shape`.
+    ///
+    /// `compute` returns the bytes of the elements in an array subset in C order, and it is only called with subsets within the bounds of `shape`.
+    ///
+    /// # Errors
+    /// Returns an [`IncompatibleDimensionalityError`] if the dimensionality of `chunk_shape` does not match `shape`.
+    pub fn new(
+        shape: ArrayShape,
+        data_type: DataType,
+        chunk_shape: ChunkShape,
+        compute: impl Fn(&ArraySubset) -> Result<Vec<u8>, ArrayError> + Send + Sync + 'static,
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if chunk_shape.len() != shape.len() {
+            return Err(IncompatibleDimensionalityError::new(
+                chunk_shape.len(),
+                shape.len(),
+            ));
+        }
+        Ok(Self {
+            shape,
+            data_type,
+            chunk_shape,
+            compute: Arc::new(compute),
+        })
+    }
+
+    /// Create a virtual array with the elements of `array`.
+    ///
+    /// The chunk shape is that of the first chunk of `array`, or the array shape if it has no chunks.
+    #[must_use]
+    pub fn from_array<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+        array: Arc<Array<TStorage>>,
+    ) -> Self {
+        let chunk_shape = array
+            .chunk_shape(&vec![0; array.dimensionality()])
+            .ok()
+            .unwrap_or_else(|| {
+                array
+                    .shape()
+                    .iter()
+                    .map(|size| NonZeroU64::new(*size).unwrap_or(NonZeroU64::MIN))
+                    .collect::<Vec<_>>()
+                    .into()
+            });
+        Self {
+            shape: array.shape().to_vec(),
+            data_type: array.data_type().clone(),
+            chunk_shape,
+            compute: Arc::new(move |array_subset| array.retrieve_array_subset(array_subset)),
+        }
+    }
+
+    /// Return the shape of the array.
+    #[must_use]
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Return the dimensionality of the array.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Return the data type of the array.
+    #[must_use]
+    pub const fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// Return the chunk shape of the array.
+    #[must_use]
+    pub const fn chunk_shape(&self) -> &ChunkShape {
+        &self.chunk_shape
+    }
+
+    /// Return the shape of the chunk grid (i.e., the number of chunks in each dimension).
+    #[must_use]
+    pub fn chunk_grid_shape(&self) -> ArrayShape {
+        std::iter::zip(&self.shape, self.chunk_shape.iter())
+            .map(|(size, chunk_size)| size.div_ceil(chunk_size.get()))
+            .collect()
+    }
+
+    /// Return the array subset of the chunk at `chunk_indices`, bounded by the array shape.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidChunkGridIndicesError`] if `chunk_indices` are incompatible with the chunk grid.
+    pub fn chunk_subset_bounded(&self, chunk_indices: &[u64]) -> Result<ArraySubset, ArrayError> {
+        if chunk_indices.len() != self.dimensionality()
+            || std::iter::zip(chunk_indices, self.chunk_grid_shape())
+                .any(|(index, size)| *index >= size)
+        {
+            return Err(ArrayError::InvalidChunkGridIndicesError(
+                chunk_indices.to_vec(),
+            ));
+        }
+        let ranges: Vec<_> = itertools::izip!(chunk_indices, self.chunk_shape.iter(), &self.shape)
+            .map(|(index, chunk_size, size)| {
+                let start = index * chunk_size.get();
+                start..std::cmp::min(start + chunk_size.get(), *size)
+            })
+            .collect();
+        Ok(ArraySubset::new_with_ranges(&ranges))
+    }
+
+    /// Compute the chunk at `chunk_indices` into its bytes.
+    ///
+    /// Unlike [`Array::retrieve_chunk`], chunks are bounded by the array shape.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `chunk_indices` are invalid or the chunk cannot be computed.
+    pub fn retrieve_chunk(&self, chunk_indices: &[u64]) -> Result<Vec<u8>, ArrayError> {
+        self.retrieve_array_subset(&self.chunk_subset_bounded(chunk_indices)?)
+    }
+
+    /// Compute the chunk at `chunk_indices` into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the size of `T` does not match the data type size or a [`retrieve_chunk`](VirtualArray::retrieve_chunk) error condition is met.
+    pub fn retrieve_chunk_elements<T: bytemuck::Pod>(
+        &self,
+        chunk_indices: &[u64],
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(&self.data_type)?;
+        Ok(transmute_from_bytes_vec(
+            self.retrieve_chunk(chunk_indices)?,
+        ))
+    }
+
+    /// Compute the `array_subset` of the array into its bytes.
+    ///
+    /// The overlap of each chunk with `array_subset` is computed in parallel.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if `array_subset` is incompatible with the array or an element cannot be computed.
+    pub fn retrieve_array_subset(&self, array_subset: &ArraySubset) -> Result<Vec<u8>, ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(&self.shape)
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape.clone(),
+            ));
+        }
+        let element_size = self.data_type.size();
+        let chunks = unsafe { array_subset.chunks_unchecked(&self.chunk_shape) };
+        if chunks.len() == 1 {
+            return self.compute(array_subset);
+        }
+
+        let chunk_bytes = (&chunks)
+            .into_par_iter()
+            .map(|(_chunk_indices, chunk_subset)| {
+                let overlap = unsafe { chunk_subset.overlap_unchecked(array_subset) };
+                let bytes = self.compute(&overlap)?;
+                Ok((overlap, bytes))
+            })
+            .collect::<Result<Vec<_>, ArrayError>>()?;
+        let mut output = vec![0; array_subset.num_elements_usize() * element_size];
+        for (overlap, bytes) in chunk_bytes {
+            unsafe {
+                overlap
+                    .relative_to_unchecked(array_subset.start())
+                    .store_bytes_unchecked(&bytes, &mut output, array_subset.shape(), element_size);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Compute the `array_subset` of the array into a vector of its elements.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the size of `T` does not match the data type size or a [`retrieve_array_subset`](VirtualArray::retrieve_array_subset) error condition is met.
+    pub fn retrieve_array_subset_elements<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Vec<T>, ArrayError> {
+        validate_element_size::<T>(&self.data_type)?;
+        Ok(transmute_from_bytes_vec(
+            self.retrieve_array_subset(array_subset)?,
+        ))
+    }
+
+    #[cfg(feature = "ndarray")]
+    /// Compute the `array_subset` of the array into an [`ndarray::ArrayD`].
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if a [`retrieve_array_subset_elements`](VirtualArray::retrieve_array_subset_elements) error condition is met.
+    pub fn retrieve_array_subset_ndarray<T: bytemuck::Pod>(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<ndarray::ArrayD<T>, ArrayError> {
+        let elements = self.retrieve_array_subset_elements(array_subset)?;
+        elements_to_ndarray(array_subset.shape(), elements)
+    }
+
+    /// Return a virtual array of the elements of this array in `array_subset`.
+    ///
+    /// The chunk shape is unchanged.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::InvalidArraySubset`] if `array_subset` is incompatible with the array.
+    pub fn slice(&self, array_subset: &ArraySubset) -> Result<Self, ArrayError> {
+        if array_subset.dimensionality() != self.dimensionality()
+            || !array_subset.inbounds(&self.shape)
+        {
+            return Err(ArrayError::InvalidArraySubset(
+                array_subset.clone(),
+                self.shape.clone(),
+            ));
+        }
+        let source = self.compute.clone();
+        let offset = array_subset.start().to_vec();
+        Ok(Self {
+            shape: array_subset.shape().to_vec(),
+            data_type: self.data_type.clone(),
+            chunk_shape: self.chunk_shape.clone(),
+            compute: Arc::new(move |array_subset: &ArraySubset| {
+                let start = std::iter::zip(array_subset.start(), &offset)
+                    .map(|(start, offset)| start + offset)
+                    .collect();
+                source(&unsafe {
+                    ArraySubset::new_with_start_shape_unchecked(
+                        start,
+                        array_subset.shape().to_vec(),
+                    )
+                })
+            }),
+        })
+    }
+
+    /// Return a virtual array with the elements of this array mapped by `f` to `data_type`.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the size of `T` does not match the data type of this array, or the size of `U` does not match `data_type`.
+    pub fn map<T: bytemuck::Pod, U: bytemuck::Pod>(
+        &self,
+        data_type: DataType,
+        f: impl Fn(T) -> U + Send + Sync + 'static,
+    ) -> Result<Self, ArrayError> {
+        validate_element_size::<T>(&self.data_type)?;
+        validate_element_size::<U>(&data_type)?;
+        let source = self.compute.clone();
+        Ok(Self {
+            shape: self.shape.clone(),
+            data_type,
+            chunk_shape: self.chunk_shape.clone(),
+            compute: Arc::new(move |array_subset| {
+                let elements = transmute_from_bytes_vec::<T>(source(array_subset)?);
+                Ok(transmute_to_bytes_vec(
+                    elements.into_iter().map(&f).collect(),
+                ))
+            }),
+        })
+    }
+
+    /// Return a virtual array combining the elements of this array and `other` with `f`.
+    ///
+    /// For example, the element-wise sum of two `float32` arrays is `a.zip_with(&b, DataType::Float32, |a: f32, b: f32| a + b)`.
+    /// The chunk shape of this array is used.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayError`] if the shapes of the arrays do not match, or the sizes of `T`, `U`, and `V` do not match the data types of this array, `other`, and `data_type`.
+    pub fn zip_with<T: bytemuck::Pod, U: bytemuck::Pod, V: bytemuck::Pod>(
+        &self,
+        other: &Self,
+        data_type: DataType,
+        f: impl Fn(T, U) -> V + Send + Sync + 'static,
+    ) -> Result<Self, ArrayError> {
+        if self.shape != other.shape {
+            return Err(ArrayError::InvalidDataShape(
+                other
+                    .shape
+                    .iter()
+                    .map(|size| usize::try_from(*size).unwrap_or(usize::MAX))
+                    .collect(),
+                self.shape
+                    .iter()
+                    .map(|size| usize::try_from(*size).unwrap_or(usize::MAX))
+                    .collect(),
+            ));
+        }
+        validate_element_size::<T>(&self.data_type)?;
+        validate_element_size::<U>(&other.data_type)?;
+        validate_element_size::<V>(&data_type)?;
+        let (source_a, source_b) = (self.compute.clone(), other.compute.clone());
+        Ok(Self {
+            shape: self.shape.clone(),
+            data_type,
+            chunk_shape: self.chunk_shape.clone(),
+            compute: Arc::new(move |array_subset| {
+                let (a, b) = rayon::join(|| source_a(array_subset), || source_b(array_subset));
+                let a = transmute_from_bytes_vec::<T>(a?);
+                let b = transmute_from_bytes_vec::<U>(b?);
+                Ok(transmute_to_bytes_vec(
+                    std::iter::zip(a, b).map(|(a, b)| f(a, b)).collect(),
+                ))
+            }),
+        })
+    }
+
+    /// Return a virtual array with the elements of this array cast to `data_type`.
+    ///
+    /// Elements are converted through [`f64`] with the semantics of an `as` cast (i.e. float to integer casts truncate and saturate).
+    /// Only integer and floating point data types are supported.
+    ///
+    /// # Errors
+    /// Returns [`ArrayError::IncompatibleElementType`] if the data type of this array or `data_type` is not supported.
+    pub fn cast(&self, data_type: DataType) -> Result<Self, ArrayError> {
+        for data_type in [&self.data_type, &data_type] {
+            if !supports_cast(data_type) {
+                return Err(ArrayError::IncompatibleElementType(
+                    data_type.clone(),
+                    "cast",
+                ));
+            }
+        }
+        let source = self.compute.clone();
+        let source_data_type = self.data_type.clone();
+        let target_data_type = data_type.clone();
+        Ok(Self {
+            shape: self.shape.clone(),
+            data_type,
+            chunk_shape: self.chunk_shape.clone(),
+            compute: Arc::new(move |array_subset| {
+                let elements = bytes_to_f64(source(array_subset)?, &source_data_type);
+                Ok(f64_to_bytes(elements, &target_data_type))
+            }),
+        })
+    }
+
+    fn compute(&self, array_subset: &ArraySubset) -> Result<Vec<u8>, ArrayError> {
+        let bytes = (self.compute)(array_subset)?;
+        let expected_size = array_subset.num_elements_usize() * self.data_type.size();
+        if bytes.len() == expected_size {
+            Ok(bytes)
+        } else {
+            Err(ArrayError::UnexpectedChunkDecodedSize(
+                bytes.len(),
+                expected_size,
+            ))
+        }
+    }
+}
+
+fn supports_cast(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float16
+            | DataType::BFloat16
+            | DataType::Float32
+            | DataType::Float64
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn bytes_to_f64(bytes: Vec<u8>, data_type: &DataType) -> Vec<f64> {
+    macro_rules! to_f64 {
+        ( $t:ty, $to_f64:expr ) => {
+            transmute_from_bytes_vec::<$t>(bytes)
+                .into_iter()
+                .map($to_f64)
+                .collect()
+        };
+    }
+    match data_type {
+        DataType::Int8 => to_f64!(i8, f64::from),
+        DataType::Int16 => to_f64!(i16, f64::from),
+        DataType::Int32 => to_f64!(i32, f64::from),
+        DataType::Int64 => to_f64!(i64, |v: i64| v as f64),
+        DataType::UInt8 => to_f64!(u8, f64::from),
+        DataType::UInt16 => to_f64!(u16, f64::from),
+        DataType::UInt32 => to_f64!(u32, f64::from),
+        DataType::UInt64 => to_f64!(u64, |v: u64| v as f64),
+        DataType::Float16 => to_f64!(half::f16, half::f16::to_f64),
+        DataType::BFloat16 => to_f64!(half::bf16, half::bf16::to_f64),
+        DataType::Float32 => to_f64!(f32, f64::from),
+        DataType::Float64 => to_f64!(f64, |v: f64| v),
+        _ => unreachable!("validated by supports_cast"),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn f64_to_bytes(elements: Vec<f64>, data_type: &DataType) -> Vec<u8> {
+    macro_rules! from_f64 {
+        ( $t:ty, $from_f64:expr ) => {
+            transmute_to_bytes_vec(elements.into_iter().map($from_f64).collect::<Vec<$t>>())
+        };
+    }
+    match data_type {
+        DataType::Int8 => from_f64!(i8, |v: f64| v as i8),
+        DataType::Int16 => from_f64!(i16, |v: f64| v as i16),
+        DataType::Int32 => from_f64!(i32, |v: f64| v as i32),
+        DataType::Int64 => from_f64!(i64, |v: f64| v as i64),
+        DataType::UInt8 => from_f64!(u8, |v: f64| v as u8),
+        DataType::UInt16 => from_f64!(u16, |v: f64| v as u16),
+        DataType::UInt32 => from_f64!(u32, |v: f64| v as u32),
+        DataType::UInt64 => from_f64!(u64, |v: f64| v as u64),
+        DataType::Float16 => from_f64!(half::f16, half::f16::from_f64),
+        DataType::BFloat16 => from_f64!(half::bf16, half::bf16::from_f64),
+        DataType::Float32 => from_f64!(f32, |v: f64| v as f32),
+        DataType::Float64 => transmute_to_bytes_vec(elements),
+        _ => unreachable!("validated by supports_cast"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn iota(shape: ArrayShape) -> VirtualArray {
+        let array_shape = shape.clone();
+        VirtualArray::new(
+            shape,
+            DataType::UInt16,
+            vec![2, 3].try_into().unwrap(),
+            move |array_subset| {
+                let elements: Vec<u16> = array_subset
+                    .linearised_indices(&array_shape)
+                    .unwrap()
+                    .into_iter()
+                    .map(|index| u16::try_from(index).unwrap())
+                    .collect();
+                Ok(transmute_to_bytes_vec(elements))
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn virtual_array_closure() {
+        let array = iota(vec![4, 5]);
+        assert_eq!(array.chunk_grid_shape(), vec![2, 2]);
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[1..3, 2..5]))
+                .unwrap(),
+            vec![7, 8, 9, 12, 13, 14]
+        );
+        assert_eq!(
+            array.retrieve_chunk_elements::<u16>(&[1, 1]).unwrap(),
+            vec![13, 14, 18, 19]
+        );
+        assert!(array.retrieve_chunk(&[2, 0]).is_err());
+        assert!(array
+            .retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..5, 0..5]))
+            .is_err());
+        assert!(array
+            .retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_shape(vec![1, 1]))
+            .is_err());
+    }
+
+    #[test]
+    fn virtual_array_derived() {
+        let store = Arc::new(MemoryStore::new());
+        let stored = ArrayBuilder::new(
+            vec![4, 5],
+            DataType::UInt16,
+            vec![3, 3].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap();
+        stored
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![4, 5]),
+                vec![100; 20],
+            )
+            .unwrap();
+        let stored = VirtualArray::from_array(Arc::new(stored));
+        assert_eq!(stored.chunk_shape(), &vec![3, 3].try_into().unwrap());
+
+        let sum = iota(vec![4, 5])
+            .zip_with(&stored, DataType::UInt16, |a: u16, b: u16| a + b)
+            .unwrap();
+        let sliced = sum
+            .slice(&ArraySubset::new_with_ranges(&[2..4, 1..4]))
+            .unwrap();
+        assert_eq!(sliced.shape(), &[2, 3]);
+        assert_eq!(
+            sliced
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![2, 3]))
+                .unwrap(),
+            vec![111, 112, 113, 116, 117, 118]
+        );
+
+        let cast = sliced
+            .map(DataType::Float32, |v: u16| f32::from(v) / 2.0)
+            .unwrap()
+            .cast(DataType::Int8)
+            .unwrap();
+        assert_eq!(
+            cast.retrieve_array_subset_elements::<i8>(&ArraySubset::new_with_shape(vec![2, 3]))
+                .unwrap(),
+            vec![55, 56, 56, 58, 58, 59]
+        );
+        assert!(cast.cast(DataType::Bool).is_err());
+        assert!(sum
+            .zip_with(&sliced, DataType::UInt16, |a: u16, b: u16| a + b)
+            .is_err());
+    }
+}
@@ -5,17 +5,21 @@
 //! Many [`Array`](crate::array::Array) store and retrieve methods have an [`ArraySubset`] parameter.
 //! This module includes various types of [`iterators`] over the elements represented by an [`ArraySubset`].
 //!
+//! A [`StridedArraySubset`] represents a subset with a step along each dimension (e.g. every second element).
+//!
 //! This module also provides convenience functions for:
 //!  - computing the byte ranges of array subsets within an array, and
 //!  - extracting the bytes within subsets of an array.
 
 pub mod iterators;
+mod strided_array_subset;
 
 use std::{num::NonZeroU64, ops::Range};
 
 use iterators::{
     Chunks, ContiguousIndices, ContiguousLinearisedIndices, Indices, LinearisedIndices,
 };
+pub use strided_array_subset::StridedArraySubset;
 
 use derive_more::{Display, From};
 use itertools::izip;
@@ -762,6 +766,8 @@ pub struct IncompatibleStartEndIndicesError(ArrayIndices, ArrayIndices);
 
 #[cfg(test)]
 mod tests {
+    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
     use super::*;
 
     #[test]
@@ -805,8 +811,8 @@ mod tests {
             unsafe { array_subset2.linearised_indices_unchecked(&[6, 7, 1]) }
                 .into_iter()
                 .next(),
-            Some(4 * 1 + 3 * 7 * 1)
-        )
+            Some(4 + 3 * 7)
+        );
     }
 
     #[test]
@@ -884,4 +890,63 @@ mod tests {
             vec![5, 6, 7, 8]
         );
     }
+
+    #[test]
+    fn array_subset_empty() {
+        let array_shape = [4, 0, 4];
+        for array_subset in [
+            ArraySubset::new_with_shape(array_shape.to_vec()),
+            ArraySubset::new_with_ranges(&[1..3, 0..0, 1..3]),
+            ArraySubset::new_with_ranges(&[0..4, 0..0, 4..4]),
+        ] {
+            assert!(array_subset.is_empty());
+            assert_eq!(array_subset.num_elements(), 0);
+            assert!(array_subset.inbounds(&array_shape));
+            assert_eq!(array_subset.indices().len(), 0);
+            assert_eq!(array_subset.indices().iter().next(), None);
+            assert_eq!(array_subset.indices().iter().next_back(), None);
+            assert_eq!((&array_subset.indices()).into_par_iter().count(), 0);
+            assert!(array_subset
+                .contiguous_indices(&array_shape)
+                .unwrap()
+                .is_empty());
+            assert_eq!(
+                array_subset
+                    .contiguous_linearised_indices(&array_shape)
+                    .unwrap()
+                    .iter()
+                    .next(),
+                None
+            );
+            assert!(array_subset
+                .byte_ranges(&array_shape, 2)
+                .unwrap()
+                .is_empty());
+            assert!(array_subset
+                .chunks(&[NonZeroU64::new(2).unwrap(); 3])
+                .unwrap()
+                .is_empty());
+            assert!(array_subset
+                .extract_bytes(&[], &array_shape, 2)
+                .unwrap()
+                .is_empty());
+            array_subset
+                .store_bytes(&[], &mut [], &array_shape, 2)
+                .unwrap();
+        }
+
+        // Empty subsets of a non-empty array
+        let array_subset = ArraySubset::new_with_ranges(&[1..1, 0..4]);
+        let bytes_array: Vec<u8> = (0..16).collect();
+        assert!(array_subset
+            .extract_bytes(&bytes_array, &[4, 4], 1)
+            .unwrap()
+            .is_empty());
+        assert!(array_subset.byte_ranges(&[4, 4], 1).unwrap().is_empty());
+        let mut bytes_array_out = bytes_array.clone();
+        array_subset
+            .store_bytes(&[], &mut bytes_array_out, &[4, 4], 1)
+            .unwrap();
+        assert_eq!(bytes_array_out, bytes_array);
+    }
 }
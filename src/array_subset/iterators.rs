@@ -15,7 +15,7 @@
 //! [`chunks`](super::ArraySubset::chunks).
 //!
 //! All iterators support [`into_iter()`](IntoIterator::into_iter) ([`IntoIterator`]).
-//! The [`Indices`] and [`Chunks`] iterators also support [`rayon`]'s [`into_par_iter()`](rayon::iter::IntoParallelIterator::into_par_iter) ([`IntoParallelIterator`](rayon::iter::IntoParallelIterator)).
+//! The [`Indices`], [`ContiguousIndices`], [`ContiguousLinearisedIndices`], and [`Chunks`] iterators also support [`rayon`]'s [`into_par_iter()`](rayon::iter::IntoParallelIterator::into_par_iter) ([`IntoParallelIterator`](rayon::iter::IntoParallelIterator)).
 
 mod chunks_iterator;
 mod contiguous_indices_iterator;
@@ -24,9 +24,12 @@ mod indices_iterator;
 mod linearised_indices_iterator;
 
 pub use chunks_iterator::{Chunks, ChunksIterator};
-pub use contiguous_indices_iterator::{ContiguousIndices, ContiguousIndicesIterator};
+pub use contiguous_indices_iterator::{
+    ContiguousIndices, ContiguousIndicesIterator, ParContiguousIndicesIterator,
+};
 pub use contiguous_linearised_indices_iterator::{
     ContiguousLinearisedIndices, ContiguousLinearisedIndicesIterator,
+    ParContiguousLinearisedIndicesIterator,
 };
 pub use indices_iterator::{Indices, IndicesIterator, ParIndicesIterator};
 pub use linearised_indices_iterator::{LinearisedIndices, LinearisedIndicesIterator};
@@ -35,7 +38,7 @@ pub use linearised_indices_iterator::{LinearisedIndices, LinearisedIndicesIterat
 mod tests {
     use std::num::NonZeroU64;
 
-    use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 
     use crate::array_subset::ArraySubset;
 
@@ -142,6 +145,26 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn array_subset_par_iter_contiguous_linearised_indices() {
+        let subset = ArraySubset::new_with_ranges(&[1..4, 1..3]);
+        let indices = subset.contiguous_linearised_indices(&[4, 4]).unwrap();
+        assert_eq!(
+            indices.into_par_iter().collect::<Vec<_>>(),
+            indices.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            indices.into_par_iter().with_max_len(1).collect::<Vec<_>>(),
+            vec![(5, 2), (9, 2), (13, 2)]
+        );
+
+        let indices = subset.contiguous_indices(&[4, 4]).unwrap();
+        assert_eq!(
+            indices.into_par_iter().collect::<Vec<_>>(),
+            vec![(vec![1, 1], 2), (vec![2, 1], 2), (vec![3, 1], 2)]
+        );
+    }
+
     #[test]
     #[rustfmt::skip]
     fn array_subset_iter_chunks1() {
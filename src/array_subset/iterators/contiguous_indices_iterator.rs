@@ -7,7 +7,12 @@ use crate::{
     array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
 };
 
-use super::IndicesIterator;
+use rayon::iter::{
+    plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer},
+    IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
+};
+
+use super::{indices_iterator::ParIndicesIteratorProducer, IndicesIterator, ParIndicesIterator};
 
 /// Iterates over contiguous element indices in an array subset.
 ///
@@ -67,6 +72,14 @@ impl ContiguousIndices {
             std::iter::zip(subset.end_exc(), array_shape).all(|(end, shape)| end <= *shape)
         );
 
+        if subset.is_empty() {
+            // An empty subset has no contiguous elements to iterate over
+            return Self {
+                subset_contiguous_start: subset.clone(),
+                contiguous_elements: 0,
+            };
+        }
+
         let mut contiguous = true;
         let mut contiguous_elements = 1;
         let mut shape_out: Vec<u64> = Vec::with_capacity(array_shape.len());
@@ -140,6 +153,18 @@ impl<'a> IntoIterator for &'a ContiguousIndices {
     }
 }
 
+impl<'a> IntoParallelIterator for &'a ContiguousIndices {
+    type Item = (ArrayIndices, u64);
+    type Iter = ParContiguousIndicesIterator<'a>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        ParContiguousIndicesIterator {
+            inner: ParIndicesIterator::new(&self.subset_contiguous_start),
+            contiguous_elements: self.contiguous_elements,
+        }
+    }
+}
+
 /// Serial contiguous indices iterator.
 ///
 /// See [`ContiguousIndices`].
@@ -173,3 +198,82 @@ impl DoubleEndedIterator for ContiguousIndicesIterator<'_> {
 impl ExactSizeIterator for ContiguousIndicesIterator<'_> {}
 
 impl FusedIterator for ContiguousIndicesIterator<'_> {}
+
+/// Parallel contiguous indices iterator.
+///
+/// See [`ContiguousIndices`].
+pub struct ParContiguousIndicesIterator<'a> {
+    inner: ParIndicesIterator<'a>,
+    contiguous_elements: u64,
+}
+
+impl ParallelIterator for ParContiguousIndicesIterator<'_> {
+    type Item = (ArrayIndices, u64);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl IndexedParallelIterator for ParContiguousIndicesIterator<'_> {
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        let producer = ParContiguousIndicesIteratorProducer::from(&self);
+        callback.callback(producer)
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct ParContiguousIndicesIteratorProducer<'a> {
+    inner: ParIndicesIteratorProducer<'a>,
+    contiguous_elements: u64,
+}
+
+impl<'a> Producer for ParContiguousIndicesIteratorProducer<'a> {
+    type Item = (ArrayIndices, u64);
+    type IntoIter = ContiguousIndicesIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ContiguousIndicesIterator {
+            inner: self.inner.into_iter(),
+            contiguous_elements: self.contiguous_elements,
+        }
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.inner.split_at(index);
+        (
+            ParContiguousIndicesIteratorProducer {
+                inner: left,
+                contiguous_elements: self.contiguous_elements,
+            },
+            ParContiguousIndicesIteratorProducer {
+                inner: right,
+                contiguous_elements: self.contiguous_elements,
+            },
+        )
+    }
+}
+
+impl<'a> From<&'a ParContiguousIndicesIterator<'_>> for ParContiguousIndicesIteratorProducer<'a> {
+    fn from(iterator: &'a ParContiguousIndicesIterator<'_>) -> Self {
+        Self {
+            inner: ParIndicesIteratorProducer::from(&iterator.inner),
+            contiguous_elements: iterator.contiguous_elements,
+        }
+    }
+}
@@ -5,7 +5,15 @@ use crate::{
     array_subset::{ArraySubset, IncompatibleArraySubsetAndShapeError},
 };
 
-use super::{contiguous_indices_iterator::ContiguousIndices, ContiguousIndicesIterator};
+use rayon::iter::{
+    plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer},
+    IndexedParallelIterator, IntoParallelIterator, ParallelIterator,
+};
+
+use super::{
+    contiguous_indices_iterator::{ContiguousIndices, ParContiguousIndicesIteratorProducer},
+    ContiguousIndicesIterator, ParContiguousIndicesIterator,
+};
 
 /// Iterates over contiguous linearised element indices in an array subset.
 ///
@@ -103,6 +111,18 @@ impl<'a> IntoIterator for &'a ContiguousLinearisedIndices {
     }
 }
 
+impl<'a> IntoParallelIterator for &'a ContiguousLinearisedIndices {
+    type Item = (u64, u64);
+    type Iter = ParContiguousLinearisedIndicesIterator<'a>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        ParContiguousLinearisedIndicesIterator {
+            inner: self.inner.into_par_iter(),
+            array_shape: &self.array_shape,
+        }
+    }
+}
+
 /// Serial contiguous linearised indices iterator.
 ///
 /// See [`ContiguousLinearisedIndices`].
@@ -136,3 +156,84 @@ impl DoubleEndedIterator for ContiguousLinearisedIndicesIterator<'_> {
 impl ExactSizeIterator for ContiguousLinearisedIndicesIterator<'_> {}
 
 impl FusedIterator for ContiguousLinearisedIndicesIterator<'_> {}
+
+/// Parallel contiguous linearised indices iterator.
+///
+/// See [`ContiguousLinearisedIndices`].
+pub struct ParContiguousLinearisedIndicesIterator<'a> {
+    inner: ParContiguousIndicesIterator<'a>,
+    array_shape: &'a [u64],
+}
+
+impl ParallelIterator for ParContiguousLinearisedIndicesIterator<'_> {
+    type Item = (u64, u64);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl IndexedParallelIterator for ParContiguousLinearisedIndicesIterator<'_> {
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        let producer = ParContiguousLinearisedIndicesIteratorProducer::from(&self);
+        callback.callback(producer)
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[derive(Debug)]
+struct ParContiguousLinearisedIndicesIteratorProducer<'a> {
+    inner: ParContiguousIndicesIteratorProducer<'a>,
+    array_shape: &'a [u64],
+}
+
+impl<'a> Producer for ParContiguousLinearisedIndicesIteratorProducer<'a> {
+    type Item = (u64, u64);
+    type IntoIter = ContiguousLinearisedIndicesIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ContiguousLinearisedIndicesIterator {
+            inner: self.inner.into_iter(),
+            array_shape: self.array_shape,
+        }
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.inner.split_at(index);
+        (
+            ParContiguousLinearisedIndicesIteratorProducer {
+                inner: left,
+                array_shape: self.array_shape,
+            },
+            ParContiguousLinearisedIndicesIteratorProducer {
+                inner: right,
+                array_shape: self.array_shape,
+            },
+        )
+    }
+}
+
+impl<'a> From<&'a ParContiguousLinearisedIndicesIterator<'_>>
+    for ParContiguousLinearisedIndicesIteratorProducer<'a>
+{
+    fn from(iterator: &'a ParContiguousLinearisedIndicesIterator<'_>) -> Self {
+        Self {
+            inner: ParContiguousIndicesIteratorProducer::from(&iterator.inner),
+            array_shape: iterator.array_shape,
+        }
+    }
+}
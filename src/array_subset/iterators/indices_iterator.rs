@@ -154,11 +154,10 @@ impl Iterator for IndicesIterator<'_> {
     type Item = ArrayIndices;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut indices = unravel_index(self.index_front, self.subset.shape());
-        std::iter::zip(indices.iter_mut(), self.subset.start())
-            .for_each(|(index, start)| *index += start);
-
         if self.index_front < self.index_back {
+            let mut indices = unravel_index(self.index_front, self.subset.shape());
+            std::iter::zip(indices.iter_mut(), self.subset.start())
+                .for_each(|(index, start)| *index += start);
             self.index_front += 1;
             Some(indices)
         } else {
@@ -199,6 +198,22 @@ pub struct ParIndicesIterator<'a> {
     length: usize,
 }
 
+impl<'a> ParIndicesIterator<'a> {
+    /// Create a new parallel indices iterator.
+    #[must_use]
+    pub(super) fn new(subset: &'a ArraySubset) -> Self {
+        let length = subset.num_elements_usize();
+        let index_front = 0;
+        let index_back = length as u64;
+        Self {
+            subset,
+            index_front,
+            index_back,
+            length,
+        }
+    }
+}
+
 impl ParallelIterator for ParIndicesIterator<'_> {
     type Item = ArrayIndices;
 
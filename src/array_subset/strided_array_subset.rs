@@ -0,0 +1,411 @@
+use std::num::NonZeroU64;
+
+use derive_more::Display;
+use itertools::izip;
+
+use crate::array::{ravel_indices, ArrayIndices, ArrayShape};
+
+use super::{ArraySubset, IncompatibleArraySubsetAndShapeError, IncompatibleDimensionalityError};
+
+/// An array subset with a step (stride) along each dimension.
+///
+/// A strided array subset selects the elements at `start + i * step` for `i` in `0..shape` along each dimension.
+/// For example, every second element of the first 8 elements of a 1D array is selected by a strided array subset with start `[0]`, shape `[4]`, and step `[2]`.
+///
+/// Create a strided array subset with [`ArraySubset::with_step`] or [`StridedArraySubset::new_with_start_shape_step`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Display)]
+#[display(fmt = "start {start:?} shape {shape:?} step {step:?}")]
+pub struct StridedArraySubset {
+    /// The start of the strided array subset.
+    start: ArrayIndices,
+    /// The number of selected elements along each dimension.
+    shape: ArrayShape,
+    /// The step along each dimension.
+    step: Vec<NonZeroU64>,
+}
+
+impl ArraySubset {
+    /// Return a strided array subset selecting every `step`th element of this array subset along each dimension, beginning at its start.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the length of `step` does not match the dimensionality of the array subset.
+    pub fn with_step(
+        &self,
+        step: &[NonZeroU64],
+    ) -> Result<StridedArraySubset, IncompatibleDimensionalityError> {
+        if step.len() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                step.len(),
+                self.dimensionality(),
+            ));
+        }
+        let shape = std::iter::zip(self.shape(), step)
+            .map(|(size, step)| size.div_ceil(step.get()))
+            .collect();
+        Ok(StridedArraySubset {
+            start: self.start().to_vec(),
+            shape,
+            step: step.to_vec(),
+        })
+    }
+}
+
+impl From<ArraySubset> for StridedArraySubset {
+    fn from(array_subset: ArraySubset) -> Self {
+        let step = vec![NonZeroU64::MIN; array_subset.dimensionality()];
+        Self {
+            start: array_subset.start().to_vec(),
+            shape: array_subset.shape().to_vec(),
+            step,
+        }
+    }
+}
+
+impl StridedArraySubset {
+    /// Create a new strided array subset from its `start`, `shape` (the number of selected elements along each dimension), and `step`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the lengths of `start`, `shape`, and `step` do not match.
+    pub fn new_with_start_shape_step(
+        start: ArrayIndices,
+        shape: ArrayShape,
+        step: Vec<NonZeroU64>,
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if shape.len() != start.len() {
+            Err(IncompatibleDimensionalityError::new(
+                shape.len(),
+                start.len(),
+            ))
+        } else if step.len() != start.len() {
+            Err(IncompatibleDimensionalityError::new(
+                step.len(),
+                start.len(),
+            ))
+        } else {
+            Ok(Self { start, shape, step })
+        }
+    }
+
+    /// Return the start of the strided array subset.
+    #[must_use]
+    pub fn start(&self) -> &[u64] {
+        &self.start
+    }
+
+    /// Return the shape of the strided array subset (the number of selected elements along each dimension).
+    #[must_use]
+    pub fn shape(&self) -> &[u64] {
+        &self.shape
+    }
+
+    /// Return the step along each dimension.
+    #[must_use]
+    pub fn step(&self) -> &[NonZeroU64] {
+        &self.step
+    }
+
+    /// Return the dimensionality of the strided array subset.
+    #[must_use]
+    pub fn dimensionality(&self) -> usize {
+        self.start.len()
+    }
+
+    /// Return the number of elements of the strided array subset.
+    #[must_use]
+    pub fn num_elements(&self) -> u64 {
+        self.shape.iter().product()
+    }
+
+    /// Return the number of elements of the strided array subset as a `usize`.
+    ///
+    /// # Panics
+    /// Panics if [`num_elements()`](Self::num_elements()) is greater than [`usize::MAX`].
+    #[must_use]
+    pub fn num_elements_usize(&self) -> usize {
+        usize::try_from(self.num_elements()).unwrap()
+    }
+
+    /// Returns true if the strided array subset is empty (i.e. has a zero element in its shape).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shape.iter().any(|i| i == &0)
+    }
+
+    /// Returns true if the step is one along every dimension.
+    #[must_use]
+    pub fn is_contiguous(&self) -> bool {
+        self.step.iter().all(|step| step.get() == 1)
+    }
+
+    /// Return the smallest array subset encapsulating the elements of the strided array subset.
+    #[must_use]
+    pub fn bounding_subset(&self) -> ArraySubset {
+        let shape = std::iter::zip(&self.shape, &self.step)
+            .map(|(size, step)| size.saturating_sub(1) * step.get() + u64::from(*size > 0))
+            .collect();
+        unsafe { ArraySubset::new_with_start_shape_unchecked(self.start.clone(), shape) }
+    }
+
+    /// Returns true if the strided array subset is within the bounds of `array_shape`.
+    #[must_use]
+    pub fn inbounds(&self, array_shape: &[u64]) -> bool {
+        self.bounding_subset().inbounds(array_shape)
+    }
+
+    /// Return the elements of the strided array subset within `array_subset`.
+    ///
+    /// The returned strided array subset has the same step.
+    /// Use [`relative_to_strided`](Self::relative_to_strided) to find its location within this strided array subset.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the dimensionality of `array_subset` does not match the dimensionality of this strided array subset.
+    pub fn overlap(
+        &self,
+        array_subset: &ArraySubset,
+    ) -> Result<Self, IncompatibleDimensionalityError> {
+        if array_subset.dimensionality() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                array_subset.dimensionality(),
+                self.dimensionality(),
+            ));
+        }
+        let mut start = Vec::with_capacity(self.dimensionality());
+        let mut shape = Vec::with_capacity(self.dimensionality());
+        for (&self_start, &self_size, step, &other_start, other_end) in izip!(
+            &self.start,
+            &self.shape,
+            &self.step,
+            array_subset.start(),
+            array_subset.end_exc(),
+        ) {
+            let step = step.get();
+            // The first and one past the last selected element index within `array_subset`
+            let first = other_start.saturating_sub(self_start).div_ceil(step);
+            let last = std::cmp::min(
+                self_size,
+                other_end.saturating_sub(self_start).div_ceil(step),
+            );
+            start.push(self_start + first * step);
+            shape.push(last.saturating_sub(first));
+        }
+        Ok(Self {
+            start,
+            shape,
+            step: self.step.clone(),
+        })
+    }
+
+    /// Return the location of `strided_array_subset`, which must have the same step and be aligned with this strided array subset, as an array subset within the elements of this strided array subset.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the dimensionality of `strided_array_subset` does not match the dimensionality of this strided array subset.
+    pub fn relative_to_strided(
+        &self,
+        strided_array_subset: &Self,
+    ) -> Result<ArraySubset, IncompatibleDimensionalityError> {
+        if strided_array_subset.dimensionality() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                strided_array_subset.dimensionality(),
+                self.dimensionality(),
+            ));
+        }
+        let start = izip!(strided_array_subset.start(), &self.start, &self.step)
+            .map(|(other_start, start, step)| other_start.saturating_sub(*start) / step.get())
+            .collect();
+        Ok(unsafe {
+            ArraySubset::new_with_start_shape_unchecked(
+                start,
+                strided_array_subset.shape().to_vec(),
+            )
+        })
+    }
+
+    /// Return the strided array subset relative to `start`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleDimensionalityError`] if the length of `start` does not match the dimensionality of this strided array subset.
+    pub fn relative_to(&self, start: &[u64]) -> Result<Self, IncompatibleDimensionalityError> {
+        if start.len() != self.dimensionality() {
+            return Err(IncompatibleDimensionalityError::new(
+                start.len(),
+                self.dimensionality(),
+            ));
+        }
+        Ok(Self {
+            start: std::iter::zip(&self.start, start)
+                .map(|(a, b)| a - b)
+                .collect(),
+            shape: self.shape.clone(),
+            step: self.step.clone(),
+        })
+    }
+
+    /// Return the bytes of the strided array subset from an array with shape `array_shape` and `element_size`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleArraySubsetAndShapeError`] if the strided array subset is not within the bounds of `array_shape` or the length of `bytes` does not match `array_shape` and `element_size`.
+    pub fn extract_bytes(
+        &self,
+        bytes: &[u8],
+        array_shape: &[u64],
+        element_size: usize,
+    ) -> Result<Vec<u8>, IncompatibleArraySubsetAndShapeError> {
+        self.validate(bytes.len(), array_shape, element_size)?;
+        let mut bytes_subset = Vec::with_capacity(self.num_elements_usize() * element_size);
+        self.for_each_element_offset(array_shape, |array_index| {
+            let byte_offset = array_index * element_size;
+            bytes_subset.extend_from_slice(&bytes[byte_offset..byte_offset + element_size]);
+        });
+        Ok(bytes_subset)
+    }
+
+    /// Store `bytes_subset` of the strided array subset into the bytes of an array (`bytes_array`) with shape `array_shape` and `element_size`.
+    ///
+    /// # Errors
+    /// Returns [`IncompatibleArraySubsetAndShapeError`] if the strided array subset is not within the bounds of `array_shape`, the length of `bytes_array` does not match `array_shape` and `element_size`, or the length of `bytes_subset` does not match the strided array subset and `element_size`.
+    pub fn store_bytes(
+        &self,
+        bytes_subset: &[u8],
+        bytes_array: &mut [u8],
+        array_shape: &[u64],
+        element_size: usize,
+    ) -> Result<(), IncompatibleArraySubsetAndShapeError> {
+        self.validate(bytes_array.len(), array_shape, element_size)?;
+        if bytes_subset.len() as u64 != self.num_elements() * element_size as u64 {
+            return Err(IncompatibleArraySubsetAndShapeError::new(
+                self.bounding_subset(),
+                array_shape.to_vec(),
+            ));
+        }
+        let mut offset = 0;
+        self.for_each_element_offset(array_shape, |array_index| {
+            let byte_offset = array_index * element_size;
+            bytes_array[byte_offset..byte_offset + element_size]
+                .copy_from_slice(&bytes_subset[offset..offset + element_size]);
+            offset += element_size;
+        });
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        num_bytes: usize,
+        array_shape: &[u64],
+        element_size: usize,
+    ) -> Result<(), IncompatibleArraySubsetAndShapeError> {
+        if self.inbounds(array_shape)
+            && num_bytes as u64 == array_shape.iter().product::<u64>() * element_size as u64
+        {
+            Ok(())
+        } else {
+            Err(IncompatibleArraySubsetAndShapeError::new(
+                self.bounding_subset(),
+                array_shape.to_vec(),
+            ))
+        }
+    }
+
+    /// Call `f` with the linearised index of each element of the strided array subset within an array with shape `array_shape` in C order.
+    fn for_each_element_offset(&self, array_shape: &[u64], mut f: impl FnMut(usize)) {
+        if self.is_empty() {
+            return;
+        }
+        let Some((&inner_size, outer_shape)) = self.shape.split_last() else {
+            // A zero-dimensional subset has one element
+            f(0);
+            return;
+        };
+        let inner_step = usize::try_from(self.step[outer_shape.len()].get()).unwrap();
+        let inner_size = usize::try_from(inner_size).unwrap();
+        let outer = ArraySubset::new_with_shape(outer_shape.to_vec());
+        let mut indices = self.start.clone();
+        for outer_indices in &outer.indices() {
+            for (index, outer_index, start, step) in
+                izip!(&mut indices, &outer_indices, &self.start, &self.step)
+            {
+                *index = start + outer_index * step.get();
+            }
+            let row_index = usize::try_from(ravel_indices(&indices, array_shape)).unwrap();
+            for i in 0..inner_size {
+                f(row_index + i * inner_step);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nz(step: &[u64]) -> Vec<NonZeroU64> {
+        step.iter()
+            .map(|step| NonZeroU64::new(*step).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn strided_array_subset() {
+        let array_subset = ArraySubset::new_with_ranges(&[1..8, 0..8]);
+        assert!(array_subset.with_step(&nz(&[2])).is_err());
+        let strided = array_subset.with_step(&nz(&[2, 3])).unwrap();
+        assert_eq!(strided.start(), &[1, 0]);
+        assert_eq!(strided.shape(), &[4, 3]);
+        assert_eq!(strided.num_elements(), 12);
+        assert!(!strided.is_contiguous());
+        assert_eq!(
+            strided.bounding_subset(),
+            ArraySubset::new_with_ranges(&[1..8, 0..7])
+        );
+        assert!(strided.inbounds(&[8, 7]));
+        assert!(!strided.inbounds(&[8, 6]));
+        assert_eq!(
+            StridedArraySubset::from(array_subset.clone()),
+            array_subset.with_step(&nz(&[1, 1])).unwrap()
+        );
+        assert!(
+            StridedArraySubset::new_with_start_shape_step(vec![0], vec![1, 1], nz(&[1])).is_err()
+        );
+
+        // Rows 3, 5 and columns 3, 6 are within [2..6, 2..8]
+        let overlap = strided
+            .overlap(&ArraySubset::new_with_ranges(&[2..6, 2..8]))
+            .unwrap();
+        assert_eq!(overlap.start(), &[3, 3]);
+        assert_eq!(overlap.shape(), &[2, 2]);
+        assert_eq!(
+            strided.relative_to_strided(&overlap).unwrap(),
+            ArraySubset::new_with_ranges(&[1..3, 1..3])
+        );
+        assert!(strided
+            .overlap(&ArraySubset::new_with_ranges(&[2..3, 0..8]))
+            .unwrap()
+            .is_empty());
+        assert_eq!(overlap.relative_to(&[2, 2]).unwrap().start(), &[1, 1]);
+    }
+
+    #[test]
+    fn strided_array_subset_bytes() {
+        let bytes: Vec<u8> = (0..20).collect();
+        let strided = ArraySubset::new_with_ranges(&[0..4, 1..5])
+            .with_step(&nz(&[2, 2]))
+            .unwrap();
+        let extracted = strided.extract_bytes(&bytes, &[4, 5], 1).unwrap();
+        assert_eq!(extracted, vec![1, 3, 11, 13]);
+        assert!(strided.extract_bytes(&bytes, &[4, 4], 1).is_err());
+
+        let mut bytes_array = vec![0u8; 20];
+        strided
+            .store_bytes(&extracted, &mut bytes_array, &[4, 5], 1)
+            .unwrap();
+        assert_eq!(
+            bytes_array,
+            vec![0, 1, 0, 3, 0, 0, 0, 0, 0, 0, 0, 11, 0, 13, 0, 0, 0, 0, 0, 0]
+        );
+        assert!(strided
+            .store_bytes(&extracted[1..], &mut bytes_array, &[4, 5], 1)
+            .is_err());
+
+        let scalar = ArraySubset::new_with_shape(vec![]).with_step(&[]).unwrap();
+        assert_eq!(scalar.extract_bytes(&[7, 8], &[], 2).unwrap(), vec![7, 8]);
+    }
+}
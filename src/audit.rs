@@ -0,0 +1,166 @@
+//! Auditing of potentially lossy or destructive operations.
+//!
+//! An [`AuditHook`] installed with [`set_audit_hook`] is called with an [`AuditEvent`] whenever zarrs performs an operation that may lose data.
+//! This enables operators to log or alert on such operations in shared environments.
+//!
+//! The hook is called synchronously on the thread performing the operation, so it should be fast and must not install or remove audit hooks.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! zarrs::audit::set_audit_hook(Some(Arc::new(|event: &zarrs::audit::AuditEvent| {
+//!     eprintln!("zarrs audit: {event}");
+//! })));
+//! # zarrs::audit::set_audit_hook(None);
+//! ```
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use derive_more::Display;
+
+use crate::{
+    array::FillValueMetadata,
+    storage::{StoreKey, StorePrefix},
+};
+
+/// A potentially lossy or destructive operation.
+#[derive(Debug, Display)]
+#[non_exhaustive]
+pub enum AuditEvent<'a> {
+    /// All keys with a prefix are being erased, e.g. by [`erase_node`](crate::storage::erase_node).
+    #[display(fmt = "erase prefix {_0}")]
+    ErasePrefix(&'a StorePrefix),
+    /// Existing metadata is being overwritten.
+    ///
+    /// This is only detected by [`store_metadata_opt`](crate::storage::store_metadata_opt), as it requires readable storage.
+    #[display(fmt = "overwrite metadata {_0}")]
+    MetadataOverwrite(&'a StoreKey),
+    /// The fill value of existing array metadata is being changed.
+    ///
+    /// Chunks that are not stored are interpreted with the new fill value.
+    /// This is only detected by [`store_metadata_opt`](crate::storage::store_metadata_opt), as it requires readable storage.
+    #[display(fmt = "change fill value of {key} from {old} to {new}")]
+    FillValueChange {
+        /// The metadata key.
+        key: &'a StoreKey,
+        /// The existing fill value.
+        old: &'a FillValueMetadata,
+        /// The new fill value.
+        new: &'a FillValueMetadata,
+    },
+    /// A codec is encoding with a lossy transformation.
+    #[display(fmt = "lossy encode with codec {codec}")]
+    LossyEncode {
+        /// The codec identifier.
+        codec: &'a str,
+    },
+}
+
+/// A callback that receives [`AuditEvent`]s.
+pub type AuditHook = Arc<dyn Fn(&AuditEvent) + Send + Sync>;
+
+static AUDIT_HOOK: RwLock<Option<AuditHook>> = RwLock::new(None);
+
+/// Install `hook` as the global audit hook, or remove it if `hook` is [`None`].
+///
+/// Returns the previously installed hook.
+pub fn set_audit_hook(hook: Option<AuditHook>) -> Option<AuditHook> {
+    let mut audit_hook = AUDIT_HOOK.write().unwrap_or_else(PoisonError::into_inner);
+    std::mem::replace(&mut audit_hook, hook)
+}
+
+/// Returns the global audit hook.
+fn audit_hook() -> Option<AuditHook> {
+    AUDIT_HOOK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Returns true if an audit hook is installed.
+pub(crate) fn audit_enabled() -> bool {
+    AUDIT_HOOK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_some()
+}
+
+/// Pass `event` to the global audit hook, if installed.
+pub(crate) fn audit(event: &AuditEvent) {
+    if let Some(hook) = audit_hook() {
+        hook(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        node::NodeMetadata,
+        storage::{erase_node, store::MemoryStore, store_metadata_opt, MetadataWriteOptions},
+    };
+
+    use super::*;
+
+    #[test]
+    fn audit_hook_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let hook_events = events.clone();
+        set_audit_hook(Some(Arc::new(move |event: &AuditEvent| {
+            let event = event.to_string();
+            if event.contains("audit_hook_events") {
+                hook_events.lock().unwrap().push(event);
+            }
+        })));
+
+        let store = Arc::new(MemoryStore::new());
+        let path = "/audit_hook_events";
+        let mut builder = ArrayBuilder::new(
+            vec![4],
+            DataType::UInt8,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0u8),
+        );
+        let array = builder.build(store.clone(), path).unwrap();
+        let options = MetadataWriteOptions::default();
+        let node_path = path.try_into().unwrap();
+        store_metadata_opt(
+            &*store,
+            &node_path,
+            &NodeMetadata::Array(array.metadata()),
+            &options,
+        )
+        .unwrap();
+        store_metadata_opt(
+            &*store,
+            &node_path,
+            &NodeMetadata::Array(array.metadata()),
+            &options,
+        )
+        .unwrap();
+        let array = builder
+            .fill_value(FillValue::from(1u8))
+            .build(store.clone(), path)
+            .unwrap();
+        store_metadata_opt(
+            &*store,
+            &node_path,
+            &NodeMetadata::Array(array.metadata()),
+            &options,
+        )
+        .unwrap();
+        erase_node(&*store, &node_path).unwrap();
+        set_audit_hook(None);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                "overwrite metadata audit_hook_events/zarr.json",
+                "overwrite metadata audit_hook_events/zarr.json",
+                "change fill value of audit_hook_events/zarr.json from 0 to 1",
+                "erase prefix audit_hook_events/",
+            ]
+        );
+    }
+}
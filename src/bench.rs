@@ -0,0 +1,297 @@
+//! Codec chain benchmarking.
+//!
+//! A [`CodecBenchmark`] measures the encode and decode throughput and the encoded size of a set of candidate [`CodecChain`]s on sample chunks produced by a user supplied generator.
+//! The resulting [`CodecBenchmarkReport`] can be used to choose codecs empirically for a given kind of data.
+//!
+//! ```
+//! # use zarrs::array::{codec::{BytesCodec, CodecChain}, ChunkRepresentation, DataType, FillValue};
+//! use zarrs::bench::CodecBenchmark;
+//!
+//! let representation =
+//!     ChunkRepresentation::new(vec![64.try_into()?; 2], DataType::UInt16, FillValue::from(0u16))?;
+//! let report = CodecBenchmark::new(representation)
+//!     .candidate(
+//!         "bytes",
+//!         CodecChain::new(vec![], Box::new(BytesCodec::default()), vec![]),
+//!     )
+//!     .samples(2)
+//!     .run(|_sample, representation| vec![0; representation.size() as usize])?;
+//! assert_eq!(report.results()[0].compression_ratio(), 1.0);
+//! println!("{report}");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::array::{
+    codec::{ArrayCodecTraits, CodecChain, CodecError, CodecOptions},
+    ChunkRepresentation,
+};
+
+/// A codec chain benchmark.
+#[derive(Debug, Clone)]
+pub struct CodecBenchmark {
+    representation: ChunkRepresentation,
+    candidates: Vec<(String, CodecChain)>,
+    samples: usize,
+    repetitions: usize,
+    options: CodecOptions,
+}
+
+impl CodecBenchmark {
+    /// Create a new codec chain benchmark for chunks with `representation`.
+    ///
+    /// By default, there are no candidates, 4 samples, and 1 repetition.
+    #[must_use]
+    pub fn new(representation: ChunkRepresentation) -> Self {
+        Self {
+            representation,
+            candidates: Vec::new(),
+            samples: 4,
+            repetitions: 1,
+            options: CodecOptions::default(),
+        }
+    }
+
+    /// Add a candidate `codec_chain` identified by `name`.
+    pub fn candidate(&mut self, name: impl Into<String>, codec_chain: CodecChain) -> &mut Self {
+        self.candidates.push((name.into(), codec_chain));
+        self
+    }
+
+    /// Set the number of sample chunks to generate.
+    pub fn samples(&mut self, samples: usize) -> &mut Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Set the number of times each sample chunk is encoded and decoded.
+    pub fn repetitions(&mut self, repetitions: usize) -> &mut Self {
+        self.repetitions = repetitions;
+        self
+    }
+
+    /// Set the codec options.
+    pub fn codec_options(&mut self, options: CodecOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+
+    /// Run the benchmark.
+    ///
+    /// The `generator` is called with the sample index and the chunk representation and must return the decoded bytes of a sample chunk.
+    /// Every candidate is benchmarked on the same samples.
+    ///
+    /// # Errors
+    /// Returns a [`CodecError`] if a generated sample has an unexpected size or a candidate fails to encode or decode a sample.
+    pub fn run<F>(&self, mut generator: F) -> Result<CodecBenchmarkReport, CodecError>
+    where
+        F: FnMut(usize, &ChunkRepresentation) -> Vec<u8>,
+    {
+        let samples: Vec<Vec<u8>> = (0..self.samples)
+            .map(|sample| generator(sample, &self.representation))
+            .collect();
+        if let Some(sample) = samples
+            .iter()
+            .find(|sample| sample.len() as u64 != self.representation.size())
+        {
+            return Err(CodecError::UnexpectedChunkDecodedSize(
+                sample.len(),
+                self.representation.size(),
+            ));
+        }
+
+        let results = self
+            .candidates
+            .iter()
+            .map(|(name, codec_chain)| self.run_candidate(name, codec_chain, &samples))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CodecBenchmarkReport { results })
+    }
+
+    fn run_candidate(
+        &self,
+        name: &str,
+        codec_chain: &CodecChain,
+        samples: &[Vec<u8>],
+    ) -> Result<CodecBenchmarkResult, CodecError> {
+        let mut result = CodecBenchmarkResult {
+            name: name.to_string(),
+            decoded_size: 0,
+            encoded_size: 0,
+            encode_duration: Duration::ZERO,
+            decode_duration: Duration::ZERO,
+            lossless: true,
+        };
+        for sample in samples {
+            for _ in 0..self.repetitions {
+                let start = Instant::now();
+                let encoded =
+                    codec_chain.encode(sample.into(), &self.representation, &self.options)?;
+                result.encode_duration += start.elapsed();
+                result.decoded_size += sample.len() as u64;
+                result.encoded_size += encoded.len() as u64;
+
+                let start = Instant::now();
+                let decoded = codec_chain.decode(encoded, &self.representation, &self.options)?;
+                result.decode_duration += start.elapsed();
+                result.lossless &= &decoded == sample;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// The benchmark result of a codec chain candidate.
+///
+/// Sizes and durations are totals over all samples and repetitions.
+#[derive(Debug, Clone)]
+pub struct CodecBenchmarkResult {
+    /// The name of the candidate.
+    pub name: String,
+    /// The total decoded size in bytes.
+    pub decoded_size: u64,
+    /// The total encoded size in bytes.
+    pub encoded_size: u64,
+    /// The total encode duration.
+    pub encode_duration: Duration,
+    /// The total decode duration.
+    pub decode_duration: Duration,
+    /// True if every decoded sample was identical to its input.
+    pub lossless: bool,
+}
+
+impl CodecBenchmarkResult {
+    /// Return the compression ratio (decoded size / encoded size).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compression_ratio(&self) -> f64 {
+        self.decoded_size as f64 / self.encoded_size as f64
+    }
+
+    /// Return the encode throughput in decoded bytes per second.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn encode_throughput(&self) -> f64 {
+        self.decoded_size as f64 / self.encode_duration.as_secs_f64()
+    }
+
+    /// Return the decode throughput in decoded bytes per second.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn decode_throughput(&self) -> f64 {
+        self.decoded_size as f64 / self.decode_duration.as_secs_f64()
+    }
+}
+
+/// A codec chain benchmark report.
+#[derive(Debug, Clone)]
+pub struct CodecBenchmarkReport {
+    results: Vec<CodecBenchmarkResult>,
+}
+
+impl CodecBenchmarkReport {
+    /// Return the results of each candidate, in the order they were added.
+    #[must_use]
+    pub fn results(&self) -> &[CodecBenchmarkResult] {
+        &self.results
+    }
+
+    /// Return the lossless candidate with the smallest encoded size.
+    #[must_use]
+    pub fn smallest(&self) -> Option<&CodecBenchmarkResult> {
+        self.results
+            .iter()
+            .filter(|result| result.lossless)
+            .min_by_key(|result| result.encoded_size)
+    }
+
+    /// Return the lossless candidate with the shortest decode duration.
+    #[must_use]
+    pub fn fastest_decode(&self) -> Option<&CodecBenchmarkResult> {
+        self.results
+            .iter()
+            .filter(|result| result.lossless)
+            .min_by_key(|result| result.decode_duration)
+    }
+
+    /// Return the lossless candidate with the shortest encode duration.
+    #[must_use]
+    pub fn fastest_encode(&self) -> Option<&CodecBenchmarkResult> {
+        self.results
+            .iter()
+            .filter(|result| result.lossless)
+            .min_by_key(|result| result.encode_duration)
+    }
+}
+
+impl core::fmt::Display for CodecBenchmarkReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:>8} {:>14} {:>14} {:>8}",
+            "codec chain", "ratio", "encode (MB/s)", "decode (MB/s)", "lossless"
+        )?;
+        for result in &self.results {
+            writeln!(
+                f,
+                "{:<24} {:>8.3} {:>14.1} {:>14.1} {:>8}",
+                result.name,
+                result.compression_ratio(),
+                result.encode_throughput() / 1e6,
+                result.decode_throughput() / 1e6,
+                result.lossless
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::{codec::BytesCodec, DataType, FillValue};
+
+    use super::*;
+
+    #[test]
+    fn codec_benchmark() {
+        let representation = ChunkRepresentation::new(
+            vec![16.try_into().unwrap(); 2],
+            DataType::UInt16,
+            FillValue::from(0u16),
+        )
+        .unwrap();
+        let report = CodecBenchmark::new(representation.clone())
+            .candidate(
+                "little",
+                CodecChain::new(vec![], Box::new(BytesCodec::little()), vec![]),
+            )
+            .candidate(
+                "big",
+                CodecChain::new(vec![], Box::new(BytesCodec::big()), vec![]),
+            )
+            .samples(3)
+            .repetitions(2)
+            .run(|sample, representation| {
+                #[allow(clippy::cast_possible_truncation)]
+                (0..representation.size())
+                    .map(|i| (i as usize + sample) as u8)
+                    .collect()
+            })
+            .unwrap();
+        assert_eq!(report.results().len(), 2);
+        for result in report.results() {
+            assert_eq!(result.decoded_size, 3 * 2 * 512);
+            assert_eq!(result.encoded_size, 3 * 2 * 512);
+            assert!(result.lossless);
+            assert!((result.compression_ratio() - 1.0).abs() < f64::EPSILON);
+        }
+        assert_eq!(report.smallest().unwrap().name, "little");
+        assert!(report.to_string().contains("big"));
+
+        // Samples with an unexpected size
+        assert!(CodecBenchmark::new(representation)
+            .run(|_, _| vec![0; 1])
+            .is_err());
+    }
+}
@@ -1,9 +1,13 @@
 //! Zarrs global configuration options.
 
-use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use rayon::ThreadPool;
+
+#[cfg(feature = "async")]
+use crate::array::codec::AsyncExecutor;
 #[cfg(doc)]
-use crate::array::codec::CodecOptions;
+use crate::{array::codec::CodecOptions, storage::MetadataWriteOptions};
 
 /// Global configuration options for the zarrs crate.
 ///
@@ -45,6 +49,61 @@ use crate::array::codec::CodecOptions;
 /// For example, `array_store_chunks` will concurrently encode and store up to four chunks at a time by default.
 /// The concurrency of internal codecs is adjusted to accomodate for the chunk concurrency in accordance with the concurrent target set in the [`CodecOptions`] parameter of an encode or decode method.
 ///
+/// ## Chunk Prefetch
+/// > default: `0`
+///
+/// [`CodecOptions::chunk_prefetch()`] defaults to [`Config::chunk_prefetch()`].
+///
+/// For retrieval of array subsets or chunks involving multiple chunks, this is the number of encoded chunks fetched from the store ahead of decoding.
+/// If non-zero, chunks fully within the array subset are fetched concurrently in a bounded pipeline that overlaps storage I/O with decoding.
+/// The number of concurrent fetches is then independent of the [codec concurrent target](#codec-concurrent-target), which otherwise limits the number of chunks in flight.
+/// This is beneficial for latency-bound stores (e.g. HTTP or object stores), where many round trips can be in flight at once.
+/// Synchronous retrieval fetches chunks on dedicated threads, so that fetches do not occupy the [thread pool](#thread-pool) that decodes them.
+/// Chunks that partially intersect the array subset are still retrieved with a partial decoder.
+/// Prefetching is disabled if the chunk prefetch is set to zero.
+///
+/// ## Fill Value On Decode Error
+/// > default: [`false`]
+///
+/// [`CodecOptions::fill_value_on_decode_error()`] defaults to [`Config::fill_value_on_decode_error()`].
+///
+/// If `true`, a chunk that fails to decode (e.g. it is corrupt or has an invalid checksum) is retrieved as if it were missing, i.e. filled with the fill value.
+/// If `false`, the retrieval fails with the codec error.
+/// Storage errors are never replaced with the fill value, except for invalid byte ranges caused by a truncated chunk.
+///
+/// ## Thread Pool
+/// > default: [`None`]
+///
+/// [`CodecOptions::thread_pool()`] defaults to [`Config::thread_pool()`].
+///
+/// The [`rayon`] thread pool that parallel array operations and codecs run in.
+/// If [`None`], they run in the global [`rayon`] thread pool.
+/// A dedicated thread pool prevents zarrs from interfering with (and being slowed by) an application's own use of the global thread pool.
+/// The [codec concurrent target](#codec-concurrent-target) should usually match the number of threads in the thread pool.
+///
+/// ## Async Executor
+/// > default: [`None`]
+///
+/// [`CodecOptions::async_executor()`] defaults to [`Config::async_executor()`].
+///
+/// The [`AsyncExecutor`](crate::array::codec::AsyncExecutor) that async array operations encode and decode chunks with.
+/// If [`None`], chunks are encoded and decoded on the thread polling the operation, which can stall other tasks of the asynchronous runtime.
+/// Requires the `async` feature.
+///
+/// ## Deterministic Encode
+/// > default: [`false`]
+///
+/// [`CodecOptions::deterministic_encode()`] and [`MetadataWriteOptions::deterministic_encode()`] default to [`Config::deterministic_encode()`].
+///
+/// If `true`, encoding produces byte-identical output for the same input across runs and machines, so that checksums of produced stores are reproducible.
+/// In particular:
+///  - the inner chunks of a shard are written in chunk order rather than in the order their (concurrent) encoding completes,
+///  - codecs that support multithreaded compression (e.g. `zstd`) compress with a single thread, and
+///  - the keys of the attributes in array and group metadata are written in sorted order, regardless of their insertion order.
+///
+/// Encoded output may still differ between versions of zarrs or its compression libraries.
+/// This may reduce encoding performance of sharded arrays, since the size of each shard can no longer be bounded before encoding.
+///
 /// ## Experimental Codec Store Metadata If Encode Only
 /// > default: [`false`]
 ///
@@ -53,11 +112,18 @@ use crate::array::codec::CodecOptions;
 /// This enables arrays to be consumed by other zarr3 implementations that do not support the experimental codec.
 /// Currently, this options only affects the `bitround` codec.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     validate_checksums: bool,
     store_empty_chunks: bool,
     codec_concurrent_target: usize,
     chunk_concurrent_minimum: usize,
+    chunk_prefetch: usize,
+    fill_value_on_decode_error: bool,
+    thread_pool: Option<Arc<ThreadPool>>,
+    #[cfg(feature = "async")]
+    async_executor: Option<AsyncExecutor>,
+    deterministic_encode: bool,
     experimental_codec_store_metadata_if_encode_only: bool,
 }
 
@@ -73,6 +139,12 @@ impl Default for Config {
                 * concurrency_multiply
                 + concurrency_add,
             chunk_concurrent_minimum: 4,
+            chunk_prefetch: 0,
+            fill_value_on_decode_error: false,
+            thread_pool: None,
+            #[cfg(feature = "async")]
+            async_executor: None,
+            deterministic_encode: false,
             experimental_codec_store_metadata_if_encode_only: false,
         }
     }
@@ -123,6 +195,63 @@ impl Config {
         self.chunk_concurrent_minimum = concurrent_minimum;
     }
 
+    /// Get the [chunk prefetch](#chunk-prefetch) configuration.
+    #[must_use]
+    pub fn chunk_prefetch(&self) -> usize {
+        self.chunk_prefetch
+    }
+
+    /// Set the [chunk prefetch](#chunk-prefetch) configuration.
+    pub fn set_chunk_prefetch(&mut self, chunk_prefetch: usize) {
+        self.chunk_prefetch = chunk_prefetch;
+    }
+
+    /// Get the [fill value on decode error](#fill-value-on-decode-error) configuration.
+    #[must_use]
+    pub fn fill_value_on_decode_error(&self) -> bool {
+        self.fill_value_on_decode_error
+    }
+
+    /// Set the [fill value on decode error](#fill-value-on-decode-error) configuration.
+    pub fn set_fill_value_on_decode_error(&mut self, fill_value_on_decode_error: bool) {
+        self.fill_value_on_decode_error = fill_value_on_decode_error;
+    }
+
+    /// Get the [thread pool](#thread-pool) configuration.
+    #[must_use]
+    pub fn thread_pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
+    /// Set the [thread pool](#thread-pool) configuration.
+    pub fn set_thread_pool(&mut self, thread_pool: Option<Arc<ThreadPool>>) {
+        self.thread_pool = thread_pool;
+    }
+
+    /// Get the [async executor](#async-executor) configuration.
+    #[cfg(feature = "async")]
+    #[must_use]
+    pub fn async_executor(&self) -> Option<&AsyncExecutor> {
+        self.async_executor.as_ref()
+    }
+
+    /// Set the [async executor](#async-executor) configuration.
+    #[cfg(feature = "async")]
+    pub fn set_async_executor(&mut self, async_executor: Option<AsyncExecutor>) {
+        self.async_executor = async_executor;
+    }
+
+    /// Get the [deterministic encode](#deterministic-encode) configuration.
+    #[must_use]
+    pub fn deterministic_encode(&self) -> bool {
+        self.deterministic_encode
+    }
+
+    /// Set the [deterministic encode](#deterministic-encode) configuration.
+    pub fn set_deterministic_encode(&mut self, deterministic_encode: bool) {
+        self.deterministic_encode = deterministic_encode;
+    }
+
     /// Get the [experimental codec store metadata if encode only](#experimental-codec-store-metadata-if-encode-only) configuration.
     #[must_use]
     pub fn experimental_codec_store_metadata_if_encode_only(&self) -> bool {
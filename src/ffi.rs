@@ -0,0 +1,800 @@
+//! A C API.
+//!
+//! This module exposes a stable C ABI for opening and creating arrays and groups, reading and writing array subsets, and listing hierarchies.
+//! It is intended for C, C++, and Fortran codes (e.g. simulations) that need to write Zarr V3 data directly.
+//! The C declarations are in [`include/zarrs.h`](https://github.com/LDeakin/zarrs/blob/main/include/zarrs.h).
+//!
+//! Enable the `ffi` feature and build `zarrs` as a shared or static library with
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! cargo rustc --release --lib --features ffi --crate-type staticlib
+//! ```
+//! or depend on `zarrs` with the `ffi` feature from a `cdylib` crate.
+//!
+//! ### Conventions
+//!  - Storage and arrays are opaque handles ([`ZarrsStorage`] and [`ZarrsArray`]) created by `zarrs_create_*`/`zarrs_open_*` functions and destroyed with `zarrs_destroy_*` functions.
+//!  - Every fallible function returns a [`ZarrsResult`]. On failure, [`zarrs_last_error`] returns a description of the error.
+//!  - Strings returned by zarrs are allocated by zarrs and must be freed with [`zarrs_free_string`].
+//!  - Array subset bytes are in C order with the native endianness, as for [`Array::retrieve_array_subset`].
+//!
+//! ```c
+//! ZarrsStorage storage = NULL;
+//! ZarrsArray array = NULL;
+//! if (zarrs_create_storage_filesystem("/path/to/hierarchy.zarr", &storage) != ZARRS_SUCCESS ||
+//!     zarrs_open_array(storage, "/array", &array) != ZARRS_SUCCESS) {
+//!     fprintf(stderr, "%s\n", zarrs_last_error());
+//! }
+//! uint64_t start[2] = {0, 0};
+//! uint64_t shape[2] = {4, 4};
+//! float elements[16];
+//! zarrs_array_retrieve_subset(array, 2, start, shape, sizeof(elements), (uint8_t*)elements);
+//! zarrs_destroy_array(array);
+//! zarrs_destroy_storage(storage);
+//! ```
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use thiserror::Error;
+
+use crate::{
+    array::{Array, ArrayCreateError, ArrayError, ArrayMetadata},
+    array_subset::ArraySubset,
+    group::{Group, GroupCreateError, GroupMetadata, GroupMetadataV3},
+    node::{Node, NodeCreateError, NodeMetadata},
+    storage::{
+        store::{FilesystemStore, FilesystemStoreCreateError},
+        ReadableWritableListableStorage, ReadableWritableListableStorageTraits, StorageError,
+    },
+};
+
+/// The result of a C API function.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZarrsResult {
+    /// Success.
+    ZARRS_SUCCESS = 0,
+    /// A required pointer argument is null.
+    ZARRS_ERROR_NULL_PTR = -1,
+    /// A storage error.
+    ZARRS_ERROR_STORAGE = -2,
+    /// An array error.
+    ZARRS_ERROR_ARRAY = -3,
+    /// A group error.
+    ZARRS_ERROR_GROUP = -4,
+    /// A node error.
+    ZARRS_ERROR_NODE = -5,
+    /// A buffer does not have the expected length.
+    ZARRS_ERROR_BUFFER_LENGTH = -6,
+    /// A dimensionality argument does not match the array dimensionality.
+    ZARRS_ERROR_INVALID_DIMENSIONALITY = -7,
+    /// A string argument is not valid UTF-8 or metadata is invalid JSON.
+    ZARRS_ERROR_INVALID_ARGUMENT = -8,
+    /// zarrs panicked.
+    ZARRS_ERROR_PANIC = -9,
+}
+
+/// The type of a node in a hierarchy.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZarrsNodeType {
+    /// An array.
+    ZARRS_NODE_TYPE_ARRAY = 0,
+    /// A group.
+    ZARRS_NODE_TYPE_GROUP = 1,
+}
+
+/// An opaque handle to readable, writable, and listable storage.
+#[allow(non_camel_case_types)]
+pub struct ZarrsStorage_T(ReadableWritableListableStorage);
+
+/// A storage handle.
+pub type ZarrsStorage = *mut ZarrsStorage_T;
+
+/// An opaque handle to an array.
+#[allow(non_camel_case_types)]
+pub struct ZarrsArray_T(Array<dyn ReadableWritableListableStorageTraits>);
+
+/// An array handle.
+pub type ZarrsArray = *mut ZarrsArray_T;
+
+/// A C API error.
+#[derive(Debug, Error)]
+enum FfiError {
+    #[error("{0} is a null pointer")]
+    NullPointer(&'static str),
+    #[error("{0} is not a valid UTF-8 string")]
+    InvalidString(&'static str),
+    #[error("invalid metadata: {0}")]
+    InvalidMetadata(#[from] serde_json::Error),
+    #[error(transparent)]
+    FilesystemStoreCreateError(#[from] FilesystemStoreCreateError),
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    #[error(transparent)]
+    GroupCreateError(#[from] GroupCreateError),
+    #[error(transparent)]
+    NodeCreateError(#[from] NodeCreateError),
+    #[error("dimensionality {_0} does not match the array dimensionality {_1}")]
+    InvalidDimensionality(usize, usize),
+    #[error("the buffer has {_0} bytes, but {_1} bytes are required")]
+    BufferLength(usize, usize),
+    #[error("the subset size exceeds usize::MAX bytes")]
+    SubsetTooLarge,
+    #[error("zarrs panicked: {0}")]
+    Panic(String),
+}
+
+impl FfiError {
+    fn result(&self) -> ZarrsResult {
+        match self {
+            Self::NullPointer(_) => ZarrsResult::ZARRS_ERROR_NULL_PTR,
+            Self::InvalidString(_) | Self::InvalidMetadata(_) => {
+                ZarrsResult::ZARRS_ERROR_INVALID_ARGUMENT
+            }
+            Self::FilesystemStoreCreateError(_) | Self::StorageError(_) => {
+                ZarrsResult::ZARRS_ERROR_STORAGE
+            }
+            Self::ArrayCreateError(_) | Self::ArrayError(_) => ZarrsResult::ZARRS_ERROR_ARRAY,
+            Self::GroupCreateError(_) => ZarrsResult::ZARRS_ERROR_GROUP,
+            Self::NodeCreateError(_) => ZarrsResult::ZARRS_ERROR_NODE,
+            Self::BufferLength(..) | Self::SubsetTooLarge => ZarrsResult::ZARRS_ERROR_BUFFER_LENGTH,
+            Self::InvalidDimensionality(..) => ZarrsResult::ZARRS_ERROR_INVALID_DIMENSIONALITY,
+            Self::Panic(_) => ZarrsResult::ZARRS_ERROR_PANIC,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Run `f`, recording any error or panic as the last error of this thread.
+fn ffi_call(f: impl FnOnce() -> Result<(), FfiError>) -> ZarrsResult {
+    let result = catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(FfiError::Panic(message))
+    });
+    match result {
+        Ok(()) => ZarrsResult::ZARRS_SUCCESS,
+        Err(err) => {
+            let message = CString::new(err.to_string().replace('\0', "")).unwrap_or_default();
+            LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+            err.result()
+        }
+    }
+}
+
+/// Dereference a non-null pointer argument.
+unsafe fn as_ref<'a, T>(ptr: *const T, name: &'static str) -> Result<&'a T, FfiError> {
+    unsafe { ptr.as_ref() }.ok_or(FfiError::NullPointer(name))
+}
+
+/// Dereference a non-null output pointer argument.
+unsafe fn as_mut<'a, T>(ptr: *mut T, name: &'static str) -> Result<&'a mut T, FfiError> {
+    unsafe { ptr.as_mut() }.ok_or(FfiError::NullPointer(name))
+}
+
+/// Convert a non-null nul-terminated string argument to a [`str`].
+unsafe fn as_str<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        Err(FfiError::NullPointer(name))
+    } else {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(|_| FfiError::InvalidString(name))
+    }
+}
+
+/// Convert a non-null array argument with `len` elements to a slice.
+unsafe fn as_slice<'a, T>(
+    ptr: *const T,
+    len: usize,
+    name: &'static str,
+) -> Result<&'a [T], FfiError> {
+    if ptr.is_null() {
+        Err(FfiError::NullPointer(name))
+    } else {
+        Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// Return a string to the caller, to be freed with [`zarrs_free_string`].
+fn into_c_string(string: &str) -> *mut c_char {
+    CString::new(string.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Return the array subset with `subset_start` and `subset_shape` of `array` and its size in bytes.
+unsafe fn array_subset(
+    array: &Array<dyn ReadableWritableListableStorageTraits>,
+    dimensionality: usize,
+    subset_start: *const u64,
+    subset_shape: *const u64,
+) -> Result<(ArraySubset, usize), FfiError> {
+    if dimensionality != array.dimensionality() {
+        return Err(FfiError::InvalidDimensionality(
+            dimensionality,
+            array.dimensionality(),
+        ));
+    }
+    let start = unsafe { as_slice(subset_start, dimensionality, "subset_start") }?;
+    let shape = unsafe { as_slice(subset_shape, dimensionality, "subset_shape") }?;
+    let subset = ArraySubset::new_with_start_shape(start.to_vec(), shape.to_vec())
+        .map_err(|err| FfiError::ArrayError(err.into()))?;
+    let size = usize::try_from(subset.num_elements())
+        .ok()
+        .and_then(|num_elements| num_elements.checked_mul(array.data_type().size()))
+        .ok_or(FfiError::SubsetTooLarge)?;
+    Ok((subset, size))
+}
+
+/// Return a description of the last error that occurred on the calling thread.
+///
+/// The returned string is owned by zarrs and is valid until the next failing C API call on the calling thread.
+#[no_mangle]
+pub extern "C" fn zarrs_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ptr())
+}
+
+/// Free a string returned by zarrs.
+///
+/// # Safety
+/// `string` must be null or a string returned by zarrs that has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Create a handle to filesystem storage at `path`.
+///
+/// The handle must be destroyed with [`zarrs_destroy_storage`].
+///
+/// # Safety
+/// `path` must be a nul-terminated string and `storage` must point to a [`ZarrsStorage`].
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_create_storage_filesystem(
+    path: *const c_char,
+    storage: *mut ZarrsStorage,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let storage = unsafe { as_mut(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let store: ReadableWritableListableStorage = Arc::new(FilesystemStore::new(path)?);
+        *storage = Box::into_raw(Box::new(ZarrsStorage_T(store)));
+        Ok(())
+    })
+}
+
+/// Destroy a storage handle.
+///
+/// Arrays opened from the storage remain valid.
+///
+/// # Safety
+/// `storage` must be a handle returned by zarrs that has not already been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_destroy_storage(storage: ZarrsStorage) -> ZarrsResult {
+    ffi_call(|| {
+        if storage.is_null() {
+            Err(FfiError::NullPointer("storage"))
+        } else {
+            drop(unsafe { Box::from_raw(storage) });
+            Ok(())
+        }
+    })
+}
+
+/// Open the array at `path` in `storage`.
+///
+/// The handle must be destroyed with [`zarrs_destroy_array`].
+///
+/// # Safety
+/// `storage` must be a valid storage handle, `path` must be a nul-terminated string, and `array` must point to a [`ZarrsArray`].
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_open_array(
+    storage: ZarrsStorage,
+    path: *const c_char,
+    array: *mut ZarrsArray,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let storage = unsafe { as_ref(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let array = unsafe { as_mut(array, "array") }?;
+        let array_new = Array::new(storage.0.clone(), path)?;
+        *array = Box::into_raw(Box::new(ZarrsArray_T(array_new)));
+        Ok(())
+    })
+}
+
+/// Create an array at `path` in `storage` with JSON array `metadata` and write its metadata.
+///
+/// The handle must be destroyed with [`zarrs_destroy_array`].
+///
+/// # Safety
+/// `storage` must be a valid storage handle, `path` and `metadata` must be nul-terminated strings, and `array` must point to a [`ZarrsArray`].
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_create_array(
+    storage: ZarrsStorage,
+    path: *const c_char,
+    metadata: *const c_char,
+    array: *mut ZarrsArray,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let storage = unsafe { as_ref(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let metadata = unsafe { as_str(metadata, "metadata") }?;
+        let array = unsafe { as_mut(array, "array") }?;
+        let metadata: ArrayMetadata = serde_json::from_str(metadata)?;
+        let array_new = Array::new_with_metadata(storage.0.clone(), path, metadata)?;
+        array_new.store_metadata()?;
+        *array = Box::into_raw(Box::new(ZarrsArray_T(array_new)));
+        Ok(())
+    })
+}
+
+/// Destroy an array handle.
+///
+/// # Safety
+/// `array` must be a handle returned by zarrs that has not already been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_destroy_array(array: ZarrsArray) -> ZarrsResult {
+    ffi_call(|| {
+        if array.is_null() {
+            Err(FfiError::NullPointer("array"))
+        } else {
+            drop(unsafe { Box::from_raw(array) });
+            Ok(())
+        }
+    })
+}
+
+/// Get the dimensionality of `array`.
+///
+/// # Safety
+/// `array` must be a valid array handle and `dimensionality` must point to a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_get_dimensionality(
+    array: ZarrsArray,
+    dimensionality: *mut usize,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        *unsafe { as_mut(dimensionality, "dimensionality") }? = array.0.dimensionality();
+        Ok(())
+    })
+}
+
+/// Get the shape of `array`.
+///
+/// # Safety
+/// `array` must be a valid array handle and `shape` must point to `dimensionality` elements.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_get_shape(
+    array: ZarrsArray,
+    dimensionality: usize,
+    shape: *mut u64,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        if dimensionality != array.0.dimensionality() {
+            return Err(FfiError::InvalidDimensionality(
+                dimensionality,
+                array.0.dimensionality(),
+            ));
+        }
+        if shape.is_null() {
+            return Err(FfiError::NullPointer("shape"));
+        }
+        let shape = unsafe { std::slice::from_raw_parts_mut(shape, dimensionality) };
+        shape.copy_from_slice(array.0.shape());
+        Ok(())
+    })
+}
+
+/// Get the size in bytes of an element of `array`.
+///
+/// # Safety
+/// `array` must be a valid array handle and `element_size` must point to a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_get_element_size(
+    array: ZarrsArray,
+    element_size: *mut usize,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        *unsafe { as_mut(element_size, "element_size") }? = array.0.data_type().size();
+        Ok(())
+    })
+}
+
+/// Get the JSON metadata of `array`.
+///
+/// The returned string must be freed with [`zarrs_free_string`].
+///
+/// # Safety
+/// `array` must be a valid array handle and `metadata` must point to a `char*`.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_get_metadata(
+    array: ZarrsArray,
+    metadata: *mut *mut c_char,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        let metadata = unsafe { as_mut(metadata, "metadata") }?;
+        *metadata = into_c_string(&serde_json::to_string_pretty(&array.0.metadata())?);
+        Ok(())
+    })
+}
+
+/// Get the size in bytes of an array subset of `array` with `subset_shape`.
+///
+/// # Safety
+/// `array` must be a valid array handle, `subset_shape` must point to `dimensionality` elements, and `subset_size` must point to a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_get_subset_size(
+    array: ZarrsArray,
+    dimensionality: usize,
+    subset_shape: *const u64,
+    subset_size: *mut usize,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        let subset_start = vec![0; dimensionality];
+        let (_, size) = unsafe {
+            array_subset(
+                &array.0,
+                dimensionality,
+                subset_start.as_ptr(),
+                subset_shape,
+            )
+        }?;
+        *unsafe { as_mut(subset_size, "subset_size") }? = size;
+        Ok(())
+    })
+}
+
+/// Retrieve the array subset of `array` with `subset_start` and `subset_shape` into `subset_bytes`.
+///
+/// `subset_bytes_length` must equal the size of the subset (see [`zarrs_array_get_subset_size`]).
+///
+/// # Safety
+/// `array` must be a valid array handle, `subset_start` and `subset_shape` must point to `dimensionality` elements, and `subset_bytes` must point to `subset_bytes_length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_retrieve_subset(
+    array: ZarrsArray,
+    dimensionality: usize,
+    subset_start: *const u64,
+    subset_shape: *const u64,
+    subset_bytes_length: usize,
+    subset_bytes: *mut u8,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        let (subset, size) =
+            unsafe { array_subset(&array.0, dimensionality, subset_start, subset_shape) }?;
+        if subset_bytes_length != size {
+            return Err(FfiError::BufferLength(subset_bytes_length, size));
+        }
+        if subset_bytes.is_null() {
+            return Err(FfiError::NullPointer("subset_bytes"));
+        }
+        let bytes = array.0.retrieve_array_subset(&subset)?;
+        let subset_bytes = unsafe { std::slice::from_raw_parts_mut(subset_bytes, size) };
+        subset_bytes.copy_from_slice(&bytes);
+        Ok(())
+    })
+}
+
+/// Store `subset_bytes` in the array subset of `array` with `subset_start` and `subset_shape`.
+///
+/// `subset_bytes_length` must equal the size of the subset (see [`zarrs_array_get_subset_size`]).
+///
+/// # Safety
+/// `array` must be a valid array handle, `subset_start` and `subset_shape` must point to `dimensionality` elements, and `subset_bytes` must point to `subset_bytes_length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_array_store_subset(
+    array: ZarrsArray,
+    dimensionality: usize,
+    subset_start: *const u64,
+    subset_shape: *const u64,
+    subset_bytes_length: usize,
+    subset_bytes: *const u8,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let array = unsafe { as_ref(array, "array") }?;
+        let (subset, size) =
+            unsafe { array_subset(&array.0, dimensionality, subset_start, subset_shape) }?;
+        if subset_bytes_length != size {
+            return Err(FfiError::BufferLength(subset_bytes_length, size));
+        }
+        let subset_bytes = unsafe { as_slice(subset_bytes, size, "subset_bytes") }?;
+        array.0.store_array_subset(&subset, subset_bytes.to_vec())?;
+        Ok(())
+    })
+}
+
+/// Create a group at `path` in `storage` with JSON group `metadata` and write its metadata.
+///
+/// If `metadata` is null, the group has default metadata.
+///
+/// # Safety
+/// `storage` must be a valid storage handle and `path` and `metadata` (if not null) must be nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_create_group(
+    storage: ZarrsStorage,
+    path: *const c_char,
+    metadata: *const c_char,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let storage = unsafe { as_ref(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let metadata: GroupMetadata = if metadata.is_null() {
+            GroupMetadataV3::default().into()
+        } else {
+            serde_json::from_str(unsafe { as_str(metadata, "metadata") }?)?
+        };
+        Group::new_with_metadata(storage.0.clone(), path, metadata)?.store_metadata()?;
+        Ok(())
+    })
+}
+
+/// Get the type of the node at `path` in `storage`.
+///
+/// A path without metadata is an implicit group.
+///
+/// # Safety
+/// `storage` must be a valid storage handle, `path` must be a nul-terminated string, and `node_type` must point to a [`ZarrsNodeType`].
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_storage_get_node_type(
+    storage: ZarrsStorage,
+    path: *const c_char,
+    node_type: *mut ZarrsNodeType,
+) -> ZarrsResult {
+    ffi_call(|| {
+        let storage = unsafe { as_ref(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let node_type = unsafe { as_mut(node_type, "node_type") }?;
+        let node = Node::new(&*storage.0, path)?;
+        *node_type = match node.metadata() {
+            NodeMetadata::Array(_) => ZarrsNodeType::ZARRS_NODE_TYPE_ARRAY,
+            NodeMetadata::Group(_) => ZarrsNodeType::ZARRS_NODE_TYPE_GROUP,
+        };
+        Ok(())
+    })
+}
+
+/// List the paths of all nodes below the node at `path` in `storage`.
+///
+/// `nodes` is set to a newline separated list of node paths in depth-first order, which must be freed with [`zarrs_free_string`].
+///
+/// # Safety
+/// `storage` must be a valid storage handle, `path` must be a nul-terminated string, and `nodes` must point to a `char*`.
+#[no_mangle]
+pub unsafe extern "C" fn zarrs_storage_list_nodes(
+    storage: ZarrsStorage,
+    path: *const c_char,
+    nodes: *mut *mut c_char,
+) -> ZarrsResult {
+    fn push_children(node: &Node, paths: &mut Vec<String>) {
+        for child in node.children() {
+            paths.push(child.path().as_str().to_string());
+            push_children(child, paths);
+        }
+    }
+
+    ffi_call(|| {
+        let storage = unsafe { as_ref(storage, "storage") }?;
+        let path = unsafe { as_str(path, "path") }?;
+        let nodes = unsafe { as_mut(nodes, "nodes") }?;
+        let node = Node::new(&*storage.0, path)?;
+        let mut paths = Vec::new();
+        push_children(&node, &mut paths);
+        *nodes = into_c_string(&paths.join("\n"));
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c_string(string: &str) -> CString {
+        CString::new(string).unwrap()
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(zarrs_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    unsafe fn take_string(string: *mut c_char) -> String {
+        let rust_string = unsafe { CStr::from_ptr(string) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { zarrs_free_string(string) };
+        rust_string
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn ffi_array_write_read() {
+        let path = tempfile::TempDir::new().unwrap();
+        let path = c_string(path.path().to_str().unwrap());
+        let metadata = c_string(
+            r#"{
+                "zarr_format": 3,
+                "node_type": "array",
+                "shape": [4, 4],
+                "data_type": "uint16",
+                "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": [2, 2]}},
+                "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+                "fill_value": 0,
+                "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}]
+            }"#,
+        );
+        unsafe {
+            let mut storage: ZarrsStorage = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_create_storage_filesystem(path.as_ptr(), &mut storage),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(
+                zarrs_create_group(storage, c_string("/group").as_ptr(), std::ptr::null()),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            let mut array: ZarrsArray = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_create_array(
+                    storage,
+                    c_string("/group/array").as_ptr(),
+                    metadata.as_ptr(),
+                    &mut array
+                ),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+
+            let start = [1u64, 1];
+            let shape = [2u64, 3];
+            let mut size = 0;
+            assert_eq!(
+                zarrs_array_get_subset_size(array, 2, shape.as_ptr(), &mut size),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(size, 2 * 3 * 2);
+            let elements: Vec<u16> = (1..=6).collect();
+            let bytes: Vec<u8> = elements.iter().flat_map(|e| e.to_ne_bytes()).collect();
+            assert_eq!(
+                zarrs_array_store_subset(
+                    array,
+                    2,
+                    start.as_ptr(),
+                    shape.as_ptr(),
+                    bytes.len(),
+                    bytes.as_ptr()
+                ),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(zarrs_destroy_array(array), ZarrsResult::ZARRS_SUCCESS);
+
+            let mut array: ZarrsArray = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_open_array(storage, c_string("/group/array").as_ptr(), &mut array),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            let mut dimensionality = 0;
+            assert_eq!(
+                zarrs_array_get_dimensionality(array, &mut dimensionality),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(dimensionality, 2);
+            let mut array_shape = [0u64; 2];
+            assert_eq!(
+                zarrs_array_get_shape(array, 2, array_shape.as_mut_ptr()),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(array_shape, [4, 4]);
+            let mut element_size = 0;
+            assert_eq!(
+                zarrs_array_get_element_size(array, &mut element_size),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(element_size, 2);
+            let mut metadata_out: *mut c_char = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_array_get_metadata(array, &mut metadata_out),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert!(take_string(metadata_out).contains("\"uint16\""));
+
+            let mut elements_out = [0u16; 4 * 4];
+            assert_eq!(
+                zarrs_array_retrieve_subset(
+                    array,
+                    2,
+                    [0u64, 0].as_ptr(),
+                    [4u64, 4].as_ptr(),
+                    4 * 4 * 2,
+                    elements_out.as_mut_ptr().cast::<u8>()
+                ),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(
+                elements_out,
+                [0, 0, 0, 0, 0, 1, 2, 3, 0, 4, 5, 6, 0, 0, 0, 0]
+            );
+
+            // Errors
+            assert_eq!(
+                zarrs_array_retrieve_subset(
+                    array,
+                    2,
+                    [0u64, 0].as_ptr(),
+                    [4u64, 4].as_ptr(),
+                    4,
+                    elements_out.as_mut_ptr().cast::<u8>()
+                ),
+                ZarrsResult::ZARRS_ERROR_BUFFER_LENGTH
+            );
+            assert_eq!(
+                last_error(),
+                "the buffer has 4 bytes, but 32 bytes are required"
+            );
+            assert_eq!(
+                zarrs_array_get_shape(array, 3, array_shape.as_mut_ptr()),
+                ZarrsResult::ZARRS_ERROR_INVALID_DIMENSIONALITY
+            );
+            assert_eq!(
+                zarrs_array_get_dimensionality(array, std::ptr::null_mut()),
+                ZarrsResult::ZARRS_ERROR_NULL_PTR
+            );
+            assert_eq!(last_error(), "dimensionality is a null pointer");
+            assert_eq!(zarrs_destroy_array(array), ZarrsResult::ZARRS_SUCCESS);
+            let mut array: ZarrsArray = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_open_array(storage, c_string("/missing").as_ptr(), &mut array),
+                ZarrsResult::ZARRS_ERROR_ARRAY
+            );
+            assert!(array.is_null());
+
+            // Hierarchy
+            let mut nodes: *mut c_char = std::ptr::null_mut();
+            assert_eq!(
+                zarrs_storage_list_nodes(storage, c_string("/").as_ptr(), &mut nodes),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(take_string(nodes), "/group\n/group/array");
+            let mut node_type = ZarrsNodeType::ZARRS_NODE_TYPE_GROUP;
+            assert_eq!(
+                zarrs_storage_get_node_type(
+                    storage,
+                    c_string("/group/array").as_ptr(),
+                    &mut node_type
+                ),
+                ZarrsResult::ZARRS_SUCCESS
+            );
+            assert_eq!(node_type, ZarrsNodeType::ZARRS_NODE_TYPE_ARRAY);
+
+            assert_eq!(zarrs_destroy_storage(storage), ZarrsResult::ZARRS_SUCCESS);
+        }
+    }
+}
@@ -19,28 +19,39 @@
 //! ```
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#group-metadata> for more information on group metadata.
 
+#[cfg(feature = "arrow")]
+mod group_arrow;
 mod group_builder;
+mod group_children;
 mod group_metadata;
+mod group_statistics;
 
 use std::sync::Arc;
 
 use derive_more::Display;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 use crate::{
     metadata::{AdditionalFields, UnsupportedAdditionalFieldError},
-    node::{NodePath, NodePathError},
+    node::{NodeMetadata, NodePath, NodePathError},
     storage::{
-        meta_key, ReadableStorageTraits, StorageError, StorageHandle, WritableStorageTraits,
+        get_child_nodes, meta_key, ListableStorageTraits, MetadataWriteError, MetadataWriteOptions,
+        ReadableStorageTraits, ReadableWritableStorageTraits, StorageError, StorageHandle,
+        WritableStorageTraits,
     },
 };
 
 #[cfg(feature = "async")]
 use crate::storage::{AsyncReadableStorageTraits, AsyncWritableStorageTraits};
 
+#[cfg(feature = "arrow")]
+pub use self::group_arrow::RecordBatchError;
+
 pub use self::{
     group_builder::GroupBuilder,
     group_metadata::{GroupMetadata, GroupMetadataV3},
+    group_statistics::{array_storage_statistics, StorageStatistics, STORAGE_STATISTICS_ATTRIBUTE},
 };
 
 /// A group.
@@ -94,6 +105,23 @@ impl<TStorage: ?Sized> Group<TStorage> {
         &self.metadata.attributes
     }
 
+    /// Get the attribute `name` deserialised as `T`.
+    ///
+    /// Returns [`None`] if the attribute does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if the attribute cannot be deserialised as `T`.
+    pub fn attribute<T: DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Result<Option<T>, serde_json::Error> {
+        self.metadata
+            .attributes
+            .get(name)
+            .map(|value| T::deserialize(value))
+            .transpose()
+    }
+
     /// Get additional fields.
     #[must_use]
     pub const fn additional_fields(&self) -> &AdditionalFields {
@@ -106,12 +134,44 @@ impl<TStorage: ?Sized> Group<TStorage> {
         self.metadata.clone().into()
     }
 
+    /// Returns the [`StorageStatistics`] summary in the [`STORAGE_STATISTICS_ATTRIBUTE`] attribute.
+    ///
+    /// Returns [`None`] if the attribute is absent or invalid.
+    /// The summary is written by [`store_storage_statistics`](Group::store_storage_statistics) and may be out of date.
+    #[must_use]
+    pub fn storage_statistics_attribute(&self) -> Option<StorageStatistics> {
+        self.attribute(STORAGE_STATISTICS_ATTRIBUTE).ok().flatten()
+    }
+
     /// Mutably borrow the group attributes.
     #[must_use]
     pub fn attributes_mut(&mut self) -> &mut serde_json::Map<String, serde_json::Value> {
         &mut self.metadata.attributes
     }
 
+    /// Set the attribute `name` to `value` serialised as JSON.
+    ///
+    /// Returns the previous value of the attribute, if any.
+    /// This does **not** write to the store, use [`store_metadata`](Group<WritableStorageTraits>::store_metadata) to write the updated metadata.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if `value` cannot be serialised.
+    pub fn set_attribute<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<Option<serde_json::Value>, serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        Ok(self.metadata.attributes.insert(name.to_string(), value))
+    }
+
+    /// Remove the attribute `name`.
+    ///
+    /// Returns the removed value of the attribute, if any.
+    pub fn remove_attribute(&mut self, name: &str) -> Option<serde_json::Value> {
+        self.metadata.attributes.remove(name)
+    }
+
     /// Mutably borrow the additional fields.
     #[must_use]
     pub fn additional_fields_mut(&mut self) -> &mut AdditionalFields {
@@ -215,6 +275,88 @@ impl<TStorage: ?Sized + WritableStorageTraits + 'static> Group<TStorage> {
     }
 }
 
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + 'static> Group<TStorage> {
+    /// Store metadata with non-default [`MetadataWriteOptions`].
+    ///
+    /// Use this instead of [`store_metadata`](Group::store_metadata) to verify the written metadata and retry failed writes.
+    ///
+    /// # Errors
+    /// Returns a [`MetadataWriteError`] if the final write attempt fails with a storage error or a verification mismatch.
+    pub fn store_metadata_opt(
+        &self,
+        options: &MetadataWriteOptions,
+    ) -> Result<(), MetadataWriteError> {
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        crate::storage::store_metadata_opt(
+            &storage_handle,
+            self.path(),
+            &NodeMetadata::Group(self.metadata()),
+            options,
+        )
+    }
+
+    /// Store metadata if the group does not already have metadata.
+    ///
+    /// Returns true if the metadata was stored, or false if metadata already exists.
+    /// Unlike [`store_metadata`](Group::store_metadata), this does not overwrite metadata created concurrently by another writer if the store supports [`set_if_not_exists`](crate::storage::WritableStorageTraits::set_if_not_exists).
+    /// See [`create_metadata_if_not_exists`](crate::storage::create_metadata_if_not_exists).
+    ///
+    /// # Errors
+    /// Returns [`StorageError`] if there is an underlying store error.
+    pub fn store_metadata_if_not_exists(&self) -> Result<bool, StorageError> {
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        crate::storage::create_metadata_if_not_exists(
+            &storage_handle,
+            self.path(),
+            &NodeMetadata::Group(self.metadata()),
+        )
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> Group<TStorage> {
+    /// Get the [`StorageStatistics`] of all arrays below the group.
+    ///
+    /// This walks the hierarchy below the group and lists the keys of every array.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn storage_statistics(&self) -> Result<StorageStatistics, StorageError> {
+        let nodes = get_child_nodes(&*self.storage, &self.path)?;
+        group_statistics::nodes_storage_statistics(&*self.storage, &nodes, &mut |_, _| Ok(()))
+    }
+}
+
+impl<
+        TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits + ListableStorageTraits + 'static,
+    > Group<TStorage>
+{
+    /// Get the [`StorageStatistics`] of all arrays below the group and store them in group attributes.
+    ///
+    /// The statistics of this group and every descendant group are rolled up from their arrays and written to the [`STORAGE_STATISTICS_ATTRIBUTE`] attribute of each group.
+    /// Implicit descendant groups become explicit groups.
+    /// Read the summary with [`storage_statistics_attribute`](Group::storage_statistics_attribute).
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn store_storage_statistics(&mut self) -> Result<StorageStatistics, StorageError> {
+        let storage_handle = StorageHandle::new(self.storage.clone());
+        let nodes = get_child_nodes(&*self.storage, &self.path)?;
+        let statistics = group_statistics::nodes_storage_statistics(
+            &*self.storage,
+            &nodes,
+            &mut |node, statistics| {
+                group_statistics::store_node_storage_statistics(&storage_handle, node, statistics)
+            },
+        )?;
+        self.metadata.attributes.insert(
+            STORAGE_STATISTICS_ATTRIBUTE.to_string(),
+            serde_json::to_value(statistics).unwrap_or_default(),
+        );
+        crate::storage::create_group(&storage_handle, self.path(), &self.metadata())?;
+        Ok(statistics)
+    }
+}
+
 #[cfg(feature = "async")]
 impl<TStorage: ?Sized + AsyncWritableStorageTraits> Group<TStorage> {
     /// Async variant of [`store_metadata`](Group::store_metadata).
@@ -394,7 +536,7 @@ mod tests {
         );
     }
 
-    #[cfg(feature = "async")]
+    #[cfg(all(feature = "async", feature = "object_store"))]
     #[tokio::test]
     async fn group_metadata_write_read_async() {
         let store = std::sync::Arc::new(crate::storage::store::AsyncObjectStore::new(
@@ -420,4 +562,90 @@ mod tests {
         assert_eq!(group.attributes(), &serde_json::Map::default());
         assert_eq!(group.additional_fields(), &AdditionalFields::default());
     }
+
+    #[test]
+    fn group_typed_attributes() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Calibration {
+            gain: f64,
+            channels: Vec<String>,
+        }
+        let calibration = Calibration {
+            gain: 1.5,
+            channels: vec!["red".to_string(), "green".to_string()],
+        };
+
+        let store = std::sync::Arc::new(MemoryStore::new());
+        let mut group = GroupBuilder::new()
+            .attribute("calibration", &calibration)
+            .unwrap()
+            .attribute("version", &3)
+            .unwrap()
+            .build(store.clone(), "/group")
+            .unwrap();
+        assert_eq!(group.attribute::<u32>("version").unwrap(), Some(3));
+        assert_eq!(group.attribute::<u32>("missing").unwrap(), None);
+        assert!(group.attribute::<String>("version").is_err());
+
+        assert_eq!(
+            group.set_attribute("version", "4").unwrap(),
+            Some(serde_json::json!(3))
+        );
+        assert_eq!(group.remove_attribute("missing"), None);
+        group.store_metadata().unwrap();
+
+        let group = Group::new(store, "/group").unwrap();
+        assert_eq!(
+            group.attribute::<Calibration>("calibration").unwrap(),
+            Some(calibration)
+        );
+        assert_eq!(
+            group.attribute::<String>("version").unwrap().as_deref(),
+            Some("4")
+        );
+    }
+
+    #[test]
+    fn group_storage_statistics() {
+        use crate::array::{ArrayBuilder, DataType, FillValue};
+
+        let store = std::sync::Arc::new(MemoryStore::new());
+        let mut root = GroupBuilder::new().build(store.clone(), "/").unwrap();
+        root.store_metadata().unwrap();
+        let array_builder = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        );
+        let array = array_builder.build(store.clone(), "/a/x").unwrap();
+        array.store_metadata().unwrap();
+        array.store_chunk_elements(&[0, 0], vec![1u8; 4]).unwrap();
+        array.store_chunk_elements(&[1, 1], vec![2u8; 4]).unwrap();
+        let array = array_builder.build(store.clone(), "/b").unwrap();
+        array.store_metadata().unwrap();
+        array.store_chunk_elements(&[0, 1], vec![3u8; 4]).unwrap();
+
+        let expected = StorageStatistics {
+            arrays: 2,
+            chunks: 3,
+            bytes: 12,
+        };
+        assert_eq!(root.storage_statistics().unwrap(), expected);
+        assert_eq!(root.storage_statistics_attribute(), None);
+        assert_eq!(root.store_storage_statistics().unwrap(), expected);
+        assert_eq!(root.storage_statistics_attribute(), Some(expected));
+
+        let root = Group::new(store.clone(), "/").unwrap();
+        assert_eq!(root.storage_statistics_attribute(), Some(expected));
+        let a = Group::new(store, "/a").unwrap();
+        assert_eq!(
+            a.storage_statistics_attribute(),
+            Some(StorageStatistics {
+                arrays: 1,
+                chunks: 2,
+                bytes: 8,
+            })
+        );
+    }
 }
@@ -0,0 +1,199 @@
+use std::{ops::Range, sync::Arc};
+
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, Field, Schema};
+use thiserror::Error;
+
+use crate::{
+    array::{arrow_data_type, Array, ArrayCreateError, ArrayError, ArrayMetadata, DataType},
+    array_subset::ArraySubset,
+    node::NodeMetadata,
+    storage::{get_child_nodes, ListableStorageTraits, ReadableStorageTraits, StorageError},
+};
+
+use super::Group;
+
+/// A [`RecordBatch`] retrieval error.
+#[derive(Debug, Error)]
+pub enum RecordBatchError {
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// An Arrow error, such as columns with different lengths.
+    #[error(transparent)]
+    ArrowError(#[from] ArrowError),
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits + 'static> Group<TStorage> {
+    /// Retrieve the one-dimensional child arrays of the group as the columns of an Arrow [`RecordBatch`].
+    ///
+    /// Columns are named by the array names and sorted by name.
+    /// Child groups and arrays that are not one-dimensional are ignored.
+    /// See [`arrow_data_type`] for the Arrow data type of each column.
+    ///
+    /// # Errors
+    /// Returns a [`RecordBatchError`] if
+    ///  - a child array has a data type with no Arrow representation,
+    ///  - the child arrays have different lengths,
+    ///  - there are no one-dimensional child arrays, or
+    ///  - there is a storage or codec error.
+    pub fn retrieve_record_batch(&self) -> Result<RecordBatch, RecordBatchError> {
+        self.retrieve_record_batch_impl(None)
+    }
+
+    /// Retrieve the `rows` of the one-dimensional child arrays of the group as the columns of an Arrow [`RecordBatch`].
+    ///
+    /// See [`retrieve_record_batch`](Group::retrieve_record_batch).
+    ///
+    /// # Errors
+    /// Returns a [`RecordBatchError`] if a [`retrieve_record_batch`](Group::retrieve_record_batch) error condition is met.
+    pub fn retrieve_record_batch_rows(
+        &self,
+        rows: Range<u64>,
+    ) -> Result<RecordBatch, RecordBatchError> {
+        self.retrieve_record_batch_impl(Some(&rows))
+    }
+
+    fn retrieve_record_batch_impl(
+        &self,
+        rows: Option<&Range<u64>>,
+    ) -> Result<RecordBatch, RecordBatchError> {
+        let mut arrays = get_child_nodes(&*self.storage, &self.path)?
+            .into_iter()
+            .filter_map(|node| {
+                let name = node.name().as_str().to_string();
+                let path = node.path().as_str().to_string();
+                match node.metadata() {
+                    NodeMetadata::Array(ArrayMetadata::V3(metadata))
+                        if metadata.shape.len() == 1 =>
+                    {
+                        Some((name, path, ArrayMetadata::V3(metadata.clone())))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        arrays.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        let mut fields = Vec::with_capacity(arrays.len());
+        let mut columns = Vec::with_capacity(arrays.len());
+        for (name, path, metadata) in arrays {
+            let array = Array::new_with_metadata(self.storage.clone(), &path, metadata)?;
+            let Some(arrow_data_type) = arrow_data_type(array.data_type()) else {
+                return Err(ArrayError::IncompatibleElementType(
+                    array.data_type().clone(),
+                    "arrow",
+                )
+                .into());
+            };
+            let nullable = matches!(
+                array.data_type(),
+                DataType::DateTime64(_) | DataType::TimeDelta64(_)
+            );
+            let array_subset = match rows {
+                Some(rows) => ArraySubset::new_with_ranges(std::slice::from_ref(rows)),
+                None => ArraySubset::new_with_shape(array.shape().to_vec()),
+            };
+            columns.push(array.retrieve_array_subset_arrow(&array_subset)?);
+            fields.push(Field::new(name, arrow_data_type, nullable));
+        }
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{cast::AsArray, types::Float32Type, types::UInt8Type};
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn group_record_batch() {
+        let store = Arc::new(MemoryStore::new());
+        let group = GroupBuilder::new().build(store.clone(), "/table").unwrap();
+        group.store_metadata().unwrap();
+        let b = ArrayBuilder::new(
+            vec![6],
+            DataType::Float32,
+            vec![4].try_into().unwrap(),
+            FillValue::from(0.0f32),
+        )
+        .build(store.clone(), "/table/b")
+        .unwrap();
+        b.store_metadata().unwrap();
+        b.store_array_subset_elements::<f32>(
+            &ArraySubset::new_with_shape(vec![6]),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        )
+        .unwrap();
+        let a = ArrayBuilder::new(
+            vec![6],
+            DataType::UInt8,
+            vec![4].try_into().unwrap(),
+            FillValue::from(7u8),
+        )
+        .build(store.clone(), "/table/a")
+        .unwrap();
+        a.store_metadata().unwrap();
+        a.store_array_subset_arrow(
+            &ArraySubset::new_with_start_shape(vec![1], vec![2]).unwrap(),
+            &arrow_array::UInt8Array::from(vec![Some(1), None]),
+        )
+        .unwrap();
+        ArrayBuilder::new(
+            vec![2, 3],
+            DataType::UInt8,
+            vec![2, 3].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/table/not_a_column")
+        .unwrap()
+        .store_metadata()
+        .unwrap();
+
+        let batch = group.retrieve_record_batch().unwrap();
+        assert_eq!(batch.num_rows(), 6);
+        assert_eq!(batch.schema().field(0).name(), "a");
+        assert_eq!(batch.schema().field(1).name(), "b");
+        assert_eq!(
+            batch.column(0).as_primitive::<UInt8Type>().values(),
+            &[7, 1, 7, 7, 7, 7]
+        );
+
+        let batch = group.retrieve_record_batch_rows(4..6).unwrap();
+        assert_eq!(
+            batch.column(1).as_primitive::<Float32Type>().values(),
+            &[5.0, 6.0]
+        );
+
+        ArrayBuilder::new(
+            vec![5],
+            DataType::UInt8,
+            vec![5].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/table/c")
+        .unwrap()
+        .store_metadata()
+        .unwrap();
+        assert!(matches!(
+            group.retrieve_record_batch(),
+            Err(RecordBatchError::ArrowError(_))
+        ));
+    }
+}
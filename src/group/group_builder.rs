@@ -34,6 +34,22 @@ impl GroupBuilder {
         self
     }
 
+    /// Set the attribute `name` to `value` serialised as JSON.
+    ///
+    /// # Errors
+    /// Returns a [`serde_json::Error`] if `value` cannot be serialised.
+    pub fn attribute<T: serde::Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<&mut Self, serde_json::Error> {
+        let GroupMetadata::V3(metadata) = &mut self.metadata;
+        metadata
+            .attributes
+            .insert(name.to_string(), serde_json::to_value(value)?);
+        Ok(self)
+    }
+
     /// Set the additional fields.
     ///
     /// Set additional fields not defined in the Zarr specification.
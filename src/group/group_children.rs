@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    array::{Array, ArrayCreateError},
+    node::{NodeMetadata, NodePath},
+    storage::{
+        discover_children, meta_key, ListableStorageTraits, ReadableStorageTraits, StorageError,
+        StorePrefix,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    async_discover_children, AsyncListableStorageTraits, AsyncReadableStorageTraits,
+};
+
+use super::{Group, GroupCreateError, GroupMetadataV3};
+
+/// Parse the metadata of the child node at `prefix`, where [`None`] is an implicit group.
+fn child_metadata(
+    prefix: &StorePrefix,
+    metadata: Option<&[u8]>,
+) -> Result<(NodePath, NodeMetadata), StorageError> {
+    let path: NodePath = prefix.try_into()?;
+    let metadata = match metadata {
+        Some(metadata) => serde_json::from_slice(metadata)
+            .map_err(|err| StorageError::InvalidMetadata(meta_key(&path), err.to_string()))?,
+        None => NodeMetadata::Group(GroupMetadataV3::default().into()),
+    };
+    Ok((path, metadata))
+}
+
+fn arrays_from_metadata<TStorage: ?Sized>(
+    storage: &Arc<TStorage>,
+    children: Vec<(NodePath, NodeMetadata)>,
+) -> Result<Vec<Array<TStorage>>, ArrayCreateError> {
+    children
+        .into_iter()
+        .filter_map(|(path, metadata)| match metadata {
+            NodeMetadata::Array(metadata) => Some(Array::new_with_metadata(
+                storage.clone(),
+                path.as_str(),
+                metadata,
+            )),
+            NodeMetadata::Group(_) => None,
+        })
+        .collect()
+}
+
+fn groups_from_metadata<TStorage: ?Sized>(
+    storage: &Arc<TStorage>,
+    children: Vec<(NodePath, NodeMetadata)>,
+) -> Result<Vec<Group<TStorage>>, GroupCreateError> {
+    children
+        .into_iter()
+        .filter_map(|(path, metadata)| match metadata {
+            NodeMetadata::Group(metadata) => Some(Group::new_with_metadata(
+                storage.clone(),
+                path.as_str(),
+                metadata,
+            )),
+            NodeMetadata::Array(_) => None,
+        })
+        .collect()
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> Group<TStorage> {
+    /// Retrieve the metadata of the direct children of the group.
+    ///
+    /// The children are listed once and their metadata is retrieved in parallel.
+    fn child_metadata(&self) -> Result<Vec<(NodePath, NodeMetadata)>, StorageError> {
+        let prefixes = discover_children(&*self.storage, &self.path)?;
+        let mut children = prefixes
+            .into_par_iter()
+            .map(|prefix| {
+                let key = meta_key(&(&prefix).try_into()?);
+                child_metadata(&prefix, self.storage.get(&key)?.as_deref())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        children.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        Ok(children)
+    }
+
+    /// Open the child arrays of the group.
+    ///
+    /// The children of the group are listed once and their metadata is retrieved in parallel, which is considerably faster than calling [`Array::new`] for each child on high-latency stores.
+    /// Arrays are sorted by path.
+    /// Child groups and descendants of child groups are not included.
+    ///
+    /// # Errors
+    /// Returns an [`ArrayCreateError`] if there is a storage error or any child array metadata is invalid.
+    pub fn arrays(&self) -> Result<Vec<Array<TStorage>>, ArrayCreateError> {
+        arrays_from_metadata(&self.storage, self.child_metadata()?)
+    }
+
+    /// Open the child groups of the group.
+    ///
+    /// Like [`arrays`](Group::arrays), the metadata of the children is retrieved in parallel.
+    /// Groups are sorted by path.
+    /// Implicit groups (without metadata) are included with default metadata.
+    ///
+    /// # Errors
+    /// Returns a [`GroupCreateError`] if there is a storage error or any child group metadata is invalid.
+    pub fn groups(&self) -> Result<Vec<Group<TStorage>>, GroupCreateError> {
+        groups_from_metadata(&self.storage, self.child_metadata()?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits> Group<TStorage> {
+    async fn async_child_metadata(&self) -> Result<Vec<(NodePath, NodeMetadata)>, StorageError> {
+        let prefixes = async_discover_children(&*self.storage, &self.path).await?;
+        let futures = prefixes.into_iter().map(|prefix| async move {
+            let key = meta_key(&(&prefix).try_into()?);
+            child_metadata(&prefix, self.storage.get(&key).await?.as_deref())
+        });
+        let mut children = futures::future::try_join_all(futures).await?;
+        children.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+        Ok(children)
+    }
+
+    /// Async variant of [`arrays`](Group::arrays).
+    ///
+    /// The metadata of the children is retrieved concurrently.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_arrays(&self) -> Result<Vec<Array<TStorage>>, ArrayCreateError> {
+        arrays_from_metadata(&self.storage, self.async_child_metadata().await?)
+    }
+
+    /// Async variant of [`groups`](Group::groups).
+    ///
+    /// The metadata of the children is retrieved concurrently.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn async_groups(&self) -> Result<Vec<Group<TStorage>>, GroupCreateError> {
+        groups_from_metadata(&self.storage, self.async_child_metadata().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::{store::MemoryStore, StoreKey, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn group_arrays_groups() {
+        let store = Arc::new(MemoryStore::new());
+        let group = GroupBuilder::new().build(store.clone(), "/root").unwrap();
+        group.store_metadata().unwrap();
+        for name in ["b", "a"] {
+            ArrayBuilder::new(
+                vec![4],
+                DataType::UInt8,
+                vec![2].try_into().unwrap(),
+                FillValue::from(0u8),
+            )
+            .build(store.clone(), &format!("/root/{name}"))
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        }
+        GroupBuilder::new()
+            .build(store.clone(), "/root/explicit")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        ArrayBuilder::new(
+            vec![4],
+            DataType::UInt8,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/root/implicit/nested")
+        .unwrap()
+        .store_metadata()
+        .unwrap();
+
+        let arrays = group.arrays().unwrap();
+        let paths: Vec<_> = arrays.iter().map(|array| array.path().as_str()).collect();
+        assert_eq!(paths, ["/root/a", "/root/b"]);
+        assert_eq!(arrays[0].shape(), &[4]);
+
+        let groups = group.groups().unwrap();
+        let paths: Vec<_> = groups.iter().map(|group| group.path().as_str()).collect();
+        assert_eq!(paths, ["/root/explicit", "/root/implicit"]);
+        assert_eq!(groups[1].arrays().unwrap().len(), 1);
+
+        store
+            .set(&StoreKey::new("root/a/zarr.json").unwrap(), b"{")
+            .unwrap();
+        assert!(matches!(
+            group.arrays(),
+            Err(ArrayCreateError::StorageError(
+                StorageError::InvalidMetadata(..)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+//! Storage statistics of a Zarr hierarchy.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    group::GroupMetadata,
+    node::{Node, NodeMetadata, NodePath},
+    storage::{
+        create_group, meta_key, ListableStorageTraits, ReadableStorageTraits, StorageError,
+        StorePrefix, WritableStorageTraits,
+    },
+};
+
+/// The group attribute that holds a [`StorageStatistics`] summary.
+pub const STORAGE_STATISTICS_ATTRIBUTE: &str = "storage_statistics";
+
+/// Storage statistics of an array or of all arrays below a group.
+///
+/// The last modified time of stored values is not available through the storage API, so it is not included.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct StorageStatistics {
+    /// The number of arrays.
+    pub arrays: u64,
+    /// The number of stored chunks.
+    pub chunks: u64,
+    /// The total size of stored chunks in bytes.
+    ///
+    /// Metadata documents are not included.
+    pub bytes: u64,
+}
+
+impl core::ops::AddAssign for StorageStatistics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.arrays += rhs.arrays;
+        self.chunks += rhs.chunks;
+        self.bytes += rhs.bytes;
+    }
+}
+
+/// Get the storage statistics of the array at `path`.
+///
+/// Every key below the array prefix other than the array metadata is counted as a chunk.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn array_storage_statistics<
+    TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits,
+>(
+    storage: &TStorage,
+    path: &NodePath,
+) -> Result<StorageStatistics, StorageError> {
+    let prefix: StorePrefix = path.try_into()?;
+    let metadata_key = meta_key(path);
+    let mut statistics = StorageStatistics {
+        arrays: 1,
+        ..StorageStatistics::default()
+    };
+    for key in storage.list_prefix(&prefix)? {
+        if key == metadata_key {
+            continue;
+        }
+        if let Some(size) = storage.size_key(&key)? {
+            statistics.chunks += 1;
+            statistics.bytes += size;
+        }
+    }
+    Ok(statistics)
+}
+
+/// Get the storage statistics of `nodes` and their descendants.
+///
+/// The statistics of every group visited are passed to `visit_group`.
+pub(crate) fn nodes_storage_statistics<
+    TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits,
+>(
+    storage: &TStorage,
+    nodes: &[Node],
+    visit_group: &mut impl FnMut(&Node, &StorageStatistics) -> Result<(), StorageError>,
+) -> Result<StorageStatistics, StorageError> {
+    let mut statistics = StorageStatistics::default();
+    for node in nodes {
+        match node.metadata() {
+            NodeMetadata::Array(_) => {
+                statistics += array_storage_statistics(storage, node.path())?;
+            }
+            NodeMetadata::Group(_) => {
+                let group_statistics =
+                    nodes_storage_statistics(storage, node.children(), visit_group)?;
+                visit_group(node, &group_statistics)?;
+                statistics += group_statistics;
+            }
+        }
+    }
+    Ok(statistics)
+}
+
+/// Store `statistics` in the [`STORAGE_STATISTICS_ATTRIBUTE`] of the group `node`.
+pub(crate) fn store_node_storage_statistics(
+    storage: &dyn WritableStorageTraits,
+    node: &Node,
+    statistics: &StorageStatistics,
+) -> Result<(), StorageError> {
+    if let NodeMetadata::Group(GroupMetadata::V3(metadata)) = node.metadata() {
+        let mut metadata = metadata.clone();
+        metadata.attributes.insert(
+            STORAGE_STATISTICS_ATTRIBUTE.to_string(),
+            serde_json::to_value(statistics).unwrap_or_default(),
+        );
+        create_group(storage, node.path(), &metadata.into())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Zarr hierarchies.
+//!
+//! A [`Hierarchy`] opens a store and provides access to its nodes by path, without manually stitching together [`Node`], [`get_child_nodes`](crate::storage::get_child_nodes), and [`Array::new`].
+//!
+//! The [`Node`] tree is read from the store on first access and cached.
+//! Indexing a hierarchy by path returns the [`Node`] at that path, and [`Hierarchy::array`] and [`Hierarchy::group`] return typed [`Array`] and [`Group`] handles.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::{array::{ArrayBuilder, DataType, FillValue}, group::GroupBuilder};
+//! use zarrs::{hierarchy::Hierarchy, storage::store::MemoryStore};
+//!
+//! let store = Arc::new(MemoryStore::new());
+//! # GroupBuilder::new().build(store.clone(), "/group")?.store_metadata()?;
+//! # ArrayBuilder::new(vec![4], DataType::Float32, vec![2].try_into()?, FillValue::from(0.0f32))
+//! #     .build(store.clone(), "/group/array")?
+//! #     .store_metadata()?;
+//! let hierarchy = Hierarchy::open(store)?;
+//! println!("{}", hierarchy["/group"].hierarchy_tree());
+//! let array = hierarchy.array("/group/array")?;
+//! # assert_eq!(array.shape(), &[4]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+use thiserror::Error;
+
+use crate::{
+    array::{Array, ArrayCreateError},
+    group::{Group, GroupCreateError, GroupMetadataV3},
+    node::{Node, NodeCreateError, NodeMetadata, NodePath, NodePathError},
+    storage::{
+        meta_key, ListableStorageTraits, ReadableStorageTraits, StorageError, WritableStorageTraits,
+    },
+};
+
+/// A Zarr hierarchy.
+pub struct Hierarchy<TStorage: ?Sized> {
+    /// The underlying storage.
+    storage: Arc<TStorage>,
+    /// The node tree, read on first access.
+    root: OnceLock<Node>,
+}
+
+/// A hierarchy error.
+#[derive(Debug, Error)]
+pub enum HierarchyError {
+    /// An invalid node path.
+    #[error(transparent)]
+    NodePathError(#[from] NodePathError),
+    /// A node creation error.
+    #[error(transparent)]
+    NodeCreateError(#[from] NodeCreateError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// A group creation error.
+    #[error(transparent)]
+    GroupCreateError(#[from] GroupCreateError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// There is no node at the path.
+    #[error("there is no node at {0}")]
+    NodeNotFound(NodePath),
+    /// The node at the path is not an array.
+    #[error("the node at {0} is not an array")]
+    NotAnArray(NodePath),
+    /// The node at the path is not a group.
+    #[error("the node at {0} is not a group")]
+    NotAGroup(NodePath),
+}
+
+impl<TStorage: ?Sized> Hierarchy<TStorage> {
+    /// Get the underlying storage.
+    #[must_use]
+    pub fn storage(&self) -> Arc<TStorage> {
+        self.storage.clone()
+    }
+
+    /// Discard the cached node tree, so that it is read from the store again on next access.
+    ///
+    /// This should be called if the hierarchy has been modified since it was first accessed.
+    pub fn refresh(&mut self) {
+        self.root = OnceLock::new();
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> Hierarchy<TStorage> {
+    /// Open the hierarchy in `storage`.
+    ///
+    /// This validates the root metadata, but the node tree is not read until it is first accessed.
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if there is a storage error or the root metadata is invalid.
+    pub fn open(storage: Arc<TStorage>) -> Result<Self, HierarchyError> {
+        let key = meta_key(&NodePath::root());
+        if let Some(metadata) = storage.get(&key)? {
+            serde_json::from_slice::<NodeMetadata>(&metadata)
+                .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))?;
+        }
+        Ok(Self {
+            storage,
+            root: OnceLock::new(),
+        })
+    }
+
+    /// Get the root [`Node`], reading the node tree from the store if it has not yet been accessed.
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if there is a storage error or any metadata is invalid.
+    pub fn root(&self) -> Result<&Node, HierarchyError> {
+        if let Some(root) = self.root.get() {
+            return Ok(root);
+        }
+        let root = Node::new(&*self.storage, "/")?;
+        Ok(self.root.get_or_init(|| root))
+    }
+
+    /// Get the [`Node`] at `path`.
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if `path` is invalid, there is no node at `path`, or the node tree cannot be read.
+    pub fn node(&self, path: &str) -> Result<&Node, HierarchyError> {
+        let path = NodePath::new(path)?;
+        find_node(self.root()?, &path).ok_or(HierarchyError::NodeNotFound(path))
+    }
+
+    /// Get the [`Array`] at `path`.
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if there is no array at `path` or its metadata is invalid.
+    pub fn array(&self, path: &str) -> Result<Array<TStorage>, HierarchyError> {
+        let node = self.node(path)?;
+        match node.metadata() {
+            NodeMetadata::Array(metadata) => Ok(Array::new_with_metadata(
+                self.storage.clone(),
+                path,
+                metadata.clone(),
+            )?),
+            NodeMetadata::Group(_) => Err(HierarchyError::NotAnArray(node.path().clone())),
+        }
+    }
+
+    /// Get the [`Group`] at `path`.
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if there is no group at `path` or its metadata is invalid.
+    pub fn group(&self, path: &str) -> Result<Group<TStorage>, HierarchyError> {
+        let node = self.node(path)?;
+        match node.metadata() {
+            NodeMetadata::Group(metadata) => Ok(Group::new_with_metadata(
+                self.storage.clone(),
+                path,
+                metadata.clone(),
+            )?),
+            NodeMetadata::Array(_) => Err(HierarchyError::NotAGroup(node.path().clone())),
+        }
+    }
+}
+
+impl<
+        TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits + ListableStorageTraits + 'static,
+    > Hierarchy<TStorage>
+{
+    /// Create a hierarchy in `storage` and open it.
+    ///
+    /// Root group metadata is written if `storage` does not already contain root metadata.
+    /// See [`create_metadata_if_not_exists`](crate::storage::create_metadata_if_not_exists).
+    ///
+    /// # Errors
+    /// Returns [`HierarchyError`] if there is a storage error or the existing root metadata is invalid.
+    pub fn create(storage: Arc<TStorage>) -> Result<Self, HierarchyError> {
+        crate::storage::create_metadata_if_not_exists(
+            &*storage,
+            &NodePath::root(),
+            &NodeMetadata::Group(GroupMetadataV3::default().into()),
+        )?;
+        Self::open(storage)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> core::ops::Index<&str>
+    for Hierarchy<TStorage>
+{
+    type Output = Node;
+
+    /// Returns the [`Node`] at `path`.
+    ///
+    /// # Panics
+    /// Panics if [`Hierarchy::node`] returns an error.
+    fn index(&self, path: &str) -> &Node {
+        match self.node(path) {
+            Ok(node) => node,
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+/// Find the node at `path` in the tree below `node`.
+fn find_node<'a>(node: &'a Node, path: &NodePath) -> Option<&'a Node> {
+    if node.path() == path {
+        return Some(node);
+    }
+    node.children()
+        .iter()
+        .find(|child| path.as_path().starts_with(child.path().as_path()))
+        .and_then(|child| find_node(child, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn hierarchy_open() {
+        let store = Arc::new(MemoryStore::new());
+        let hierarchy = Hierarchy::create(store.clone()).unwrap();
+        GroupBuilder::new()
+            .build(store.clone(), "/a")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/a/b")
+        .unwrap()
+        .store_metadata()
+        .unwrap();
+
+        // The node tree is read on first access
+        assert_eq!(hierarchy["/"].children().len(), 1);
+        assert_eq!(hierarchy["/a"].children().len(), 1);
+        assert_eq!(hierarchy["/a/b"].name().as_str(), "b");
+
+        let array = hierarchy.array("/a/b").unwrap();
+        assert_eq!(array.shape(), &[4, 4]);
+        assert_eq!(array.path().as_str(), "/a/b");
+        let group = hierarchy.group("/a").unwrap();
+        assert_eq!(group.path().as_str(), "/a");
+
+        assert!(matches!(
+            hierarchy.array("/a"),
+            Err(HierarchyError::NotAnArray(_))
+        ));
+        assert!(matches!(
+            hierarchy.group("/a/b"),
+            Err(HierarchyError::NotAGroup(_))
+        ));
+        assert!(matches!(
+            hierarchy.node("/a/c"),
+            Err(HierarchyError::NodeNotFound(_))
+        ));
+        assert!(matches!(
+            hierarchy.node("a"),
+            Err(HierarchyError::NodePathError(_))
+        ));
+    }
+
+    #[test]
+    fn hierarchy_refresh() {
+        let store = Arc::new(MemoryStore::new());
+        let mut hierarchy = Hierarchy::create(store.clone()).unwrap();
+        assert!(hierarchy["/"].children().is_empty());
+        GroupBuilder::new()
+            .build(store.clone(), "/a")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        assert!(hierarchy["/"].children().is_empty());
+        hierarchy.refresh();
+        assert_eq!(hierarchy["/"].children().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no node at /a")]
+    fn hierarchy_index_missing() {
+        let hierarchy = Hierarchy::create(Arc::new(MemoryStore::new())).unwrap();
+        let _ = &hierarchy["/a"];
+    }
+}
@@ -0,0 +1,97 @@
+//! Import helpers for external file formats.
+//!
+//! The [`nifti`] module converts [`NIfTI-1` and `NIfTI-2`](https://nifti.nimh.nih.gov/) volumes (`.nii` and `.nii.gz`) into chunked Zarr arrays, so that medical imaging data can be adopted without preprocessing in other languages.
+//! DICOM series are not currently supported; convert them to `NIfTI` first (e.g. with `dcm2niix`).
+//!
+//! Volumes are streamed into the destination array one slab at a time, where a slab is a row of chunks along the outermost (slowest varying) dimension.
+//! Uncompressed volumes are read with concurrent positioned reads, and the next slab is read while the current slab is encoded and stored.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use zarrs::{import::{nifti::import_nifti, ImportOptions}, storage::store::FilesystemStore};
+//!
+//! let store = Arc::new(FilesystemStore::new("/path/to/hierarchy.zarr")?);
+//! let array = import_nifti("/path/to/volume.nii.gz", store, "/volume", &ImportOptions::default())?;
+//! println!("{:?}", array.shape()); // e.g. [t, z, y, x]
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod nifti;
+
+use thiserror::Error;
+
+use crate::{
+    array::{codec::CodecOptions, ArrayCreateError, ArrayError, ArrayShape},
+    storage::StorageError,
+};
+
+/// Options for importing external file formats.
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    concurrent_target: usize,
+    codec_options: CodecOptions,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            concurrent_target: rayon::current_num_threads(),
+            codec_options: CodecOptions::default(),
+        }
+    }
+}
+
+impl ImportOptions {
+    /// Return the concurrent target.
+    #[must_use]
+    pub fn concurrent_target(&self) -> usize {
+        self.concurrent_target
+    }
+
+    /// Set the number of concurrent reads of an uncompressed input file. Defaults to the number of [`rayon`] threads.
+    pub fn set_concurrent_target(&mut self, concurrent_target: usize) -> &mut Self {
+        self.concurrent_target = concurrent_target;
+        self
+    }
+
+    /// Return the codec options.
+    #[must_use]
+    pub fn codec_options(&self) -> &CodecOptions {
+        &self.codec_options
+    }
+
+    /// Set the codec options used to encode and store chunks.
+    pub fn set_codec_options(&mut self, codec_options: CodecOptions) -> &mut Self {
+        self.codec_options = codec_options;
+        self
+    }
+}
+
+/// An import error.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// An IO error reading the input file.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// The input file header is invalid.
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+    /// The input file is valid but uses an unsupported feature.
+    #[error("unsupported input: {0}")]
+    Unsupported(String),
+    /// The destination array shape differs from the input shape.
+    #[error("destination array shape {1:?} differs from the input shape {0:?}")]
+    IncompatibleShape(ArrayShape, ArrayShape),
+    /// The destination array data type differs from the input data type.
+    #[error("destination array data type {1} differs from the input data type {0}")]
+    IncompatibleDataType(String, String),
+}
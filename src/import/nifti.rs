@@ -0,0 +1,831 @@
+//! `NIfTI` import.
+//!
+//! [`import_nifti`] converts a single file `NIfTI-1` or `NIfTI-2` volume (`.nii`, or `.nii.gz` with the `gzip` feature) into a chunked Zarr array.
+//!
+//! The array dimensions are the reverse of the `NIfTI` dimensions, so that the array is in C (row-major) order with the `x` dimension varying fastest.
+//! For example, a 4D `NIfTI` volume with dimensions `(x, y, z, t)` becomes an array with shape `[t, z, y, x]` and dimension names `["t", "z", "y", "x"]`.
+//! By default, the spatial (last three) dimensions are chunked with a chunk size of up to 64 and the remaining dimensions have a chunk size of 1 (see [`NiftiHeader::array_builder`]).
+//! Use [`import_nifti_with_builder`] to choose a different chunk grid, codecs, or other metadata.
+//!
+//! The `NIfTI` header is mapped to the `nifti` attribute of the array, which holds the voxel spacing (`pixdim`), units, the qform and sform orientations, the intensity scaling (`scl_slope` and `scl_inter`), and the description.
+//! Voxel values are stored as is and are not scaled.
+//!
+//! Two file (`.hdr`/`.img`) volumes and header extensions are not supported.
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use rayon::{iter::IndexedParallelIterator, iter::ParallelIterator, slice::ParallelSliceMut};
+
+use crate::{
+    array::{Array, ArrayBuilder, DataType},
+    array_subset::ArraySubset,
+    storage::{StorageError, WritableStorageTraits},
+};
+
+use super::{ImportError, ImportOptions};
+
+/// The size of a `NIfTI-1` header.
+const NIFTI1_HEADER_SIZE: usize = 348;
+
+/// The size of a `NIfTI-2` header.
+const NIFTI2_HEADER_SIZE: usize = 540;
+
+/// The minimum size of a concurrent read of an uncompressed file.
+const MIN_READ_SIZE: usize = 1 << 20;
+
+/// The `NIfTI` dimension names, from fastest to slowest varying.
+const DIMENSION_NAMES: [&str; 7] = ["x", "y", "z", "t", "u", "v", "w"];
+
+/// The default chunk size of the spatial dimensions.
+const SPATIAL_CHUNK_SIZE: u64 = 64;
+
+/// A `NIfTI-1` or `NIfTI-2` header.
+///
+/// Dimensions and voxel spacings are in `NIfTI` order, with `x` first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NiftiHeader {
+    /// The `NIfTI` version (1 or 2).
+    pub version: u8,
+    /// True if the file is big endian.
+    pub big_endian: bool,
+    /// The dimensions.
+    pub dim: Vec<u64>,
+    /// The `NIfTI` data type code.
+    pub datatype: i16,
+    /// The voxel spacing in each dimension.
+    pub pixdim: Vec<f64>,
+    /// The byte offset of the voxel data in the (decompressed) file.
+    pub vox_offset: u64,
+    /// The intensity scaling slope.
+    pub scl_slope: f64,
+    /// The intensity scaling intercept.
+    pub scl_inter: f64,
+    /// The spatial and temporal units code.
+    pub xyzt_units: u8,
+    /// The intent code.
+    pub intent_code: i32,
+    /// The qform code.
+    pub qform_code: i32,
+    /// The sform code.
+    pub sform_code: i32,
+    /// The qform quaternion parameters `b`, `c`, and `d`.
+    pub quatern: [f64; 3],
+    /// The qform offsets `x`, `y`, and `z`.
+    pub qoffset: [f64; 3],
+    /// The rows of the sform affine transform.
+    pub srow: [[f64; 4]; 3],
+    /// The description.
+    pub descrip: String,
+}
+
+/// Read little or big endian values from header bytes.
+struct HeaderBytes<'a> {
+    bytes: &'a [u8],
+    big_endian: bool,
+}
+
+macro_rules! header_bytes_read {
+    ($($name:ident: $type:ty),*) => {
+        $(
+            fn $name(&self, offset: usize) -> $type {
+                let bytes = self.bytes[offset..offset + std::mem::size_of::<$type>()]
+                    .try_into()
+                    .unwrap();
+                if self.big_endian {
+                    <$type>::from_be_bytes(bytes)
+                } else {
+                    <$type>::from_le_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl HeaderBytes<'_> {
+    header_bytes_read!(i16: i16, i32: i32, i64: i64, f32: f32, f64: f64);
+
+    fn string(&self, offset: usize, length: usize) -> String {
+        let bytes = &self.bytes[offset..offset + length];
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(length);
+        String::from_utf8_lossy(&bytes[..end])
+            .trim_end()
+            .to_string()
+    }
+}
+
+/// Convert the `NIfTI` dimensions `dim[1..=dim[0]]`.
+fn dimensions(dim: &[i64]) -> Result<Vec<u64>, ImportError> {
+    let ndim = usize::try_from(dim[0])
+        .ok()
+        .filter(|ndim| (1..=7).contains(ndim))
+        .ok_or_else(|| ImportError::InvalidHeader(format!("invalid dimensionality {}", dim[0])))?;
+    dim[1..=ndim]
+        .iter()
+        .map(|&size| {
+            u64::try_from(size)
+                .map_err(|_| ImportError::InvalidHeader(format!("invalid dimension size {size}")))
+        })
+        .collect()
+}
+
+impl NiftiHeader {
+    /// Read a `NIfTI` header from the start of `reader`.
+    ///
+    /// # Errors
+    /// Returns an [`ImportError`] if the header cannot be read or is not a valid single file `NIfTI-1` or `NIfTI-2` header.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, ImportError> {
+        let mut bytes = vec![0; NIFTI2_HEADER_SIZE];
+        reader.read_exact(&mut bytes[..NIFTI1_HEADER_SIZE])?;
+        let sizeof_hdr = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match (
+            i32::from_le_bytes(sizeof_hdr),
+            i32::from_be_bytes(sizeof_hdr),
+        ) {
+            (348, _) => Self::read_nifti1(&bytes, false),
+            (_, 348) => Self::read_nifti1(&bytes, true),
+            (540, _) | (_, 540) => {
+                reader.read_exact(&mut bytes[NIFTI1_HEADER_SIZE..])?;
+                Self::read_nifti2(&bytes, i32::from_le_bytes(sizeof_hdr) != 540)
+            }
+            _ => Err(ImportError::InvalidHeader(
+                "the header size is not 348 (NIfTI-1) or 540 (NIfTI-2)".to_string(),
+            )),
+        }
+    }
+
+    /// Read the `NIfTI` header of the file at `path`, which is decompressed if it is gzip compressed.
+    ///
+    /// # Errors
+    /// Returns an [`ImportError`] if the file cannot be read, is gzip compressed and the `gzip` feature is disabled, or has an invalid header.
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Self, ImportError> {
+        Self::read(&mut open(path.as_ref())?.0)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn read_nifti1(bytes: &[u8], big_endian: bool) -> Result<Self, ImportError> {
+        match &bytes[344..348] {
+            b"n+1\0" => {}
+            b"ni1\0" => {
+                return Err(ImportError::Unsupported(
+                    "two file (.hdr/.img) NIfTI-1 volumes".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ImportError::InvalidHeader(
+                    "invalid NIfTI-1 magic".to_string(),
+                ))
+            }
+        }
+        let h = HeaderBytes { bytes, big_endian };
+        let dim: Vec<i64> = (0..8).map(|i| i64::from(h.i16(40 + 2 * i))).collect();
+        let dim = dimensions(&dim)?;
+        let pixdim = (1..=dim.len())
+            .map(|i| f64::from(h.f32(76 + 4 * i)))
+            .collect();
+        let vox_offset = h.f32(108);
+        let srow = |offset: usize| std::array::from_fn(|i| f64::from(h.f32(offset + 4 * i)));
+        Self {
+            version: 1,
+            big_endian,
+            dim,
+            datatype: h.i16(70),
+            pixdim,
+            vox_offset: if vox_offset.is_finite() && vox_offset > 0.0 {
+                vox_offset as u64
+            } else {
+                0
+            },
+            scl_slope: f64::from(h.f32(112)),
+            scl_inter: f64::from(h.f32(116)),
+            xyzt_units: bytes[123],
+            intent_code: i32::from(h.i16(68)),
+            qform_code: i32::from(h.i16(252)),
+            sform_code: i32::from(h.i16(254)),
+            quatern: std::array::from_fn(|i| f64::from(h.f32(256 + 4 * i))),
+            qoffset: std::array::from_fn(|i| f64::from(h.f32(268 + 4 * i))),
+            srow: [srow(280), srow(296), srow(312)],
+            descrip: h.string(148, 80),
+        }
+        .validated(NIFTI1_HEADER_SIZE)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn read_nifti2(bytes: &[u8], big_endian: bool) -> Result<Self, ImportError> {
+        match &bytes[4..12] {
+            b"n+2\0\r\n\x1a\n" => {}
+            b"ni2\0\r\n\x1a\n" => {
+                return Err(ImportError::Unsupported(
+                    "two file (.hdr/.img) NIfTI-2 volumes".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ImportError::InvalidHeader(
+                    "invalid NIfTI-2 magic".to_string(),
+                ))
+            }
+        }
+        let h = HeaderBytes { bytes, big_endian };
+        let dim: Vec<i64> = (0..8).map(|i| h.i64(16 + 8 * i)).collect();
+        let dim = dimensions(&dim)?;
+        let pixdim = (1..=dim.len()).map(|i| h.f64(104 + 8 * i)).collect();
+        let srow = |offset: usize| std::array::from_fn(|i| h.f64(offset + 8 * i));
+        Self {
+            version: 2,
+            big_endian,
+            dim,
+            datatype: h.i16(12),
+            pixdim,
+            vox_offset: u64::try_from(h.i64(168)).unwrap_or_default(),
+            scl_slope: h.f64(176),
+            scl_inter: h.f64(184),
+            xyzt_units: h.i32(500) as u8,
+            intent_code: h.i32(504),
+            qform_code: h.i32(344),
+            sform_code: h.i32(348),
+            quatern: std::array::from_fn(|i| h.f64(352 + 8 * i)),
+            qoffset: std::array::from_fn(|i| h.f64(376 + 8 * i)),
+            srow: [srow(400), srow(432), srow(464)],
+            descrip: h.string(240, 80),
+        }
+        .validated(NIFTI2_HEADER_SIZE)
+    }
+
+    /// Check that the voxel data does not overlap the header and that the data type is supported.
+    fn validated(self, header_size: usize) -> Result<Self, ImportError> {
+        if self.vox_offset < header_size as u64 {
+            return Err(ImportError::InvalidHeader(format!(
+                "the voxel offset {} overlaps the header",
+                self.vox_offset
+            )));
+        }
+        self.data_type()?;
+        Ok(self)
+    }
+
+    /// Return the array shape, which is the reverse of the `NIfTI` dimensions.
+    #[must_use]
+    pub fn shape(&self) -> Vec<u64> {
+        self.dim.iter().rev().copied().collect()
+    }
+
+    /// Return the array dimension names, which are the reverse of the `NIfTI` dimension names.
+    #[must_use]
+    pub fn dimension_names(&self) -> Vec<&'static str> {
+        DIMENSION_NAMES[..self.dim.len()]
+            .iter()
+            .rev()
+            .copied()
+            .collect()
+    }
+
+    /// Return the array data type corresponding to the `NIfTI` data type.
+    ///
+    /// RGB (24-bit) and RGBA (32-bit) voxels are mapped to `r24` and `r32` raw bits data types.
+    ///
+    /// # Errors
+    /// Returns [`ImportError::Unsupported`] if the `NIfTI` data type is not supported.
+    pub fn data_type(&self) -> Result<DataType, ImportError> {
+        match self.datatype {
+            2 => Ok(DataType::UInt8),
+            4 => Ok(DataType::Int16),
+            8 => Ok(DataType::Int32),
+            16 => Ok(DataType::Float32),
+            32 => Ok(DataType::Complex64),
+            64 => Ok(DataType::Float64),
+            128 => Ok(DataType::RawBits(3)),
+            256 => Ok(DataType::Int8),
+            512 => Ok(DataType::UInt16),
+            768 => Ok(DataType::UInt32),
+            1024 => Ok(DataType::Int64),
+            1280 => Ok(DataType::UInt64),
+            1792 => Ok(DataType::Complex128),
+            2304 => Ok(DataType::RawBits(4)),
+            datatype => Err(ImportError::Unsupported(format!(
+                "NIfTI data type {datatype}"
+            ))),
+        }
+    }
+
+    /// Return the header fields as the `nifti` attribute value.
+    #[must_use]
+    pub fn attributes(&self) -> serde_json::Value {
+        serde_json::json!({
+            "version": self.version,
+            "datatype": self.datatype,
+            "pixdim": self.pixdim,
+            "xyzt_units": self.xyzt_units,
+            "scl_slope": self.scl_slope,
+            "scl_inter": self.scl_inter,
+            "intent_code": self.intent_code,
+            "qform_code": self.qform_code,
+            "sform_code": self.sform_code,
+            "quatern_bcd": self.quatern,
+            "qoffset_xyz": self.qoffset,
+            "srow_x": self.srow[0],
+            "srow_y": self.srow[1],
+            "srow_z": self.srow[2],
+            "descrip": self.descrip,
+        })
+    }
+
+    /// Return an [`ArrayBuilder`] for an array holding the volume.
+    ///
+    /// The spatial (last three) dimensions have a chunk size of up to 64 and the remaining dimensions have a chunk size of 1.
+    /// The fill value is zero and the header is stored in the `nifti` attribute.
+    ///
+    /// # Errors
+    /// Returns [`ImportError::Unsupported`] if the `NIfTI` data type is not supported.
+    pub fn array_builder(&self) -> Result<ArrayBuilder, ImportError> {
+        let data_type = self.data_type()?;
+        let shape = self.shape();
+        let spatial = shape.len().saturating_sub(3);
+        let chunk_shape: Vec<u64> = shape
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                if i >= spatial {
+                    size.clamp(1, SPATIAL_CHUNK_SIZE)
+                } else {
+                    1
+                }
+            })
+            .collect();
+        let fill_value = vec![0; data_type.size()].into();
+        let mut attributes = serde_json::Map::new();
+        attributes.insert("nifti".to_string(), self.attributes());
+        let mut builder = ArrayBuilder::new(
+            shape,
+            data_type,
+            chunk_shape
+                .try_into()
+                .map_err(|_| ImportError::InvalidHeader("invalid dimensions".to_string()))?,
+            fill_value,
+        );
+        builder
+            .dimension_names(Some(self.dimension_names()))
+            .attributes(attributes);
+        Ok(builder)
+    }
+}
+
+/// Open the file at `path`, returning a reader of its decompressed bytes and whether it is gzip compressed.
+fn open(path: &Path) -> Result<(Box<dyn Read + Send>, bool), ImportError> {
+    let mut file = File::open(path)?;
+    let mut magic = [0; 2];
+    let gzip = file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b];
+    file.seek(SeekFrom::Start(0))?;
+    if gzip {
+        #[cfg(feature = "gzip")]
+        return Ok((
+            Box::new(flate2::read::GzDecoder::new(std::io::BufReader::new(file))),
+            true,
+        ));
+        #[cfg(not(feature = "gzip"))]
+        return Err(ImportError::Unsupported(
+            "gzip compressed volumes without the gzip feature".to_string(),
+        ));
+    }
+    Ok((Box::new(file), false))
+}
+
+/// A reader of the voxel data of a volume.
+enum VoxelReader {
+    /// An uncompressed file, which supports concurrent positioned reads.
+    File { path: PathBuf, vox_offset: u64 },
+    /// A compressed file, which must be read sequentially.
+    Stream(Box<dyn Read + Send>),
+}
+
+impl VoxelReader {
+    fn new(path: &Path, header: &NiftiHeader) -> Result<Self, ImportError> {
+        let (mut reader, gzip) = open(path)?;
+        if gzip {
+            std::io::copy(
+                &mut (&mut reader).take(header.vox_offset),
+                &mut std::io::sink(),
+            )?;
+            Ok(Self::Stream(reader))
+        } else {
+            Ok(Self::File {
+                path: path.to_path_buf(),
+                vox_offset: header.vox_offset,
+            })
+        }
+    }
+
+    /// Read `bytes` starting at `offset` bytes into the voxel data.
+    ///
+    /// A stream must be read in order.
+    fn read(
+        &mut self,
+        offset: u64,
+        bytes: &mut [u8],
+        concurrent_target: usize,
+    ) -> std::io::Result<()> {
+        match self {
+            Self::File { path, vox_offset } => {
+                let read_size = std::cmp::max(
+                    bytes.len().div_ceil(std::cmp::max(concurrent_target, 1)),
+                    MIN_READ_SIZE,
+                );
+                bytes
+                    .par_chunks_mut(read_size)
+                    .enumerate()
+                    .try_for_each(|(i, bytes)| {
+                        let mut file = File::open(&*path)?;
+                        file.seek(SeekFrom::Start(
+                            *vox_offset + offset + (i * read_size) as u64,
+                        ))?;
+                        file.read_exact(bytes)
+                    })
+            }
+            Self::Stream(reader) => reader.read_exact(bytes),
+        }
+    }
+}
+
+/// Import the `NIfTI` volume at `path` to a new array at `array_path` in `storage`.
+///
+/// The array is built with [`NiftiHeader::array_builder`].
+/// The metadata of the array is stored and the array is returned.
+///
+/// # Errors
+/// Returns an [`ImportError`] if the volume cannot be read or is unsupported, or there is an underlying array or store error.
+pub fn import_nifti<TStorage: ?Sized + WritableStorageTraits + 'static>(
+    path: impl AsRef<Path>,
+    storage: Arc<TStorage>,
+    array_path: &str,
+    options: &ImportOptions,
+) -> Result<Array<TStorage>, ImportError> {
+    let header = NiftiHeader::read_file(path.as_ref())?;
+    let builder = header.array_builder()?;
+    import(
+        path.as_ref(),
+        &header,
+        &builder,
+        storage,
+        array_path,
+        options,
+    )
+}
+
+/// Import the `NIfTI` volume at `path` to a new array at `array_path` in `storage` built with `builder`.
+///
+/// The array must have the shape and data type of the volume (see [`NiftiHeader::shape`] and [`NiftiHeader::data_type`]).
+/// [`NiftiHeader::array_builder`] is a convenient starting point for `builder`.
+/// The metadata of the array is stored and the array is returned.
+///
+/// # Errors
+/// Returns an [`ImportError`] if
+///  - the volume cannot be read or is unsupported,
+///  - the array cannot be built,
+///  - the shape or data type of the array differs from the volume, or
+///  - there is an underlying array or store error.
+pub fn import_nifti_with_builder<TStorage: ?Sized + WritableStorageTraits + 'static>(
+    path: impl AsRef<Path>,
+    builder: &ArrayBuilder,
+    storage: Arc<TStorage>,
+    array_path: &str,
+    options: &ImportOptions,
+) -> Result<Array<TStorage>, ImportError> {
+    let header = NiftiHeader::read_file(path.as_ref())?;
+    import(
+        path.as_ref(),
+        &header,
+        builder,
+        storage,
+        array_path,
+        options,
+    )
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn import<TStorage: ?Sized + WritableStorageTraits + 'static>(
+    path: &Path,
+    header: &NiftiHeader,
+    builder: &ArrayBuilder,
+    storage: Arc<TStorage>,
+    array_path: &str,
+    options: &ImportOptions,
+) -> Result<Array<TStorage>, ImportError> {
+    let data_type = header.data_type()?;
+    let dst = builder.build(storage, array_path)?;
+    if dst.shape() != header.shape() {
+        return Err(ImportError::IncompatibleShape(
+            header.shape(),
+            dst.shape().to_vec(),
+        ));
+    }
+    if dst.data_type().name() != data_type.name() {
+        return Err(ImportError::IncompatibleDataType(
+            data_type.name(),
+            dst.data_type().name(),
+        ));
+    }
+    dst.store_metadata()?;
+
+    let chunk_grid_shape = dst.chunk_grid_shape().ok_or_else(|| {
+        StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+    })?;
+    if chunk_grid_shape.contains(&0) {
+        return Ok(dst);
+    }
+
+    // Byte swap each component (e.g. the real and imaginary parts of complex numbers)
+    let component_size = match data_type {
+        DataType::Complex64 => 4,
+        DataType::Complex128 => 8,
+        DataType::RawBits(_) => 1,
+        _ => data_type.size(),
+    };
+    let swap = header.big_endian != cfg!(target_endian = "big") && component_size > 1;
+    let element_size = data_type.size();
+    let row_size = dst.shape()[1..].iter().product::<u64>() * element_size as u64;
+
+    // A slab is a row of chunks along the first dimension, which is contiguous in the volume
+    let slab_chunks = |slab: u64| {
+        let mut chunks_start = vec![0; chunk_grid_shape.len()];
+        chunks_start[0] = slab;
+        let mut chunks_end = chunk_grid_shape.clone();
+        chunks_end[0] = slab + 1;
+        unsafe { ArraySubset::new_with_start_end_exc_unchecked(chunks_start, chunks_end) }
+    };
+    let read_slab = |reader: &mut VoxelReader, slab: u64| -> Result<_, ImportError> {
+        let chunks = slab_chunks(slab);
+        let slab_subset = dst.chunks_subset(&chunks)?;
+        let slab_subset_bounded = unsafe { slab_subset.bound_unchecked(dst.shape()) };
+        let mut bytes = vec![0; slab_subset_bounded.num_elements_usize() * element_size];
+        reader.read(
+            slab_subset.start()[0] * row_size,
+            &mut bytes,
+            options.concurrent_target,
+        )?;
+        if swap {
+            bytes
+                .par_chunks_exact_mut(component_size)
+                .for_each(<[u8]>::reverse);
+        }
+        Ok((chunks, slab_subset, slab_subset_bounded, bytes))
+    };
+    let store_slab = |(chunks, slab_subset, slab_subset_bounded, mut bytes): (
+        ArraySubset,
+        ArraySubset,
+        ArraySubset,
+        Vec<u8>,
+    )|
+     -> Result<(), ImportError> {
+        if slab_subset_bounded != slab_subset {
+            // Pad edge chunks with the fill value
+            let mut bytes_padded = dst
+                .fill_value()
+                .as_ne_bytes()
+                .repeat(slab_subset.num_elements_usize());
+            unsafe {
+                slab_subset_bounded
+                    .relative_to_unchecked(slab_subset.start())
+                    .store_bytes_unchecked(
+                        &bytes,
+                        &mut bytes_padded,
+                        slab_subset.shape(),
+                        element_size,
+                    );
+            }
+            bytes = bytes_padded;
+        }
+        dst.store_chunks_opt(&chunks, bytes, &options.codec_options)?;
+        Ok(())
+    };
+
+    // Read the next slab while the current slab is encoded and stored
+    let mut reader = VoxelReader::new(path, header)?;
+    let mut slab = read_slab(&mut reader, 0)?;
+    for next in 1..chunk_grid_shape[0] {
+        let (stored, next_slab) = rayon::join(|| store_slab(slab), || read_slab(&mut reader, next));
+        stored?;
+        slab = next_slab?;
+    }
+    store_slab(slab)?;
+
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    /// Return a `NIfTI-1` file with dimensions `dim`, data type `datatype`, and voxel data `data`.
+    fn nifti1(dim: &[i16], datatype: i16, big_endian: bool, data: &[u8]) -> Vec<u8> {
+        let i16_bytes = |v: i16| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let f32_bytes = |v: f32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let mut bytes = vec![0; 352];
+        let sizeof_hdr = if big_endian {
+            348i32.to_be_bytes()
+        } else {
+            348i32.to_le_bytes()
+        };
+        bytes[0..4].copy_from_slice(&sizeof_hdr);
+        bytes[40..42].copy_from_slice(&i16_bytes(dim.len().try_into().unwrap()));
+        for (i, &size) in dim.iter().enumerate() {
+            bytes[42 + 2 * i..44 + 2 * i].copy_from_slice(&i16_bytes(size));
+            bytes[80 + 4 * i..84 + 4 * i]
+                .copy_from_slice(&f32_bytes(0.5 * f32::from(u8::try_from(i + 1).unwrap())));
+        }
+        bytes[70..72].copy_from_slice(&i16_bytes(datatype));
+        bytes[108..112].copy_from_slice(&f32_bytes(352.0));
+        bytes[112..116].copy_from_slice(&f32_bytes(1.0));
+        bytes[148..152].copy_from_slice(b"test");
+        bytes[344..348].copy_from_slice(b"n+1\0");
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn import_nifti1_little_endian() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("volume.nii");
+        let elements: Vec<u16> = (0..60).collect();
+        let data: Vec<u8> = elements.iter().flat_map(|v| v.to_le_bytes()).collect();
+        std::fs::write(&path, nifti1(&[5, 4, 3], 512, false, &data)).unwrap();
+
+        let header = NiftiHeader::read_file(&path).unwrap();
+        assert_eq!(header.dim, vec![5, 4, 3]);
+        assert_eq!(header.shape(), vec![3, 4, 5]);
+        assert_eq!(header.pixdim, vec![0.5, 1.0, 1.5]);
+        assert_eq!(header.descrip, "test");
+
+        let store = Arc::new(MemoryStore::new());
+        let mut builder = header.array_builder().unwrap();
+        builder.chunk_grid(vec![2, 3, 2].try_into().unwrap());
+        let array =
+            import_nifti_with_builder(&path, &builder, store, "/volume", &ImportOptions::default())
+                .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![3, 4, 5]))
+                .unwrap(),
+            elements
+        );
+        let metadata = serde_json::to_value(array.metadata()).unwrap();
+        assert_eq!(
+            metadata["dimension_names"],
+            serde_json::json!(["z", "y", "x"])
+        );
+        assert_eq!(
+            metadata["attributes"]["nifti"]["pixdim"],
+            serde_json::json!([0.5, 1.0, 1.5])
+        );
+    }
+
+    #[test]
+    fn import_nifti1_big_endian() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("volume.nii");
+        let elements: Vec<i32> = (-12..12).collect();
+        let data: Vec<u8> = elements.iter().flat_map(|v| v.to_be_bytes()).collect();
+        std::fs::write(&path, nifti1(&[3, 2, 2, 2], 8, true, &data)).unwrap();
+
+        let store = Arc::new(MemoryStore::new());
+        let array = import_nifti(&path, store, "/volume", &ImportOptions::default()).unwrap();
+        assert_eq!(array.shape(), &[2, 2, 2, 3]);
+        assert_eq!(array.data_type(), &DataType::Int32);
+        assert_eq!(
+            crate::array::chunk_shape_to_array_shape(&array.chunk_shape(&[0, 0, 0, 0]).unwrap()),
+            vec![1, 2, 2, 3]
+        );
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<i32>(&ArraySubset::new_with_shape(vec![
+                    2, 2, 2, 3
+                ]))
+                .unwrap(),
+            elements
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn import_nifti1_gzip() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("volume.nii.gz");
+        let elements: Vec<f32> = (0..70u8).map(|v| f32::from(v) / 2.0).collect();
+        let data: Vec<u8> = elements.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder
+            .write_all(&nifti1(&[7, 2, 5], 16, false, &data))
+            .unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let header = NiftiHeader::read_file(&path).unwrap();
+        let mut builder = header.array_builder().unwrap();
+        builder.chunk_grid(vec![2, 2, 4].try_into().unwrap());
+        let store = Arc::new(MemoryStore::new());
+        let array =
+            import_nifti_with_builder(&path, &builder, store, "/volume", &ImportOptions::default())
+                .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<f32>(&ArraySubset::new_with_shape(vec![5, 2, 7]))
+                .unwrap(),
+            elements
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn import_nifti2() {
+        let mut bytes = vec![0; 544];
+        bytes[0..4].copy_from_slice(&540i32.to_le_bytes());
+        bytes[4..12].copy_from_slice(b"n+2\0\r\n\x1a\n");
+        bytes[12..14].copy_from_slice(&1792i16.to_le_bytes());
+        for (i, size) in [2i64, 2, 2, 1].into_iter().enumerate() {
+            bytes[16 + 8 * i..24 + 8 * i].copy_from_slice(&size.to_le_bytes());
+        }
+        bytes[112..120].copy_from_slice(&2.5f64.to_le_bytes());
+        bytes[168..176].copy_from_slice(&544i64.to_le_bytes());
+        bytes[400..408].copy_from_slice(&(-1.0f64).to_le_bytes());
+        let elements: Vec<f64> = (0..8u8).map(f64::from).collect();
+        bytes.extend(elements.iter().flat_map(|v| v.to_le_bytes()));
+
+        let header = NiftiHeader::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(header.version, 2);
+        assert_eq!(header.dim, vec![2, 2]);
+        assert_eq!(header.pixdim, vec![2.5, 0.0]);
+        assert_eq!(header.srow[0], [-1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(header.data_type().unwrap(), DataType::Complex128);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("volume.nii");
+        std::fs::write(&path, &bytes).unwrap();
+        let array = import_nifti(
+            &path,
+            Arc::new(MemoryStore::new()),
+            "/volume",
+            &ImportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset(&ArraySubset::new_with_shape(vec![2, 2]))
+                .unwrap(),
+            bytes[544..]
+        );
+    }
+
+    #[test]
+    fn import_nifti_invalid() {
+        let mut bytes = nifti1(&[2, 2], 2, false, &[0; 4]);
+        bytes[344..348].copy_from_slice(b"ni1\0");
+        assert!(matches!(
+            NiftiHeader::read(&mut bytes.as_slice()),
+            Err(ImportError::Unsupported(_))
+        ));
+        bytes[344..348].copy_from_slice(b"abc\0");
+        assert!(matches!(
+            NiftiHeader::read(&mut bytes.as_slice()),
+            Err(ImportError::InvalidHeader(_))
+        ));
+        let bytes = nifti1(&[2, 2], 2048, false, &[0; 4]);
+        assert!(matches!(
+            NiftiHeader::read(&mut bytes.as_slice()),
+            Err(ImportError::Unsupported(_))
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("volume.nii");
+        std::fs::write(&path, nifti1(&[2, 2], 2, false, &[0; 4])).unwrap();
+        let header = NiftiHeader::read_file(&path).unwrap();
+        let mut builder = header.array_builder().unwrap();
+        builder.shape(vec![3, 2]);
+        assert!(matches!(
+            import_nifti_with_builder(
+                &path,
+                &builder,
+                Arc::new(MemoryStore::new()),
+                "/volume",
+                &ImportOptions::default()
+            ),
+            Err(ImportError::IncompatibleShape(_, _))
+        ));
+    }
+}
@@ -64,11 +64,18 @@
 //!  - Codecs: `blosc`, `gzip`, `transpose`, `zstd`, `sharding`, `crc32c`.
 //!
 //! #### Non-Default
+//!  - `arrow`: [Arrow](https://arrow.apache.org/) conversions for elements of an [`Array`](crate::array::Array) and record batches of the one-dimensional arrays in a [`Group`](group::Group).
 //!  - `async`: an experimental asynchronous API for [`stores`](storage), [`Array`](crate::array::Array), and [`Group`](group::Group).
 //!    - The async API is runtime-agnostic. This has some limitations that are detailed in the [`Array`](crate::array::Array) docs.
 //!    - The async API is not as performant as the sync API.
-//!  - Codecs: `bitround`, `bz2`, `pcodec`, `zfp`, `zstd`.
-//!  - Stores: `http`, `object_store`, `opendal`, `zip`.
+//!  - `ffi`: a C API for opening and creating arrays and groups, reading and writing array subsets, and listing hierarchies (see [`ffi`](crate::ffi)).
+//!  - `chrono`: [`chrono`] conversions for `numpy.datetime64` and `numpy.timedelta64` elements of an [`Array`](crate::array::Array).
+//!  - `n5`: [N5](https://github.com/saalfeldlab/n5) compatibility with the experimental `n5` codec (see [`n5`](crate::n5)).
+//!  - `python`: Python bindings with [NumPy](https://numpy.org/) array retrieval and storage (see [`python`](crate::python)).
+//!  - `tracing`: [`tracing`](https://docs.rs/tracing) spans for chunk storage operations, codec chain encoding/decoding, and chunk/array subset operations of an [`Array`](crate::array::Array).
+//!    - Spans have fields such as the store key, chunk indices, and sizes. Durations are available from the span timings of a subscriber.
+//!  - Codecs: `bitround`, `bz2`, `packbits`, `pcodec`, `quantize`, `zfp`, `zstd`.
+//!  - Stores: `azure`, `dedup`, `gcp`, `http`, `lmdb`, `mmap`, `object_store`, `opendal`, `parquet`, `zip`.
 //!
 //! ## Examples
 //! Examples can be run with `cargo run --example <EXAMPLE_NAME>`.
@@ -111,15 +118,37 @@
 
 pub mod array;
 pub mod array_subset;
+pub mod audit;
+pub mod bench;
 pub mod byte_range;
 pub mod config;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod group;
+pub mod hierarchy;
+pub mod import;
 pub mod metadata;
+pub mod multiscale;
+#[cfg(feature = "n5")]
+pub mod n5;
 pub mod node;
+pub mod ome;
+pub mod ops;
 pub mod plugin;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rechunk;
 pub mod storage;
 pub mod version;
 
+#[cfg(feature = "arrow")]
+/// Re-export [`arrow_array`].
+pub use arrow_array;
+
+#[cfg(feature = "arrow")]
+/// Re-export [`arrow_schema`].
+pub use arrow_schema;
+
 /// Re-export [`bytemuck`].
 pub use bytemuck;
 
@@ -282,8 +282,17 @@ impl AdditionalFields {
     /// # Errors
     /// Returns an [`UnsupportedAdditionalFieldError`] if an unsupported additional field is identified.
     pub fn validate(&self) -> Result<(), UnsupportedAdditionalFieldError> {
+        match self.unsupported_fields().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns an [`UnsupportedAdditionalFieldError`] for every unsupported additional field.
+    #[must_use]
+    pub fn unsupported_fields(&self) -> Vec<UnsupportedAdditionalFieldError> {
         fn is_unknown_field_allowed(field: &serde_json::Value) -> bool {
-            field.as_object().map_or(false, |value| {
+            field.as_object().is_some_and(|value| {
                 if value.contains_key("must_understand") {
                     let must_understand = &value["must_understand"];
                     match must_understand {
@@ -296,15 +305,14 @@ impl AdditionalFields {
             })
         }
 
-        for (key, value) in &self.0 {
-            if !is_unknown_field_allowed(value) {
-                return Err(UnsupportedAdditionalFieldError {
-                    name: key.to_string(),
-                    value: value.clone(),
-                });
-            }
-        }
-        Ok(())
+        self.0
+            .iter()
+            .filter(|(_, value)| !is_unknown_field_allowed(value))
+            .map(|(key, value)| UnsupportedAdditionalFieldError {
+                name: key.clone(),
+                value: value.clone(),
+            })
+            .collect()
     }
 
     /// Return the underlying map.
@@ -322,7 +330,7 @@ mod tests {
     fn metadata() {
         let metadata = Metadata::try_from(r#""bytes""#);
         assert!(metadata.is_ok());
-        assert_eq!(metadata.unwrap().to_string(), r#"bytes"#);
+        assert_eq!(metadata.unwrap().to_string(), "bytes");
         assert!(Metadata::try_from(r#"{ "name": "bytes" }"#).is_ok());
         let metadata =
             Metadata::try_from(r#"{ "name": "bytes", "configuration": { "endian": "little" } }"#);
@@ -0,0 +1,505 @@
+//! Multiscale (pyramid) generation.
+//!
+//! A [`MultiscaleBuilder`] derives a sequence of successively downsampled resolution levels from a base array.
+//! Each level is downsampled from the previous level by integer `factors` (one per dimension) with a [`DownsampleReducer`].
+//! The shape of a level is the shape of the previous level divided by the factors, rounded up, and all other array metadata (data type, chunk grid, codecs, etc.) is inherited from the base array.
+//!
+//! Levels are generated chunk by chunk: only the region of the previous level overlapping a chunk is retrieved, so memory usage is bounded by the chunk size rather than the array size.
+//! Chunks are processed in parallel with [`rayon`].
+//!
+//! Generation is resumable.
+//! Existing level metadata is reused (if its shape is as expected) and chunks which are already stored are skipped, so an interrupted [`generate`](MultiscaleBuilder::generate) can simply be called again.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::{array::{ArrayBuilder, DataType, FillValue}, array_subset::ArraySubset, storage::store::MemoryStore};
+//! # let store = Arc::new(MemoryStore::new());
+//! # let array = ArrayBuilder::new(vec![8, 8], DataType::UInt8, vec![4, 4].try_into()?, FillValue::from(0u8))
+//! #     .build(store.clone(), "/image/0")?;
+//! # array.store_metadata()?;
+//! # array.store_array_subset_elements(&ArraySubset::new_with_shape(vec![8, 8]), (0..64u8).collect())?;
+//! use zarrs::multiscale::{DownsampleReducer, MultiscaleBuilder};
+//!
+//! let levels = MultiscaleBuilder::new(vec![2, 2])
+//!     .reducer(DownsampleReducer::Mean)
+//!     .generate(store, "/image/0", &["/image/1", "/image/2"])?;
+//! assert_eq!(levels[0].shape(), &[4, 4]);
+//! assert_eq!(levels[1].shape(), &[2, 2]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use thiserror::Error;
+
+use crate::{
+    array::{
+        transmute_from_bytes_vec, transmute_to_bytes_vec, Array, ArrayCreateError, ArrayError,
+        ArrayMetadata, ArrayShape, DataType,
+    },
+    array_subset::ArraySubset,
+    storage::{ReadableWritableStorageTraits, StorageError},
+};
+
+/// A reducer mapping a downsampling window of elements to a single element.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DownsampleReducer {
+    /// The mean of the window.
+    ///
+    /// The mean is rounded to the nearest integer for integer data types.
+    /// Only integer and floating point data types are supported.
+    #[default]
+    Mean,
+    /// The most frequent element of the window.
+    ///
+    /// Ties are broken by the first occurrence in the window.
+    /// Suitable for label (segmentation) images.
+    Mode,
+    /// The first element of the window.
+    Nearest,
+}
+
+/// A multiscale builder.
+///
+/// Use [`generate`](MultiscaleBuilder::generate) to generate the resolution levels of a base array.
+#[derive(Clone, Debug)]
+pub struct MultiscaleBuilder {
+    factors: Vec<u64>,
+    reducer: DownsampleReducer,
+}
+
+impl MultiscaleBuilder {
+    /// Create a new multiscale builder with per-dimension downsampling `factors`.
+    ///
+    /// The reducer defaults to [`DownsampleReducer::Mean`].
+    #[must_use]
+    pub fn new(factors: Vec<u64>) -> Self {
+        Self {
+            factors,
+            reducer: DownsampleReducer::default(),
+        }
+    }
+
+    /// Set the downsampling reducer.
+    pub fn reducer(&mut self, reducer: DownsampleReducer) -> &mut Self {
+        self.reducer = reducer;
+        self
+    }
+
+    /// Return the per-dimension downsampling factors.
+    #[must_use]
+    pub fn factors(&self) -> &[u64] {
+        &self.factors
+    }
+
+    /// Generate the resolution levels of the array at `base_path` in `storage`.
+    ///
+    /// Level `i` is written to `level_paths[i]` and is downsampled from level `i - 1` (or the base array for the first level).
+    /// Returns the arrays of the generated levels.
+    ///
+    /// # Errors
+    /// Returns a [`MultiscaleError`] if
+    ///  - the base array cannot be opened,
+    ///  - the factors are incompatible with the base array,
+    ///  - the reducer does not support the data type of the base array,
+    ///  - an existing level has an unexpected shape, or
+    ///  - there is an underlying array or store error.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn generate<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+        &self,
+        storage: Arc<TStorage>,
+        base_path: &str,
+        level_paths: &[&str],
+    ) -> Result<Vec<Array<TStorage>>, MultiscaleError> {
+        let base = Array::new(storage.clone(), base_path)?;
+        self.validate(&base)?;
+
+        let mut levels: Vec<Array<TStorage>> = Vec::with_capacity(level_paths.len());
+        for level_path in level_paths {
+            let source = levels.last().unwrap_or(&base);
+            let level = self.create_level(storage.clone(), source, level_path)?;
+            self.downsample(source, &level)?;
+            levels.push(level);
+        }
+        Ok(levels)
+    }
+
+    fn validate<TStorage: ?Sized>(&self, base: &Array<TStorage>) -> Result<(), MultiscaleError> {
+        if self.factors.len() != base.dimensionality() || self.factors.contains(&0) {
+            return Err(MultiscaleError::InvalidFactors(
+                self.factors.clone(),
+                base.dimensionality(),
+            ));
+        }
+        if self.reducer == DownsampleReducer::Mean && !supports_mean(base.data_type()) {
+            return Err(MultiscaleError::UnsupportedDataType(
+                base.data_type().name(),
+                self.reducer,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Open the level at `path` if it exists, otherwise create it and store its metadata.
+    fn create_level<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+        &self,
+        storage: Arc<TStorage>,
+        source: &Array<TStorage>,
+        path: &str,
+    ) -> Result<Array<TStorage>, MultiscaleError> {
+        let shape: ArrayShape = std::iter::zip(source.shape(), &self.factors)
+            .map(|(size, factor)| size.div_ceil(*factor))
+            .collect();
+        match Array::new(storage.clone(), path) {
+            Ok(level) => {
+                if level.shape() == shape {
+                    Ok(level)
+                } else {
+                    Err(MultiscaleError::IncompatibleLevel(
+                        path.to_string(),
+                        shape,
+                        level.shape().to_vec(),
+                    ))
+                }
+            }
+            Err(ArrayCreateError::MissingMetadata) => {
+                let ArrayMetadata::V3(mut metadata) = source.metadata();
+                metadata.shape = shape;
+                let level = Array::new_with_metadata(storage, path, ArrayMetadata::V3(metadata))?;
+                level.store_metadata()?;
+                Ok(level)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Downsample `source` into the chunks of `target` which are not yet stored.
+    fn downsample<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+        &self,
+        source: &Array<TStorage>,
+        target: &Array<TStorage>,
+    ) -> Result<(), MultiscaleError> {
+        let chunk_grid_shape = target.chunk_grid_shape().ok_or_else(|| {
+            StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+        })?;
+        let chunks = ArraySubset::new_with_shape(chunk_grid_shape);
+        let indices = chunks.indices();
+        indices.into_par_iter().try_for_each(|chunk_indices| {
+            if target.chunk_exists(&chunk_indices)? {
+                return Ok(());
+            }
+            let target_subset = target.chunk_subset_bounded(&chunk_indices)?;
+            let source_subset = self.source_subset(&target_subset, source.shape());
+            let source_bytes = source.retrieve_array_subset(&source_subset)?;
+            let target_bytes = self.reduce(
+                source_bytes,
+                source_subset.shape(),
+                target_subset.num_elements_usize(),
+                source.data_type(),
+            )?;
+            target.store_array_subset(&target_subset, target_bytes)?;
+            Ok::<_, MultiscaleError>(())
+        })
+    }
+
+    /// Return the subset of the source level covered by `target_subset`, bounded by `source_shape`.
+    fn source_subset(&self, target_subset: &ArraySubset, source_shape: &[u64]) -> ArraySubset {
+        let ranges: Vec<_> = itertools::izip!(
+            target_subset.start(),
+            target_subset.end_exc(),
+            &self.factors,
+            source_shape
+        )
+        .map(|(start, end, factor, size)| start * factor..std::cmp::min(end * factor, *size))
+        .collect();
+        ArraySubset::new_with_ranges(&ranges)
+    }
+
+    /// Reduce the elements of a source region with shape `source_shape` to `num_elements` target elements.
+    fn reduce(
+        &self,
+        source_bytes: Vec<u8>,
+        source_shape: &[u64],
+        num_elements: usize,
+        data_type: &DataType,
+    ) -> Result<Vec<u8>, MultiscaleError> {
+        let element_size = data_type.size();
+        let windows = self.windows(source_shape, num_elements);
+        match self.reducer {
+            DownsampleReducer::Nearest => Ok(windows
+                .iter()
+                .flat_map(|window| element(&source_bytes, window[0], element_size))
+                .copied()
+                .collect()),
+            DownsampleReducer::Mode => Ok(windows
+                .iter()
+                .flat_map(|window| mode(&source_bytes, window, element_size))
+                .copied()
+                .collect()),
+            DownsampleReducer::Mean => {
+                macro_rules! mean {
+                    ( $t:ty, $to_f64:expr, $from_f64:expr ) => {{
+                        let elements = transmute_from_bytes_vec::<$t>(source_bytes);
+                        let mean: Vec<$t> = windows
+                            .iter()
+                            .map(|window| {
+                                let sum: f64 = window.iter().map(|i| $to_f64(elements[*i])).sum();
+                                $from_f64(sum / window.len() as f64)
+                            })
+                            .collect();
+                        Ok(transmute_to_bytes_vec(mean))
+                    }};
+                }
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_precision_loss,
+                    clippy::cast_sign_loss
+                )]
+                match data_type {
+                    DataType::Int8 => mean!(i8, f64::from, |v: f64| v.round() as i8),
+                    DataType::Int16 => mean!(i16, f64::from, |v: f64| v.round() as i16),
+                    DataType::Int32 => mean!(i32, f64::from, |v: f64| v.round() as i32),
+                    DataType::Int64 => mean!(i64, |v: i64| v as f64, |v: f64| v.round() as i64),
+                    DataType::UInt8 => mean!(u8, f64::from, |v: f64| v.round() as u8),
+                    DataType::UInt16 => mean!(u16, f64::from, |v: f64| v.round() as u16),
+                    DataType::UInt32 => mean!(u32, f64::from, |v: f64| v.round() as u32),
+                    DataType::UInt64 => mean!(u64, |v: u64| v as f64, |v: f64| v.round() as u64),
+                    DataType::Float16 => mean!(half::f16, half::f16::to_f64, half::f16::from_f64),
+                    DataType::BFloat16 => {
+                        mean!(half::bf16, half::bf16::to_f64, half::bf16::from_f64)
+                    }
+                    DataType::Float32 => mean!(f32, f64::from, |v: f64| v as f32),
+                    DataType::Float64 => mean!(f64, |v: f64| v, |v: f64| v),
+                    _ => Err(MultiscaleError::UnsupportedDataType(
+                        data_type.name(),
+                        self.reducer,
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Return the source element indices of each target element window, in order.
+    ///
+    /// Source elements are in C order within a region with shape `source_shape`.
+    fn windows(&self, source_shape: &[u64], num_elements: usize) -> Vec<Vec<usize>> {
+        let target_shape: Vec<u64> = std::iter::zip(source_shape, &self.factors)
+            .map(|(size, factor)| size.div_ceil(*factor))
+            .collect();
+        let mut windows = vec![Vec::new(); num_elements];
+        for (source_index, indices) in ArraySubset::new_with_shape(source_shape.to_vec())
+            .indices()
+            .into_iter()
+            .enumerate()
+        {
+            let target_index = itertools::izip!(&indices, &self.factors, &target_shape)
+                .fold(0, |acc, (index, factor, size)| acc * size + index / factor);
+            #[allow(clippy::cast_possible_truncation)]
+            windows[target_index as usize].push(source_index);
+        }
+        windows
+    }
+}
+
+fn supports_mean(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float16
+            | DataType::BFloat16
+            | DataType::Float32
+            | DataType::Float64
+    )
+}
+
+fn element(bytes: &[u8], index: usize, element_size: usize) -> &[u8] {
+    &bytes[index * element_size..(index + 1) * element_size]
+}
+
+/// Return the most frequent element of a window, ties are broken by the first occurrence.
+fn mode<'a>(bytes: &'a [u8], window: &[usize], element_size: usize) -> &'a [u8] {
+    let mut mode = element(bytes, window[0], element_size);
+    let mut mode_count = 0;
+    for (i, index) in window.iter().enumerate() {
+        let candidate = element(bytes, *index, element_size);
+        let count = window[i..]
+            .iter()
+            .filter(|index| element(bytes, **index, element_size) == candidate)
+            .count();
+        if count > mode_count {
+            mode = candidate;
+            mode_count = count;
+        }
+    }
+    mode
+}
+
+/// A multiscale generation error.
+#[derive(Debug, Error)]
+pub enum MultiscaleError {
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// The downsampling factors are invalid.
+    #[error("downsampling factors {0:?} are invalid for an array with dimensionality {1}, factors must be non-zero")]
+    InvalidFactors(Vec<u64>, usize),
+    /// The data type is not supported by the reducer.
+    #[error("data type {0} is not supported by the {1:?} reducer")]
+    UnsupportedDataType(String, DownsampleReducer),
+    /// An existing level has an unexpected shape.
+    #[error("existing level {0} has shape {2:?}, expected {1:?}")]
+    IncompatibleLevel(String, ArrayShape, ArrayShape),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn base_array(store: &Arc<MemoryStore>, data_type: DataType) -> Array<MemoryStore> {
+        let fill_value = FillValue::new(vec![0; data_type.size()]);
+        let array = ArrayBuilder::new(
+            vec![5, 6],
+            data_type,
+            vec![2, 2].try_into().unwrap(),
+            fill_value,
+        )
+        .build(store.clone(), "/0")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+    }
+
+    #[test]
+    fn multiscale_mean() {
+        let store = Arc::new(MemoryStore::new());
+        let array = base_array(&store, DataType::UInt16);
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![5, 6]),
+                (0..30).collect(),
+            )
+            .unwrap();
+
+        let levels = MultiscaleBuilder::new(vec![2, 3])
+            .generate(store.clone(), "/0", &["/1", "/2"])
+            .unwrap();
+        assert_eq!(levels[0].shape(), &[3, 2]);
+        assert_eq!(levels[1].shape(), &[2, 1]);
+        let elements_1 = levels[0]
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![3, 2]))
+            .unwrap();
+        // [[0, 1, 2, 6, 7, 8], ...], the last row is a partial window [[24, 25, 26], [27, 28, 29]]
+        assert_eq!(elements_1, vec![4, 7, 16, 19, 25, 28]);
+        let elements_2 = levels[1]
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![2, 1]))
+            .unwrap();
+        assert_eq!(elements_2, vec![12, 27]);
+    }
+
+    #[test]
+    fn multiscale_mode_nearest() {
+        let store = Arc::new(MemoryStore::new());
+        let array = base_array(&store, DataType::Int8);
+        #[rustfmt::skip]
+        let elements: Vec<i8> = vec![
+            1, 2, 3, 3, 5, 6,
+            2, 2, 4, 3, 6, 5,
+            7, 7, 7, 7, 7, 7,
+            7, 8, 9, 9, 7, 7,
+            1, 1, 1, 2, 2, 2,
+        ];
+        array
+            .store_array_subset_elements(&ArraySubset::new_with_shape(vec![5, 6]), elements)
+            .unwrap();
+
+        let mode = MultiscaleBuilder::new(vec![2, 2])
+            .reducer(DownsampleReducer::Mode)
+            .generate(store.clone(), "/0", &["/mode"])
+            .unwrap();
+        let mode = mode[0]
+            .retrieve_array_subset_elements::<i8>(&ArraySubset::new_with_shape(vec![3, 3]))
+            .unwrap();
+        assert_eq!(mode, vec![2, 3, 5, 7, 7, 7, 1, 1, 2]);
+
+        let nearest = MultiscaleBuilder::new(vec![2, 2])
+            .reducer(DownsampleReducer::Nearest)
+            .generate(store.clone(), "/0", &["/nearest"])
+            .unwrap();
+        let nearest = nearest[0]
+            .retrieve_array_subset_elements::<i8>(&ArraySubset::new_with_shape(vec![3, 3]))
+            .unwrap();
+        assert_eq!(nearest, vec![1, 3, 5, 7, 7, 7, 1, 1, 2]);
+    }
+
+    #[test]
+    fn multiscale_resume() {
+        let store = Arc::new(MemoryStore::new());
+        let array = base_array(&store, DataType::Float32);
+        array
+            .store_array_subset_elements::<f32>(
+                &ArraySubset::new_with_shape(vec![5, 6]),
+                vec![1.0; 30],
+            )
+            .unwrap();
+
+        let builder = MultiscaleBuilder::new(vec![2, 2]);
+        let levels = builder.generate(store.clone(), "/0", &["/1"]).unwrap();
+        // Overwrite a chunk, it is not regenerated on resume
+        levels[0]
+            .store_chunk_elements::<f32>(&[0, 0], vec![2.0; 4])
+            .unwrap();
+        levels[0].erase_chunk(&[1, 1]).unwrap();
+        let levels = builder.generate(store.clone(), "/0", &["/1"]).unwrap();
+        let elements = levels[0]
+            .retrieve_array_subset_elements::<f32>(&ArraySubset::new_with_shape(vec![3, 3]))
+            .unwrap();
+        assert_eq!(elements, vec![2.0, 2.0, 1.0, 2.0, 2.0, 1.0, 1.0, 1.0, 1.0]);
+
+        // An existing level with an incompatible shape
+        assert!(matches!(
+            MultiscaleBuilder::new(vec![3, 3]).generate(store.clone(), "/0", &["/1"]),
+            Err(MultiscaleError::IncompatibleLevel(..))
+        ));
+    }
+
+    #[test]
+    fn multiscale_invalid() {
+        let store = Arc::new(MemoryStore::new());
+        base_array(&store, DataType::Bool);
+        assert!(matches!(
+            MultiscaleBuilder::new(vec![2]).generate(store.clone(), "/0", &["/1"]),
+            Err(MultiscaleError::InvalidFactors(..))
+        ));
+        assert!(matches!(
+            MultiscaleBuilder::new(vec![2, 0]).generate(store.clone(), "/0", &["/1"]),
+            Err(MultiscaleError::InvalidFactors(..))
+        ));
+        assert!(matches!(
+            MultiscaleBuilder::new(vec![2, 2]).generate(store.clone(), "/0", &["/1"]),
+            Err(MultiscaleError::UnsupportedDataType(..))
+        ));
+        assert!(MultiscaleBuilder::new(vec![2, 2])
+            .reducer(DownsampleReducer::Mode)
+            .generate(store, "/0", &["/1"])
+            .is_ok());
+    }
+}
@@ -0,0 +1,319 @@
+//! [N5](https://github.com/saalfeldlab/n5) compatibility.
+//!
+//! An N5 dataset can be opened as an [`Array`] with [`open_array`] (or [`async_open_array`]).
+//! The `attributes.json` of the dataset is read and converted to Zarr V3 array metadata with:
+//!  - the N5 `dimensions` as the array shape and the N5 `blockSize` as a regular chunk grid,
+//!  - a [`v2`](V2ChunkKeyEncoding) chunk key encoding with a `/` separator, matching the paths of N5 blocks,
+//!  - a `transpose` codec that reverses the dimensions followed by an [`n5`](N5Codec) codec with the N5 `compression`,
+//!  - a fill value of zero, and
+//!  - the remaining N5 attributes as the array attributes.
+//!
+//! Arrays are presented in N5 dimension order, so the element at N5 coordinates `[x, y, z]` is at array indices `[x, y, z]`.
+//! The converted metadata is not written to the store.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::storage::{store::MemoryStore, WritableStorageTraits};
+//! # let store = Arc::new(MemoryStore::new());
+//! # store.set(
+//! #     &"dataset/attributes.json".try_into()?,
+//! #     br#"{"dimensions":[5,3],"blockSize":[4,2],"dataType":"uint16","compression":{"type":"raw"}}"#,
+//! # )?;
+//! let array = zarrs::n5::open_array(store, "/dataset")?;
+//! assert_eq!(array.shape(), &[5, 3]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    array::{
+        chunk_grid::{ChunkGridTraits, RegularChunkGrid},
+        chunk_key_encoding::{ChunkKeyEncodingTraits, V2ChunkKeyEncoding},
+        codec::{
+            array_to_array::transpose::TransposeOrder, CodecTraits, N5Codec, N5Compression,
+            TransposeCodec,
+        },
+        Array, ArrayCreateError, ArrayMetadata, ArrayMetadataV3, ChunkShape, DataType, FillValue,
+        MaybeBytes,
+    },
+    metadata::AdditionalFields,
+    node::{NodePath, NodePathError},
+    storage::{ReadableStorageTraits, StorageError, StoreKey},
+};
+
+#[cfg(feature = "async")]
+use crate::storage::AsyncReadableStorageTraits;
+
+/// The attributes of an N5 dataset, stored in its `attributes.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct N5DatasetAttributes {
+    /// The shape of the dataset.
+    pub dimensions: Vec<u64>,
+    /// The shape of a block.
+    #[serde(rename = "blockSize")]
+    pub block_size: Vec<u64>,
+    /// The data type.
+    #[serde(rename = "dataType")]
+    pub data_type: N5DataType,
+    /// The compression of blocks.
+    pub compression: N5Compression,
+    /// Other (user) attributes.
+    #[serde(flatten)]
+    pub attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An N5 data type.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum N5DataType {
+    /// `uint8`
+    Uint8,
+    /// `uint16`
+    Uint16,
+    /// `uint32`
+    Uint32,
+    /// `uint64`
+    Uint64,
+    /// `int8`
+    Int8,
+    /// `int16`
+    Int16,
+    /// `int32`
+    Int32,
+    /// `int64`
+    Int64,
+    /// `float32`
+    Float32,
+    /// `float64`
+    Float64,
+    /// `object`, which is not supported.
+    Object,
+}
+
+impl N5DataType {
+    /// Return the equivalent Zarr data type, or [`None`] if unsupported.
+    #[must_use]
+    pub const fn to_data_type(self) -> Option<DataType> {
+        match self {
+            Self::Uint8 => Some(DataType::UInt8),
+            Self::Uint16 => Some(DataType::UInt16),
+            Self::Uint32 => Some(DataType::UInt32),
+            Self::Uint64 => Some(DataType::UInt64),
+            Self::Int8 => Some(DataType::Int8),
+            Self::Int16 => Some(DataType::Int16),
+            Self::Int32 => Some(DataType::Int32),
+            Self::Int64 => Some(DataType::Int64),
+            Self::Float32 => Some(DataType::Float32),
+            Self::Float64 => Some(DataType::Float64),
+            Self::Object => None,
+        }
+    }
+}
+
+impl N5DatasetAttributes {
+    /// Convert the N5 dataset attributes to Zarr V3 array metadata.
+    ///
+    /// # Errors
+    /// Returns an [`N5Error`] if the attributes are invalid or the data type is not supported.
+    pub fn to_array_metadata(&self) -> Result<ArrayMetadata, N5Error> {
+        if self.dimensions.len() != self.block_size.len() {
+            return Err(N5Error::InvalidAttributes(
+                "dimensions and blockSize have different lengths".to_string(),
+            ));
+        }
+        let data_type = self
+            .data_type
+            .to_data_type()
+            .ok_or(N5Error::UnsupportedDataType(self.data_type))?;
+        let chunk_shape = ChunkShape::try_from(self.block_size.clone()).map_err(|_| {
+            N5Error::InvalidAttributes("blockSize must not contain zeros".to_string())
+        })?;
+        let fill_value = FillValue::new(vec![0; data_type.size()]);
+
+        let dimensionality = self.dimensions.len();
+        let mut codecs = Vec::with_capacity(2);
+        if dimensionality > 1 {
+            let order = TransposeOrder((0..dimensionality).rev().collect());
+            codecs.extend(TransposeCodec::new(order).create_metadata());
+        }
+        codecs.extend(N5Codec::new(self.compression.clone()).create_metadata());
+
+        Ok(ArrayMetadata::V3(ArrayMetadataV3::new(
+            self.dimensions.clone(),
+            data_type.metadata(),
+            RegularChunkGrid::new(chunk_shape).create_metadata(),
+            V2ChunkKeyEncoding::new_slash().create_metadata(),
+            data_type.metadata_fill_value(&fill_value),
+            codecs,
+            self.attributes.clone(),
+            vec![],
+            None,
+            AdditionalFields::default(),
+        )))
+    }
+}
+
+/// Return the key of the N5 `attributes.json` of the node at `path`.
+#[must_use]
+pub fn attributes_key(path: &NodePath) -> StoreKey {
+    let path = path.as_str();
+    if path.eq("/") {
+        unsafe { StoreKey::new_unchecked("attributes.json".to_string()) }
+    } else {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        unsafe { StoreKey::new_unchecked(path.to_string() + "/attributes.json") }
+    }
+}
+
+fn parse_attributes(key: StoreKey, attributes: MaybeBytes) -> Result<N5DatasetAttributes, N5Error> {
+    let attributes = attributes.ok_or(N5Error::MissingAttributes(key))?;
+    serde_json::from_slice(&attributes).map_err(|err| N5Error::InvalidAttributes(err.to_string()))
+}
+
+/// Open the N5 dataset at `path` in `storage` as an [`Array`].
+///
+/// # Errors
+/// Returns an [`N5Error`] if the `attributes.json` of the dataset is missing or invalid, or the array cannot be created.
+pub fn open_array<TStorage: ?Sized + ReadableStorageTraits>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Array<TStorage>, N5Error> {
+    let node_path = NodePath::new(path)?;
+    let key = attributes_key(&node_path);
+    let attributes = parse_attributes(key.clone(), storage.get(&key)?)?;
+    Ok(Array::new_with_metadata(
+        storage,
+        path,
+        attributes.to_array_metadata()?,
+    )?)
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously open the N5 dataset at `path` in `storage` as an [`Array`].
+///
+/// # Errors
+/// Returns an [`N5Error`] if the `attributes.json` of the dataset is missing or invalid, or the array cannot be created.
+pub async fn async_open_array<TStorage: ?Sized + AsyncReadableStorageTraits>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Array<TStorage>, N5Error> {
+    let node_path = NodePath::new(path)?;
+    let key = attributes_key(&node_path);
+    let attributes = parse_attributes(key.clone(), storage.get(&key).await?)?;
+    Ok(Array::new_with_metadata(
+        storage,
+        path,
+        attributes.to_array_metadata()?,
+    )?)
+}
+
+/// An N5 error.
+#[derive(Debug, Error)]
+pub enum N5Error {
+    /// An invalid node path.
+    #[error(transparent)]
+    NodePathError(#[from] NodePathError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// The `attributes.json` is missing.
+    #[error("N5 attributes {0} are missing")]
+    MissingAttributes(StoreKey),
+    /// The `attributes.json` is not valid N5 dataset attributes.
+    #[error("N5 dataset attributes are invalid: {0}")]
+    InvalidAttributes(String),
+    /// The data type is not supported.
+    #[error("N5 data type {0:?} is not supported")]
+    UnsupportedDataType(N5DataType),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array_subset::ArraySubset,
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    /// Encode an uncompressed N5 block with `shape` in N5 dimension order and column-major `elements`.
+    fn n5_block(shape: &[u32], elements: &[u16]) -> Vec<u8> {
+        let mut block = vec![0, 0];
+        block.extend(u16::try_from(shape.len()).unwrap().to_be_bytes());
+        block.extend(shape.iter().flat_map(|size| size.to_be_bytes()));
+        block.extend(elements.iter().flat_map(|element| element.to_be_bytes()));
+        block
+    }
+
+    #[test]
+    fn n5_open_array() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::new());
+        store.set(
+            &"dataset/attributes.json".try_into()?,
+            br#"{"dimensions":[5,3],"blockSize":[4,2],"dataType":"uint16","compression":{"type":"raw"},"units":"nm"}"#,
+        )?;
+
+        // The element at N5 coordinates [x, y] is 10 * x + y
+        let value = |x: u32, y: u32| u16::try_from(10 * x + y).unwrap();
+        let block = |x0: u32, y0: u32, nx: u32, ny: u32| {
+            let elements: Vec<u16> = (y0..y0 + ny)
+                .flat_map(|y| (x0..x0 + nx).map(move |x| value(x, y)))
+                .collect();
+            n5_block(&[nx, ny], &elements)
+        };
+        store.set(&"dataset/0/0".try_into()?, &block(0, 0, 4, 2))?;
+        store.set(&"dataset/1/0".try_into()?, &block(4, 0, 1, 2))?;
+        store.set(&"dataset/0/1".try_into()?, &block(0, 2, 4, 1))?;
+        // dataset/1/1 is missing
+
+        let array = open_array(store, "/dataset")?;
+        assert_eq!(array.shape(), &[5, 3]);
+        assert_eq!(array.data_type(), &DataType::UInt16);
+        assert_eq!(array.attributes()["units"], "nm");
+
+        let elements = array
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![5, 3]))?;
+        let expected: Vec<u16> = (0..5)
+            .flat_map(|x| (0..3).map(move |y| if x == 4 && y == 2 { 0 } else { value(x, y) }))
+            .collect();
+        assert_eq!(elements, expected);
+
+        let elements = array
+            .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_ranges(&[3..5, 1..2]))?;
+        assert_eq!(elements, vec![value(3, 1), value(4, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn n5_open_array_invalid() {
+        let store = Arc::new(MemoryStore::new());
+        assert!(matches!(
+            open_array(store.clone(), "/dataset"),
+            Err(N5Error::MissingAttributes(_))
+        ));
+        store
+            .set(
+                &"dataset/attributes.json".try_into().unwrap(),
+                br#"{"dimensions":[5],"blockSize":[4],"dataType":"object","compression":{"type":"raw"}}"#,
+            )
+            .unwrap();
+        assert!(matches!(
+            open_array(store.clone(), "/dataset"),
+            Err(N5Error::UnsupportedDataType(N5DataType::Object))
+        ));
+        store
+            .set(&"attributes.json".try_into().unwrap(), br#"{"n5":"2.0.0"}"#)
+            .unwrap();
+        assert!(matches!(
+            open_array(store, "/"),
+            Err(N5Error::InvalidAttributes(_))
+        ));
+    }
+}
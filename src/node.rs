@@ -5,6 +5,7 @@
 //! A [`Node`] has an associated [`NodePath`], [`NodeMetadata`], and children.
 //!
 //! The [`Node::hierarchy_tree`] function can be used to create a string representation of a the hierarchy below a node.
+//! The [`Node::tree`] function additionally annotates nodes with chunking, codecs, and storage statistics.
 
 mod node_metadata;
 mod node_name;
@@ -16,10 +17,12 @@ pub use node_path::{NodePath, NodePathError};
 use thiserror::Error;
 
 use crate::{
-    array::ArrayMetadata,
+    array::{chunk_grid::RegularChunkGridConfiguration, ArrayMetadata, DataType},
     group::GroupMetadataV3,
+    metadata::Metadata,
     storage::{
         get_child_nodes, meta_key, ListableStorageTraits, ReadableStorageTraits, StorageError,
+        StorePrefix,
     },
 };
 
@@ -68,7 +71,7 @@ impl Node {
         let key = meta_key(&path);
         let metadata = storage.get(&key)?;
         let metadata: NodeMetadata = match metadata {
-            Some(metadata) => serde_json::from_slice(metadata.as_slice()).map_err(|e| {
+            Some(metadata) => serde_json::from_slice(&metadata).map_err(|e| {
                 NodeCreateError::StorageError(StorageError::InvalidMetadata(key, e.to_string()))
             })?,
             None => NodeMetadata::Group(GroupMetadataV3::default().into()),
@@ -101,7 +104,7 @@ impl Node {
         let key = meta_key(&path);
         let metadata = storage.get(&key).await?;
         let metadata: NodeMetadata = match metadata {
-            Some(metadata) => serde_json::from_slice(metadata.as_slice()).map_err(|e| {
+            Some(metadata) => serde_json::from_slice(&metadata).map_err(|e| {
                 NodeCreateError::StorageError(StorageError::InvalidMetadata(key, e.to_string()))
             })?,
             None => NodeMetadata::Group(GroupMetadataV3::default().into()),
@@ -137,7 +140,12 @@ impl Node {
     /// Returns the name of the node.
     #[must_use]
     pub fn name(&self) -> NodeName {
-        let name = self.path.as_str().split('/').last().unwrap_or_default();
+        let name = self
+            .path
+            .as_str()
+            .split('/')
+            .next_back()
+            .unwrap_or_default();
         unsafe { NodeName::new_unchecked(name) }
     }
 
@@ -184,7 +192,7 @@ impl Node {
                 NodeMetadata::Group(_) => {
                     string.push_str(name);
                 }
-            };
+            }
             string.push('\n');
         }
 
@@ -202,6 +210,87 @@ impl Node {
         update_tree(&mut string, &self.children, 1);
         string
     }
+
+    /// Return a tree representation of a hierarchy annotated with node statistics as a string.
+    ///
+    /// Arrays are annotated with their shape, data type, chunk shape, codecs, and their stored and logical size in bytes.
+    /// The logical size is `?` if the data type is unsupported or the size exceeds [`u64::MAX`].
+    /// Groups are annotated with their stored size in bytes.
+    /// Stored sizes are retrieved with [`ReadableStorageTraits::size_prefix`] and include metadata.
+    /// For example:
+    /// ```text
+    /// / stored 1418 bytes
+    ///   a stored 1274 bytes
+    ///     baz [10000, 1000] float64 chunks [1000, 100] codecs [bytes, gzip] stored 691 bytes, logical 80000000 bytes
+    ///     foo [10000, 1000] float64 chunks [1000, 100] codecs [gzip] stored 583 bytes, logical 80000000 bytes
+    ///   b stored 98 bytes
+    /// ```
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the store does not support `size()` or there is an underlying error with the store.
+    pub fn tree<TStorage: ?Sized + ReadableStorageTraits>(
+        &self,
+        storage: &TStorage,
+    ) -> Result<String, StorageError> {
+        fn print_node<TStorage: ?Sized + ReadableStorageTraits>(
+            storage: &TStorage,
+            string: &mut String,
+            name: &str,
+            node: &Node,
+            depth: usize,
+        ) -> Result<(), StorageError> {
+            let prefix: StorePrefix = (&node.path).try_into()?;
+            let stored = storage.size_prefix(&prefix)?;
+            string.push_str(&" ".repeat(depth * 2));
+            match &node.metadata {
+                NodeMetadata::Array(array_metadata) => {
+                    let ArrayMetadata::V3(array_metadata) = array_metadata;
+                    let chunk_shape = array_metadata
+                        .chunk_grid
+                        .to_configuration::<RegularChunkGridConfiguration>()
+                        .map_or_else(
+                            |_| array_metadata.chunk_grid.name().to_string(),
+                            |configuration| format!("{:?}", configuration.chunk_shape.as_slice()),
+                        );
+                    let codecs = array_metadata
+                        .codecs
+                        .iter()
+                        .map(Metadata::name)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    // The logical size is unknown if the data type is unsupported or the size overflows
+                    let logical = DataType::from_metadata(&array_metadata.data_type)
+                        .ok()
+                        .and_then(|data_type| {
+                            array_metadata
+                                .shape
+                                .iter()
+                                .try_fold(data_type.size() as u64, |size, &length| {
+                                    size.checked_mul(length)
+                                })
+                        })
+                        .map_or_else(|| "?".to_string(), |logical| logical.to_string());
+                    let s = format!(
+                        "{name} {:?} {} chunks {chunk_shape} codecs [{codecs}] stored {stored} bytes, logical {logical} bytes\n",
+                        array_metadata.shape, array_metadata.data_type
+                    );
+                    string.push_str(&s);
+                }
+                NodeMetadata::Group(_) => {
+                    let s = format!("{name} stored {stored} bytes\n");
+                    string.push_str(&s);
+                }
+            }
+            for child in &node.children {
+                print_node(storage, string, child.name().as_str(), child, depth + 1)?;
+            }
+            Ok(())
+        }
+
+        let mut string = String::default();
+        print_node(storage, &mut string, self.path.as_str(), self, 0)?;
+        Ok(string)
+    }
 }
 
 #[cfg(test)]
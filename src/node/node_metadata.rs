@@ -3,6 +3,7 @@ use crate::{array::ArrayMetadata, group::GroupMetadata};
 /// Node metadata ([`ArrayMetadata`] or [`GroupMetadata`]).
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum NodeMetadata {
     /// Array metadata.
     Array(ArrayMetadata),
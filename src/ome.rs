@@ -0,0 +1,607 @@
+//! [OME-NGFF](https://ngff.openmicroscopy.org/latest/) (OME-Zarr) multiscale images.
+//!
+//! An OME-NGFF multiscale image is a group with `multiscales` metadata in its attributes (or nested under `ome` as of version 0.5).
+//! Each multiscale has a list of [axes](OmeAxis) and a list of [datasets](OmeDataset) (the resolution levels, from highest to lowest resolution), which are arrays at a path relative to the group.
+//!
+//! [`read_multiscales`] reads and [validates](OmeMultiscale::validate) the multiscales of a group, and [`open_multiscale`] and [`open_multiscale_level`] open the resolution levels of its first multiscale as [`Array`]s.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::{array::{ArrayBuilder, DataType, FillValue}, group::GroupBuilder, storage::store::MemoryStore};
+//! # let store = Arc::new(MemoryStore::new());
+//! # let mut group = GroupBuilder::new().build(store.clone(), "/image")?;
+//! # group.attributes_mut().insert("multiscales".to_string(), serde_json::json!([{
+//! #     "version": "0.4",
+//! #     "axes": [{"name": "y", "type": "space", "unit": "micrometer"}, {"name": "x", "type": "space", "unit": "micrometer"}],
+//! #     "datasets": [
+//! #         {"path": "0", "coordinateTransformations": [{"type": "scale", "scale": [0.5, 0.5]}]},
+//! #         {"path": "1", "coordinateTransformations": [{"type": "scale", "scale": [1.0, 1.0]}]}
+//! #     ]
+//! # }]));
+//! # group.store_metadata()?;
+//! # for (path, size) in [("/image/0", 8), ("/image/1", 4)] {
+//! #     ArrayBuilder::new(vec![size, size], DataType::UInt8, vec![4, 4].try_into()?, FillValue::from(0u8))
+//! #         .build(store.clone(), path)?
+//! #         .store_metadata()?;
+//! # }
+//! use zarrs::ome;
+//!
+//! let multiscales = ome::read_multiscales(store.clone(), "/image")?;
+//! assert_eq!(multiscales[0].scale(1), Some(vec![1.0, 1.0]));
+//! let levels = ome::open_multiscale(store.clone(), "/image")?;
+//! assert_eq!(levels[1].shape(), &[4, 4]);
+//! let lowest_resolution = ome::open_multiscale_level(store, "/image", 1)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    array::{Array, ArrayCreateError},
+    group::{Group, GroupCreateError},
+    node::NodePath,
+    storage::ReadableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use crate::storage::AsyncReadableStorageTraits;
+
+/// An OME-NGFF axis.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OmeAxis {
+    /// The name of the axis.
+    pub name: String,
+    /// The type of the axis.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub axis_type: Option<OmeAxisType>,
+    /// The unit of the axis, e.g. `micrometer` or `second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// The type of an OME-NGFF axis.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OmeAxisType {
+    /// A space axis.
+    Space,
+    /// A time axis.
+    Time,
+    /// A channel axis.
+    Channel,
+    /// A custom axis type.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+/// An OME-NGFF coordinate transformation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum OmeCoordinateTransformation {
+    /// The identity transformation.
+    Identity,
+    /// A scale transformation.
+    Scale {
+        /// The scale of each axis.
+        scale: Vec<f64>,
+    },
+    /// A translation transformation.
+    Translation {
+        /// The translation of each axis.
+        translation: Vec<f64>,
+    },
+}
+
+/// An OME-NGFF multiscale dataset (a resolution level).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OmeDataset {
+    /// The path of the array relative to the multiscale image group.
+    pub path: String,
+    /// The coordinate transformations of the array, which are a scale optionally followed by a translation.
+    #[serde(rename = "coordinateTransformations")]
+    pub coordinate_transformations: Vec<OmeCoordinateTransformation>,
+}
+
+/// OME-NGFF multiscale metadata.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OmeMultiscale {
+    /// The OME-NGFF version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The name of the multiscale image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The axes.
+    pub axes: Vec<OmeAxis>,
+    /// The datasets (resolution levels), from highest to lowest resolution.
+    pub datasets: Vec<OmeDataset>,
+    /// The coordinate transformations applied to every dataset after its own coordinate transformations.
+    #[serde(
+        rename = "coordinateTransformations",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub coordinate_transformations: Vec<OmeCoordinateTransformation>,
+    /// The type of downscaling, e.g. `gaussian`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub downscaling_type: Option<String>,
+    /// Information about the downscaling method.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl OmeMultiscale {
+    /// Validate the multiscale against the OME-NGFF specification.
+    ///
+    /// The multiscale must have
+    ///  - 2 to 5 axes with unique names: 2 or 3 space axes, optionally preceded by a channel (or custom) axis, optionally preceded by a time axis,
+    ///  - at least one dataset, and
+    ///  - dataset coordinate transformations which are a scale optionally followed by a translation, matching the number of axes.
+    ///
+    /// # Errors
+    /// Returns [`OmeError::InvalidMetadata`] if the multiscale is invalid.
+    pub fn validate(&self) -> Result<(), OmeError> {
+        let invalid = |message: String| Err(OmeError::InvalidMetadata(message));
+
+        let num_axes = self.axes.len();
+        if !(2..=5).contains(&num_axes) {
+            return invalid(format!("there are {num_axes} axes, expected 2 to 5"));
+        }
+        for (i, axis) in self.axes.iter().enumerate() {
+            if self.axes[..i].iter().any(|other| other.name == axis.name) {
+                return invalid(format!("the axis name {} is not unique", axis.name));
+            }
+        }
+        // Axis order rank: time, then channel or custom, then space
+        let rank = |axis: &OmeAxis| match axis.axis_type {
+            Some(OmeAxisType::Time) => 0,
+            Some(OmeAxisType::Space) => 2,
+            _ => 1,
+        };
+        let ranks: Vec<usize> = self.axes.iter().map(rank).collect();
+        if ranks.windows(2).any(|ranks| ranks[0] > ranks[1]) {
+            return invalid("the axes must be ordered time, channel, then space".to_string());
+        }
+        let count = |r| ranks.iter().filter(|&&rank| rank == r).count();
+        if !(2..=3).contains(&count(2)) || count(0) > 1 || count(1) > 1 {
+            return invalid(
+                "there must be 2 or 3 space axes, at most one time axis, and at most one other axis"
+                    .to_string(),
+            );
+        }
+
+        if self.datasets.is_empty() {
+            return invalid("there are no datasets".to_string());
+        }
+        for dataset in &self.datasets {
+            validate_coordinate_transformations(
+                &dataset.coordinate_transformations,
+                num_axes,
+                true,
+            )
+            .map_err(|message| {
+                OmeError::InvalidMetadata(format!("dataset {}: {message}", dataset.path))
+            })?;
+        }
+        validate_coordinate_transformations(&self.coordinate_transformations, num_axes, false)
+            .map_err(|message| OmeError::InvalidMetadata(format!("multiscale: {message}")))?;
+        Ok(())
+    }
+
+    /// Return the number of resolution levels.
+    #[must_use]
+    pub fn num_levels(&self) -> usize {
+        self.datasets.len()
+    }
+
+    /// Return the physical scale of each axis of the resolution level `level`, or [`None`] if `level` is out of bounds.
+    ///
+    /// This is the scale of the dataset multiplied by the scale of the multiscale (if any).
+    #[must_use]
+    pub fn scale(&self, level: usize) -> Option<Vec<f64>> {
+        let dataset = self.datasets.get(level)?;
+        let mut scale = vec![1.0; self.axes.len()];
+        for transformation in dataset
+            .coordinate_transformations
+            .iter()
+            .chain(&self.coordinate_transformations)
+        {
+            if let OmeCoordinateTransformation::Scale { scale: s } = transformation {
+                std::iter::zip(&mut scale, s).for_each(|(scale, s)| *scale *= s);
+            }
+        }
+        Some(scale)
+    }
+
+    /// Return the physical translation of each axis of the resolution level `level`, or [`None`] if `level` is out of bounds.
+    ///
+    /// This is the translation of the dataset, followed by the scale and translation of the multiscale (if any).
+    #[must_use]
+    pub fn translation(&self, level: usize) -> Option<Vec<f64>> {
+        let dataset = self.datasets.get(level)?;
+        let mut translation = vec![0.0; self.axes.len()];
+        for transformation in dataset
+            .coordinate_transformations
+            .iter()
+            .chain(&self.coordinate_transformations)
+        {
+            match transformation {
+                OmeCoordinateTransformation::Scale { scale } => {
+                    std::iter::zip(&mut translation, scale).for_each(|(t, s)| *t *= s);
+                }
+                OmeCoordinateTransformation::Translation { translation: t } => {
+                    std::iter::zip(&mut translation, t).for_each(|(translation, t)| {
+                        *translation += t;
+                    });
+                }
+                OmeCoordinateTransformation::Identity => {}
+            }
+        }
+        Some(translation)
+    }
+
+    /// Return the node path of the array of the resolution level `level` in the multiscale image group at `group_path`.
+    ///
+    /// # Errors
+    /// Returns an [`OmeError`] if `level` is out of bounds or the path is invalid.
+    pub fn level_path(&self, group_path: &NodePath, level: usize) -> Result<NodePath, OmeError> {
+        let dataset = self
+            .datasets
+            .get(level)
+            .ok_or(OmeError::ResolutionLevelOutOfBounds(
+                level,
+                self.num_levels(),
+            ))?;
+        let group_path = group_path.as_str().trim_end_matches('/');
+        let path = format!("{group_path}/{}", dataset.path.trim_matches('/'));
+        NodePath::new(&path).map_err(|err| OmeError::InvalidMetadata(err.to_string()))
+    }
+
+    fn validate_array<TStorage: ?Sized>(&self, array: &Array<TStorage>) -> Result<(), OmeError> {
+        if array.dimensionality() == self.axes.len() {
+            Ok(())
+        } else {
+            Err(OmeError::InvalidMetadata(format!(
+                "the array at {} has {} dimensions, expected {}",
+                array.path(),
+                array.dimensionality(),
+                self.axes.len()
+            )))
+        }
+    }
+}
+
+/// Validate a list of coordinate transformations, which must be a scale optionally followed by a translation.
+fn validate_coordinate_transformations(
+    transformations: &[OmeCoordinateTransformation],
+    num_axes: usize,
+    required: bool,
+) -> Result<(), String> {
+    let lengths = match transformations {
+        [] if !required => vec![],
+        [OmeCoordinateTransformation::Scale { scale }] => vec![scale.len()],
+        [OmeCoordinateTransformation::Scale { scale }, OmeCoordinateTransformation::Translation { translation }] =>
+        {
+            vec![scale.len(), translation.len()]
+        }
+        _ => {
+            return Err(
+                "coordinate transformations must be a scale optionally followed by a translation"
+                    .to_string(),
+            )
+        }
+    };
+    if lengths.iter().any(|&length| length != num_axes) {
+        return Err(format!(
+            "coordinate transformations must have {num_axes} elements"
+        ));
+    }
+    Ok(())
+}
+
+/// Parse and validate the OME-NGFF multiscales in group `attributes`.
+///
+/// The multiscales are read from the `multiscales` attribute, or the `ome` attribute `multiscales` field as of OME-NGFF version 0.5.
+///
+/// # Errors
+/// Returns an [`OmeError`] if there are no multiscales or they are invalid.
+pub fn multiscales_from_attributes(
+    attributes: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Vec<OmeMultiscale>, OmeError> {
+    let multiscales = attributes
+        .get("ome")
+        .and_then(|ome| ome.get("multiscales"))
+        .or_else(|| attributes.get("multiscales"))
+        .ok_or(OmeError::MissingMultiscales)?;
+    let multiscales: Vec<OmeMultiscale> = serde_json::from_value(multiscales.clone())
+        .map_err(|err| OmeError::InvalidMetadata(err.to_string()))?;
+    if multiscales.is_empty() {
+        return Err(OmeError::MissingMultiscales);
+    }
+    for multiscale in &multiscales {
+        multiscale.validate()?;
+    }
+    Ok(multiscales)
+}
+
+/// Read and validate the OME-NGFF multiscales of the group at `path`.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the group cannot be read or its multiscales are missing or invalid.
+pub fn read_multiscales<TStorage: ?Sized + ReadableStorageTraits>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Vec<OmeMultiscale>, OmeError> {
+    let group = Group::new(storage, path)?;
+    multiscales_from_attributes(group.attributes())
+}
+
+/// Open the resolution levels of the first multiscale of the OME-NGFF multiscale image at `path`, from highest to lowest resolution.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the multiscales are missing or invalid, or an array cannot be opened or does not match the axes.
+#[allow(clippy::needless_pass_by_value)]
+pub fn open_multiscale<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Vec<Array<TStorage>>, OmeError> {
+    let multiscale = read_multiscales(storage.clone(), path)?.remove(0);
+    (0..multiscale.num_levels())
+        .map(|level| open_level(&multiscale, storage.clone(), path, level))
+        .collect()
+}
+
+/// Open the resolution level `level` of the first multiscale of the OME-NGFF multiscale image at `path`.
+///
+/// Level `0` is the highest resolution.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the multiscales are missing or invalid, `level` is out of bounds, or the array cannot be opened or does not match the axes.
+pub fn open_multiscale_level<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    storage: Arc<TStorage>,
+    path: &str,
+    level: usize,
+) -> Result<Array<TStorage>, OmeError> {
+    let multiscale = read_multiscales(storage.clone(), path)?.remove(0);
+    open_level(&multiscale, storage, path, level)
+}
+
+fn open_level<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    multiscale: &OmeMultiscale,
+    storage: Arc<TStorage>,
+    path: &str,
+    level: usize,
+) -> Result<Array<TStorage>, OmeError> {
+    let level_path = multiscale.level_path(&NodePath::new(path)?, level)?;
+    let array = Array::new(storage, level_path.as_str())?;
+    multiscale.validate_array(&array)?;
+    Ok(array)
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously read and validate the OME-NGFF multiscales of the group at `path`.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the group cannot be read or its multiscales are missing or invalid.
+pub async fn async_read_multiscales<TStorage: ?Sized + AsyncReadableStorageTraits>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Vec<OmeMultiscale>, OmeError> {
+    let group = Group::async_new(storage, path).await?;
+    multiscales_from_attributes(group.attributes())
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously open the resolution levels of the first multiscale of the OME-NGFF multiscale image at `path`, from highest to lowest resolution.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the multiscales are missing or invalid, or an array cannot be opened or does not match the axes.
+pub async fn async_open_multiscale<TStorage: ?Sized + AsyncReadableStorageTraits + 'static>(
+    storage: Arc<TStorage>,
+    path: &str,
+) -> Result<Vec<Array<TStorage>>, OmeError> {
+    let multiscale = async_read_multiscales(storage.clone(), path)
+        .await?
+        .remove(0);
+    let mut arrays = Vec::with_capacity(multiscale.num_levels());
+    for level in 0..multiscale.num_levels() {
+        arrays.push(async_open_level(&multiscale, storage.clone(), path, level).await?);
+    }
+    Ok(arrays)
+}
+
+#[cfg(feature = "async")]
+/// Asynchronously open the resolution level `level` of the first multiscale of the OME-NGFF multiscale image at `path`.
+///
+/// Level `0` is the highest resolution.
+///
+/// # Errors
+/// Returns an [`OmeError`] if the multiscales are missing or invalid, `level` is out of bounds, or the array cannot be opened or does not match the axes.
+pub async fn async_open_multiscale_level<
+    TStorage: ?Sized + AsyncReadableStorageTraits + 'static,
+>(
+    storage: Arc<TStorage>,
+    path: &str,
+    level: usize,
+) -> Result<Array<TStorage>, OmeError> {
+    let multiscale = async_read_multiscales(storage.clone(), path)
+        .await?
+        .remove(0);
+    async_open_level(&multiscale, storage, path, level).await
+}
+
+#[cfg(feature = "async")]
+async fn async_open_level<TStorage: ?Sized + AsyncReadableStorageTraits + 'static>(
+    multiscale: &OmeMultiscale,
+    storage: Arc<TStorage>,
+    path: &str,
+    level: usize,
+) -> Result<Array<TStorage>, OmeError> {
+    let level_path = multiscale.level_path(&NodePath::new(path)?, level)?;
+    let array = Array::async_new(storage, level_path.as_str()).await?;
+    multiscale.validate_array(&array)?;
+    Ok(array)
+}
+
+/// An OME-NGFF error.
+#[derive(Debug, Error)]
+pub enum OmeError {
+    /// An invalid node path.
+    #[error(transparent)]
+    NodePathError(#[from] crate::node::NodePathError),
+    /// A group creation error.
+    #[error(transparent)]
+    GroupCreateError(#[from] GroupCreateError),
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// There is no multiscales metadata.
+    #[error("there is no OME-NGFF multiscales metadata")]
+    MissingMultiscales,
+    /// The multiscales metadata is invalid.
+    #[error("OME-NGFF multiscales metadata is invalid: {0}")]
+    InvalidMetadata(String),
+    /// The resolution level is out of bounds.
+    #[error("resolution level {0} is out of bounds, there are {1} levels")]
+    ResolutionLevelOutOfBounds(usize, usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        group::GroupBuilder,
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn multiscale_json() -> serde_json::Value {
+        json!({
+            "version": "0.4",
+            "name": "example",
+            "axes": [
+                {"name": "t", "type": "time", "unit": "millisecond"},
+                {"name": "c", "type": "channel"},
+                {"name": "y", "type": "space", "unit": "micrometer"},
+                {"name": "x", "type": "space", "unit": "micrometer"}
+            ],
+            "datasets": [
+                {
+                    "path": "0",
+                    "coordinateTransformations": [{"type": "scale", "scale": [1.0, 1.0, 0.5, 0.5]}]
+                },
+                {
+                    "path": "1",
+                    "coordinateTransformations": [
+                        {"type": "scale", "scale": [1.0, 1.0, 1.0, 1.0]},
+                        {"type": "translation", "translation": [0.0, 0.0, 0.25, 0.25]}
+                    ]
+                }
+            ],
+            "coordinateTransformations": [{"type": "scale", "scale": [0.1, 1.0, 1.0, 1.0]}],
+            "type": "gaussian"
+        })
+    }
+
+    #[test]
+    fn ome_multiscale_metadata() {
+        let multiscale: OmeMultiscale = serde_json::from_value(multiscale_json()).unwrap();
+        multiscale.validate().unwrap();
+        assert_eq!(multiscale.axes[1].axis_type, Some(OmeAxisType::Channel));
+        assert_eq!(multiscale.num_levels(), 2);
+        assert_eq!(multiscale.scale(0), Some(vec![0.1, 1.0, 0.5, 0.5]));
+        assert_eq!(multiscale.translation(1), Some(vec![0.0, 0.0, 0.25, 0.25]));
+        assert_eq!(multiscale.scale(2), None);
+        assert_eq!(
+            serde_json::to_value(&multiscale).unwrap(),
+            multiscale_json()
+        );
+
+        let custom: OmeAxis =
+            serde_json::from_value(json!({"name": "a", "type": "angle"})).unwrap();
+        assert_eq!(
+            custom.axis_type,
+            Some(OmeAxisType::Custom("angle".to_string()))
+        );
+    }
+
+    #[test]
+    fn ome_multiscale_validate() {
+        let invalid = |update: &dyn Fn(&mut serde_json::Value)| {
+            let mut multiscale = multiscale_json();
+            update(&mut multiscale);
+            let multiscale: OmeMultiscale = serde_json::from_value(multiscale).unwrap();
+            multiscale.validate().is_err()
+        };
+        assert!(!invalid(&|_| {}));
+        // Wrong axis order
+        assert!(invalid(&|m| m["axes"][0]["type"] = json!("space")));
+        // Duplicate axis name
+        assert!(invalid(&|m| m["axes"][1]["name"] = json!("t")));
+        // No datasets
+        assert!(invalid(&|m| m["datasets"] = json!([])));
+        // Translation before scale
+        assert!(invalid(&|m| {
+            m["datasets"][1]["coordinateTransformations"] = json!([
+                {"type": "translation", "translation": [0.0, 0.0, 0.0, 0.0]},
+                {"type": "scale", "scale": [1.0, 1.0, 1.0, 1.0]}
+            ]);
+        }));
+        // Wrong scale length
+        assert!(invalid(&|m| {
+            m["datasets"][0]["coordinateTransformations"][0]["scale"] = json!([1.0, 1.0]);
+        }));
+    }
+
+    #[test]
+    fn ome_open_multiscale() -> Result<(), Box<dyn std::error::Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let mut group = GroupBuilder::new().build(store.clone(), "/image")?;
+        group.attributes_mut().insert(
+            "ome".to_string(),
+            json!({"multiscales": [multiscale_json()]}),
+        );
+        group.store_metadata()?;
+        for (path, size) in [("/image/0", 8), ("/image/1", 4)] {
+            ArrayBuilder::new(
+                vec![2, 3, size, size],
+                DataType::UInt8,
+                vec![1, 1, 4, 4].try_into()?,
+                FillValue::from(0u8),
+            )
+            .build(store.clone(), path)?
+            .store_metadata()?;
+        }
+
+        let multiscales = read_multiscales(store.clone(), "/image")?;
+        assert_eq!(multiscales.len(), 1);
+        assert_eq!(multiscales[0].name.as_deref(), Some("example"));
+
+        let levels = open_multiscale(store.clone(), "/image")?;
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].shape(), &[2, 3, 8, 8]);
+        assert_eq!(levels[1].path().as_str(), "/image/1");
+
+        let level = open_multiscale_level(store.clone(), "/image", 1)?;
+        assert_eq!(level.shape(), &[2, 3, 4, 4]);
+        assert!(matches!(
+            open_multiscale_level(store.clone(), "/image", 2),
+            Err(OmeError::ResolutionLevelOutOfBounds(2, 2))
+        ));
+
+        GroupBuilder::new()
+            .build(store.clone(), "/other")?
+            .store_metadata()?;
+        assert!(matches!(
+            read_multiscales(store, "/other"),
+            Err(OmeError::MissingMultiscales)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,541 @@
+//! Streaming reductions over arrays.
+//!
+//! A [`StatisticsBuilder`] computes the [`ArrayStatistics`] (count, minimum, maximum, mean, standard deviation, and optionally a [`Histogram`]) of an array or an array subset.
+//! Statistics are computed chunk by chunk: only the region of a chunk overlapping the subset is retrieved, so memory usage is bounded by the chunk size rather than the array size.
+//! Chunks are processed in parallel with [`rayon`] and their partial results are merged.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::{array::{ArrayBuilder, DataType, FillValue}, array_subset::ArraySubset, storage::store::MemoryStore};
+//! # let store = Arc::new(MemoryStore::new());
+//! # let mut array = ArrayBuilder::new(vec![8, 8], DataType::UInt8, vec![4, 4].try_into()?, FillValue::from(0u8))
+//! #     .build(store.clone(), "/array")?;
+//! # array.store_array_subset_elements(&ArraySubset::new_with_shape(vec![8, 8]), (0..64u8).collect())?;
+//! use zarrs::ops::{statistics_attribute, StatisticsBuilder};
+//!
+//! let statistics = StatisticsBuilder::new()
+//!     .histogram(4)
+//!     .compute_and_store(&mut array, None)?;
+//! assert_eq!(statistics.count, 64);
+//! assert_eq!(statistics.max, Some(63.0));
+//! assert_eq!(statistics.histogram.as_ref().unwrap().counts, vec![16, 16, 16, 16]);
+//! assert_eq!(statistics_attribute(&array), Some(statistics));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    array::{transmute_from_bytes_vec, Array, ArrayError, DataType},
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, ReadableWritableStorageTraits, StorageError},
+};
+
+/// The array attribute that holds an [`ArrayStatistics`] summary.
+pub const STATISTICS_ATTRIBUTE: &str = "statistics";
+
+/// A histogram with equal width bins.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// The lower edge of the first bin.
+    pub min: f64,
+    /// The upper edge of the last bin.
+    pub max: f64,
+    /// The number of elements in each bin.
+    ///
+    /// Bins are half-open, except for the last bin which includes `max`.
+    /// Elements outside of the histogram range are not counted.
+    pub counts: Vec<u64>,
+}
+
+impl Histogram {
+    fn new(min: f64, max: f64, bins: usize) -> Self {
+        Self {
+            min,
+            max,
+            counts: vec![0; bins],
+        }
+    }
+
+    /// Return the edges of the bins.
+    ///
+    /// There is one more edge than the number of bins.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn bin_edges(&self) -> Vec<f64> {
+        let bins = self.counts.len();
+        (0..=bins)
+            .map(|i| self.min + (self.max - self.min) * i as f64 / bins as f64)
+            .collect()
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn add(&mut self, value: f64) {
+        if value < self.min || value > self.max {
+            return;
+        }
+        let bins = self.counts.len();
+        let bin = if self.max > self.min {
+            (((value - self.min) / (self.max - self.min) * bins as f64) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        self.counts[bin] += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (count, other) in std::iter::zip(&mut self.counts, &other.counts) {
+            *count += other;
+        }
+    }
+}
+
+/// Summary statistics of the elements of an array or array subset.
+///
+/// `NaN` elements are counted in `nan_count` and otherwise ignored.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ArrayStatistics {
+    /// The number of elements, excluding `NaN`s.
+    pub count: u64,
+    /// The number of `NaN` elements.
+    pub nan_count: u64,
+    /// The minimum, or [`None`] if there are no elements.
+    pub min: Option<f64>,
+    /// The maximum, or [`None`] if there are no elements.
+    pub max: Option<f64>,
+    /// The mean, or [`None`] if there are no elements.
+    pub mean: Option<f64>,
+    /// The population standard deviation, or [`None`] if there are no elements.
+    pub std: Option<f64>,
+    /// The histogram, if requested and there are elements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<Histogram>,
+}
+
+/// Partial statistics of a set of elements, which can be merged in any order.
+#[derive(Clone, Debug)]
+struct Accumulator {
+    count: u64,
+    nan_count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    /// The sum of squared differences from the mean.
+    m2: f64,
+    histogram: Option<Histogram>,
+}
+
+impl Accumulator {
+    fn new(histogram: Option<Histogram>) -> Self {
+        Self {
+            count: 0,
+            nan_count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            histogram,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            self.nan_count += 1;
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        if let Some(histogram) = &mut self.histogram {
+            histogram.add(value);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn merge(mut self, other: &Self) -> Self {
+        if other.count > 0 {
+            let count = self.count + other.count;
+            let delta = other.mean - self.mean;
+            self.mean += delta * other.count as f64 / count as f64;
+            self.m2 += other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64;
+            self.count = count;
+            self.min = self.min.min(other.min);
+            self.max = self.max.max(other.max);
+        }
+        self.nan_count += other.nan_count;
+        if let (Some(histogram), Some(other)) = (&mut self.histogram, &other.histogram) {
+            histogram.merge(other);
+        }
+        self
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(self) -> ArrayStatistics {
+        let some = |value: f64| (self.count > 0).then_some(value);
+        ArrayStatistics {
+            count: self.count,
+            nan_count: self.nan_count,
+            min: some(self.min),
+            max: some(self.max),
+            mean: some(self.mean),
+            std: some((self.m2 / self.count as f64).sqrt()),
+            histogram: self.histogram.filter(|_| self.count > 0),
+        }
+    }
+}
+
+/// A builder for the [`ArrayStatistics`] of an array.
+///
+/// Use [`compute`](StatisticsBuilder::compute) to compute statistics, or [`compute_and_store`](StatisticsBuilder::compute_and_store) to also persist them in the [`STATISTICS_ATTRIBUTE`] of the array.
+#[derive(Clone, Debug, Default)]
+pub struct StatisticsBuilder {
+    histogram_bins: Option<usize>,
+    histogram_range: Option<(f64, f64)>,
+}
+
+impl StatisticsBuilder {
+    /// Create a new statistics builder without a histogram.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute a histogram with `bins` equal width bins.
+    ///
+    /// The histogram spans the minimum and maximum of the elements unless a range is set with [`histogram_range`](StatisticsBuilder::histogram_range).
+    /// Without a range, the elements are read twice.
+    pub fn histogram(&mut self, bins: usize) -> &mut Self {
+        self.histogram_bins = Some(bins);
+        self
+    }
+
+    /// Set the range of the histogram from `min` to `max` (inclusive).
+    pub fn histogram_range(&mut self, min: f64, max: f64) -> &mut Self {
+        self.histogram_range = Some((min, max));
+        self
+    }
+
+    /// Compute the statistics of the elements of `array` in `array_subset`, or the entire array if `array_subset` is [`None`].
+    ///
+    /// Chunks that are not stored contribute their fill value.
+    ///
+    /// # Errors
+    /// Returns a [`StatisticsError`] if
+    ///  - the data type of the array is not an integer or floating point type,
+    ///  - the histogram bins or range are invalid,
+    ///  - `array_subset` is incompatible with the array, or
+    ///  - there is an underlying array error.
+    pub fn compute<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+        &self,
+        array: &Array<TStorage>,
+        array_subset: Option<&ArraySubset>,
+    ) -> Result<ArrayStatistics, StatisticsError> {
+        if !supports_statistics(array.data_type()) {
+            return Err(StatisticsError::UnsupportedDataType(
+                array.data_type().name(),
+            ));
+        }
+        let histogram = self
+            .histogram_bins
+            .map(|bins| {
+                let (min, max) = self.histogram_range.unwrap_or((0.0, 0.0));
+                if bins == 0 || !min.is_finite() || !max.is_finite() || min > max {
+                    Err(StatisticsError::InvalidHistogram(bins, min, max))
+                } else {
+                    Ok(Histogram::new(min, max, bins))
+                }
+            })
+            .transpose()?;
+        let array_subset = array_subset.map_or_else(
+            || ArraySubset::new_with_shape(array.shape().to_vec()),
+            Clone::clone,
+        );
+
+        if let (Some(bins), None) = (self.histogram_bins, self.histogram_range) {
+            // Find the histogram range in a first pass
+            let statistics = reduce(array, &array_subset, None)?;
+            let (Some(min), Some(max)) = (statistics.min, statistics.max) else {
+                return Ok(statistics);
+            };
+            reduce(array, &array_subset, Some(&Histogram::new(min, max, bins)))
+        } else {
+            reduce(array, &array_subset, histogram.as_ref())
+        }
+    }
+
+    /// Compute the statistics of `array` and store them in the [`STATISTICS_ATTRIBUTE`] of the array.
+    ///
+    /// See [`compute`](StatisticsBuilder::compute).
+    /// The array metadata is updated in the store.
+    ///
+    /// # Errors
+    /// Returns a [`StatisticsError`] if a [`compute`](StatisticsBuilder::compute) error condition is met or the array metadata cannot be stored.
+    pub fn compute_and_store<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+        &self,
+        array: &mut Array<TStorage>,
+        array_subset: Option<&ArraySubset>,
+    ) -> Result<ArrayStatistics, StatisticsError> {
+        let statistics = self.compute(array, array_subset)?;
+        array.attributes_mut().insert(
+            STATISTICS_ATTRIBUTE.to_string(),
+            serde_json::to_value(&statistics).unwrap_or_default(),
+        );
+        array.store_metadata()?;
+        Ok(statistics)
+    }
+}
+
+/// Return the [`ArrayStatistics`] stored in the [`STATISTICS_ATTRIBUTE`] of `array`.
+///
+/// The statistics are written by [`StatisticsBuilder::compute_and_store`] and may be out of date.
+#[must_use]
+pub fn statistics_attribute<TStorage: ?Sized>(array: &Array<TStorage>) -> Option<ArrayStatistics> {
+    array
+        .attributes()
+        .get(STATISTICS_ATTRIBUTE)
+        .and_then(|statistics| serde_json::from_value(statistics.clone()).ok())
+}
+
+/// Reduce the elements of `array` in `array_subset` chunk by chunk in parallel.
+fn reduce<TStorage: ?Sized + ReadableStorageTraits + 'static>(
+    array: &Array<TStorage>,
+    array_subset: &ArraySubset,
+    histogram: Option<&Histogram>,
+) -> Result<ArrayStatistics, StatisticsError> {
+    let Some(chunks) = array
+        .chunks_in_array_subset(array_subset)
+        .map_err(ArrayError::from)?
+    else {
+        return Err(
+            ArrayError::InvalidArraySubset(array_subset.clone(), array.shape().to_vec()).into(),
+        );
+    };
+    let indices = chunks.indices();
+    let accumulator = indices
+        .into_par_iter()
+        .map(|chunk_indices| {
+            let chunk_subset = array.chunk_subset_bounded(&chunk_indices)?;
+            let chunk_subset = chunk_subset
+                .overlap(array_subset)
+                .map_err(ArrayError::from)?;
+            let bytes = array.retrieve_array_subset(&chunk_subset)?;
+            let mut accumulator = Accumulator::new(histogram.cloned());
+            for_each_element(bytes, array.data_type(), |value| accumulator.add(value));
+            Ok::<_, StatisticsError>(accumulator)
+        })
+        .try_reduce(
+            || Accumulator::new(histogram.cloned()),
+            |a, b| Ok(a.merge(&b)),
+        )?;
+    Ok(accumulator.finish())
+}
+
+fn supports_statistics(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float16
+            | DataType::BFloat16
+            | DataType::Float32
+            | DataType::Float64
+    )
+}
+
+/// Call `f` with each element of `bytes` converted to [`f64`].
+fn for_each_element(bytes: Vec<u8>, data_type: &DataType, mut f: impl FnMut(f64)) {
+    macro_rules! for_each {
+        ( $t:ty, $to_f64:expr ) => {
+            transmute_from_bytes_vec::<$t>(bytes)
+                .into_iter()
+                .for_each(|element| f($to_f64(element)))
+        };
+    }
+    #[allow(clippy::cast_precision_loss)]
+    match data_type {
+        DataType::Int8 => for_each!(i8, f64::from),
+        DataType::Int16 => for_each!(i16, f64::from),
+        DataType::Int32 => for_each!(i32, f64::from),
+        DataType::Int64 => for_each!(i64, |v: i64| v as f64),
+        DataType::UInt8 => for_each!(u8, f64::from),
+        DataType::UInt16 => for_each!(u16, f64::from),
+        DataType::UInt32 => for_each!(u32, f64::from),
+        DataType::UInt64 => for_each!(u64, |v: u64| v as f64),
+        DataType::Float16 => for_each!(half::f16, half::f16::to_f64),
+        DataType::BFloat16 => for_each!(half::bf16, half::bf16::to_f64),
+        DataType::Float32 => for_each!(f32, f64::from),
+        DataType::Float64 => for_each!(f64, |v: f64| v),
+        _ => {}
+    }
+}
+
+/// A statistics error.
+#[derive(Debug, Error)]
+pub enum StatisticsError {
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// The data type is not supported.
+    #[error(
+        "data type {0} is not supported, statistics require an integer or floating point data type"
+    )]
+    UnsupportedDataType(String),
+    /// The histogram is invalid.
+    #[error("histogram with {0} bins from {1} to {2} is invalid")]
+    InvalidHistogram(usize, f64, f64),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn statistics_integer() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![5, 4],
+            DataType::Int16,
+            vec![2, 3].try_into().unwrap(),
+            FillValue::from(0i16),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<i16>(
+                &ArraySubset::new_with_shape(vec![4, 4]),
+                (-8..8).collect(),
+            )
+            .unwrap();
+
+        let statistics = StatisticsBuilder::new().compute(&array, None).unwrap();
+        assert_eq!(statistics.count, 20);
+        assert_eq!(statistics.nan_count, 0);
+        assert_eq!(statistics.min, Some(-8.0));
+        assert_eq!(statistics.max, Some(7.0));
+        assert!((statistics.mean.unwrap() + 0.4).abs() < 1e-12);
+        let expected_std = ((-8..8).map(|v| (f64::from(v) + 0.4).powi(2)).sum::<f64>()
+            + 4.0 * 0.4f64.powi(2))
+            / 20.0;
+        assert!((statistics.std.unwrap() - expected_std.sqrt()).abs() < 1e-12);
+        assert!(statistics.histogram.is_none());
+
+        let statistics = StatisticsBuilder::new()
+            .histogram(2)
+            .histogram_range(0.0, 10.0)
+            .compute(&array, Some(&ArraySubset::new_with_ranges(&[2..5, 0..4])))
+            .unwrap();
+        assert_eq!(statistics.count, 12);
+        assert!((statistics.mean.unwrap() - 28.0 / 12.0).abs() < 1e-12);
+        let histogram = statistics.histogram.unwrap();
+        assert_eq!(histogram.counts, vec![9, 3]);
+        assert_eq!(histogram.bin_edges(), vec![0.0, 5.0, 10.0]);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn statistics_float_nan() {
+        let store = Arc::new(MemoryStore::new());
+        let mut array = ArrayBuilder::new(
+            vec![6],
+            DataType::Float32,
+            vec![4].try_into().unwrap(),
+            FillValue::from(f32::NAN),
+        )
+        .build(store, "/array")
+        .unwrap();
+        array
+            .store_array_subset_elements::<f32>(
+                &ArraySubset::new_with_ranges(&[0..4]),
+                vec![1.0, f32::NAN, 3.0, 5.0],
+            )
+            .unwrap();
+
+        let statistics = StatisticsBuilder::new()
+            .histogram(2)
+            .compute_and_store(&mut array, None)
+            .unwrap();
+        assert_eq!(statistics.count, 3);
+        assert_eq!(statistics.nan_count, 3);
+        assert_eq!(statistics.mean, Some(3.0));
+        assert_eq!(
+            statistics.histogram,
+            Some(Histogram {
+                min: 1.0,
+                max: 5.0,
+                counts: vec![1, 2]
+            })
+        );
+        assert_eq!(statistics_attribute(&array), Some(statistics));
+
+        let statistics = StatisticsBuilder::new()
+            .histogram(2)
+            .compute(&array, Some(&ArraySubset::new_with_ranges(&[4..6])))
+            .unwrap();
+        assert_eq!(
+            statistics,
+            ArrayStatistics {
+                nan_count: 2,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn statistics_errors() {
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4],
+            DataType::Bool,
+            vec![2].try_into().unwrap(),
+            FillValue::from(false),
+        )
+        .build(store.clone(), "/bool")
+        .unwrap();
+        assert!(matches!(
+            StatisticsBuilder::new().compute(&array, None),
+            Err(StatisticsError::UnsupportedDataType(_))
+        ));
+
+        let array = ArrayBuilder::new(
+            vec![4],
+            DataType::UInt8,
+            vec![2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store, "/uint8")
+        .unwrap();
+        assert!(matches!(
+            StatisticsBuilder::new().histogram(0).compute(&array, None),
+            Err(StatisticsError::InvalidHistogram(..))
+        ));
+        assert!(StatisticsBuilder::new()
+            .compute(&array, Some(&ArraySubset::new_with_ranges(&[0..2, 0..2])))
+            .is_err());
+    }
+}
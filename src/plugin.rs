@@ -1,9 +1,7 @@
 //! [Zarr extension points](https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#extension-points) utilities.
 //!
 //! A [`Plugin`] creates objects from [`Metadata`] (consisting of a name and optional configuration).
-//! It is used to implement [Zarr extension points](https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#extension-points), such as [chunk grids][`crate::array::chunk_grid`], [chunk key encodings](`crate::array::chunk_key_encoding`), [codecs](`crate::array::codec`), and [storage transformers](`crate::storage::storage_transformer`).
-//!
-//! [Data types](`crate::array::data_type`) are not currently supported as an extension point.
+//! It is used to implement [Zarr extension points](https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#extension-points), such as [chunk grids][`crate::array::chunk_grid`], [chunk key encodings](`crate::array::chunk_key_encoding`), [codecs](`crate::array::codec`), [data types](`crate::array::data_type`), and [storage transformers](`crate::storage::storage_transformer`).
 //!
 //! Plugins are registered at compile time using the [inventory] crate.
 //! At runtime, a name matching function is applied to identify which registered plugin is associated with the metadata.
@@ -29,7 +27,7 @@ pub struct Plugin<TPlugin> {
 pub struct PluginMetadataInvalidError {
     identifier: &'static str,
     plugin_type: &'static str,
-    metadata: Metadata,
+    metadata: Box<Metadata>,
 }
 
 impl PluginMetadataInvalidError {
@@ -39,7 +37,7 @@ impl PluginMetadataInvalidError {
         Self {
             identifier,
             plugin_type,
-            metadata,
+            metadata: Box::new(metadata),
         }
     }
 }
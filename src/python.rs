@@ -0,0 +1,358 @@
+//! Python bindings.
+//!
+//! This module provides a `zarrs` Python extension module with an `Array` class that can open, retrieve, and store array subsets with [NumPy](https://numpy.org/) arrays.
+//! It is intended as a drop-in accelerator for heavy read paths that are otherwise served by [zarr-python](https://github.com/zarr-developers/zarr-python).
+//!
+//! Retrieved array subsets are decoded directly into the buffer of a newly allocated `NumPy` array without an intermediate copy.
+//! The Python global interpreter lock is released while chunks are retrieved, decoded, encoded, and stored, so other Python threads can run concurrently.
+//!
+//! Build the extension module with the `python` feature and the `pyo3/extension-module` feature, for example with [maturin](https://www.maturin.rs/) or
+//! ```text
+//! cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib
+//! ```
+//! and rename the library to `zarrs.so` (or `zarrs.pyd` on Windows).
+//!
+//! ```python
+//! import zarrs
+//!
+//! array = zarrs.Array("/path/to/hierarchy.zarr", "/array")
+//! print(array.shape, array.dtype)
+//! subset = array.retrieve([0, 0], [100, 100])  # a numpy.ndarray
+//! array.store([0, 0], subset * 2)
+//! everything = array.retrieve()
+//! ```
+//!
+//! Supported data types are `bool`, `int8`-`int64`, `uint8`-`uint64`, `float16`, `float32`, `float64`, `complex64`, and `complex128`.
+
+use std::sync::Arc;
+
+use numpy::{
+    Complex32, Complex64, Element, PyArrayDyn, PyArrayMethods, PyUntypedArray,
+    PyUntypedArrayMethods,
+};
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    prelude::*,
+};
+
+use crate::{
+    array::{Array, ArrayView, DataType},
+    array_subset::ArraySubset,
+    storage::{
+        store::FilesystemStore, ReadableWritableListableStorage,
+        ReadableWritableListableStorageTraits,
+    },
+};
+
+/// Call `$f::<T>($args)` where `T` is the element type corresponding to `$data_type`.
+macro_rules! dispatch_element_type {
+    ($data_type:expr, $($f:ident)::+($($args:expr),*)) => {
+        match $data_type {
+            DataType::Bool => $($f)::+::<bool>($($args),*),
+            DataType::Int8 => $($f)::+::<i8>($($args),*),
+            DataType::Int16 => $($f)::+::<i16>($($args),*),
+            DataType::Int32 => $($f)::+::<i32>($($args),*),
+            DataType::Int64 => $($f)::+::<i64>($($args),*),
+            DataType::UInt8 => $($f)::+::<u8>($($args),*),
+            DataType::UInt16 => $($f)::+::<u16>($($args),*),
+            DataType::UInt32 => $($f)::+::<u32>($($args),*),
+            DataType::UInt64 => $($f)::+::<u64>($($args),*),
+            DataType::Float16 => $($f)::+::<half::f16>($($args),*),
+            DataType::Float32 => $($f)::+::<f32>($($args),*),
+            DataType::Float64 => $($f)::+::<f64>($($args),*),
+            DataType::Complex64 => $($f)::+::<Complex32>($($args),*),
+            DataType::Complex128 => $($f)::+::<Complex64>($($args),*),
+            data_type => Err(PyTypeError::new_err(format!(
+                "the {data_type} data type is not supported by the Python bindings"
+            ))),
+        }
+    };
+}
+
+/// Return the `NumPy` data type name of a zarrs data type, if it is supported.
+fn numpy_dtype(data_type: &DataType) -> Option<&'static str> {
+    match data_type {
+        DataType::Bool => Some("bool"),
+        DataType::Int8 => Some("int8"),
+        DataType::Int16 => Some("int16"),
+        DataType::Int32 => Some("int32"),
+        DataType::Int64 => Some("int64"),
+        DataType::UInt8 => Some("uint8"),
+        DataType::UInt16 => Some("uint16"),
+        DataType::UInt32 => Some("uint32"),
+        DataType::UInt64 => Some("uint64"),
+        DataType::Float16 => Some("float16"),
+        DataType::Float32 => Some("float32"),
+        DataType::Float64 => Some("float64"),
+        DataType::Complex64 => Some("complex64"),
+        DataType::Complex128 => Some("complex128"),
+        _ => None,
+    }
+}
+
+/// Return the array subset of `array` with `start` and `shape`, defaulting to the whole array.
+fn array_subset<TStorage: ?Sized>(
+    array: &Array<TStorage>,
+    start: Option<Vec<u64>>,
+    shape: Option<Vec<u64>>,
+) -> PyResult<ArraySubset> {
+    let start = start.unwrap_or_else(|| vec![0; array.dimensionality()]);
+    let shape = match shape {
+        Some(shape) => shape,
+        None => std::iter::zip(array.shape(), &start)
+            .map(|(&array_shape, &start)| array_shape.saturating_sub(start))
+            .collect(),
+    };
+    let array_subset = ArraySubset::new_with_start_shape(start, shape)
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    if array_subset.dimensionality() != array.dimensionality() {
+        return Err(PyValueError::new_err(format!(
+            "the array subset {array_subset} has a different dimensionality to the array shape {:?}",
+            array.shape()
+        )));
+    }
+    Ok(array_subset)
+}
+
+/// A Zarr array.
+#[pyclass(name = "Array", module = "zarrs", frozen)]
+pub struct PythonArray {
+    array: Array<dyn ReadableWritableListableStorageTraits>,
+}
+
+impl PythonArray {
+    /// Retrieve `array_subset` into a new `NumPy` array with elements of type `T`.
+    fn retrieve_elements<'py, T: Element>(
+        &self,
+        py: Python<'py>,
+        array_subset: &ArraySubset,
+    ) -> PyResult<Bound<'py, PyUntypedArray>> {
+        let shape: Vec<usize> = array_subset
+            .shape()
+            .iter()
+            .map(|&size| usize::try_from(size))
+            .collect::<Result<_, _>>()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        // SAFETY: every element of the uninitialised array is written by the array view below before it is returned
+        let elements = unsafe { PyArrayDyn::<T>::new(py, shape, false) };
+        let bytes = unsafe {
+            let elements = elements
+                .as_slice_mut()
+                .map_err(|err| PyValueError::new_err(err.to_string()))?;
+            std::slice::from_raw_parts_mut(
+                elements.as_mut_ptr().cast::<u8>(),
+                std::mem::size_of_val(elements),
+            )
+        };
+        let array_view_shape = array_subset.shape().to_vec();
+        py.detach(|| {
+            let array_view = ArrayView::new(
+                bytes,
+                &array_view_shape,
+                ArraySubset::new_with_shape(array_view_shape.clone()),
+            )
+            .map_err(|err| err.to_string())?;
+            self.array
+                .retrieve_array_subset_into_array_view(array_subset, &array_view)
+                .map_err(|err| err.to_string())
+        })
+        .map_err(PyValueError::new_err)?;
+        Ok(elements.as_untyped().clone())
+    }
+
+    /// Store the elements of type `T` in `elements` in `array_subset`.
+    fn store_elements<T: Element>(
+        &self,
+        py: Python<'_>,
+        array_subset: &ArraySubset,
+        elements: &Bound<'_, PyUntypedArray>,
+    ) -> PyResult<()> {
+        let elements = elements.cast::<PyArrayDyn<T>>()?.readonly();
+        let elements = elements
+            .as_slice()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        // Copy while the global interpreter lock is held, since Python code could otherwise mutate the elements
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                elements.as_ptr().cast::<u8>(),
+                std::mem::size_of_val(elements),
+            )
+        }
+        .to_vec();
+        py.detach(|| self.array.store_array_subset(array_subset, bytes))
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+}
+
+#[pymethods]
+impl PythonArray {
+    /// Open the array at `array_path` in the filesystem store at `store_path`.
+    #[new]
+    #[pyo3(signature = (store_path, array_path = "/"))]
+    fn new(store_path: &str, array_path: &str) -> PyResult<Self> {
+        let store: ReadableWritableListableStorage = Arc::new(
+            FilesystemStore::new(store_path)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        );
+        let array =
+            Array::new(store, array_path).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(Self { array })
+    }
+
+    /// The array shape.
+    #[getter]
+    fn shape(&self) -> Vec<u64> {
+        self.array.shape().to_vec()
+    }
+
+    /// The number of array dimensions.
+    #[getter]
+    fn ndim(&self) -> usize {
+        self.array.dimensionality()
+    }
+
+    /// The `NumPy` data type name of the array elements.
+    #[getter]
+    fn dtype(&self) -> PyResult<&'static str> {
+        numpy_dtype(self.array.data_type()).ok_or_else(|| {
+            PyTypeError::new_err(format!(
+                "the {} data type is not supported by the Python bindings",
+                self.array.data_type()
+            ))
+        })
+    }
+
+    /// The array metadata as a JSON string.
+    #[getter]
+    fn metadata(&self) -> PyResult<String> {
+        serde_json::to_string(&self.array.metadata())
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Retrieve the array subset with `start` and `shape` as a new `NumPy` array.
+    ///
+    /// `start` defaults to the array origin and `shape` defaults to the remainder of the array.
+    #[pyo3(signature = (start = None, shape = None))]
+    fn retrieve<'py>(
+        &self,
+        py: Python<'py>,
+        start: Option<Vec<u64>>,
+        shape: Option<Vec<u64>>,
+    ) -> PyResult<Bound<'py, PyUntypedArray>> {
+        let array_subset = array_subset(&self.array, start, shape)?;
+        dispatch_element_type!(
+            self.array.data_type(),
+            Self::retrieve_elements(self, py, &array_subset)
+        )
+    }
+
+    /// Store the C-contiguous `NumPy` array `elements` in the array subset starting at `start`.
+    ///
+    /// The data type of `elements` must match the array data type.
+    fn store(
+        &self,
+        py: Python<'_>,
+        start: Vec<u64>,
+        elements: &Bound<'_, PyUntypedArray>,
+    ) -> PyResult<()> {
+        let shape = elements.shape().iter().map(|&size| size as u64).collect();
+        let array_subset = array_subset(&self.array, Some(start), Some(shape))?;
+        dispatch_element_type!(
+            self.array.data_type(),
+            Self::store_elements(self, py, &array_subset, elements)
+        )
+    }
+}
+
+/// The `zarrs` Python module.
+#[pymodule]
+fn zarrs(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Fail on import rather than on first use if NumPy is not installed
+    module.py().import("numpy")?;
+    module.add_class::<PythonArray>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use numpy::{PyArray2, PyArrayDyn};
+
+    use crate::array::{ArrayBuilder, FillValue};
+
+    use super::*;
+
+    #[test]
+    fn python_numpy_dtype() {
+        assert_eq!(numpy_dtype(&DataType::Float32), Some("float32"));
+        assert_eq!(numpy_dtype(&DataType::Complex128), Some("complex128"));
+        assert_eq!(numpy_dtype(&DataType::BFloat16), None);
+        assert_eq!(numpy_dtype(&DataType::RawBits(2)), None);
+    }
+
+    #[test]
+    fn python_array_store_retrieve() {
+        let path = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(FilesystemStore::new(path.path()).unwrap());
+        ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt16,
+            vec![2, 3].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store, "/array")
+        .unwrap()
+        .store_metadata()
+        .unwrap();
+
+        Python::initialize();
+        Python::attach(|py| {
+            if let Err(err) = py.import("numpy") {
+                eprintln!("skipping python_array_store_retrieve, NumPy is not available: {err}");
+                return;
+            }
+            let array = PythonArray::new(path.path().to_str().unwrap(), "/array").unwrap();
+            assert_eq!(array.dtype().unwrap(), "uint16");
+
+            let elements: Vec<Vec<u16>> = (0..3)
+                .map(|i| (0..4).map(|j| i * 4 + j).collect())
+                .collect();
+            let elements = PyArray2::from_vec2(py, &elements).unwrap();
+            array.store(py, vec![1, 0], elements.as_untyped()).unwrap();
+            let retrieved = array.retrieve(py, None, None).unwrap();
+            assert_eq!(retrieved.shape(), [4, 4]);
+            let retrieved = retrieved.cast::<PyArrayDyn<u16>>().unwrap().readonly();
+            let expected: Vec<u16> = [0; 4].into_iter().chain(0..12).collect();
+            assert_eq!(retrieved.as_slice().unwrap(), expected);
+            let retrieved = array
+                .retrieve(py, Some(vec![2, 1]), Some(vec![1, 2]))
+                .unwrap()
+                .cast::<PyArrayDyn<u16>>()
+                .unwrap()
+                .to_vec()
+                .unwrap();
+            assert_eq!(retrieved, [5, 6]);
+
+            // Elements that are not C-contiguous are rejected
+            let elements = PyArray2::from_vec2(py, &[vec![1u16, 2], vec![3, 4]]).unwrap();
+            let transposed = elements.transpose().unwrap();
+            let err = array
+                .store(py, vec![0, 0], transposed.as_untyped())
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(err.to_string().contains("contiguous"), "{err}");
+            // The array is unchanged
+            let retrieved = array
+                .retrieve(py, Some(vec![0, 0]), Some(vec![2, 2]))
+                .unwrap()
+                .cast::<PyArrayDyn<u16>>()
+                .unwrap()
+                .to_vec()
+                .unwrap();
+            assert_eq!(retrieved, [0, 0, 0, 1]);
+
+            // Elements of a different data type are rejected
+            let elements = PyArray2::from_vec2(py, &[vec![1.0f32]]).unwrap();
+            assert!(array.store(py, vec![0, 0], elements.as_untyped()).is_err());
+        });
+    }
+}
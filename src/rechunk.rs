@@ -0,0 +1,535 @@
+//! Rechunking.
+//!
+//! [`rechunk`] copies an array to a new array with a different chunk grid, codecs, or other metadata (as defined by an [`ArrayBuilder`]).
+//!
+//! The copy is streamed through chunk-aligned blocks of the destination array, so that every destination chunk is encoded and stored exactly once and memory usage is bounded.
+//! The block shape is chosen to cover as much of a source chunk as possible within the [memory limit](RechunkOptions::set_memory_limit), which reduces redundant decoding of source chunks.
+//! Blocks are processed in parallel with [`rayon`].
+//!
+//! [`reshard`] converts an array to the `sharding_indexed` codec (or changes its shard shape) in place, which reduces the number of stored objects.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use zarrs::{array::{DataType, FillValue}, array_subset::ArraySubset, storage::store::MemoryStore};
+//! # let store = Arc::new(MemoryStore::new());
+//! # let array = ArrayBuilder::new(vec![8, 8], DataType::UInt8, vec![8, 1].try_into()?, FillValue::from(0u8))
+//! #     .build(store.clone(), "/array")?;
+//! # array.store_array_subset_elements(&ArraySubset::new_with_shape(vec![8, 8]), (0..64u8).collect())?;
+//! use zarrs::{array::ArrayBuilder, rechunk::{rechunk, RechunkOptions}};
+//!
+//! let mut builder = ArrayBuilder::from_array(&array);
+//! builder.chunk_grid(vec![1, 8].try_into()?);
+//! let rechunked = rechunk(&array, &builder, store, "/rechunked", &RechunkOptions::default())?;
+//! assert_eq!(rechunked.retrieve_chunk_elements::<u8>(&[1, 0])?, (8..16).collect::<Vec<_>>());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::Arc;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon_iter_concurrent_limit::iter_concurrent_limit;
+use thiserror::Error;
+
+use crate::{
+    array::{codec::CodecOptions, Array, ArrayBuilder, ArrayCreateError, ArrayError, ArrayShape},
+    array_subset::ArraySubset,
+    storage::{ReadableStorageTraits, StorageError, WritableStorageTraits},
+};
+
+#[cfg(feature = "sharding")]
+use crate::{array::ChunkShape, storage::ReadableWritableStorageTraits};
+
+/// Options for [`rechunk`].
+#[derive(Debug, Clone)]
+pub struct RechunkOptions {
+    memory_limit: u64,
+    concurrent_target: usize,
+    codec_options: CodecOptions,
+}
+
+impl Default for RechunkOptions {
+    fn default() -> Self {
+        Self {
+            memory_limit: 1 << 30,
+            concurrent_target: rayon::current_num_threads(),
+            codec_options: CodecOptions::default(),
+        }
+    }
+}
+
+impl RechunkOptions {
+    /// Return the memory limit in bytes.
+    #[must_use]
+    pub fn memory_limit(&self) -> u64 {
+        self.memory_limit
+    }
+
+    /// Set the memory limit in bytes. Defaults to 1 GiB.
+    ///
+    /// This is the maximum combined size of the decoded blocks being copied concurrently.
+    /// A block is always at least one destination chunk, so the limit can be exceeded if it is smaller than the destination chunk size multiplied by the concurrent target.
+    /// Note that source chunks intersecting a block are also decoded in their entirety.
+    pub fn set_memory_limit(&mut self, memory_limit: u64) -> &mut Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// Return the concurrent target.
+    #[must_use]
+    pub fn concurrent_target(&self) -> usize {
+        self.concurrent_target
+    }
+
+    /// Set the number of blocks to copy concurrently. Defaults to the number of [`rayon`] threads.
+    pub fn set_concurrent_target(&mut self, concurrent_target: usize) -> &mut Self {
+        self.concurrent_target = concurrent_target;
+        self
+    }
+
+    /// Return the codec options.
+    #[must_use]
+    pub fn codec_options(&self) -> &CodecOptions {
+        &self.codec_options
+    }
+
+    /// Set the codec options used to retrieve and store chunks.
+    pub fn set_codec_options(&mut self, codec_options: CodecOptions) -> &mut Self {
+        self.codec_options = codec_options;
+        self
+    }
+}
+
+/// Copy `src` to a new array at `dst_path` in `dst_storage` built with `dst_builder`.
+///
+/// The destination array must have the same shape and data type as the source array.
+/// The metadata of the destination array is stored and the destination array is returned.
+///
+/// # Errors
+/// Returns a [`RechunkError`] if
+///  - the destination array cannot be built,
+///  - the shape or data type of the destination array differs from the source array, or
+///  - there is an underlying array or store error.
+#[allow(clippy::needless_pass_by_value)]
+pub fn rechunk<
+    TSrcStorage: ?Sized + ReadableStorageTraits + 'static,
+    TDstStorage: ?Sized + WritableStorageTraits + 'static,
+>(
+    src: &Array<TSrcStorage>,
+    dst_builder: &ArrayBuilder,
+    dst_storage: Arc<TDstStorage>,
+    dst_path: &str,
+    options: &RechunkOptions,
+) -> Result<Array<TDstStorage>, RechunkError> {
+    let dst = dst_builder.build(dst_storage, dst_path)?;
+    if dst.shape() != src.shape() {
+        return Err(RechunkError::IncompatibleShape(
+            src.shape().to_vec(),
+            dst.shape().to_vec(),
+        ));
+    }
+    if dst.data_type().name() != src.data_type().name() {
+        return Err(RechunkError::IncompatibleDataType(
+            src.data_type().name(),
+            dst.data_type().name(),
+        ));
+    }
+    dst.store_metadata()?;
+
+    let chunk_grid_shape = dst.chunk_grid_shape().ok_or_else(|| {
+        StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string())
+    })?;
+    if chunk_grid_shape.contains(&0) {
+        return Ok(dst);
+    }
+
+    // Partition the destination chunk grid into blocks
+    let concurrent_target = std::cmp::max(options.concurrent_target, 1);
+    let block_memory_limit = options.memory_limit / concurrent_target as u64;
+    let block_chunks = block_chunks(src, &dst, &chunk_grid_shape, block_memory_limit)?;
+    let block_grid_shape: ArrayShape = std::iter::zip(&chunk_grid_shape, &block_chunks)
+        .map(|(chunks, block_chunks)| chunks.div_ceil(*block_chunks))
+        .collect();
+
+    let copy_block = |block_indices: Vec<u64>| -> Result<(), RechunkError> {
+        let chunks_start: Vec<u64> = std::iter::zip(&block_indices, &block_chunks)
+            .map(|(index, block_chunks)| index * block_chunks)
+            .collect();
+        let chunks_end: Vec<u64> =
+            itertools::izip!(&chunks_start, &block_chunks, &chunk_grid_shape)
+                .map(|(start, block_chunks, chunks)| std::cmp::min(start + block_chunks, *chunks))
+                .collect();
+        let chunks =
+            unsafe { ArraySubset::new_with_start_end_exc_unchecked(chunks_start, chunks_end) };
+        let block_subset = dst.chunks_subset(&chunks)?;
+        let block_subset_bounded = unsafe { block_subset.bound_unchecked(dst.shape()) };
+        let mut block_bytes =
+            src.retrieve_array_subset_opt(&block_subset_bounded, &options.codec_options)?;
+        if block_subset_bounded != block_subset {
+            // Pad edge chunks with the fill value
+            let mut block_bytes_padded = dst
+                .fill_value()
+                .as_ne_bytes()
+                .repeat(block_subset.num_elements_usize());
+            unsafe {
+                block_subset_bounded
+                    .relative_to_unchecked(block_subset.start())
+                    .store_bytes_unchecked(
+                        &block_bytes,
+                        &mut block_bytes_padded,
+                        block_subset.shape(),
+                        dst.data_type().size(),
+                    );
+            }
+            block_bytes = block_bytes_padded;
+        }
+        dst.store_chunks_opt(&chunks, block_bytes, &options.codec_options)?;
+        Ok(())
+    };
+    let blocks = ArraySubset::new_with_shape(block_grid_shape);
+    let indices = blocks.indices();
+    iter_concurrent_limit!(concurrent_target, indices, try_for_each, copy_block)?;
+
+    Ok(dst)
+}
+
+/// Convert `array` in place to a sharded array with shards of shape `shard_shape`.
+///
+/// The chunks of an unsharded array become the inner chunks of the shards and keep their codecs.
+/// The inner chunks and codecs of a sharded array are retained and only the shard shape is changed.
+///
+/// Shards are first encoded into a staging array below the array path with [`rechunk`], so memory usage is bounded and shards are processed in parallel as configured by `options`.
+/// The encoded shards are then moved over the existing chunks, chunks outside of the new chunk grid are erased, and the new metadata is stored last.
+/// The array is left in an inconsistent state if resharding is interrupted while moving shards.
+///
+/// The returned array has the new metadata, and `array` should not be used afterwards.
+///
+/// # Errors
+/// Returns a [`RechunkError`] if
+///  - `shard_shape` is not a multiple of the inner chunk shape, or
+///  - there is an underlying array or store error.
+#[cfg(feature = "sharding")]
+pub fn reshard<TStorage: ?Sized + ReadableWritableStorageTraits + 'static>(
+    array: &Array<TStorage>,
+    shard_shape: ChunkShape,
+    options: &RechunkOptions,
+) -> Result<Array<TStorage>, RechunkError> {
+    use crate::array::{
+        chunk_shape_to_array_shape, codec::array_to_bytes::sharding::ShardingCodecBuilder,
+        ArrayShardedExt,
+    };
+
+    let mut builder = ArrayBuilder::from_array(array);
+    let inner_chunk_shape = if let Some(inner_chunk_shape) = array.inner_chunk_shape() {
+        inner_chunk_shape
+    } else {
+        let inner_chunk_shape = array.chunk_shape(&vec![0; array.dimensionality()])?;
+        let codecs = array.codecs();
+        let sharding_codec = ShardingCodecBuilder::new(inner_chunk_shape.clone())
+            .array_to_array_codecs(codecs.array_to_array_codecs().to_vec())
+            .array_to_bytes_codec(codecs.array_to_bytes_codec().clone())
+            .bytes_to_bytes_codecs(codecs.bytes_to_bytes_codecs().to_vec())
+            .build();
+        builder
+            .array_to_array_codecs(vec![])
+            .array_to_bytes_codec(Box::new(sharding_codec))
+            .bytes_to_bytes_codecs(vec![]);
+        inner_chunk_shape
+    };
+    if shard_shape.len() != inner_chunk_shape.len()
+        || std::iter::zip(shard_shape.as_slice(), inner_chunk_shape.as_slice())
+            .any(|(shard, inner)| shard.get() % inner.get() != 0)
+    {
+        return Err(RechunkError::IncompatibleShardShape(
+            chunk_shape_to_array_shape(&shard_shape),
+            chunk_shape_to_array_shape(&inner_chunk_shape),
+        ));
+    }
+    builder.chunk_grid(shard_shape.into());
+
+    // Encode the shards into a staging array
+    let storage = array.storage().clone();
+    let staged_path = if array.path().as_str() == "/" {
+        "/__reshard".to_string()
+    } else {
+        format!("{}/__reshard", array.path().as_str())
+    };
+    let staged = rechunk(array, &builder, storage.clone(), &staged_path, options)?;
+    let resharded = builder.build(storage, array.path().as_str())?;
+
+    // Move the shards over the existing chunks
+    let concurrent_target = std::cmp::max(options.concurrent_target, 1);
+    let unsupported =
+        || StorageError::Unsupported("the chunk grid shape of the array is unknown".to_string());
+    let shard_grid_shape = resharded.chunk_grid_shape().ok_or_else(unsupported)?;
+    let move_shard = |shard_indices: Vec<u64>| -> Result<(), RechunkError> {
+        if let Some(shard) = staged.retrieve_encoded_chunk(&shard_indices)? {
+            resharded.store_encoded_chunk(&shard_indices, &shard)?;
+            staged.erase_chunk(&shard_indices)?;
+        } else {
+            resharded.erase_chunk(&shard_indices)?;
+        }
+        Ok(())
+    };
+    let indices = ArraySubset::new_with_shape(shard_grid_shape.clone()).indices();
+    iter_concurrent_limit!(concurrent_target, indices, try_for_each, move_shard)?;
+    staged.erase_metadata()?;
+
+    // Erase chunks that were not overwritten by a shard
+    let chunk_grid_shape = array.chunk_grid_shape().ok_or_else(unsupported)?;
+    let erase_chunk = |chunk_indices: Vec<u64>| -> Result<(), RechunkError> {
+        if std::iter::zip(&chunk_indices, &shard_grid_shape).any(|(index, shards)| index >= shards)
+        {
+            array.erase_chunk(&chunk_indices)?;
+        }
+        Ok(())
+    };
+    let indices = ArraySubset::new_with_shape(chunk_grid_shape).indices();
+    iter_concurrent_limit!(concurrent_target, indices, try_for_each, erase_chunk)?;
+
+    resharded.store_metadata()?;
+    Ok(resharded)
+}
+
+/// Return the number of destination chunks per block in each dimension.
+///
+/// Blocks are grown from the last dimension to the first towards covering the shape of the first source chunk, while remaining within `block_memory_limit`.
+fn block_chunks<TSrcStorage: ?Sized, TDstStorage: ?Sized>(
+    src: &Array<TSrcStorage>,
+    dst: &Array<TDstStorage>,
+    chunk_grid_shape: &[u64],
+    block_memory_limit: u64,
+) -> Result<Vec<u64>, ArrayError> {
+    let zeros = vec![0; src.dimensionality()];
+    let src_chunk_shape = src.chunk_shape(&zeros)?;
+    let dst_chunk_shape = dst.chunk_shape(&zeros)?;
+    let element_size = dst.data_type().size() as u64;
+
+    let mut block_chunks = vec![1; dst.dimensionality()];
+    let block_size = |block_chunks: &[u64]| {
+        std::iter::zip(block_chunks, dst_chunk_shape.as_slice())
+            .map(|(block_chunks, chunk_size)| block_chunks * chunk_size.get())
+            .product::<u64>()
+            * element_size
+    };
+    for dim in (0..block_chunks.len()).rev() {
+        let target = std::cmp::min(
+            src_chunk_shape[dim]
+                .get()
+                .div_ceil(dst_chunk_shape[dim].get()),
+            chunk_grid_shape[dim],
+        );
+        while block_chunks[dim] < target {
+            block_chunks[dim] += 1;
+            if block_size(&block_chunks) > block_memory_limit {
+                block_chunks[dim] -= 1;
+                return Ok(block_chunks);
+            }
+        }
+    }
+    Ok(block_chunks)
+}
+
+/// A rechunk error.
+#[derive(Debug, Error)]
+pub enum RechunkError {
+    /// An array creation error.
+    #[error(transparent)]
+    ArrayCreateError(#[from] ArrayCreateError),
+    /// An array error.
+    #[error(transparent)]
+    ArrayError(#[from] ArrayError),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+    /// The destination array shape differs from the source array shape.
+    #[error("destination array shape {1:?} differs from the source array shape {0:?}")]
+    IncompatibleShape(ArrayShape, ArrayShape),
+    /// The destination array data type differs from the source array data type.
+    #[error("destination array data type {1} differs from the source array data type {0}")]
+    IncompatibleDataType(String, String),
+    /// The shard shape is not a multiple of the inner chunk shape.
+    #[error("shard shape {0:?} is not a multiple of the inner chunk shape {1:?}")]
+    IncompatibleShardShape(ArrayShape, ArrayShape),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    use super::*;
+
+    fn src_array(store: &Arc<MemoryStore>) -> Array<MemoryStore> {
+        let array = ArrayBuilder::new(
+            vec![9, 10],
+            DataType::UInt16,
+            vec![9, 1].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/src")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![9, 10]),
+                (0..90).collect(),
+            )
+            .unwrap();
+        array
+    }
+
+    #[test]
+    fn rechunk_copy() {
+        let store = Arc::new(MemoryStore::new());
+        let src = src_array(&store);
+        let mut builder = ArrayBuilder::from_array(&src);
+        builder.chunk_grid(vec![2, 3].try_into().unwrap());
+
+        // A memory limit smaller than a destination chunk, so every block is a single chunk
+        let mut options = RechunkOptions::default();
+        options.set_memory_limit(1).set_concurrent_target(2);
+        let dst = rechunk(&src, &builder, store.clone(), "/dst", &options).unwrap();
+        assert_eq!(block_chunks(&src, &dst, &[5, 4], 1).unwrap(), vec![1, 1]);
+        assert_eq!(dst.chunk_grid_shape(), Some(vec![5, 4]));
+        assert_eq!(
+            dst.retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![9, 10]))
+                .unwrap(),
+            (0..90).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            dst.retrieve_chunk_elements::<u16>(&[4, 3]).unwrap(),
+            vec![89, 0, 0, 0, 0, 0]
+        );
+
+        // Blocks covering the source chunks
+        let dst = rechunk(
+            &src,
+            &builder,
+            store.clone(),
+            "/dst2",
+            &RechunkOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            block_chunks(&src, &dst, &[5, 4], 1 << 30).unwrap(),
+            vec![5, 1]
+        );
+        assert_eq!(
+            Array::new(store.clone(), "/dst2")
+                .unwrap()
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![9, 10]))
+                .unwrap(),
+            (0..90).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "sharding")]
+    #[test]
+    fn reshard_in_place() {
+        use crate::{
+            array::ArrayShardedExt,
+            storage::{ListableStorageTraits, StoreKey},
+        };
+
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![9, 10],
+            DataType::UInt16,
+            vec![3, 2].try_into().unwrap(),
+            FillValue::from(0u16),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        let mut elements: Vec<u16> = (0..90).collect();
+        for element in &mut elements[60..] {
+            if *element % 10 >= 8 {
+                *element = 0;
+            }
+        }
+        array
+            .store_array_subset_elements::<u16>(
+                &ArraySubset::new_with_shape(vec![9, 10]),
+                elements.clone(),
+            )
+            .unwrap();
+
+        let mut options = RechunkOptions::default();
+        options.set_memory_limit(1);
+        let resharded = reshard(&array, vec![6, 4].try_into().unwrap(), &options).unwrap();
+        assert!(resharded.is_sharded());
+        assert_eq!(
+            resharded.inner_chunk_shape(),
+            Some(vec![3, 2].try_into().unwrap())
+        );
+        assert_eq!(resharded.chunk_grid_shape(), Some(vec![2, 3]));
+        let array = Array::new(store.clone(), "/array").unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![9, 10]))
+                .unwrap(),
+            elements
+        );
+
+        // The fill value shard [1, 2] is not stored and the colliding chunk was erased
+        let mut keys = store.list().unwrap();
+        keys.sort();
+        let expected: Vec<StoreKey> = [
+            "array/c/0/0",
+            "array/c/0/1",
+            "array/c/0/2",
+            "array/c/1/0",
+            "array/c/1/1",
+            "array/zarr.json",
+        ]
+        .into_iter()
+        .map(|key| StoreKey::new(key).unwrap())
+        .collect();
+        assert_eq!(keys, expected);
+
+        // Change the shard shape
+        let resharded = reshard(&array, vec![3, 10].try_into().unwrap(), &options).unwrap();
+        assert_eq!(resharded.chunk_grid_shape(), Some(vec![3, 1]));
+        assert_eq!(
+            Array::new(store.clone(), "/array")
+                .unwrap()
+                .retrieve_array_subset_elements::<u16>(&ArraySubset::new_with_shape(vec![9, 10]))
+                .unwrap(),
+            elements
+        );
+        assert_eq!(store.list().unwrap().len(), 4);
+
+        assert!(matches!(
+            reshard(&resharded, vec![4, 4].try_into().unwrap(), &options),
+            Err(RechunkError::IncompatibleShardShape(..))
+        ));
+    }
+
+    #[test]
+    fn rechunk_incompatible() {
+        let store = Arc::new(MemoryStore::new());
+        let src = src_array(&store);
+        let mut builder = ArrayBuilder::from_array(&src);
+        builder.shape(vec![9, 11]);
+        assert!(matches!(
+            rechunk(
+                &src,
+                &builder,
+                store.clone(),
+                "/dst",
+                &RechunkOptions::default()
+            ),
+            Err(RechunkError::IncompatibleShape(..))
+        ));
+        let mut builder = ArrayBuilder::from_array(&src);
+        builder
+            .data_type(DataType::Int16)
+            .fill_value(FillValue::from(0i16));
+        assert!(matches!(
+            rechunk(&src, &builder, store, "/dst", &RechunkOptions::default()),
+            Err(RechunkError::IncompatibleDataType(..))
+        ));
+    }
+}
@@ -11,14 +11,20 @@
 //!
 //! This module defines abstract store interfaces, includes various store and storage transformers, and has functions for performing the store operations defined at <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#operations>.
 
+mod etag;
+mod gc;
+mod hierarchy_check;
+mod metadata_json;
+mod metadata_write_options;
 pub mod storage_adapter;
 mod storage_handle;
 mod storage_sync;
 pub mod storage_transformer;
 mod storage_value_io;
 pub mod store;
+mod store_capabilities;
 mod store_key;
-// pub mod store_lock;
+pub mod store_lock;
 mod store_prefix;
 
 #[cfg(feature = "async")]
@@ -34,24 +40,27 @@ use crate::{
     node::{NodeNameError, NodePath, NodePathError},
 };
 
+pub use store_capabilities::StoreCapabilities;
 pub use store_key::{StoreKey, StoreKeyError, StoreKeys};
 pub use store_prefix::{StorePrefix, StorePrefixError, StorePrefixes};
 
 #[cfg(feature = "async")]
 pub use self::storage_async::{
-    async_create_array, async_create_group, async_discover_children, async_discover_nodes,
-    async_erase_chunk, async_erase_metadata, async_erase_node, async_get_child_nodes,
-    async_node_exists, async_node_exists_listable, async_retrieve_chunk,
-    async_retrieve_partial_values, async_store_chunk, async_store_set_partial_values,
-    AsyncListableStorageTraits, AsyncReadableListableStorageTraits, AsyncReadableStorageTraits,
+    async_copy_node, async_create_array, async_create_group, async_discover_children,
+    async_discover_nodes, async_erase_chunk, async_erase_metadata, async_erase_node,
+    async_get_child_nodes, async_move_node, async_node_exists, async_node_exists_listable,
+    async_retrieve_chunk, async_retrieve_partial_values, async_store_chunk,
+    async_store_metadata_opt, async_store_set_partial_values, AsyncListableStorageTraits,
+    AsyncReadableListableStorageTraits, AsyncReadableStorageTraits,
     AsyncReadableWritableListableStorageTraits, AsyncReadableWritableStorageTraits,
     AsyncWritableStorageTraits,
 };
 
 pub use self::storage_sync::{
-    create_array, create_group, discover_children, discover_nodes, erase_chunk, erase_metadata,
-    erase_node, get_child_nodes, node_exists, node_exists_listable, retrieve_chunk,
-    retrieve_partial_values, store_chunk, store_set_partial_values, ListableStorageTraits,
+    copy_node, create_array, create_group, create_metadata_if_not_exists, discover_children,
+    discover_nodes, erase_chunk, erase_metadata, erase_node, get_child_nodes, move_node,
+    node_exists, node_exists_listable, retrieve_chunk, retrieve_partial_values, store_chunk,
+    store_metadata_opt, store_set_partial_values, ListableStorageTraits,
     ReadableListableStorageTraits, ReadableStorageTraits, ReadableWritableListableStorageTraits,
     ReadableWritableStorageTraits, WritableStorageTraits,
 };
@@ -61,6 +70,15 @@ pub use self::storage_handle::StorageHandle;
 
 pub use storage_value_io::StorageValueIO;
 
+pub use etag::ETag;
+
+pub(crate) use metadata_json::metadata_to_json_opt;
+pub use metadata_write_options::{FillValueFloatFormat, MetadataWriteOptions};
+
+pub use hierarchy_check::{check_hierarchy, HierarchyCheckReport, HierarchyIssue};
+
+pub use gc::{gc, gc_dry_run, gc_dry_run_opt, gc_opt, GcOptions, GcReport};
+
 /// [`Arc`] wrapped readable storage.
 pub type ReadableStorage = Arc<dyn ReadableStorageTraits>;
 
@@ -122,6 +140,35 @@ impl std::fmt::Display for StoreKeyRange {
     }
 }
 
+/// A [`StoreKey`] and value (bytes).
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct StoreKeyValue<'a> {
+    /// The key.
+    key: StoreKey,
+    /// The store value.
+    value: &'a [u8],
+}
+
+impl StoreKeyValue<'_> {
+    /// Create a new [`StoreKeyValue`].
+    pub const fn new(key: StoreKey, value: &[u8]) -> StoreKeyValue<'_> {
+        StoreKeyValue { key, value }
+    }
+
+    /// Return the key.
+    #[must_use]
+    pub const fn key(&self) -> &StoreKey {
+        &self.key
+    }
+
+    /// Return the value.
+    #[must_use]
+    pub const fn value(&self) -> &[u8] {
+        self.value
+    }
+}
+
 /// A [`StoreKey`], [`ByteOffset`], and value (bytes).
 #[derive(Debug, Clone)]
 #[must_use]
@@ -136,7 +183,7 @@ pub struct StoreKeyStartValue<'a> {
 
 impl StoreKeyStartValue<'_> {
     /// Create a new [`StoreKeyStartValue`].
-    pub const fn new(key: StoreKey, start: ByteOffset, value: &[u8]) -> StoreKeyStartValue {
+    pub const fn new(key: StoreKey, start: ByteOffset, value: &[u8]) -> StoreKeyStartValue<'_> {
         StoreKeyStartValue { key, start, value }
     }
 
@@ -169,12 +216,26 @@ impl StoreKeysPrefixes {
     }
 }
 
+/// The progress of a [`copy_node`] or [`move_node`] operation.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct NodeTransferProgress {
+    /// The number of keys transferred.
+    pub keys_transferred: usize,
+    /// The total number of keys to transfer.
+    pub keys_total: usize,
+    /// The number of bytes transferred.
+    pub bytes_transferred: u64,
+}
+
 /// A storage error.
 #[derive(Debug, Error)]
 pub enum StorageError {
     /// A write operation was attempted on a read only store.
     #[error("a write operation was attempted on a read only store")]
     ReadOnly,
+    /// A write operation attempted to modify an existing key in a write once store.
+    #[error("a write operation attempted to modify existing key {0} in a write once store")]
+    WriteOnce(StoreKey),
     /// An IO error.
     #[error(transparent)]
     IOError(#[from] std::io::Error),
@@ -208,6 +269,40 @@ pub enum StorageError {
     /// Any other error.
     #[error("{0}")]
     Other(String),
+    /// An error accessing a store key.
+    #[error("{_0}: {_1}")]
+    KeyError(StoreKey, #[source] Box<StorageError>),
+}
+
+impl StorageError {
+    /// Add the store key `key` to the error context.
+    ///
+    /// An error that already has a key is returned unchanged.
+    #[must_use]
+    pub fn with_key(self, key: StoreKey) -> Self {
+        match self {
+            Self::KeyError(..) => self,
+            err => Self::KeyError(key, Box::new(err)),
+        }
+    }
+
+    /// Return the store key of the error context, if any.
+    #[must_use]
+    pub fn key(&self) -> Option<&StoreKey> {
+        match self {
+            Self::KeyError(key, _) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Return the underlying error without its context.
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::KeyError(_, err) => err.root(),
+            err => err,
+        }
+    }
 }
 
 impl From<&str> for StorageError {
@@ -222,6 +317,71 @@ impl From<String> for StorageError {
     }
 }
 
+/// A metadata write error.
+#[derive(Debug, Error)]
+pub enum MetadataWriteError {
+    /// The metadata could not be serialised.
+    #[error("error serialising metadata for {0}: {1}")]
+    InvalidMetadata(StoreKey, String),
+    /// A storage error occurred on the final write attempt.
+    #[error("failed to write metadata {key} after {attempts} attempt(s): {source}")]
+    StorageError {
+        /// The metadata key.
+        key: StoreKey,
+        /// The number of write attempts.
+        attempts: usize,
+        /// The serialised metadata document.
+        document: String,
+        /// The storage error of the final attempt.
+        source: StorageError,
+    },
+    /// The stored metadata was modified by another writer on every update attempt.
+    #[error(
+        "failed to update metadata {key} after {attempts} attempt(s), it was modified concurrently"
+    )]
+    Conflict {
+        /// The metadata key.
+        key: StoreKey,
+        /// The number of update attempts.
+        attempts: usize,
+    },
+    /// The metadata read back from the store did not match the serialised document on the final write attempt.
+    #[error("failed to verify metadata {key} after {attempts} attempt(s)")]
+    VerificationFailed {
+        /// The metadata key.
+        key: StoreKey,
+        /// The number of write attempts.
+        attempts: usize,
+        /// The serialised metadata document.
+        document: String,
+    },
+}
+
+impl MetadataWriteError {
+    /// Returns the serialised metadata document that failed to write, if it could be serialised.
+    #[must_use]
+    pub fn document(&self) -> Option<&str> {
+        match self {
+            Self::InvalidMetadata(..) | Self::Conflict { .. } => None,
+            Self::StorageError { document, .. } | Self::VerificationFailed { document, .. } => {
+                Some(document)
+            }
+        }
+    }
+}
+
+impl From<MetadataWriteError> for StorageError {
+    fn from(err: MetadataWriteError) -> Self {
+        match err {
+            MetadataWriteError::InvalidMetadata(key, err) => Self::InvalidMetadata(key, err),
+            MetadataWriteError::StorageError { source, .. } => source,
+            MetadataWriteError::VerificationFailed { .. } | MetadataWriteError::Conflict { .. } => {
+                Self::Other(err.to_string())
+            }
+        }
+    }
+}
+
 #[cfg(feature = "opendal")]
 impl From<opendal::Error> for StorageError {
     fn from(err: opendal::Error) -> Self {
@@ -241,6 +401,31 @@ pub fn meta_key(path: &NodePath) -> StoreKey {
     }
 }
 
+/// Serialise node metadata to a pretty-printed JSON document.
+///
+/// If [deterministic encode](crate::config::Config#deterministic-encode) is enabled, the keys of the attributes are sorted.
+pub(crate) fn metadata_to_json<T: serde::Serialize + ?Sized>(
+    metadata: &T,
+) -> Result<String, serde_json::Error> {
+    metadata_to_json_opt(metadata, None, &MetadataWriteOptions::default())
+}
+
+/// Recursively sort the keys of the objects in `value`.
+fn sort_json_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (_, value) in &mut entries {
+                sort_json_keys(value);
+            }
+            *map = entries.into_iter().collect();
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(sort_json_keys),
+        _ => {}
+    }
+}
+
 /// Return the data key given a node path, chunk grid coordinates, and a chunk key encoding.
 #[must_use]
 pub fn data_key(
@@ -283,10 +468,22 @@ pub fn data_key(
 mod tests {
     use std::io::Write;
 
+    use crate::node::NodeMetadata;
+
     use self::store::MemoryStore;
 
     use super::*;
 
+    #[test]
+    fn sort_json_keys_nested() {
+        let mut value = serde_json::json!({"b": [{"d": 1, "c": 2}], "a": {"f": 3, "e": 4}});
+        sort_json_keys(&mut value);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"a":{"e":4,"f":3},"b":[{"c":2,"d":1}]}"#
+        );
+    }
+
     #[test]
     fn transformers_multithreaded() {
         use rayon::prelude::*;
@@ -317,12 +514,12 @@ mod tests {
 
         (0..10).into_par_iter().for_each(|i| {
             transformer
-                .set(&StoreKey::new(&i.to_string()).unwrap(), &[i; 5])
+                .set(&StoreKey::new(i.to_string()).unwrap(), &[i; 5])
                 .unwrap();
         });
 
         for i in 0..10 {
-            let _ = transformer.get(&StoreKey::new(&i.to_string()).unwrap());
+            let _ = transformer.get(&StoreKey::new(i.to_string()).unwrap());
         }
 
         log_writer.lock().unwrap().flush().unwrap();
@@ -335,4 +532,232 @@ mod tests {
             storage_transformer_performance_metrics.reads()
         );
     }
+
+    /// A store which truncates the first `truncated_writes` writes.
+    struct TruncatingStore {
+        store: MemoryStore,
+        truncated_writes: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ReadableStorageTraits for TruncatingStore {
+        fn get(&self, key: &StoreKey) -> Result<crate::array::MaybeBytes, StorageError> {
+            self.store.get(key)
+        }
+
+        fn get_partial_values_key(
+            &self,
+            key: &StoreKey,
+            byte_ranges: &[ByteRange],
+        ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+            self.store.get_partial_values_key(key, byte_ranges)
+        }
+
+        fn get_partial_values(
+            &self,
+            key_ranges: &[StoreKeyRange],
+        ) -> Result<Vec<crate::array::MaybeBytes>, StorageError> {
+            self.store.get_partial_values(key_ranges)
+        }
+
+        fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+            self.store.size_prefix(prefix)
+        }
+
+        fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+            self.store.size_key(key)
+        }
+    }
+
+    impl WritableStorageTraits for TruncatingStore {
+        fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+            use std::sync::atomic::Ordering;
+            let truncate = self
+                .truncated_writes
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if truncate {
+                self.store.set(key, &value[..value.len() / 2])
+            } else {
+                self.store.set(key, value)
+            }
+        }
+
+        fn set_partial_values(
+            &self,
+            key_start_values: &[StoreKeyStartValue],
+        ) -> Result<(), StorageError> {
+            self.store.set_partial_values(key_start_values)
+        }
+
+        fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+            self.store.erase(key)
+        }
+
+        fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+            self.store.erase_prefix(prefix)
+        }
+    }
+
+    impl ReadableWritableStorageTraits for TruncatingStore {}
+
+    #[test]
+    fn store_metadata_verify_retry() {
+        let store = TruncatingStore {
+            store: MemoryStore::default(),
+            truncated_writes: 2.into(),
+        };
+        let path = NodePath::root();
+        let metadata = NodeMetadata::Group(crate::group::GroupMetadataV3::default().into());
+
+        let mut options = MetadataWriteOptions::default();
+        options.set_verify(true);
+        options.set_max_retries(1);
+        options.set_retry_delay(std::time::Duration::ZERO);
+        let err = store_metadata_opt(&store, &path, &metadata, &options).unwrap_err();
+        assert!(matches!(
+            err,
+            MetadataWriteError::VerificationFailed { attempts: 2, .. }
+        ));
+        assert_eq!(
+            err.document().unwrap(),
+            serde_json::to_string_pretty(&metadata).unwrap()
+        );
+
+        store
+            .truncated_writes
+            .store(2, std::sync::atomic::Ordering::SeqCst);
+        options.set_max_retries(2);
+        store_metadata_opt(&store, &path, &metadata, &options).unwrap();
+        assert_eq!(
+            store.get(&meta_key(&path)).unwrap().unwrap(),
+            serde_json::to_vec_pretty(&metadata).unwrap()
+        );
+    }
+
+    #[test]
+    fn store_capabilities() {
+        let store = Arc::new(MemoryStore::new());
+        let capabilities = StoreCapabilities::new(&*store);
+        assert!(capabilities.listing);
+        assert!(capabilities.partial_reads);
+        assert!(!capabilities.partial_writes);
+        assert!(capabilities.atomic_writes);
+        assert!(capabilities.set_if_not_exists);
+        assert!(capabilities.set_if_match);
+        assert!(capabilities.sizes);
+
+        let read_only = storage_adapter::ReadOnlyStorageAdapter::new(store.clone());
+        let capabilities = StoreCapabilities::new(&read_only);
+        assert!(capabilities.listing);
+        assert!(capabilities.partial_reads);
+        assert!(!capabilities.atomic_writes);
+        assert!(!capabilities.set_if_not_exists);
+
+        let capabilities = StoreCapabilities::readable(&StorageHandle::new(store));
+        assert_eq!(
+            capabilities,
+            StoreCapabilities {
+                partial_reads: true,
+                sizes: true,
+                ..StoreCapabilities::default()
+            }
+        );
+    }
+
+    #[test]
+    fn copy_move_node() {
+        let store = MemoryStore::default();
+        let group = crate::group::GroupMetadataV3::default().into();
+        create_group(&store, &"/a".try_into().unwrap(), &group).unwrap();
+        create_group(&store, &"/a/b".try_into().unwrap(), &group).unwrap();
+        store
+            .set(&StoreKey::new("a/b/c/0").unwrap(), &[0; 4])
+            .unwrap();
+
+        let mut progress = Vec::new();
+        move_node(
+            &store,
+            &"/a".try_into().unwrap(),
+            &"/d/e".try_into().unwrap(),
+            |p| progress.push(p),
+        )
+        .unwrap();
+        assert_eq!(progress.len(), 3);
+        assert_eq!(
+            progress.last().unwrap(),
+            &NodeTransferProgress {
+                keys_transferred: 3,
+                keys_total: 3,
+                bytes_transferred: store
+                    .size_prefix(&StorePrefix::new("d/e/").unwrap())
+                    .unwrap(),
+            }
+        );
+        assert!(store
+            .list_prefix(&StorePrefix::new("a/").unwrap())
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store.get(&StoreKey::new("d/e/b/c/0").unwrap()).unwrap(),
+            Some(vec![0; 4].into())
+        );
+        assert!(move_node(
+            &store,
+            &"/d".try_into().unwrap(),
+            &"/d/e/f".try_into().unwrap(),
+            |_| {},
+        )
+        .is_err());
+
+        let dst_store = MemoryStore::default();
+        copy_node(&store, &dst_store, &"/d/e/b".try_into().unwrap(), |_| {}).unwrap();
+        assert_eq!(
+            dst_store.list().unwrap(),
+            store
+                .list_prefix(&StorePrefix::new("d/e/b/").unwrap())
+                .unwrap()
+        );
+        assert_eq!(store.list().unwrap().len(), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_copy_move_node() {
+        let store = store::AsyncObjectStore::new(object_store::memory::InMemory::new());
+        let metadata = NodeMetadata::Group(crate::group::GroupMetadataV3::default().into());
+        let options = MetadataWriteOptions::default();
+        async_store_metadata_opt(&store, &"/a".try_into().unwrap(), &metadata, &options)
+            .await
+            .unwrap();
+        store
+            .set(&StoreKey::new("a/b/0").unwrap(), vec![0; 4].into())
+            .await
+            .unwrap();
+
+        let mut progress = Vec::new();
+        async_move_node(
+            &store,
+            &"/a".try_into().unwrap(),
+            &"/c".try_into().unwrap(),
+            |p| progress.push(p),
+        )
+        .await
+        .unwrap();
+        assert_eq!(progress.len(), 2);
+        assert!(store
+            .list_prefix(&StorePrefix::new("a/").unwrap())
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store.get(&StoreKey::new("c/b/0").unwrap()).await.unwrap(),
+            Some(vec![0; 4].into())
+        );
+
+        let dst_store = store::AsyncObjectStore::new(object_store::memory::InMemory::new());
+        async_copy_node(&store, &dst_store, &"/c".try_into().unwrap(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!(dst_store.list().await.unwrap(), store.list().await.unwrap());
+    }
 }
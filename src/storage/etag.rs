@@ -0,0 +1,34 @@
+use std::hash::{Hash, Hasher};
+
+use derive_more::Display;
+
+/// An entity tag identifying a version of a store value.
+///
+/// Entity tags are returned by [`etag`](crate::storage::WritableStorageTraits::etag) and used by [`set_if_match`](crate::storage::WritableStorageTraits::set_if_match) to only overwrite a value that is unchanged since it was read.
+/// They are opaque and only comparable for values of the same key in the same store.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Display)]
+pub struct ETag(String);
+
+impl ETag {
+    /// Create a new entity tag.
+    #[must_use]
+    pub fn new(etag: impl Into<String>) -> Self {
+        Self(etag.into())
+    }
+
+    /// Create an entity tag from the hash of a store value.
+    ///
+    /// This is used by stores without native entity tags.
+    #[must_use]
+    pub fn from_value(value: &[u8]) -> Self {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        Self(format!("{:x}-{:016x}", value.len(), hasher.finish()))
+    }
+
+    /// Return the entity tag as a string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,499 @@
+//! Garbage collection of unreachable keys.
+
+use std::collections::HashMap;
+
+use crate::{
+    array::{
+        chunk_grid::ChunkGrid, chunk_key_encoding::ChunkKeyEncoding, ArrayMetadata, ArrayShape,
+        ALLOCATION_MAP_KEY_NAME, CHUNK_STATISTICS_KEY_SUFFIX,
+    },
+    group::GroupMetadata,
+    node::NodePath,
+};
+
+use super::{
+    hierarchy_check::metadata_prefix, meta_key, ListableStorageTraits, ReadableStorageTraits,
+    ReadableWritableListableStorageTraits, StorageError, StoreKey, StoreKeys, StorePrefix,
+};
+
+/// A report of the unreachable keys found by [`gc`] or [`gc_dry_run`].
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    num_keys: usize,
+    keys: StoreKeys,
+    bytes: u64,
+}
+
+impl GcReport {
+    /// Return the number of keys below the root.
+    #[must_use]
+    pub const fn num_keys(&self) -> usize {
+        self.num_keys
+    }
+
+    /// Return the unreachable keys, ordered by key.
+    #[must_use]
+    pub fn keys(&self) -> &[StoreKey] {
+        &self.keys
+    }
+
+    /// Return the total size in bytes of the unreachable keys.
+    ///
+    /// This is zero if the store does not support sizes.
+    #[must_use]
+    pub const fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+/// Options for [`gc_opt`] and [`gc_dry_run_opt`].
+///
+/// By default, only keys below Zarr V3 arrays are collected.
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    collect_orphans: bool,
+}
+
+impl GcOptions {
+    /// Return the collect orphans setting.
+    #[must_use]
+    pub fn collect_orphans(&self) -> bool {
+        self.collect_orphans
+    }
+
+    /// Set whether or not to collect orphaned keys, which are keys that are not below any array.
+    ///
+    /// These include chunks left behind after their array metadata was erased, but also any other files in the hierarchy.
+    pub fn set_collect_orphans(&mut self, collect_orphans: bool) {
+        self.collect_orphans = collect_orphans;
+    }
+}
+
+/// The names of the Zarr V2 metadata keys.
+const ZARR_V2_METADATA_KEY_NAMES: [&str; 3] = [".zarray", ".zgroup", ".zattrs"];
+
+/// Return the prefix of the node of `key` if it is Zarr V2 metadata.
+fn zarr_v2_metadata_prefix(key: &StoreKey) -> Option<&str> {
+    let key = key.as_str();
+    let (prefix, name) = key
+        .rsplit_once('/')
+        .map_or(("", key), |(prefix, name)| (&key[..=prefix.len()], name));
+    ZARR_V2_METADATA_KEY_NAMES.contains(&name).then_some(prefix)
+}
+
+/// The chunks of an array found by [`gc`].
+enum ArrayChunks {
+    /// The chunk key encoding and chunk grid shape of the array.
+    Grid(ChunkKeyEncoding, ArrayShape),
+    /// All keys below the node are retained, since its chunk keys cannot be determined (e.g. a Zarr V2 node).
+    Unknown,
+}
+
+impl ArrayChunks {
+    /// Returns true if `key` (relative to the array) is reachable.
+    fn is_reachable(&self, key: &str) -> bool {
+        let Self::Grid(chunk_key_encoding, chunk_grid_shape) = self else {
+            return true;
+        };
+        if key == ALLOCATION_MAP_KEY_NAME {
+            return true;
+        }
+        let key = key.strip_suffix(CHUNK_STATISTICS_KEY_SUFFIX).unwrap_or(key);
+        if chunk_grid_shape.is_empty() {
+            return chunk_key_encoding.encode(&[]).as_str() == key;
+        }
+        chunk_key_encoding.decode(key).is_some_and(|chunk_indices| {
+            chunk_indices.len() == chunk_grid_shape.len()
+                && std::iter::zip(&chunk_indices, chunk_grid_shape)
+                    .all(|(index, shape)| index < shape)
+        })
+    }
+}
+
+/// Return the chunks of the node with metadata `value`, or [`None`] if the node is a group.
+fn node_chunks(value: &[u8]) -> Option<ArrayChunks> {
+    let Ok(metadata) = serde_json::from_slice::<serde_json::Value>(value) else {
+        return Some(ArrayChunks::Unknown);
+    };
+    match metadata
+        .get("node_type")
+        .and_then(serde_json::Value::as_str)
+    {
+        Some("group") if serde_json::from_value::<GroupMetadata>(metadata.clone()).is_ok() => None,
+        Some("array") => {
+            let Ok(ArrayMetadata::V3(metadata)) = serde_json::from_value::<ArrayMetadata>(metadata)
+            else {
+                return Some(ArrayChunks::Unknown);
+            };
+            if !metadata.storage_transformers.is_empty() {
+                return Some(ArrayChunks::Unknown);
+            }
+            let chunk_key_encoding = ChunkKeyEncoding::from_metadata(&metadata.chunk_key_encoding);
+            let chunk_grid_shape = ChunkGrid::from_metadata(&metadata.chunk_grid)
+                .ok()
+                .and_then(|chunk_grid| chunk_grid.grid_shape(&metadata.shape).ok().flatten());
+            Some(match (chunk_key_encoding, chunk_grid_shape) {
+                (Ok(chunk_key_encoding), Some(chunk_grid_shape)) => {
+                    ArrayChunks::Grid(chunk_key_encoding, chunk_grid_shape)
+                }
+                _ => ArrayChunks::Unknown,
+            })
+        }
+        _ => Some(ArrayChunks::Unknown),
+    }
+}
+
+/// Return the chunks of the node at `prefix` read from `storage`, or [`None`] if there is no array or Zarr V2 node at `prefix`.
+fn prefix_chunks<TStorage: ?Sized + ReadableStorageTraits>(
+    storage: &TStorage,
+    prefix: &str,
+) -> Result<Option<ArrayChunks>, StorageError> {
+    for name in ZARR_V2_METADATA_KEY_NAMES {
+        let key = unsafe { StoreKey::new_unchecked(format!("{prefix}{name}")) };
+        if storage.get(&key)?.is_some() {
+            return Ok(Some(ArrayChunks::Unknown));
+        }
+    }
+    let node_path = NodePath::new(&format!("/{}", prefix.trim_end_matches('/')))?;
+    Ok(storage
+        .get(&meta_key(&node_path))?
+        .and_then(|value| node_chunks(&value)))
+}
+
+/// Retain the keys of `report` that have become reachable, re-reading the metadata of the nodes above each key from `storage`.
+fn recheck_unreachable<TStorage: ?Sized + ReadableStorageTraits>(
+    storage: &TStorage,
+    report: &mut GcReport,
+    options: &GcOptions,
+) -> Result<(), StorageError> {
+    let mut arrays: HashMap<String, Option<ArrayChunks>> = HashMap::new();
+    let mut unreachable = StoreKeys::with_capacity(report.keys.len());
+    for key in std::mem::take(&mut report.keys) {
+        let key_str = key.as_str();
+        let mut reachable = None;
+        for prefix in key_str
+            .rmatch_indices('/')
+            .map(|(i, _)| &key_str[..=i])
+            .chain(std::iter::once(""))
+        {
+            if !arrays.contains_key(prefix) {
+                arrays.insert(prefix.to_string(), prefix_chunks(storage, prefix)?);
+            }
+            if let Some(chunks) = &arrays[prefix] {
+                reachable = Some(chunks.is_reachable(&key_str[prefix.len()..]));
+                break;
+            }
+        }
+        if reachable.unwrap_or(!options.collect_orphans()) {
+            let size = storage.size_key(&key)?.unwrap_or_default();
+            report.bytes = report.bytes.saturating_sub(size);
+        } else {
+            unreachable.push(key);
+        }
+    }
+    report.keys = unreachable;
+    Ok(())
+}
+
+/// Returns true if any component of `key` starts with a `.`.
+fn is_hidden(key: &StoreKey) -> bool {
+    key.as_str()
+        .split('/')
+        .any(|component| component.starts_with('.'))
+}
+
+/// Find the unreachable keys below `root` without erasing them.
+///
+/// See [`gc`].
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn gc_dry_run<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    storage: &TStorage,
+    root: &NodePath,
+) -> Result<GcReport, StorageError> {
+    gc_dry_run_opt(storage, root, &GcOptions::default())
+}
+
+/// Find the unreachable keys below `root` with non-default [`GcOptions`] without erasing them.
+///
+/// See [`gc_opt`].
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn gc_dry_run_opt<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    storage: &TStorage,
+    root: &NodePath,
+    options: &GcOptions,
+) -> Result<GcReport, StorageError> {
+    let root_prefix = StorePrefix::try_from(root)?;
+    let keys = storage.list_prefix(&root_prefix)?;
+
+    // Node metadata below the root and of the ancestors of the root
+    let ancestors = root_prefix
+        .as_str()
+        .match_indices('/')
+        .map(|(i, _)| &root_prefix.as_str()[..=i])
+        .chain(std::iter::once(""))
+        .filter(|prefix| *prefix != root_prefix.as_str())
+        .map(|prefix| NodePath::new(&format!("/{}", prefix.trim_end_matches('/'))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let ancestor_keys: StoreKeys = ancestors.iter().map(meta_key).collect();
+    let ancestor_v2_keys: StoreKeys = ancestor_keys
+        .iter()
+        .filter_map(metadata_prefix)
+        .flat_map(|prefix| {
+            ZARR_V2_METADATA_KEY_NAMES
+                .iter()
+                .map(move |name| unsafe { StoreKey::new_unchecked(format!("{prefix}{name}")) })
+        })
+        .collect();
+    let mut arrays: HashMap<&str, ArrayChunks> = HashMap::new();
+    for key in keys.iter().chain(&ancestor_keys) {
+        let Some(prefix) = metadata_prefix(key) else {
+            continue;
+        };
+        if let Some(value) = storage.get(key)? {
+            if let Some(chunks) = node_chunks(&value) {
+                arrays.insert(prefix, chunks);
+            }
+        }
+    }
+
+    // Zarr V2 nodes, whose keys are all retained
+    for key in &keys {
+        if let Some(prefix) = zarr_v2_metadata_prefix(key) {
+            arrays.insert(prefix, ArrayChunks::Unknown);
+        }
+    }
+    for key in &ancestor_v2_keys {
+        if let Some(prefix) = zarr_v2_metadata_prefix(key) {
+            if !arrays.contains_key(prefix) && storage.get(key)?.is_some() {
+                arrays.insert(prefix, ArrayChunks::Unknown);
+            }
+        }
+    }
+
+    // Keys that are not node metadata, not hidden, and not a chunk (or chunk sidecar) within the chunk grid of an array (or optionally not below an array)
+    let mut report = GcReport {
+        num_keys: keys.len(),
+        ..Default::default()
+    };
+    for key in &keys {
+        if metadata_prefix(key).is_some() || is_hidden(key) {
+            continue;
+        }
+        let key_str = key.as_str();
+        let reachable = key_str
+            .rmatch_indices('/')
+            .map(|(i, _)| &key_str[..=i])
+            .chain(std::iter::once(""))
+            .find_map(|prefix| {
+                arrays
+                    .get(prefix)
+                    .map(|chunks| chunks.is_reachable(&key_str[prefix.len()..]))
+            })
+            .unwrap_or(!options.collect_orphans());
+        if !reachable {
+            report.bytes += storage.size_key(key)?.unwrap_or_default();
+            report.keys.push(key.clone());
+        }
+    }
+    report.keys.sort();
+    Ok(report)
+}
+
+/// Erase the unreachable keys below `root` and return a report of the erased keys.
+///
+/// The keys below `root` are listed and cross-referenced against the metadata of the arrays and groups at and above `root`.
+/// A key is unreachable if it is below a Zarr V3 array but is not the key of a chunk within its chunk grid (e.g. chunks outside of the shape of a shrunk array), the chunk statistics of such a chunk, or the allocation map.
+/// Keys that are not below any array (e.g. chunks left behind after their array metadata was erased, or unrelated files) are only collected if enabled with [`GcOptions::set_collect_orphans`] and [`gc_opt`].
+///
+/// Keys with a component starting with a `.` (e.g. the data of a [`VersionedStorageAdapter`](super::storage_adapter::VersionedStorageAdapter)) are never collected.
+/// Keys below Zarr V2 nodes (with `.zarray`, `.zgroup`, or `.zattrs` metadata) and arrays with storage transformers or invalid metadata are also never collected, since their chunk keys cannot be determined.
+///
+/// If `storage` is a [`VersionedStorageAdapter`](super::storage_adapter::VersionedStorageAdapter), keys are only erased from the checked out version and remain available in earlier versions.
+/// Use [`gc_dry_run`] to find the unreachable keys and their size without erasing them.
+///
+/// Immediately before they are erased, unreachable keys are checked again against freshly read metadata, so that keys that became reachable while the hierarchy was scanned (e.g. the chunks of a concurrently grown array) are retained.
+/// A key written by a concurrent writer between this check and the erasure may still be erased, so `gc` should not run concurrently with writers that grow arrays or create nodes below `root`.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn gc<TStorage: ?Sized + ReadableWritableListableStorageTraits>(
+    storage: &TStorage,
+    root: &NodePath,
+) -> Result<GcReport, StorageError> {
+    gc_opt(storage, root, &GcOptions::default())
+}
+
+/// Erase the unreachable keys below `root` with non-default [`GcOptions`] and return a report of the erased keys.
+///
+/// See [`gc`].
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn gc_opt<TStorage: ?Sized + ReadableWritableListableStorageTraits>(
+    storage: &TStorage,
+    root: &NodePath,
+    options: &GcOptions,
+) -> Result<GcReport, StorageError> {
+    let mut report = gc_dry_run_opt(storage, root, options)?;
+    recheck_unreachable(storage, &mut report, options)?;
+    storage.erase_values(&report.keys)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        array_subset::ArraySubset,
+        group::GroupBuilder,
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn gc_unreachable_keys() {
+        let store = Arc::new(MemoryStore::default());
+        GroupBuilder::new()
+            .build(store.clone(), "/group")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        let mut array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/group/array")
+        .unwrap();
+        array.set_include_chunk_statistics(true);
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(array.shape().to_vec()),
+                (1..=16).collect(),
+            )
+            .unwrap();
+        array.set_allocation_map(Some(array.build_allocation_map().unwrap()));
+        array.store_allocation_map().unwrap();
+        let key = |key: &str| StoreKey::new(key).unwrap();
+        store.set(&key("group/orphan/c/0/0"), &[0; 4]).unwrap();
+        store.set(&key("group/.hidden"), &[0]).unwrap();
+        store.set(&key("group/README.md"), &[0]).unwrap();
+        store.set(&key("group/v2/.zarray"), b"{}").unwrap();
+        store.set(&key("group/v2/0.0"), &[0]).unwrap();
+        store.set(&key("group/v2/sub/0.0"), &[0]).unwrap();
+
+        // Shrink the array
+        array.set_shape(vec![2, 4]);
+        array.store_metadata().unwrap();
+
+        // Keys below V2 nodes and orphaned keys are retained by default
+        let root = NodePath::root();
+        let report = gc_dry_run(&*store, &root).unwrap();
+        assert_eq!(report.num_keys(), 17);
+        assert_eq!(
+            report.keys(),
+            &[
+                key("group/array/c/1/0"),
+                key("group/array/c/1/0.stats.json"),
+                key("group/array/c/1/1"),
+                key("group/array/c/1/1.stats.json"),
+            ]
+        );
+        let report = gc_dry_run(&*store, &NodePath::new("/group/v2/sub").unwrap()).unwrap();
+        assert!(report.keys().is_empty());
+
+        let mut options = GcOptions::default();
+        options.set_collect_orphans(true);
+        let report = gc_dry_run_opt(&*store, &root, &options).unwrap();
+        assert_eq!(
+            report.keys(),
+            &[
+                key("group/README.md"),
+                key("group/array/c/1/0"),
+                key("group/array/c/1/0.stats.json"),
+                key("group/array/c/1/1"),
+                key("group/array/c/1/1.stats.json"),
+                key("group/orphan/c/0/0"),
+            ]
+        );
+        let stats_size = store
+            .size_key(&key("group/array/c/1/0.stats.json"))
+            .unwrap()
+            .unwrap()
+            + store
+                .size_key(&key("group/array/c/1/1.stats.json"))
+                .unwrap()
+                .unwrap();
+        assert_eq!(report.bytes(), 13 + stats_size);
+        assert_eq!(store.list().unwrap().len(), 17);
+
+        // A root within an array uses the metadata of the array
+        let report = gc_dry_run(&*store, &NodePath::new("/group/array/c").unwrap()).unwrap();
+        assert_eq!(report.keys().len(), 4);
+
+        let report = gc(&*store, &root).unwrap();
+        assert_eq!(report.keys().len(), 4);
+        assert_eq!(store.list().unwrap().len(), 13);
+        let report = gc_opt(&*store, &root, &options).unwrap();
+        assert_eq!(report.keys().len(), 2);
+        assert_eq!(store.list().unwrap().len(), 11);
+        assert!(gc_dry_run_opt(&*store, &root, &options)
+            .unwrap()
+            .keys()
+            .is_empty());
+        assert_eq!(
+            store.get(&key("group/v2/0.0")).unwrap(),
+            Some(vec![0].into())
+        );
+        assert_eq!(
+            array.retrieve_chunk_elements::<u8>(&[0, 1]).unwrap(),
+            vec![3, 4, 7, 8]
+        );
+    }
+
+    #[test]
+    fn gc_recheck_regrown_array() {
+        let store = Arc::new(MemoryStore::default());
+        let mut array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(array.shape().to_vec()),
+                (1..=16).collect(),
+            )
+            .unwrap();
+        array.set_shape(vec![2, 4]);
+        array.store_metadata().unwrap();
+
+        let options = GcOptions::default();
+        let mut report = gc_dry_run_opt(&*store, &NodePath::root(), &options).unwrap();
+        assert_eq!(report.keys().len(), 2);
+        assert_eq!(report.bytes(), 8);
+
+        // Regrow and rewrite the array before the keys are erased
+        array.set_shape(vec![4, 2]);
+        array.store_metadata().unwrap();
+        array
+            .store_chunk_elements::<u8>(&[1, 0], vec![1, 2, 3, 4])
+            .unwrap();
+        recheck_unreachable(&*store, &mut report, &options).unwrap();
+        assert_eq!(report.keys(), &[StoreKey::new("array/c/1/1").unwrap()]);
+        assert_eq!(report.bytes(), 4);
+    }
+}
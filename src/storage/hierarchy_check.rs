@@ -0,0 +1,380 @@
+//! Store-level hierarchy consistency checks.
+
+use std::collections::HashMap;
+
+use derive_more::Display;
+
+use crate::{
+    array::{
+        chunk_grid::ChunkGrid, chunk_key_encoding::ChunkKeyEncoding, ArrayMetadata, ArrayMetadataV3,
+    },
+    group::GroupMetadata,
+    node::NodePath,
+};
+
+use super::{ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey};
+
+const METADATA_NAME: &str = "zarr.json";
+
+/// An inconsistency found by [`check_hierarchy`].
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum HierarchyIssue {
+    /// A key that is neither node metadata nor below an array.
+    #[display(fmt = "{_0}: not below an array")]
+    OrphanKey(StoreKey),
+    /// Node metadata that is not valid JSON, or not valid array or group metadata.
+    #[display(fmt = "{key}: invalid metadata: {error}")]
+    InvalidMetadata {
+        /// The metadata key.
+        key: StoreKey,
+        /// A description of the error.
+        error: String,
+    },
+    /// A key below an array that is not a chunk key of the chunk key encoding of the array.
+    #[display(fmt = "{key}: not a chunk key of array {array}")]
+    InvalidChunkKey {
+        /// The key.
+        key: StoreKey,
+        /// The path of the array.
+        array: NodePath,
+    },
+    /// A chunk key, chunk grid, or dimension names with a dimensionality that does not match the shape of its array.
+    #[display(fmt = "{key}: expected dimensionality {expected}, found {found}")]
+    DimensionalityMismatch {
+        /// The chunk key or array metadata key.
+        key: StoreKey,
+        /// The dimensionality of the array shape.
+        expected: usize,
+        /// The mismatched dimensionality.
+        found: usize,
+    },
+}
+
+impl HierarchyIssue {
+    /// Return the key with the issue.
+    #[must_use]
+    pub const fn key(&self) -> &StoreKey {
+        match self {
+            Self::OrphanKey(key)
+            | Self::InvalidMetadata { key, .. }
+            | Self::InvalidChunkKey { key, .. }
+            | Self::DimensionalityMismatch { key, .. } => key,
+        }
+    }
+}
+
+/// A report of the consistency of the hierarchy in a store.
+///
+/// See [`check_hierarchy`].
+#[derive(Debug, Clone, Default)]
+pub struct HierarchyCheckReport {
+    num_keys: usize,
+    arrays: Vec<NodePath>,
+    groups: Vec<NodePath>,
+    issues: Vec<HierarchyIssue>,
+}
+
+impl HierarchyCheckReport {
+    /// Return the number of keys in the store.
+    #[must_use]
+    pub const fn num_keys(&self) -> usize {
+        self.num_keys
+    }
+
+    /// Return the paths of the arrays with valid metadata.
+    #[must_use]
+    pub fn arrays(&self) -> &[NodePath] {
+        &self.arrays
+    }
+
+    /// Return the paths of the groups with valid metadata.
+    #[must_use]
+    pub fn groups(&self) -> &[NodePath] {
+        &self.groups
+    }
+
+    /// Return the issues, ordered by key.
+    #[must_use]
+    pub fn issues(&self) -> &[HierarchyIssue] {
+        &self.issues
+    }
+
+    /// Returns true if no issues were found.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// An array found by [`check_hierarchy`].
+struct ArrayEntry {
+    path: NodePath,
+    dimensionality: usize,
+    /// [`None`] if chunk keys cannot be checked.
+    chunk_key_encoding: Option<ChunkKeyEncoding>,
+}
+
+/// Check the consistency of the hierarchy in `storage`.
+///
+/// All keys in the store are listed and every node metadata document is parsed.
+/// The following issues are reported:
+///  - keys that are not node metadata and are not below an array (e.g. chunks left behind after their array metadata was erased),
+///  - node metadata that is not valid JSON, or not valid array or group metadata,
+///  - keys below an array that are not chunk keys of its chunk key encoding, and
+///  - chunk keys, chunk grids, and dimension names with a dimensionality that does not match the array shape.
+///
+/// Chunk keys are not checked for arrays with storage transformers, since these may change the keys of chunks.
+/// This is useful for detecting inconsistencies after partial deletions or interrupted writes.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with the store.
+pub fn check_hierarchy<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    storage: &TStorage,
+) -> Result<HierarchyCheckReport, StorageError> {
+    let keys = storage.list()?;
+    let mut report = HierarchyCheckReport {
+        num_keys: keys.len(),
+        ..Default::default()
+    };
+
+    // Node metadata
+    let mut arrays: HashMap<&str, ArrayEntry> = HashMap::new();
+    for key in &keys {
+        let Some(prefix) = metadata_prefix(key) else {
+            continue;
+        };
+        let path = NodePath::new(&format!("/{}", prefix.trim_end_matches('/')))?;
+        let Some(value) = storage.get(key)? else {
+            continue;
+        };
+        let invalid_metadata = |error: String| HierarchyIssue::InvalidMetadata {
+            key: key.clone(),
+            error,
+        };
+        let metadata: serde_json::Value = match serde_json::from_slice(&value) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                report.issues.push(invalid_metadata(err.to_string()));
+                continue;
+            }
+        };
+        match metadata
+            .get("node_type")
+            .and_then(serde_json::Value::as_str)
+        {
+            Some("array") => match serde_json::from_value::<ArrayMetadata>(metadata) {
+                Ok(ArrayMetadata::V3(metadata)) => {
+                    arrays.insert(
+                        prefix,
+                        check_array_metadata(key, path, &metadata, &mut report),
+                    );
+                }
+                Err(err) => report.issues.push(invalid_metadata(err.to_string())),
+            },
+            Some("group") => match serde_json::from_value::<GroupMetadata>(metadata) {
+                Ok(_) => report.groups.push(path),
+                Err(err) => report.issues.push(invalid_metadata(err.to_string())),
+            },
+            _ => report
+                .issues
+                .push(invalid_metadata("unknown node type".to_string())),
+        }
+    }
+
+    // Chunks
+    for key in &keys {
+        if metadata_prefix(key).is_some() {
+            continue;
+        }
+        let key_str = key.as_str();
+        let array = key_str
+            .rmatch_indices('/')
+            .map(|(i, _)| &key_str[..=i])
+            .chain(std::iter::once(""))
+            .find_map(|prefix| arrays.get(prefix).map(|array| (prefix, array)));
+        let Some((prefix, array)) = array else {
+            report.issues.push(HierarchyIssue::OrphanKey(key.clone()));
+            continue;
+        };
+        let Some(chunk_key_encoding) = &array.chunk_key_encoding else {
+            continue;
+        };
+        let chunk_key = &key_str[prefix.len()..];
+        if array.dimensionality == 0 && chunk_key_encoding.encode(&[]).as_str() == chunk_key {
+            continue;
+        }
+        match chunk_key_encoding.decode(chunk_key) {
+            Some(chunk_indices) if chunk_indices.len() != array.dimensionality => {
+                report.issues.push(HierarchyIssue::DimensionalityMismatch {
+                    key: key.clone(),
+                    expected: array.dimensionality,
+                    found: chunk_indices.len(),
+                });
+            }
+            Some(_) => {}
+            None => report.issues.push(HierarchyIssue::InvalidChunkKey {
+                key: key.clone(),
+                array: array.path.clone(),
+            }),
+        }
+    }
+
+    report.arrays = arrays.into_values().map(|array| array.path).collect();
+    report.arrays.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    report.groups.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    report.issues.sort_by(|a, b| a.key().cmp(b.key()));
+    Ok(report)
+}
+
+/// Return the prefix of the node if `key` is a node metadata key.
+pub(super) fn metadata_prefix(key: &StoreKey) -> Option<&str> {
+    let key = key.as_str();
+    if key == METADATA_NAME {
+        Some("")
+    } else {
+        key.strip_suffix(METADATA_NAME)
+            .filter(|prefix| prefix.ends_with('/'))
+    }
+}
+
+/// Check the array metadata at `key`, recording any issues in `report`.
+fn check_array_metadata(
+    key: &StoreKey,
+    path: NodePath,
+    metadata: &ArrayMetadataV3,
+    report: &mut HierarchyCheckReport,
+) -> ArrayEntry {
+    let dimensionality = metadata.shape.len();
+    let dimensionality_mismatch = |found: usize| {
+        (found != dimensionality).then(|| HierarchyIssue::DimensionalityMismatch {
+            key: key.clone(),
+            expected: dimensionality,
+            found,
+        })
+    };
+    match ChunkGrid::from_metadata(&metadata.chunk_grid) {
+        Ok(chunk_grid) => report
+            .issues
+            .extend(dimensionality_mismatch(chunk_grid.dimensionality())),
+        Err(err) => report.issues.push(HierarchyIssue::InvalidMetadata {
+            key: key.clone(),
+            error: err.to_string(),
+        }),
+    }
+    if let Some(dimension_names) = &metadata.dimension_names {
+        report
+            .issues
+            .extend(dimensionality_mismatch(dimension_names.len()));
+    }
+    let chunk_key_encoding = match ChunkKeyEncoding::from_metadata(&metadata.chunk_key_encoding) {
+        Ok(chunk_key_encoding) => Some(chunk_key_encoding),
+        Err(err) => {
+            report.issues.push(HierarchyIssue::InvalidMetadata {
+                key: key.clone(),
+                error: err.to_string(),
+            });
+            None
+        }
+    };
+    ArrayEntry {
+        path,
+        dimensionality,
+        chunk_key_encoding: chunk_key_encoding.filter(|_| metadata.storage_transformers.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{ArrayBuilder, DataType, FillValue},
+        array_subset::ArraySubset,
+        group::GroupBuilder,
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn check_hierarchy_issues() {
+        let store = Arc::new(MemoryStore::default());
+        GroupBuilder::new()
+            .build(store.clone(), "/")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into().unwrap(),
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/group/array")
+        .unwrap();
+        array.store_metadata().unwrap();
+        array
+            .store_array_subset_elements::<u8>(
+                &ArraySubset::new_with_shape(array.shape().to_vec()),
+                (0..16).collect(),
+            )
+            .unwrap();
+        GroupBuilder::new()
+            .build(store.clone(), "/group")
+            .unwrap()
+            .store_metadata()
+            .unwrap();
+
+        let report = check_hierarchy(&*store).unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.num_keys(), 7);
+        assert_eq!(report.arrays(), &[NodePath::new("/group/array").unwrap()]);
+        assert_eq!(
+            report.groups(),
+            &[NodePath::root(), NodePath::new("/group").unwrap()]
+        );
+
+        let key = |key: &str| StoreKey::new(key).unwrap();
+        store.set(&key("group/array/c/0"), &[]).unwrap();
+        store.set(&key("group/array/c/0/01"), &[]).unwrap();
+        store.set(&key("group/c/0/0"), &[]).unwrap();
+        store.set(&key("other/zarr.json"), b"{").unwrap();
+        store
+            .set(
+                &key("scalar/zarr.json"),
+                br#"{"zarr_format":3,"node_type":"array","shape":[],"data_type":"uint8","chunk_grid":{"name":"regular","configuration":{"chunk_shape":[1]}},"chunk_key_encoding":{"name":"v2","configuration":{"separator":"."}},"fill_value":0,"codecs":[{"name":"bytes"}]}"#,
+            )
+            .unwrap();
+        store.set(&key("scalar/0"), &[0]).unwrap();
+
+        let report = check_hierarchy(&*store).unwrap();
+        assert_eq!(
+            report.issues(),
+            &[
+                HierarchyIssue::DimensionalityMismatch {
+                    key: key("group/array/c/0"),
+                    expected: 2,
+                    found: 1
+                },
+                HierarchyIssue::InvalidChunkKey {
+                    key: key("group/array/c/0/01"),
+                    array: NodePath::new("/group/array").unwrap()
+                },
+                HierarchyIssue::OrphanKey(key("group/c/0/0")),
+                HierarchyIssue::InvalidMetadata {
+                    key: key("other/zarr.json"),
+                    error: "EOF while parsing an object at line 1 column 1".to_string()
+                },
+                HierarchyIssue::DimensionalityMismatch {
+                    key: key("scalar/zarr.json"),
+                    expected: 0,
+                    found: 1
+                },
+            ]
+        );
+        assert_eq!(
+            report.issues()[2].to_string(),
+            "group/c/0/0: not below an array"
+        );
+    }
+}
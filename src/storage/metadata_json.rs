@@ -0,0 +1,320 @@
+//! Formatting of node metadata JSON documents.
+
+use std::{collections::HashMap, fmt::Write};
+
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Number, Value};
+
+use crate::{
+    array::{DataType, FillValueMetadata},
+    metadata::Metadata,
+};
+
+use super::{sort_json_keys, FillValueFloatFormat, MetadataWriteOptions};
+
+/// Serialise node metadata to a JSON document formatted according to `options`.
+///
+/// If `existing` is a JSON document and [`preserve_format`](MetadataWriteOptions::preserve_format) is enabled, its format is preserved.
+/// If [`deterministic_encode`](MetadataWriteOptions::deterministic_encode) is enabled, the keys of the attributes are sorted.
+pub(crate) fn metadata_to_json_opt<T: Serialize + ?Sized>(
+    metadata: &T,
+    existing: Option<&[u8]>,
+    options: &MetadataWriteOptions,
+) -> Result<String, serde_json::Error> {
+    let mut metadata = serde_json::to_value(metadata)?;
+    if options.sort_keys() {
+        sort_json_keys(&mut metadata);
+    } else if options.deterministic_encode() {
+        if let Some(attributes) = metadata.get_mut("attributes") {
+            sort_json_keys(attributes);
+        }
+    }
+    format_fill_value(&mut metadata, options.fill_value_float_format());
+
+    let existing = existing
+        .filter(|_| options.preserve_format())
+        .and_then(|existing| std::str::from_utf8(existing).ok())
+        .and_then(|existing| {
+            serde_json::from_str::<Value>(existing)
+                .ok()
+                .map(|value| (existing, value))
+        });
+    let mut indent = options.indent();
+    let mut trailing_newline = false;
+    if let Some((document, existing)) = &existing {
+        preserve_fill_value(&mut metadata, existing);
+        preserve_values(&mut metadata, existing);
+        indent = detect_indent(document);
+        trailing_newline = document.ends_with('\n');
+    }
+
+    let mut document = if let Some(indent) = indent {
+        let indent = vec![b' '; indent];
+        let mut writer = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut writer,
+            PrettyFormatter::with_indent(&indent),
+        );
+        metadata.serialize(&mut serializer)?;
+        String::from_utf8(writer).map_err(serde::ser::Error::custom)?
+    } else {
+        serde_json::to_string(&metadata)?
+    };
+    if trailing_newline {
+        document.push('\n');
+    }
+    Ok(document)
+}
+
+/// Return the number of spaces that `document` is indented by, or [`None`] if it is compact.
+fn detect_indent(document: &str) -> Option<usize> {
+    let line = document.trim_start().lines().nth(1)?;
+    Some(line.len() - line.trim_start_matches(' ').len())
+}
+
+/// Return the data type of the array metadata `metadata`, or [`None`] if it is not array metadata or the data type is unsupported.
+fn data_type(metadata: &Value) -> Option<DataType> {
+    let data_type = serde_json::from_value::<Metadata>(metadata.get("data_type")?.clone()).ok()?;
+    DataType::from_metadata(&data_type).ok()
+}
+
+/// Return the size in bytes of the float components of `data_type`, or [`None`] if it is not a float or complex data type.
+fn float_component_size(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::Float16 | DataType::BFloat16 => Some(2),
+        DataType::Float32 | DataType::Complex64 => Some(4),
+        DataType::Float64 | DataType::Complex128 => Some(8),
+        _ => None,
+    }
+}
+
+/// Format the float fill value of the array metadata `metadata` according to `format`.
+fn format_fill_value(metadata: &mut Value, format: FillValueFloatFormat) {
+    if format == FillValueFloatFormat::Number {
+        return;
+    }
+    let Some(data_type) = data_type(metadata) else {
+        return;
+    };
+    let Some(component_size) = float_component_size(&data_type) else {
+        return;
+    };
+    let Some(fill_value) = metadata.get_mut("fill_value") else {
+        return;
+    };
+    match format {
+        FillValueFloatFormat::Number => {}
+        FillValueFloatFormat::IntegralNumber => match fill_value {
+            Value::Array(components) => components.iter_mut().for_each(integral_number),
+            fill_value => integral_number(fill_value),
+        },
+        FillValueFloatFormat::HexString => {
+            let Some(fill_value_bytes) =
+                serde_json::from_value::<FillValueMetadata>(fill_value.clone())
+                    .ok()
+                    .and_then(|fill_value| data_type.fill_value_from_metadata(&fill_value).ok())
+            else {
+                return;
+            };
+            let mut components = fill_value_bytes
+                .as_ne_bytes()
+                .chunks(component_size)
+                .map(|component| {
+                    let mut component = component.to_vec();
+                    if cfg!(target_endian = "little") {
+                        component.reverse();
+                    }
+                    let hex = component.iter().fold(String::new(), |mut hex, byte| {
+                        let _ = write!(hex, "{byte:02x}");
+                        hex
+                    });
+                    Value::String(format!("0x{hex}"))
+                })
+                .collect::<Vec<_>>();
+            *fill_value = if components.len() == 1 {
+                components.remove(0)
+            } else {
+                Value::Array(components)
+            };
+        }
+    }
+}
+
+/// Replace a float number with an integral value by an integer.
+fn integral_number(value: &mut Value) {
+    const MAX_EXACT: f64 = 9_007_199_254_740_992.0; // 2^53
+    if let Some(float) = value.as_f64().filter(|_| value.is_f64()) {
+        if float.fract() == 0.0
+            && float.abs() <= MAX_EXACT
+            && !(float == 0.0 && float.is_sign_negative())
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            let integer = float as i64;
+            *value = Value::Number(Number::from(integer));
+        }
+    }
+}
+
+/// Retain the existing fill value of the array metadata `metadata` if it represents the same value.
+fn preserve_fill_value(metadata: &mut Value, existing: &Value) {
+    if metadata.get("data_type") != existing.get("data_type") {
+        return;
+    }
+    let (Some(data_type), Some(fill_value), Some(existing_fill_value)) = (
+        data_type(metadata),
+        metadata.get("fill_value"),
+        existing.get("fill_value"),
+    ) else {
+        return;
+    };
+    let to_fill_value = |fill_value: &Value| {
+        serde_json::from_value::<FillValueMetadata>(fill_value.clone())
+            .ok()
+            .and_then(|fill_value| data_type.fill_value_from_metadata(&fill_value).ok())
+    };
+    let equal = to_fill_value(fill_value).is_some_and(|fill_value| {
+        to_fill_value(existing_fill_value)
+            .is_some_and(|existing_fill_value| existing_fill_value == fill_value)
+    });
+    if equal {
+        metadata["fill_value"] = existing_fill_value.clone();
+    }
+}
+
+/// Retain the key order of objects and the representation of equal numbers in `existing`.
+fn preserve_values(value: &mut Value, existing: &Value) {
+    match (value, existing) {
+        (Value::Object(map), Value::Object(existing)) => {
+            let order: HashMap<&str, usize> = existing
+                .keys()
+                .enumerate()
+                .map(|(i, key)| (key.as_str(), i))
+                .collect();
+            let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+            entries.sort_by_key(|(key, _)| order.get(key.as_str()).copied().unwrap_or(usize::MAX));
+            for (key, value) in &mut entries {
+                if let Some(existing) = existing.get(key) {
+                    preserve_values(value, existing);
+                }
+            }
+            *map = entries.into_iter().collect();
+        }
+        (Value::Array(values), Value::Array(existing)) if values.len() == existing.len() => {
+            for (value, existing) in std::iter::zip(values, existing) {
+                preserve_values(value, existing);
+            }
+        }
+        (Value::Number(number), Value::Number(existing)) if numbers_equal(number, existing) => {
+            *number = existing.clone();
+        }
+        _ => {}
+    }
+}
+
+/// Returns true if two numbers represent the same value (e.g. `1` and `1.0`).
+fn numbers_equal(a: &Number, b: &Number) -> bool {
+    /// Return the number as a float if it is exactly representable.
+    fn exact_f64(number: &Number) -> Option<f64> {
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        if let Some(integer) = number.as_i64() {
+            let float = integer as f64;
+            (float as i64 == integer).then_some(float)
+        } else if let Some(integer) = number.as_u64() {
+            let float = integer as f64;
+            (float as u64 == integer).then_some(float)
+        } else {
+            number.as_f64()
+        }
+    }
+    a == b || exact_f64(a).is_some_and(|a| exact_f64(b) == Some(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array_metadata(data_type: &str, fill_value: &str) -> Value {
+        serde_json::from_str(&format!(
+            r#"{{"zarr_format":3,"node_type":"array","shape":[4],"data_type":"{data_type}","chunk_grid":{{"name":"regular","configuration":{{"chunk_shape":[2]}}}},"chunk_key_encoding":{{"name":"default"}},"fill_value":{fill_value},"codecs":[{{"name":"bytes"}}],"attributes":{{"b":1,"a":2.5}}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn metadata_json_format() {
+        let metadata = array_metadata("float32", "1.0");
+        let mut options = MetadataWriteOptions::default();
+        options.set_indent(None);
+        options.set_sort_keys(true);
+        options.set_fill_value_float_format(FillValueFloatFormat::IntegralNumber);
+        let document = metadata_to_json_opt(&metadata, None, &options).unwrap();
+        assert!(document.starts_with(r#"{"attributes":{"a":2.5,"b":1},"chunk_grid""#));
+        assert!(document.contains(r#""fill_value":1,"#));
+
+        let mut options = MetadataWriteOptions::default();
+        options.set_fill_value_float_format(FillValueFloatFormat::HexString);
+        let document = metadata_to_json_opt(&metadata, None, &options).unwrap();
+        assert!(document.starts_with("{\n  \"zarr_format\": 3,"));
+        assert!(document.contains(r#""fill_value": "0x3f800000","#));
+        let metadata = array_metadata("complex64", r#"[1.0,"NaN"]"#);
+        let document = metadata_to_json_opt(&metadata, None, &options).unwrap();
+        assert!(document.contains(r#""fill_value": ["#));
+        assert!(document.contains(r#""0x7fc00000""#));
+    }
+
+    #[test]
+    fn metadata_json_deterministic_encode() {
+        let metadata = array_metadata("float32", "1.0");
+        let mut options = MetadataWriteOptions::default();
+        options.set_indent(None);
+        options.set_deterministic_encode(false);
+        let document = metadata_to_json_opt(&metadata, None, &options).unwrap();
+        assert!(document.ends_with(r#""attributes":{"b":1,"a":2.5}}"#));
+
+        options.set_deterministic_encode(true);
+        let document = metadata_to_json_opt(&metadata, None, &options).unwrap();
+        assert!(document.starts_with(r#"{"zarr_format":3,"#));
+        assert!(document.ends_with(r#""attributes":{"a":2.5,"b":1}}"#));
+    }
+
+    #[test]
+    fn metadata_json_preserve_format() {
+        let existing = "{\n    \"attributes\": {\"a\": 2.5, \"b\": 1},\n    \"fill_value\": \"0x7fc00000\",\n    \"zarr_format\": 3, \"node_type\": \"array\", \"shape\": [4.0], \"data_type\": \"float32\"\n}\n";
+        let metadata = array_metadata("float32", r#""NaN""#);
+        let mut options = MetadataWriteOptions::default();
+        options.set_preserve_format(true);
+        let document =
+            metadata_to_json_opt(&metadata, Some(existing.as_bytes()), &options).unwrap();
+        let expected = r#"{
+    "attributes": {
+        "a": 2.5,
+        "b": 1
+    },
+    "fill_value": "0x7fc00000",
+    "zarr_format": 3,
+    "node_type": "array",
+    "shape": [
+        4.0
+    ],
+    "data_type": "float32",
+    "chunk_grid": {"#;
+        assert!(document.starts_with(expected), "{document}");
+        assert!(document.ends_with("}\n"));
+
+        // A different fill value is not preserved
+        let metadata = array_metadata("float32", "0.0");
+        let document =
+            metadata_to_json_opt(&metadata, Some(existing.as_bytes()), &options).unwrap();
+        assert!(document.contains(r#""fill_value": 0.0,"#));
+
+        // The format is not preserved unless enabled
+        options.set_preserve_format(false);
+        let document =
+            metadata_to_json_opt(&metadata, Some(existing.as_bytes()), &options).unwrap();
+        assert!(document.starts_with("{\n  \"zarr_format\": 3,"));
+    }
+}
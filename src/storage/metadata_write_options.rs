@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use crate::config::global_config;
+
+/// The format of float fill values in array metadata written with [`MetadataWriteOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillValueFloatFormat {
+    /// Finite values are numbers (e.g. `1.0`), and non-finite values are strings (e.g. `"NaN"`) or hex strings for non-standard NaNs.
+    #[default]
+    Number,
+    /// Like [`Number`](FillValueFloatFormat::Number), except integral values are integers (e.g. `1`).
+    IntegralNumber,
+    /// Values are hex strings of their big-endian bytes (e.g. `"0x3f800000"`).
+    HexString,
+}
+
+/// Options for writing node metadata.
+///
+/// By default, metadata is written once and is not verified.
+/// If verification is enabled, the metadata is read back after it is written and compared against the serialised document.
+/// On a storage error or verification failure, the write is retried up to [`max_retries`](MetadataWriteOptions::max_retries) times.
+/// The delay between attempts starts at [`retry_delay`](MetadataWriteOptions::retry_delay) and doubles after each attempt.
+///
+/// By default, metadata is pretty-printed with an indent of two spaces, keys are in the order of the metadata fields, and float fill values are numbers where possible.
+/// If [`preserve_format`](MetadataWriteOptions::preserve_format) is enabled and the metadata already exists, the indentation, key order, trailing newline, and representation of equal values (e.g. a fill value of `0` rather than `0.0`, or `"0x7fc00000"` rather than `"NaN"`) of the existing document are retained.
+/// This minimises diffs against documents written by other tools.
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct MetadataWriteOptions {
+    verify: bool,
+    max_retries: usize,
+    retry_delay: Duration,
+    indent: Option<usize>,
+    sort_keys: bool,
+    deterministic_encode: bool,
+    fill_value_float_format: FillValueFloatFormat,
+    preserve_format: bool,
+}
+
+impl Default for MetadataWriteOptions {
+    fn default() -> Self {
+        Self {
+            verify: false,
+            max_retries: 0,
+            retry_delay: Duration::from_millis(100),
+            indent: Some(2),
+            sort_keys: false,
+            deterministic_encode: global_config().deterministic_encode(),
+            fill_value_float_format: FillValueFloatFormat::default(),
+            preserve_format: false,
+        }
+    }
+}
+
+impl MetadataWriteOptions {
+    /// Return the verify setting.
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        self.verify
+    }
+
+    /// Set whether or not to verify written metadata by reading it back.
+    pub fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Return the maximum number of retries.
+    #[must_use]
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Set the maximum number of retries after a failed write.
+    pub fn set_max_retries(&mut self, max_retries: usize) {
+        self.max_retries = max_retries;
+    }
+
+    /// Return the initial retry delay.
+    #[must_use]
+    pub fn retry_delay(&self) -> Duration {
+        self.retry_delay
+    }
+
+    /// Set the initial retry delay.
+    pub fn set_retry_delay(&mut self, retry_delay: Duration) {
+        self.retry_delay = retry_delay;
+    }
+
+    /// Return the indent, where [`None`] is compact.
+    #[must_use]
+    pub fn indent(&self) -> Option<usize> {
+        self.indent
+    }
+
+    /// Set the number of spaces to indent pretty-printed metadata by, or [`None`] for compact metadata.
+    pub fn set_indent(&mut self, indent: Option<usize>) {
+        self.indent = indent;
+    }
+
+    /// Return the sort keys setting.
+    #[must_use]
+    pub fn sort_keys(&self) -> bool {
+        self.sort_keys
+    }
+
+    /// Set whether or not to sort the keys of all objects in the metadata.
+    pub fn set_sort_keys(&mut self, sort_keys: bool) {
+        self.sort_keys = sort_keys;
+    }
+
+    /// Return the deterministic encode setting.
+    #[must_use]
+    pub fn deterministic_encode(&self) -> bool {
+        self.deterministic_encode
+    }
+
+    /// Set whether or not to sort the keys of the attributes so that the metadata is byte-identical regardless of their insertion order.
+    ///
+    /// See the [deterministic encode](crate::config::Config#deterministic-encode) configuration.
+    pub fn set_deterministic_encode(&mut self, deterministic_encode: bool) {
+        self.deterministic_encode = deterministic_encode;
+    }
+
+    /// Return the fill value float format.
+    #[must_use]
+    pub fn fill_value_float_format(&self) -> FillValueFloatFormat {
+        self.fill_value_float_format
+    }
+
+    /// Set the format of float (and complex) fill values.
+    pub fn set_fill_value_float_format(&mut self, fill_value_float_format: FillValueFloatFormat) {
+        self.fill_value_float_format = fill_value_float_format;
+    }
+
+    /// Return the preserve format setting.
+    #[must_use]
+    pub fn preserve_format(&self) -> bool {
+        self.preserve_format
+    }
+
+    /// Set whether or not to preserve the format of existing metadata when it is rewritten.
+    ///
+    /// The existing format takes precedence over the other formatting options.
+    pub fn set_preserve_format(&mut self, preserve_format: bool) {
+        self.preserve_format = preserve_format;
+    }
+}
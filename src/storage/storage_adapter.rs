@@ -1,8 +1,24 @@
-//! Storage adapters. Includes a [zip](ZipStorageAdapter) implementation.
+//! Storage adapters. Includes [deduplicating](DedupStorageAdapter) (with the `dedup` feature), [read only](ReadOnlyStorageAdapter), [tar](TarStorageAdapter), [transaction](TransactionStorageAdapter), [versioned](VersionedStorageAdapter), [write once](WriteOnceStorageAdapter), and [zip](ZipStorageAdapter) implementations.
 //!
 //! An adapter is a nested resource using a specified protocol they can be chained with a an absolute resource location (e.g. a filesystem store).
 
+mod read_only;
+mod tar;
+mod transaction;
+mod versioned;
+mod write_once;
+pub use self::read_only::ReadOnlyStorageAdapter;
+pub use self::tar::{TarStorageAdapter, TarStorageAdapterCreateError};
+pub use self::transaction::TransactionStorageAdapter;
+pub use self::versioned::{Commit, VersionedStorageAdapter, VERSIONS_PREFIX};
+pub use self::write_once::WriteOnceStorageAdapter;
+
 #[cfg(feature = "zip")]
 mod zip;
 #[cfg(feature = "zip")]
 pub use self::zip::{ZipStorageAdapter, ZipStorageAdapterCreateError};
+
+#[cfg(feature = "dedup")]
+mod dedup;
+#[cfg(feature = "dedup")]
+pub use self::dedup::{DedupStorageAdapter, DEDUP_PREFIX};
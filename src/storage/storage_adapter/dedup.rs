@@ -0,0 +1,367 @@
+//! A content-addressed deduplicating storage adapter.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    storage::{
+        store_set_partial_values, ListableStorageTraits, ReadableStorageTraits,
+        ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
+        StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+    },
+};
+
+/// The prefix in the underlying storage holding the index and objects of a [`DedupStorageAdapter`].
+pub const DEDUP_PREFIX: &str = ".dedup/";
+
+fn refs_prefix() -> StorePrefix {
+    unsafe { StorePrefix::new_unchecked(DEDUP_PREFIX.to_string() + "refs/") }
+}
+
+fn objects_prefix() -> StorePrefix {
+    unsafe { StorePrefix::new_unchecked(DEDUP_PREFIX.to_string() + "objects/") }
+}
+
+fn ref_key(key: &StoreKey) -> StoreKey {
+    unsafe { StoreKey::new_unchecked(format!("{DEDUP_PREFIX}refs/{}", key.as_str())) }
+}
+
+fn ref_prefix(prefix: &StorePrefix) -> StorePrefix {
+    unsafe { StorePrefix::new_unchecked(format!("{DEDUP_PREFIX}refs/{}", prefix.as_str())) }
+}
+
+fn object_key(hash: &str) -> StoreKey {
+    unsafe { StoreKey::new_unchecked(format!("{DEDUP_PREFIX}objects/{hash}")) }
+}
+
+/// Strip the index prefix from a key in the underlying storage.
+fn strip_ref_key(key: &StoreKey) -> StoreKey {
+    let key = key
+        .as_str()
+        .strip_prefix(refs_prefix().as_str())
+        .expect("keys listed in the index have the index prefix");
+    unsafe { StoreKey::new_unchecked(key.to_string()) }
+}
+
+/// A content-addressed deduplicating storage adapter.
+///
+/// Values are hashed with [BLAKE3](https://github.com/BLAKE3-team/BLAKE3) and stored once under their hash below [`DEDUP_PREFIX`] in the underlying storage.
+/// An index maps each key to the hash of its value, so identical values (e.g. chunks of repeated tiles) are only stored once and [`copy`](DedupStorageAdapter::copy) only copies index entries.
+///
+/// Erasing a key only removes its index entry.
+/// Objects that are no longer referenced by any key can be erased with [`erase_unreferenced_objects`](DedupStorageAdapter::erase_unreferenced_objects), which must not run concurrently with writes.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+/// use zarrs::storage::{storage_adapter::DedupStorageAdapter, store::MemoryStore};
+///
+/// let store = Arc::new(MemoryStore::new());
+/// let dedup = Arc::new(DedupStorageAdapter::new(store));
+/// let array = ArrayBuilder::new(vec![8], DataType::UInt8, vec![2].try_into()?, FillValue::from(0u8))
+///     .build(dedup.clone(), "/array")?;
+/// array.store_metadata()?;
+/// for chunk in 0..4 {
+///     array.store_chunk_elements::<u8>(&[chunk], vec![1, 2])?;
+/// }
+/// assert_eq!(dedup.num_objects()?, 2); // the metadata and one chunk
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct DedupStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+}
+
+impl<TStorage: ?Sized> DedupStorageAdapter<TStorage> {
+    /// Create a new deduplicating storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self { storage }
+    }
+
+    /// Return the hash of `value`, which identifies its object in the underlying storage.
+    #[must_use]
+    pub fn hash(value: &[u8]) -> String {
+        blake3::hash(value).to_hex().to_string()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> DedupStorageAdapter<TStorage> {
+    /// Retrieve the hash of the value of `key`, or [`None`] if `key` does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or the index entry is invalid.
+    pub fn retrieve_hash(&self, key: &StoreKey) -> Result<Option<String>, StorageError> {
+        let ref_key = ref_key(key);
+        self.storage
+            .get(&ref_key)?
+            .map(|hash| {
+                String::from_utf8(hash.to_vec())
+                    .map_err(|err| StorageError::InvalidMetadata(ref_key, err.to_string()))
+            })
+            .transpose()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>
+    DedupStorageAdapter<TStorage>
+{
+    /// Return the number of objects in the underlying storage.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn num_objects(&self) -> Result<usize, StorageError> {
+        Ok(self.storage.list_prefix(&objects_prefix())?.len())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> DedupStorageAdapter<TStorage> {
+    /// Copy the value of `src` to `dst` by copying its index entry.
+    ///
+    /// `dst` is erased if `src` does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn copy(&self, src: &StoreKey, dst: &StoreKey) -> Result<(), StorageError> {
+        match self.retrieve_hash(src)? {
+            Some(hash) => self.storage.set(&ref_key(dst), hash.as_bytes()),
+            None => self.storage.erase(&ref_key(dst)),
+        }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits + ListableStorageTraits>
+    DedupStorageAdapter<TStorage>
+{
+    /// Copy the values of all keys with `src` prefix to the same keys with the `dst` prefix by copying their index entries.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn copy_prefix(&self, src: &StorePrefix, dst: &StorePrefix) -> Result<(), StorageError> {
+        for key in self.list_prefix(src)? {
+            let key_strip = &key.as_str()[src.as_str().len()..];
+            let dst_key = StoreKey::new(dst.as_str().to_string() + key_strip)?;
+            self.copy(&key, &dst_key)?;
+        }
+        Ok(())
+    }
+
+    /// Erase the objects in the underlying storage that are not referenced by any key, and return the number of erased objects.
+    ///
+    /// This must not run concurrently with writes through any adapter of the underlying storage, as an object may be erased just before it is referenced.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or an index entry is invalid.
+    pub fn erase_unreferenced_objects(&self) -> Result<usize, StorageError> {
+        let mut referenced = BTreeSet::new();
+        for key in self.list()? {
+            if let Some(hash) = self.retrieve_hash(&key)? {
+                referenced.insert(object_key(&hash));
+            }
+        }
+        let unreferenced: StoreKeys = self
+            .storage
+            .list_prefix(&objects_prefix())?
+            .into_iter()
+            .filter(|key| !referenced.contains(key))
+            .collect();
+        self.storage.erase_values(&unreferenced)?;
+        Ok(unreferenced.len())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        match self.retrieve_hash(key)? {
+            Some(hash) => self.storage.get(&object_key(&hash)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        match self.retrieve_hash(key)? {
+            Some(hash) => self
+                .storage
+                .get_partial_values_key(&object_key(&hash), byte_ranges),
+            None => Ok(None),
+        }
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, _prefix: &StorePrefix) -> Result<u64, StorageError> {
+        Err(StorageError::Unsupported(
+            "size_prefix is not supported by a deduplicating store".to_string(),
+        ))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        match self.retrieve_hash(key)? {
+            Some(hash) => self.storage.size_key(&object_key(&hash)),
+            None => Ok(None),
+        }
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self
+            .storage
+            .list_prefix(&ref_prefix(prefix))?
+            .iter()
+            .map(strip_ref_key)
+            .collect())
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let keys_prefixes = self.storage.list_dir(&ref_prefix(prefix))?;
+        let keys = keys_prefixes.keys().iter().map(strip_ref_key).collect();
+        let prefixes = keys_prefixes
+            .prefixes()
+            .iter()
+            .map(|child| {
+                let child = child
+                    .as_str()
+                    .strip_prefix(refs_prefix().as_str())
+                    .expect("prefixes listed in the index have the index prefix");
+                unsafe { StorePrefix::new_unchecked(child.to_string()) }
+            })
+            .collect();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> WritableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        let hash = Self::hash(value);
+        let object_key = object_key(&hash);
+        if self.storage.size_key(&object_key)?.is_none() {
+            self.storage.set(&object_key, value)?;
+        }
+        self.storage.set(&ref_key(key), hash.as_bytes())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_start_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.storage.erase(&ref_key(key))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.storage.erase_prefix(&ref_prefix(prefix))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for DedupStorageAdapter<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn dedup_storage_adapter() {
+        let store = Arc::new(MemoryStore::new());
+        let dedup = DedupStorageAdapter::new(store.clone());
+        let key_a = StoreKey::new("a/0").unwrap();
+        let key_b = StoreKey::new("a/1").unwrap();
+        let key_c = StoreKey::new("b/c/0").unwrap();
+
+        dedup.set(&key_a, &[0, 1, 2]).unwrap();
+        dedup.set(&key_b, &[0, 1, 2]).unwrap();
+        dedup.set(&key_c, &[3]).unwrap();
+        assert_eq!(dedup.num_objects().unwrap(), 2);
+        assert_eq!(dedup.get(&key_b).unwrap(), Some(vec![0, 1, 2].into()));
+        assert_eq!(dedup.size_key(&key_b).unwrap(), Some(3));
+        assert_eq!(
+            dedup.retrieve_hash(&key_a).unwrap(),
+            Some(DedupStorageAdapter::<MemoryStore>::hash(&[0, 1, 2]))
+        );
+        assert_eq!(
+            dedup
+                .get_partial_values_key(&key_a, &[ByteRange::FromStart(1, Some(1))])
+                .unwrap(),
+            Some(vec![vec![1]])
+        );
+
+        assert_eq!(
+            dedup.list().unwrap(),
+            vec![key_a.clone(), key_b.clone(), key_c.clone()]
+        );
+        let root = dedup.list_dir(&StorePrefix::root()).unwrap();
+        assert!(root.keys().is_empty());
+        assert_eq!(
+            root.prefixes(),
+            &[
+                StorePrefix::new("a/").unwrap(),
+                StorePrefix::new("b/").unwrap()
+            ]
+        );
+
+        // Copies only copy index entries
+        dedup
+            .copy_prefix(
+                &StorePrefix::new("b/").unwrap(),
+                &StorePrefix::new("d/").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            dedup.get(&StoreKey::new("d/c/0").unwrap()).unwrap(),
+            Some(vec![3].into())
+        );
+        assert_eq!(dedup.num_objects().unwrap(), 2);
+
+        // Overwriting and erasing leaves unreferenced objects
+        dedup.set(&key_a, &[4]).unwrap();
+        dedup.erase(&key_b).unwrap();
+        dedup
+            .erase_prefix(&StorePrefix::new("b/").unwrap())
+            .unwrap();
+        assert_eq!(dedup.get(&key_b).unwrap(), None);
+        assert_eq!(dedup.num_objects().unwrap(), 3);
+        assert_eq!(dedup.erase_unreferenced_objects().unwrap(), 1);
+        assert_eq!(dedup.num_objects().unwrap(), 2);
+        assert_eq!(dedup.get(&key_a).unwrap(), Some(vec![4].into()));
+        assert_eq!(
+            dedup.get(&StoreKey::new("d/c/0").unwrap()).unwrap(),
+            Some(vec![3].into())
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! A read only storage adapter.
+
+use std::sync::Arc;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits, StorageError,
+        StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+        WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncReadableWritableStorageTraits,
+    AsyncWritableStorageTraits,
+};
+
+/// A read only storage adapter.
+///
+/// Reads and listing are passed through to the underlying storage, and all writes and erasures fail with [`StorageError::ReadOnly`].
+/// This is useful for protecting published archives from accidental modification, where an API requires writable storage.
+///
+/// The adapter implements the sync storage traits if the underlying storage does, and the async storage traits if the underlying storage does (with the `async` feature).
+#[derive(Debug)]
+pub struct ReadOnlyStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+}
+
+impl<TStorage: ?Sized> ReadOnlyStorageAdapter<TStorage> {
+    /// Create a new read only storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_partial_values(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + Send + Sync> WritableStorageTraits for ReadOnlyStorageAdapter<TStorage> {
+    fn set(&self, _key: &StoreKey, _value: &[u8]) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    fn set_partial_values(
+        &self,
+        _key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    fn erase(&self, _key: &StoreKey) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    fn erase_values(&self, _keys: &[StoreKey]) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableWritableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges).await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_partial_values(key_ranges).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + Send + Sync> AsyncWritableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+    async fn set(&self, _key: &StoreKey, _value: bytes::Bytes) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    async fn set_partial_values(
+        &self,
+        _key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    async fn erase(&self, _key: &StoreKey) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    async fn erase_values(&self, _keys: &[StoreKey]) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+
+    async fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        Err(StorageError::ReadOnly)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableWritableStorageTraits
+    for ReadOnlyStorageAdapter<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn read_only_storage_adapter() {
+        let store = Arc::new(MemoryStore::new());
+        let key = StoreKey::new("a").unwrap();
+        store.set(&key, &[0, 1, 2]).unwrap();
+
+        let storage = ReadOnlyStorageAdapter::new(store);
+        assert_eq!(storage.get(&key).unwrap(), Some(vec![0, 1, 2].into()));
+        assert_eq!(storage.list().unwrap(), vec![key.clone()]);
+        assert!(matches!(
+            WritableStorageTraits::set(&storage, &key, &[3]),
+            Err(StorageError::ReadOnly)
+        ));
+        assert!(matches!(
+            WritableStorageTraits::set_partial_values(
+                &storage,
+                &[StoreKeyStartValue::new(key.clone(), 0, &[3])]
+            ),
+            Err(StorageError::ReadOnly)
+        ));
+        assert!(matches!(
+            WritableStorageTraits::erase(&storage, &key),
+            Err(StorageError::ReadOnly)
+        ));
+        assert!(matches!(
+            WritableStorageTraits::erase_prefix(&storage, &StorePrefix::root()),
+            Err(StorageError::ReadOnly)
+        ));
+        assert_eq!(storage.get(&key).unwrap(), Some(vec![0, 1, 2].into()));
+    }
+}
@@ -0,0 +1,493 @@
+//! A tar storage adapter.
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{ByteRange, InvalidByteRangeError},
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, StorageError, StoreKey, StoreKeyRange,
+        StoreKeys, StoreKeysPrefixes, StorePrefix,
+    },
+};
+
+use thiserror::Error;
+
+use std::{collections::BTreeMap, sync::Arc};
+
+const BLOCK_SIZE: u64 = 512;
+
+/// The location of a regular file within a tar archive.
+#[derive(Debug, Clone, Copy)]
+struct TarEntry {
+    offset: u64,
+    size: u64,
+}
+
+/// A tar storage adapter.
+///
+/// A read only store over an uncompressed tar archive (ustar, GNU, or pax) stored under a key of the underlying storage.
+/// The tar headers are read once on creation to build an index of the offset and size of each regular file.
+/// Reads are then translated to byte range requests of the archive, so partial reads of a value only fetch the requested bytes.
+pub struct TarStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    key: StoreKey,
+    size: u64,
+    entries: BTreeMap<StoreKey, TarEntry>,
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> TarStorageAdapter<TStorage> {
+    /// Create a new tar storage adapter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TarStorageAdapterCreateError`] if the value at `key` is not a valid tar archive.
+    pub fn new(
+        storage: Arc<TStorage>,
+        key: StoreKey,
+    ) -> Result<Self, TarStorageAdapterCreateError> {
+        Self::new_with_path(storage, key, "")
+    }
+
+    /// Create a new tar storage adapter to `path` within the tar archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TarStorageAdapterCreateError`] if the value at `key` is not a valid tar archive.
+    pub fn new_with_path(
+        storage: Arc<TStorage>,
+        key: StoreKey,
+        path: &str,
+    ) -> Result<Self, TarStorageAdapterCreateError> {
+        let size = storage
+            .size_key(&key)?
+            .ok_or::<TarStorageAdapterCreateError>(
+                StorageError::UnknownKeySize(key.clone()).into(),
+            )?;
+
+        let read = |offset: u64, length: u64| -> Result<Vec<u8>, TarStorageAdapterCreateError> {
+            storage
+                .get_partial_values_key(&key, &[ByteRange::FromStart(offset, Some(length))])?
+                .map(|mut bytes| bytes.remove(0))
+                .ok_or_else(|| StorageError::UnknownKeySize(key.clone()).into())
+        };
+
+        let mut entries = BTreeMap::new();
+        let mut long_name: Option<String> = None;
+        let mut pax_path: Option<String> = None;
+        let mut pax_size: Option<u64> = None;
+        let mut offset = 0;
+        while offset + BLOCK_SIZE <= size {
+            let header = read(offset, BLOCK_SIZE)?;
+            if header.iter().all(|&byte| byte == 0) {
+                break; // end of archive
+            }
+            if !header_checksum_valid(&header) {
+                return Err(TarStorageAdapterCreateError::TarError(format!(
+                    "invalid header checksum at offset {offset}"
+                )));
+            }
+
+            let typeflag = header[156];
+            let header_size = parse_numeric(&header[124..136]).ok_or_else(|| {
+                TarStorageAdapterCreateError::TarError(format!(
+                    "invalid entry size at offset {offset}"
+                ))
+            })?;
+            let entry_size = match typeflag {
+                b'L' | b'x' | b'g' => header_size,
+                _ => pax_size.take().unwrap_or(header_size),
+            };
+            let data_offset = offset + BLOCK_SIZE;
+            if data_offset + entry_size > size {
+                return Err(TarStorageAdapterCreateError::TarError(format!(
+                    "truncated entry at offset {offset}"
+                )));
+            }
+
+            match typeflag {
+                b'L' => {
+                    let data = read(data_offset, entry_size)?;
+                    long_name = Some(parse_string(&data));
+                }
+                b'x' => {
+                    let data = read(data_offset, entry_size)?;
+                    for (keyword, value) in parse_pax_records(&data) {
+                        match keyword {
+                            "path" => pax_path = Some(value.to_string()),
+                            "size" => pax_size = value.parse().ok(),
+                            _ => {}
+                        }
+                    }
+                }
+                b'g' => {}
+                _ => {
+                    let name = pax_path
+                        .take()
+                        .or(long_name.take())
+                        .unwrap_or_else(|| header_name(&header));
+                    if matches!(typeflag, b'0' | b'\0' | b'7') {
+                        let name = name.trim_start_matches("./").trim_start_matches('/');
+                        if let Some(key) = name
+                            .strip_prefix(path)
+                            .and_then(|name| StoreKey::try_from(name).ok())
+                        {
+                            entries.insert(
+                                key,
+                                TarEntry {
+                                    offset: data_offset,
+                                    size: entry_size,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            offset = data_offset + entry_size.next_multiple_of(BLOCK_SIZE);
+        }
+
+        Ok(Self {
+            storage,
+            key,
+            size,
+            entries,
+        })
+    }
+
+    fn entries_prefix<'a>(
+        &'a self,
+        prefix: &'a StorePrefix,
+    ) -> impl Iterator<Item = (&'a StoreKey, &'a TarEntry)> + 'a {
+        self.entries
+            .iter()
+            .skip_while(move |(key, _)| key.as_str() < prefix.as_str())
+            .take_while(move |(key, _)| key.as_str().starts_with(prefix.as_str()))
+    }
+}
+
+/// Returns true if the checksum of a tar header is valid.
+fn header_checksum_valid(header: &[u8]) -> bool {
+    let checksum: u64 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| {
+            if (148..156).contains(&i) {
+                u64::from(b' ')
+            } else {
+                u64::from(byte)
+            }
+        })
+        .sum();
+    parse_numeric(&header[148..156]) == Some(checksum)
+}
+
+/// Parse a numeric header field, which is either octal or base-256 encoded.
+fn parse_numeric(field: &[u8]) -> Option<u64> {
+    if field[0] & 0x80 != 0 {
+        field[1..]
+            .iter()
+            .try_fold(u64::from(field[0] & 0x7f), |value, &byte| {
+                value.checked_mul(256).map(|value| value + u64::from(byte))
+            })
+    } else {
+        let field = std::str::from_utf8(field).ok()?;
+        let field = field.trim_matches(|c| c == '\0' || c == ' ');
+        if field.is_empty() {
+            Some(0)
+        } else {
+            u64::from_str_radix(field, 8).ok()
+        }
+    }
+}
+
+/// Parse a nul terminated string.
+fn parse_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Returns the name of a tar header, including the ustar prefix if present.
+fn header_name(header: &[u8]) -> String {
+    let name = parse_string(&header[0..100]);
+    if &header[257..262] == b"ustar" {
+        let prefix = parse_string(&header[345..500]);
+        if !prefix.is_empty() {
+            return prefix + "/" + &name;
+        }
+    }
+    name
+}
+
+/// Parse the `<length> <keyword>=<value>\n` records of a pax extended header.
+fn parse_pax_records(data: &[u8]) -> Vec<(&str, &str)> {
+    let mut records = Vec::new();
+    let mut data = data;
+    while let Some(space) = data.iter().position(|&b| b == b' ') {
+        let Some(length) = std::str::from_utf8(&data[..space])
+            .ok()
+            .and_then(|length| length.parse::<usize>().ok())
+            .filter(|&length| length > space && length <= data.len())
+        else {
+            break;
+        };
+        let record = &data[space + 1..length];
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+        if let Some((keyword, value)) = std::str::from_utf8(record)
+            .ok()
+            .and_then(|record| record.split_once('='))
+        {
+            records.push((keyword, value));
+        }
+        data = &data[length..];
+    }
+    records
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for TarStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        Ok(self
+            .get_partial_values_key(key, &[ByteRange::FromStart(0, None)])?
+            .map(|mut bytes| bytes.remove(0).into()))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let Some(entry) = self.entries.get(key) else {
+            return Ok(None);
+        };
+        let byte_ranges = byte_ranges
+            .iter()
+            .map(|byte_range| match byte_range {
+                ByteRange::FromStart(offset, length) | ByteRange::FromEnd(offset, length)
+                    if offset + length.unwrap_or(0) > entry.size =>
+                {
+                    Err(InvalidByteRangeError::new(*byte_range, entry.size))
+                }
+                _ => Ok(ByteRange::FromStart(
+                    entry.offset + byte_range.start(entry.size),
+                    Some(byte_range.length(entry.size)),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.storage
+            .get_partial_values_key(&self.key, &byte_ranges)?
+            .map(Some)
+            .ok_or_else(|| StorageError::UnknownKeySize(self.key.clone()))
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        Ok(self.size)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        Ok(self
+            .entries_prefix(prefix)
+            .map(|(_, entry)| entry.size)
+            .sum())
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        Ok(self.entries.get(key).map(|entry| entry.size))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ListableStorageTraits
+    for TarStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.entries.keys().cloned().collect())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self
+            .entries_prefix(prefix)
+            .map(|(key, _)| key.clone())
+            .collect())
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys = StoreKeys::new();
+        let mut prefixes = Vec::<StorePrefix>::new();
+        for (key, _) in self.entries_prefix(prefix) {
+            let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+            if let Some((child, _)) = key_strip.split_once('/') {
+                // Keys are ordered, so keys under the same child prefix are adjacent
+                let child = StorePrefix::new(prefix.as_str().to_string() + child + "/")?;
+                if prefixes.last() != Some(&child) {
+                    prefixes.push(child);
+                }
+            } else {
+                keys.push(key.clone());
+            }
+        }
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+/// A tar store creation error.
+#[derive(Debug, Error)]
+pub enum TarStorageAdapterCreateError {
+    /// A tar error.
+    #[error("{0}")]
+    TarError(String),
+    /// A storage error.
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{store::MemoryStore, WritableStorageTraits};
+
+    use super::*;
+    use std::error::Error;
+
+    fn tar_header(name: &str, size: usize, typeflag: u8) -> Vec<u8> {
+        let mut header = vec![0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{size:011o}").as_bytes());
+        header[156] = typeflag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        header[148..155].copy_from_slice(format!("{checksum:06o}\0").as_bytes());
+        header
+    }
+
+    fn tar_append(tar: &mut Vec<u8>, name: &str, data: &[u8], typeflag: u8) {
+        tar.extend(tar_header(name, data.len(), typeflag));
+        tar.extend(data);
+        tar.resize(tar.len().next_multiple_of(512), 0);
+    }
+
+    fn tar_write() -> Vec<u8> {
+        let long_name = format!("a/{}/i", "x".repeat(120));
+        let pax_record = format!(" path={long_name}\n");
+        let pax_record = format!("{} {}", pax_record.len() + 3, &pax_record[1..]);
+
+        let mut tar = Vec::new();
+        tar_append(&mut tar, "./a/", &[], b'5');
+        tar_append(&mut tar, "./a/b", &[0, 1, 2, 3], b'0');
+        tar_append(&mut tar, "a/c", &[], b'0');
+        tar_append(&mut tar, "a/d/e", &[4, 5], b'0');
+        tar_append(&mut tar, "a/f/g", &[], b'0');
+        tar_append(&mut tar, "a/f/h", &[], b'0');
+        tar_append(&mut tar, "a/link", &[], b'2');
+        tar_append(&mut tar, "PaxHeader", pax_record.as_bytes(), b'x');
+        tar_append(&mut tar, "truncated", &[6, 7, 8], b'0');
+        tar_append(&mut tar, "b/c/d", &[], b'0');
+        tar_append(&mut tar, "c", &(0..=255).collect::<Vec<u8>>(), b'0');
+        tar.resize(tar.len() + 1024, 0);
+        tar
+    }
+
+    #[test]
+    fn tar_root() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        store.set(&"test.tar".try_into()?, &tar_write())?;
+
+        let long_name = format!("a/{}/i", "x".repeat(120));
+        let store = TarStorageAdapter::new(store, StoreKey::new("test.tar")?)?;
+        assert_eq!(
+            store.list()?,
+            &[
+                "a/b".try_into()?,
+                "a/c".try_into()?,
+                "a/d/e".try_into()?,
+                "a/f/g".try_into()?,
+                "a/f/h".try_into()?,
+                long_name.as_str().try_into()?,
+                "b/c/d".try_into()?,
+                "c".try_into()?,
+            ]
+        );
+        assert_eq!(
+            store.list_prefix(&"a/d/".try_into()?)?,
+            &["a/d/e".try_into()?]
+        );
+
+        let list = store.list_dir(&"a/".try_into()?)?;
+        assert_eq!(list.keys(), &["a/b".try_into()?, "a/c".try_into()?]);
+        assert_eq!(
+            list.prefixes(),
+            &[
+                "a/d/".try_into()?,
+                "a/f/".try_into()?,
+                format!("a/{}/", "x".repeat(120)).as_str().try_into()?,
+            ]
+        );
+        let list = store.list_dir(&"".try_into()?)?;
+        assert_eq!(list.keys(), &["c".try_into()?]);
+        assert_eq!(list.prefixes(), &["a/".try_into()?, "b/".try_into()?]);
+
+        assert_eq!(
+            store.get(&"a/b".try_into()?)?.unwrap(),
+            [0, 1, 2, 3].as_slice()
+        );
+        assert!(store.get(&"a/c".try_into()?)?.unwrap().is_empty());
+        assert_eq!(
+            store.get(&long_name.as_str().try_into()?)?.unwrap(),
+            [6, 7, 8].as_slice()
+        );
+        assert!(store.get(&"a/link".try_into()?)?.is_none());
+        assert!(store.get(&"a/d".try_into()?)?.is_none());
+        assert_eq!(
+            store.get_partial_values_key(
+                &"c".try_into()?,
+                &[
+                    ByteRange::FromStart(1, Some(2)),
+                    ByteRange::FromEnd(0, Some(3)),
+                    ByteRange::FromEnd(254, None),
+                ]
+            )?,
+            Some(vec![vec![1, 2], vec![253, 254, 255], vec![0, 1]])
+        );
+        assert!(store
+            .get_partial_values_key(&"c".try_into()?, &[ByteRange::FromStart(255, Some(2))])
+            .is_err());
+
+        assert_eq!(store.size_key(&"c".try_into()?)?, Some(256));
+        assert_eq!(store.size_prefix(&"a/".try_into()?)?, 9);
+        assert_eq!(store.size_prefix(&"".try_into()?)?, 265);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tar_path() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        store.set(&"test.tar".try_into()?, &tar_write())?;
+
+        let store = TarStorageAdapter::new_with_path(store, StoreKey::new("test.tar")?, "a/f/")?;
+        assert_eq!(store.list()?, &["g".try_into()?, "h".try_into()?]);
+        assert!(crate::storage::node_exists_listable(
+            &store,
+            &"/g".try_into()?
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn tar_invalid() -> Result<(), Box<dyn Error>> {
+        let store = Arc::new(MemoryStore::new());
+        let mut tar = tar_write();
+        tar[0] = b'z';
+        store.set(&"test.tar".try_into()?, &tar)?;
+        assert!(TarStorageAdapter::new(store.clone(), StoreKey::new("test.tar")?).is_err());
+        assert!(TarStorageAdapter::new(store, StoreKey::new("missing.tar")?).is_err());
+        Ok(())
+    }
+}
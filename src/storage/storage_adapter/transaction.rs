@@ -0,0 +1,403 @@
+//! A transaction storage adapter.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{extract_byte_ranges, ByteRange},
+    storage::{
+        store_set_partial_values, ListableStorageTraits, ReadableStorageTraits,
+        ReadableWritableListableStorageTraits, ReadableWritableStorageTraits, StorageError,
+        StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeyValue, StoreKeys, StoreKeysPrefixes,
+        StorePrefix, WritableStorageTraits,
+    },
+};
+
+/// Changes staged in a transaction.
+#[derive(Debug, Default)]
+struct Staged {
+    /// Values set (`Some`) or erased (`None`) in the transaction.
+    values: BTreeMap<StoreKey, MaybeBytes>,
+    /// Prefixes erased in the transaction. These are applied before `values`.
+    erased_prefixes: Vec<StorePrefix>,
+}
+
+impl Staged {
+    /// Returns the staged value of `key`, or [`None`] if the transaction does not change `key`.
+    fn get(&self, key: &StoreKey) -> Option<&MaybeBytes> {
+        if let Some(value) = self.values.get(key) {
+            Some(value)
+        } else if self
+            .erased_prefixes
+            .iter()
+            .any(|prefix| key.has_prefix(prefix))
+        {
+            Some(&None)
+        } else {
+            None
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.erased_prefixes.is_empty()
+    }
+}
+
+/// A transaction storage adapter.
+///
+/// Writes and erasures are staged in memory and are only applied to the underlying storage on [`commit`](TransactionStorageAdapter::commit).
+/// Staged changes are discarded on [`rollback`](TransactionStorageAdapter::rollback) or if the adapter is dropped without committing.
+/// Reads and listing observe staged changes.
+///
+/// This provides all-or-nothing semantics for writing a hierarchy, such as the metadata and chunks of an array.
+/// On commit, chunks are written before metadata, and the original values of all affected keys are restored if any operation fails.
+/// Changes are applied to the underlying storage sequentially, so other readers of the underlying storage may observe a partially applied commit.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+/// use zarrs::storage::{storage_adapter::TransactionStorageAdapter, store::MemoryStore, ListableStorageTraits};
+///
+/// let store = Arc::new(MemoryStore::new());
+/// let transaction = Arc::new(TransactionStorageAdapter::new(store.clone()));
+/// let array = ArrayBuilder::new(vec![4], DataType::UInt8, vec![2].try_into()?, FillValue::from(0u8))
+///     .build(transaction.clone(), "/array")?;
+/// array.store_metadata()?;
+/// array.store_chunk_elements::<u8>(&[0], vec![1, 2])?;
+/// assert!(store.list()?.is_empty());
+/// transaction.commit()?;
+/// assert_eq!(store.list()?.len(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct TransactionStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    staged: Mutex<Staged>,
+}
+
+impl<TStorage: ?Sized> TransactionStorageAdapter<TStorage> {
+    /// Create a new transaction storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self {
+            storage,
+            staged: Mutex::new(Staged::default()),
+        }
+    }
+
+    /// Returns true if the transaction has staged changes.
+    #[must_use]
+    pub fn has_staged_changes(&self) -> bool {
+        !self.staged.lock().is_empty()
+    }
+
+    /// Discard all staged changes.
+    pub fn rollback(&self) {
+        *self.staged.lock() = Staged::default();
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableListableStorageTraits> TransactionStorageAdapter<TStorage> {
+    /// Apply all staged changes to the underlying storage.
+    ///
+    /// Erased prefixes are applied first, then erased keys, then set keys with metadata keys (`zarr.json`) last.
+    /// Staged changes are cleared if the commit succeeds.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying error with the store.
+    /// The original values of all affected keys are restored on a best-effort basis, and the staged changes are retained so that the commit can be retried or rolled back.
+    pub fn commit(&self) -> Result<(), StorageError> {
+        let mut staged = self.staged.lock();
+
+        // Record the original values of all affected keys
+        let mut keys = staged.values.keys().cloned().collect::<BTreeSet<_>>();
+        for prefix in &staged.erased_prefixes {
+            keys.extend(self.storage.list_prefix(prefix)?);
+        }
+        let original = keys
+            .into_iter()
+            .map(|key| {
+                let value = self.storage.get(&key)?;
+                Ok((key, value))
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        if let Err(err) = self.apply(&staged) {
+            for (key, value) in original {
+                // Best-effort, the original error is returned
+                let _ = match value {
+                    Some(value) => self.storage.set(&key, &value),
+                    None => self.storage.erase(&key),
+                };
+            }
+            return Err(err);
+        }
+
+        *staged = Staged::default();
+        Ok(())
+    }
+
+    fn apply(&self, staged: &Staged) -> Result<(), StorageError> {
+        for prefix in &staged.erased_prefixes {
+            self.storage.erase_prefix(prefix)?;
+        }
+
+        let is_metadata =
+            |key: &StoreKey| key.as_str() == "zarr.json" || key.as_str().ends_with("/zarr.json");
+        let mut erased = vec![];
+        let mut set_data = vec![];
+        let mut set_metadata = vec![];
+        for (key, value) in &staged.values {
+            match value {
+                None => erased.push(key.clone()),
+                Some(value) if is_metadata(key) => set_metadata.push((key, value)),
+                Some(value) => set_data.push((key, value)),
+            }
+        }
+
+        self.storage.erase_values(&erased)?;
+        for (key, value) in set_data.into_iter().chain(set_metadata) {
+            self.storage.set(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> ReadableStorageTraits
+    for TransactionStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        if let Some(value) = self.staged.lock().get(key) {
+            return Ok(value.clone());
+        }
+        self.storage.get(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        if let Some(value) = self.staged.lock().get(key) {
+            return Ok(value
+                .as_ref()
+                .map(|value| extract_byte_ranges(value, byte_ranges))
+                .transpose()?);
+        }
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.list_prefix(prefix)? {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        if let Some(value) = self.staged.lock().get(key) {
+            return Ok(value.as_ref().map(|value| value.len() as u64));
+        }
+        self.storage.size_key(key)
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for TransactionStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let keys = self.storage.list_prefix(prefix)?;
+        let staged = self.staged.lock();
+        let mut keys = keys
+            .into_iter()
+            .filter(|key| !matches!(staged.get(key), Some(None)))
+            .collect::<StoreKeys>();
+        keys.extend(
+            staged
+                .values
+                .iter()
+                .filter(|(key, value)| value.is_some() && key.has_prefix(prefix))
+                .map(|(key, _)| key.clone()),
+        );
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        for key in self.list_prefix(prefix)? {
+            let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+            if let Some((child, _)) = key_strip.split_once('/') {
+                prefixes.insert(StorePrefix::new(prefix.as_str().to_string() + child + "/")?);
+            } else {
+                keys.push(key);
+            }
+        }
+        let prefixes: Vec<StorePrefix> = prefixes.into_iter().collect();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> WritableStorageTraits
+    for TransactionStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.staged
+            .lock()
+            .values
+            .insert(key.clone(), Some(bytes::Bytes::copy_from_slice(value)));
+        Ok(())
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        let mut staged = self.staged.lock();
+        for key_value in key_values {
+            staged.values.insert(
+                key_value.key().clone(),
+                Some(bytes::Bytes::copy_from_slice(key_value.value())),
+            );
+        }
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_start_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.staged.lock().values.insert(key.clone(), None);
+        Ok(())
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let mut staged = self.staged.lock();
+        for key in keys {
+            staged.values.insert(key.clone(), None);
+        }
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let mut staged = self.staged.lock();
+        staged.values.retain(|key, _| !key.has_prefix(prefix));
+        staged
+            .erased_prefixes
+            .retain(|erased_prefix| !erased_prefix.as_str().starts_with(prefix.as_str()));
+        staged.erased_prefixes.push(prefix.clone());
+        Ok(())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits> ReadableWritableStorageTraits
+    for TransactionStorageAdapter<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn transaction_storage_adapter() {
+        let store = Arc::new(MemoryStore::new());
+        let key_a = StoreKey::new("a/0").unwrap();
+        let key_b = StoreKey::new("a/1").unwrap();
+        let key_c = StoreKey::new("b/0").unwrap();
+        let key_d = StoreKey::new("b/1").unwrap();
+        store.set(&key_a, &[0]).unwrap();
+        store.set(&key_c, &[1]).unwrap();
+
+        let transaction = TransactionStorageAdapter::new(store.clone());
+        let stage = |transaction: &TransactionStorageAdapter<MemoryStore>| {
+            transaction.set(&key_b, &[2]).unwrap();
+            transaction.erase(&key_a).unwrap();
+            transaction
+                .erase_prefix(&StorePrefix::new("b/").unwrap())
+                .unwrap();
+            transaction.set(&key_d, &[3]).unwrap();
+            transaction
+                .set_partial_values(&[StoreKeyStartValue::new(key_b.clone(), 1, &[4])])
+                .unwrap();
+        };
+
+        // Staged changes are visible through the transaction, but not the store
+        stage(&transaction);
+        assert!(transaction.has_staged_changes());
+        assert_eq!(transaction.get(&key_a).unwrap(), None);
+        assert_eq!(transaction.get(&key_b).unwrap(), Some(vec![2, 4].into()));
+        assert_eq!(transaction.get(&key_c).unwrap(), None);
+        assert_eq!(transaction.size().unwrap(), 3);
+        assert_eq!(
+            transaction.list().unwrap(),
+            vec![key_b.clone(), key_d.clone()]
+        );
+        assert_eq!(
+            transaction
+                .list_dir(&StorePrefix::root())
+                .unwrap()
+                .prefixes(),
+            &[
+                StorePrefix::new("a/").unwrap(),
+                StorePrefix::new("b/").unwrap()
+            ]
+        );
+        assert_eq!(store.list().unwrap(), vec![key_a.clone(), key_c.clone()]);
+
+        // Rollback discards staged changes
+        transaction.rollback();
+        assert!(!transaction.has_staged_changes());
+        assert_eq!(
+            transaction.list().unwrap(),
+            vec![key_a.clone(), key_c.clone()]
+        );
+
+        // Commit applies staged changes
+        stage(&transaction);
+        transaction.commit().unwrap();
+        assert!(!transaction.has_staged_changes());
+        assert_eq!(store.list().unwrap(), vec![key_b.clone(), key_d.clone()]);
+        assert_eq!(store.get(&key_b).unwrap(), Some(vec![2, 4].into()));
+
+        // Dropping discards staged changes
+        let transaction = TransactionStorageAdapter::new(store.clone());
+        transaction.erase_prefix(&StorePrefix::root()).unwrap();
+        drop(transaction);
+        assert_eq!(store.list().unwrap(), vec![key_b, key_d]);
+    }
+}
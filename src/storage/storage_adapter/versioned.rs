@@ -0,0 +1,562 @@
+//! A versioned storage adapter.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    storage::{
+        store_set_partial_values, ListableStorageTraits, ReadableStorageTraits,
+        ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
+        StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+    },
+};
+
+/// The prefix in the underlying storage holding the commits and objects of a [`VersionedStorageAdapter`].
+pub const VERSIONS_PREFIX: &str = ".versions/";
+
+/// A commit of a [`VersionedStorageAdapter`].
+///
+/// A commit maps every key of a version to the generation (the version in which the value was written) of an immutable object in the underlying storage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commit {
+    version: u64,
+    parent: Option<u64>,
+    message: String,
+    timestamp: u64,
+    keys: BTreeMap<String, u64>,
+}
+
+impl Commit {
+    /// Return the version.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Return the parent version, which is [`None`] for the first version.
+    #[must_use]
+    pub fn parent(&self) -> Option<u64> {
+        self.parent
+    }
+
+    /// Return the commit message.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Return the commit time in seconds since the Unix epoch.
+    #[must_use]
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Return the number of keys in the version.
+    #[must_use]
+    pub fn num_keys(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+/// The checked out version and staged changes of a [`VersionedStorageAdapter`].
+#[derive(Debug, Default)]
+struct State {
+    /// The checked out commit, [`None`] if there are no commits.
+    commit: Option<Commit>,
+    /// True if the checked out commit is not the latest version.
+    read_only: bool,
+    /// Keys set (with the generation of their object) or erased (`None`) since the checked out commit.
+    staged: BTreeMap<StoreKey, Option<u64>>,
+}
+
+impl State {
+    /// Return the generation of the objects written since the checked out commit.
+    fn generation(&self) -> u64 {
+        self.commit.as_ref().map_or(0, |commit| commit.version + 1)
+    }
+
+    /// Return the generation of the object of `key`, or [`None`] if `key` does not exist.
+    fn get(&self, key: &StoreKey) -> Option<u64> {
+        match self.staged.get(key) {
+            Some(generation) => *generation,
+            None => self
+                .commit
+                .as_ref()
+                .and_then(|commit| commit.keys.get(key.as_str()).copied()),
+        }
+    }
+
+    /// Return all existing keys with `prefix`.
+    fn keys(&self, prefix: &StorePrefix) -> StoreKeys {
+        let committed = self.commit.iter().flat_map(|commit| {
+            commit
+                .keys
+                .keys()
+                .filter(|key| key.starts_with(prefix.as_str()))
+                .map(|key| unsafe { StoreKey::new_unchecked(key.clone()) })
+        });
+        let mut keys = committed
+            .filter(|key| !self.staged.contains_key(key))
+            .chain(
+                self.staged
+                    .iter()
+                    .filter(|(key, generation)| generation.is_some() && key.has_prefix(prefix))
+                    .map(|(key, _)| key.clone()),
+            )
+            .collect::<StoreKeys>();
+        keys.sort();
+        keys
+    }
+
+    fn check_writable(&self) -> Result<(), StorageError> {
+        if self.read_only {
+            Err(StorageError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A versioned (copy-on-write) storage adapter.
+///
+/// Every value written through the adapter is stored as a new immutable object in the underlying storage below [`VERSIONS_PREFIX`], suffixed by a generation.
+/// [`commit`](VersionedStorageAdapter::commit) records a new version that references the objects of all keys, so committed values are never modified or erased.
+/// A reader can [`checkout`](VersionedStorageAdapter::checkout) any version and observe a consistent snapshot while the latest version continues to be updated.
+/// Changes since the checked out version are visible through the adapter but not to other adapters until committed, and can be discarded with [`rollback`](VersionedStorageAdapter::rollback).
+///
+/// Writing is only permitted if the latest version is checked out, otherwise writes fail with [`StorageError::ReadOnly`].
+/// Only one adapter should write to the underlying storage at a time, as concurrent writers write objects of the same generation.
+/// Each commit stores the generation of every key, so commits grow with the number of keys.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use zarrs::array::{ArrayBuilder, DataType, FillValue};
+/// use zarrs::storage::{storage_adapter::VersionedStorageAdapter, store::MemoryStore};
+///
+/// let store = Arc::new(MemoryStore::new());
+/// let versioned = Arc::new(VersionedStorageAdapter::new(store.clone())?);
+/// let array = ArrayBuilder::new(vec![4], DataType::UInt8, vec![2].try_into()?, FillValue::from(0u8))
+///     .build(versioned.clone(), "/array")?;
+/// array.store_metadata()?;
+/// array.store_chunk_elements::<u8>(&[0], vec![1, 2])?;
+/// let version = versioned.commit("initial")?;
+///
+/// array.store_chunk_elements::<u8>(&[0], vec![3, 4])?;
+/// versioned.commit("update")?;
+///
+/// let snapshot = Arc::new(VersionedStorageAdapter::new(store)?);
+/// snapshot.checkout(version)?;
+/// let array = zarrs::array::Array::new(snapshot, "/array")?;
+/// assert_eq!(array.retrieve_chunk_elements::<u8>(&[0])?, vec![1, 2]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct VersionedStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    state: Mutex<State>,
+}
+
+fn head_key() -> StoreKey {
+    unsafe { StoreKey::new_unchecked(VERSIONS_PREFIX.to_string() + "HEAD") }
+}
+
+fn commit_key(version: u64) -> StoreKey {
+    unsafe { StoreKey::new_unchecked(format!("{VERSIONS_PREFIX}commits/{version}.json")) }
+}
+
+fn object_key(generation: u64, key: &StoreKey) -> StoreKey {
+    unsafe {
+        StoreKey::new_unchecked(format!(
+            "{VERSIONS_PREFIX}objects/{generation}/{}",
+            key.as_str()
+        ))
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> VersionedStorageAdapter<TStorage> {
+    /// Create a new versioned storage adapter with the latest version of `storage` checked out.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or the latest commit is invalid.
+    pub fn new(storage: Arc<TStorage>) -> Result<Self, StorageError> {
+        let adapter = Self {
+            storage,
+            state: Mutex::new(State::default()),
+        };
+        adapter.checkout_latest()?;
+        Ok(adapter)
+    }
+
+    /// Return the latest version, or [`None`] if there are no commits.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or the head is invalid.
+    pub fn latest_version(&self) -> Result<Option<u64>, StorageError> {
+        let key = head_key();
+        self.storage
+            .get(&key)?
+            .map(|head| {
+                serde_json::from_slice(&head)
+                    .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Retrieve the commit of `version`.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the version does not exist, there is an underlying store error, or the commit is invalid.
+    pub fn retrieve_commit(&self, version: u64) -> Result<Commit, StorageError> {
+        let key = commit_key(version);
+        let commit = self
+            .storage
+            .get(&key)?
+            .ok_or_else(|| StorageError::Other(format!("version {version} does not exist")))?;
+        serde_json::from_slice(&commit)
+            .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))
+    }
+
+    /// Retrieve the commits of all versions from the first to the latest.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or any commit is invalid.
+    pub fn versions(&self) -> Result<Vec<Commit>, StorageError> {
+        self.latest_version()?.map_or(Ok(vec![]), |latest| {
+            (0..=latest)
+                .map(|version| self.retrieve_commit(version))
+                .collect()
+        })
+    }
+
+    /// Return the checked out version, or [`None`] if there are no commits.
+    #[must_use]
+    pub fn version(&self) -> Option<u64> {
+        self.state.lock().commit.as_ref().map(Commit::version)
+    }
+
+    /// Checkout `version`, discarding any staged changes.
+    ///
+    /// The adapter is read only unless `version` is the latest version.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the version does not exist or there is an underlying store error.
+    pub fn checkout(&self, version: u64) -> Result<(), StorageError> {
+        let latest = self.latest_version()?;
+        let commit = self.retrieve_commit(version)?;
+        *self.state.lock() = State {
+            commit: Some(commit),
+            read_only: latest != Some(version),
+            staged: BTreeMap::default(),
+        };
+        Ok(())
+    }
+
+    /// Checkout the latest version, discarding any staged changes.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error or the latest commit is invalid.
+    pub fn checkout_latest(&self) -> Result<(), StorageError> {
+        if let Some(latest) = self.latest_version()? {
+            self.checkout(latest)
+        } else {
+            *self.state.lock() = State::default();
+            Ok(())
+        }
+    }
+
+    /// Returns true if there are changes since the checked out version.
+    #[must_use]
+    pub fn has_staged_changes(&self) -> bool {
+        !self.state.lock().staged.is_empty()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits>
+    VersionedStorageAdapter<TStorage>
+{
+    /// Commit the changes since the checked out version as a new version with `message`, and return the new version.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the checked out version is not the latest version or there is an underlying store error.
+    pub fn commit(&self, message: &str) -> Result<u64, StorageError> {
+        let latest = self.latest_version()?;
+        let mut state = self.state.lock();
+        state.check_writable()?;
+        let parent = state.commit.as_ref().map(Commit::version);
+        if latest != parent {
+            return Err(StorageError::Other(format!(
+                "the checked out version {parent:?} is not the latest version {latest:?}"
+            )));
+        }
+
+        let mut keys = state
+            .commit
+            .as_ref()
+            .map(|commit| commit.keys.clone())
+            .unwrap_or_default();
+        for (key, generation) in &state.staged {
+            if let Some(generation) = generation {
+                keys.insert(key.as_str().to_string(), *generation);
+            } else {
+                keys.remove(key.as_str());
+            }
+        }
+        let commit = Commit {
+            version: state.generation(),
+            parent,
+            message: message.to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            keys,
+        };
+        let version = commit.version;
+        let commit_json =
+            serde_json::to_vec(&commit).map_err(|err| StorageError::Other(err.to_string()))?;
+        self.storage.set(&commit_key(version), &commit_json)?;
+        self.storage
+            .set(&head_key(), version.to_string().as_bytes())?;
+
+        state.commit = Some(commit);
+        state.staged.clear();
+        Ok(version)
+    }
+
+    /// Discard the changes since the checked out version.
+    ///
+    /// The objects written since the checked out version are erased from the underlying storage.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying store error.
+    pub fn rollback(&self) -> Result<(), StorageError> {
+        let mut state = self.state.lock();
+        let generation = state.generation();
+        for (key, key_generation) in &state.staged {
+            if *key_generation == Some(generation) {
+                self.storage.erase(&object_key(generation, key))?;
+            }
+        }
+        state.staged.clear();
+        Ok(())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for VersionedStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let generation = self.state.lock().get(key);
+        match generation {
+            Some(generation) => self.storage.get(&object_key(generation, key)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let generation = self.state.lock().get(key);
+        match generation {
+            Some(generation) => self
+                .storage
+                .get_partial_values_key(&object_key(generation, key), byte_ranges),
+            None => Ok(None),
+        }
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let keys = self.state.lock().keys(prefix);
+        let mut size = 0;
+        for key in keys {
+            size += self.size_key(&key)?.unwrap_or_default();
+        }
+        Ok(size)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let generation = self.state.lock().get(key);
+        match generation {
+            Some(generation) => self.storage.size_key(&object_key(generation, key)),
+            None => Ok(None),
+        }
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + Send + Sync> ListableStorageTraits for VersionedStorageAdapter<TStorage> {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self.state.lock().keys(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        for key in self.list_prefix(prefix)? {
+            let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+            if let Some((child, _)) = key_strip.split_once('/') {
+                prefixes.insert(StorePrefix::new(prefix.as_str().to_string() + child + "/")?);
+            } else {
+                keys.push(key);
+            }
+        }
+        let prefixes: Vec<StorePrefix> = prefixes.into_iter().collect();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits> WritableStorageTraits
+    for VersionedStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        let generation = {
+            let state = self.state.lock();
+            state.check_writable()?;
+            state.generation()
+        };
+        self.storage.set(&object_key(generation, key), value)?;
+        self.state
+            .lock()
+            .staged
+            .insert(key.clone(), Some(generation));
+        Ok(())
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        store_set_partial_values(self, key_start_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let mut state = self.state.lock();
+        state.check_writable()?;
+        let generation = state.generation();
+        if state.get(key) == Some(generation) {
+            // The object is not referenced by a commit
+            self.storage.erase(&object_key(generation, key))?;
+        }
+        let committed = state
+            .commit
+            .as_ref()
+            .is_some_and(|commit| commit.keys.contains_key(key.as_str()));
+        if committed {
+            state.staged.insert(key.clone(), None);
+        } else {
+            state.staged.remove(key);
+        }
+        Ok(())
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let keys = self.state.lock().keys(prefix);
+        self.erase_values(&keys)
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits> ReadableWritableStorageTraits
+    for VersionedStorageAdapter<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn versioned_storage_adapter() {
+        let store = Arc::new(MemoryStore::new());
+        let key_a = StoreKey::new("a/0").unwrap();
+        let key_b = StoreKey::new("a/1").unwrap();
+        let key_c = StoreKey::new("b/0").unwrap();
+
+        let versioned = VersionedStorageAdapter::new(store.clone()).unwrap();
+        assert_eq!(versioned.version(), None);
+        versioned.set(&key_a, &[0]).unwrap();
+        versioned.set(&key_c, &[1]).unwrap();
+        assert!(versioned.has_staged_changes());
+        assert_eq!(versioned.commit("first").unwrap(), 0);
+        assert!(!versioned.has_staged_changes());
+
+        // A reader pinned to version 0
+        let reader = VersionedStorageAdapter::new(store.clone()).unwrap();
+        assert_eq!(reader.version(), Some(0));
+
+        versioned.set(&key_a, &[2]).unwrap();
+        versioned.set(&key_b, &[3]).unwrap();
+        versioned
+            .erase_prefix(&StorePrefix::new("b/").unwrap())
+            .unwrap();
+        assert_eq!(
+            versioned.list().unwrap(),
+            vec![key_a.clone(), key_b.clone()]
+        );
+        assert_eq!(
+            versioned.list_dir(&StorePrefix::root()).unwrap().prefixes(),
+            &[StorePrefix::new("a/").unwrap()]
+        );
+        assert_eq!(versioned.commit("second").unwrap(), 1);
+        assert_eq!(versioned.get(&key_a).unwrap(), Some(vec![2].into()));
+        assert_eq!(versioned.get(&key_c).unwrap(), None);
+
+        // The reader observes a consistent snapshot
+        assert_eq!(reader.get(&key_a).unwrap(), Some(vec![0].into()));
+        assert_eq!(reader.get(&key_c).unwrap(), Some(vec![1].into()));
+        assert_eq!(reader.size().unwrap(), 2);
+        reader.checkout(0).unwrap();
+        assert!(matches!(
+            reader.set(&key_a, &[4]),
+            Err(StorageError::ReadOnly)
+        ));
+        reader.checkout_latest().unwrap();
+        assert_eq!(reader.get(&key_b).unwrap(), Some(vec![3].into()));
+
+        // Rollback erases uncommitted objects
+        let num_objects = store.list().unwrap().len();
+        versioned.set(&key_c, &[5]).unwrap();
+        versioned.erase(&key_a).unwrap();
+        assert_eq!(
+            versioned.list().unwrap(),
+            vec![key_b.clone(), key_c.clone()]
+        );
+        versioned.rollback().unwrap();
+        assert_eq!(store.list().unwrap().len(), num_objects);
+        assert_eq!(versioned.list().unwrap(), vec![key_a, key_b]);
+
+        let versions = versioned.versions().unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].message(), "second");
+        assert_eq!(versions[1].parent(), Some(0));
+        assert_eq!(versions[1].num_keys(), 2);
+        assert!(versioned.checkout(2).is_err());
+    }
+}
@@ -0,0 +1,396 @@
+//! A write once storage adapter.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits, StorageError,
+        StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeys, StoreKeysPrefixes, StorePrefix,
+        WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncReadableWritableStorageTraits,
+    AsyncWritableStorageTraits,
+};
+
+/// A write once storage adapter.
+///
+/// Keys can be written if they do not exist in the underlying storage, and any attempt to write to or erase an existing key fails with [`StorageError::WriteOnce`].
+/// Erasing a key that does not exist is permitted (e.g. when a chunk equal to the fill value is stored), and erasing a prefix is not supported.
+/// This is useful for append-only ingestion policies.
+///
+/// If the underlying storage supports `set_if_not_exists`, values are written with it so that concurrent writes to the same key cannot both succeed.
+/// Otherwise, the existence check and write are not atomic, so concurrent writes to the same key may both succeed.
+///
+/// The adapter implements the sync storage traits if the underlying storage does, and the async storage traits if the underlying storage does (with the `async` feature).
+#[derive(Debug)]
+pub struct WriteOnceStorageAdapter<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+}
+
+impl<TStorage: ?Sized> WriteOnceStorageAdapter<TStorage> {
+    /// Create a new write once storage adapter.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>) -> Self {
+        Self { storage }
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> WriteOnceStorageAdapter<TStorage> {
+    fn check_not_exists(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.storage.size_key(key)?.is_some() {
+            Err(StorageError::WriteOnce(key.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> WriteOnceStorageAdapter<TStorage> {
+    async fn async_check_not_exists(&self, key: &StoreKey) -> Result<(), StorageError> {
+        if self.storage.size_key(key).await?.is_some() {
+            Err(StorageError::WriteOnce(key.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn erase_prefix_unsupported() -> StorageError {
+    StorageError::Unsupported("erase_prefix is not supported by a write once store".to_string())
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_partial_values(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> WritableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        if self.storage.supports_set_if_not_exists() {
+            return if self.storage.set_if_not_exists(key, value)? {
+                Ok(())
+            } else {
+                Err(StorageError::WriteOnce(key.clone()))
+            };
+        }
+        self.check_not_exists(key)?;
+        self.storage.set(key, value)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        for key in key_start_values
+            .iter()
+            .map(|key_start_value| &key_start_value.key)
+            .unique()
+        {
+            self.check_not_exists(key)?;
+        }
+        self.storage.set_partial_values(key_start_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.check_not_exists(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        keys.iter().try_for_each(|key| self.check_not_exists(key))
+    }
+
+    fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        Err(erase_prefix_unsupported())
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        self.storage.set_if_not_exists(key, value)
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.storage.get(key).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.storage.get_partial_values_key(key, byte_ranges).await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.storage.get_partial_values(key_ranges).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncWritableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        if self.storage.supports_set_if_not_exists() {
+            match self.storage.set_if_not_exists(key, value.clone()).await {
+                Ok(true) => return Ok(()),
+                Ok(false) => return Err(StorageError::WriteOnce(key.clone())),
+                // Not all object stores support conditional writes
+                Err(StorageError::Unsupported(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.async_check_not_exists(key).await?;
+        self.storage.set(key, value).await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        let keys = key_start_values
+            .iter()
+            .map(|key_start_value| &key_start_value.key)
+            .unique()
+            .collect::<Vec<_>>();
+        for key in keys {
+            self.async_check_not_exists(key).await?;
+        }
+        self.storage.set_partial_values(key_start_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.async_check_not_exists(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.async_check_not_exists(key).await?;
+        }
+        Ok(())
+    }
+
+    async fn erase_prefix(&self, _prefix: &StorePrefix) -> Result<(), StorageError> {
+        Err(erase_prefix_unsupported())
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    async fn set_if_not_exists(
+        &self,
+        key: &StoreKey,
+        value: bytes::Bytes,
+    ) -> Result<bool, StorageError> {
+        self.storage.set_if_not_exists(key, value).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
+    for WriteOnceStorageAdapter<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn write_once_storage_adapter() {
+        let store = Arc::new(MemoryStore::new());
+        let key_a = StoreKey::new("a").unwrap();
+        let key_b = StoreKey::new("b").unwrap();
+        store.set(&key_a, &[0]).unwrap();
+
+        let storage = WriteOnceStorageAdapter::new(store);
+        assert!(matches!(
+            storage.set(&key_a, &[1]),
+            Err(StorageError::WriteOnce(_))
+        ));
+        assert!(matches!(
+            storage.erase(&key_a),
+            Err(StorageError::WriteOnce(_))
+        ));
+        assert!(matches!(
+            storage.erase_prefix(&StorePrefix::root()),
+            Err(StorageError::Unsupported(_))
+        ));
+        assert_eq!(storage.get(&key_a).unwrap(), Some(vec![0].into()));
+
+        storage.erase(&key_b).unwrap();
+        storage.set(&key_b, &[1]).unwrap();
+        assert!(matches!(
+            storage.set_partial_values(&[StoreKeyStartValue::new(key_b.clone(), 0, &[2])]),
+            Err(StorageError::WriteOnce(_))
+        ));
+        assert_eq!(storage.get(&key_b).unwrap(), Some(vec![1].into()));
+        assert_eq!(storage.list().unwrap(), vec![key_a, key_b]);
+    }
+
+    #[test]
+    fn write_once_storage_adapter_concurrent() {
+        use rayon::prelude::*;
+
+        let storage = WriteOnceStorageAdapter::new(Arc::new(MemoryStore::new()));
+        assert!(storage.supports_set_if_not_exists());
+        let key = StoreKey::new("a").unwrap();
+        let written = (0..16u8)
+            .into_par_iter()
+            .filter(|i| storage.set(&key, &[*i]).is_ok())
+            .count();
+        assert_eq!(written, 1);
+    }
+
+    #[cfg(all(feature = "async", feature = "object_store"))]
+    #[tokio::test]
+    async fn async_write_once_storage_adapter() {
+        let store = Arc::new(crate::storage::store::AsyncObjectStore::new(
+            object_store::memory::InMemory::new(),
+        ));
+        let storage = WriteOnceStorageAdapter::new(store);
+        assert!(AsyncWritableStorageTraits::supports_set_if_not_exists(
+            &storage
+        ));
+        let key = StoreKey::new("a").unwrap();
+        storage.set(&key, vec![0].into()).await.unwrap();
+        assert!(matches!(
+            storage.set(&key, vec![1].into()).await,
+            Err(StorageError::WriteOnce(_))
+        ));
+        assert!(!storage
+            .set_if_not_exists(&key, vec![2].into())
+            .await
+            .unwrap());
+        assert_eq!(
+            AsyncReadableStorageTraits::get(&storage, &key)
+                .await
+                .unwrap(),
+            Some(vec![0].into())
+        );
+    }
+}
@@ -101,7 +101,7 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
 {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
         Ok(self.get_impl(key, &[ByteRange::FromStart(0, None)])?.map(
-            |mut bytes| bytes.remove(0), // extract single byte range
+            |mut bytes| bytes.remove(0).into(), // extract single byte range
         ))
     }
 
@@ -124,6 +124,11 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         Ok(self.size)
     }
 
+    fn supports_partial_reads(&self) -> bool {
+        // Entries are decompressed in full to read byte ranges
+        false
+    }
+
     fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
         let mut size = 0;
         for key in self.list_prefix(prefix)? {
@@ -367,7 +372,10 @@ mod tests {
             &"/a/b".try_into()?
         )?);
 
-        assert_eq!(store.get(&"a/b".try_into()?)?.unwrap(), &[0, 1, 2, 3]);
+        assert_eq!(
+            store.get(&"a/b".try_into()?)?.unwrap(),
+            [0, 1, 2, 3].as_slice()
+        );
         assert_eq!(
             store.get(&"a/c".try_into()?)?.unwrap(),
             Vec::<u8>::new().as_slice()
@@ -421,7 +429,10 @@ mod tests {
             &"/b".try_into()?
         )?);
 
-        assert_eq!(store.get(&"b".try_into()?)?.unwrap(), &[0, 1, 2, 3]);
+        assert_eq!(
+            store.get(&"b".try_into()?)?.unwrap(),
+            [0, 1, 2, 3].as_slice()
+        );
         // assert_eq!(store.get(&"c".try_into()?)?, Vec::<u8>::new().as_slice());
 
         Ok(())
@@ -6,14 +6,16 @@ use itertools::Itertools;
 
 use crate::{
     array::{ArrayMetadata, ChunkKeyEncoding, MaybeBytes},
+    audit::{audit, audit_enabled, AuditEvent},
     byte_range::ByteRange,
     group::{GroupMetadata, GroupMetadataV3},
     node::{Node, NodeMetadata, NodePath},
 };
 
 use super::{
-    data_key, meta_key, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeys,
-    StoreKeysPrefixes, StorePrefix, StorePrefixes,
+    data_key, meta_key, storage_sync::audit_metadata_overwrite, storage_transformer::sleep::Sleep,
+    MetadataWriteError, MetadataWriteOptions, NodeTransferProgress, StorageError, StoreKey,
+    StoreKeyRange, StoreKeyStartValue, StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
 };
 
 /// Async readable storage traits.
@@ -62,7 +64,7 @@ pub trait AsyncReadableStorageTraits: Send + Sync {
     ///
     /// # Errors
     ///
-    /// Returns a `StorageError` if the store does not support size() or there is an underlying error with the store.
+    /// Returns a `StorageError` if the store does not support `size()` or there is an underlying error with the store.
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError>;
 
     /// Return the size in bytes of the value at `key`.
@@ -78,7 +80,7 @@ pub trait AsyncReadableStorageTraits: Send + Sync {
     ///
     /// # Errors
     ///
-    /// Returns a `StorageError` if the store does not support size() or there is an underlying error with the store.
+    /// Returns a `StorageError` if the store does not support `size()` or there is an underlying error with the store.
     async fn size(&self) -> Result<u64, StorageError>;
 
     /// A utility method with the same input and output as [`get_partial_values`](AsyncReadableStorageTraits::get_partial_values) that internally calls [`get_partial_values_key`](AsyncReadableStorageTraits::get_partial_values_key) with byte ranges grouped by key.
@@ -108,7 +110,9 @@ pub trait AsyncReadableStorageTraits: Send + Sync {
                     .await?)
                     .map_or_else(
                         || vec![None; byte_ranges_key.len()],
-                        |partial_values| partial_values.into_iter().map(Some).collect(),
+                        |partial_values| {
+                            partial_values.into_iter().map(|v| Some(v.into())).collect()
+                        },
                     );
                 out.extend(bytes);
                 last_key = Some(&key_range.key);
@@ -125,7 +129,7 @@ pub trait AsyncReadableStorageTraits: Send + Sync {
                 .await?)
                 .map_or_else(
                     || vec![None; byte_ranges_key.len()],
-                    |partial_values| partial_values.into_iter().map(Some).collect(),
+                    |partial_values| partial_values.into_iter().map(|v| Some(v.into())).collect(),
                 );
             out.extend(bytes);
         }
@@ -188,7 +192,11 @@ pub async fn async_store_set_partial_values<T: AsyncReadableWritableStorageTrait
             // let _lock = mutex.lock().await;
 
             // Read the store key
-            let mut bytes = store.get(&key.clone()).await?.unwrap_or_else(Vec::default);
+            let mut bytes: Vec<u8> = store
+                .get(&key.clone())
+                .await?
+                .map(Vec::from)
+                .unwrap_or_default();
 
             // Expand the store key if needed
             let end_max =
@@ -233,6 +241,23 @@ pub trait AsyncWritableStorageTraits: Send + Sync {
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError>;
 
+    /// Store a list of [`StoreKey`] and value pairs.
+    ///
+    /// Stores which support batched writes should override the default implementation, which calls [`set`](AsyncWritableStorageTraits::set) for each key concurrently.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store.
+    async fn set_values(&self, key_values: Vec<(StoreKey, Bytes)>) -> Result<(), StorageError> {
+        let futures_set = key_values
+            .into_iter()
+            .map(|(key, value)| async move { self.set(&key, value).await });
+        futures::future::join_all(futures_set)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(())
+    }
+
     /// Erase a [`StoreKey`].
     ///
     /// Succeeds if the key does not exist.
@@ -259,6 +284,29 @@ pub trait AsyncWritableStorageTraits: Send + Sync {
     /// # Errors
     /// Returns a [`StorageError`] if there is an underlying storage error.
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError>;
+
+    /// Returns true if the store supports [`set_if_not_exists`](AsyncWritableStorageTraits::set_if_not_exists).
+    fn supports_set_if_not_exists(&self) -> bool {
+        false
+    }
+
+    /// Store bytes at a [`StoreKey`] only if it does not exist.
+    ///
+    /// Returns true if the value was stored, or false if the key already exists.
+    /// The check and the store are atomic with respect to other writers of the store.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store, or [`StorageError::Unsupported`] if the operation is not supported by the store.
+    async fn set_if_not_exists(
+        &self,
+        key: &StoreKey,
+        value: bytes::Bytes,
+    ) -> Result<bool, StorageError> {
+        let _ = (key, value);
+        Err(StorageError::Unsupported(
+            "set_if_not_exists is not supported by this store".to_string(),
+        ))
+    }
 }
 
 /// A supertrait of [`AsyncReadableStorageTraits`] and [`AsyncWritableStorageTraits`].
@@ -314,7 +362,7 @@ where
         let key = meta_key(&prefix.try_into()?);
         let child_metadata = match storage.get(&key).await? {
             Some(child_metadata) => {
-                let metadata: NodeMetadata = serde_json::from_slice(child_metadata.as_slice())
+                let metadata: NodeMetadata = serde_json::from_slice(&child_metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))?;
                 metadata
             }
@@ -340,7 +388,8 @@ pub async fn async_create_group(
     group: &GroupMetadata,
 ) -> Result<(), StorageError> {
     let key = meta_key(path);
-    let json = serde_json::to_vec_pretty(group)
+    let json = crate::storage::metadata_to_json(group)
+        .map(String::into_bytes)
         .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
     storage.set(&meta_key(path), json.into()).await?;
     Ok(())
@@ -356,16 +405,98 @@ pub async fn async_create_array(
     array: &ArrayMetadata,
 ) -> Result<(), StorageError> {
     let key = meta_key(path);
-    let json = serde_json::to_vec_pretty(array)
+    let json = crate::storage::metadata_to_json(array)
+        .map(String::into_bytes)
         .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
     storage.set(&meta_key(path), json.into()).await?;
     Ok(())
 }
 
+/// Asynchronously store node metadata with non-default [`MetadataWriteOptions`].
+///
+/// If verification is enabled, the metadata is read back after it is written and compared with the serialised document.
+/// Failed writes are retried with exponential backoff up to the maximum number of retries set in `options`.
+/// The retry delay is awaited with a runtime independent timer, so it does not block the executor.
+///
+/// # Errors
+/// Returns a [`MetadataWriteError`] if the metadata cannot be serialised, or the final write attempt fails with a storage error or a verification mismatch.
+pub async fn async_store_metadata_opt(
+    storage: &dyn AsyncReadableWritableStorageTraits,
+    path: &NodePath,
+    metadata: &NodeMetadata,
+    options: &MetadataWriteOptions,
+) -> Result<(), MetadataWriteError> {
+    let key = meta_key(path);
+    let existing = if options.preserve_format() || audit_enabled() {
+        storage.get(&key).await.ok().flatten()
+    } else {
+        None
+    };
+    let document = crate::storage::metadata_to_json_opt(metadata, existing.as_deref(), options)
+        .map_err(|err| MetadataWriteError::InvalidMetadata(key.clone(), err.to_string()))?;
+    if audit_enabled() {
+        if let Some(existing) = &existing {
+            audit_metadata_overwrite(&key, existing, metadata);
+        }
+    }
+    let mut retry_delay = options.retry_delay();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = match storage
+            .set(&key, Bytes::copy_from_slice(document.as_bytes()))
+            .await
+        {
+            Ok(()) if options.verify() => storage
+                .get(&key)
+                .await
+                .map(|value| value.as_deref() == Some(document.as_bytes())),
+            Ok(()) => Ok(true),
+            Err(err) => Err(err),
+        };
+        let can_retry = attempts <= options.max_retries();
+        match result {
+            Ok(true) => return Ok(()),
+            Ok(false) if !can_retry => {
+                return Err(MetadataWriteError::VerificationFailed {
+                    key,
+                    attempts,
+                    document,
+                })
+            }
+            Err(source) if !can_retry => {
+                return Err(MetadataWriteError::StorageError {
+                    key,
+                    attempts,
+                    document,
+                    source,
+                })
+            }
+            _ => {
+                if !retry_delay.is_zero() {
+                    Sleep::new(retry_delay).await;
+                }
+                retry_delay = retry_delay.saturating_mul(2);
+            }
+        }
+    }
+}
+
 /// Asynchronously store a chunk.
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+            size = chunk_serialised.len(),
+        )
+    )
+)]
 pub async fn async_store_chunk(
     storage: &dyn AsyncWritableStorageTraits,
     array_path: &NodePath,
@@ -386,19 +517,35 @@ pub async fn async_store_chunk(
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+            size = tracing::field::Empty,
+        )
+    )
+)]
 pub async fn async_retrieve_chunk(
     storage: &dyn AsyncReadableStorageTraits,
     array_path: &NodePath,
     chunk_grid_indices: &[u64],
     chunk_key_encoding: &ChunkKeyEncoding,
 ) -> Result<MaybeBytes, StorageError> {
-    storage
+    let chunk = storage
         .get(&data_key(
             array_path,
             chunk_grid_indices,
             chunk_key_encoding,
         ))
-        .await
+        .await?;
+    #[cfg(feature = "tracing")]
+    if let Some(chunk) = &chunk {
+        tracing::Span::current().record("size", chunk.len());
+    }
+    Ok(chunk)
 }
 
 /// Asynchronously erase metadata.
@@ -416,6 +563,16 @@ pub async fn async_erase_metadata(
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+        )
+    )
+)]
 pub async fn async_erase_chunk(
     storage: &dyn AsyncWritableStorageTraits,
     array_path: &NodePath,
@@ -496,9 +653,92 @@ pub async fn async_erase_node(
     path: &NodePath,
 ) -> Result<(), StorageError> {
     let prefix = path.try_into()?;
+    audit(&AuditEvent::ErasePrefix(&prefix));
     storage.erase_prefix(&prefix).await
 }
 
+/// Asynchronously copy a node (group or array) and all of its children from `src_storage` to `dst_storage`.
+///
+/// All keys below the node prefix (metadata and chunks) are copied to the same keys in `dst_storage`, overwriting any existing values.
+/// `progress` is called after each key is copied.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with either store.
+pub async fn async_copy_node<
+    TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits,
+>(
+    src_storage: &TStorage,
+    dst_storage: &dyn AsyncWritableStorageTraits,
+    path: &NodePath,
+    progress: impl FnMut(NodeTransferProgress) + Send,
+) -> Result<(), StorageError> {
+    let prefix: StorePrefix = path.try_into()?;
+    async_transfer_prefix(src_storage, dst_storage, &prefix, &prefix, progress).await
+}
+
+/// Asynchronously move a node (group or array) and all of its children from `src_path` to `dst_path`.
+///
+/// All keys below `src_path` (metadata and chunks) are copied below `dst_path`, overwriting any existing values, and then the node at `src_path` is erased.
+/// `progress` is called after each key is copied.
+///
+/// # Errors
+/// Returns a [`StorageError`] if `dst_path` is `src_path` or one of its descendants, or there is an underlying error with the store.
+pub async fn async_move_node<
+    TStorage: ?Sized + AsyncReadableStorageTraits + AsyncWritableStorageTraits + AsyncListableStorageTraits,
+>(
+    storage: &TStorage,
+    src_path: &NodePath,
+    dst_path: &NodePath,
+    progress: impl FnMut(NodeTransferProgress) + Send,
+) -> Result<(), StorageError> {
+    if dst_path.as_path().starts_with(src_path.as_path()) {
+        return Err(StorageError::Other(format!(
+            "cannot move node {src_path} to {dst_path}"
+        )));
+    }
+    let src_prefix: StorePrefix = src_path.try_into()?;
+    let dst_prefix: StorePrefix = dst_path.try_into()?;
+    async_transfer_prefix(storage, storage, &src_prefix, &dst_prefix, progress).await?;
+    audit(&AuditEvent::ErasePrefix(&src_prefix));
+    storage.erase_prefix(&src_prefix).await
+}
+
+/// Asynchronously copy all keys below `src_prefix` in `src_storage` to below `dst_prefix` in `dst_storage`.
+async fn async_transfer_prefix<
+    TStorage: ?Sized + AsyncReadableStorageTraits + AsyncListableStorageTraits,
+>(
+    src_storage: &TStorage,
+    dst_storage: &(impl ?Sized + AsyncWritableStorageTraits),
+    src_prefix: &StorePrefix,
+    dst_prefix: &StorePrefix,
+    mut progress: impl FnMut(NodeTransferProgress) + Send,
+) -> Result<(), StorageError> {
+    let keys = src_storage.list_prefix(src_prefix).await?;
+    let mut transfer_progress = NodeTransferProgress {
+        keys_transferred: 0,
+        keys_total: keys.len(),
+        bytes_transferred: 0,
+    };
+    for key in &keys {
+        if let Some(value) = src_storage.get(key).await? {
+            let suffix = key
+                .as_str()
+                .strip_prefix(src_prefix.as_str())
+                .unwrap_or(key.as_str());
+            transfer_progress.bytes_transferred += value.len() as u64;
+            dst_storage
+                .set(
+                    &StoreKey::new(dst_prefix.as_str().to_string() + suffix)?,
+                    value,
+                )
+                .await?;
+        }
+        transfer_progress.keys_transferred += 1;
+        progress(transfer_progress);
+    }
+    Ok(())
+}
+
 /// Asynchronously check if a node exists.
 ///
 /// # Errors
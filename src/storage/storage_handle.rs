@@ -3,8 +3,8 @@ use std::sync::Arc;
 use crate::{array::MaybeBytes, byte_range::ByteRange};
 
 use super::{
-    ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits, StorageError,
-    StoreKey, StorePrefix, WritableStorageTraits,
+    ETag, ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits,
+    StorageError, StoreKey, StoreKeyValue, StorePrefix, WritableStorageTraits,
 };
 
 #[cfg(feature = "async")]
@@ -57,6 +57,14 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits for Storage
     fn size_key(&self, key: &super::StoreKey) -> Result<Option<u64>, super::StorageError> {
         self.0.size_key(key)
     }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.0.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.0.supports_sizes()
+    }
 }
 
 impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits for StorageHandle<TStorage> {
@@ -77,6 +85,10 @@ impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits for Storage
     ) -> Result<super::StoreKeysPrefixes, super::StorageError> {
         self.0.list_dir(prefix)
     }
+
+    fn supports_listing(&self) -> bool {
+        self.0.supports_listing()
+    }
 }
 
 impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits for StorageHandle<TStorage> {
@@ -84,6 +96,10 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits for Storage
         self.0.set(key, value)
     }
 
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        self.0.set_values(key_values)
+    }
+
     fn set_partial_values(
         &self,
         key_start_values: &[super::StoreKeyStartValue],
@@ -102,6 +118,39 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits for Storage
     fn erase_prefix(&self, prefix: &super::StorePrefix) -> Result<(), super::StorageError> {
         self.0.erase_prefix(prefix)
     }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.0.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.0.supports_set_if_match()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        self.0.set_if_not_exists(key, value)
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        self.0.etag(key)
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        self.0.set_if_match(key, value, etag)
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.0.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.0.supports_atomic_writes()
+    }
 }
 
 impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
@@ -182,6 +231,13 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
         self.0.set(key, value).await
     }
 
+    async fn set_values(
+        &self,
+        key_values: Vec<(super::StoreKey, bytes::Bytes)>,
+    ) -> Result<(), super::StorageError> {
+        self.0.set_values(key_values).await
+    }
+
     async fn set_partial_values(
         &self,
         key_start_values: &[super::StoreKeyStartValue],
@@ -200,6 +256,18 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
     async fn erase_prefix(&self, prefix: &super::StorePrefix) -> Result<(), super::StorageError> {
         self.0.erase_prefix(prefix).await
     }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.0.supports_set_if_not_exists()
+    }
+
+    async fn set_if_not_exists(
+        &self,
+        key: &super::StoreKey,
+        value: bytes::Bytes,
+    ) -> Result<bool, super::StorageError> {
+        self.0.set_if_not_exists(key, value).await
+    }
 }
 
 #[cfg(feature = "async")]
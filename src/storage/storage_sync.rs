@@ -1,14 +1,17 @@
 use itertools::Itertools;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::{
     array::{ArrayMetadata, ChunkKeyEncoding, MaybeBytes},
+    audit::{audit, audit_enabled, AuditEvent},
     byte_range::ByteRange,
     group::{GroupMetadata, GroupMetadataV3},
     node::{Node, NodeMetadata, NodePath},
 };
 
 use super::{
-    data_key, meta_key, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeys,
+    data_key, meta_key, ETag, MetadataWriteError, MetadataWriteOptions, NodeTransferProgress,
+    StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeyValue, StoreKeys,
     StoreKeysPrefixes, StorePrefix, StorePrefixes,
 };
 
@@ -72,6 +75,18 @@ pub trait ReadableStorageTraits: Send + Sync {
         self.size_prefix(&StorePrefix::root())
     }
 
+    /// Returns true if the store reads byte ranges of a value without reading the whole value.
+    ///
+    /// If false, [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key) and [`get_partial_values`](ReadableStorageTraits::get_partial_values) are supported but may read the whole value for each request.
+    fn supports_partial_reads(&self) -> bool {
+        true
+    }
+
+    /// Returns true if the store supports [`size`](ReadableStorageTraits::size), [`size_prefix`](ReadableStorageTraits::size_prefix), and [`size_key`](ReadableStorageTraits::size_key).
+    fn supports_sizes(&self) -> bool {
+        true
+    }
+
     /// A utility method with the same input and output as [`get_partial_values`](ReadableStorageTraits::get_partial_values) that internally calls [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key) with byte ranges grouped by key.
     ///
     /// Readable storage can use this function in the implementation of [`get_partial_values`](ReadableStorageTraits::get_partial_values) if that is optimal.
@@ -96,7 +111,9 @@ pub trait ReadableStorageTraits: Send + Sync {
                 let bytes = (self.get_partial_values_key(last_key.unwrap(), &byte_ranges_key)?)
                     .map_or_else(
                         || vec![None; byte_ranges_key.len()],
-                        |partial_values| partial_values.into_iter().map(Some).collect(),
+                        |partial_values| {
+                            partial_values.into_iter().map(|v| Some(v.into())).collect()
+                        },
                     );
                 out.extend(bytes);
                 last_key = Some(&key_range.key);
@@ -111,7 +128,7 @@ pub trait ReadableStorageTraits: Send + Sync {
             let bytes = (self.get_partial_values_key(last_key.unwrap(), &byte_ranges_key)?)
                 .map_or_else(
                     || vec![None; byte_ranges_key.len()],
-                    |partial_values| partial_values.into_iter().map(Some).collect(),
+                    |partial_values| partial_values.into_iter().map(|v| Some(v.into())).collect(),
                 );
             out.extend(bytes);
         }
@@ -140,6 +157,13 @@ pub trait ListableStorageTraits: Send + Sync {
     /// Returns a [`StorageError`] if the prefix is not a directory or there is an underlying error with the store.
     ///
     fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError>;
+
+    /// Returns true if the store supports listing.
+    ///
+    /// Some stores implement [`ListableStorageTraits`] but can only list keys if supported by the underlying service.
+    fn supports_listing(&self) -> bool {
+        true
+    }
 }
 
 /// Set partial values for a store.
@@ -165,7 +189,7 @@ pub fn store_set_partial_values<T: ReadableWritableStorageTraits>(
             // let _lock = mutex.lock();
 
             // Read the store key
-            let mut bytes = store.get(&key)?.unwrap_or_default();
+            let mut bytes: Vec<u8> = store.get(&key)?.map(Vec::from).unwrap_or_default();
 
             // Expand the store key if needed
             let end_max =
@@ -204,6 +228,18 @@ pub trait WritableStorageTraits: Send + Sync {
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError>;
 
+    /// Store bytes according to a list of [`StoreKeyValue`].
+    ///
+    /// Stores which support batched writes (e.g. in a single transaction) should override the default implementation, which calls [`set`](WritableStorageTraits::set) for each key concurrently.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store.
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        key_values
+            .par_iter()
+            .try_for_each(|key_value| self.set(&key_value.key, key_value.value))
+    }
+
     /// Erase a [`StoreKey`].
     ///
     /// # Errors
@@ -224,6 +260,72 @@ pub trait WritableStorageTraits: Send + Sync {
     /// # Errors
     /// Returns a [`StorageError`] is the prefix is not in the store, or the erase otherwise fails.
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError>;
+
+    /// Returns true if the store writes byte ranges of a value without rewriting the whole value.
+    ///
+    /// If false, [`set_partial_values`](WritableStorageTraits::set_partial_values) may read, update, and rewrite the whole value.
+    fn supports_partial_writes(&self) -> bool {
+        false
+    }
+
+    /// Returns true if a value stored with [`set`](WritableStorageTraits::set) is never observed partially written.
+    fn supports_atomic_writes(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the store supports [`set_if_not_exists`](WritableStorageTraits::set_if_not_exists).
+    fn supports_set_if_not_exists(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the store supports [`etag`](WritableStorageTraits::etag) and [`set_if_match`](WritableStorageTraits::set_if_match).
+    fn supports_set_if_match(&self) -> bool {
+        false
+    }
+
+    /// Store bytes at a [`StoreKey`] only if it does not exist.
+    ///
+    /// Returns true if the value was stored, or false if the key already exists.
+    /// The check and the store are atomic with respect to other writers of the store.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store, or [`StorageError::Unsupported`] if the operation is not supported by the store.
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        let _ = (key, value);
+        Err(StorageError::Unsupported(
+            "set_if_not_exists is not supported by this store".to_string(),
+        ))
+    }
+
+    /// Return the [`ETag`] of the value at a [`StoreKey`], or [`None`] if the key does not exist.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if there is an underlying storage error, or [`StorageError::Unsupported`] if the operation is not supported by the store.
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        let _ = key;
+        Err(StorageError::Unsupported(
+            "etag is not supported by this store".to_string(),
+        ))
+    }
+
+    /// Store bytes at a [`StoreKey`] only if its current [`ETag`] is `etag`.
+    ///
+    /// Returns true if the value was stored, or false if the key does not exist or its value has changed.
+    /// The check and the store are atomic with respect to other writers of the store.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] on failure to store, or [`StorageError::Unsupported`] if the operation is not supported by the store.
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        let _ = (key, value, etag);
+        Err(StorageError::Unsupported(
+            "set_if_match is not supported by this store".to_string(),
+        ))
+    }
 }
 
 /// A supertrait of [`ReadableStorageTraits`] and [`WritableStorageTraits`].
@@ -265,7 +367,7 @@ pub fn get_child_nodes<TStorage: ?Sized + ReadableStorageTraits + ListableStorag
         let key = meta_key(&prefix.try_into()?);
         let child_metadata = match storage.get(&key)? {
             Some(child_metadata) => {
-                let metadata: NodeMetadata = serde_json::from_slice(child_metadata.as_slice())
+                let metadata: NodeMetadata = serde_json::from_slice(&child_metadata)
                     .map_err(|err| StorageError::InvalidMetadata(key, err.to_string()))?;
                 metadata
             }
@@ -291,7 +393,8 @@ pub fn create_group(
     group: &GroupMetadata,
 ) -> Result<(), StorageError> {
     let key = meta_key(path);
-    let json = serde_json::to_vec_pretty(group)
+    let json = crate::storage::metadata_to_json(group)
+        .map(String::into_bytes)
         .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
     storage.set(&meta_key(path), &json)?;
     Ok(())
@@ -307,16 +410,138 @@ pub fn create_array(
     array: &ArrayMetadata,
 ) -> Result<(), StorageError> {
     let key = meta_key(path);
-    let json = serde_json::to_vec_pretty(array)
+    let json = crate::storage::metadata_to_json(array)
+        .map(String::into_bytes)
         .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
     storage.set(&key, &json)?;
     Ok(())
 }
 
+/// Create node metadata if it does not already exist.
+///
+/// Returns true if the metadata was stored, or false if the node already has metadata.
+/// If the store [supports](WritableStorageTraits::supports_set_if_not_exists) [`set_if_not_exists`](WritableStorageTraits::set_if_not_exists), concurrent writers cannot overwrite each other's metadata.
+/// Otherwise, the metadata is stored if it does not exist when checked, and a concurrent writer could store metadata between the check and the write.
+///
+/// # Errors
+/// Returns a [`StorageError`] if the metadata cannot be serialised or there is an underlying error with the store.
+pub fn create_metadata_if_not_exists<
+    TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits,
+>(
+    storage: &TStorage,
+    path: &NodePath,
+    metadata: &NodeMetadata,
+) -> Result<bool, StorageError> {
+    let key = meta_key(path);
+    let json = crate::storage::metadata_to_json(metadata)
+        .map(String::into_bytes)
+        .map_err(|err| StorageError::InvalidMetadata(key.clone(), err.to_string()))?;
+    if storage.supports_set_if_not_exists() {
+        storage.set_if_not_exists(&key, &json)
+    } else if storage.get(&key)?.is_some() {
+        Ok(false)
+    } else {
+        storage.set(&key, &json)?;
+        Ok(true)
+    }
+}
+
+/// Store node metadata with non-default [`MetadataWriteOptions`].
+///
+/// If verification is enabled, the metadata is read back after it is written and compared with the serialised document.
+/// Failed writes are retried with exponential backoff up to the maximum number of retries set in `options`.
+///
+/// # Errors
+/// Returns a [`MetadataWriteError`] if the metadata cannot be serialised, or the final write attempt fails with a storage error or a verification mismatch.
+pub fn store_metadata_opt(
+    storage: &dyn ReadableWritableStorageTraits,
+    path: &NodePath,
+    metadata: &NodeMetadata,
+    options: &MetadataWriteOptions,
+) -> Result<(), MetadataWriteError> {
+    let key = meta_key(path);
+    let existing = if options.preserve_format() || audit_enabled() {
+        storage.get(&key).ok().flatten()
+    } else {
+        None
+    };
+    let document = crate::storage::metadata_to_json_opt(metadata, existing.as_deref(), options)
+        .map_err(|err| MetadataWriteError::InvalidMetadata(key.clone(), err.to_string()))?;
+    if audit_enabled() {
+        if let Some(existing) = &existing {
+            audit_metadata_overwrite(&key, existing, metadata);
+        }
+    }
+    let mut retry_delay = options.retry_delay();
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = storage.set(&key, document.as_bytes()).and_then(|()| {
+            if options.verify() {
+                Ok(storage.get(&key)?.as_deref() == Some(document.as_bytes()))
+            } else {
+                Ok(true)
+            }
+        });
+        let can_retry = attempts <= options.max_retries();
+        match result {
+            Ok(true) => return Ok(()),
+            Ok(false) if !can_retry => {
+                return Err(MetadataWriteError::VerificationFailed {
+                    key,
+                    attempts,
+                    document,
+                })
+            }
+            Err(source) if !can_retry => {
+                return Err(MetadataWriteError::StorageError {
+                    key,
+                    attempts,
+                    document,
+                    source,
+                })
+            }
+            _ => {
+                std::thread::sleep(retry_delay);
+                retry_delay = retry_delay.saturating_mul(2);
+            }
+        }
+    }
+}
+
+/// Audit the overwrite of `existing` metadata at `key` with `metadata`.
+pub(super) fn audit_metadata_overwrite(key: &StoreKey, existing: &[u8], metadata: &NodeMetadata) {
+    audit(&AuditEvent::MetadataOverwrite(key));
+    if let (
+        Ok(NodeMetadata::Array(ArrayMetadata::V3(existing))),
+        NodeMetadata::Array(ArrayMetadata::V3(metadata)),
+    ) = (serde_json::from_slice::<NodeMetadata>(existing), metadata)
+    {
+        if existing.fill_value != metadata.fill_value {
+            audit(&AuditEvent::FillValueChange {
+                key,
+                old: &existing.fill_value,
+                new: &metadata.fill_value,
+            });
+        }
+    }
+}
+
 /// Store a chunk.
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+            size = chunk_serialised.len(),
+        )
+    )
+)]
 pub fn store_chunk(
     storage: &dyn WritableStorageTraits,
     array_path: &NodePath,
@@ -335,17 +560,33 @@ pub fn store_chunk(
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+            size = tracing::field::Empty,
+        )
+    )
+)]
 pub fn retrieve_chunk(
     storage: &dyn ReadableStorageTraits,
     array_path: &NodePath,
     chunk_grid_indices: &[u64],
     chunk_key_encoding: &ChunkKeyEncoding,
 ) -> Result<MaybeBytes, StorageError> {
-    storage.get(&data_key(
+    let chunk = storage.get(&data_key(
         array_path,
         chunk_grid_indices,
         chunk_key_encoding,
-    ))
+    ))?;
+    #[cfg(feature = "tracing")]
+    if let Some(chunk) = &chunk {
+        tracing::Span::current().record("size", chunk.len());
+    }
+    Ok(chunk)
 }
 
 /// Erase a chunk.
@@ -354,6 +595,16 @@ pub fn retrieve_chunk(
 ///
 /// # Errors
 /// Returns a [`StorageError`] if there is an underlying error with the store.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            key = %data_key(array_path, chunk_grid_indices, chunk_key_encoding),
+        )
+    )
+)]
 pub fn erase_chunk(
     storage: &dyn WritableStorageTraits,
     array_path: &NodePath,
@@ -440,9 +691,86 @@ pub fn erase_node(
     path: &NodePath,
 ) -> Result<(), StorageError> {
     let prefix = path.try_into()?;
+    audit(&AuditEvent::ErasePrefix(&prefix));
     storage.erase_prefix(&prefix)
 }
 
+/// Copy a node (group or array) and all of its children from `src_storage` to `dst_storage`.
+///
+/// All keys below the node prefix (metadata and chunks) are copied to the same keys in `dst_storage`, overwriting any existing values.
+/// `progress` is called after each key is copied.
+///
+/// # Errors
+/// Returns a [`StorageError`] if there is an underlying error with either store.
+pub fn copy_node<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    src_storage: &TStorage,
+    dst_storage: &dyn WritableStorageTraits,
+    path: &NodePath,
+    progress: impl FnMut(NodeTransferProgress),
+) -> Result<(), StorageError> {
+    let prefix: StorePrefix = path.try_into()?;
+    transfer_prefix(src_storage, dst_storage, &prefix, &prefix, progress)
+}
+
+/// Move a node (group or array) and all of its children from `src_path` to `dst_path`.
+///
+/// All keys below `src_path` (metadata and chunks) are copied below `dst_path`, overwriting any existing values, and then the node at `src_path` is erased.
+/// `progress` is called after each key is copied.
+///
+/// # Errors
+/// Returns a [`StorageError`] if `dst_path` is `src_path` or one of its descendants, or there is an underlying error with the store.
+pub fn move_node<
+    TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits + ListableStorageTraits,
+>(
+    storage: &TStorage,
+    src_path: &NodePath,
+    dst_path: &NodePath,
+    progress: impl FnMut(NodeTransferProgress),
+) -> Result<(), StorageError> {
+    if dst_path.as_path().starts_with(src_path.as_path()) {
+        return Err(StorageError::Other(format!(
+            "cannot move node {src_path} to {dst_path}"
+        )));
+    }
+    let src_prefix: StorePrefix = src_path.try_into()?;
+    let dst_prefix: StorePrefix = dst_path.try_into()?;
+    transfer_prefix(storage, storage, &src_prefix, &dst_prefix, progress)?;
+    audit(&AuditEvent::ErasePrefix(&src_prefix));
+    storage.erase_prefix(&src_prefix)
+}
+
+/// Copy all keys below `src_prefix` in `src_storage` to below `dst_prefix` in `dst_storage`.
+fn transfer_prefix<TStorage: ?Sized + ReadableStorageTraits + ListableStorageTraits>(
+    src_storage: &TStorage,
+    dst_storage: &(impl ?Sized + WritableStorageTraits),
+    src_prefix: &StorePrefix,
+    dst_prefix: &StorePrefix,
+    mut progress: impl FnMut(NodeTransferProgress),
+) -> Result<(), StorageError> {
+    let keys = src_storage.list_prefix(src_prefix)?;
+    let mut transfer_progress = NodeTransferProgress {
+        keys_transferred: 0,
+        keys_total: keys.len(),
+        bytes_transferred: 0,
+    };
+    for key in &keys {
+        if let Some(value) = src_storage.get(key)? {
+            let suffix = key
+                .as_str()
+                .strip_prefix(src_prefix.as_str())
+                .unwrap_or(key.as_str());
+            dst_storage.set(
+                &StoreKey::new(dst_prefix.as_str().to_string() + suffix)?,
+                &value,
+            )?;
+            transfer_progress.bytes_transferred += value.len() as u64;
+        }
+        transfer_progress.keys_transferred += 1;
+        progress(transfer_progress);
+    }
+    Ok(())
+}
+
 /// Check if a node exists.
 ///
 /// # Errors
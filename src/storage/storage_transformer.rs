@@ -1,14 +1,36 @@
-//! Zarr storage transformers. Includes [performance metrics](performance_metrics::PerformanceMetricsStorageTransformer) and [usage log](usage_log::UsageLogStorageTransformer) implementations for internal use.
+//! Zarr storage transformers. Includes [cache](cache::CacheStorageTransformer), [performance metrics](performance_metrics::PerformanceMetricsStorageTransformer), and [usage log](usage_log::UsageLogStorageTransformer) implementations for internal use.
+//!
+//! Also includes a [buffered write](buffered_write::BufferedWriteStorageTransformer) storage wrapper which batches writes to the underlying storage,
+//! a [retry](retry::RetryStorageTransformer) storage transformer which retries transient failures with exponential backoff,
+//! and a [throttle](throttle::ThrottleStorageTransformer) storage transformer which limits the request and byte rates to the underlying storage.
+//!
+//! Third-party storage transformers named in array metadata are supported by implementing [`StorageTransformerExtension`] and registering a [`StorageTransformerPlugin`] with [`inventory::submit!`].
+//! [`StorageTransformerChain::from_metadata`] then creates them from the `storage_transformers` of array metadata, and [`StorageTransformerExtension::create_metadata`] writes them back.
 //!
 //! See <https://zarr-specs.readthedocs.io/en/latest/v3/core/v3.0.html#id23>.
 
+mod buffered_write;
+mod cache;
 mod performance_metrics;
+mod retry;
+#[cfg(feature = "async")]
+pub(crate) mod sleep;
 mod storage_transformer_chain;
+mod throttle;
 mod usage_log;
 
-pub use performance_metrics::PerformanceMetricsStorageTransformer;
+#[cfg(feature = "async")]
+pub use buffered_write::AsyncBufferedWriteStorageTransformer;
+pub use buffered_write::BufferedWriteStorageTransformer;
+pub use cache::CacheStorageTransformer;
+pub use performance_metrics::{
+    PerformanceMetricsLatency, PerformanceMetricsOperation, PerformanceMetricsReport,
+    PerformanceMetricsStorageTransformer, PerformanceMetricsSummary,
+};
+pub use retry::{is_transient_storage_error, RetryPredicate, RetryStorageTransformer};
 pub use storage_transformer_chain::StorageTransformerChain;
-pub use usage_log::UsageLogStorageTransformer;
+pub use throttle::ThrottleStorageTransformer;
+pub use usage_log::{UsageLogOperation, UsageLogRecord, UsageLogStorageTransformer};
 
 use std::sync::Arc;
 
@@ -32,7 +54,9 @@ use super::{
 pub type StorageTransformer = Arc<dyn StorageTransformerExtension>;
 
 /// A storage transformer plugin.
-type StorageTransformerPlugin = Plugin<StorageTransformer>;
+///
+/// Register a plugin with [`inventory::submit!`] so that [`StorageTransformerChain::from_metadata`] can create the storage transformer from metadata.
+pub type StorageTransformerPlugin = Plugin<StorageTransformer>;
 inventory::collect!(StorageTransformerPlugin);
 
 /// Create a storage transformer from metadata.
@@ -55,8 +79,12 @@ pub fn try_create_storage_transformer(
 }
 
 /// A storage transformer extension.
+///
+/// Implement this trait and register a [`StorageTransformerPlugin`] to add a storage transformer that can be created from array metadata.
 pub trait StorageTransformerExtension: core::fmt::Debug + Send + Sync {
     /// Create metadata.
+    ///
+    /// Returns [`None`] if the storage transformer is not written to array metadata.
     fn create_metadata(&self) -> Option<Metadata>;
 
     /// Create a readable transformer.
@@ -121,3 +149,183 @@ pub trait StorageTransformerExtension: core::fmt::Debug + Send + Sync {
         storage: AsyncReadableWritableListableStorage,
     ) -> AsyncReadableWritableListableStorage;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        array::{Array, ArrayMetadata},
+        storage::{store::MemoryStore, StoreKey, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    /// A storage transformer that does not transform the storage.
+    #[derive(Debug)]
+    struct PassthroughStorageTransformer(Metadata);
+
+    impl StorageTransformerExtension for PassthroughStorageTransformer {
+        fn create_metadata(&self) -> Option<Metadata> {
+            Some(self.0.clone())
+        }
+
+        fn create_readable_transformer(
+            self: Arc<Self>,
+            storage: ReadableStorage,
+        ) -> ReadableStorage {
+            storage
+        }
+
+        fn create_writable_transformer(
+            self: Arc<Self>,
+            storage: WritableStorage,
+        ) -> WritableStorage {
+            storage
+        }
+
+        fn create_readable_writable_transformer(
+            self: Arc<Self>,
+            storage: ReadableWritableStorage,
+        ) -> ReadableWritableStorage {
+            storage
+        }
+
+        fn create_listable_transformer(
+            self: Arc<Self>,
+            storage: ListableStorage,
+        ) -> ListableStorage {
+            storage
+        }
+
+        fn create_readable_listable_transformer(
+            self: Arc<Self>,
+            storage: ReadableListableStorage,
+        ) -> ReadableListableStorage {
+            storage
+        }
+
+        fn create_readable_writable_listable_transformer(
+            self: Arc<Self>,
+            storage: ReadableWritableListableStorage,
+        ) -> ReadableWritableListableStorage {
+            storage
+        }
+
+        #[cfg(feature = "async")]
+        fn create_async_readable_transformer(
+            self: Arc<Self>,
+            storage: AsyncReadableStorage,
+        ) -> AsyncReadableStorage {
+            storage
+        }
+
+        #[cfg(feature = "async")]
+        fn create_async_writable_transformer(
+            self: Arc<Self>,
+            storage: AsyncWritableStorage,
+        ) -> AsyncWritableStorage {
+            storage
+        }
+
+        #[cfg(feature = "async")]
+        fn create_async_listable_transformer(
+            self: Arc<Self>,
+            storage: AsyncListableStorage,
+        ) -> AsyncListableStorage {
+            storage
+        }
+
+        #[cfg(feature = "async")]
+        fn create_async_readable_listable_transformer(
+            self: Arc<Self>,
+            storage: AsyncReadableListableStorage,
+        ) -> AsyncReadableListableStorage {
+            storage
+        }
+
+        #[cfg(feature = "async")]
+        fn create_async_readable_writable_listable_transformer(
+            self: Arc<Self>,
+            storage: AsyncReadableWritableListableStorage,
+        ) -> AsyncReadableWritableListableStorage {
+            storage
+        }
+    }
+
+    fn is_name_passthrough(name: &str) -> bool {
+        name == "zarrs.test.passthrough"
+    }
+
+    fn create_storage_transformer_passthrough(
+        metadata: &Metadata,
+    ) -> Result<StorageTransformer, PluginCreateError> {
+        if metadata.configuration().is_some() {
+            Ok(Arc::new(PassthroughStorageTransformer(metadata.clone())))
+        } else {
+            Err(crate::plugin::PluginMetadataInvalidError::new(
+                "zarrs.test.passthrough",
+                "storage transformer",
+                metadata.clone(),
+            )
+            .into())
+        }
+    }
+
+    inventory::submit! {
+        StorageTransformerPlugin::new("zarrs.test.passthrough", is_name_passthrough, create_storage_transformer_passthrough)
+    }
+
+    #[test]
+    fn storage_transformer_plugin() {
+        let metadatas: Vec<Metadata> = serde_json::from_str(
+            r#"[{"name":"zarrs.test.passthrough","configuration":{"tag":"a"}}]"#,
+        )
+        .unwrap();
+        let chain = StorageTransformerChain::from_metadata(&metadatas).unwrap();
+        assert_eq!(chain.create_metadatas(), metadatas);
+
+        let unknown: Vec<Metadata> = serde_json::from_str(r#"[{"name":"unknown"}]"#).unwrap();
+        assert_eq!(
+            StorageTransformerChain::from_metadata(&unknown)
+                .unwrap_err()
+                .to_string(),
+            "storage transformer unknown is not supported"
+        );
+    }
+
+    #[test]
+    fn storage_transformer_plugin_array() {
+        let json = r#"{
+    "zarr_format": 3,
+    "node_type": "array",
+    "shape": [4],
+    "data_type": "uint8",
+    "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": [2]}},
+    "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+    "fill_value": 0,
+    "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+    "storage_transformers": [{"name": "zarrs.test.passthrough", "configuration": {"tag": "a"}}]
+}"#;
+        let store = Arc::new(MemoryStore::new());
+        store
+            .set(&StoreKey::new("array/zarr.json").unwrap(), json.as_bytes())
+            .unwrap();
+        let array = Array::new(store.clone(), "/array").unwrap();
+        array.store_chunk_elements::<u8>(&[1], vec![1, 2]).unwrap();
+        assert_eq!(
+            array
+                .retrieve_array_subset_elements::<u8>(
+                    &crate::array_subset::ArraySubset::new_with_shape(vec![4])
+                )
+                .unwrap(),
+            vec![0, 0, 1, 2]
+        );
+        let ArrayMetadata::V3(metadata) = array.metadata();
+        assert_eq!(
+            metadata.storage_transformers,
+            serde_json::from_str::<Vec<Metadata>>(
+                r#"[{"name":"zarrs.test.passthrough","configuration":{"tag":"a"}}]"#
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,750 @@
+//! A storage wrapper which buffers writes in memory.
+
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{extract_byte_ranges, ByteRange},
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits, StorageError,
+        StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeyValue, StoreKeys, StoreKeysPrefixes,
+        StorePrefix, WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorageTraits, AsyncReadableStorageTraits, AsyncReadableWritableStorageTraits,
+    AsyncWritableStorageTraits,
+};
+
+/// Values written to a buffered write storage transformer which have not yet reached the underlying storage.
+#[derive(Debug, Default)]
+struct WriteBuffer {
+    /// Values waiting to be flushed.
+    pending: BTreeMap<StoreKey, Vec<u8>>,
+    /// Values being flushed.
+    flushing: Arc<BTreeMap<StoreKey, Vec<u8>>>,
+    /// The number of bytes in `pending`.
+    size: usize,
+    /// The time of the oldest write in `pending`.
+    oldest: Option<Instant>,
+}
+
+impl WriteBuffer {
+    fn get(&self, key: &StoreKey) -> Option<&Vec<u8>> {
+        self.pending.get(key).or_else(|| self.flushing.get(key))
+    }
+
+    fn set(&mut self, key: StoreKey, value: Vec<u8>) {
+        self.size += value.len();
+        if let Some(value_old) = self.pending.insert(key, value) {
+            self.size -= value_old.len();
+        }
+        self.oldest.get_or_insert_with(Instant::now);
+    }
+
+    /// Apply a partial write to a pending value. Returns false if the key is not pending.
+    fn set_partial(&mut self, key_start_value: &StoreKeyStartValue) -> bool {
+        let Some(value) = self.pending.get_mut(&key_start_value.key) else {
+            return false;
+        };
+        let start = usize::try_from(key_start_value.start).unwrap();
+        let end = usize::try_from(key_start_value.end()).unwrap();
+        if value.len() < end {
+            self.size += end - value.len();
+            value.resize(end, 0);
+        }
+        value[start..end].copy_from_slice(key_start_value.value);
+        true
+    }
+
+    fn remove(&mut self, key: &StoreKey) {
+        if let Some(value) = self.pending.remove(key) {
+            self.size -= value.len();
+        }
+    }
+
+    fn remove_prefix(&mut self, prefix: &StorePrefix) {
+        let removed = self
+            .pending
+            .keys()
+            .filter(|key| key.has_prefix(prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in &removed {
+            self.remove(key);
+        }
+    }
+
+    /// Move the pending values to a new flushing batch.
+    fn take(&mut self) -> Arc<BTreeMap<StoreKey, Vec<u8>>> {
+        self.flushing = Arc::new(std::mem::take(&mut self.pending));
+        self.size = 0;
+        self.oldest = None;
+        self.flushing.clone()
+    }
+
+    /// Complete a flush, keeping `failed` values unless they have since been overwritten.
+    fn finish(&mut self, failed: Vec<StoreKey>) {
+        let flushing = std::mem::take(&mut self.flushing);
+        for key in failed {
+            if !self.pending.contains_key(&key) {
+                if let Some(value) = flushing.get(&key) {
+                    self.set(key, value.clone());
+                }
+            }
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &StoreKey> {
+        self.pending.keys().chain(self.flushing.keys())
+    }
+
+    /// Add the buffered keys under `prefix` to `keys`.
+    fn merge_keys(&self, mut keys: StoreKeys, prefix: &StorePrefix) -> StoreKeys {
+        keys.extend(self.keys().filter(|key| key.has_prefix(prefix)).cloned());
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Add the buffered keys and prefixes directly under `prefix` to `list_dir`.
+    fn merge_keys_prefixes(
+        &self,
+        list_dir: StoreKeysPrefixes,
+        prefix: &StorePrefix,
+    ) -> Result<StoreKeysPrefixes, StorageError> {
+        let StoreKeysPrefixes {
+            mut keys,
+            mut prefixes,
+        } = list_dir;
+        for key in self.keys().filter(|key| key.has_prefix(prefix)) {
+            let child = &key.as_str()[prefix.as_str().len()..];
+            if let Some((child_prefix, _)) = child.split_once('/') {
+                prefixes.push(StorePrefix::new(format!(
+                    "{}{child_prefix}/",
+                    prefix.as_str()
+                ))?);
+            } else {
+                keys.push(key.clone());
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        prefixes.sort();
+        prefixes.dedup();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+/// Flush thresholds of a buffered write storage transformer.
+#[derive(Debug, Clone, Copy)]
+struct FlushThresholds {
+    max_buffered_bytes: usize,
+    flush_interval: Option<Duration>,
+}
+
+impl FlushThresholds {
+    fn exceeded(&self, buffer: &WriteBuffer) -> bool {
+        buffer.size >= self.max_buffered_bytes
+            || self
+                .flush_interval
+                .zip(buffer.oldest)
+                .is_some_and(|(flush_interval, oldest)| oldest.elapsed() >= flush_interval)
+    }
+}
+
+/// The buffered write storage transformer. Buffers writes in memory and writes them to the underlying storage in batches.
+///
+/// This wraps a store directly, rather than being part of a [`StorageTransformerChain`](super::StorageTransformerChain), because buffered values must be flushed explicitly.
+/// It is intended to reduce the number of requests made to stores which charge per request (e.g. object stores) when many small writes are made.
+///
+/// Repeated writes to a key are coalesced into a single write, and buffered values are written concurrently when flushed.
+/// Values are flushed when the buffered bytes reach a threshold, when the oldest buffered write exceeds the flush interval (checked on each write), or on [`flush`](BufferedWriteStorageTransformer::flush).
+/// Reads and listing observe buffered values.
+///
+/// <div class="warning">
+/// Buffered values are not flushed on drop. Call <code>flush</code> before dropping the storage, otherwise buffered writes are lost.
+/// </div>
+///
+/// Partial writes and erasures are applied to buffered values and then passed through to the underlying storage immediately.
+/// Sizes reported by the underlying storage do not include buffered values, except for [`size_key`](ReadableStorageTraits::size_key).
+#[derive(Debug)]
+pub struct BufferedWriteStorageTransformer<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    thresholds: FlushThresholds,
+    buffer: Mutex<WriteBuffer>,
+    flush_lock: Mutex<()>,
+}
+
+impl<TStorage: ?Sized> BufferedWriteStorageTransformer<TStorage> {
+    /// Create a new buffered write storage transformer which flushes when `max_buffered_bytes` are buffered.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>, max_buffered_bytes: usize) -> Self {
+        Self {
+            storage,
+            thresholds: FlushThresholds {
+                max_buffered_bytes,
+                flush_interval: None,
+            },
+            buffer: Mutex::new(WriteBuffer::default()),
+            flush_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create a new buffered write storage transformer which flushes when `max_buffered_bytes` are buffered or the oldest buffered write is older than `flush_interval`.
+    #[must_use]
+    pub fn new_with_flush_interval(
+        storage: Arc<TStorage>,
+        max_buffered_bytes: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let mut transformer = Self::new(storage, max_buffered_bytes);
+        transformer.thresholds.flush_interval = Some(flush_interval);
+        transformer
+    }
+
+    /// Returns the number of bytes waiting to be flushed.
+    #[must_use]
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.lock().size
+    }
+
+    /// Returns the number of keys waiting to be flushed.
+    #[must_use]
+    pub fn buffered_keys(&self) -> usize {
+        self.buffer.lock().pending.len()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> BufferedWriteStorageTransformer<TStorage> {
+    /// Write all buffered values to the underlying storage.
+    ///
+    /// Values which fail to be written remain buffered.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if any value fails to be written.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock();
+        self.flush_locked()
+    }
+
+    fn flush_locked(&self) -> Result<(), StorageError> {
+        let batch = self.buffer.lock().take();
+        let concurrency = std::thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(batch.len());
+        let failed = Mutex::new(Vec::new());
+        let write = |(key, value): (&StoreKey, &Vec<u8>)| {
+            if let Err(err) = self.storage.set(key, value) {
+                failed.lock().push((key.clone(), err));
+            }
+        };
+        if concurrency <= 1 {
+            batch.iter().for_each(write);
+        } else {
+            // Plain threads rather than rayon, so a flush never runs other work while holding the flush lock
+            let entries = Mutex::new(batch.iter());
+            std::thread::scope(|scope| {
+                for _ in 0..concurrency {
+                    scope.spawn(|| loop {
+                        let entry = entries.lock().next();
+                        let Some(entry) = entry else {
+                            break;
+                        };
+                        write(entry);
+                    });
+                }
+            });
+        }
+
+        let (failed, errors): (Vec<_>, Vec<_>) = failed.into_inner().into_iter().unzip();
+        self.buffer.lock().finish(failed);
+        errors.into_iter().next().map_or(Ok(()), Err)
+    }
+
+    fn flush_if_exceeded(&self) -> Result<(), StorageError> {
+        if self.thresholds.exceeded(&self.buffer.lock()) {
+            // Skip if another flush is in progress, the values will be flushed later
+            if let Some(_flush_lock) = self.flush_lock.try_lock() {
+                return self.flush_locked();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for BufferedWriteStorageTransformer<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        if let Some(value) = self.buffer.lock().get(key).cloned() {
+            return Ok(Some(value.into()));
+        }
+        self.storage.get(key)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        if let Some(value) = self.buffer.lock().get(key) {
+            return Ok(Some(extract_byte_ranges(value, byte_ranges)?));
+        }
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        if let Some(value) = self.buffer.lock().get(key) {
+            return Ok(Some(value.len() as u64));
+        }
+        self.storage.size_key(key)
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for BufferedWriteStorageTransformer<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let keys = self.storage.list_prefix(prefix)?;
+        Ok(self.buffer.lock().merge_keys(keys, prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let list_dir = self.storage.list_dir(prefix)?;
+        self.buffer.lock().merge_keys_prefixes(list_dir, prefix)
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for BufferedWriteStorageTransformer<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.buffer.lock().set(key.clone(), value.to_vec());
+        self.flush_if_exceeded()
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        {
+            let mut buffer = self.buffer.lock();
+            for key_value in key_values {
+                buffer.set(key_value.key().clone(), key_value.value().to_vec());
+            }
+        }
+        self.flush_if_exceeded()
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock();
+        let mut buffer = self.buffer.lock();
+        let key_start_values = key_start_values
+            .iter()
+            .filter(|key_start_value| !buffer.set_partial(key_start_value))
+            .cloned()
+            .collect::<Vec<_>>();
+        drop(buffer);
+        if key_start_values.is_empty() {
+            Ok(())
+        } else {
+            self.storage.set_partial_values(&key_start_values)
+        }
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock();
+        self.buffer.lock().remove(key);
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock();
+        {
+            let mut buffer = self.buffer.lock();
+            for key in keys {
+                buffer.remove(key);
+            }
+        }
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock();
+        self.buffer.lock().remove_prefix(prefix);
+        self.storage.erase_prefix(prefix)
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for BufferedWriteStorageTransformer<TStorage>
+{
+}
+
+/// The async buffered write storage transformer. Buffers writes in memory and writes them to the underlying storage in batches.
+///
+/// This is the async counterpart of [`BufferedWriteStorageTransformer`], refer to it for details.
+///
+/// <div class="warning">
+/// Buffered values are not flushed on drop. Call <code>flush</code> before dropping the storage, otherwise buffered writes are lost.
+/// </div>
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncBufferedWriteStorageTransformer<TStorage: ?Sized> {
+    storage: Arc<TStorage>,
+    thresholds: FlushThresholds,
+    buffer: Mutex<WriteBuffer>,
+    flush_lock: async_lock::Mutex<()>,
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized> AsyncBufferedWriteStorageTransformer<TStorage> {
+    /// Create a new async buffered write storage transformer which flushes when `max_buffered_bytes` are buffered.
+    #[must_use]
+    pub fn new(storage: Arc<TStorage>, max_buffered_bytes: usize) -> Self {
+        Self {
+            storage,
+            thresholds: FlushThresholds {
+                max_buffered_bytes,
+                flush_interval: None,
+            },
+            buffer: Mutex::new(WriteBuffer::default()),
+            flush_lock: async_lock::Mutex::new(()),
+        }
+    }
+
+    /// Create a new async buffered write storage transformer which flushes when `max_buffered_bytes` are buffered or the oldest buffered write is older than `flush_interval`.
+    #[must_use]
+    pub fn new_with_flush_interval(
+        storage: Arc<TStorage>,
+        max_buffered_bytes: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        let mut transformer = Self::new(storage, max_buffered_bytes);
+        transformer.thresholds.flush_interval = Some(flush_interval);
+        transformer
+    }
+
+    /// Returns the number of bytes waiting to be flushed.
+    #[must_use]
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.lock().size
+    }
+
+    /// Returns the number of keys waiting to be flushed.
+    #[must_use]
+    pub fn buffered_keys(&self) -> usize {
+        self.buffer.lock().pending.len()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncBufferedWriteStorageTransformer<TStorage> {
+    /// Write all buffered values to the underlying storage.
+    ///
+    /// Values which fail to be written remain buffered.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if any value fails to be written.
+    pub async fn flush(&self) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock().await;
+        self.flush_locked().await
+    }
+
+    async fn flush_locked(&self) -> Result<(), StorageError> {
+        use futures::StreamExt;
+
+        let batch = self.buffer.lock().take();
+        let concurrency = batch.len().max(1);
+        let entries = batch
+            .iter()
+            .map(|(key, value)| (key.clone(), bytes::Bytes::copy_from_slice(value)))
+            .collect::<Vec<_>>();
+        let futures = entries.into_iter().map(|(key, value)| async move {
+            let result = self.storage.set(&key, value).await;
+            (key, result)
+        });
+        let mut stream = futures::stream::iter(futures).buffer_unordered(concurrency);
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        while let Some((key, result)) = stream.next().await {
+            if let Err(err) = result {
+                failed.push(key);
+                errors.push(err);
+            }
+        }
+        drop(stream);
+
+        self.buffer.lock().finish(failed);
+        errors.into_iter().next().map_or(Ok(()), Err)
+    }
+
+    async fn flush_if_exceeded(&self) -> Result<(), StorageError> {
+        let exceeded = self.thresholds.exceeded(&self.buffer.lock());
+        if exceeded {
+            // Skip if another flush is in progress, the values will be flushed later
+            if let Some(_flush_lock) = self.flush_lock.try_lock() {
+                return self.flush_locked().await;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for AsyncBufferedWriteStorageTransformer<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let value = self.buffer.lock().get(key).cloned();
+        if let Some(value) = value {
+            return Ok(Some(value.into()));
+        }
+        self.storage.get(key).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let values = self
+            .buffer
+            .lock()
+            .get(key)
+            .map(|value| extract_byte_ranges(value, byte_ranges));
+        if let Some(values) = values {
+            return Ok(Some(values?));
+        }
+        self.storage.get_partial_values_key(key, byte_ranges).await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let size = self.buffer.lock().get(key).map(|value| value.len() as u64);
+        if let Some(size) = size {
+            return Ok(Some(size));
+        }
+        self.storage.size_key(key).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for AsyncBufferedWriteStorageTransformer<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root()).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let keys = self.storage.list_prefix(prefix).await?;
+        Ok(self.buffer.lock().merge_keys(keys, prefix))
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let list_dir = self.storage.list_dir(prefix).await?;
+        self.buffer.lock().merge_keys_prefixes(list_dir, prefix)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for AsyncBufferedWriteStorageTransformer<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        self.buffer.lock().set(key.clone(), value.to_vec());
+        self.flush_if_exceeded().await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock().await;
+        let key_start_values = {
+            let mut buffer = self.buffer.lock();
+            key_start_values
+                .iter()
+                .filter(|key_start_value| !buffer.set_partial(key_start_value))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        if key_start_values.is_empty() {
+            Ok(())
+        } else {
+            self.storage.set_partial_values(&key_start_values).await
+        }
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock().await;
+        self.buffer.lock().remove(key);
+        self.storage.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock().await;
+        {
+            let mut buffer = self.buffer.lock();
+            for key in keys {
+                buffer.remove(key);
+            }
+        }
+        self.storage.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let _flush_lock = self.flush_lock.lock().await;
+        self.buffer.lock().remove_prefix(prefix);
+        self.storage.erase_prefix(prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
+    for AsyncBufferedWriteStorageTransformer<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{
+        storage_transformer::{PerformanceMetricsStorageTransformer, StorageTransformerExtension},
+        store::MemoryStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn buffered_write_storage_transformer() {
+        let metrics = Arc::new(PerformanceMetricsStorageTransformer::new());
+        let store = metrics
+            .clone()
+            .create_readable_writable_listable_transformer(Arc::new(MemoryStore::new()));
+        let storage = BufferedWriteStorageTransformer::new(store.clone(), 16);
+        let key_a = StoreKey::new("a/0").unwrap();
+        let key_b = StoreKey::new("a/1").unwrap();
+        let key_c = StoreKey::new("b").unwrap();
+
+        // Repeated writes are coalesced and visible before they are flushed
+        storage.set(&key_a, &[0; 4]).unwrap();
+        storage.set(&key_a, &[1; 4]).unwrap();
+        storage.set(&key_b, &[2; 4]).unwrap();
+        storage
+            .set_partial_values(&[StoreKeyStartValue::new(key_b.clone(), 2, &[3; 4])])
+            .unwrap();
+        assert_eq!((storage.buffered_keys(), storage.buffered_bytes()), (2, 10));
+        assert_eq!(metrics.writes(), 0);
+        assert_eq!(storage.get(&key_a).unwrap(), Some(vec![1; 4].into()));
+        assert_eq!(
+            storage.get(&key_b).unwrap(),
+            Some(vec![2, 2, 3, 3, 3, 3].into())
+        );
+        assert_eq!(storage.size_key(&key_b).unwrap(), Some(6));
+        assert_eq!(storage.list().unwrap(), vec![key_a.clone(), key_b.clone()]);
+        let list_dir = storage.list_dir(&StorePrefix::root()).unwrap();
+        assert!(list_dir.keys().is_empty());
+        assert_eq!(list_dir.prefixes(), &[StorePrefix::new("a/").unwrap()]);
+        assert!(store.get(&key_a).unwrap().is_none());
+
+        storage.flush().unwrap();
+        assert_eq!((storage.buffered_keys(), storage.buffered_bytes()), (0, 0));
+        assert_eq!(metrics.writes(), 2);
+        assert_eq!(store.get(&key_a).unwrap(), Some(vec![1; 4].into()));
+
+        // Writes are flushed once the buffered bytes reach the threshold
+        storage.set(&key_c, &[4; 8]).unwrap();
+        assert_eq!(metrics.writes(), 2);
+        storage.set(&key_a, &[5; 8]).unwrap();
+        assert_eq!(metrics.writes(), 4);
+        assert_eq!(store.get(&key_c).unwrap(), Some(vec![4; 8].into()));
+
+        // Erasing removes buffered values
+        storage.set(&key_c, &[6; 4]).unwrap();
+        storage.erase_prefix(&StorePrefix::root()).unwrap();
+        storage.flush().unwrap();
+        assert!(storage.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn buffered_write_storage_transformer_flush_interval() {
+        let store = Arc::new(MemoryStore::new());
+        let storage = BufferedWriteStorageTransformer::new_with_flush_interval(
+            store.clone(),
+            usize::MAX,
+            Duration::ZERO,
+        );
+        let key = StoreKey::new("a").unwrap();
+        storage.set(&key, &[0; 4]).unwrap();
+        assert_eq!(storage.buffered_keys(), 0);
+        assert_eq!(store.get(&key).unwrap(), Some(vec![0; 4].into()));
+    }
+}
@@ -0,0 +1,621 @@
+//! A storage transformer which caches values in memory.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{extract_byte_ranges, ByteRange},
+    metadata::Metadata,
+    storage::{
+        ETag, ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
+        ReadableStorageTraits, ReadableWritableListableStorage, ReadableWritableStorage,
+        ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage,
+        WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorage, AsyncListableStorageTraits, AsyncReadableListableStorage,
+    AsyncReadableStorage, AsyncReadableStorageTraits, AsyncReadableWritableListableStorage,
+    AsyncReadableWritableStorageTraits, AsyncWritableStorage, AsyncWritableStorageTraits,
+};
+
+use super::StorageTransformerExtension;
+
+/// The cache storage transformer. Caches values retrieved with `get` in memory.
+///
+/// This storage transformer is for internal use and will not to be included in `storage_transformers` array metadata.
+/// It is intended to hide storage latency when values are read repeatedly, such as when an interactive viewer revisits a region of an array.
+/// The cache can be warmed ahead of time with [`Array::prefetch_array_subset`](crate::array::Array::prefetch_array_subset).
+///
+/// Partial reads are served from the cache if the whole value is cached, otherwise they are passed through to the underlying storage without being cached.
+/// Missing keys are cached too, so repeated reads of empty chunks do not reach the underlying storage.
+/// The least recently used values are evicted once the cached bytes exceed the capacity.
+///
+/// Cached values are invalidated when they are written or erased through this storage transformer.
+/// Writes that bypass it are not observed, so the cache should be [cleared](CacheStorageTransformer::clear) if the underlying storage is modified by other means.
+#[derive(Debug)]
+pub struct CacheStorageTransformer {
+    capacity: u64,
+    cache: Mutex<Cache>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A least recently used cache of values.
+#[derive(Debug, Default)]
+struct Cache {
+    values: HashMap<StoreKey, (MaybeBytes, u64)>,
+    recency: BTreeMap<u64, StoreKey>,
+    size: u64,
+    counter: u64,
+}
+
+/// The number of bytes attributed to a cached value.
+fn entry_size(key: &StoreKey, value: &MaybeBytes) -> u64 {
+    (key.as_str().len() + value.as_ref().map_or(0, bytes::Bytes::len)) as u64
+}
+
+impl Cache {
+    fn get(&mut self, key: &StoreKey) -> Option<MaybeBytes> {
+        let (value, last_used) = self.values.get_mut(key)?;
+        self.recency.remove(last_used);
+        self.counter += 1;
+        *last_used = self.counter;
+        self.recency.insert(self.counter, key.clone());
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: StoreKey, value: MaybeBytes, capacity: u64) {
+        self.remove(&key);
+        let size = entry_size(&key, &value);
+        if size > capacity {
+            return;
+        }
+        while self.size + size > capacity {
+            let Some((_, key_lru)) = self.recency.pop_first() else {
+                break;
+            };
+            if let Some((value_lru, _)) = self.values.remove(&key_lru) {
+                self.size -= entry_size(&key_lru, &value_lru);
+            }
+        }
+        self.counter += 1;
+        self.recency.insert(self.counter, key.clone());
+        self.values.insert(key, (value, self.counter));
+        self.size += size;
+    }
+
+    fn remove(&mut self, key: &StoreKey) {
+        if let Some((value, last_used)) = self.values.remove(key) {
+            self.recency.remove(&last_used);
+            self.size -= entry_size(key, &value);
+        }
+    }
+
+    fn remove_prefix(&mut self, prefix: &StorePrefix) {
+        let keys = self
+            .values
+            .keys()
+            .filter(|key| key.has_prefix(prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in &keys {
+            self.remove(key);
+        }
+    }
+}
+
+impl CacheStorageTransformer {
+    /// Create a new cache storage transformer which holds up to `capacity` bytes.
+    #[must_use]
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            cache: Mutex::new(Cache::default()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the capacity of the cache in bytes.
+    #[must_use]
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Returns the number of bytes currently held by the cache.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.cache.lock().size
+    }
+
+    /// Returns the number of cached keys.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.lock().values.len()
+    }
+
+    /// Returns true if the cache is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of reads served from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of reads passed through to the underlying storage.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Remove all values from the cache.
+    pub fn clear(&self) {
+        *self.cache.lock() = Cache::default();
+    }
+
+    fn cached(&self, key: &StoreKey) -> Option<MaybeBytes> {
+        let value = self.cache.lock().get(key);
+        if value.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        value
+    }
+
+    fn cached_partial(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Option<Result<Option<Vec<Vec<u8>>>, StorageError>> {
+        let value = self.cached(key)?;
+        Some(match value {
+            Some(bytes) => extract_byte_ranges(&bytes, byte_ranges)
+                .map(Some)
+                .map_err(StorageError::from),
+            None => Ok(None),
+        })
+    }
+
+    fn insert(&self, key: &StoreKey, value: &MaybeBytes) {
+        self.cache
+            .lock()
+            .insert(key.clone(), value.clone(), self.capacity);
+    }
+
+    fn invalidate(&self, key: &StoreKey) {
+        self.cache.lock().remove(key);
+    }
+
+    fn invalidate_prefix(&self, prefix: &StorePrefix) {
+        self.cache.lock().remove_prefix(prefix);
+    }
+
+    fn create_transformer<TStorage: ?Sized + 'static>(
+        self: Arc<Self>,
+        storage: Arc<TStorage>,
+    ) -> Arc<CacheStorageTransformerImpl<TStorage>> {
+        Arc::new(CacheStorageTransformerImpl {
+            storage,
+            transformer: self,
+        })
+    }
+}
+
+impl StorageTransformerExtension for CacheStorageTransformer {
+    /// Returns [`None`], since this storage transformer is not intended to be included in array `storage_transformers` metadata.
+    fn create_metadata(&self) -> Option<Metadata> {
+        None
+    }
+
+    fn create_readable_transformer(self: Arc<Self>, storage: ReadableStorage) -> ReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_writable_transformer(self: Arc<Self>, storage: WritableStorage) -> WritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableStorage,
+    ) -> ReadableWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_listable_transformer(self: Arc<Self>, storage: ListableStorage) -> ListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableListableStorage,
+    ) -> ReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableListableStorage,
+    ) -> ReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableStorage,
+    ) -> AsyncReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_writable_transformer(
+        self: Arc<Self>,
+        storage: AsyncWritableStorage,
+    ) -> AsyncWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncListableStorage,
+    ) -> AsyncListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableListableStorage,
+    ) -> AsyncReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableWritableListableStorage,
+    ) -> AsyncReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+}
+
+#[derive(Debug)]
+struct CacheStorageTransformerImpl<TStorage: ?Sized + 'static> {
+    storage: Arc<TStorage>,
+    transformer: Arc<CacheStorageTransformer>,
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        if let Some(value) = self.transformer.cached(key) {
+            return Ok(value);
+        }
+        let value = self.storage.get(key)?;
+        self.transformer.insert(key, &value);
+        Ok(value)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        if let Some(values) = self.transformer.cached_partial(key, byte_ranges) {
+            return values;
+        }
+        self.storage.get_partial_values_key(key, byte_ranges)
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size()
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key)
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list()
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix)
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.set(key, value)
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        for key_value in key_values {
+            self.transformer.invalidate(key_value.key());
+        }
+        self.storage.set_values(key_values)
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        for key_start_value in key_start_values {
+            self.transformer.invalidate(&key_start_value.key);
+        }
+        self.storage.set_partial_values(key_start_values)
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.erase(key)
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.transformer.invalidate(key);
+        }
+        self.storage.erase_values(keys)
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer.invalidate_prefix(prefix);
+        self.storage.erase_prefix(prefix)
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.set_if_not_exists(key, value)
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        self.storage.etag(key)
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.set_if_match(key, value, etag)
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        if let Some(value) = self.transformer.cached(key) {
+            return Ok(value);
+        }
+        let value = self.storage.get(key).await?;
+        self.transformer.insert(key, &value);
+        Ok(value)
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        if let Some(values) = self.transformer.cached_partial(key, byte_ranges) {
+            return values;
+        }
+        self.storage.get_partial_values_key(key, byte_ranges).await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges).await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.storage.size().await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.storage.size_prefix(prefix).await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.storage.size_key(key).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.storage.list().await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.storage.list_prefix(prefix).await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.storage.list_dir(prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.set(key, value).await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        for key_start_value in key_start_values {
+            self.transformer.invalidate(&key_start_value.key);
+        }
+        self.storage.set_partial_values(key_start_values).await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer.invalidate(key);
+        self.storage.erase(key).await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        for key in keys {
+            self.transformer.invalidate(key);
+        }
+        self.storage.erase_values(keys).await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer.invalidate_prefix(prefix);
+        self.storage.erase_prefix(prefix).await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
+    for CacheStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn cache_storage_transformer() {
+        let store = Arc::new(MemoryStore::new());
+        let cache = Arc::new(CacheStorageTransformer::new(20));
+        let storage = cache.clone().create_readable_writable_transformer(store);
+        let key_a = StoreKey::new("a").unwrap();
+        let key_b = StoreKey::new("b").unwrap();
+        let key_c = StoreKey::new("c").unwrap();
+        let key_d = StoreKey::new("d").unwrap();
+        storage.set(&key_a, &[0; 8]).unwrap();
+        storage.set(&key_b, &[1; 8]).unwrap();
+        storage.set(&key_d, &[3; 8]).unwrap();
+
+        assert_eq!(storage.get(&key_a).unwrap(), Some(vec![0; 8].into()));
+        assert_eq!(storage.get(&key_b).unwrap(), Some(vec![1; 8].into()));
+        assert_eq!(storage.get(&key_a).unwrap(), Some(vec![0; 8].into()));
+        assert_eq!(
+            storage
+                .get_partial_values_key(&key_a, &[ByteRange::FromStart(2, Some(2))])
+                .unwrap(),
+            Some(vec![vec![0; 2]])
+        );
+        assert!(storage.get(&key_c).unwrap().is_none());
+        assert_eq!((cache.len(), cache.size()), (3, 19));
+        assert_eq!((cache.hits(), cache.misses()), (2, 3));
+
+        // The least recently used value (b) is evicted
+        assert_eq!(storage.get(&key_d).unwrap(), Some(vec![3; 8].into()));
+        assert_eq!((cache.len(), cache.size()), (3, 19));
+        assert!(storage.get(&key_c).unwrap().is_none());
+        assert_eq!(storage.get(&key_b).unwrap(), Some(vec![1; 8].into()));
+        assert_eq!((cache.hits(), cache.misses()), (3, 5));
+
+        // Writes invalidate cached values
+        storage.set(&key_c, &[2; 4]).unwrap();
+        assert_eq!(storage.get(&key_c).unwrap(), Some(vec![2; 4].into()));
+        storage.erase_prefix(&StorePrefix::root()).unwrap();
+        assert!(cache.is_empty());
+        assert!(storage.get(&key_b).unwrap().is_none());
+
+        // Values larger than the capacity are not cached
+        let key_e = StoreKey::new("e").unwrap();
+        storage.set(&key_e, &[4; 32]).unwrap();
+        assert_eq!(storage.get(&key_e).unwrap(), Some(vec![4; 32].into()));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert_eq!((cache.len(), cache.size()), (0, 0));
+    }
+}
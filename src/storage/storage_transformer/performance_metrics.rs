@@ -4,10 +4,11 @@ use crate::{
     array::MaybeBytes,
     metadata::Metadata,
     storage::{
-        ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
+        ETag, ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
         ReadableStorageTraits, ReadableWritableListableStorage, ReadableWritableStorage,
         ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
-        StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage, WritableStorageTraits,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage,
+        WritableStorageTraits,
     },
 };
 
@@ -18,23 +19,281 @@ use crate::storage::{
     AsyncReadableWritableStorageTraits, AsyncWritableStorage, AsyncWritableStorageTraits,
 };
 
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+use serde::{Deserialize, Serialize};
+
 use super::StorageTransformerExtension;
 
-/// The performance metrics storage transformer. Accumulates metrics, such as bytes read and written.
+/// A storage operation category tracked by the [`PerformanceMetricsStorageTransformer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerformanceMetricsOperation {
+    /// [`get`](ReadableStorageTraits::get).
+    Get,
+    /// [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key) and [`get_partial_values`](ReadableStorageTraits::get_partial_values).
+    GetPartial,
+    /// [`set`](WritableStorageTraits::set).
+    Set,
+    /// [`set_partial_values`](WritableStorageTraits::set_partial_values).
+    SetPartial,
+    /// [`erase`](WritableStorageTraits::erase), [`erase_values`](WritableStorageTraits::erase_values), and [`erase_prefix`](WritableStorageTraits::erase_prefix).
+    Erase,
+    /// [`list`](ListableStorageTraits::list), [`list_prefix`](ListableStorageTraits::list_prefix), and [`list_dir`](ListableStorageTraits::list_dir).
+    List,
+    /// [`size`](ReadableStorageTraits::size), [`size_prefix`](ReadableStorageTraits::size_prefix), and [`size_key`](ReadableStorageTraits::size_key).
+    Size,
+}
+
+impl PerformanceMetricsOperation {
+    const ALL: [Self; 7] = [
+        Self::Get,
+        Self::GetPartial,
+        Self::Set,
+        Self::SetPartial,
+        Self::Erase,
+        Self::List,
+        Self::Size,
+    ];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A latency summary for a storage operation in a [`PerformanceMetricsSummary`].
 ///
-/// This storage transformer is for internal use and will not to be included in `storage_transformers` array metadata.
-/// It is intended to aid in testing by allowing the application to validate that metrics (e.g., bytes read/written, total read/write operations, lock requests) match expected values for specific operations.
+/// Quantiles are estimated from a log-linear histogram and are accurate to within 12.5%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceMetricsLatency {
+    /// The number of operations.
+    pub count: usize,
+    /// The mean latency in microseconds.
+    pub mean_us: u64,
+    /// The median latency in microseconds.
+    pub p50_us: u64,
+    /// The 95th percentile latency in microseconds.
+    pub p95_us: u64,
+    /// The 99th percentile latency in microseconds.
+    pub p99_us: u64,
+    /// The maximum latency in microseconds.
+    pub max_us: u64,
+}
+
+/// Performance metrics for all storage operations, or for those under a prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceMetricsSummary {
+    /// The number of bytes read.
+    pub bytes_read: usize,
+    /// The number of bytes written.
+    pub bytes_written: usize,
+    /// The number of read requests.
+    pub reads: usize,
+    /// The number of write requests.
+    pub writes: usize,
+    /// Latencies of operations which have been performed at least once.
+    pub latencies: BTreeMap<PerformanceMetricsOperation, PerformanceMetricsLatency>,
+}
+
+/// A serializable report of the metrics recorded by a [`PerformanceMetricsStorageTransformer`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceMetricsReport {
+    /// Metrics for all storage operations.
+    #[serde(flatten)]
+    pub total: PerformanceMetricsSummary,
+    /// Metrics for each prefix registered with [`PerformanceMetricsStorageTransformer::new_with_prefixes`].
+    pub prefixes: BTreeMap<String, PerformanceMetricsSummary>,
+}
+
+/// The number of linear sub-buckets per power of two in a [`LatencyHistogram`].
+const LATENCY_SUB_BUCKETS: u64 = 8;
+
+/// The number of buckets in a [`LatencyHistogram`], covering latencies up to 2^40 microseconds.
+const LATENCY_BUCKETS: usize = 38 * 8 + 8;
+
+/// A lock free log-linear histogram of latencies in microseconds.
+#[derive(Debug)]
+struct LatencyHistogram {
+    buckets: Vec<AtomicUsize>,
+    count: AtomicUsize,
+    total_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..LATENCY_BUCKETS).map(|_| AtomicUsize::new(0)).collect(),
+            count: AtomicUsize::new(0),
+            total_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn bucket_index(us: u64) -> usize {
+        if us < LATENCY_SUB_BUCKETS {
+            usize::try_from(us).unwrap()
+        } else {
+            let exponent = u64::from(us.ilog2());
+            let mantissa = (us >> (exponent - 3)) & (LATENCY_SUB_BUCKETS - 1);
+            usize::try_from((exponent - 2) * LATENCY_SUB_BUCKETS + mantissa)
+                .unwrap()
+                .min(LATENCY_BUCKETS - 1)
+        }
+    }
+
+    /// Returns the exclusive upper bound of the bucket at `index` in microseconds.
+    fn bucket_upper_bound(index: usize) -> u64 {
+        let index = index as u64;
+        if index < LATENCY_SUB_BUCKETS {
+            index + 1
+        } else {
+            let exponent = index / LATENCY_SUB_BUCKETS + 2;
+            let mantissa = index % LATENCY_SUB_BUCKETS;
+            (LATENCY_SUB_BUCKETS + 1 + mantissa) << (exponent - 3)
+        }
+    }
+
+    fn record(&self, duration: Duration) {
+        let us = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.buckets[Self::bucket_index(us)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_us.fetch_add(us, Ordering::Relaxed);
+        self.max_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    /// Returns an estimate of the `quantile` latency in microseconds, or [`None`] if nothing has been recorded.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn quantile(&self, quantile: f64) -> Option<u64> {
+        let counts: Vec<usize> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        let count: usize = counts.iter().sum();
+        if count == 0 {
+            return None;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * count as f64).ceil() as usize).max(1);
+        let max_us = self.max_us.load(Ordering::Relaxed);
+        let mut cumulative = 0;
+        for (index, bucket_count) in counts.iter().enumerate() {
+            cumulative += bucket_count;
+            if cumulative >= rank {
+                return Some((Self::bucket_upper_bound(index) - 1).min(max_us));
+            }
+        }
+        Some(max_us)
+    }
+
+    fn summary(&self) -> Option<PerformanceMetricsLatency> {
+        let count = self.count.load(Ordering::Relaxed);
+        if count == 0 {
+            return None;
+        }
+        Some(PerformanceMetricsLatency {
+            count,
+            mean_us: self.total_us.load(Ordering::Relaxed) / count as u64,
+            p50_us: self.quantile(0.50)?,
+            p95_us: self.quantile(0.95)?,
+            p99_us: self.quantile(0.99)?,
+            max_us: self.max_us.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Counters and latency histograms for a set of storage operations.
 #[derive(Debug, Default)]
-pub struct PerformanceMetricsStorageTransformer {
+struct Metrics {
     bytes_read: AtomicUsize,
     bytes_written: AtomicUsize,
     reads: AtomicUsize,
     writes: AtomicUsize,
+    latencies: [LatencyHistogram; PerformanceMetricsOperation::ALL.len()],
+}
+
+impl Metrics {
+    fn read(&self, bytes: usize, reads: usize) {
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+        self.reads.fetch_add(reads, Ordering::Relaxed);
+    }
+
+    fn write(&self, bytes: usize, writes: usize) {
+        self.bytes_written.fetch_add(bytes, Ordering::Relaxed);
+        self.writes.fetch_add(writes, Ordering::Relaxed);
+    }
+
+    fn summary(&self) -> PerformanceMetricsSummary {
+        PerformanceMetricsSummary {
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            latencies: PerformanceMetricsOperation::ALL
+                .iter()
+                .filter_map(|operation| {
+                    self.latencies[operation.index()]
+                        .summary()
+                        .map(|latency| (*operation, latency))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The target of a storage operation, used to attribute it to registered prefixes.
+#[derive(Clone, Copy)]
+enum Target<'a> {
+    Store,
+    Key(&'a StoreKey),
+    Keys(&'a [StoreKey]),
+    KeyRanges(&'a [StoreKeyRange]),
+    KeyStartValues(&'a [StoreKeyStartValue<'a>]),
+    Prefix(&'a StorePrefix),
+}
+
+impl Target<'_> {
+    fn is_under(&self, prefix: &StorePrefix) -> bool {
+        match self {
+            Self::Store => false,
+            Self::Key(key) => key.has_prefix(prefix),
+            Self::Keys(keys) => keys.iter().any(|key| key.has_prefix(prefix)),
+            Self::KeyRanges(key_ranges) => key_ranges
+                .iter()
+                .any(|key_range| key_range.key.has_prefix(prefix)),
+            Self::KeyStartValues(key_start_values) => key_start_values
+                .iter()
+                .any(|key_start_value| key_start_value.key.has_prefix(prefix)),
+            Self::Prefix(target) => target.as_str().starts_with(prefix.as_str()),
+        }
+    }
+}
+
+/// The performance metrics storage transformer. Accumulates metrics, such as bytes read and written and per-operation latencies.
+///
+/// This storage transformer is for internal use and will not to be included in `storage_transformers` array metadata.
+/// It is intended to aid in testing by allowing the application to validate that metrics (e.g., bytes read/written, total read/write operations, lock requests) match expected values for specific operations.
+///
+/// The latency of each storage operation is recorded in a histogram per [`PerformanceMetricsOperation`], from which p50/p95/p99 latencies are estimated.
+/// Metrics can also be broken down by prefix (e.g. one per array) with [`new_with_prefixes`](PerformanceMetricsStorageTransformer::new_with_prefixes).
+/// A serializable [`PerformanceMetricsReport`] is returned by [`report`](PerformanceMetricsStorageTransformer::report), which is useful for comparing store backends.
+#[derive(Debug, Default)]
+pub struct PerformanceMetricsStorageTransformer {
+    total: Metrics,
+    prefixes: Vec<(StorePrefix, Metrics)>,
     locks: AtomicUsize,
 }
 
@@ -45,24 +304,39 @@ impl PerformanceMetricsStorageTransformer {
         Self::default()
     }
 
+    /// Create a new performance metrics storage transformer which also records metrics for operations under each of `prefixes`.
+    ///
+    /// An operation is attributed to every registered prefix it touches, so the metrics of nested prefixes overlap.
+    /// Operations on the whole store (e.g. [`list`](ListableStorageTraits::list)) are only included in the total metrics.
+    #[must_use]
+    pub fn new_with_prefixes(prefixes: Vec<StorePrefix>) -> Self {
+        Self {
+            prefixes: prefixes
+                .into_iter()
+                .map(|prefix| (prefix, Metrics::default()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
     /// Returns the number of bytes read.
     pub fn bytes_read(&self) -> usize {
-        self.bytes_read.load(Ordering::Relaxed)
+        self.total.bytes_read.load(Ordering::Relaxed)
     }
 
     /// Returns the number of bytes written.
     pub fn bytes_written(&self) -> usize {
-        self.bytes_written.load(Ordering::Relaxed)
+        self.total.bytes_written.load(Ordering::Relaxed)
     }
 
     /// Returns the number of read requests.
     pub fn reads(&self) -> usize {
-        self.reads.load(Ordering::Relaxed)
+        self.total.reads.load(Ordering::Relaxed)
     }
 
     /// Returns the number of write requests.
     pub fn writes(&self) -> usize {
-        self.writes.load(Ordering::Relaxed)
+        self.total.writes.load(Ordering::Relaxed)
     }
 
     /// Returns the number of lock requests.
@@ -70,6 +344,62 @@ impl PerformanceMetricsStorageTransformer {
         self.locks.load(Ordering::Relaxed)
     }
 
+    /// Returns the latency summary of `operation`, or [`None`] if it has not been performed.
+    #[must_use]
+    pub fn latency(
+        &self,
+        operation: PerformanceMetricsOperation,
+    ) -> Option<PerformanceMetricsLatency> {
+        self.total.latencies[operation.index()].summary()
+    }
+
+    /// Returns an estimate of the `quantile` (in `[0, 1]`) latency of `operation`, or [`None`] if it has not been performed.
+    #[must_use]
+    pub fn latency_quantile(
+        &self,
+        operation: PerformanceMetricsOperation,
+        quantile: f64,
+    ) -> Option<Duration> {
+        self.total.latencies[operation.index()]
+            .quantile(quantile)
+            .map(Duration::from_micros)
+    }
+
+    /// Returns a serializable report of the recorded metrics.
+    #[must_use]
+    pub fn report(&self) -> PerformanceMetricsReport {
+        PerformanceMetricsReport {
+            total: self.total.summary(),
+            prefixes: self
+                .prefixes
+                .iter()
+                .map(|(prefix, metrics)| (prefix.as_str().to_string(), metrics.summary()))
+                .collect(),
+        }
+    }
+
+    /// Apply `f` to the total metrics and the metrics of each registered prefix which `target` is under.
+    fn record(&self, target: Target, f: impl Fn(&Metrics)) {
+        f(&self.total);
+        for (prefix, metrics) in &self.prefixes {
+            if target.is_under(prefix) {
+                f(metrics);
+            }
+        }
+    }
+
+    fn record_latency(
+        &self,
+        operation: PerformanceMetricsOperation,
+        target: Target,
+        start: Instant,
+    ) {
+        let duration = start.elapsed();
+        self.record(target, |metrics| {
+            metrics.latencies[operation.index()].record(duration);
+        });
+    }
+
     fn create_transformer<TStorage: ?Sized + 'static>(
         self: Arc<Self>,
         storage: Arc<TStorage>,
@@ -167,18 +497,76 @@ struct PerformanceMetricsStorageTransformerImpl<TStorage: ?Sized + 'static> {
     transformer: Arc<PerformanceMetricsStorageTransformer>,
 }
 
+impl PerformanceMetricsStorageTransformer {
+    fn record_get(&self, key: &StoreKey, value: &Result<MaybeBytes, StorageError>, start: Instant) {
+        self.record_latency(PerformanceMetricsOperation::Get, Target::Key(key), start);
+        let bytes_read = value
+            .as_ref()
+            .map_or(0, |v| v.as_ref().map_or(0, bytes::Bytes::len));
+        self.record(Target::Key(key), |metrics| metrics.read(bytes_read, 1));
+    }
+
+    fn record_get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[crate::byte_range::ByteRange],
+        values: &Result<Option<Vec<Vec<u8>>>, StorageError>,
+        start: Instant,
+    ) {
+        self.record_latency(
+            PerformanceMetricsOperation::GetPartial,
+            Target::Key(key),
+            start,
+        );
+        if let Ok(Some(values)) = values {
+            let bytes_read = values.iter().map(Vec::len).sum();
+            self.record(Target::Key(key), |metrics| {
+                metrics.read(bytes_read, byte_ranges.len());
+            });
+        }
+    }
+
+    fn record_get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+        values: &Result<Vec<MaybeBytes>, StorageError>,
+        start: Instant,
+    ) {
+        self.record_latency(
+            PerformanceMetricsOperation::GetPartial,
+            Target::KeyRanges(key_ranges),
+            start,
+        );
+        if let Ok(values) = values {
+            for (key_range, value) in key_ranges.iter().zip(values) {
+                let bytes_read = value.as_ref().map_or(0, bytes::Bytes::len);
+                self.record(Target::Key(&key_range.key), |metrics| {
+                    metrics.read(bytes_read, 1);
+                });
+            }
+        }
+    }
+
+    fn record_set(&self, key: &StoreKey, bytes_written: usize) {
+        self.record(Target::Key(key), |metrics| metrics.write(bytes_written, 1));
+    }
+
+    fn record_set_partial_values(&self, key_start_values: &[StoreKeyStartValue]) {
+        for key_start_value in key_start_values {
+            self.record(Target::Key(&key_start_value.key), |metrics| {
+                metrics.write(key_start_value.value.len(), 1);
+            });
+        }
+    }
+}
+
 impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let start = Instant::now();
         let value = self.storage.get(key);
-        let bytes_read = value
-            .as_ref()
-            .map_or(0, |v| v.as_ref().map_or(0, std::vec::Vec::len));
-        self.transformer
-            .bytes_read
-            .fetch_add(bytes_read, Ordering::Relaxed);
-        self.transformer.reads.fetch_add(1, Ordering::Relaxed);
+        self.transformer.record_get(key, &value, start);
         value
     }
 
@@ -187,47 +575,57 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[crate::byte_range::ByteRange],
     ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
-        let values = self.storage.get_partial_values_key(key, byte_ranges)?;
-        if let Some(values) = &values {
-            let bytes_read = values.iter().map(Vec::len).sum();
-            self.transformer
-                .bytes_read
-                .fetch_add(bytes_read, Ordering::Relaxed);
-            self.transformer
-                .reads
-                .fetch_add(byte_ranges.len(), Ordering::Relaxed);
-        }
-        Ok(values)
+        let start = Instant::now();
+        let values = self.storage.get_partial_values_key(key, byte_ranges);
+        self.transformer
+            .record_get_partial_values_key(key, byte_ranges, &values, start);
+        values
     }
 
     fn get_partial_values(
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
-        let values = self.storage.get_partial_values(key_ranges)?;
-        let bytes_read = values
-            .iter()
-            .map(|value| value.as_ref().map_or(0, Vec::len))
-            .sum::<usize>();
-        self.transformer
-            .bytes_read
-            .fetch_add(bytes_read, Ordering::Relaxed);
+        let start = Instant::now();
+        let values = self.storage.get_partial_values(key_ranges);
         self.transformer
-            .reads
-            .fetch_add(key_ranges.len(), Ordering::Relaxed);
-        Ok(values)
+            .record_get_partial_values(key_ranges, &values, start);
+        values
     }
 
     fn size(&self) -> Result<u64, StorageError> {
-        self.storage.size()
+        let start = Instant::now();
+        let size = self.storage.size();
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Size, Target::Store, start);
+        size
     }
 
     fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        self.storage.size_prefix(prefix)
+        let start = Instant::now();
+        let size = self.storage.size_prefix(prefix);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Size,
+            Target::Prefix(prefix),
+            start,
+        );
+        size
     }
 
     fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
-        self.storage.size_key(key)
+        let start = Instant::now();
+        let size = self.storage.size_key(key);
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Size, Target::Key(key), start);
+        size
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
     }
 }
 
@@ -235,15 +633,37 @@ impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     fn list(&self) -> Result<StoreKeys, StorageError> {
-        self.storage.list()
+        let start = Instant::now();
+        let keys = self.storage.list();
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::List, Target::Store, start);
+        keys
     }
 
     fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        self.storage.list_prefix(prefix)
+        let start = Instant::now();
+        let keys = self.storage.list_prefix(prefix);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::List,
+            Target::Prefix(prefix),
+            start,
+        );
+        keys
     }
 
     fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
-        self.storage.list_dir(prefix)
+        let start = Instant::now();
+        let keys_prefixes = self.storage.list_dir(prefix);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::List,
+            Target::Prefix(prefix),
+            start,
+        );
+        keys_prefixes
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
     }
 }
 
@@ -251,40 +671,126 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.transformer.record_set(key, value.len());
+        let start = Instant::now();
+        let result = self.storage.set(key, value);
         self.transformer
-            .bytes_written
-            .fetch_add(value.len(), Ordering::Relaxed);
-        self.transformer.writes.fetch_add(1, Ordering::Relaxed);
-        self.storage.set(key, value)
+            .record_latency(PerformanceMetricsOperation::Set, Target::Key(key), start);
+        result
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        for key_value in key_values {
+            self.transformer
+                .record_set(key_value.key(), key_value.value().len());
+        }
+        let keys = key_values
+            .iter()
+            .map(|key_value| key_value.key().clone())
+            .collect::<Vec<_>>();
+        let start = Instant::now();
+        let result = self.storage.set_values(key_values);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Set,
+            Target::Keys(&keys),
+            start,
+        );
+        result
     }
 
     fn set_partial_values(
         &self,
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError> {
-        let bytes_written = key_start_values
-            .iter()
-            .map(|ksv| ksv.value.len())
-            .sum::<usize>();
-        self.transformer
-            .bytes_written
-            .fetch_add(bytes_written, Ordering::Relaxed);
-        self.transformer
-            .writes
-            .fetch_add(key_start_values.len(), Ordering::Relaxed);
-        self.storage.set_partial_values(key_start_values)
+        self.transformer.record_set_partial_values(key_start_values);
+        let start = Instant::now();
+        let result = self.storage.set_partial_values(key_start_values);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::SetPartial,
+            Target::KeyStartValues(key_start_values),
+            start,
+        );
+        result
     }
 
     fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
-        self.storage.erase(key)
+        let start = Instant::now();
+        let result = self.storage.erase(key);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Key(key),
+            start,
+        );
+        result
     }
 
     fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
-        self.storage.erase_values(keys)
+        let start = Instant::now();
+        let result = self.storage.erase_values(keys);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Keys(keys),
+            start,
+        );
+        result
     }
 
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
-        self.storage.erase_prefix(prefix)
+        let start = Instant::now();
+        let result = self.storage.erase_prefix(prefix);
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Prefix(prefix),
+            start,
+        );
+        result
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        let start = Instant::now();
+        let result = self.storage.set_if_not_exists(key, value);
+        if let Ok(true) = result {
+            self.transformer.record_set(key, value.len());
+        }
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Set, Target::Key(key), start);
+        result
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        self.storage.etag(key)
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        let start = Instant::now();
+        let result = self.storage.set_if_match(key, value, etag);
+        if let Ok(true) = result {
+            self.transformer.record_set(key, value.len());
+        }
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Set, Target::Key(key), start);
+        result
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
     }
 }
 
@@ -303,14 +809,9 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let start = Instant::now();
         let value = self.storage.get(key).await;
-        let bytes_read = value
-            .as_ref()
-            .map_or(0, |v| v.as_ref().map_or(0, std::vec::Vec::len));
-        self.transformer
-            .bytes_read
-            .fetch_add(bytes_read, Ordering::Relaxed);
-        self.transformer.reads.fetch_add(1, Ordering::Relaxed);
+        self.transformer.record_get(key, &value, start);
         value
     }
 
@@ -319,50 +820,49 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[crate::byte_range::ByteRange],
     ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
-        let values = self
-            .storage
-            .get_partial_values_key(key, byte_ranges)
-            .await?;
-        if let Some(values) = &values {
-            let bytes_read = values.iter().map(Vec::len).sum();
-            self.transformer
-                .bytes_read
-                .fetch_add(bytes_read, Ordering::Relaxed);
-            self.transformer
-                .reads
-                .fetch_add(byte_ranges.len(), Ordering::Relaxed);
-        }
-        Ok(values)
+        let start = Instant::now();
+        let values = self.storage.get_partial_values_key(key, byte_ranges).await;
+        self.transformer
+            .record_get_partial_values_key(key, byte_ranges, &values, start);
+        values
     }
 
     async fn get_partial_values(
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
-        let values = self.storage.get_partial_values(key_ranges).await?;
-        let bytes_read = values
-            .iter()
-            .map(|value| value.as_ref().map_or(0, Vec::len))
-            .sum::<usize>();
-        self.transformer
-            .bytes_read
-            .fetch_add(bytes_read, Ordering::Relaxed);
+        let start = Instant::now();
+        let values = self.storage.get_partial_values(key_ranges).await;
         self.transformer
-            .reads
-            .fetch_add(key_ranges.len(), Ordering::Relaxed);
-        Ok(values)
+            .record_get_partial_values(key_ranges, &values, start);
+        values
     }
 
     async fn size(&self) -> Result<u64, StorageError> {
-        self.storage.size().await
+        let start = Instant::now();
+        let size = self.storage.size().await;
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Size, Target::Store, start);
+        size
     }
 
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        self.storage.size_prefix(prefix).await
+        let start = Instant::now();
+        let size = self.storage.size_prefix(prefix).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Size,
+            Target::Prefix(prefix),
+            start,
+        );
+        size
     }
 
     async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
-        self.storage.size_key(key).await
+        let start = Instant::now();
+        let size = self.storage.size_key(key).await;
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::Size, Target::Key(key), start);
+        size
     }
 }
 
@@ -372,15 +872,33 @@ impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     async fn list(&self) -> Result<StoreKeys, StorageError> {
-        self.storage.list().await
+        let start = Instant::now();
+        let keys = self.storage.list().await;
+        self.transformer
+            .record_latency(PerformanceMetricsOperation::List, Target::Store, start);
+        keys
     }
 
     async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        self.storage.list_prefix(prefix).await
+        let start = Instant::now();
+        let keys = self.storage.list_prefix(prefix).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::List,
+            Target::Prefix(prefix),
+            start,
+        );
+        keys
     }
 
     async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
-        self.storage.list_dir(prefix).await
+        let start = Instant::now();
+        let keys_prefixes = self.storage.list_dir(prefix).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::List,
+            Target::Prefix(prefix),
+            start,
+        );
+        keys_prefixes
     }
 }
 
@@ -390,40 +908,60 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
     for PerformanceMetricsStorageTransformerImpl<TStorage>
 {
     async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        self.transformer.record_set(key, value.len());
+        let start = Instant::now();
+        let result = self.storage.set(key, value).await;
         self.transformer
-            .bytes_written
-            .fetch_add(value.len(), Ordering::Relaxed);
-        self.transformer.writes.fetch_add(1, Ordering::Relaxed);
-        self.storage.set(key, value).await
+            .record_latency(PerformanceMetricsOperation::Set, Target::Key(key), start);
+        result
     }
 
     async fn set_partial_values(
         &self,
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError> {
-        let bytes_written = key_start_values
-            .iter()
-            .map(|ksv| ksv.value.len())
-            .sum::<usize>();
-        self.transformer
-            .bytes_written
-            .fetch_add(bytes_written, Ordering::Relaxed);
-        self.transformer
-            .writes
-            .fetch_add(key_start_values.len(), Ordering::Relaxed);
-        self.storage.set_partial_values(key_start_values).await
+        self.transformer.record_set_partial_values(key_start_values);
+        let start = Instant::now();
+        let result = self.storage.set_partial_values(key_start_values).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::SetPartial,
+            Target::KeyStartValues(key_start_values),
+            start,
+        );
+        result
     }
 
     async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
-        self.storage.erase(key).await
+        let start = Instant::now();
+        let result = self.storage.erase(key).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Key(key),
+            start,
+        );
+        result
     }
 
     async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
-        self.storage.erase_values(keys).await
+        let start = Instant::now();
+        let result = self.storage.erase_values(keys).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Keys(keys),
+            start,
+        );
+        result
     }
 
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
-        self.storage.erase_prefix(prefix).await
+        let start = Instant::now();
+        let result = self.storage.erase_prefix(prefix).await;
+        self.transformer.record_latency(
+            PerformanceMetricsOperation::Erase,
+            Target::Prefix(prefix),
+            start,
+        );
+        result
     }
 }
 
@@ -437,3 +975,94 @@ impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritabl
     //     self.storage.mutex(key).await
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn latency_histogram() {
+        for us in [0, 1, 7, 8, 15, 16, 17, 1000, 123_456, u64::MAX] {
+            let index = LatencyHistogram::bucket_index(us);
+            assert!(
+                us < LatencyHistogram::bucket_upper_bound(index) || index == LATENCY_BUCKETS - 1
+            );
+            assert!(index == 0 || us >= LatencyHistogram::bucket_upper_bound(index - 1));
+        }
+
+        let histogram = LatencyHistogram::default();
+        assert!(histogram.summary().is_none());
+        for us in 1..=100 {
+            histogram.record(Duration::from_micros(us));
+        }
+        let latency = histogram.summary().unwrap();
+        assert_eq!(latency.count, 100);
+        assert_eq!(latency.mean_us, 50);
+        assert_eq!(latency.max_us, 100);
+        assert!((50..=56).contains(&latency.p50_us));
+        assert!((95..=100).contains(&latency.p95_us));
+        assert!((99..=100).contains(&latency.p99_us));
+    }
+
+    #[test]
+    fn performance_metrics_report() {
+        let array_a = StorePrefix::new("a/").unwrap();
+        let array_b = StorePrefix::new("b/").unwrap();
+        let transformer = Arc::new(PerformanceMetricsStorageTransformer::new_with_prefixes(
+            vec![array_a.clone(), array_b],
+        ));
+        let storage = transformer
+            .clone()
+            .create_readable_writable_listable_transformer(Arc::new(MemoryStore::new()));
+
+        let key_a = StoreKey::new("a/c/0").unwrap();
+        let key_c = StoreKey::new("c/c/0").unwrap();
+        storage.set(&key_a, &[0, 1, 2, 3]).unwrap();
+        storage.set(&key_c, &[0, 1]).unwrap();
+        storage.get(&key_a).unwrap();
+        storage.list_prefix(&array_a).unwrap();
+        storage.list().unwrap();
+
+        assert_eq!(transformer.bytes_written(), 6);
+        assert_eq!(transformer.bytes_read(), 4);
+        assert_eq!(
+            transformer
+                .latency(PerformanceMetricsOperation::Set)
+                .unwrap()
+                .count,
+            2
+        );
+        assert!(transformer
+            .latency(PerformanceMetricsOperation::Erase)
+            .is_none());
+        assert!(transformer
+            .latency_quantile(PerformanceMetricsOperation::Get, 0.99)
+            .is_some());
+
+        let report = transformer.report();
+        assert_eq!(report.total.writes, 2);
+        assert_eq!(
+            report.total.latencies[&PerformanceMetricsOperation::List].count,
+            2
+        );
+        let report_a = &report.prefixes["a/"];
+        assert_eq!(report_a.bytes_written, 4);
+        assert_eq!(report_a.reads, 1);
+        assert_eq!(
+            report_a.latencies[&PerformanceMetricsOperation::List].count,
+            1
+        );
+        let report_b = &report.prefixes["b/"];
+        assert_eq!(report_b.writes, 0);
+        assert!(report_b.latencies.is_empty());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""list":{"count":2"#));
+        assert_eq!(
+            serde_json::from_str::<PerformanceMetricsReport>(&json).unwrap(),
+            report
+        );
+    }
+}
@@ -0,0 +1,649 @@
+//! A storage transformer which retries transient failures.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    metadata::Metadata,
+    storage::{
+        ETag, ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
+        ReadableStorageTraits, ReadableWritableListableStorage, ReadableWritableStorage,
+        ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage,
+        WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorage, AsyncListableStorageTraits, AsyncReadableListableStorage,
+    AsyncReadableStorage, AsyncReadableStorageTraits, AsyncReadableWritableListableStorage,
+    AsyncReadableWritableStorageTraits, AsyncWritableStorage, AsyncWritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use super::sleep::Sleep;
+use super::StorageTransformerExtension;
+
+/// A predicate which returns true if a [`StorageError`] is transient and the operation should be retried.
+pub type RetryPredicate = Arc<dyn Fn(&StorageError) -> bool + Send + Sync>;
+
+/// Returns true if `err` is likely to be transient.
+///
+/// This is the default [`RetryPredicate`] of a [`RetryStorageTransformer`].
+/// IO errors are transient if they are interrupted, timed out, or an interrupted connection.
+/// [`StorageError::Other`] errors are also transient, since network stores (e.g. HTTP, `object_store`, and `opendal` stores) map their errors to this variant.
+#[must_use]
+pub fn is_transient_storage_error(err: &StorageError) -> bool {
+    match err {
+        StorageError::IOError(err) => matches!(
+            err.kind(),
+            std::io::ErrorKind::Interrupted
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::WouldBlock
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::UnexpectedEof
+        ),
+        StorageError::Other(_) => true,
+        _ => false,
+    }
+}
+
+/// The retry storage transformer. Retries storage operations which fail with a transient error with exponential backoff and jitter.
+///
+/// This storage transformer is for internal use and will not to be included in `storage_transformers` array metadata.
+/// It is intended to make stores with intermittent failures (e.g. cloud object stores) reliable without retry loops above the [`Array`](crate::array::Array) API.
+///
+/// A failed operation is retried up to [`max_retries`](RetryStorageTransformer::max_retries) times if the [`RetryPredicate`] returns true for its error.
+/// The delay before retry `n` (starting from zero) is `initial_delay * 2^n`, capped at `max_delay` and reduced by a random fraction of up to `jitter` so that concurrent clients do not retry in lockstep.
+/// The error of the final attempt is returned if all attempts fail.
+///
+/// Every storage operation is retried, including writes and erasures, which are idempotent.
+/// Async retries wait on a timer thread, so they are independent of the async runtime.
+pub struct RetryStorageTransformer {
+    max_retries: usize,
+    initial_delay: Duration,
+    max_delay: Duration,
+    jitter: f64,
+    predicate: RetryPredicate,
+    random_state: RandomState,
+    retries: AtomicUsize,
+}
+
+impl std::fmt::Debug for RetryStorageTransformer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryStorageTransformer")
+            .field("max_retries", &self.max_retries)
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("retries", &self.retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryStorageTransformer {
+    /// Create a new retry storage transformer which retries failed operations up to `max_retries` times, starting with a delay of `initial_delay`.
+    ///
+    /// The maximum delay defaults to 30 seconds, the jitter to 0.5, and the predicate to [`is_transient_storage_error`].
+    #[must_use]
+    pub fn new(max_retries: usize, initial_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay: Duration::from_secs(30),
+            jitter: 0.5,
+            predicate: Arc::new(is_transient_storage_error),
+            random_state: RandomState::new(),
+            retries: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the maximum number of retries.
+    #[must_use]
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Set the maximum number of retries after a failed operation.
+    pub fn set_max_retries(&mut self, max_retries: usize) {
+        self.max_retries = max_retries;
+    }
+
+    /// Return the initial retry delay.
+    #[must_use]
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Set the initial retry delay.
+    pub fn set_initial_delay(&mut self, initial_delay: Duration) {
+        self.initial_delay = initial_delay;
+    }
+
+    /// Return the maximum retry delay.
+    #[must_use]
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Set the maximum retry delay.
+    pub fn set_max_delay(&mut self, max_delay: Duration) {
+        self.max_delay = max_delay;
+    }
+
+    /// Return the jitter.
+    #[must_use]
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    /// Set the jitter, the maximum fraction (clamped to `[0, 1]`) by which a retry delay is randomly reduced.
+    pub fn set_jitter(&mut self, jitter: f64) {
+        self.jitter = jitter.clamp(0.0, 1.0);
+    }
+
+    /// Set the predicate which determines if a failed operation is retried.
+    pub fn set_predicate(&mut self, predicate: RetryPredicate) {
+        self.predicate = predicate;
+    }
+
+    /// Returns the number of retries.
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Returns the delay before retry `retry` (starting from zero).
+    #[allow(clippy::cast_precision_loss)]
+    fn delay(&self, retry: usize) -> Duration {
+        let delay = u32::try_from(retry)
+            .ok()
+            .and_then(|retry| 2u32.checked_pow(retry))
+            .map_or(self.max_delay, |multiplier| {
+                self.initial_delay.saturating_mul(multiplier)
+            })
+            .min(self.max_delay);
+        let random = self
+            .random_state
+            .hash_one((retry, std::time::Instant::now()));
+        let fraction = (random >> 11) as f64 / (1u64 << 53) as f64;
+        delay.mul_f64(1.0 - self.jitter * fraction)
+    }
+
+    /// Returns the delay before the next attempt if an operation which failed with `err` after `retry` retries should be retried.
+    fn should_retry(&self, err: &StorageError, retry: usize) -> Option<Duration> {
+        if retry < self.max_retries && (self.predicate)(err) {
+            self.retries.fetch_add(1, Ordering::Relaxed);
+            Some(self.delay(retry))
+        } else {
+            None
+        }
+    }
+
+    fn retry<T>(&self, operation: impl Fn() -> Result<T, StorageError>) -> Result<T, StorageError> {
+        let mut retry = 0;
+        loop {
+            match operation() {
+                Err(err) => match self.should_retry(&err, retry) {
+                    Some(delay) => std::thread::sleep(delay),
+                    None => return Err(err),
+                },
+                result => return result,
+            }
+            retry += 1;
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_retry<T, F: std::future::Future<Output = Result<T, StorageError>>>(
+        &self,
+        operation: impl Fn() -> F,
+    ) -> Result<T, StorageError> {
+        let mut retry = 0;
+        loop {
+            match operation().await {
+                Err(err) => match self.should_retry(&err, retry) {
+                    Some(delay) => Sleep::new(delay).await,
+                    None => return Err(err),
+                },
+                result => return result,
+            }
+            retry += 1;
+        }
+    }
+
+    fn create_transformer<TStorage: ?Sized + 'static>(
+        self: Arc<Self>,
+        storage: Arc<TStorage>,
+    ) -> Arc<RetryStorageTransformerImpl<TStorage>> {
+        Arc::new(RetryStorageTransformerImpl {
+            storage,
+            transformer: self,
+        })
+    }
+}
+
+impl StorageTransformerExtension for RetryStorageTransformer {
+    /// Returns [`None`], since this storage transformer is not intended to be included in array `storage_transformers` metadata.
+    fn create_metadata(&self) -> Option<Metadata> {
+        None
+    }
+
+    fn create_readable_transformer(self: Arc<Self>, storage: ReadableStorage) -> ReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_writable_transformer(self: Arc<Self>, storage: WritableStorage) -> WritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableStorage,
+    ) -> ReadableWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_listable_transformer(self: Arc<Self>, storage: ListableStorage) -> ListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableListableStorage,
+    ) -> ReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableListableStorage,
+    ) -> ReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableStorage,
+    ) -> AsyncReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_writable_transformer(
+        self: Arc<Self>,
+        storage: AsyncWritableStorage,
+    ) -> AsyncWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncListableStorage,
+    ) -> AsyncListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableListableStorage,
+    ) -> AsyncReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableWritableListableStorage,
+    ) -> AsyncReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+}
+
+#[derive(Debug)]
+struct RetryStorageTransformerImpl<TStorage: ?Sized + 'static> {
+    storage: Arc<TStorage>,
+    transformer: Arc<RetryStorageTransformer>,
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.transformer.retry(|| self.storage.get(key))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.transformer
+            .retry(|| self.storage.get_partial_values_key(key, byte_ranges))
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.transformer
+            .retry(|| self.storage.get_partial_values(key_ranges))
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.transformer.retry(|| self.storage.size())
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.transformer.retry(|| self.storage.size_prefix(prefix))
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.transformer.retry(|| self.storage.size_key(key))
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.transformer.retry(|| self.storage.list())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.transformer.retry(|| self.storage.list_prefix(prefix))
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.transformer.retry(|| self.storage.list_dir(prefix))
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.transformer.retry(|| self.storage.set(key, value))
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        self.transformer
+            .retry(|| self.storage.set_values(key_values))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        self.transformer
+            .retry(|| self.storage.set_partial_values(key_start_values))
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer.retry(|| self.storage.erase(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.transformer.retry(|| self.storage.erase_values(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer.retry(|| self.storage.erase_prefix(prefix))
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    // Conditional writes are not retried, since a retry after a write that succeeded but returned an error would report a failed precondition
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        self.storage.set_if_not_exists(key, value)
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        self.transformer.retry(|| self.storage.etag(key))
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        self.storage.set_if_match(key, value, etag)
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.transformer.async_retry(|| self.storage.get(key)).await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.get_partial_values_key(key, byte_ranges))
+            .await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.get_partial_values(key_ranges))
+            .await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.transformer.async_retry(|| self.storage.size()).await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.size_prefix(prefix))
+            .await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.size_key(key))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.transformer.async_retry(|| self.storage.list()).await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.list_prefix(prefix))
+            .await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.list_dir(prefix))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.set(key, value.clone()))
+            .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.set_partial_values(key_start_values))
+            .await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.erase(key))
+            .await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.erase_values(keys))
+            .await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer
+            .async_retry(|| self.storage.erase_prefix(prefix))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
+    for RetryStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    fn flaky(
+        failures: usize,
+        calls: &AtomicUsize,
+        err: fn() -> StorageError,
+    ) -> Result<usize, StorageError> {
+        let call = calls.fetch_add(1, Ordering::Relaxed);
+        if call < failures {
+            Err(err())
+        } else {
+            Ok(call)
+        }
+    }
+
+    #[test]
+    fn retry_storage_transformer() {
+        let mut transformer = RetryStorageTransformer::new(2, Duration::from_millis(1));
+        transformer.set_jitter(1.0);
+        let transient = || StorageError::Other("timeout".to_string());
+
+        let calls = AtomicUsize::new(0);
+        assert_eq!(
+            transformer.retry(|| flaky(2, &calls, transient)).unwrap(),
+            2
+        );
+        assert_eq!(transformer.retries(), 2);
+
+        let calls = AtomicUsize::new(0);
+        assert!(transformer.retry(|| flaky(3, &calls, transient)).is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        let calls = AtomicUsize::new(0);
+        assert!(matches!(
+            transformer.retry(|| flaky(1, &calls, || StorageError::ReadOnly)),
+            Err(StorageError::ReadOnly)
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        transformer.set_predicate(Arc::new(|err| matches!(err, StorageError::ReadOnly)));
+        let calls = AtomicUsize::new(0);
+        assert!(transformer
+            .retry(|| flaky(1, &calls, || StorageError::ReadOnly))
+            .is_ok());
+
+        transformer.set_max_delay(Duration::from_millis(4));
+        for retry in 0..100 {
+            assert!(transformer.delay(retry) <= Duration::from_millis(4));
+        }
+
+        let store = Arc::new(MemoryStore::new());
+        let storage = Arc::new(transformer).create_readable_writable_listable_transformer(store);
+        let key = StoreKey::new("a").unwrap();
+        storage.set(&key, &[0, 1]).unwrap();
+        assert_eq!(storage.get(&key).unwrap(), Some(vec![0, 1].into()));
+        assert_eq!(storage.list().unwrap(), vec![key]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_retry_storage_transformer() {
+        let transformer = RetryStorageTransformer::new(2, Duration::from_millis(1));
+        let calls = AtomicUsize::new(0);
+        let result = transformer
+            .async_retry(|| async {
+                flaky(2, &calls, || StorageError::Other("timeout".to_string()))
+            })
+            .await;
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(transformer.retries(), 2);
+    }
+}
@@ -0,0 +1,51 @@
+//! A runtime independent async sleep.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+
+/// A runtime independent future which completes after a duration has elapsed on a timer thread.
+pub(crate) struct Sleep {
+    duration: Option<Duration>,
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+impl Sleep {
+    pub(crate) fn new(duration: Duration) -> Self {
+        Self {
+            duration: Some(duration),
+            state: Arc::default(),
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if let Some(duration) = self.duration.take() {
+            let state = self.state.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(duration);
+                let mut state = state.lock();
+                state.0 = true;
+                if let Some(waker) = state.1.take() {
+                    waker.wake();
+                }
+            });
+        }
+        let mut state = self.state.lock();
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,681 @@
+//! A storage transformer which limits the rate of requests and bytes transferred.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::ByteRange,
+    metadata::Metadata,
+    storage::{
+        ETag, ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
+        ReadableStorageTraits, ReadableWritableListableStorage, ReadableWritableStorage,
+        ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage,
+        WritableStorageTraits,
+    },
+};
+
+#[cfg(feature = "async")]
+use crate::storage::{
+    AsyncListableStorage, AsyncListableStorageTraits, AsyncReadableListableStorage,
+    AsyncReadableStorage, AsyncReadableStorageTraits, AsyncReadableWritableListableStorage,
+    AsyncReadableWritableStorageTraits, AsyncWritableStorage, AsyncWritableStorageTraits,
+};
+
+#[cfg(feature = "async")]
+use super::sleep::Sleep;
+use super::StorageTransformerExtension;
+
+/// A rate limiter which admits up to one second of its rate as a burst.
+///
+/// Requests are never rejected, instead they are delayed until the rate limiter has capacity (the generic cell rate algorithm).
+#[derive(Debug, Default)]
+struct RateLimiter {
+    /// The rate per second, or [`None`] if unlimited.
+    rate: Option<f64>,
+    /// The theoretical arrival time of the next request.
+    arrival: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(rate: Option<f64>) -> Self {
+        Self {
+            rate: rate.filter(|rate| *rate > 0.0),
+            arrival: Mutex::default(),
+        }
+    }
+
+    /// Reserve `cost` units and return how long to wait before proceeding.
+    fn reserve(&self, cost: f64) -> Duration {
+        let Some(rate) = self.rate else {
+            return Duration::ZERO;
+        };
+        let now = Instant::now();
+        let mut arrival = self.arrival.lock();
+        let next_arrival =
+            arrival.map_or(now, |arrival| arrival.max(now)) + Duration::from_secs_f64(cost / rate);
+        *arrival = Some(next_arrival);
+        (next_arrival - now).saturating_sub(Duration::from_secs(1))
+    }
+}
+
+/// The throttle storage transformer. Limits the requests per second and bytes per second to the underlying storage.
+///
+/// This storage transformer is for internal use and will not to be included in `storage_transformers` array metadata.
+/// It is intended to avoid tripping the rate limits of storage providers, such as when storing thousands of chunks in parallel.
+///
+/// Reads (including `size` and `list` operations) and writes (including erasures) have separate budgets, and all limits are unlimited by default.
+/// Up to one second of a budget can be used in a burst, after which operations are delayed until the budget has recovered.
+/// The number of bytes read is only known once a read completes, so it delays subsequent reads rather than the read itself.
+#[derive(Debug, Default)]
+pub struct ThrottleStorageTransformer {
+    read_requests: RateLimiter,
+    read_bytes: RateLimiter,
+    write_requests: RateLimiter,
+    write_bytes: RateLimiter,
+    throttled_nanos: AtomicU64,
+}
+
+impl ThrottleStorageTransformer {
+    /// Create a new throttle storage transformer with no limits.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the read requests per second limit.
+    #[must_use]
+    pub fn read_requests_per_second(&self) -> Option<f64> {
+        self.read_requests.rate
+    }
+
+    /// Set the read requests per second limit, or [`None`] for no limit.
+    pub fn set_read_requests_per_second(&mut self, requests_per_second: Option<f64>) {
+        self.read_requests = RateLimiter::new(requests_per_second);
+    }
+
+    /// Return the read bytes per second limit.
+    #[must_use]
+    pub fn read_bytes_per_second(&self) -> Option<f64> {
+        self.read_bytes.rate
+    }
+
+    /// Set the read bytes per second limit, or [`None`] for no limit.
+    pub fn set_read_bytes_per_second(&mut self, bytes_per_second: Option<f64>) {
+        self.read_bytes = RateLimiter::new(bytes_per_second);
+    }
+
+    /// Return the write requests per second limit.
+    #[must_use]
+    pub fn write_requests_per_second(&self) -> Option<f64> {
+        self.write_requests.rate
+    }
+
+    /// Set the write requests per second limit, or [`None`] for no limit.
+    pub fn set_write_requests_per_second(&mut self, requests_per_second: Option<f64>) {
+        self.write_requests = RateLimiter::new(requests_per_second);
+    }
+
+    /// Return the write bytes per second limit.
+    #[must_use]
+    pub fn write_bytes_per_second(&self) -> Option<f64> {
+        self.write_bytes.rate
+    }
+
+    /// Set the write bytes per second limit, or [`None`] for no limit.
+    pub fn set_write_bytes_per_second(&mut self, bytes_per_second: Option<f64>) {
+        self.write_bytes = RateLimiter::new(bytes_per_second);
+    }
+
+    /// Returns the total time that operations have been delayed.
+    pub fn throttled(&self) -> Duration {
+        Duration::from_nanos(self.throttled_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Reserve a read request and return how long to wait before performing it.
+    fn reserve_read(&self) -> Duration {
+        self.throttle(
+            self.read_requests
+                .reserve(1.0)
+                .max(self.read_bytes.reserve(0.0)),
+        )
+    }
+
+    /// Record `bytes` read.
+    #[allow(clippy::cast_precision_loss)]
+    fn record_read(&self, bytes: usize) {
+        self.read_bytes.reserve(bytes as f64);
+    }
+
+    /// Reserve a write request of `bytes` and return how long to wait before performing it.
+    #[allow(clippy::cast_precision_loss)]
+    fn reserve_write(&self, bytes: usize) -> Duration {
+        self.throttle(
+            self.write_requests
+                .reserve(1.0)
+                .max(self.write_bytes.reserve(bytes as f64)),
+        )
+    }
+
+    fn throttle(&self, delay: Duration) -> Duration {
+        if !delay.is_zero() {
+            self.throttled_nanos.fetch_add(
+                u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX),
+                Ordering::Relaxed,
+            );
+        }
+        delay
+    }
+
+    fn read<T>(
+        &self,
+        operation: impl FnOnce() -> Result<T, StorageError>,
+        bytes: impl FnOnce(&T) -> usize,
+    ) -> Result<T, StorageError> {
+        sleep(self.reserve_read());
+        let result = operation();
+        if let Ok(value) = &result {
+            self.record_read(bytes(value));
+        }
+        result
+    }
+
+    fn write<T>(
+        &self,
+        bytes: usize,
+        operation: impl FnOnce() -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        sleep(self.reserve_write(bytes));
+        operation()
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_read<T>(
+        &self,
+        operation: impl std::future::Future<Output = Result<T, StorageError>>,
+        bytes: impl FnOnce(&T) -> usize,
+    ) -> Result<T, StorageError> {
+        async_sleep(self.reserve_read()).await;
+        let result = operation.await;
+        if let Ok(value) = &result {
+            self.record_read(bytes(value));
+        }
+        result
+    }
+
+    #[cfg(feature = "async")]
+    async fn async_write(
+        &self,
+        bytes: usize,
+        operation: impl std::future::Future<Output = Result<(), StorageError>>,
+    ) -> Result<(), StorageError> {
+        async_sleep(self.reserve_write(bytes)).await;
+        operation.await
+    }
+
+    fn create_transformer<TStorage: ?Sized + 'static>(
+        self: Arc<Self>,
+        storage: Arc<TStorage>,
+    ) -> Arc<ThrottleStorageTransformerImpl<TStorage>> {
+        Arc::new(ThrottleStorageTransformerImpl {
+            storage,
+            transformer: self,
+        })
+    }
+}
+
+fn sleep(delay: Duration) {
+    if !delay.is_zero() {
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(feature = "async")]
+async fn async_sleep(delay: Duration) {
+    if !delay.is_zero() {
+        Sleep::new(delay).await;
+    }
+}
+
+fn maybe_bytes_len(value: &MaybeBytes) -> usize {
+    value.as_ref().map_or(0, bytes::Bytes::len)
+}
+
+fn partial_values_len(values: Option<&Vec<Vec<u8>>>) -> usize {
+    values.map_or(0, |values| values.iter().map(Vec::len).sum())
+}
+
+fn maybe_bytes_vec_len(values: &[MaybeBytes]) -> usize {
+    values.iter().map(maybe_bytes_len).sum()
+}
+
+fn key_start_values_len(key_start_values: &[StoreKeyStartValue]) -> usize {
+    key_start_values
+        .iter()
+        .map(|key_start_value| key_start_value.value.len())
+        .sum()
+}
+
+impl StorageTransformerExtension for ThrottleStorageTransformer {
+    /// Returns [`None`], since this storage transformer is not intended to be included in array `storage_transformers` metadata.
+    fn create_metadata(&self) -> Option<Metadata> {
+        None
+    }
+
+    fn create_readable_transformer(self: Arc<Self>, storage: ReadableStorage) -> ReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_writable_transformer(self: Arc<Self>, storage: WritableStorage) -> WritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableStorage,
+    ) -> ReadableWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_listable_transformer(self: Arc<Self>, storage: ListableStorage) -> ListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableListableStorage,
+    ) -> ReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    fn create_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: ReadableWritableListableStorage,
+    ) -> ReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableStorage,
+    ) -> AsyncReadableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_writable_transformer(
+        self: Arc<Self>,
+        storage: AsyncWritableStorage,
+    ) -> AsyncWritableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncListableStorage,
+    ) -> AsyncListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableListableStorage,
+    ) -> AsyncReadableListableStorage {
+        self.create_transformer(storage)
+    }
+
+    #[cfg(feature = "async")]
+    fn create_async_readable_writable_listable_transformer(
+        self: Arc<Self>,
+        storage: AsyncReadableWritableListableStorage,
+    ) -> AsyncReadableWritableListableStorage {
+        self.create_transformer(storage)
+    }
+}
+
+#[derive(Debug)]
+struct ThrottleStorageTransformerImpl<TStorage: ?Sized + 'static> {
+    storage: Arc<TStorage>,
+    transformer: Arc<ThrottleStorageTransformer>,
+}
+
+impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.transformer
+            .read(|| self.storage.get(key), maybe_bytes_len)
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.transformer.read(
+            || self.storage.get_partial_values_key(key, byte_ranges),
+            |values| partial_values_len(values.as_ref()),
+        )
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.transformer.read(
+            || self.storage.get_partial_values(key_ranges),
+            |values| maybe_bytes_vec_len(values),
+        )
+    }
+
+    fn size(&self) -> Result<u64, StorageError> {
+        self.transformer.read(|| self.storage.size(), |_| 0)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.transformer
+            .read(|| self.storage.size_prefix(prefix), |_| 0)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.transformer.read(|| self.storage.size_key(key), |_| 0)
+    }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
+}
+
+impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.transformer.read(|| self.storage.list(), |_| 0)
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.transformer
+            .read(|| self.storage.list_prefix(prefix), |_| 0)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.transformer
+            .read(|| self.storage.list_dir(prefix), |_| 0)
+    }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
+}
+
+impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.transformer
+            .write(value.len(), || self.storage.set(key, value))
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        let bytes = key_values
+            .iter()
+            .map(|key_value| key_value.value().len())
+            .sum();
+        self.transformer
+            .write(bytes, || self.storage.set_values(key_values))
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        self.transformer
+            .write(key_start_values_len(key_start_values), || {
+                self.storage.set_partial_values(key_start_values)
+            })
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer.write(0, || self.storage.erase(key))
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.transformer
+            .write(0, || self.storage.erase_values(keys))
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer
+            .write(0, || self.storage.erase_prefix(prefix))
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        self.transformer
+            .write(value.len(), || self.storage.set_if_not_exists(key, value))
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        self.transformer.read(|| self.storage.etag(key), |_| 0)
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        self.transformer
+            .write(value.len(), || self.storage.set_if_match(key, value, etag))
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
+}
+
+impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        self.transformer
+            .async_read(self.storage.get(key), maybe_bytes_len)
+            .await
+    }
+
+    async fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        self.transformer
+            .async_read(
+                self.storage.get_partial_values_key(key, byte_ranges),
+                |values| partial_values_len(values.as_ref()),
+            )
+            .await
+    }
+
+    async fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.transformer
+            .async_read(self.storage.get_partial_values(key_ranges), |values| {
+                maybe_bytes_vec_len(values)
+            })
+            .await
+    }
+
+    async fn size(&self) -> Result<u64, StorageError> {
+        self.transformer
+            .async_read(self.storage.size(), |_| 0)
+            .await
+    }
+
+    async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        self.transformer
+            .async_read(self.storage.size_prefix(prefix), |_| 0)
+            .await
+    }
+
+    async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        self.transformer
+            .async_read(self.storage.size_key(key), |_| 0)
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    async fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.transformer
+            .async_read(self.storage.list(), |_| 0)
+            .await
+    }
+
+    async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        self.transformer
+            .async_read(self.storage.list_prefix(prefix), |_| 0)
+            .await
+    }
+
+    async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        self.transformer
+            .async_read(self.storage.list_dir(prefix), |_| 0)
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+    async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
+        self.transformer
+            .async_write(value.len(), self.storage.set(key, value))
+            .await
+    }
+
+    async fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        self.transformer
+            .async_write(
+                key_start_values_len(key_start_values),
+                self.storage.set_partial_values(key_start_values),
+            )
+            .await
+    }
+
+    async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.transformer
+            .async_write(0, self.storage.erase(key))
+            .await
+    }
+
+    async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.transformer
+            .async_write(0, self.storage.erase_values(keys))
+            .await
+    }
+
+    async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.transformer
+            .async_write(0, self.storage.erase_prefix(prefix))
+            .await
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
+    for ThrottleStorageTransformerImpl<TStorage>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn rate_limiter() {
+        let unlimited = RateLimiter::new(None);
+        assert!(unlimited.reserve(1e9).is_zero());
+
+        let limiter = RateLimiter::new(Some(10.0));
+        for _ in 0..10 {
+            assert!(limiter.reserve(1.0).is_zero());
+        }
+        let delay = limiter.reserve(1.0);
+        assert!(!delay.is_zero() && delay <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn throttle_storage_transformer() {
+        let store = Arc::new(MemoryStore::new());
+        let key = StoreKey::new("a").unwrap();
+        store.set(&key, &vec![0; 110_000]).unwrap();
+
+        let mut transformer = ThrottleStorageTransformer::new();
+        transformer.set_read_bytes_per_second(Some(100_000.0));
+        transformer.set_write_requests_per_second(Some(1.0));
+        assert_eq!(transformer.read_bytes_per_second(), Some(100_000.0));
+        assert_eq!(transformer.read_requests_per_second(), None);
+        let transformer = Arc::new(transformer);
+        let storage = transformer
+            .clone()
+            .create_readable_writable_listable_transformer(store);
+
+        // The second read waits for the bytes of the first read
+        assert_eq!(storage.get(&key).unwrap().unwrap().len(), 110_000);
+        assert!(transformer.throttled().is_zero());
+        storage.list().unwrap();
+        assert!(!transformer.throttled().is_zero());
+
+        // Writes have a separate budget
+        let throttled = transformer.throttled();
+        storage.set(&key, &[0]).unwrap();
+        assert_eq!(transformer.throttled(), throttled);
+    }
+}
@@ -1,8 +1,13 @@
-//! A storage transformer which prints function calls.
+//! A storage transformer which logs function calls.
 
 use std::{
     io::Write,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::ThreadId,
+    time::{Duration, Instant},
 };
 
 use itertools::Itertools;
@@ -12,10 +17,11 @@ use crate::{
     byte_range::ByteRange,
     metadata::Metadata,
     storage::{
-        ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
+        ETag, ListableStorage, ListableStorageTraits, ReadableListableStorage, ReadableStorage,
         ReadableStorageTraits, ReadableWritableListableStorage, ReadableWritableStorage,
         ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
-        StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage, WritableStorageTraits,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorage,
+        WritableStorageTraits,
     },
 };
 
@@ -63,9 +69,115 @@ use super::StorageTransformerExtension;
 /// [23:41:19.891] get(group/array/zarr.json) -> len=Ok(1315)
 /// [23:41:19.892] list() -> [group/array/c/0/0, group/array/c/1/0, group/array/zarr.json, group/zarr.json]
 /// ```
+///
+/// ### Example (structured records)
+/// [`UsageLogStorageTransformer::new_with_callback`] passes a [`UsageLogRecord`] to a callback for each storage method call instead.
+/// The callback could aggregate records or forward them to a logging framework such as `log` or `tracing`.
+/// ```rust
+/// # use std::sync::{Arc, Mutex};
+/// # use zarrs::storage::store::MemoryStore;
+/// # use zarrs::storage::storage_transformer::{UsageLogStorageTransformer, StorageTransformerExtension};
+/// let store = Arc::new(MemoryStore::new());
+/// let mut usage_log = UsageLogStorageTransformer::new_with_callback(|record| {
+///     println!(
+///         "{:?} {:?} bytes={} duration={:?}",
+///         record.operation, record.keys, record.bytes, record.duration
+///     );
+/// });
+/// usage_log.set_sample_interval(10); // log every 10th call
+/// let store = Arc::new(usage_log).create_readable_writable_transformer(store);
+/// ````
 pub struct UsageLogStorageTransformer {
-    handle: Arc<Mutex<dyn Write + Send + Sync>>,
-    prefix_func: fn() -> String,
+    sink: UsageLogSink,
+    sample_interval: usize,
+    count: Arc<AtomicUsize>,
+}
+
+/// A storage method call recorded by the [`UsageLogStorageTransformer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UsageLogOperation {
+    /// [`get`](ReadableStorageTraits::get).
+    Get,
+    /// [`get_partial_values_key`](ReadableStorageTraits::get_partial_values_key).
+    GetPartialValuesKey,
+    /// [`get_partial_values`](ReadableStorageTraits::get_partial_values).
+    GetPartialValues,
+    /// [`size`](ReadableStorageTraits::size).
+    Size,
+    /// [`size_prefix`](ReadableStorageTraits::size_prefix).
+    SizePrefix,
+    /// [`size_key`](ReadableStorageTraits::size_key).
+    SizeKey,
+    /// [`list`](ListableStorageTraits::list).
+    List,
+    /// [`list_prefix`](ListableStorageTraits::list_prefix).
+    ListPrefix,
+    /// [`list_dir`](ListableStorageTraits::list_dir).
+    ListDir,
+    /// [`set`](WritableStorageTraits::set).
+    Set,
+    /// [`set_values`](WritableStorageTraits::set_values).
+    SetValues,
+    /// [`set_partial_values`](WritableStorageTraits::set_partial_values).
+    SetPartialValues,
+    /// [`erase`](WritableStorageTraits::erase).
+    Erase,
+    /// [`erase_values`](WritableStorageTraits::erase_values).
+    EraseValues,
+    /// [`erase_prefix`](WritableStorageTraits::erase_prefix).
+    ErasePrefix,
+    /// [`set_if_not_exists`](WritableStorageTraits::set_if_not_exists).
+    SetIfNotExists,
+    /// [`etag`](WritableStorageTraits::etag).
+    ETag,
+    /// [`set_if_match`](WritableStorageTraits::set_if_match).
+    SetIfMatch,
+}
+
+/// A structured record of a storage method call, passed to the callback of a [`UsageLogStorageTransformer`].
+#[derive(Debug, Clone)]
+pub struct UsageLogRecord {
+    /// The storage method.
+    pub operation: UsageLogOperation,
+    /// The keys accessed by the method (deduplicated), or empty if the method operates on a prefix or the whole store.
+    pub keys: Vec<StoreKey>,
+    /// The prefix accessed by the method, if any.
+    pub prefix: Option<StorePrefix>,
+    /// The number of value bytes read or written.
+    pub bytes: usize,
+    /// The duration of the method call.
+    pub duration: Duration,
+    /// The thread which called the method.
+    pub thread: ThreadId,
+    /// True if the method call succeeded.
+    pub success: bool,
+}
+
+impl UsageLogRecord {
+    fn new(operation: UsageLogOperation, duration: Duration, success: bool) -> Self {
+        Self {
+            operation,
+            keys: vec![],
+            prefix: None,
+            bytes: 0,
+            duration,
+            thread: std::thread::current().id(),
+            success,
+        }
+    }
+}
+
+/// A usage log callback.
+type UsageLogCallback = Arc<dyn Fn(&UsageLogRecord) + Send + Sync>;
+
+/// The destination of usage log entries.
+#[derive(Clone)]
+enum UsageLogSink {
+    Writer {
+        handle: Arc<Mutex<dyn Write + Send + Sync>>,
+        prefix_func: fn() -> String,
+    },
+    Callback(UsageLogCallback),
 }
 
 impl core::fmt::Debug for UsageLogStorageTransformer {
@@ -75,22 +187,52 @@ impl core::fmt::Debug for UsageLogStorageTransformer {
 }
 
 impl UsageLogStorageTransformer {
-    /// Create a new usage log storage transformer.
+    /// Create a new usage log storage transformer which writes a line to `handle` for each storage method call.
+    ///
+    /// Each line starts with the output of `prefix_func` (e.g. a timestamp).
     pub fn new(handle: Arc<Mutex<dyn Write + Send + Sync>>, prefix_func: fn() -> String) -> Self {
         Self {
-            handle,
-            prefix_func,
+            sink: UsageLogSink::Writer {
+                handle,
+                prefix_func,
+            },
+            sample_interval: 1,
+            count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Create a new usage log storage transformer which passes a [`UsageLogRecord`] to `callback` for each storage method call.
+    pub fn new_with_callback(callback: impl Fn(&UsageLogRecord) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: UsageLogSink::Callback(Arc::new(callback)),
+            sample_interval: 1,
+            count: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Returns the sample interval.
+    #[must_use]
+    pub fn sample_interval(&self) -> usize {
+        self.sample_interval
+    }
+
+    /// Set the sample interval. Only every `sample_interval`th storage method call is logged. Defaults to 1 (all calls are logged).
+    ///
+    /// The count of calls is shared by all storage created by this transformer.
+    /// A sample interval of 0 is treated as 1.
+    pub fn set_sample_interval(&mut self, sample_interval: usize) {
+        self.sample_interval = sample_interval.max(1);
+    }
+
     fn create_transformer<TStorage: ?Sized>(
         &self,
         storage: Arc<TStorage>,
     ) -> Arc<UsageLogStorageTransformerImpl<TStorage>> {
         Arc::new(UsageLogStorageTransformerImpl {
             storage,
-            prefix_func: self.prefix_func,
-            handle: self.handle.clone(),
+            sink: self.sink.clone(),
+            sample_interval: self.sample_interval,
+            count: self.count.clone(),
         })
     }
 }
@@ -177,22 +319,88 @@ impl StorageTransformerExtension for UsageLogStorageTransformer {
 
 struct UsageLogStorageTransformerImpl<TStorage: ?Sized> {
     storage: Arc<TStorage>,
-    prefix_func: fn() -> String,
-    handle: Arc<Mutex<dyn Write + Send + Sync>>,
+    sink: UsageLogSink,
+    sample_interval: usize,
+    count: Arc<AtomicUsize>,
+}
+
+impl<TStorage: ?Sized> UsageLogStorageTransformerImpl<TStorage> {
+    /// Log a storage method call if it is sampled.
+    ///
+    /// `message` is written for a writer sink, and `record` is passed to a callback sink.
+    fn log(
+        &self,
+        message: impl FnOnce() -> String,
+        record: impl FnOnce() -> UsageLogRecord,
+    ) -> Result<(), StorageError> {
+        if self.count.fetch_add(1, Ordering::Relaxed) % self.sample_interval != 0 {
+            return Ok(());
+        }
+        match &self.sink {
+            UsageLogSink::Writer {
+                handle,
+                prefix_func,
+            } => writeln!(handle.lock().unwrap(), "{}{}", prefix_func(), message())?,
+            UsageLogSink::Callback(callback) => callback(&record()),
+        }
+        Ok(())
+    }
+}
+
+fn maybe_bytes_len(value: &MaybeBytes) -> usize {
+    value.as_ref().map_or(0, bytes::Bytes::len)
+}
+
+fn key_ranges_keys(key_ranges: &[StoreKeyRange]) -> Vec<StoreKey> {
+    key_ranges
+        .iter()
+        .map(|key_range| key_range.key.clone())
+        .unique()
+        .collect()
+}
+
+fn key_start_values_keys(key_start_values: &[StoreKeyStartValue]) -> Vec<StoreKey> {
+    key_start_values
+        .iter()
+        .map(|key_start_value| key_start_value.key.clone())
+        .unique()
+        .collect()
+}
+
+fn key_start_values_len(key_start_values: &[StoreKeyStartValue]) -> usize {
+    key_start_values
+        .iter()
+        .map(|key_start_value| key_start_value.value.len())
+        .sum()
+}
+
+/// Format the keys and prefixes of a [`StoreKeysPrefixes`] result.
+fn format_list_dir(result: &Result<StoreKeysPrefixes, StorageError>) -> (String, String) {
+    (
+        result.as_ref().map_or(String::new(), |skp| {
+            skp.keys().iter().format(", ").to_string()
+        }),
+        result.as_ref().map_or(String::new(), |skp| {
+            skp.prefixes().iter().format(", ").to_string()
+        }),
+    )
 }
 
 impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get({key}) -> len={:?}",
-            (self.prefix_func)(),
-            result
-                .as_ref()
-                .map(|v| v.as_ref().map_or(0, std::vec::Vec::len))
+        let duration = start.elapsed();
+        let len = result.as_ref().map(maybe_bytes_len);
+        self.log(
+            || format!("get({key}) -> len={len:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: len.as_ref().map_or(0, |len| *len),
+                ..UsageLogRecord::new(UsageLogOperation::Get, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -202,16 +410,29 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[ByteRange],
     ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values_key(key, byte_ranges);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values_key({key}, [{}]) -> len={:?}",
-            (self.prefix_func)(),
-            byte_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.as_ref()
-                    .map_or(vec![], |v| v.iter().map(std::vec::Vec::len).collect_vec())
-            })
+        let duration = start.elapsed();
+        let len = result.as_ref().map(|v| {
+            v.as_ref()
+                .map_or(vec![], |v| v.iter().map(Vec::len).collect_vec())
+        });
+        self.log(
+            || {
+                format!(
+                    "get_partial_values_key({key}, [{}]) -> len={len:?}",
+                    byte_ranges.iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: len.as_ref().map_or(0, |len| len.iter().sum()),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::GetPartialValuesKey,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
@@ -220,107 +441,190 @@ impl<TStorage: ?Sized + ReadableStorageTraits> ReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values(key_ranges);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values([{}]) -> len={:?}",
-            (self.prefix_func)(),
-            key_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.iter()
-                    .map(|v| v.iter().map(std::vec::Vec::len).collect_vec())
-            })
+        let duration = start.elapsed();
+        let len = result.as_ref().map(|v| {
+            v.iter()
+                .map(|v| v.iter().map(bytes::Bytes::len).collect_vec())
+                .collect_vec()
+        });
+        self.log(
+            || {
+                format!(
+                    "get_partial_values([{}]) -> len={len:?}",
+                    key_ranges.iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                keys: key_ranges_keys(key_ranges),
+                bytes: len.as_ref().map_or(0, |len| len.iter().flatten().sum()),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::GetPartialValues,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
 
     fn size(&self) -> Result<u64, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size();
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size() -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("size() -> {result:?}"),
+            || UsageLogRecord::new(UsageLogOperation::Size, duration, result.is_ok()),
         )?;
         result
     }
 
     fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        let result: Result<u64, StorageError> = self.storage.size_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
+        let start = Instant::now();
+        let result = self.storage.size_prefix(prefix);
+        let duration = start.elapsed();
+        self.log(
+            || format!("size_prefix({prefix}) -> {result:?}"),
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::SizePrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size_key(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_key({key}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("size_key({key}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                ..UsageLogRecord::new(UsageLogOperation::SizeKey, duration, result.is_ok())
+            },
         )?;
         result
     }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.storage.supports_partial_reads()
+    }
+
+    fn supports_sizes(&self) -> bool {
+        self.storage.supports_sizes()
+    }
 }
 
 impl<TStorage: ?Sized + ListableStorageTraits> ListableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
     fn list(&self) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list();
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list() -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "list() -> [{}]",
+                    result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+                )
+            },
+            || UsageLogRecord::new(UsageLogOperation::List, duration, result.is_ok()),
         )?;
         result
     }
 
     fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_prefix({prefix}) -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "list_prefix({prefix}) -> [{}]",
+                    result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ListPrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_dir(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_dir({prefix}) -> (keys:[{}], prefixes:[{}])",
-            (self.prefix_func)(),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .keys()
-                .iter()
-                .format(", ")
-                .to_string()),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .prefixes()
-                .iter()
-                .format(", ")
-                .to_string()),
+        let duration = start.elapsed();
+        self.log(
+            || {
+                let (keys, prefixes) = format_list_dir(&result);
+                format!("list_dir({prefix}) -> (keys:[{keys}], prefixes:[{prefixes}])")
+            },
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ListDir, duration, result.is_ok())
+            },
         )?;
         result
     }
+
+    fn supports_listing(&self) -> bool {
+        self.storage.supports_listing()
+    }
 }
 
 impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
     fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.set(key, value);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set({key}, len={}) -> {result:?}",
-            (self.prefix_func)(),
-            value.len()
+        let duration = start.elapsed();
+        self.log(
+            || format!("set({key}, len={}) -> {result:?}", value.len()),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: value.len(),
+                ..UsageLogRecord::new(UsageLogOperation::Set, duration, result.is_ok())
+            },
+        )?;
+        result
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        let start = Instant::now();
+        let result = self.storage.set_values(key_values);
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "set_values([{}]) -> {result:?}",
+                    key_values
+                        .iter()
+                        .map(|key_value| format!(
+                            "{}:len={}",
+                            key_value.key(),
+                            key_value.value().len()
+                        ))
+                        .format(", ")
+                )
+            },
+            || UsageLogRecord {
+                keys: key_values
+                    .iter()
+                    .map(|key_value| key_value.key().clone())
+                    .unique()
+                    .collect(),
+                bytes: key_values
+                    .iter()
+                    .map(|key_value| key_value.value().len())
+                    .sum(),
+                ..UsageLogRecord::new(UsageLogOperation::SetValues, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -329,60 +633,145 @@ impl<TStorage: ?Sized + WritableStorageTraits> WritableStorageTraits
         &self,
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.set_partial_values(key_start_values);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set_partial_values({key_start_values:?}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("set_partial_values({key_start_values:?}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: key_start_values_keys(key_start_values),
+                bytes: key_start_values_len(key_start_values),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::SetPartialValues,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
 
     fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase(key);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase({key}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase({key}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                ..UsageLogRecord::new(UsageLogOperation::Erase, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_values(keys);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_values([{}]) -> {result:?}",
-            keys.iter().format(", "),
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase_values([{}]) -> {result:?}", keys.iter().format(", ")),
+            || UsageLogRecord {
+                keys: keys.iter().unique().cloned().collect(),
+                ..UsageLogRecord::new(UsageLogOperation::EraseValues, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_prefix(prefix);
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase_prefix({prefix}) -> {result:?}"),
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ErasePrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        self.storage.supports_set_if_not_exists()
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        self.storage.supports_set_if_match()
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        let start = Instant::now();
+        let result = self.storage.set_if_not_exists(key, value);
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "set_if_not_exists({key}, len={}) -> {result:?}",
+                    value.len()
+                )
+            },
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: value.len(),
+                ..UsageLogRecord::new(UsageLogOperation::SetIfNotExists, duration, result.is_ok())
+            },
+        )?;
+        result
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        let start = Instant::now();
+        let result = self.storage.etag(key);
+        let duration = start.elapsed();
+        self.log(
+            || format!("etag({key}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                ..UsageLogRecord::new(UsageLogOperation::ETag, duration, result.is_ok())
+            },
+        )?;
+        result
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        let start = Instant::now();
+        let result = self.storage.set_if_match(key, value, etag);
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "set_if_match({key}, len={}, {etag}) -> {result:?}",
+                    value.len()
+                )
+            },
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: value.len(),
+                ..UsageLogRecord::new(UsageLogOperation::SetIfMatch, duration, result.is_ok())
+            },
+        )?;
+        result
+    }
+
+    fn supports_partial_writes(&self) -> bool {
+        self.storage.supports_partial_writes()
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        self.storage.supports_atomic_writes()
+    }
 }
 
 impl<TStorage: ?Sized + ReadableWritableStorageTraits> ReadableWritableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
-    // fn mutex(&self, key: &StoreKey) -> Result<StoreKeyMutex, StorageError> {
-    //     let result = self.storage.mutex(key);
-    //     writeln!(
-    //         self.handle.lock().unwrap(),
-    //         "{}mutex({key}) -> {:?}",
-    //         (self.prefix_func)(),
-    //         result.as_ref().map_or((), |_| ())
-    //     )?;
-    //     result
-    // }
 }
 
 #[cfg(feature = "async")]
@@ -391,14 +780,17 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
     async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get({key}) -> len={:?}",
-            (self.prefix_func)(),
-            result
-                .as_ref()
-                .map(|v| v.as_ref().map_or(0, std::vec::Vec::len))
+        let duration = start.elapsed();
+        let len = result.as_ref().map(maybe_bytes_len);
+        self.log(
+            || format!("get({key}) -> len={len:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: len.as_ref().map_or(0, |len| *len),
+                ..UsageLogRecord::new(UsageLogOperation::Get, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -408,16 +800,29 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         key: &StoreKey,
         byte_ranges: &[ByteRange],
     ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values_key(key, byte_ranges).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values_key({key}, [{}]) -> len={:?}",
-            (self.prefix_func)(),
-            byte_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.as_ref()
-                    .map_or(vec![], |v| v.iter().map(std::vec::Vec::len).collect_vec())
-            })
+        let duration = start.elapsed();
+        let len = result.as_ref().map(|v| {
+            v.as_ref()
+                .map_or(vec![], |v| v.iter().map(Vec::len).collect_vec())
+        });
+        self.log(
+            || {
+                format!(
+                    "get_partial_values_key({key}, [{}]) -> len={len:?}",
+                    byte_ranges.iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: len.as_ref().map_or(0, |len| len.iter().sum()),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::GetPartialValuesKey,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
@@ -426,46 +831,69 @@ impl<TStorage: ?Sized + AsyncReadableStorageTraits> AsyncReadableStorageTraits
         &self,
         key_ranges: &[StoreKeyRange],
     ) -> Result<Vec<MaybeBytes>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.get_partial_values(key_ranges).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}get_partial_values([{}]) -> len={:?}",
-            (self.prefix_func)(),
-            key_ranges.iter().format(", "),
-            result.as_ref().map(|v| {
-                v.iter()
-                    .map(|v| v.iter().map(std::vec::Vec::len).collect_vec())
-            })
+        let duration = start.elapsed();
+        let len = result.as_ref().map(|v| {
+            v.iter()
+                .map(|v| v.iter().map(bytes::Bytes::len).collect_vec())
+                .collect_vec()
+        });
+        self.log(
+            || {
+                format!(
+                    "get_partial_values([{}]) -> len={len:?}",
+                    key_ranges.iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                keys: key_ranges_keys(key_ranges),
+                bytes: len.as_ref().map_or(0, |len| len.iter().flatten().sum()),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::GetPartialValues,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
 
     async fn size(&self) -> Result<u64, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size().await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size() -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("size() -> {result:?}"),
+            || UsageLogRecord::new(UsageLogOperation::Size, duration, result.is_ok()),
         )?;
         result
     }
 
     async fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
-        let result: Result<u64, StorageError> = self.storage.size_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
+        let start = Instant::now();
+        let result = self.storage.size_prefix(prefix).await;
+        let duration = start.elapsed();
+        self.log(
+            || format!("size_prefix({prefix}) -> {result:?}"),
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::SizePrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     async fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let start = Instant::now();
         let result = self.storage.size_key(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}size_key({key}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("size_key({key}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                ..UsageLogRecord::new(UsageLogOperation::SizeKey, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -477,43 +905,53 @@ impl<TStorage: ?Sized + AsyncListableStorageTraits> AsyncListableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
     async fn list(&self) -> Result<StoreKeys, StorageError> {
-        let keys = self.storage.list().await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list() -> [{}]",
-            (self.prefix_func)(),
-            keys.as_ref().unwrap_or(&vec![]).iter().format(", "),
+        let start = Instant::now();
+        let result = self.storage.list().await;
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "list() -> [{}]",
+                    result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+                )
+            },
+            || UsageLogRecord::new(UsageLogOperation::List, duration, result.is_ok()),
         )?;
-        keys
+        result
     }
 
     async fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_prefix({prefix}) -> [{}]",
-            (self.prefix_func)(),
-            result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+        let duration = start.elapsed();
+        self.log(
+            || {
+                format!(
+                    "list_prefix({prefix}) -> [{}]",
+                    result.as_ref().unwrap_or(&vec![]).iter().format(", ")
+                )
+            },
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ListPrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     async fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let start = Instant::now();
         let result = self.storage.list_dir(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}list_dir({prefix}) -> (keys:[{}], prefixes:[{}])",
-            (self.prefix_func)(),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .keys()
-                .iter()
-                .format(", ")
-                .to_string()),
-            result.as_ref().map_or(String::new(), |skp| skp
-                .prefixes()
-                .iter()
-                .format(", ")
-                .to_string()),
+        let duration = start.elapsed();
+        self.log(
+            || {
+                let (keys, prefixes) = format_list_dir(&result);
+                format!("list_dir({prefix}) -> (keys:[{keys}], prefixes:[{prefixes}])")
+            },
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ListDir, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -526,11 +964,16 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
 {
     async fn set(&self, key: &StoreKey, value: bytes::Bytes) -> Result<(), StorageError> {
         let len = value.len();
+        let start = Instant::now();
         let result = self.storage.set(key, value).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set({key}, len={len}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("set({key}, len={len}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                bytes: len,
+                ..UsageLogRecord::new(UsageLogOperation::Set, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -539,42 +982,62 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
         &self,
         key_start_values: &[StoreKeyStartValue],
     ) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.set_partial_values(key_start_values).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}set_partial_values({key_start_values:?}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("set_partial_values({key_start_values:?}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: key_start_values_keys(key_start_values),
+                bytes: key_start_values_len(key_start_values),
+                ..UsageLogRecord::new(
+                    UsageLogOperation::SetPartialValues,
+                    duration,
+                    result.is_ok(),
+                )
+            },
         )?;
         result
     }
 
     async fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase(key).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase({key}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase({key}) -> {result:?}"),
+            || UsageLogRecord {
+                keys: vec![key.clone()],
+                ..UsageLogRecord::new(UsageLogOperation::Erase, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     async fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_values(keys).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_values([{}]) -> {result:?}",
-            (self.prefix_func)(),
-            keys.iter().format(", ")
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase_values([{}]) -> {result:?}", keys.iter().format(", ")),
+            || UsageLogRecord {
+                keys: keys.iter().unique().cloned().collect(),
+                ..UsageLogRecord::new(UsageLogOperation::EraseValues, duration, result.is_ok())
+            },
         )?;
         result
     }
 
     async fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        let start = Instant::now();
         let result = self.storage.erase_prefix(prefix).await;
-        writeln!(
-            self.handle.lock().unwrap(),
-            "{}erase_prefix({prefix}) -> {result:?}",
-            (self.prefix_func)()
+        let duration = start.elapsed();
+        self.log(
+            || format!("erase_prefix({prefix}) -> {result:?}"),
+            || UsageLogRecord {
+                prefix: Some(prefix.clone()),
+                ..UsageLogRecord::new(UsageLogOperation::ErasePrefix, duration, result.is_ok())
+            },
         )?;
         result
     }
@@ -585,14 +1048,49 @@ impl<TStorage: ?Sized + AsyncWritableStorageTraits> AsyncWritableStorageTraits
 impl<TStorage: ?Sized + AsyncReadableWritableStorageTraits> AsyncReadableWritableStorageTraits
     for UsageLogStorageTransformerImpl<TStorage>
 {
-    // async fn mutex(&self, key: &StoreKey) -> Result<AsyncStoreKeyMutex, StorageError> {
-    //     let result = self.storage.mutex(key).await;
-    //     writeln!(
-    //         self.handle.lock().unwrap(),
-    //         "{}mutex({key}) -> {:?}",
-    //         (self.prefix_func)(),
-    //         result.as_ref().map_or((), |_| ())
-    //     )?;
-    //     result
-    // }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::store::MemoryStore;
+
+    use super::*;
+
+    #[test]
+    fn usage_log_storage_transformer_callback() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let mut usage_log = UsageLogStorageTransformer::new_with_callback({
+            let records = records.clone();
+            move |record| records.lock().unwrap().push(record.clone())
+        });
+        usage_log.set_sample_interval(2);
+        let storage = Arc::new(usage_log)
+            .create_readable_writable_listable_transformer(Arc::new(MemoryStore::new()));
+
+        let key_a = StoreKey::new("a").unwrap();
+        let key_b = StoreKey::new("b").unwrap();
+        storage.set(&key_a, &[0; 4]).unwrap(); // sampled
+        storage.set(&key_b, &[0; 8]).unwrap();
+        storage.get(&key_a).unwrap(); // sampled
+        storage.get(&key_b).unwrap();
+        storage.list_dir(&StorePrefix::root()).unwrap(); // sampled
+
+        let records = records.lock().unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| (record.operation, record.keys.clone(), record.bytes))
+                .collect_vec(),
+            vec![
+                (UsageLogOperation::Set, vec![key_a.clone()], 4),
+                (UsageLogOperation::Get, vec![key_a], 4),
+                (UsageLogOperation::ListDir, vec![], 0),
+            ]
+        );
+        assert_eq!(records[2].prefix, Some(StorePrefix::root()));
+        assert!(records.iter().all(|record| record.success));
+        assert!(records
+            .iter()
+            .all(|record| record.thread == std::thread::current().id()));
+    }
 }
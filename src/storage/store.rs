@@ -10,10 +10,18 @@ mod store_sync;
 
 pub use store_sync::filesystem_store::{FilesystemStore, FilesystemStoreCreateError};
 pub use store_sync::memory_store::MemoryStore;
+pub use store_sync::reference_store::{Reference, ReferenceStore, ReferenceStoreCreateError};
 
 #[cfg(feature = "http")]
 pub use store_sync::http_store::{HTTPStore, HTTPStoreCreateError};
 
+#[cfg(feature = "lmdb")]
+pub use store_sync::lmdb::{LmdbStore, LmdbStoreCreateError};
+
+#[cfg(feature = "azure")]
+pub use store_async::object_store::AsyncAzureBlobStore;
+#[cfg(feature = "gcp")]
+pub use store_async::object_store::AsyncGoogleCloudStore;
 #[cfg(feature = "object_store")]
 pub use store_async::object_store::AsyncObjectStore;
 
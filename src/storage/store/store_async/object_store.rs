@@ -60,13 +60,59 @@ impl<T: object_store::ObjectStore> AsyncObjectStore<T> {
     // }
 }
 
+/// An asynchronous store backed by [Google Cloud Storage](object_store::gcp).
+///
+/// Create with [`from_env`](AsyncGoogleCloudStore::from_env), or with [`new`](AsyncObjectStore::new) and a [`GoogleCloudStorageBuilder`](object_store::gcp::GoogleCloudStorageBuilder) for other credential configurations.
+#[cfg(feature = "gcp")]
+pub type AsyncGoogleCloudStore = AsyncObjectStore<object_store::gcp::GoogleCloudStorage>;
+
+#[cfg(feature = "gcp")]
+impl AsyncGoogleCloudStore {
+    /// Create a new Google Cloud Storage store for `bucket_name`, configured from `GOOGLE_*` environment variables.
+    ///
+    /// Credentials are read from `GOOGLE_SERVICE_ACCOUNT` (a service account file path), `GOOGLE_SERVICE_ACCOUNT_KEY` (a serialised service account key), or application default credentials.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the store cannot be configured.
+    pub fn from_env(bucket_name: &str) -> Result<Self, StorageError> {
+        Ok(Self::new(
+            object_store::gcp::GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(bucket_name)
+                .build()?,
+        ))
+    }
+}
+
+/// An asynchronous store backed by [Azure Blob Storage](object_store::azure).
+///
+/// Create with [`from_env`](AsyncAzureBlobStore::from_env), or with [`new`](AsyncObjectStore::new) and a [`MicrosoftAzureBuilder`](object_store::azure::MicrosoftAzureBuilder) for other credential configurations.
+#[cfg(feature = "azure")]
+pub type AsyncAzureBlobStore = AsyncObjectStore<object_store::azure::MicrosoftAzure>;
+
+#[cfg(feature = "azure")]
+impl AsyncAzureBlobStore {
+    /// Create a new Azure Blob Storage store for `container_name`, configured from `AZURE_*` environment variables.
+    ///
+    /// The account is read from `AZURE_STORAGE_ACCOUNT_NAME`, and credentials from `AZURE_STORAGE_ACCOUNT_KEY`, `AZURE_STORAGE_SAS_KEY`, service principal variables (e.g. `AZURE_CLIENT_ID`), or managed identity.
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the store cannot be configured.
+    pub fn from_env(container_name: &str) -> Result<Self, StorageError> {
+        Ok(Self::new(
+            object_store::azure::MicrosoftAzureBuilder::from_env()
+                .with_container_name(container_name)
+                .build()?,
+        ))
+    }
+}
+
 #[async_trait::async_trait]
 impl<T: object_store::ObjectStore> AsyncReadableStorageTraits for AsyncObjectStore<T> {
     async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
         let get = handle_result(self.object_store.get(&key_to_path(key)).await)?;
         if let Some(get) = get {
             let bytes = get.bytes().await?;
-            Ok(Some(bytes.to_vec()))
+            Ok(Some(bytes))
         } else {
             Ok(None)
         }
@@ -182,6 +228,33 @@ impl<T: object_store::ObjectStore> AsyncWritableStorageTraits for AsyncObjectSto
             .await?;
         Ok(())
     }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        true
+    }
+
+    async fn set_if_not_exists(
+        &self,
+        key: &StoreKey,
+        value: bytes::Bytes,
+    ) -> Result<bool, StorageError> {
+        match self
+            .object_store
+            .put_opts(
+                &key_to_path(key),
+                value,
+                object_store::PutMode::Create.into(),
+            )
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::AlreadyExists { .. }) => Ok(false),
+            Err(err @ object_store::Error::NotImplemented) => {
+                Err(StorageError::Unsupported(err.to_string()))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
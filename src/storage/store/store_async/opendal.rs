@@ -53,7 +53,12 @@ fn handle_result<T>(result: Result<T, opendal::Error>) -> Result<Option<T>, Stor
 #[async_trait::async_trait]
 impl AsyncReadableStorageTraits for AsyncOpendalStore {
     async fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
-        handle_result(self.operator.read(key.as_str()).await)
+        handle_result(
+            self.operator
+                .read(key.as_str())
+                .await
+                .map(bytes::Bytes::from),
+        )
     }
 
     async fn get_partial_values_key(
@@ -1,9 +1,13 @@
 pub mod filesystem_store;
 pub mod memory_store;
+pub mod reference_store;
 
 #[cfg(feature = "http")]
 pub mod http_store;
 
+#[cfg(feature = "lmdb")]
+pub mod lmdb;
+
 #[cfg(feature = "opendal")]
 pub mod opendal;
 
@@ -14,7 +18,7 @@ mod test_util {
     use crate::{
         byte_range::ByteRange,
         storage::{
-            ListableStorageTraits, ReadableStorageTraits, StoreKeyRange, StoreKeyStartValue,
+            ETag, ListableStorageTraits, ReadableStorageTraits, StoreKeyRange, StoreKeyStartValue,
             StorePrefix, WritableStorageTraits,
         },
     };
@@ -61,7 +65,7 @@ mod test_util {
     pub fn store_read<T: ReadableStorageTraits>(store: &T) -> Result<(), Box<dyn Error>> {
         assert!(store.get(&"notfound".try_into()?)?.is_none());
         assert!(store.size_key(&"notfound".try_into()?)?.is_none());
-        assert_eq!(store.get(&"a/b".try_into()?)?, Some(vec![0, 1, 2]));
+        assert_eq!(store.get(&"a/b".try_into()?)?, Some(vec![0, 1, 2].into()));
         assert_eq!(store.size_key(&"a/b".try_into()?)?, Some(3));
         assert_eq!(store.size_key(&"a/c".try_into()?)?, Some(1));
         assert_eq!(store.size_key(&"i/j/k".try_into()?)?, Some(2));
@@ -81,7 +85,11 @@ mod test_util {
                 StoreKeyRange::new("a/b".try_into()?, ByteRange::FromEnd(1, Some(2))),
                 StoreKeyRange::new("i/j/k".try_into()?, ByteRange::FromStart(1, Some(1))),
             ])?,
-            vec![Some(vec![1, 2]), Some(vec![0, 1]), Some(vec![1])]
+            vec![
+                Some(vec![1, 2].into()),
+                Some(vec![0, 1].into()),
+                Some(vec![1].into())
+            ]
         );
         assert!(store
             .get_partial_values(&[StoreKeyRange::new(
@@ -135,4 +143,30 @@ mod test_util {
         }
         Ok(())
     }
+    pub fn store_conditional_write<T: ReadableStorageTraits + WritableStorageTraits>(
+        store: &T,
+    ) -> Result<(), Box<dyn Error>> {
+        assert!(store.supports_set_if_not_exists());
+        assert!(store.supports_set_if_match());
+        let key = "conditional".try_into()?;
+        store.erase(&key)?;
+
+        assert!(store.etag(&key)?.is_none());
+        assert!(!store.set_if_match(&key, &[0], &ETag::new("missing"))?);
+        assert!(store.get(&key)?.is_none());
+
+        assert!(store.set_if_not_exists(&key, &[0, 1])?);
+        assert!(!store.set_if_not_exists(&key, &[2])?);
+        assert_eq!(store.get(&key)?, Some(vec![0, 1].into()));
+
+        let etag = store.etag(&key)?.unwrap();
+        assert!(store.set_if_match(&key, &[3], &etag)?);
+        assert_eq!(store.get(&key)?, Some(vec![3].into()));
+        assert!(!store.set_if_match(&key, &[4], &etag)?);
+        assert_eq!(store.get(&key)?, Some(vec![3].into()));
+        assert_ne!(store.etag(&key)?, Some(etag));
+
+        store.erase(&key)?;
+        Ok(())
+    }
 }
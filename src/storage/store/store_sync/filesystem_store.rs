@@ -6,7 +6,7 @@ use crate::{
     array::MaybeBytes,
     byte_range::{ByteOffset, ByteRange},
     storage::{
-        store_set_partial_values, ListableStorageTraits, ReadableStorageTraits,
+        store_set_partial_values, ETag, ListableStorageTraits, ReadableStorageTraits,
         ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyError, StoreKeyRange,
         StoreKeyStartValue, StoreKeys, StoreKeysPrefixes, StorePrefix, StorePrefixes,
         WritableStorageTraits,
@@ -22,7 +22,10 @@ use std::{
     fs::{File, OpenOptions},
     io::{Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
 
 // // Register the store.
@@ -49,16 +52,31 @@ use std::{
 /// A synchronous file system store.
 ///
 /// See <https://zarr-specs.readthedocs.io/en/latest/v3/stores/filesystem/v1.0.html>.
+///
+/// [`set_if_not_exists`](WritableStorageTraits::set_if_not_exists) writes the value to a temporary file and publishes it with a hard link, which fails if the file already exists, so it is atomic across processes.
+/// On Unix, [`set_if_match`](WritableStorageTraits::set_if_match) holds an advisory lock on the directory of the file while it compares the [`ETag`] of the current file contents and renames a temporary file over it.
+/// This makes it atomic across processes with respect to other conditional writes, and a failed write never leaves a partially written value.
+/// [`set`](WritableStorageTraits::set) does not take the advisory lock, so unconditional writes from other processes are not serialised with conditional writes.
+/// Temporary files are hidden (their names start with a `.`) and are removed once the write completes.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct FilesystemStore {
     base_path: PathBuf,
     sort: bool,
+    #[cfg(feature = "mmap")]
+    memory_mapped: bool,
+    #[cfg(target_os = "linux")]
+    direct_io: bool,
     readonly: bool,
     files: Mutex<HashMap<StoreKey, Arc<RwLock<()>>>>,
     // locks: StoreLocks,
 }
 
 impl FilesystemStore {
+    /// The alignment of buffers written with [`direct_io`](FilesystemStore::direct_io).
+    #[cfg(target_os = "linux")]
+    pub const DIRECT_IO_ALIGNMENT: usize = 4096;
+
     /// Create a new file system store at a given `base_path`.
     ///
     /// # Errors
@@ -85,6 +103,10 @@ impl FilesystemStore {
         Ok(Self {
             base_path,
             sort: false,
+            #[cfg(feature = "mmap")]
+            memory_mapped: false,
+            #[cfg(target_os = "linux")]
+            direct_io: false,
             readonly,
             files: Mutex::default(),
         })
@@ -133,6 +155,83 @@ impl FilesystemStore {
         self
     }
 
+    /// Makes the store write whole values with direct I/O (`O_DIRECT`), bypassing the page cache.
+    ///
+    /// This avoids page cache thrashing when writing very large chunks sequentially.
+    /// Values are copied into a buffer aligned to [`DIRECT_IO_ALIGNMENT`](FilesystemStore::DIRECT_IO_ALIGNMENT) and padded to a multiple of it, then the file is truncated to the value length.
+    /// Partial writes (e.g. with [`set_partial_values`](WritableStorageTraits::set_partial_values)) use buffered I/O, as do filesystems which do not support direct I/O.
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    pub const fn direct_io(mut self) -> Self {
+        self.direct_io = true;
+        self
+    }
+
+    /// Write `value` to a new or truncated file at `path` with direct I/O.
+    ///
+    /// Returns [`None`] if the file cannot be opened or written with direct I/O, in which case the value must be written with buffered I/O.
+    #[cfg(target_os = "linux")]
+    fn set_direct_io(path: &Path, value: &[u8]) -> Result<Option<()>, StorageError> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(path);
+        let mut file = match file {
+            Ok(file) => file,
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let padded_len = value.len().next_multiple_of(Self::DIRECT_IO_ALIGNMENT);
+        let mut buffer = vec![0u8; padded_len + Self::DIRECT_IO_ALIGNMENT];
+        let buffer_offset = buffer.as_ptr().align_offset(Self::DIRECT_IO_ALIGNMENT);
+        let aligned = &mut buffer[buffer_offset..buffer_offset + padded_len];
+        aligned[..value.len()].copy_from_slice(value);
+        // Some filesystems accept O_DIRECT on open but reject the write itself
+        match file
+            .write_all(aligned)
+            .and_then(|()| file.set_len(value.len() as u64))
+        {
+            Ok(()) => Ok(Some(())),
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Makes the store read values through memory mappings rather than buffered reads.
+    ///
+    /// This avoids read system calls and intermediate copies in the kernel, which benefits large uncompressed chunks on local storage.
+    /// Files must not be modified or truncated by other processes while they are being read.
+    #[cfg(feature = "mmap")]
+    #[must_use]
+    pub const fn memory_mapped(mut self) -> Self {
+        self.memory_mapped = true;
+        self
+    }
+
+    /// Read `byte_ranges` of `file` through a memory mapping.
+    #[cfg(feature = "mmap")]
+    fn get_partial_values_mmap(
+        file: &File,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Vec<Vec<u8>>, StorageError> {
+        // Empty files cannot be mapped on all platforms
+        let mmap = if file.metadata()?.len() == 0 {
+            None
+        } else {
+            // SAFETY: the file is locked against writes through this store, and must not be modified externally
+            Some(unsafe { memmap2::Mmap::map(file)? })
+        };
+        Ok(crate::byte_range::extract_byte_ranges(
+            mmap.as_deref().unwrap_or_default(),
+            byte_ranges,
+        )?)
+    }
+
     /// Maps a [`StoreKey`] to a filesystem [`PathBuf`].
     ///
     /// If key is empty `""` then this is the top level file/directory
@@ -171,6 +270,45 @@ impl FilesystemStore {
         file
     }
 
+    /// Write `value` to a new hidden temporary file next to `path`, and return the path of the temporary file.
+    fn write_temporary(path: &Path, value: &[u8]) -> Result<PathBuf, StorageError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temporary_path = path.with_file_name(format!(
+            ".{file_name}.{}.{}.tmp",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary_path)?;
+        if let Err(err) = file.write_all(value).and_then(|()| file.sync_all()) {
+            drop(file);
+            let _ = std::fs::remove_file(&temporary_path);
+            return Err(err.into());
+        }
+        Ok(temporary_path)
+    }
+
+    /// Exclusively lock the directory at `path` across processes until the returned [`File`] is dropped.
+    #[cfg(unix)]
+    fn lock_directory(path: &Path) -> std::io::Result<File> {
+        use std::os::unix::io::AsRawFd;
+
+        let directory = File::open(path)?;
+        loop {
+            // SAFETY: the file descriptor is valid while `directory` is alive
+            if unsafe { libc::flock(directory.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(directory);
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
     fn set_impl(
         &self,
         key: &StoreKey,
@@ -189,6 +327,15 @@ impl FilesystemStore {
             }
         }
 
+        #[cfg(target_os = "linux")]
+        if self.direct_io
+            && offset.is_none()
+            && truncate
+            && Self::set_direct_io(&key_path, value)?.is_some()
+        {
+            return Ok(());
+        }
+
         let mut file = OpenOptions::new()
             .write(true)
             .create(true)
@@ -209,7 +356,7 @@ impl ReadableStorageTraits for FilesystemStore {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
         Ok(self
             .get_partial_values_key(key, &[ByteRange::FromStart(0, None)])?
-            .map(|mut v| v.remove(0)))
+            .map(|mut v| v.remove(0).into()))
     }
 
     fn get_partial_values_key(
@@ -230,6 +377,11 @@ impl ReadableStorageTraits for FilesystemStore {
             }
         };
 
+        #[cfg(feature = "mmap")]
+        if self.memory_mapped {
+            return Self::get_partial_values_mmap(&file, byte_ranges).map(Some);
+        }
+
         let mut out = Vec::with_capacity(byte_ranges.len());
         for byte_range in byte_ranges {
             let bytes = {
@@ -358,6 +510,79 @@ impl WritableStorageTraits for FilesystemStore {
             Ok(())
         }
     }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        !self.readonly
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        cfg!(unix) && !self.readonly
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let file = self.get_file_mutex(key);
+        let _lock = file.write();
+
+        let key_path = self.key_to_fspath(key);
+        if let Some(parent) = key_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let temporary_path = Self::write_temporary(&key_path, value)?;
+        let result = std::fs::hard_link(&temporary_path, &key_path);
+        std::fs::remove_file(&temporary_path)?;
+        match result {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        Ok(self.get(key)?.map(|value| ETag::from_value(&value)))
+    }
+
+    #[cfg(unix)]
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        if self.readonly {
+            return Err(StorageError::ReadOnly);
+        }
+
+        let file = self.get_file_mutex(key);
+        let _lock = file.write();
+
+        let key_path = self.key_to_fspath(key);
+        let directory = key_path.parent().unwrap_or(&self.base_path);
+        let _directory_lock = match Self::lock_directory(directory) {
+            Ok(directory_lock) => directory_lock,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        let current = match std::fs::read(&key_path) {
+            Ok(current) => current,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        if ETag::from_value(&current) != *etag {
+            return Ok(false);
+        }
+        let temporary_path = Self::write_temporary(&key_path, value)?;
+        if let Err(err) = std::fs::rename(&temporary_path, &key_path) {
+            let _ = std::fs::remove_file(&temporary_path);
+            return Err(err.into());
+        }
+        Ok(true)
+    }
 }
 
 impl ReadableWritableStorageTraits for FilesystemStore {
@@ -378,8 +603,7 @@ impl ListableStorageTraits for FilesystemStore {
     }
 
     fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
-        let key: StoreKey = prefix.into();
-        Ok(WalkDir::new(self.key_to_fspath(&key))
+        Ok(WalkDir::new(self.prefix_to_fs_path(prefix))
             .sort_by_file_name()
             .into_iter()
             .filter_map(std::result::Result::ok)
@@ -442,6 +666,100 @@ mod tests {
         super::super::test_util::store_write(&store)?;
         super::super::test_util::store_read(&store)?;
         super::super::test_util::store_list(&store)?;
+        #[cfg(unix)]
+        super::super::test_util::store_conditional_write(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[cfg_attr(miri, ignore)]
+    fn filesystem_conditional_write_concurrent() -> Result<(), Box<dyn Error>> {
+        // Separate stores do not share in-process locks, like stores in separate processes
+        let path = tempfile::TempDir::new()?;
+        let key = StoreKey::new("a/counter")?;
+        let created = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..8u8)
+                .map(|i| {
+                    let store = FilesystemStore::new(path.path()).unwrap();
+                    let key = &key;
+                    scope.spawn(move || store.set_if_not_exists(key, &[i]).unwrap())
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .filter(|created| *created)
+                .count()
+        });
+        assert_eq!(created, 1);
+
+        let store = FilesystemStore::new(path.path())?;
+        store.set(&key, &0u64.to_le_bytes())?;
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                let store = FilesystemStore::new(path.path()).unwrap();
+                let key = &key;
+                scope.spawn(move || {
+                    for _ in 0..25 {
+                        loop {
+                            let value = store.get(key).unwrap().unwrap();
+                            let etag = ETag::from_value(&value);
+                            let count = u64::from_le_bytes(value[..].try_into().unwrap()) + 1;
+                            if store
+                                .set_if_match(key, &count.to_le_bytes(), &etag)
+                                .unwrap()
+                            {
+                                break;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(store.get(&key)?, Some(200u64.to_le_bytes().to_vec().into()));
+        // No temporary files are left behind
+        assert_eq!(store.list()?, &[key]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    #[cfg_attr(miri, ignore)]
+    fn filesystem_memory_mapped() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = FilesystemStore::new(path.path())?.sorted().memory_mapped();
+        super::super::test_util::store_write(&store)?;
+        super::super::test_util::store_read(&store)?;
+        super::super::test_util::store_list(&store)?;
+
+        let key = StoreKey::new("empty")?;
+        store.set(&key, &[])?;
+        assert_eq!(store.get(&key)?, Some(vec![].into()));
+        assert!(store
+            .get_partial_values_key(&key, &[ByteRange::FromStart(0, Some(1))])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(miri, ignore)]
+    fn filesystem_direct_io() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = FilesystemStore::new(path.path())?.sorted().direct_io();
+        super::super::test_util::store_write(&store)?;
+        super::super::test_util::store_read(&store)?;
+        super::super::test_util::store_list(&store)?;
+
+        let key = StoreKey::new("large")?;
+        let value: Vec<u8> = (0..=3 * FilesystemStore::DIRECT_IO_ALIGNMENT)
+            .map(|i| u8::try_from(i % 251).unwrap())
+            .collect();
+        store.set(&key, &value)?;
+        assert_eq!(store.get(&key)?, Some(value.clone().into()));
+        store.set(&key, &value[..5])?;
+        assert_eq!(store.get(&key)?, Some(value[..5].to_vec().into()));
         Ok(())
     }
 }
@@ -6,22 +6,22 @@ use crate::{
     storage::{ReadableStorageTraits, StorageError, StoreKey, StoreKeyRange, StorePrefix},
 };
 
+use base64::{engine::general_purpose::URL_SAFE, Engine as _};
 use itertools::Itertools;
+use netrc::Netrc;
 use reqwest::{
-    header::{HeaderValue, CONTENT_LENGTH, RANGE, HeaderMap, AUTHORIZATION},
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, RANGE},
     StatusCode, Url,
 };
-use std::str::FromStr;
-use thiserror::Error;
-use base64::{engine::general_purpose::URL_SAFE, Engine as _};
-use netrc::Netrc;
 use std::env;
-use std::path::PathBuf;
-use std::io::BufReader;
+use std::fs;
 use std::fs::File;
 use std::io::copy;
-use std::fs;
+use std::io::BufReader;
 use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::str::FromStr;
+use thiserror::Error;
 
 /// A synchronous HTTP store.
 #[derive(Debug)]
@@ -83,14 +83,17 @@ impl HTTPStore {
 
 impl ReadableStorageTraits for HTTPStore {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
-        let (user, token) = load_netrc().unwrap();    
+        let (user, token) = load_netrc().unwrap();
         let credentials = format!("{}:{}", user, token);
         let credentials_enc = URL_SAFE.encode(&credentials);
-    
+
         // Create a HeaderMap and add the Authorization header
         let mut headers = HeaderMap::new();
-            headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("Basic {}", credentials_enc)).unwrap());
-    
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Basic {}", credentials_enc)).unwrap(),
+        );
+
         // Create a client with the headers
         let client = reqwest::blocking::Client::builder()
             .default_headers(headers)
@@ -100,11 +103,9 @@ impl ReadableStorageTraits for HTTPStore {
         let url = self.key_to_url(key)?;
         // let client = reqwest::blocking::Client::new();
         // let response = client.get(url).send()?;
-        let mut response = client.get(url)
-            .send()
-            .unwrap();
+        let mut response = client.get(url).send().unwrap();
         match response.status() {
-            StatusCode::OK => Ok(Some(response.bytes()?.to_vec())),
+            StatusCode::OK => Ok(Some(response.bytes()?)),
             StatusCode::NOT_FOUND => Ok(None),
             _ => Err(StorageError::from(format!(
                 "http unexpected status code: {}",
@@ -180,6 +181,10 @@ impl ReadableStorageTraits for HTTPStore {
         self.get_partial_values_batched_by_key(key_ranges)
     }
 
+    fn supports_sizes(&self) -> bool {
+        false
+    }
+
     fn size_prefix(&self, _prefix: &StorePrefix) -> Result<u64, StorageError> {
         Err(StorageError::Unsupported(
             "size_prefix() not supported for HTTP store".into(),
@@ -209,8 +214,6 @@ impl ReadableStorageTraits for HTTPStore {
     }
 }
 
-
-
 pub fn load_netrc() -> Result<(String, String), Box<dyn std::error::Error>> {
     let home_dir = env::var("HOME")?;
     let netrc_path = PathBuf::from(home_dir).join(".netrc");
@@ -227,8 +230,6 @@ pub fn load_netrc() -> Result<(String, String), Box<dyn std::error::Error>> {
     Err("Machine not found in .netrc file".into())
 }
 
-
-
 /// A HTTP store creation error.
 #[derive(Debug, Error)]
 pub enum HTTPStoreCreateError {
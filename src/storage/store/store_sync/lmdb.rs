@@ -0,0 +1,307 @@
+//! A synchronous [LMDB](http://www.lmdb.tech/doc/) store.
+
+use std::path::{Path, PathBuf};
+
+use heed::{
+    types::{Bytes, Str},
+    Database, Env, EnvOpenOptions, RoTxn, RwTxn,
+};
+use thiserror::Error;
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{extract_byte_ranges, ByteRange},
+    storage::{
+        ListableStorageTraits, ReadableStorageTraits, ReadableWritableStorageTraits, StorageError,
+        StoreKey, StoreKeyRange, StoreKeyStartValue, StoreKeyValue, StoreKeys, StoreKeysPrefixes,
+        StorePrefix, WritableStorageTraits,
+    },
+};
+
+impl From<heed::Error> for StorageError {
+    fn from(err: heed::Error) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+/// A synchronous store backed by an [LMDB](http://www.lmdb.tech/doc/) environment.
+///
+/// LMDB is an embedded memory-mapped key-value store with ordered keys.
+/// It offers very high throughput for many small values (e.g. small or compressed chunks) compared to a [`FilesystemStore`](crate::storage::store::FilesystemStore), which creates a file per key.
+///
+/// Keys are listed with prefix scans over the ordered keys.
+/// Each write is a separate transaction, except for [`set_partial_values`](WritableStorageTraits::set_partial_values), [`erase_values`](WritableStorageTraits::erase_values), and [`erase_prefix`](WritableStorageTraits::erase_prefix) which are atomic.
+/// LMDB permits a single writer at a time, so concurrent writes are serialised.
+pub struct LmdbStore {
+    path: PathBuf,
+    env: Env,
+    database: Database<Str, Bytes>,
+}
+
+type LmdbIter<'txn> = Box<dyn Iterator<Item = heed::Result<(&'txn str, &'txn [u8])>> + 'txn>;
+
+impl std::fmt::Debug for LmdbStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LmdbStore")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LmdbStore {
+    /// The default maximum size of the LMDB environment, 1 TiB.
+    pub const DEFAULT_MAP_SIZE: usize = 1 << 40;
+
+    /// Create a new LMDB store in the directory at `path`, creating it if it does not exist.
+    ///
+    /// The maximum size of the store is [`DEFAULT_MAP_SIZE`](LmdbStore::DEFAULT_MAP_SIZE).
+    ///
+    /// # Errors
+    /// Returns a [`LmdbStoreCreateError`] if the directory cannot be created or the LMDB environment cannot be opened.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, LmdbStoreCreateError> {
+        Self::new_with_map_size(path, Self::DEFAULT_MAP_SIZE)
+    }
+
+    /// Create a new LMDB store in the directory at `path` with a maximum size of `map_size` bytes.
+    ///
+    /// The map size should be a multiple of the OS page size.
+    /// The map is sparse, so a large map size does not use disk space or memory until it is written to.
+    ///
+    /// # Errors
+    /// Returns a [`LmdbStoreCreateError`] if the directory cannot be created or the LMDB environment cannot be opened.
+    pub fn new_with_map_size<P: AsRef<Path>>(
+        path: P,
+        map_size: usize,
+    ) -> Result<Self, LmdbStoreCreateError> {
+        let path = path.as_ref().to_path_buf();
+        std::fs::create_dir_all(&path)?;
+        // SAFETY: the environment is not opened more than once in this process by this store, and the LMDB files must not be modified externally while open
+        let env = unsafe { EnvOpenOptions::new().map_size(map_size).open(&path)? };
+        let mut txn = env.write_txn()?;
+        let database = env.create_database(&mut txn, None)?;
+        txn.commit()?;
+        Ok(Self {
+            path,
+            env,
+            database,
+        })
+    }
+
+    /// Return the path of the LMDB environment.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns an iterator over the keys and values with `prefix`.
+    ///
+    /// LMDB does not support empty keys, so the root prefix iterates over the whole database.
+    fn prefix_iter<'txn>(
+        &self,
+        txn: &'txn RoTxn,
+        prefix: &StorePrefix,
+    ) -> Result<LmdbIter<'txn>, StorageError> {
+        Ok(if prefix.as_str().is_empty() {
+            Box::new(self.database.iter(txn)?)
+        } else {
+            Box::new(self.database.prefix_iter(txn, prefix.as_str())?)
+        })
+    }
+
+    /// Run `f` in a write transaction, which is committed if `f` succeeds.
+    fn write<T>(
+        &self,
+        f: impl FnOnce(&mut RwTxn) -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
+        let mut txn = self.env.write_txn()?;
+        let result = f(&mut txn)?;
+        txn.commit()?;
+        Ok(result)
+    }
+}
+
+impl ReadableStorageTraits for LmdbStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        let txn = self.env.read_txn()?;
+        Ok(self
+            .database
+            .get(&txn, key.as_str())?
+            .map(bytes::Bytes::copy_from_slice))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let txn = self.env.read_txn()?;
+        self.database
+            .get(&txn, key.as_str())?
+            .map(|value| Ok(extract_byte_ranges(value, byte_ranges)?))
+            .transpose()
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let txn = self.env.read_txn()?;
+        let mut size = 0;
+        for item in self.prefix_iter(&txn, prefix)? {
+            let (_, value) = item?;
+            size += value.len() as u64;
+        }
+        Ok(size)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        let txn = self.env.read_txn()?;
+        Ok(self
+            .database
+            .get(&txn, key.as_str())?
+            .map(|value| value.len() as u64))
+    }
+}
+
+impl WritableStorageTraits for LmdbStore {
+    fn set(&self, key: &StoreKey, value: &[u8]) -> Result<(), StorageError> {
+        self.write(|txn| Ok(self.database.put(txn, key.as_str(), value)?))
+    }
+
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        self.write(|txn| {
+            for key_value in key_values {
+                self.database
+                    .put(txn, key_value.key().as_str(), key_value.value())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn set_partial_values(
+        &self,
+        key_start_values: &[StoreKeyStartValue],
+    ) -> Result<(), StorageError> {
+        self.write(|txn| {
+            for key_start_value in key_start_values {
+                let key = key_start_value.key.as_str();
+                let mut value = self
+                    .database
+                    .get(txn, key)?
+                    .map(<[u8]>::to_vec)
+                    .unwrap_or_default();
+                let start = usize::try_from(key_start_value.start).unwrap();
+                let end = usize::try_from(key_start_value.end()).unwrap();
+                if value.len() < end {
+                    value.resize(end, 0);
+                }
+                value[start..end].copy_from_slice(key_start_value.value);
+                self.database.put(txn, key, &value)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn erase(&self, key: &StoreKey) -> Result<(), StorageError> {
+        self.write(|txn| {
+            self.database.delete(txn, key.as_str())?;
+            Ok(())
+        })
+    }
+
+    fn erase_values(&self, keys: &[StoreKey]) -> Result<(), StorageError> {
+        self.write(|txn| {
+            for key in keys {
+                self.database.delete(txn, key.as_str())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn erase_prefix(&self, prefix: &StorePrefix) -> Result<(), StorageError> {
+        self.write(|txn| {
+            if prefix.as_str().is_empty() {
+                self.database.clear(txn)?;
+                return Ok(());
+            }
+            let mut iter = self.database.prefix_iter_mut(txn, prefix.as_str())?;
+            while iter.next().transpose()?.is_some() {
+                // SAFETY: no references to the current entry are held
+                unsafe { iter.del_current()? };
+            }
+            Ok(())
+        })
+    }
+
+    fn supports_atomic_writes(&self) -> bool {
+        true
+    }
+}
+
+impl ReadableWritableStorageTraits for LmdbStore {}
+
+impl ListableStorageTraits for LmdbStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        self.list_prefix(&StorePrefix::root())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        let txn = self.env.read_txn()?;
+        let mut keys = StoreKeys::new();
+        for item in self.prefix_iter(&txn, prefix)? {
+            let (key, _) = item?;
+            keys.push(StoreKey::new(key)?);
+        }
+        Ok(keys)
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys = StoreKeys::new();
+        let mut prefixes = Vec::<StorePrefix>::new();
+        for key in self.list_prefix(prefix)? {
+            let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+            if let Some((child, _)) = key_strip.split_once('/') {
+                // Keys are ordered, so keys under the same child prefix are adjacent
+                let child = StorePrefix::new(prefix.as_str().to_string() + child + "/")?;
+                if prefixes.last() != Some(&child) {
+                    prefixes.push(child);
+                }
+            } else {
+                keys.push(key);
+            }
+        }
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+/// An LMDB store creation error.
+#[derive(Debug, Error)]
+pub enum LmdbStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An LMDB error.
+    #[error(transparent)]
+    LmdbError(#[from] heed::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn lmdb() -> Result<(), Box<dyn Error>> {
+        let path = tempfile::TempDir::new()?;
+        let store = LmdbStore::new_with_map_size(path.path(), 1 << 24)?;
+        super::super::test_util::store_write(&store)?;
+        super::super::test_util::store_read(&store)?;
+        super::super::test_util::store_list(&store)?;
+        Ok(())
+    }
+}
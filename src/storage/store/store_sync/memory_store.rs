@@ -1,5 +1,6 @@
 //! A synchronous in-memory store.
 
+use bytes::Bytes;
 use parking_lot::RwLock;
 use std::sync::Mutex;
 
@@ -7,21 +8,31 @@ use crate::{
     array::MaybeBytes,
     byte_range::{ByteOffset, ByteRange, InvalidByteRangeError},
     storage::{
-        store_set_partial_values, ListableStorageTraits, ReadableStorageTraits,
+        store_set_partial_values, ETag, ListableStorageTraits, ReadableStorageTraits,
         ReadableWritableStorageTraits, StorageError, StoreKey, StoreKeyRange, StoreKeyStartValue,
-        StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
+        StoreKeyValue, StoreKeys, StoreKeysPrefixes, StorePrefix, WritableStorageTraits,
     },
 };
 
 use std::{
     collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
     sync::Arc,
 };
 
+/// The magic bytes at the start of a [`MemoryStore`] snapshot.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ZARRSMEM";
+
+fn invalid_snapshot(message: &str) -> StorageError {
+    StorageError::Other(format!("invalid memory store snapshot: {message}"))
+}
+
 /// A synchronous in-memory store.
 #[derive(Debug)]
 pub struct MemoryStore {
-    data_map: Mutex<BTreeMap<StoreKey, Arc<RwLock<Vec<u8>>>>>,
+    data_map: Mutex<BTreeMap<StoreKey, Arc<RwLock<Bytes>>>>,
     // locks: StoreLocks,
 }
 
@@ -50,7 +61,105 @@ impl MemoryStore {
     //     }
     // }
 
-    fn set_impl(&self, key: &StoreKey, value: &[u8], offset: Option<ByteOffset>, _truncate: bool) {
+    /// Serialise the keys and values of the store to a [`Vec<u8>`].
+    ///
+    /// The snapshot can be restored with [`MemoryStore::from_bytes`].
+    /// Values written concurrently with the snapshot may or may not be included.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.to_writer(&mut bytes)
+            .expect("writing to a Vec<u8> is infallible");
+        bytes
+    }
+
+    /// Create a new memory store from a snapshot created with [`MemoryStore::to_bytes`].
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if `bytes` is not a valid memory store snapshot.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        Self::from_reader(bytes)
+    }
+
+    /// Serialise the keys and values of the store to a file at `path`.
+    ///
+    /// The snapshot can be restored with [`MemoryStore::from_file`].
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the file cannot be written.
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), StorageError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Create a new memory store from a snapshot file created with [`MemoryStore::to_file`].
+    ///
+    /// # Errors
+    /// Returns a [`StorageError`] if the file cannot be read or is not a valid memory store snapshot.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Write a snapshot: the magic bytes, the number of keys, then the length and bytes of each key and value.
+    /// Lengths are little endian [`u64`]s.
+    fn to_writer(&self, writer: &mut impl Write) -> Result<(), StorageError> {
+        let data_map = self.data_map.lock().unwrap();
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        writer.write_all(&(data_map.len() as u64).to_le_bytes())?;
+        for (key, value) in data_map.iter() {
+            let value = value.read();
+            for bytes in [key.as_str().as_bytes(), value.as_ref()] {
+                writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+                writer.write_all(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn from_reader(mut reader: impl Read) -> Result<Self, StorageError> {
+        fn read_bytes(reader: &mut impl Read) -> Result<Vec<u8>, StorageError> {
+            let len = read_u64(reader)?;
+            let mut bytes = Vec::new();
+            reader.by_ref().take(len).read_to_end(&mut bytes)?;
+            if bytes.len() as u64 == len {
+                Ok(bytes)
+            } else {
+                Err(invalid_snapshot("unexpected end of snapshot"))
+            }
+        }
+        fn read_u64(reader: &mut impl Read) -> Result<u64, StorageError> {
+            let mut bytes = [0; 8];
+            reader
+                .read_exact(&mut bytes)
+                .map_err(|_| invalid_snapshot("unexpected end of snapshot"))?;
+            Ok(u64::from_le_bytes(bytes))
+        }
+
+        let mut magic = [0; SNAPSHOT_MAGIC.len()];
+        if reader.read_exact(&mut magic).is_err() || magic != *SNAPSHOT_MAGIC {
+            return Err(invalid_snapshot("missing snapshot header"));
+        }
+        let num_keys = read_u64(&mut reader)?;
+        let mut data_map = BTreeMap::new();
+        for _ in 0..num_keys {
+            let key = String::from_utf8(read_bytes(&mut reader)?)
+                .map_err(|_| invalid_snapshot("a key is not valid UTF-8"))?;
+            let key = StoreKey::new(key)?;
+            let value = read_bytes(&mut reader)?;
+            data_map.insert(key, Arc::new(RwLock::new(value.into())));
+        }
+        if reader.read(&mut [0])? != 0 {
+            return Err(invalid_snapshot("trailing bytes after snapshot"));
+        }
+        Ok(Self {
+            data_map: Mutex::new(data_map),
+        })
+    }
+
+    fn set_impl(&self, key: &StoreKey, value: &[u8], offset: Option<ByteOffset>, truncate: bool) {
         let mut data_map = self.data_map.lock().unwrap();
         let data = data_map
             .entry(key.clone())
@@ -62,14 +171,17 @@ impl MemoryStore {
         let offset = offset.unwrap_or(0);
         if offset == 0 && data.is_empty() {
             // fast path
-            *data = value.to_vec();
+            *data = Bytes::copy_from_slice(value);
         } else {
+            // Only copies if the value is still shared with a reader
+            let mut bytes = Vec::from(std::mem::take(&mut *data));
             let length = usize::try_from(offset + value.len() as u64).unwrap();
-            if data.len() < length {
-                data.resize(length, 0);
+            if bytes.len() < length || truncate {
+                bytes.resize(length, 0);
             }
             let offset = usize::try_from(offset).unwrap();
-            data[offset..offset + value.len()].copy_from_slice(value);
+            bytes[offset..offset + value.len()].copy_from_slice(value);
+            *data = bytes.into();
         }
     }
 }
@@ -146,6 +258,18 @@ impl WritableStorageTraits for MemoryStore {
         Ok(())
     }
 
+    fn set_values(&self, key_values: &[StoreKeyValue]) -> Result<(), StorageError> {
+        // Insert all values under one lock so they become visible together
+        let mut data_map = self.data_map.lock().unwrap();
+        for key_value in key_values {
+            data_map.insert(
+                key_value.key().clone(),
+                Arc::new(RwLock::new(Bytes::copy_from_slice(key_value.value()))),
+            );
+        }
+        Ok(())
+    }
+
     fn set_partial_values(
         &self,
         key_start_values: &[StoreKeyStartValue],
@@ -169,6 +293,55 @@ impl WritableStorageTraits for MemoryStore {
         }
         Ok(())
     }
+
+    fn supports_atomic_writes(&self) -> bool {
+        true
+    }
+
+    fn supports_set_if_not_exists(&self) -> bool {
+        true
+    }
+
+    fn supports_set_if_match(&self) -> bool {
+        true
+    }
+
+    fn set_if_not_exists(&self, key: &StoreKey, value: &[u8]) -> Result<bool, StorageError> {
+        let mut data_map = self.data_map.lock().unwrap();
+        if data_map.contains_key(key) {
+            Ok(false)
+        } else {
+            data_map.insert(
+                key.clone(),
+                Arc::new(RwLock::new(Bytes::copy_from_slice(value))),
+            );
+            Ok(true)
+        }
+    }
+
+    fn etag(&self, key: &StoreKey) -> Result<Option<ETag>, StorageError> {
+        Ok(self.get(key)?.map(|value| ETag::from_value(&value)))
+    }
+
+    fn set_if_match(
+        &self,
+        key: &StoreKey,
+        value: &[u8],
+        etag: &ETag,
+    ) -> Result<bool, StorageError> {
+        // Hold the map lock so the key cannot be erased or replaced between the check and the write
+        let data_map = self.data_map.lock().unwrap();
+        let Some(data) = data_map.get(key) else {
+            return Ok(false);
+        };
+        let mut data = data.write();
+        if ETag::from_value(&data) == *etag {
+            *data = Bytes::copy_from_slice(value);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
 }
 
 impl ReadableWritableStorageTraits for MemoryStore {
@@ -229,6 +402,49 @@ mod tests {
         super::super::test_util::store_write(&store)?;
         super::super::test_util::store_read(&store)?;
         super::super::test_util::store_list(&store)?;
+        super::super::test_util::store_conditional_write(&store)?;
+        Ok(())
+    }
+
+    #[test]
+    fn memory_snapshot() -> Result<(), Box<dyn Error>> {
+        let store = MemoryStore::new();
+        super::super::test_util::store_write(&store)?;
+        store.set(&StoreKey::new("empty")?, &[])?;
+
+        let bytes = store.to_bytes();
+        let restored = MemoryStore::from_bytes(&bytes)?;
+        assert_eq!(restored.list()?, store.list()?);
+        for key in store.list()? {
+            assert_eq!(restored.get(&key)?, store.get(&key)?);
+        }
+        super::super::test_util::store_read(&restored)?;
+        assert_eq!(restored.to_bytes(), bytes);
+
+        let dir = tempfile::TempDir::new()?;
+        let path = dir.path().join("snapshot");
+        store.to_file(&path)?;
+        assert_eq!(MemoryStore::from_file(&path)?.to_bytes(), bytes);
+
+        assert!(MemoryStore::from_bytes(b"ZARRSMEX").is_err());
+        assert!(MemoryStore::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(MemoryStore::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn memory_get_shared() -> Result<(), Box<dyn Error>> {
+        let store = MemoryStore::new();
+        let key = StoreKey::new("a")?;
+        store.set(&key, &[0, 1, 2, 3])?;
+        let a = store.get(&key)?.unwrap();
+        let b = store.get(&key)?.unwrap();
+        assert_eq!(a.as_ptr(), b.as_ptr());
+
+        // Writing to a key does not modify values already handed out
+        store.set_partial_values(&[StoreKeyStartValue::new(key.clone(), 1, &[4])])?;
+        assert_eq!(a, [0, 1, 2, 3].as_slice());
+        assert_eq!(store.get(&key)?.unwrap(), [0, 4, 2, 3].as_slice());
         Ok(())
     }
 }
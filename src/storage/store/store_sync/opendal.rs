@@ -51,7 +51,7 @@ fn handle_result<T>(result: Result<T, opendal::Error>) -> Result<Option<T>, Stor
 #[async_trait::async_trait]
 impl ReadableStorageTraits for OpendalStore {
     fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
-        handle_result(self.operator.read(key.as_str()))
+        handle_result(self.operator.read(key.as_str()).map(bytes::Bytes::from))
     }
 
     fn get_partial_values_key(
@@ -137,6 +137,15 @@ impl ReadableStorageTraits for OpendalStore {
     fn size(&self) -> Result<u64, StorageError> {
         self.size_prefix(&StorePrefix::root())
     }
+
+    fn supports_partial_reads(&self) -> bool {
+        self.operator.info().full_capability().read_with_range
+    }
+
+    fn supports_sizes(&self) -> bool {
+        let capability = self.operator.info().full_capability();
+        capability.stat && capability.list
+    }
 }
 
 #[async_trait::async_trait]
@@ -223,6 +232,10 @@ impl ListableStorageTraits for OpendalStore {
         prefixes.sort();
         Ok(StoreKeysPrefixes { keys, prefixes })
     }
+
+    fn supports_listing(&self) -> bool {
+        self.operator.info().full_capability().list
+    }
 }
 
 #[cfg(test)]
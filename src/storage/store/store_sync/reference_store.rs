@@ -0,0 +1,902 @@
+//! A read only store of references to byte ranges in other files.
+//!
+//! See [`ReferenceStore`].
+
+use crate::{
+    array::MaybeBytes,
+    byte_range::{ByteRange, InvalidByteRangeError},
+    storage::{
+        ListableStorageTraits, ReadableStorage, ReadableStorageTraits, StorageError, StoreKey,
+        StoreKeyError, StoreKeyRange, StoreKeys, StoreKeysPrefixes, StorePrefix,
+    },
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use thiserror::Error;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// A reference to the value of a store key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// The value is stored inline.
+    Inline(Vec<u8>),
+    /// The value is the entire target at a URL.
+    Url(String),
+    /// The value is a byte range of the target at a URL.
+    Range {
+        /// The target URL.
+        url: String,
+        /// The byte offset of the value in the target.
+        offset: u64,
+        /// The length of the value in bytes.
+        length: u64,
+    },
+}
+
+/// A synchronous read only store of references to byte ranges in other files.
+///
+/// Each store key maps to a [`Reference`], which is an inline value or a byte range of a target file.
+/// This is the structure of a [kerchunk](https://fsspec.github.io/kerchunk/) reference manifest, which indexes the chunks of an existing file format (e.g. `NetCDF`, HDF5, or TIFF) so that it can be read as a "virtual" Zarr hierarchy.
+/// [`ReferenceStore::from_json`] reads version 0 and 1 JSON manifests, and [`ReferenceStore::from_parquet`] reads parquet manifests with the `parquet` feature.
+///
+/// Targets that are local paths or `file://` URLs are read directly from the file system.
+/// Targets with any other URL scheme are read from a store registered with [`ReferenceStore::add_target`], such as an `HTTPStore` with the `http` feature.
+///
+/// Note that kerchunk manifests generally reference Zarr V2 metadata, which `zarrs` does not support.
+/// The store serves any references, but arrays and groups can only be opened if the manifest has Zarr V3 metadata.
+pub struct ReferenceStore {
+    references: BTreeMap<StoreKey, Reference>,
+    targets: Vec<(String, ReadableStorage)>,
+}
+
+impl std::fmt::Debug for ReferenceStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReferenceStore")
+            .field("references", &self.references.len())
+            .field(
+                "targets",
+                &self
+                    .targets
+                    .iter()
+                    .map(|(prefix, _)| prefix)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl ReferenceStore {
+    /// Create a new reference store with `references`.
+    #[must_use]
+    pub fn new(references: BTreeMap<StoreKey, Reference>) -> Self {
+        Self {
+            references,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Create a new reference store from a version 0 or 1 kerchunk JSON reference manifest.
+    ///
+    /// Inline values are strings, which are base64 encoded if they have a `base64:` prefix.
+    /// Inline JSON objects are serialised.
+    /// Version 1 URL templates (e.g. `{{u}}`) are expanded, but generators (`gen`) are not supported.
+    ///
+    /// # Errors
+    /// Returns a [`ReferenceStoreCreateError`] if the manifest is not valid or uses generators.
+    pub fn from_json(json: &[u8]) -> Result<Self, ReferenceStoreCreateError> {
+        let json: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(json)?;
+        let (refs, templates) = match json.get("version") {
+            None => (json, serde_json::Map::new()),
+            Some(serde_json::Value::Number(version)) if version.as_u64() == Some(1) => {
+                if json
+                    .get("gen")
+                    .and_then(serde_json::Value::as_array)
+                    .is_some_and(|gen| !gen.is_empty())
+                {
+                    return Err(ReferenceStoreCreateError::Unsupported(
+                        "reference generators (gen)".to_string(),
+                    ));
+                }
+                let object = |name: &str| match json.get(name) {
+                    Some(serde_json::Value::Object(object)) => object.clone(),
+                    _ => serde_json::Map::new(),
+                };
+                (object("refs"), object("templates"))
+            }
+            Some(version) => {
+                return Err(ReferenceStoreCreateError::Unsupported(format!(
+                    "reference manifest version {version}"
+                )))
+            }
+        };
+
+        let expand = |url: &str| {
+            templates
+                .iter()
+                .fold(url.to_string(), |url, (name, value)| {
+                    url.replace(
+                        &format!("{{{{{name}}}}}"),
+                        value.as_str().unwrap_or_default(),
+                    )
+                })
+        };
+        let mut references = BTreeMap::new();
+        for (key, value) in refs {
+            let reference = match value {
+                serde_json::Value::String(value) => inline_reference(&key, &value)?,
+                serde_json::Value::Object(_) => Reference::Inline(value.to_string().into_bytes()),
+                serde_json::Value::Array(ref target) => match target.as_slice() {
+                    [serde_json::Value::String(url)] => Reference::Url(expand(url)),
+                    [serde_json::Value::String(url), offset, length] => {
+                        match (offset.as_u64(), length.as_u64()) {
+                            (Some(offset), Some(length)) => Reference::Range {
+                                url: expand(url),
+                                offset,
+                                length,
+                            },
+                            _ => {
+                                return Err(ReferenceStoreCreateError::InvalidReference(
+                                    key,
+                                    value.to_string(),
+                                ))
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(ReferenceStoreCreateError::InvalidReference(
+                            key,
+                            value.to_string(),
+                        ))
+                    }
+                },
+                value => {
+                    return Err(ReferenceStoreCreateError::InvalidReference(
+                        key,
+                        value.to_string(),
+                    ))
+                }
+            };
+            references.insert(StoreKey::new(key)?, reference);
+        }
+        Ok(Self::new(references))
+    }
+
+    /// Create a new reference store from a kerchunk parquet reference manifest in the directory at `path`.
+    ///
+    /// The directory holds the metadata and the record size in a `.zmetadata` JSON file and the chunk references of each array in `<array>/refs.<i>.parq` files.
+    /// Each row of the parquet files references the chunk at the same (C order) linear index of the chunk grid of the array with the `path`, `offset`, `size`, and `raw` columns.
+    /// Arrays can have Zarr V2 (`.zarray`) or Zarr V3 (`zarr.json`) metadata.
+    ///
+    /// All references are read when the store is created.
+    ///
+    /// # Errors
+    /// Returns a [`ReferenceStoreCreateError`] if the manifest cannot be read or is not valid.
+    #[cfg(feature = "parquet")]
+    pub fn from_parquet(path: impl AsRef<Path>) -> Result<Self, ReferenceStoreCreateError> {
+        parquet_references(path.as_ref()).map(Self::new)
+    }
+
+    /// Create a new reference store from the reference manifest at `path`.
+    ///
+    /// A directory is read as a parquet manifest (see [`ReferenceStore::from_parquet`]) and a file is read as a JSON manifest (see [`ReferenceStore::from_json`]).
+    ///
+    /// # Errors
+    /// Returns a [`ReferenceStoreCreateError`] if the manifest cannot be read or is not valid.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ReferenceStoreCreateError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            #[cfg(feature = "parquet")]
+            return Self::from_parquet(path);
+            #[cfg(not(feature = "parquet"))]
+            return Err(ReferenceStoreCreateError::Unsupported(
+                "parquet reference manifests without the parquet feature".to_string(),
+            ));
+        }
+        Self::from_json(&std::fs::read(path)?)
+    }
+
+    /// Register `storage` as the store of targets with URLs starting with `url_prefix`.
+    ///
+    /// The store key of a target is the remainder of its URL after `url_prefix`, without a leading `/`.
+    /// For example, with a `url_prefix` of `s3://bucket/`, the target `s3://bucket/data/file.nc` is read from the `data/file.nc` key of `storage`.
+    /// If the URL of a target matches multiple prefixes, the longest prefix is used.
+    pub fn add_target(&mut self, url_prefix: &str, storage: ReadableStorage) -> &mut Self {
+        self.targets.push((url_prefix.to_string(), storage));
+        self.targets
+            .sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self
+    }
+
+    /// Return the references.
+    #[must_use]
+    pub fn references(&self) -> &BTreeMap<StoreKey, Reference> {
+        &self.references
+    }
+
+    /// Return the registered target store and store key of `url`, if any.
+    fn target(&self, url: &str) -> Result<Option<(&ReadableStorage, StoreKey)>, StorageError> {
+        for (prefix, storage) in &self.targets {
+            if let Some(key) = url.strip_prefix(prefix.as_str()) {
+                let key = StoreKey::new(key.strip_prefix('/').unwrap_or(key))?;
+                return Ok(Some((storage, key)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the local path of `url`, or an error if the target is not local and no target store is registered for it.
+    fn local_path(url: &str) -> Result<&Path, StorageError> {
+        match url.strip_prefix("file://") {
+            Some(path) => Ok(Path::new(path)),
+            None if !url.contains("://") => Ok(Path::new(url)),
+            None => Err(StorageError::Unsupported(format!(
+                "no target store is registered for {url}"
+            ))),
+        }
+    }
+
+    /// Read `byte_ranges` of the target at `url`.
+    fn read_target(
+        &self,
+        url: &str,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Vec<Vec<u8>>, StorageError> {
+        if let Some((storage, key)) = self.target(url)? {
+            return storage
+                .get_partial_values_key(&key, byte_ranges)?
+                .ok_or_else(|| {
+                    StorageError::Other(format!("the reference target {url} does not exist"))
+                });
+        }
+        let mut file = File::open(Self::local_path(url)?)?;
+        let size = file.metadata()?.len();
+        byte_ranges
+            .iter()
+            .map(|byte_range| {
+                if byte_range.end(size) > size {
+                    return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                }
+                let mut bytes = vec![0; usize::try_from(byte_range.length(size)).unwrap()];
+                file.seek(SeekFrom::Start(byte_range.start(size)))?;
+                file.read_exact(&mut bytes)?;
+                Ok(bytes)
+            })
+            .collect()
+    }
+
+    /// Return the size of the target at `url`.
+    fn target_size(&self, url: &str) -> Result<u64, StorageError> {
+        if let Some((storage, key)) = self.target(url)? {
+            return storage.size_key(&key)?.ok_or_else(|| {
+                StorageError::Other(format!("the reference target {url} does not exist"))
+            });
+        }
+        Ok(std::fs::metadata(Self::local_path(url)?)?.len())
+    }
+}
+
+/// Return the reference of the inline value `value` of `key`.
+fn inline_reference(key: &str, value: &str) -> Result<Reference, ReferenceStoreCreateError> {
+    match value.strip_prefix("base64:") {
+        Some(value) => STANDARD
+            .decode(value)
+            .map(Reference::Inline)
+            .map_err(|err| {
+                ReferenceStoreCreateError::InvalidReference(key.to_string(), err.to_string())
+            }),
+        None => Ok(Reference::Inline(value.as_bytes().to_vec())),
+    }
+}
+
+/// The chunk references of an array in a parquet reference manifest.
+#[cfg(feature = "parquet")]
+struct ParquetArray {
+    /// The directory of the parquet files relative to the manifest.
+    dir: String,
+    /// The chunk grid shape.
+    chunk_grid_shape: crate::array::ArrayShape,
+    /// The chunk key encoding.
+    chunk_key_encoding: ParquetChunkKeyEncoding,
+}
+
+/// The chunk key encoding of an array in a parquet reference manifest.
+#[cfg(feature = "parquet")]
+enum ParquetChunkKeyEncoding {
+    /// Zarr V2 chunk keys with an array path prefix and a dimension separator.
+    V2 { prefix: String, separator: String },
+    /// The chunk keys of a Zarr V3 array.
+    V3(Box<crate::array::Array<crate::storage::store::MemoryStore>>),
+}
+
+#[cfg(feature = "parquet")]
+impl ParquetArray {
+    /// Return the array with the metadata `value` at `key`, or [`None`] if `key` is not array metadata.
+    fn new(
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<Option<Self>, ReferenceStoreCreateError> {
+        use crate::array::{Array, ArrayMetadata};
+
+        #[derive(serde::Deserialize)]
+        struct ZArray {
+            shape: Vec<u64>,
+            chunks: Vec<u64>,
+            #[serde(default)]
+            dimension_separator: Option<String>,
+        }
+
+        fn parse<T: serde::de::DeserializeOwned>(
+            value: &serde_json::Value,
+        ) -> Result<T, serde_json::Error> {
+            match value {
+                serde_json::Value::String(value) => serde_json::from_str(value),
+                value => serde_json::from_value(value.clone()),
+            }
+        }
+
+        let invalid = |err: &dyn std::fmt::Display| {
+            ReferenceStoreCreateError::InvalidReference(key.to_string(), err.to_string())
+        };
+        if let Some(array_path) = key.strip_suffix(".zarray") {
+            let zarray: ZArray = parse(value).map_err(|err| invalid(&err))?;
+            if zarray.shape.len() != zarray.chunks.len() || zarray.chunks.contains(&0) {
+                return Err(invalid(
+                    &"the chunk shape is not compatible with the array shape",
+                ));
+            }
+            let chunk_grid_shape = std::iter::zip(&zarray.shape, &zarray.chunks)
+                .map(|(shape, chunks)| shape.div_ceil(*chunks))
+                .collect();
+            let separator = zarray
+                .dimension_separator
+                .unwrap_or_else(|| ".".to_string());
+            Ok(Some(Self {
+                dir: array_path.trim_end_matches('/').to_string(),
+                chunk_grid_shape,
+                chunk_key_encoding: ParquetChunkKeyEncoding::V2 {
+                    prefix: array_path.to_string(),
+                    separator,
+                },
+            }))
+        } else if let Some(array_path) = key.strip_suffix("zarr.json") {
+            if !(array_path.is_empty() || array_path.ends_with('/')) {
+                return Ok(None);
+            }
+            let metadata: ArrayMetadata = match parse(value) {
+                Ok(metadata) => metadata,
+                Err(_) => return Ok(None), // group metadata
+            };
+            let array_path = array_path.trim_end_matches('/');
+            let array = Array::new_with_metadata(
+                std::sync::Arc::new(crate::storage::store::MemoryStore::new()),
+                &format!("/{array_path}"),
+                metadata,
+            )
+            .map_err(|err| invalid(&err))?;
+            let chunk_grid_shape = array
+                .chunk_grid_shape()
+                .ok_or_else(|| invalid(&"the chunk grid shape is unknown"))?;
+            Ok(Some(Self {
+                dir: array_path.to_string(),
+                chunk_grid_shape,
+                chunk_key_encoding: ParquetChunkKeyEncoding::V3(Box::new(array)),
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Return the store key of the chunk at `chunk_indices`.
+    fn chunk_key(&self, chunk_indices: &[u64]) -> String {
+        use itertools::Itertools;
+
+        match &self.chunk_key_encoding {
+            ParquetChunkKeyEncoding::V2 { prefix, separator } => {
+                if chunk_indices.is_empty() {
+                    format!("{prefix}0")
+                } else {
+                    format!("{prefix}{}", chunk_indices.iter().join(separator))
+                }
+            }
+            ParquetChunkKeyEncoding::V3(array) => {
+                array.chunk_key(chunk_indices).as_str().to_string()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn parquet_references(
+    path: &Path,
+) -> Result<BTreeMap<StoreKey, Reference>, ReferenceStoreCreateError> {
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::Field,
+    };
+
+    #[derive(serde::Deserialize)]
+    struct ZMetadata {
+        metadata: serde_json::Map<String, serde_json::Value>,
+        record_size: u64,
+    }
+
+    let zmetadata: ZMetadata = serde_json::from_slice(&std::fs::read(path.join(".zmetadata"))?)?;
+    if zmetadata.record_size == 0 {
+        return Err(ReferenceStoreCreateError::InvalidReference(
+            ".zmetadata".to_string(),
+            "the record size is zero".to_string(),
+        ));
+    }
+    let mut references = BTreeMap::new();
+    for (key, value) in &zmetadata.metadata {
+        let value = match value {
+            serde_json::Value::String(value) => inline_reference(key, value)?,
+            value => Reference::Inline(value.to_string().into_bytes()),
+        };
+        references.insert(StoreKey::new(key.clone())?, value);
+    }
+
+    for (key, value) in &zmetadata.metadata {
+        let Some(array) = ParquetArray::new(key, value)? else {
+            continue;
+        };
+        let invalid = |err: &dyn std::fmt::Display| {
+            ReferenceStoreCreateError::InvalidReference(key.clone(), err.to_string())
+        };
+        let num_chunks = array.chunk_grid_shape.iter().product::<u64>();
+        for record in 0..num_chunks.div_ceil(zmetadata.record_size) {
+            let record_path = path.join(&array.dir).join(format!("refs.{record}.parq"));
+            if !record_path.exists() {
+                continue;
+            }
+            let reader = SerializedFileReader::new(File::open(&record_path)?)?;
+            for (row_index, row) in reader.get_row_iter(None)?.enumerate() {
+                let chunk_index = record * zmetadata.record_size + row_index as u64;
+                if chunk_index >= num_chunks {
+                    break;
+                }
+                let (mut url, mut offset, mut size, mut raw) = (None, 0, 0, None);
+                for (name, field) in row?.get_column_iter() {
+                    match (name.as_str(), field) {
+                        ("path", Field::Str(path)) => url = Some(path.clone()),
+                        ("offset", Field::Long(value)) => offset = *value,
+                        ("offset", Field::Int(value)) => offset = i64::from(*value),
+                        ("size", Field::Long(value)) => size = *value,
+                        ("size", Field::Int(value)) => size = i64::from(*value),
+                        ("raw", Field::Bytes(bytes)) => raw = Some(bytes.data().to_vec()),
+                        _ => {}
+                    }
+                }
+                let reference = match (raw, url) {
+                    (Some(raw), _) => Reference::Inline(raw),
+                    (None, Some(url)) if size == 0 => Reference::Url(url),
+                    (None, Some(url)) => Reference::Range {
+                        url,
+                        offset: u64::try_from(offset).map_err(|err| invalid(&err))?,
+                        length: u64::try_from(size).map_err(|err| invalid(&err))?,
+                    },
+                    (None, None) => continue, // missing chunk
+                };
+                let chunk_indices =
+                    crate::array::unravel_index(chunk_index, &array.chunk_grid_shape);
+                references.insert(StoreKey::new(array.chunk_key(&chunk_indices))?, reference);
+            }
+        }
+    }
+    Ok(references)
+}
+
+impl ReadableStorageTraits for ReferenceStore {
+    fn get(&self, key: &StoreKey) -> Result<MaybeBytes, StorageError> {
+        Ok(self
+            .get_partial_values_key(key, &[ByteRange::FromStart(0, None)])?
+            .map(|mut values| values.remove(0).into()))
+    }
+
+    fn get_partial_values_key(
+        &self,
+        key: &StoreKey,
+        byte_ranges: &[ByteRange],
+    ) -> Result<Option<Vec<Vec<u8>>>, StorageError> {
+        let Some(reference) = self.references.get(key) else {
+            return Ok(None);
+        };
+        let values = match reference {
+            Reference::Inline(value) => {
+                let size = value.len() as u64;
+                byte_ranges
+                    .iter()
+                    .map(|byte_range| {
+                        if byte_range.end(size) > size {
+                            return Err(InvalidByteRangeError::new(*byte_range, size).into());
+                        }
+                        let start = usize::try_from(byte_range.start(size)).unwrap();
+                        let end = usize::try_from(byte_range.end(size)).unwrap();
+                        Ok(value[start..end].to_vec())
+                    })
+                    .collect::<Result<Vec<_>, StorageError>>()
+            }
+            Reference::Url(url) => self.read_target(url, byte_ranges),
+            Reference::Range {
+                url,
+                offset,
+                length,
+            } => {
+                let byte_ranges = byte_ranges
+                    .iter()
+                    .map(|byte_range| {
+                        if byte_range.end(*length) > *length {
+                            return Err(InvalidByteRangeError::new(*byte_range, *length).into());
+                        }
+                        Ok(ByteRange::FromStart(
+                            offset + byte_range.start(*length),
+                            Some(byte_range.length(*length)),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, StorageError>>()?;
+                self.read_target(url, &byte_ranges)
+            }
+        };
+        values.map(Some).map_err(|err| err.with_key(key.clone()))
+    }
+
+    fn get_partial_values(
+        &self,
+        key_ranges: &[StoreKeyRange],
+    ) -> Result<Vec<MaybeBytes>, StorageError> {
+        self.get_partial_values_batched_by_key(key_ranges)
+    }
+
+    fn size_prefix(&self, prefix: &StorePrefix) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for key in self.references.keys() {
+            if key.has_prefix(prefix) {
+                size += self.size_key(key)?.unwrap_or_default();
+            }
+        }
+        Ok(size)
+    }
+
+    fn size_key(&self, key: &StoreKey) -> Result<Option<u64>, StorageError> {
+        match self.references.get(key) {
+            None => Ok(None),
+            Some(Reference::Inline(value)) => Ok(Some(value.len() as u64)),
+            Some(Reference::Range { length, .. }) => Ok(Some(*length)),
+            Some(Reference::Url(url)) => self
+                .target_size(url)
+                .map(Some)
+                .map_err(|err| err.with_key(key.clone())),
+        }
+    }
+}
+
+impl ListableStorageTraits for ReferenceStore {
+    fn list(&self) -> Result<StoreKeys, StorageError> {
+        Ok(self.references.keys().cloned().collect())
+    }
+
+    fn list_prefix(&self, prefix: &StorePrefix) -> Result<StoreKeys, StorageError> {
+        Ok(self
+            .references
+            .keys()
+            .filter(|&key| key.has_prefix(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn list_dir(&self, prefix: &StorePrefix) -> Result<StoreKeysPrefixes, StorageError> {
+        let mut keys: StoreKeys = vec![];
+        let mut prefixes: BTreeSet<StorePrefix> = BTreeSet::default();
+        for key in self.references.keys() {
+            if key.has_prefix(prefix) {
+                let key_strip = key.as_str().strip_prefix(prefix.as_str()).unwrap();
+                let key_strip = key_strip.strip_prefix('/').unwrap_or(key_strip);
+                let components: Vec<_> = key_strip.split('/').collect();
+                if components.len() > 1 {
+                    prefixes.insert(StorePrefix::new(
+                        prefix.as_str().to_string() + components[0] + "/",
+                    )?);
+                } else if key.parent().eq(prefix) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+        let prefixes = prefixes.into_iter().collect();
+        Ok(StoreKeysPrefixes { keys, prefixes })
+    }
+}
+
+/// A reference store creation error.
+#[derive(Debug, Error)]
+pub enum ReferenceStoreCreateError {
+    /// An IO error.
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+    /// An error deserialising the JSON reference manifest.
+    #[error(transparent)]
+    JSONError(#[from] serde_json::Error),
+    /// A parquet error.
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    ParquetError(#[from] parquet::errors::ParquetError),
+    /// An invalid store key.
+    #[error(transparent)]
+    InvalidStoreKey(#[from] StoreKeyError),
+    /// An invalid reference.
+    #[error("invalid reference for {0}: {1}")]
+    InvalidReference(String, String),
+    /// An unsupported reference manifest feature.
+    #[error("unsupported reference manifest: {0}")]
+    Unsupported(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        array::{Array, ArrayBuilder, DataType, FillValue},
+        array_subset::ArraySubset,
+        storage::{store::MemoryStore, WritableStorageTraits},
+    };
+
+    use super::*;
+
+    #[test]
+    fn reference_store_json() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let target = dir.path().join("target.bin");
+        std::fs::write(&target, [9, 9, 0, 1, 2, 3, 9])?;
+        let json = serde_json::json!({
+            "version": 1,
+            "templates": {"u": target.to_str().unwrap()},
+            "refs": {
+                "a/b": ["{{u}}", 2, 4],
+                "a/c": "base64:AAE=",
+                "a/d/e": "text",
+                "f": ["file://{{u}}"],
+                "g": ["s3://bucket/data/target", 1, 2],
+                "h": {"zarr_format": 3}
+            }
+        });
+        let mut store = ReferenceStore::from_json(json.to_string().as_bytes())?;
+        let target_store = Arc::new(MemoryStore::new());
+        target_store.set(&"data/target".try_into()?, &[5, 6, 7, 8])?;
+        store.add_target("s3://bucket", target_store);
+
+        assert_eq!(
+            store.get(&"a/b".try_into()?)?,
+            Some(vec![0, 1, 2, 3].into())
+        );
+        assert_eq!(store.get(&"a/c".try_into()?)?, Some(vec![0, 1].into()));
+        assert_eq!(
+            store.get(&"a/d/e".try_into()?)?,
+            Some(b"text".to_vec().into())
+        );
+        assert_eq!(store.get(&"g".try_into()?)?, Some(vec![6, 7].into()));
+        assert_eq!(
+            store.get(&"h".try_into()?)?,
+            Some(br#"{"zarr_format":3}"#.to_vec().into())
+        );
+        assert!(store.get(&"notfound".try_into()?)?.is_none());
+        assert_eq!(
+            store.get_partial_values_key(
+                &"a/b".try_into()?,
+                &[
+                    ByteRange::FromStart(1, Some(2)),
+                    ByteRange::FromEnd(0, Some(1))
+                ]
+            )?,
+            Some(vec![vec![1, 2], vec![3]])
+        );
+        assert!(store
+            .get_partial_values_key(&"a/b".try_into()?, &[ByteRange::FromStart(3, Some(2))])
+            .is_err());
+        assert_eq!(
+            store.get_partial_values_key(&"f".try_into()?, &[ByteRange::FromEnd(0, Some(2))])?,
+            Some(vec![vec![3, 9]])
+        );
+
+        assert_eq!(store.size_key(&"f".try_into()?)?, Some(7));
+        assert_eq!(store.size_prefix(&"a/".try_into()?)?, 10);
+        assert_eq!(
+            store.list_prefix(&"a/".try_into()?)?,
+            &["a/b".try_into()?, "a/c".try_into()?, "a/d/e".try_into()?]
+        );
+        let list_dir = store.list_dir(&"a/".try_into()?)?;
+        assert_eq!(list_dir.keys(), &["a/b".try_into()?, "a/c".try_into()?]);
+        assert_eq!(list_dir.prefixes(), &["a/d/".try_into()?]);
+        Ok(())
+    }
+
+    #[test]
+    fn reference_store_json_invalid() {
+        assert!(matches!(
+            ReferenceStore::from_json(br#"{"version": 1, "refs": {}, "gen": [{"key": "a"}]}"#),
+            Err(ReferenceStoreCreateError::Unsupported(_))
+        ));
+        assert!(matches!(
+            ReferenceStore::from_json(br#"{"a": ["file", "0", 1]}"#),
+            Err(ReferenceStoreCreateError::InvalidReference(_, _))
+        ));
+        assert!(matches!(
+            ReferenceStore::from_json(br#"{"a": 1}"#),
+            Err(ReferenceStoreCreateError::InvalidReference(_, _))
+        ));
+        let store =
+            ReferenceStore::from_json(br#"{"a": ["https://example.com/a", 0, 1]}"#).unwrap();
+        assert!(store.get(&"a".try_into().unwrap()).is_err());
+    }
+
+    #[test]
+    fn reference_store_array() -> Result<(), Box<dyn std::error::Error>> {
+        // Write an array to a store and reference its chunks in a single "virtual" file
+        let store = Arc::new(MemoryStore::new());
+        let array = ArrayBuilder::new(
+            vec![4, 4],
+            DataType::UInt8,
+            vec![2, 2].try_into()?,
+            FillValue::from(0u8),
+        )
+        .build(store.clone(), "/array")?;
+        array.store_metadata()?;
+        array.store_array_subset_elements::<u8>(
+            &ArraySubset::new_with_shape(vec![4, 4]),
+            (0..16).collect(),
+        )?;
+
+        let dir = tempfile::tempdir()?;
+        let target = dir.path().join("virtual.bin");
+        let mut target_bytes = Vec::new();
+        let mut refs = serde_json::Map::new();
+        for key in store.list()? {
+            let value = store.get(&key)?.unwrap();
+            if key.as_str().ends_with("zarr.json") {
+                refs.insert(
+                    key.as_str().to_string(),
+                    String::from_utf8(value.to_vec())?.into(),
+                );
+            } else {
+                refs.insert(
+                    key.as_str().to_string(),
+                    serde_json::json!([target.to_str().unwrap(), target_bytes.len(), value.len()]),
+                );
+                target_bytes.extend_from_slice(&value);
+            }
+        }
+        std::fs::write(&target, target_bytes)?;
+        let manifest = dir.path().join("references.json");
+        std::fs::write(&manifest, serde_json::Value::Object(refs).to_string())?;
+
+        let store = Arc::new(ReferenceStore::open(&manifest)?);
+        let array = Array::new(store, "/array")?;
+        assert_eq!(
+            array.retrieve_array_subset_elements::<u8>(&ArraySubset::new_with_shape(vec![4, 4]))?,
+            (0..16).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn reference_store_parquet() -> Result<(), Box<dyn std::error::Error>> {
+        use parquet::{
+            data_type::{ByteArray, ByteArrayType, Int64Type},
+            file::{properties::WriterProperties, writer::SerializedFileWriter},
+            schema::parser::parse_message_type,
+        };
+
+        let dir = tempfile::tempdir()?;
+        let target = dir.path().join("target.bin");
+        std::fs::write(&target, [0, 1, 2, 3, 4, 5, 6, 7])?;
+
+        // A 2x3 array with 1x2 chunks (a 2x2 chunk grid) and 3 references per record
+        let manifest = dir.path().join("references.parq");
+        std::fs::create_dir_all(manifest.join("var"))?;
+        let zmetadata = serde_json::json!({
+            "metadata": {
+                ".zgroup": {"zarr_format": 2},
+                "var/.zarray": {"shape": [2, 3], "chunks": [1, 2], "zarr_format": 2},
+            },
+            "record_size": 3
+        });
+        std::fs::write(manifest.join(".zmetadata"), zmetadata.to_string())?;
+
+        let schema = Arc::new(parse_message_type(
+            "message schema {
+                OPTIONAL BYTE_ARRAY path (UTF8);
+                REQUIRED INT64 offset;
+                REQUIRED INT64 size;
+                OPTIONAL BYTE_ARRAY raw;
+            }",
+        )?);
+        let target_path = ByteArray::from(target.to_str().unwrap());
+        let write_record = |record: usize,
+                            paths: &[ByteArray],
+                            path_levels: &[i16],
+                            offsets: &[i64],
+                            sizes: &[i64],
+                            raws: &[ByteArray],
+                            raw_levels: &[i16]|
+         -> Result<(), Box<dyn std::error::Error>> {
+            let file = File::create(manifest.join("var").join(format!("refs.{record}.parq")))?;
+            let mut writer = SerializedFileWriter::new(
+                file,
+                schema.clone(),
+                Arc::new(WriterProperties::builder().build()),
+            )?;
+            let mut row_group = writer.next_row_group()?;
+            let mut column = row_group.next_column()?.unwrap();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(paths, Some(path_levels), None)?;
+            column.close()?;
+            let mut column = row_group.next_column()?.unwrap();
+            column
+                .typed::<Int64Type>()
+                .write_batch(offsets, None, None)?;
+            column.close()?;
+            let mut column = row_group.next_column()?.unwrap();
+            column.typed::<Int64Type>().write_batch(sizes, None, None)?;
+            column.close()?;
+            let mut column = row_group.next_column()?.unwrap();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(raws, Some(raw_levels), None)?;
+            column.close()?;
+            row_group.close()?;
+            writer.close()?;
+            Ok(())
+        };
+        // Chunks 0.0, 0.1, and 1.0
+        write_record(
+            0,
+            &[target_path.clone(), target_path.clone()],
+            &[1, 1, 0],
+            &[2, 6, 0],
+            &[3, 0, 0],
+            &[],
+            &[0, 0, 0],
+        )?;
+        // Chunk 1.1 is inline
+        write_record(
+            1,
+            &[],
+            &[0],
+            &[0],
+            &[0],
+            &[ByteArray::from(vec![42u8])],
+            &[1],
+        )?;
+
+        let store = ReferenceStore::open(&manifest)?;
+        assert_eq!(
+            store.list()?,
+            &[
+                ".zgroup".try_into()?,
+                "var/.zarray".try_into()?,
+                "var/0.0".try_into()?,
+                "var/0.1".try_into()?,
+                "var/1.1".try_into()?
+            ]
+        );
+        assert_eq!(
+            store.get(&"var/0.0".try_into()?)?,
+            Some(vec![2, 3, 4].into())
+        );
+        assert_eq!(
+            store.get(&"var/0.1".try_into()?)?,
+            Some(vec![0, 1, 2, 3, 4, 5, 6, 7].into())
+        );
+        assert_eq!(store.get(&"var/1.1".try_into()?)?, Some(vec![42].into()));
+        assert!(store.get(&"var/1.0".try_into()?)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use super::{ListableStorageTraits, ReadableStorageTraits, WritableStorageTraits};
+
+/// The capabilities of a store.
+///
+/// Capabilities are queried from the `supports_*` methods of the storage traits, so operations can pick a strategy suited to a store rather than fail with [`StorageError::Unsupported`](super::StorageError::Unsupported) or degrade silently.
+/// Capabilities of traits that are not queried are false.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
+pub struct StoreCapabilities {
+    /// Keys can be listed. See [`ListableStorageTraits::supports_listing`].
+    pub listing: bool,
+    /// Byte ranges of a value can be read without reading the whole value. See [`ReadableStorageTraits::supports_partial_reads`].
+    pub partial_reads: bool,
+    /// Byte ranges of a value can be written without rewriting the whole value. See [`WritableStorageTraits::supports_partial_writes`].
+    pub partial_writes: bool,
+    /// Stored values are never observed partially written. See [`WritableStorageTraits::supports_atomic_writes`].
+    pub atomic_writes: bool,
+    /// Values can be stored only if their key does not exist. See [`WritableStorageTraits::supports_set_if_not_exists`].
+    pub set_if_not_exists: bool,
+    /// Values can be stored only if their entity tag matches. See [`WritableStorageTraits::supports_set_if_match`].
+    pub set_if_match: bool,
+    /// Sizes of values, prefixes, and the store can be queried. See [`ReadableStorageTraits::supports_sizes`].
+    pub sizes: bool,
+}
+
+impl StoreCapabilities {
+    /// Query the capabilities of readable, writable, and listable storage.
+    #[must_use]
+    pub fn new<
+        TStorage: ?Sized + ReadableStorageTraits + WritableStorageTraits + ListableStorageTraits,
+    >(
+        storage: &TStorage,
+    ) -> Self {
+        Self::readable(storage)
+            .union(&Self::writable(storage))
+            .union(&Self::listable(storage))
+    }
+
+    /// Query the read capabilities of `storage`.
+    #[must_use]
+    pub fn readable<TStorage: ?Sized + ReadableStorageTraits>(storage: &TStorage) -> Self {
+        Self {
+            partial_reads: storage.supports_partial_reads(),
+            sizes: storage.supports_sizes(),
+            ..Self::default()
+        }
+    }
+
+    /// Query the write capabilities of `storage`.
+    #[must_use]
+    pub fn writable<TStorage: ?Sized + WritableStorageTraits>(storage: &TStorage) -> Self {
+        Self {
+            partial_writes: storage.supports_partial_writes(),
+            atomic_writes: storage.supports_atomic_writes(),
+            set_if_not_exists: storage.supports_set_if_not_exists(),
+            set_if_match: storage.supports_set_if_match(),
+            ..Self::default()
+        }
+    }
+
+    /// Query the list capabilities of `storage`.
+    #[must_use]
+    pub fn listable<TStorage: ?Sized + ListableStorageTraits>(storage: &TStorage) -> Self {
+        Self {
+            listing: storage.supports_listing(),
+            ..Self::default()
+        }
+    }
+
+    /// Return the union of these capabilities and `other`.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            listing: self.listing || other.listing,
+            partial_reads: self.partial_reads || other.partial_reads,
+            partial_writes: self.partial_writes || other.partial_writes,
+            atomic_writes: self.atomic_writes || other.atomic_writes,
+            set_if_not_exists: self.set_if_not_exists || other.set_if_not_exists,
+            set_if_match: self.set_if_match || other.set_if_match,
+            sizes: self.sizes || other.sizes,
+        }
+    }
+}
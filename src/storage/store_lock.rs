@@ -2,21 +2,25 @@
 //!
 //! [`StoreLocks`] implements [`StoreLocksTraits`] to request a locking primitive for a [`StoreKey`](crate::storage::StoreKey).
 //! This is needed for some array operations, such as storing an array or chunk subset.
+//! Chunk locks are set on an array with [`Array::set_chunk_locks`](crate::array::Array::set_chunk_locks) or `Array::set_async_chunk_locks`.
 //!
 //! Currently, the only abstract locking primitive is a [`StoreKeyMutex`] with a [`StoreKeyMutexGuard`] RAII guard.
 //! The lock implementations include:
 //!  - [`DefaultStoreLocks`] (with [`DefaultStoreMutex`]) implement [`parking_lot::Mutex`]-based locking in a single process.
-//!    - Used by default in stores.
+//!    - Mutexes are held in a map sharded by key hash and are removed once unused. Lock contention metrics are available.
 //!    - Async variants use [`async_lock::Mutex`].
 //!  - [`DisabledStoreLocks`] (with [`DisabledStoreMutex`]) and their async variants disable locks for potentially improved performance.
 //!    - **Requires careful usage of [`Array`](crate::array::Array) to maintain data integrity** (see [`Array`](crate::array::Array) for more information).
 //!
-//! Specialised locks are planned for distributed applications.
+//! Chunks are not locked by default.
+//! Multi-process or distributed applications can implement [`StoreLocksTraits`] (or `AsyncStoreLocksTraits`) with file-based (e.g. `flock`) or distributed locks.
 
 #[cfg(feature = "async")]
 pub mod store_lock_async;
 pub mod store_lock_sync;
 
+mod sharded_lock_map;
+
 pub use store_lock_sync::{
     StoreKeyMutex, StoreKeyMutexGuard, StoreKeyMutexGuardTraits, StoreKeyMutexTraits, StoreLocks,
     StoreLocksTraits,
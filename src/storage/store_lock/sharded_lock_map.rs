@@ -0,0 +1,117 @@
+//! A sharded map of store key locks.
+
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::Mutex;
+
+use crate::storage::StoreKey;
+
+/// The default number of shards in a [`ShardedLockMap`].
+pub(super) const DEFAULT_NUM_SHARDS: usize = 64;
+
+/// A shard of a [`ShardedLockMap`].
+#[derive(Debug, Default)]
+pub(super) struct LockMapShard<T> {
+    locks: Mutex<HashMap<StoreKey, Arc<T>>>,
+    acquisitions: AtomicUsize,
+    contentions: AtomicUsize,
+}
+
+/// A map of store key locks, sharded by key hash to reduce contention on the map itself.
+///
+/// Entries are removed when the last [`LockMapEntry`] for a key is dropped, so the map only holds keys with outstanding mutexes.
+#[derive(Debug)]
+pub(super) struct ShardedLockMap<T> {
+    shards: Vec<Arc<LockMapShard<T>>>,
+}
+
+impl<T: Default> ShardedLockMap<T> {
+    /// Create a new lock map with `num_shards` shards (at least one).
+    pub(super) fn new(num_shards: usize) -> Self {
+        Self {
+            shards: (0..num_shards.max(1))
+                .map(|_| Arc::new(LockMapShard::default()))
+                .collect(),
+        }
+    }
+
+    /// Returns the entry for `key`, creating its lock if it does not exist.
+    pub(super) fn entry(&self, key: &StoreKey) -> LockMapEntry<T> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        let shard_index = usize::try_from(hasher.finish() % self.shards.len() as u64).unwrap();
+        let shard = self.shards[shard_index].clone();
+        let lock = shard.locks.lock().entry(key.clone()).or_default().clone();
+        LockMapEntry {
+            shard,
+            key: key.clone(),
+            lock,
+        }
+    }
+}
+
+impl<T> ShardedLockMap<T> {
+    /// Returns the number of keys with outstanding mutexes.
+    pub(super) fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.locks.lock().len())
+            .sum()
+    }
+
+    /// Returns the number of lock acquisitions.
+    pub(super) fn acquisitions(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.acquisitions.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Returns the number of lock acquisitions which had to wait for another holder.
+    pub(super) fn contentions(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.contentions.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
+/// An entry in a [`ShardedLockMap`]. The entry is removed from the map when the last entry for its key is dropped.
+#[derive(Debug)]
+pub(super) struct LockMapEntry<T> {
+    shard: Arc<LockMapShard<T>>,
+    key: StoreKey,
+    lock: Arc<T>,
+}
+
+impl<T> LockMapEntry<T> {
+    /// Returns the mutex.
+    pub(super) fn mutex(&self) -> &T {
+        &self.lock
+    }
+
+    /// Record a lock acquisition, which was `contended` if it had to wait for another holder.
+    pub(super) fn record_acquisition(&self, contended: bool) {
+        self.shard.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if contended {
+            self.shard.contentions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T> Drop for LockMapEntry<T> {
+    fn drop(&mut self) {
+        let mut locks = self.shard.locks.lock();
+        // Entries are only created with the shard locked, so the count cannot increase here
+        if Arc::strong_count(&self.lock) == 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
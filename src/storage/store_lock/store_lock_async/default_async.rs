@@ -1,12 +1,11 @@
 //! Default asynchronous store mutex.
 
-use std::{collections::HashMap, sync::Arc};
-
 use async_lock::{Mutex, MutexGuard};
 
 use crate::storage::StoreKey;
 
 use super::{
+    super::sharded_lock_map::{LockMapEntry, ShardedLockMap, DEFAULT_NUM_SHARDS},
     AsyncStoreKeyMutex, AsyncStoreKeyMutexGuard, AsyncStoreKeyMutexGuardTraits,
     AsyncStoreKeyMutexTraits, AsyncStoreLocksTraits,
 };
@@ -20,51 +19,96 @@ impl AsyncStoreKeyMutexGuardTraits for AsyncDefaultStoreMutexGuard<'_> {}
 
 /// Default store mutex.
 #[derive(Debug)]
-pub struct AsyncDefaultStoreMutex(Arc<Mutex<()>>);
+pub struct AsyncDefaultStoreMutex(LockMapEntry<Mutex<()>>);
 
 #[async_trait::async_trait]
 impl AsyncStoreKeyMutexTraits for AsyncDefaultStoreMutex {
     async fn lock(&self) -> AsyncStoreKeyMutexGuard<'_> {
-        Box::new(AsyncDefaultStoreMutexGuard::<'_>(self.0.lock().await))
+        let guard = if let Some(guard) = self.0.mutex().try_lock() {
+            self.0.record_acquisition(false);
+            guard
+        } else {
+            self.0.record_acquisition(true);
+            self.0.mutex().lock().await
+        };
+        Box::new(AsyncDefaultStoreMutexGuard::<'_>(guard))
     }
 }
 
 /// Default store locks.
-#[derive(Debug, Default)]
-pub struct AsyncDefaultStoreLocks(Mutex<HashMap<StoreKey, Arc<Mutex<()>>>>);
+///
+/// Async variant of [`DefaultStoreLocks`](crate::storage::store_lock::DefaultStoreLocks).
+#[derive(Debug)]
+pub struct AsyncDefaultStoreLocks(ShardedLockMap<Mutex<()>>);
+
+impl Default for AsyncDefaultStoreLocks {
+    fn default() -> Self {
+        Self::new_with_shards(DEFAULT_NUM_SHARDS)
+    }
+}
+
+impl AsyncDefaultStoreLocks {
+    /// Create new default store locks with `num_shards` shards (at least one).
+    #[must_use]
+    pub fn new_with_shards(num_shards: usize) -> Self {
+        Self(ShardedLockMap::new(num_shards))
+    }
+
+    /// Returns the number of keys with outstanding mutexes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no keys with outstanding mutexes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lock acquisitions.
+    #[must_use]
+    pub fn acquisitions(&self) -> usize {
+        self.0.acquisitions()
+    }
+
+    /// Returns the number of lock acquisitions which had to wait for another holder of the lock.
+    #[must_use]
+    pub fn contentions(&self) -> usize {
+        self.0.contentions()
+    }
+}
 
 #[async_trait::async_trait]
 impl AsyncStoreLocksTraits for AsyncDefaultStoreLocks {
     async fn mutex(&self, key: &StoreKey) -> AsyncStoreKeyMutex {
-        let mut locks = self.0.lock().await;
-        Box::new(AsyncDefaultStoreMutex(
-            locks.entry(key.clone()).or_default().clone(),
-        ))
+        Box::new(AsyncDefaultStoreMutex(self.0.entry(key)))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{sync::atomic::AtomicUsize, time::Duration};
+    use std::{
+        sync::{atomic::AtomicUsize, Arc},
+        time::Duration,
+    };
 
-    use crate::storage::{store::AsyncObjectStore, AsyncReadableWritableStorageTraits};
+    use crate::storage::store_lock::AsyncStoreLocks;
 
     use super::*;
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     #[cfg_attr(miri, ignore)]
     async fn store_default_lock_async() {
-        let store = Arc::new(AsyncObjectStore::new_with_locks(
-            object_store::memory::InMemory::default(),
-            Arc::new(AsyncDefaultStoreLocks::default()),
-        ));
+        let default_locks = Arc::new(AsyncDefaultStoreLocks::default());
+        let locks: AsyncStoreLocks = default_locks.clone();
         let locks_held = Arc::new(AtomicUsize::new(0));
-        let futures = (0..20).into_iter().map(|_| {
+        let futures = (0..20).map(|_| {
             let key = StoreKey::new("key").unwrap();
-            let store = store.clone();
+            let locks = locks.clone();
             let locks_held = locks_held.clone();
             tokio::task::spawn(async move {
-                let mutex = store.mutex(&key).await.unwrap();
+                let mutex = locks.mutex(&key).await;
                 let _lock = mutex.lock().await;
                 locks_held.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
                 std::thread::sleep(Duration::from_millis(10));
@@ -75,5 +119,6 @@ mod tests {
         let result = futures::future::try_join_all(futures).await.unwrap();
         println!("{result:?}");
         assert!(result.iter().all(|b| *b));
+        assert!(default_locks.is_empty());
     }
 }
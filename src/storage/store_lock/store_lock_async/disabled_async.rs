@@ -37,38 +37,16 @@ impl AsyncStoreLocksTraits for AsyncDisabledStoreLocks {
 
 #[cfg(test)]
 mod tests {
-    use std::{
-        sync::{atomic::AtomicUsize, Arc},
-        time::Duration,
-    };
-
-    use crate::storage::{store::AsyncObjectStore, AsyncReadableWritableStorageTraits};
-
     use super::*;
 
-    #[tokio::test(flavor = "multi_thread")]
-    #[cfg_attr(miri, ignore)]
+    #[tokio::test]
     async fn store_disabled_lock_async() {
-        let store = Arc::new(AsyncObjectStore::new_with_locks(
-            object_store::memory::InMemory::default(),
-            Arc::new(AsyncDisabledStoreLocks::default()),
-        ));
-        let locks_held = Arc::new(AtomicUsize::new(0));
-        let futures = (0..20).into_iter().map(|_| {
-            let key = StoreKey::new("key").unwrap();
-            let store = store.clone();
-            let locks_held = locks_held.clone();
-            tokio::task::spawn(async move {
-                let mutex = store.mutex(&key).await.unwrap();
-                let _lock = mutex.lock().await;
-                locks_held.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-                std::thread::sleep(Duration::from_millis(10));
-                let locks_held = locks_held.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
-                locks_held > 1
-            })
-        });
-        let result = futures::future::try_join_all(futures).await.unwrap();
-        println!("{result:?}");
-        assert!(result.iter().any(|b| *b));
+        let locks = AsyncDisabledStoreLocks;
+        let key = StoreKey::new("key").unwrap();
+        let mutex_a = locks.mutex(&key).await;
+        let mutex_b = locks.mutex(&key).await;
+        // Does not block
+        let _lock_a = mutex_a.lock().await;
+        let _lock_b = mutex_b.lock().await;
     }
 }
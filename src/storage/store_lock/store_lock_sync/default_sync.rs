@@ -1,12 +1,11 @@
 //! Default synchronous store mutex.
 
-use std::{collections::HashMap, sync::Arc};
-
 use parking_lot::{Mutex, MutexGuard};
 
 use crate::storage::StoreKey;
 
 use super::{
+    super::sharded_lock_map::{LockMapEntry, ShardedLockMap, DEFAULT_NUM_SHARDS},
     StoreKeyMutex, StoreKeyMutexGuard, StoreKeyMutexGuardTraits, StoreKeyMutexTraits,
     StoreLocksTraits,
 };
@@ -20,24 +19,69 @@ impl StoreKeyMutexGuardTraits for DefaultStoreMutexGuard<'_> {}
 
 /// Default store mutex.
 #[derive(Debug)]
-pub struct DefaultStoreMutex(Arc<Mutex<()>>);
+pub struct DefaultStoreMutex(LockMapEntry<Mutex<()>>);
 
 impl StoreKeyMutexTraits for DefaultStoreMutex {
     fn lock(&self) -> StoreKeyMutexGuard<'_> {
-        Box::new(DefaultStoreMutexGuard::<'_>(self.0.lock()))
+        let guard = if let Some(guard) = self.0.mutex().try_lock() {
+            self.0.record_acquisition(false);
+            guard
+        } else {
+            self.0.record_acquisition(true);
+            self.0.mutex().lock()
+        };
+        Box::new(DefaultStoreMutexGuard::<'_>(guard))
     }
 }
 
 /// Default store locks.
-#[derive(Debug, Default)]
-pub struct DefaultStoreLocks(Mutex<HashMap<StoreKey, Arc<Mutex<()>>>>);
+///
+/// Mutexes are held in a map sharded by key hash.
+/// The mutex of a key is removed from the map when the last [`DefaultStoreMutex`] for that key is dropped, so memory usage is proportional to the number of keys in use rather than the number of keys ever locked.
+#[derive(Debug)]
+pub struct DefaultStoreLocks(ShardedLockMap<Mutex<()>>);
+
+impl Default for DefaultStoreLocks {
+    fn default() -> Self {
+        Self::new_with_shards(DEFAULT_NUM_SHARDS)
+    }
+}
+
+impl DefaultStoreLocks {
+    /// Create new default store locks with `num_shards` shards (at least one).
+    #[must_use]
+    pub fn new_with_shards(num_shards: usize) -> Self {
+        Self(ShardedLockMap::new(num_shards))
+    }
+
+    /// Returns the number of keys with outstanding mutexes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no keys with outstanding mutexes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lock acquisitions.
+    #[must_use]
+    pub fn acquisitions(&self) -> usize {
+        self.0.acquisitions()
+    }
+
+    /// Returns the number of lock acquisitions which had to wait for another holder of the lock.
+    #[must_use]
+    pub fn contentions(&self) -> usize {
+        self.0.contentions()
+    }
+}
 
 impl StoreLocksTraits for DefaultStoreLocks {
     fn mutex(&self, key: &StoreKey) -> StoreKeyMutex {
-        let mut locks = self.0.lock();
-        Box::new(DefaultStoreMutex(
-            locks.entry(key.clone()).or_default().clone(),
-        ))
+        Box::new(DefaultStoreMutex(self.0.entry(key)))
     }
 }
 
@@ -47,23 +91,52 @@ mod tests {
 
     use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-    use crate::storage::{store::MemoryStore, ReadableWritableStorageTraits};
-
     use super::*;
 
     #[test]
     #[cfg_attr(miri, ignore)]
     fn store_default_lock_sync() {
-        let store = MemoryStore::new_with_locks(Arc::new(DefaultStoreLocks::default()));
+        let locks = DefaultStoreLocks::default();
         let key = StoreKey::new("key").unwrap();
         let locks_held = AtomicUsize::new(0);
         (0..20).into_par_iter().for_each(|_| {
-            let mutex = store.mutex(&key).unwrap();
+            let mutex = locks.mutex(&key);
             let _lock = mutex.lock();
             locks_held.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
             std::thread::sleep(Duration::from_millis(10));
             let locks_held = locks_held.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
             assert_eq!(locks_held, 1);
         });
+        assert!(locks.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn store_default_lock_sync_cleanup_contention() {
+        let locks = DefaultStoreLocks::new_with_shards(2);
+        let key_a = StoreKey::new("a").unwrap();
+        let key_b = StoreKey::new("b").unwrap();
+        {
+            let mutex_a = locks.mutex(&key_a);
+            let mutex_b = locks.mutex(&key_b);
+            let _mutex_a = locks.mutex(&key_a);
+            assert_eq!(locks.len(), 2);
+            drop(mutex_b);
+            assert_eq!(locks.len(), 1);
+
+            let lock_a = mutex_a.lock();
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    let mutex_a = locks.mutex(&key_a);
+                    let _lock_a = mutex_a.lock();
+                });
+                while locks.contentions() == 0 {
+                    std::thread::yield_now();
+                }
+                drop(lock_a);
+            });
+        }
+        assert!(locks.is_empty());
+        assert_eq!((locks.acquisitions(), locks.contentions()), (2, 1));
     }
 }
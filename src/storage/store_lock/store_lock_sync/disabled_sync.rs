@@ -35,30 +35,16 @@ impl StoreLocksTraits for DisabledStoreLocks {
 
 #[cfg(test)]
 mod tests {
-    use std::{
-        sync::{atomic::AtomicUsize, Arc},
-        time::Duration,
-    };
-
-    use rayon::iter::{IntoParallelIterator, ParallelIterator};
-
-    use crate::storage::{store::MemoryStore, ReadableWritableStorageTraits};
-
     use super::*;
 
     #[test]
-    #[cfg_attr(miri, ignore)]
     fn store_disable_lock_sync() {
-        let store = MemoryStore::new_with_locks(Arc::new(DisabledStoreLocks::default()));
+        let locks = DisabledStoreLocks;
         let key = StoreKey::new("key").unwrap();
-        let locks_held = AtomicUsize::new(0);
-        assert!((0..20).into_par_iter().any(|_| {
-            let mutex = store.mutex(&key).unwrap();
-            let _lock = mutex.lock();
-            locks_held.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-            std::thread::sleep(Duration::from_millis(10));
-            let locks_held = locks_held.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
-            locks_held > 1
-        }));
+        let mutex_a = locks.mutex(&key);
+        let mutex_b = locks.mutex(&key);
+        // Does not block
+        let _lock_a = mutex_a.lock();
+        let _lock_b = mutex_b.lock();
     }
 }
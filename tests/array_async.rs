@@ -1,6 +1,11 @@
+#![cfg(all(feature = "ndarray", feature = "async", feature = "object_store"))]
+#![allow(clippy::single_range_in_vec_init)]
+
 use std::sync::Arc;
 
-use zarrs::array::codec::{array_to_bytes::sharding::ShardingCodecBuilder, GzipCodec};
+use zarrs::array::codec::{
+    array_to_bytes::sharding::ShardingCodecBuilder, CodecOptions, GzipCodec,
+};
 use zarrs::array::{Array, ArrayBuilder, ArrayView, DataType, FillValue};
 use zarrs::array_subset::ArraySubset;
 
@@ -70,6 +75,10 @@ async fn array_async_read(array: Array<AsyncObjectStore<InMemory>>) -> Result<()
     assert_eq!(array.async_retrieve_chunks(&ArraySubset::new_with_ranges(&[0..2, 1..2])).await?, [3, 4, 7, 8, 0, 0, 0, 0]);
     assert_eq!(array.async_retrieve_chunks(&ArraySubset::new_with_ranges(&[0..1, 1..3])).await?, [3, 4, 0, 0, 7, 8, 0, 0]);
 
+    let options = CodecOptions::builder().chunk_prefetch(2).build();
+    assert_eq!(array.async_retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..2, 0..2]), &options).await?, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(array.async_retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..1, 1..3]), &options).await?, [3, 4, 0, 0, 7, 8, 0, 0]);
+
     assert!(array.async_retrieve_chunks_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..2])).await.is_err());
     assert!(array.async_retrieve_chunks_ndarray::<u16>(&ArraySubset::new_with_ranges(&[0..2, 0..2])).await.is_err());
     assert_eq!(array.async_retrieve_chunks_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..2, 0..2])).await?, ndarray::array![[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 0, 0], [0, 0, 0, 0]].into_dyn());
@@ -85,6 +94,11 @@ async fn array_async_read(array: Array<AsyncObjectStore<InMemory>>) -> Result<()
     assert_eq!(array.async_retrieve_array_subset(&ArraySubset::new_with_ranges(&[5..7, 5..6])).await?, [0, 0]); // OOB -> fill value
     assert_eq!(array.async_retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..5, 0..5])).await?, [1, 2, 3, 4, 0, 5, 6, 7, 8, 0, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // OOB -> fill value
 
+    assert_eq!(array.async_retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..4, 0..4]), &options).await?, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(array.async_retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[1..3, 1..3]), &options).await?, [6, 7, 10 ,0]);
+    assert_eq!(array.async_retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..4, 1..4]), &options).await?, [2, 3, 4, 6, 7, 8, 10, 0, 0, 0, 0, 0]);
+    assert_eq!(array.async_retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..5, 0..5]), &options).await?, [1, 2, 3, 4, 0, 5, 6, 7, 8, 0, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // OOB -> fill value
+
     assert!(array.async_retrieve_array_subset_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..4])).await.is_err());
     assert!(array.async_retrieve_array_subset_ndarray::<u16>(&ArraySubset::new_with_ranges(&[0..4, 0..4])).await.is_err());
     assert_eq!(array.async_retrieve_array_subset_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..0, 0..0])).await?, ndarray::Array2::<u8>::zeros((0, 0)).into_dyn());
@@ -1,6 +1,10 @@
+#![allow(clippy::single_range_in_vec_init)]
+
 use std::sync::Arc;
 
-use zarrs::array::codec::{array_to_bytes::sharding::ShardingCodecBuilder, GzipCodec};
+use zarrs::array::codec::{
+    array_to_bytes::sharding::ShardingCodecBuilder, CodecOptions, GzipCodec,
+};
 use zarrs::array::{Array, ArrayBuilder, ArrayCodecTraits, ArrayView, DataType, FillValue};
 use zarrs::array_subset::ArraySubset;
 use zarrs::storage::store::MemoryStore;
@@ -65,6 +69,10 @@ fn array_sync_read(array: Array<MemoryStore>) -> Result<(), Box<dyn std::error::
     assert_eq!(array.retrieve_chunks(&ArraySubset::new_with_ranges(&[0..2, 1..2]))?, [3, 4, 7, 8, 0, 0, 0, 0]);
     assert_eq!(array.retrieve_chunks(&ArraySubset::new_with_ranges(&[0..1, 1..3]))?, [3, 4, 0, 0, 7, 8, 0, 0]);
 
+    let options = CodecOptions::builder().chunk_prefetch(2).build();
+    assert_eq!(array.retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..2, 0..2]), &options)?, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(array.retrieve_chunks_opt(&ArraySubset::new_with_ranges(&[0..1, 1..3]), &options)?, [3, 4, 0, 0, 7, 8, 0, 0]);
+
     assert!(array.retrieve_chunks_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..2])).is_err());
     assert!(array.retrieve_chunks_ndarray::<u16>(&ArraySubset::new_with_ranges(&[0..2, 0..2])).is_err());
     assert_eq!(array.retrieve_chunks_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..2, 0..2]))?, ndarray::array![[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 0, 0], [0, 0, 0, 0]].into_dyn());
@@ -80,6 +88,11 @@ fn array_sync_read(array: Array<MemoryStore>) -> Result<(), Box<dyn std::error::
     assert_eq!(array.retrieve_array_subset(&ArraySubset::new_with_ranges(&[5..7, 5..6]))?, [0, 0]); // OOB -> fill value
     assert_eq!(array.retrieve_array_subset(&ArraySubset::new_with_ranges(&[0..5, 0..5]))?, [1, 2, 3, 4, 0, 5, 6, 7, 8, 0, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // OOB -> fill value
 
+    assert_eq!(array.retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..4, 0..4]), &options)?, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(array.retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[1..3, 1..3]), &options)?, [6, 7, 10 ,0]);
+    assert_eq!(array.retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..4, 1..4]), &options)?, [2, 3, 4, 6, 7, 8, 10, 0, 0, 0, 0, 0]);
+    assert_eq!(array.retrieve_array_subset_opt(&ArraySubset::new_with_ranges(&[0..5, 0..5]), &options)?, [1, 2, 3, 4, 0, 5, 6, 7, 8, 0, 9, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]); // OOB -> fill value
+
     assert!(array.retrieve_array_subset_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..4])).is_err());
     assert!(array.retrieve_array_subset_ndarray::<u16>(&ArraySubset::new_with_ranges(&[0..4, 0..4])).is_err());
     assert_eq!(array.retrieve_array_subset_ndarray::<u8>(&ArraySubset::new_with_ranges(&[0..0, 0..0]))?, ndarray::Array2::<u8>::zeros((0, 0)).into_dyn());
@@ -18,3 +18,47 @@ fn hierarchy_tree() {
 "
     );
 }
+
+#[test]
+fn hierarchy_tree_statistics() {
+    let store = FilesystemStore::new("./tests/data/hierarchy.zarr")
+        .unwrap()
+        .sorted();
+    let node = Node::new(&store, "/").unwrap();
+    let tree = node.tree(&store).unwrap();
+    println!("{:?}", tree);
+    assert_eq!(
+        tree,
+        "/ stored 1418 bytes
+  a stored 1274 bytes
+    baz [10000, 1000] float64 chunks [1000, 100] codecs [bytes, gzip] stored 691 bytes, logical 80000000 bytes
+    foo [10000, 1000] float64 chunks [1000, 100] codecs [gzip] stored 583 bytes, logical 80000000 bytes
+  b stored 98 bytes
+"
+    );
+}
+
+#[test]
+fn hierarchy_tree_statistics_overflow() {
+    use std::sync::Arc;
+
+    use zarrs::{
+        array::{ArrayBuilder, DataType, FillValue},
+        storage::store::MemoryStore,
+    };
+
+    let store = Arc::new(MemoryStore::new());
+    ArrayBuilder::new(
+        vec![u64::MAX, 2],
+        DataType::Float64,
+        vec![1000, 1].try_into().unwrap(),
+        FillValue::from(0.0f64),
+    )
+    .build(store.clone(), "/array")
+    .unwrap()
+    .store_metadata()
+    .unwrap();
+    let node = Node::new(&*store, "/array").unwrap();
+    let tree = node.tree(&*store).unwrap();
+    assert!(tree.ends_with(", logical ? bytes\n"), "{tree}");
+}